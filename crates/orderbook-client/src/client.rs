@@ -346,6 +346,20 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    /// Gets the reference data of an option: contract multiplier, lot size,
+    /// maximum order size and tick-size bands.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_instrument_spec(
+        &self,
+        path: &OptionPath,
+    ) -> Result<InstrumentSpecResponse, Error> {
+        let url = format!("{}/spec", self.option_base(path));
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Orders
     // ========================================================================
@@ -488,6 +502,55 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    /// Gets the market maker's delta hedge status and recent hedge trades.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_hedging(&self) -> Result<HedgingResponse, Error> {
+        let url = format!("{}/api/v1/controls/hedging", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets the queue position of every resting market-maker order.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_queue_positions(
+        &self,
+        query: Option<&QueuePositionQuery>,
+    ) -> Result<QueuePositionsResponse, Error> {
+        let mut url = format!("{}/api/v1/controls/queue", self.base_url);
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets the requote throttling settings and sent vs. skipped counters.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_requote_stats(&self) -> Result<RequoteStatsResponse, Error> {
+        let url = format!("{}/api/v1/controls/requotes", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets the market maker's P&L attribution per underlying and period.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_pnl_attribution(&self) -> Result<PnlAttributionResponse, Error> {
+        let url = format!("{}/api/v1/controls/pnl", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Prices
     // ========================================================================
@@ -525,6 +588,30 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    /// Gets the recorded price history of a symbol.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_price_history(
+        &self,
+        symbol: &str,
+        query: Option<&PriceHistoryQuery>,
+    ) -> Result<PriceHistoryResponse, Error> {
+        let mut url = format!(
+            "{}/api/v1/prices/{}/history",
+            self.base_url,
+            encode_segment(symbol)
+        );
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Authentication
     // ========================================================================
@@ -616,6 +703,183 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    // ========================================================================
+    // Portfolio Risk
+    // ========================================================================
+
+    /// Gets net portfolio greeks for the caller's account and the market
+    /// maker's inventory.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_portfolio_greeks(
+        &self,
+        query: Option<&RiskGreeksQuery>,
+    ) -> Result<PortfolioGreeksResponse, Error> {
+        let mut url = format!("{}/api/v1/risk/greeks", self.base_url);
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Revalues the caller's positions over a spot/vol/time shock grid and
+    /// the server's configured stress scenarios.
+    ///
+    /// # Errors
+    /// Returns error if the request fails or a shock list is invalid.
+    pub async fn get_risk_scenarios(
+        &self,
+        query: Option<&ScenarioQuery>,
+    ) -> Result<ScenarioAnalysisResponse, Error> {
+        let mut url = format!("{}/api/v1/risk/scenarios", self.base_url);
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets Value-at-Risk and expected shortfall for the caller's positions.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_value_at_risk(&self, query: Option<&VarQuery>) -> Result<VarResponse, Error> {
+        let mut url = format!("{}/api/v1/risk/var", self.base_url);
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets portfolio margin and collateral for the caller's account.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_account_margin(&self) -> Result<AccountMarginResponse, Error> {
+        let url = format!("{}/api/v1/risk/margin", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Sets an account's collateral (admin).
+    ///
+    /// # Errors
+    /// Returns error if the request fails or the amount is invalid.
+    pub async fn set_account_collateral(
+        &self,
+        account: &str,
+        request: &SetCollateralRequest,
+    ) -> Result<AccountMarginResponse, Error> {
+        let url = format!(
+            "{}/api/v1/admin/accounts/{}/collateral",
+            self.base_url,
+            encode_segment(account)
+        );
+        let resp = self.client.post(&url).json(request).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets the latest liquidation of the caller's account.
+    ///
+    /// # Errors
+    /// Returns error if the request fails or the account was never liquidated.
+    pub async fn get_liquidation(&self) -> Result<Liquidation, Error> {
+        let url = format!("{}/api/v1/risk/liquidation", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Lists the latest liquidation of every account (admin).
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn list_liquidations(&self) -> Result<LiquidationsResponse, Error> {
+        let url = format!("{}/api/v1/admin/liquidations", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    // ========================================================================
+    // Account Cash
+    // ========================================================================
+
+    /// Gets the caller's cash balance.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_account_balance(&self) -> Result<AccountBalanceResponse, Error> {
+        let url = format!("{}/api/v1/account/balance", self.base_url);
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Gets the caller's ledger entries, newest first.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_account_ledger(
+        &self,
+        query: Option<&LedgerQuery>,
+    ) -> Result<LedgerEntriesResponse, Error> {
+        let mut url = format!("{}/api/v1/account/ledger", self.base_url);
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Deposits cash into an account (admin).
+    ///
+    /// # Errors
+    /// Returns error if the request fails or the amount is invalid.
+    pub async fn deposit_funds(
+        &self,
+        account: &str,
+        request: &FundsRequest,
+    ) -> Result<AccountBalanceResponse, Error> {
+        let url = format!(
+            "{}/api/v1/admin/accounts/{}/deposit",
+            self.base_url,
+            encode_segment(account)
+        );
+        let resp = self.client.post(&url).json(request).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Withdraws available cash from an account (admin).
+    ///
+    /// # Errors
+    /// Returns error if the request fails or the amount exceeds available cash.
+    pub async fn withdraw_funds(
+        &self,
+        account: &str,
+        request: &FundsRequest,
+    ) -> Result<AccountBalanceResponse, Error> {
+        let url = format!(
+            "{}/api/v1/admin/accounts/{}/withdraw",
+            self.base_url,
+            encode_segment(account)
+        );
+        let resp = self.client.post(&url).json(request).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Orderbook Snapshots (Persistence)
     // ========================================================================
@@ -827,6 +1091,39 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    /// Gets the recorded top-of-book history for an option.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_quote_history(
+        &self,
+        path: &OptionPath,
+        query: Option<&QuoteHistoryQuery>,
+    ) -> Result<QuoteHistoryResponse, Error> {
+        let mut url = format!("{}/quotes/history", self.option_base(path));
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
+    /// Rebuilds the server's stored OHLC bars from its persisted executions.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn backfill_ohlc(
+        &self,
+        request: &OhlcBackfillRequest,
+    ) -> Result<OhlcBackfillResponse, Error> {
+        let url = format!("{}/api/v1/admin/ohlc/backfill", self.base_url);
+        let resp = self.client.post(&url).json(request).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Orderbook Metrics
     // ========================================================================
@@ -865,10 +1162,59 @@ impl OrderbookClient {
         self.handle_response(resp).await
     }
 
+    /// Gets the stored volatility surfaces and skew series for an underlying.
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_volatility_surface_history(
+        &self,
+        underlying: &str,
+        query: Option<&VolatilitySurfaceHistoryQuery>,
+    ) -> Result<VolatilitySurfaceHistoryResponse, Error> {
+        let mut url = format!(
+            "{}/api/v1/underlyings/{}/volatility-surface/history",
+            self.base_url,
+            encode_segment(underlying)
+        );
+        if let Some(q) = query {
+            let params = serde_urlencoded::to_string(q).unwrap_or_default();
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params));
+            }
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
     // ========================================================================
     // Option Chain
     // ========================================================================
 
+    /// Gets the implied forward and put-call parity diagnostics for an
+    /// expiration. `threshold_bps` overrides the server's default violation
+    /// threshold (50 bps of the forward).
+    ///
+    /// # Errors
+    /// Returns error if the request fails.
+    pub async fn get_implied_forward(
+        &self,
+        underlying: &str,
+        expiration: &str,
+        threshold_bps: Option<f64>,
+    ) -> Result<ImpliedForwardResponse, Error> {
+        let mut url = format!(
+            "{}/api/v1/underlyings/{}/expirations/{}/forward",
+            self.base_url,
+            encode_segment(underlying),
+            encode_segment(expiration)
+        );
+        if let Some(threshold) = threshold_bps {
+            url.push_str(&format!("?threshold_bps={}", threshold));
+        }
+        let resp = self.client.get(&url).send().await?;
+        self.handle_response(resp).await
+    }
+
     /// Gets the option chain for an expiration.
     ///
     /// # Errors
@@ -406,6 +406,12 @@ pub struct InstrumentStatus {
     pub quoting_enabled: bool,
     /// Current price (if available).
     pub current_price: Option<f64>,
+    /// Name of the quoting strategy (e.g. `single`, `layered`).
+    pub strategy: String,
+    /// Most price levels the strategy quotes per side.
+    pub ladder_levels: usize,
+    /// Maker orders resting on the underlying, over every level.
+    pub resting_orders: usize,
 }
 
 /// Response for listing instruments.
@@ -415,6 +421,168 @@ pub struct InstrumentsListResponse {
     pub instruments: Vec<InstrumentStatus>,
 }
 
+/// Hedge status of one underlying.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgeStatusResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents, if known.
+    pub spot: Option<u64>,
+    /// Net delta of the market maker's option inventory.
+    pub option_delta: f64,
+    /// Signed position in the underlying held as a hedge.
+    pub hedge_position: f64,
+    /// Option delta plus hedge position.
+    pub residual_delta: f64,
+    /// Hedge P&L in cents, marked at spot.
+    pub hedge_pnl: i64,
+    /// Number of hedge trades executed.
+    pub trades: u64,
+}
+
+/// One executed hedge trade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgeTradeResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Signed quantity of the underlying (positive = bought).
+    pub quantity: f64,
+    /// Execution price in cents.
+    pub price: u64,
+    /// Net option delta that was hedged.
+    pub option_delta: f64,
+    /// Hedge position after the trade.
+    pub hedge_position: f64,
+    /// Option delta plus hedge position after the trade.
+    pub residual_delta: f64,
+    /// Execution time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Query parameters for the maker queue-position endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueuePositionQuery {
+    /// Only report orders on this underlying.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+}
+
+/// Queue position of one resting maker order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakerQueuePositionResponse {
+    /// Instrument identifier (`UNDERLYING-YYYYMMDD-STRIKE-STYLE`).
+    pub instrument: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Side of the maker order.
+    pub side: OrderSide,
+    /// Price in cents.
+    pub price: u128,
+    /// Resting quantity of the order.
+    pub quantity: u64,
+    /// Orders ahead of it at the same price.
+    pub orders_ahead: usize,
+    /// Quantity ahead of it at the same price.
+    pub quantity_ahead: u64,
+    /// Total quantity resting at the price.
+    pub level_quantity: u64,
+}
+
+/// Response for the maker queue-position endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuePositionsResponse {
+    /// Resting maker orders, ordered by instrument with the bid first.
+    pub orders: Vec<MakerQueuePositionResponse>,
+}
+
+/// Requote throttling settings and counters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequoteStatsResponse {
+    /// Smallest bid or ask move, in price ticks, that replaces a quote.
+    pub min_price_change_ticks: u64,
+    /// Smallest bid or ask size change, in percent, that replaces a quote.
+    pub min_size_change_pct: f64,
+    /// Maximum quote replacements per instrument per second (0 = unlimited).
+    pub max_updates_per_second: u32,
+    /// Quotes replaced since start-up.
+    pub sent: u64,
+    /// Requotes skipped since start-up, for any reason.
+    pub skipped: u64,
+    /// Requotes skipped because the quote was unchanged.
+    pub skipped_unchanged: u64,
+    /// Requotes skipped because the change was below the thresholds.
+    pub skipped_below_threshold: u64,
+    /// Requotes skipped by the per-instrument rate limit.
+    pub skipped_rate_limited: u64,
+}
+
+/// Market-maker P&L by source, in cents.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlAttribution {
+    /// Spread captured on fills against the quote-time theo.
+    pub edge: i64,
+    /// First-order P&L from spot moves.
+    pub delta: i64,
+    /// Second-order P&L from spot moves.
+    pub gamma: i64,
+    /// P&L from marking-volatility moves.
+    pub vega: i64,
+    /// Time decay.
+    pub theta: i64,
+    /// P&L of the delta hedge.
+    pub hedging: i64,
+    /// Sum of every source.
+    pub total: i64,
+}
+
+/// P&L attribution of one underlying.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnderlyingPnlAttribution {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// P&L by source.
+    pub pnl: PnlAttribution,
+}
+
+/// P&L attribution over one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlPeriodResponse {
+    /// Period start in milliseconds.
+    pub start_ms: u64,
+    /// Period end in milliseconds, absent while the period is open.
+    pub end_ms: Option<u64>,
+    /// P&L per underlying, ordered by symbol.
+    pub underlyings: Vec<UnderlyingPnlAttribution>,
+    /// P&L across every underlying.
+    pub total: PnlAttribution,
+}
+
+/// Response for the P&L attribution endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlAttributionResponse {
+    /// P&L since the engine started.
+    pub since_start: PnlPeriodResponse,
+    /// The open period.
+    pub current_period: PnlPeriodResponse,
+    /// Closed periods, newest first.
+    pub periods: Vec<PnlPeriodResponse>,
+}
+
+/// Response for the hedging status endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgingResponse {
+    /// Whether delta hedging is enabled.
+    pub enabled: bool,
+    /// Absolute residual delta tolerated before hedging.
+    pub delta_band: f64,
+    /// Smallest hedge quantity of the underlying.
+    pub lot_size: f64,
+    /// Per-underlying hedge status, ordered by symbol.
+    pub underlyings: Vec<HedgeStatusResponse>,
+    /// Most recent hedge trades, newest first.
+    pub trades: Vec<HedgeTradeResponse>,
+}
+
 // ============================================================================
 // Prices
 // ============================================================================
@@ -470,6 +638,69 @@ pub struct LatestPriceResponse {
     pub timestamp: String,
 }
 
+/// Query parameters for price history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceHistoryQuery {
+    /// Start timestamp in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// End timestamp in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Maximum number of points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Downsampling interval (1m, 5m, 15m, 1h, 4h, 1d); raw prices when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+}
+
+/// One point of a price history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceHistoryPoint {
+    /// Time of the price, or start of the interval.
+    pub timestamp: String,
+    /// First price in dollars.
+    pub open: f64,
+    /// Highest price in dollars.
+    pub high: f64,
+    /// Lowest price in dollars.
+    pub low: f64,
+    /// Last price in dollars.
+    pub close: f64,
+    /// Summed volume (if available).
+    pub volume: Option<i64>,
+    /// Number of prices in the point.
+    pub samples: u64,
+}
+
+/// Realized volatility of a price history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RealizedVolatilityStats {
+    /// Number of log returns between consecutive points.
+    pub returns: u64,
+    /// Sum of the log returns.
+    pub total_log_return: Option<f64>,
+    /// Sample standard deviation of the log returns.
+    pub return_stddev: Option<f64>,
+    /// Annualized realized volatility.
+    pub annualized: Option<f64>,
+}
+
+/// Response for price history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceHistoryResponse {
+    /// Symbol.
+    pub symbol: String,
+    /// Downsampling interval; absent for raw prices.
+    #[serde(default)]
+    pub interval: Option<String>,
+    /// Points, oldest first.
+    pub points: Vec<PriceHistoryPoint>,
+    /// Realized volatility of the points.
+    pub realized_volatility: RealizedVolatilityStats,
+}
+
 // ============================================================================
 // Option Path Parameters
 // ============================================================================
@@ -538,6 +769,9 @@ pub struct TokenRequest {
     /// Optional token lifetime in seconds (defaults to the server's TTL).
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub ttl_secs: Option<u64>,
+    /// Optional trading account to embed as the token subject.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub account: Option<String>,
 }
 
 /// Response for `POST /api/v1/auth/token`.
@@ -781,10 +1015,27 @@ pub struct CreateSnapshotResponse {
     /// Defaults to 0 when talking to an older server that omits the field.
     #[serde(default)]
     pub orderbooks_failed: u64,
+    /// Whether the snapshot was written to durable storage. Defaults to
+    /// `false` when talking to an older server that omits the field.
+    #[serde(default)]
+    pub persisted: bool,
     /// Timestamp of the snapshot.
     pub timestamp_ms: u64,
 }
 
+/// What took a snapshot. Mirrors the server `SnapshotTrigger`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotTrigger {
+    /// An admin request.
+    #[default]
+    Manual,
+    /// The server's snapshot schedule.
+    Scheduled,
+    /// Graceful shutdown.
+    Shutdown,
+}
+
 /// Summary of a snapshot for listing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotSummary {
@@ -794,10 +1045,21 @@ pub struct SnapshotSummary {
     pub orderbook_count: u64,
     /// Total number of orders.
     pub total_orders: u64,
+    /// `false` when the snapshot is partial. Defaults to `true` when talking
+    /// to an older server that omits the field.
+    #[serde(default = "default_true")]
+    pub success: bool,
+    /// What took the snapshot. Defaults to manual for older servers.
+    #[serde(default)]
+    pub trigger: SnapshotTrigger,
     /// Creation timestamp in milliseconds.
     pub created_at: u64,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 /// Response for listing snapshots.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotsListResponse {
@@ -824,6 +1086,14 @@ pub struct RestoreSnapshotResponse {
     /// Defaults to 0 when talking to an older server that omits the field.
     #[serde(default)]
     pub orderbooks_failed: u64,
+    /// Whether the orders, positions, executions, trades, OHLC bars and
+    /// maker order tracking were restored too (whole-state snapshots only).
+    #[serde(default)]
+    pub state_restored: bool,
+    /// Sequence of the last journal entry the snapshot includes (0 when it
+    /// was taken without a journal).
+    #[serde(default)]
+    pub journal_seq: u64,
     /// Timestamp of the restore operation.
     pub timestamp_ms: u64,
 }
@@ -977,12 +1247,12 @@ pub enum ModifyOrderStatus {
 
 /// Response for modifying an order. Mirrors the server `ModifyOrderResponse`.
 ///
-/// Modification is cancel-and-replace, so `order_id` is the id of the NEW resting
-/// order on success and `priority_changed` is always `true` for a successful
-/// modification.
+/// Modification amends the order in place, so `order_id` is unchanged.
+/// `priority_changed` is `false` for a quantity decrease at the same price and
+/// `true` for a quantity increase or a price change.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModifyOrderResponse {
-    /// The resulting order ID (the new id on success).
+    /// The order ID (unchanged by the amend).
     pub order_id: String,
     /// Status of the modification.
     pub status: ModifyOrderStatus,
@@ -1158,6 +1428,44 @@ pub struct GreeksResponse {
     pub timestamp_ms: u64,
 }
 
+// ============================================================================
+// Instrument Reference Data
+// ============================================================================
+
+/// One price band of a tick-size table. Mirrors the server `TickBandInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickBandInfo {
+    /// Exclusive upper bound of the band in cents; `None` for the last band.
+    #[serde(default)]
+    pub below: Option<u128>,
+    /// Tick of the band in cents.
+    pub tick: u128,
+}
+
+/// Reference data of an option. Mirrors the server `InstrumentSpecResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentSpecResponse {
+    /// Option symbol.
+    pub symbol: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration date (YYYYMMDD).
+    pub expiration: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Option style.
+    pub style: OptionStyle,
+    /// Units of the underlying per contract.
+    pub contract_multiplier: u64,
+    /// Contracts per lot.
+    pub min_lot_size: u64,
+    /// Largest quantity of a single order, if limited.
+    #[serde(default)]
+    pub max_order_size: Option<u64>,
+    /// Tick-size bands in ascending price order.
+    pub tick_bands: Vec<TickBandInfo>,
+}
+
 // ============================================================================
 // Last Trade
 // ============================================================================
@@ -1234,6 +1542,58 @@ pub struct OhlcResponse {
     pub bars: Vec<OhlcBar>,
 }
 
+/// Query parameters for quote history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuoteHistoryQuery {
+    /// Start time in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// End time in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Maximum number of quotes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Response for quote history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteHistoryResponse {
+    /// Option symbol.
+    pub symbol: String,
+    /// Recorded top-of-book changes, oldest first.
+    pub quotes: Vec<QuoteResponse>,
+}
+
+/// Request to rebuild stored OHLC bars from persisted executions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OhlcBackfillRequest {
+    /// Start time in seconds, widened to the start of its day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// End time in seconds, widened to the end of its day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Option symbol to rebuild; every symbol when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+/// Response for an OHLC backfill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhlcBackfillResponse {
+    /// Executions read.
+    pub executions: u64,
+    /// Bars written or updated, across every interval.
+    pub bars: u64,
+    /// Start of the rebuilt range in seconds.
+    pub from: u64,
+    /// End of the rebuilt range in seconds (exclusive); `None` when
+    /// open-ended.
+    #[serde(default)]
+    pub to: Option<u64>,
+}
+
 // ============================================================================
 // Orderbook Metrics
 // ============================================================================
@@ -1346,12 +1706,73 @@ pub struct VolatilitySurfaceResponse {
     pub expirations: Vec<String>,
     /// List of strikes.
     pub strikes: Vec<u64>,
+    /// Implied forward per expiration in cents (put-call parity).
+    /// Defaults to empty when talking to an older server that omits the field.
+    #[serde(default)]
+    pub forwards: std::collections::HashMap<String, u64>,
     /// Surface data: expiration -> strike -> StrikeIV.
     pub surface: std::collections::HashMap<String, std::collections::HashMap<u64, StrikeIV>>,
     /// ATM term structure.
     pub atm_term_structure: Vec<ATMTermStructurePoint>,
 }
 
+/// Skew metrics of one expiration of a volatility surface.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpirationSkew {
+    /// Expiration date string.
+    pub expiration: String,
+    /// Days to expiration when the surface was computed.
+    pub days: u64,
+    /// ATM implied volatility.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atm_iv: Option<f64>,
+    /// 25-delta call IV minus 25-delta put IV.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_reversal_25d: Option<f64>,
+    /// Mean of the 25-delta call and put IVs minus the ATM IV.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub butterfly_25d: Option<f64>,
+}
+
+/// Skew metrics of one stored volatility surface.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurfaceSkewPoint {
+    /// Surface computation time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Spot price the surface was computed against (if available).
+    pub spot_price: Option<u64>,
+    /// Metrics per expiration, nearest first.
+    pub expirations: Vec<ExpirationSkew>,
+}
+
+/// Query parameters for volatility surface history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VolatilitySurfaceHistoryQuery {
+    /// Start of the metrics series in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// End of the metrics series in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Comma-separated times in milliseconds to return full surfaces at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+    /// Maximum number of series points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Response for volatility surface history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolatilitySurfaceHistoryResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Stored surfaces at the requested times, in request order.
+    pub surfaces: Vec<VolatilitySurfaceResponse>,
+    /// Skew metrics per stored surface, oldest first.
+    pub series: Vec<SurfaceSkewPoint>,
+}
+
 // ============================================================================
 // Option Chain
 // ============================================================================
@@ -1410,8 +1831,473 @@ pub struct OptionChainResponse {
     pub expiration: String,
     /// Current spot price (if available).
     pub spot_price: Option<u128>,
-    /// At-the-money strike (closest to spot price).
+    /// Implied forward price in cents (put-call parity), when derivable.
+    #[serde(default)]
+    pub forward_price: Option<u64>,
+    /// At-the-money strike (closest to the implied forward, else spot).
     pub atm_strike: Option<u64>,
     /// Chain rows.
     pub chain: Vec<ChainStrikeRow>,
 }
+
+// ============================================================================
+// Implied Forward
+// ============================================================================
+
+/// Put-call parity diagnostics for one strike. Mirrors the server
+/// `ParityStrikeDiagnostic`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParityStrikeDiagnostic {
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Two-sided call mid in cents.
+    pub call_mid: Option<u128>,
+    /// Two-sided put mid in cents.
+    pub put_mid: Option<u128>,
+    /// Synthetic forward `K + (C - P) / D` in cents.
+    pub synthetic_forward: Option<u64>,
+    /// Parity residual in cents.
+    pub residual_cents: Option<i64>,
+    /// Absolute residual in basis points of the forward.
+    pub deviation_bps: Option<f64>,
+    /// Whether the deviation exceeds the requested threshold.
+    pub violation: bool,
+}
+
+/// Response for the implied forward endpoint. Mirrors the server
+/// `ImpliedForwardResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpliedForwardResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration date string.
+    pub expiration: String,
+    /// Current spot price in cents (if available).
+    pub spot_price: Option<u64>,
+    /// Days to expiration.
+    pub days_to_expiry: u64,
+    /// Implied forward price in cents.
+    pub forward_price: Option<u64>,
+    /// Implied discount factor to expiry.
+    pub discount_factor: Option<f64>,
+    /// Continuously-compounded implied rate.
+    pub implied_rate: Option<f64>,
+    /// Continuously-compounded implied carry against spot.
+    pub implied_carry: Option<f64>,
+    /// Forward minus spot in cents.
+    pub basis: Option<i64>,
+    /// Root-mean-square parity residual in cents.
+    pub rmse_cents: Option<f64>,
+    /// Number of strikes used in the regression.
+    pub strikes_used: usize,
+    /// Deviation threshold applied, in basis points.
+    pub threshold_bps: f64,
+    /// Number of strikes flagged as parity violations.
+    pub violations: usize,
+    /// Per-strike diagnostics.
+    pub strikes: Vec<ParityStrikeDiagnostic>,
+    /// Timestamp of calculation in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+// ============================================================================
+// Portfolio Risk
+// ============================================================================
+
+/// Query parameters for the portfolio greeks endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskGreeksQuery {
+    /// Restrict both books to a single underlying symbol.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+}
+
+/// Net greeks of a set of positions. Mirrors the server `NetGreeks`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NetGreeks {
+    /// Net delta (underlying units).
+    pub delta: f64,
+    /// Net gamma (per $1 underlying move).
+    pub gamma: f64,
+    /// Net vega (dollars per vol point).
+    pub vega: f64,
+    /// Net theta (dollars per day).
+    pub theta: f64,
+    /// Net rho (dollars per 1% rate move).
+    pub rho: f64,
+    /// Net dollar delta.
+    pub dollar_delta: f64,
+    /// Net dollar gamma (dollar-delta change per 1% move).
+    pub dollar_gamma: f64,
+}
+
+/// Net greeks of one expiration bucket. Mirrors the server
+/// `ExpirationGreeksResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpirationGreeksResponse {
+    /// Expiration date (YYYYMMDD).
+    pub expiration: String,
+    /// Number of open positions in the bucket.
+    pub positions: usize,
+    /// Net greeks of the bucket.
+    pub greeks: NetGreeks,
+}
+
+/// Net greeks of one underlying. Mirrors the server `UnderlyingGreeksResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnderlyingGreeksResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents the positions were valued against.
+    pub spot: u64,
+    /// Net greeks across the underlying.
+    pub greeks: NetGreeks,
+    /// Per-expiration breakdown.
+    pub expirations: Vec<ExpirationGreeksResponse>,
+}
+
+/// Aggregated greeks of one book. Mirrors the server `GreekBookResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreekBookResponse {
+    /// Per-underlying breakdown.
+    pub underlyings: Vec<UnderlyingGreeksResponse>,
+    /// Net greeks across the whole book.
+    pub total: NetGreeks,
+    /// Instruments that could not be valued.
+    pub unpriced: Vec<String>,
+}
+
+/// Response for the portfolio greeks endpoint. Mirrors the server
+/// `PortfolioGreeksResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioGreeksResponse {
+    /// Account the `positions` book belongs to.
+    pub account: String,
+    /// Greeks of the caller's positions.
+    pub positions: GreekBookResponse,
+    /// Greeks of the market maker's own inventory.
+    pub market_maker: GreekBookResponse,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Query parameters for the scenario endpoint. Lists are comma-separated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioQuery {
+    /// Restrict the book to a single underlying symbol.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+    /// Spot shocks in percent (e.g. `-20,-10,0,10,20`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spot_shocks: Option<String>,
+    /// Vol shocks in vol points (e.g. `-5,0,5`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vol_shocks: Option<String>,
+    /// Horizons in days (e.g. `0,1,7`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<String>,
+}
+
+/// P&L matrix for one horizon. Mirrors the server `ScenarioGridSlice`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioGridSlice {
+    /// Days rolled forward before revaluing.
+    pub days_forward: u32,
+    /// P&L in cents, indexed `[spot shock][vol shock]`.
+    pub pnl: Vec<Vec<i64>>,
+}
+
+/// P&L attributed to one underlying. Mirrors the server `UnderlyingPnl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnderlyingPnl {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// P&L in cents.
+    pub pnl: i64,
+}
+
+/// Result of one named stress scenario. Mirrors the server
+/// `StressScenarioResult`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressScenarioResult {
+    /// Scenario name.
+    pub name: String,
+    /// Scenario description.
+    pub description: String,
+    /// Days rolled forward before revaluing.
+    pub days_forward: u32,
+    /// Total P&L in cents.
+    pub pnl: i64,
+    /// P&L in cents per underlying.
+    pub by_underlying: Vec<UnderlyingPnl>,
+}
+
+/// Response for the scenario endpoint. Mirrors the server
+/// `ScenarioAnalysisResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioAnalysisResponse {
+    /// Account the positions belong to.
+    pub account: String,
+    /// Number of positions revalued.
+    pub positions: usize,
+    /// Current model value of the book in cents.
+    pub base_value: i64,
+    /// Spot shock axis in percent.
+    pub spot_shocks_pct: Vec<f64>,
+    /// Vol shock axis in vol points.
+    pub vol_shocks_pts: Vec<f64>,
+    /// One P&L matrix per horizon.
+    pub grid: Vec<ScenarioGridSlice>,
+    /// Configured stress scenarios.
+    pub stress: Vec<StressScenarioResult>,
+    /// Instruments that could not be revalued.
+    pub unpriced: Vec<String>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Query parameters for the VaR endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VarQuery {
+    /// Recompute instead of returning the latest scheduled report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<bool>,
+}
+
+/// VaR and expected shortfall of one method. Mirrors the server
+/// `VarEstimateResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarEstimateResponse {
+    /// Value-at-Risk in cents (positive = loss).
+    pub var: i64,
+    /// Expected shortfall in cents (positive = loss).
+    pub expected_shortfall: i64,
+    /// Number of scenarios.
+    pub scenarios: usize,
+}
+
+/// Response for the VaR endpoint. Mirrors the server `VarResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarResponse {
+    /// Account the positions belong to.
+    pub account: String,
+    /// Confidence level.
+    pub confidence: f64,
+    /// Holding period in days.
+    pub horizon_days: u32,
+    /// Number of positions revalued.
+    pub positions: usize,
+    /// Current model value of the book in cents.
+    pub base_value: i64,
+    /// Monte Carlo estimate.
+    pub monte_carlo: Option<VarEstimateResponse>,
+    /// Historical-simulation estimate.
+    pub historical: Option<VarEstimateResponse>,
+    /// Instruments that could not be revalued.
+    pub unpriced: Vec<String>,
+    /// Why an estimate is missing, if one is.
+    pub notes: Vec<String>,
+    /// When the report was computed, in milliseconds.
+    pub computed_at_ms: u64,
+}
+
+/// Request body for setting an account's collateral.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCollateralRequest {
+    /// Collateral in dollars.
+    pub collateral: f64,
+}
+
+/// Margin of one underlying. Mirrors the server `UnderlyingMarginResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnderlyingMarginResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Worst loss across the risk array, in cents.
+    pub scan_risk: i64,
+    /// Index of the risk-array scenario that set the scan risk.
+    pub worst_scenario: usize,
+    /// Scan risk saved by netting positions, in cents.
+    pub spread_credit: i64,
+    /// Short option minimum, in cents.
+    pub short_option_minimum: i64,
+    /// Requirement in cents.
+    pub requirement: i64,
+}
+
+/// Margin status of an account. Mirrors the server `AccountMarginResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMarginResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Whether the pre-trade margin check is enforced.
+    pub enforced: bool,
+    /// Collateral in cents.
+    pub collateral: i64,
+    /// Initial margin in cents.
+    pub initial_margin: i64,
+    /// Maintenance margin in cents.
+    pub maintenance_margin: i64,
+    /// Collateral left after initial margin, in cents (negative = shortfall).
+    pub excess_collateral: i64,
+    /// Whether collateral is below maintenance margin.
+    pub margin_call: bool,
+    /// Per-underlying breakdown.
+    pub underlyings: Vec<UnderlyingMarginResponse>,
+    /// Instruments that could not be valued.
+    pub unpriced: Vec<String>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Whether a liquidation is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidationStatus {
+    /// Positions are being worked down; risk-increasing orders are blocked.
+    Liquidating,
+    /// Equity covers maintenance again, or no position is left.
+    Completed,
+}
+
+/// What a liquidation step did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidationAction {
+    /// Equity fell below maintenance margin.
+    MarginBreach,
+    /// The account's resting orders were canceled.
+    OrdersCanceled,
+    /// A position was reduced by an IOC order against the book.
+    PositionReduced,
+    /// A position was transferred to the market maker.
+    TransferredToMarketMaker,
+    /// The liquidation finished.
+    Completed,
+}
+
+/// One step of a liquidation. Mirrors the server `LiquidationStep`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidationStep {
+    /// What the step did.
+    pub action: LiquidationAction,
+    /// Instrument traded, for position steps.
+    pub instrument: Option<String>,
+    /// Side the account traded, for position steps.
+    pub side: Option<OrderSide>,
+    /// Contracts traded, or orders canceled.
+    pub quantity: u64,
+    /// Average price in cents, for position steps.
+    pub price: Option<u128>,
+    /// Account equity after the step, in cents.
+    pub equity: i64,
+    /// Maintenance margin after the step, in cents.
+    pub maintenance_margin: i64,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Liquidation record of an account. Mirrors the server `Liquidation`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Liquidation {
+    /// Account identifier.
+    pub account: String,
+    /// Current status.
+    pub status: LiquidationStatus,
+    /// When the breach was detected, in milliseconds.
+    pub started_at_ms: u64,
+    /// When the liquidation finished, in milliseconds.
+    pub completed_at_ms: Option<u64>,
+    /// Steps taken, oldest first.
+    pub steps: Vec<LiquidationStep>,
+}
+
+/// Liquidations of every account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidationsResponse {
+    /// Latest liquidation per account, most recent first.
+    pub liquidations: Vec<Liquidation>,
+}
+
+/// Request body for a deposit or withdrawal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundsRequest {
+    /// Amount in dollars.
+    pub amount: f64,
+}
+
+/// Query parameters for the account ledger.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LedgerQuery {
+    /// Maximum number of entries, newest first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Cash balance of an account. Mirrors the server `AccountBalanceResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalanceResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Cash balance in cents.
+    pub cash: i64,
+    /// Cash reserved by open buy orders, in cents.
+    pub reserved: i64,
+    /// Cash free for new buy orders and withdrawals, in cents.
+    pub available: i64,
+    /// Whether order entry is checked against available cash.
+    pub buying_power_enforced: bool,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// What a ledger entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEntryKind {
+    /// Cash paid in from outside.
+    Deposit,
+    /// Cash paid out.
+    Withdrawal,
+    /// Premium and fees of a fill.
+    Trade,
+    /// Cash settlement of an expired position.
+    Settlement,
+}
+
+/// One leg of a ledger entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerPosting {
+    /// Ledger account (`cash:<account>`, `external`, `fees` or `clearing`).
+    pub account: String,
+    /// Signed amount in cents.
+    pub amount: i64,
+}
+
+/// A ledger entry. Mirrors the server `LedgerEntryResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Entry sequence number.
+    pub entry_id: u64,
+    /// What the entry records.
+    pub kind: LedgerEntryKind,
+    /// Trade id, symbol or note the entry refers to.
+    pub reference: String,
+    /// Net change to the account's cash, in cents.
+    pub amount: i64,
+    /// Every leg of the entry.
+    pub postings: Vec<LedgerPosting>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Ledger entries of an account. Mirrors the server `LedgerEntriesResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntriesResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Cash balance in cents.
+    pub cash: i64,
+    /// Entries, newest first.
+    pub entries: Vec<LedgerEntry>,
+}
@@ -417,6 +417,7 @@ fn test_token_request_serialization() {
         secret: "bootstrap".to_string(),
         permissions: vec![Permission::Read, Permission::Trade],
         ttl_secs: Some(3600),
+        account: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -431,6 +432,7 @@ fn test_token_request_skips_none_ttl() {
         secret: "bootstrap".to_string(),
         permissions: vec![Permission::Admin],
         ttl_secs: None,
+        account: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -513,6 +515,19 @@ fn test_orderbook_snapshot_info_serialization() {
     assert!(json.contains("\"underlying\":\"AAPL\""));
 }
 
+#[test]
+fn test_snapshot_summary_status_defaults() {
+    let json = r#"{"snapshot_id":"snap-1","orderbook_count":2,"total_orders":7,"created_at":1}"#;
+    let summary: SnapshotSummary = serde_json::from_str(json).unwrap();
+    assert!(summary.success);
+    assert_eq!(summary.trigger, SnapshotTrigger::Manual);
+
+    let json = r#"{"snapshot_id":"snap-2","orderbook_count":2,"total_orders":7,"success":false,"trigger":"shutdown","created_at":1}"#;
+    let summary: SnapshotSummary = serde_json::from_str(json).unwrap();
+    assert!(!summary.success);
+    assert_eq!(summary.trigger, SnapshotTrigger::Shutdown);
+}
+
 // ============================================================================
 // OrderStatus Tests
 // ============================================================================
@@ -653,6 +668,29 @@ fn test_ohlc_bar_serialization() {
     assert!(json.contains("\"close\":10200"));
 }
 
+// ============================================================================
+// PriceHistory Tests
+// ============================================================================
+
+#[test]
+fn test_price_history_round_trip() {
+    let query = PriceHistoryQuery {
+        interval: Some("1h".to_string()),
+        limit: Some(24),
+        ..Default::default()
+    };
+    let params = serde_urlencoded::to_string(&query).unwrap();
+    assert_eq!(params, "limit=24&interval=1h");
+
+    let response: PriceHistoryResponse = serde_json::from_str(
+        r#"{"symbol":"BTC","points":[{"timestamp":"2024-01-01T00:00:00+00:00","open":1.0,"high":2.0,"low":0.5,"close":1.5,"volume":null,"samples":3}],"realized_volatility":{"returns":0,"total_log_return":null,"return_stddev":null,"annualized":null}}"#,
+    )
+    .unwrap();
+    assert!(response.interval.is_none());
+    assert_eq!(response.points[0].samples, 3);
+    assert!(response.realized_volatility.annualized.is_none());
+}
+
 // ============================================================================
 // OhlcQuery Tests
 // ============================================================================
@@ -667,6 +705,44 @@ fn test_ohlc_query_default() {
     assert!(query.limit.is_none());
 }
 
+#[test]
+fn test_ohlc_backfill_round_trip() {
+    let request = OhlcBackfillRequest {
+        symbol: Some("BTC-20251231-100000-C".to_string()),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&request).unwrap();
+    assert_eq!(json, r#"{"symbol":"BTC-20251231-100000-C"}"#);
+
+    let response: OhlcBackfillResponse =
+        serde_json::from_str(r#"{"executions":5,"bars":13,"from":0}"#).unwrap();
+    assert_eq!(response.bars, 13);
+    assert!(response.to.is_none());
+}
+
+// ============================================================================
+// QuoteHistory Tests
+// ============================================================================
+
+#[test]
+fn test_quote_history_round_trip() {
+    let query = QuoteHistoryQuery {
+        from: Some(1_000),
+        to: Some(2_000),
+        ..Default::default()
+    };
+    let params = serde_urlencoded::to_string(&query).unwrap();
+    assert_eq!(params, "from=1000&to=2000");
+
+    let response: QuoteHistoryResponse = serde_json::from_str(
+        r#"{"symbol":"BTC-20251231-100000-C","quotes":[{"bid_price":500,"bid_size":3,"ask_price":null,"ask_size":0,"timestamp_ms":1500}]}"#,
+    )
+    .unwrap();
+    assert_eq!(response.quotes.len(), 1);
+    assert_eq!(response.quotes[0].bid_price, Some(500));
+    assert!(response.quotes[0].ask_price.is_none());
+}
+
 // ============================================================================
 // SpreadMetrics Tests
 // ============================================================================
@@ -706,6 +782,7 @@ fn test_volatility_surface_response_serialization() {
         timestamp_ms: 1704067200000,
         expirations: vec!["20240315".to_string()],
         strikes: vec![15000],
+        forwards: std::collections::HashMap::new(),
         surface,
         atm_term_structure: vec![],
     };
@@ -714,6 +791,26 @@ fn test_volatility_surface_response_serialization() {
     assert!(json.contains("\"underlying\":\"AAPL\""));
 }
 
+#[test]
+fn test_volatility_surface_history_round_trip() {
+    let query = VolatilitySurfaceHistoryQuery {
+        at: Some("1000,2000".to_string()),
+        limit: Some(10),
+        ..Default::default()
+    };
+    let params = serde_urlencoded::to_string(&query).unwrap();
+    assert_eq!(params, "at=1000%2C2000&limit=10");
+
+    let response: VolatilitySurfaceHistoryResponse = serde_json::from_str(
+        r#"{"underlying":"BTC","surfaces":[],"series":[{"timestamp_ms":1500,"spot_price":5000000,"expirations":[{"expiration":"20251231","days":30,"atm_iv":0.5,"risk_reversal_25d":-0.02}]}]}"#,
+    )
+    .unwrap();
+    let skew = &response.series[0].expirations[0];
+    assert_eq!(skew.atm_iv, Some(0.5));
+    assert_eq!(skew.risk_reversal_25d, Some(-0.02));
+    assert!(skew.butterfly_25d.is_none());
+}
+
 // ============================================================================
 // OptionChainResponse Tests
 // ============================================================================
@@ -724,6 +821,7 @@ fn test_option_chain_response_serialization() {
         underlying: "AAPL".to_string(),
         expiration: "20240315".to_string(),
         spot_price: Some(15000),
+        forward_price: Some(15050),
         atm_strike: Some(15000),
         chain: vec![ChainStrikeRow {
             strike: 15000,
@@ -823,3 +921,457 @@ fn test_quote_response_deserializes_beyond_u64_prices() {
     assert_eq!(quote.bid_price, Some(18_446_744_073_709_551_616_u128));
     assert_eq!(quote.ask_price, Some(36_893_488_147_419_103_232_u128));
 }
+
+// ============================================================================
+// ImpliedForwardResponse Tests
+// ============================================================================
+
+#[test]
+fn test_option_chain_response_tolerates_missing_forward() {
+    let json = r#"{"underlying":"AAPL","expiration":"20240315","spot_price":15000,"atm_strike":15000,"chain":[]}"#;
+    let response: OptionChainResponse = serde_json::from_str(json).unwrap();
+    assert!(response.forward_price.is_none());
+}
+
+#[test]
+fn test_implied_forward_response_deserialization() {
+    let json = r#"{
+        "underlying": "BTC",
+        "expiration": "20240329",
+        "spot_price": 5000000,
+        "days_to_expiry": 30,
+        "forward_price": 5020000,
+        "discount_factor": 0.996,
+        "implied_rate": 0.049,
+        "implied_carry": 0.048,
+        "basis": 20000,
+        "rmse_cents": 12.5,
+        "strikes_used": 2,
+        "threshold_bps": 50.0,
+        "violations": 1,
+        "strikes": [
+            {"strike": 4900000, "call_mid": 180000, "put_mid": 60000, "synthetic_forward": 5020000,
+             "residual_cents": -3, "deviation_bps": 0.01, "violation": false},
+            {"strike": 5100000, "call_mid": 60000, "put_mid": null, "synthetic_forward": null,
+             "residual_cents": null, "deviation_bps": null, "violation": false}
+        ],
+        "timestamp_ms": 1704067200000
+    }"#;
+
+    let response: ImpliedForwardResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.forward_price, Some(5_020_000));
+    assert_eq!(response.basis, Some(20_000));
+    assert_eq!(response.strikes.len(), 2);
+    assert_eq!(response.strikes[0].residual_cents, Some(-3));
+    assert!(response.strikes[1].put_mid.is_none());
+}
+
+#[test]
+fn test_token_request_serializes_account_when_set() {
+    let request = TokenRequest {
+        secret: "bootstrap".to_string(),
+        permissions: vec![Permission::Trade],
+        ttl_secs: None,
+        account: Some("desk-7".to_string()),
+    };
+
+    let json = serde_json::to_string(&request).unwrap();
+    assert!(json.contains("\"account\":\"desk-7\""));
+}
+
+#[test]
+fn test_risk_greeks_query_serialization() {
+    let query = RiskGreeksQuery {
+        underlying: Some("BTC".to_string()),
+    };
+    assert_eq!(
+        serde_urlencoded::to_string(&query).unwrap(),
+        "underlying=BTC"
+    );
+    assert_eq!(
+        serde_urlencoded::to_string(RiskGreeksQuery::default()).unwrap(),
+        ""
+    );
+}
+
+#[test]
+fn test_portfolio_greeks_response_deserialization() {
+    let greeks = r#"{"delta":1.2,"gamma":0.01,"vega":3.5,"theta":-0.8,"rho":0.4,"dollar_delta":60000.0,"dollar_gamma":250.0}"#;
+    let json = format!(
+        r#"{{
+        "account": "desk-7",
+        "positions": {{
+            "underlyings": [{{
+                "underlying": "BTC",
+                "spot": 5000000,
+                "greeks": {greeks},
+                "expirations": [{{"expiration": "20240329", "positions": 2, "greeks": {greeks}}}]
+            }}],
+            "total": {greeks},
+            "unpriced": ["XYZ-20240329-100-C"]
+        }},
+        "market_maker": {{"underlyings": [], "total": {greeks}, "unpriced": []}},
+        "timestamp_ms": 1704067200000
+    }}"#
+    );
+
+    let response: PortfolioGreeksResponse = serde_json::from_str(&json).unwrap();
+    assert_eq!(response.account, "desk-7");
+    assert_eq!(
+        response.positions.underlyings[0].expirations[0].positions,
+        2
+    );
+    assert_eq!(response.positions.total.theta, -0.8);
+    assert_eq!(response.positions.unpriced.len(), 1);
+    assert!(response.market_maker.underlyings.is_empty());
+}
+
+#[test]
+fn test_scenario_query_serialization() {
+    let query = ScenarioQuery {
+        underlying: None,
+        spot_shocks: Some("-10,0,10".to_string()),
+        vol_shocks: None,
+        days: Some("0,7".to_string()),
+    };
+    assert_eq!(
+        serde_urlencoded::to_string(&query).unwrap(),
+        "spot_shocks=-10%2C0%2C10&days=0%2C7"
+    );
+}
+
+#[test]
+fn test_scenario_analysis_response_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "positions": 2,
+        "base_value": 125000,
+        "spot_shocks_pct": [-10.0, 0.0, 10.0],
+        "vol_shocks_pts": [0.0],
+        "grid": [{"days_forward": 0, "pnl": [[-5000], [0], [6200]]}],
+        "stress": [{
+            "name": "btc-crash",
+            "description": "BTC -30%",
+            "days_forward": 0,
+            "pnl": -40000,
+            "by_underlying": [{"underlying": "BTC", "pnl": -40000}]
+        }],
+        "unpriced": [],
+        "timestamp_ms": 1704067200000
+    }"#;
+
+    let response: ScenarioAnalysisResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.grid[0].pnl[2][0], 6200);
+    assert_eq!(response.stress[0].by_underlying[0].underlying, "BTC");
+    assert_eq!(response.base_value, 125_000);
+}
+
+#[test]
+fn test_var_response_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "confidence": 0.99,
+        "horizon_days": 1,
+        "positions": 3,
+        "base_value": 250000,
+        "monte_carlo": {"var": 41000, "expected_shortfall": 52000, "scenarios": 1000},
+        "historical": null,
+        "unpriced": [],
+        "notes": ["historical VaR unavailable: no database configured"],
+        "computed_at_ms": 1704067200000
+    }"#;
+
+    let response: VarResponse = serde_json::from_str(json).unwrap();
+    let mc = response.monte_carlo.expect("monte carlo");
+    assert_eq!(mc.var, 41_000);
+    assert!(mc.expected_shortfall >= mc.var);
+    assert!(response.historical.is_none());
+    assert_eq!(
+        serde_urlencoded::to_string(VarQuery {
+            refresh: Some(true)
+        })
+        .unwrap(),
+        "refresh=true"
+    );
+}
+
+#[test]
+fn test_account_margin_response_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "enforced": true,
+        "collateral": 500000,
+        "initial_margin": 180000,
+        "maintenance_margin": 135000,
+        "excess_collateral": 320000,
+        "margin_call": false,
+        "underlyings": [{
+            "underlying": "BTC",
+            "scan_risk": 180000,
+            "worst_scenario": 10,
+            "spread_credit": 42000,
+            "short_option_minimum": 5000,
+            "requirement": 180000
+        }],
+        "unpriced": [],
+        "timestamp_ms": 1704067200000
+    }"#;
+
+    let response: AccountMarginResponse = serde_json::from_str(json).unwrap();
+    assert!(response.enforced);
+    assert_eq!(response.underlyings[0].spread_credit, 42_000);
+    assert_eq!(
+        response.collateral - response.initial_margin,
+        response.excess_collateral
+    );
+    assert_eq!(
+        serde_json::to_value(SetCollateralRequest { collateral: 5000.0 }).unwrap(),
+        serde_json::json!({"collateral": 5000.0})
+    );
+}
+
+#[test]
+fn test_hedging_response_deserialization() {
+    let json = r#"{
+        "enabled": true,
+        "delta_band": 1.0,
+        "lot_size": 0.01,
+        "underlyings": [
+            {
+                "underlying": "BTC",
+                "spot": 5000000,
+                "option_delta": 9.87,
+                "hedge_position": -9.87,
+                "residual_delta": 0.0,
+                "hedge_pnl": -98700,
+                "trades": 1
+            }
+        ],
+        "trades": [
+            {
+                "underlying": "BTC",
+                "quantity": -9.87,
+                "price": 4990000,
+                "option_delta": 9.87,
+                "hedge_position": -9.87,
+                "residual_delta": 0.0,
+                "timestamp_ms": 1704067200000
+            }
+        ]
+    }"#;
+
+    let response: HedgingResponse = serde_json::from_str(json).unwrap();
+    assert!(response.enabled);
+    assert_eq!(response.underlyings[0].spot, Some(5_000_000));
+    assert_eq!(response.underlyings[0].hedge_pnl, -98_700);
+    assert_eq!(response.trades[0].quantity, -9.87);
+}
+
+#[test]
+fn test_queue_positions_response_deserialization() {
+    let json = r#"{
+        "orders": [
+            {
+                "instrument": "BTC-20351231-5000000-C",
+                "underlying": "BTC",
+                "side": "buy",
+                "price": 412550,
+                "quantity": 10,
+                "orders_ahead": 2,
+                "quantity_ahead": 15,
+                "level_quantity": 30
+            }
+        ]
+    }"#;
+
+    let response: QueuePositionsResponse = serde_json::from_str(json).unwrap();
+    let order = &response.orders[0];
+    assert_eq!(order.side, OrderSide::Buy);
+    assert_eq!(order.orders_ahead, 2);
+    assert_eq!(order.quantity_ahead, 15);
+
+    let query = QueuePositionQuery {
+        underlying: Some("BTC".to_string()),
+    };
+    assert_eq!(
+        serde_urlencoded::to_string(&query).unwrap(),
+        "underlying=BTC"
+    );
+}
+
+#[test]
+fn test_requote_stats_response_deserialization() {
+    let json = r#"{
+        "min_price_change_ticks": 2,
+        "min_size_change_pct": 10.0,
+        "max_updates_per_second": 5,
+        "sent": 1200,
+        "skipped": 8800,
+        "skipped_unchanged": 8000,
+        "skipped_below_threshold": 700,
+        "skipped_rate_limited": 100
+    }"#;
+
+    let response: RequoteStatsResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.min_price_change_ticks, 2);
+    assert_eq!(response.sent, 1200);
+    assert_eq!(response.skipped, 8800);
+    assert_eq!(response.skipped_rate_limited, 100);
+}
+
+#[test]
+fn test_pnl_attribution_response_deserialization() {
+    let json = r#"{
+        "since_start": {
+            "start_ms": 1704067200000,
+            "end_ms": null,
+            "underlyings": [
+                {
+                    "underlying": "BTC",
+                    "pnl": {"edge": 900, "delta": -300, "gamma": 40, "vega": 0, "theta": -75, "hedging": 280, "total": 845}
+                }
+            ],
+            "total": {"edge": 900, "delta": -300, "gamma": 40, "vega": 0, "theta": -75, "hedging": 280, "total": 845}
+        },
+        "current_period": {
+            "start_ms": 1704067500000,
+            "end_ms": null,
+            "underlyings": [],
+            "total": {"edge": 0, "delta": 0, "gamma": 0, "vega": 0, "theta": 0, "hedging": 0, "total": 0}
+        },
+        "periods": []
+    }"#;
+
+    let response: PnlAttributionResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.since_start.end_ms, None);
+    assert_eq!(response.since_start.underlyings[0].pnl.hedging, 280);
+    assert_eq!(response.since_start.total.total, 845);
+    assert!(response.periods.is_empty());
+}
+
+#[test]
+fn test_liquidation_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "status": "completed",
+        "started_at_ms": 1704067200000,
+        "completed_at_ms": 1704067200005,
+        "steps": [
+            {
+                "action": "margin_breach",
+                "instrument": null,
+                "side": null,
+                "quantity": 0,
+                "price": null,
+                "equity": -1200,
+                "maintenance_margin": 75000,
+                "timestamp_ms": 1704067200000
+            },
+            {
+                "action": "transferred_to_market_maker",
+                "instrument": "BTC-20351231-5000000-C",
+                "side": "buy",
+                "quantity": 3,
+                "price": 52500,
+                "equity": -9000,
+                "maintenance_margin": 0,
+                "timestamp_ms": 1704067200005
+            }
+        ]
+    }"#;
+
+    let liquidation: Liquidation = serde_json::from_str(json).unwrap();
+    assert_eq!(liquidation.status, LiquidationStatus::Completed);
+    assert_eq!(liquidation.steps[0].action, LiquidationAction::MarginBreach);
+    let transfer = &liquidation.steps[1];
+    assert_eq!(transfer.action, LiquidationAction::TransferredToMarketMaker);
+    assert_eq!(transfer.side, Some(OrderSide::Buy));
+    assert_eq!(transfer.price, Some(52_500));
+}
+
+#[test]
+fn test_ledger_entries_response_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "cash": 97495,
+        "entries": [{
+            "entry_id": 2,
+            "kind": "trade",
+            "reference": "trade-1",
+            "amount": -2505,
+            "postings": [
+                {"account": "cash:desk-7", "amount": -2500},
+                {"account": "clearing", "amount": 2500},
+                {"account": "cash:desk-7", "amount": -5},
+                {"account": "fees", "amount": 5}
+            ],
+            "timestamp_ms": 1704067200000
+        }]
+    }"#;
+
+    let response: LedgerEntriesResponse = serde_json::from_str(json).unwrap();
+    let entry = &response.entries[0];
+    assert_eq!(entry.kind, LedgerEntryKind::Trade);
+    assert_eq!(entry.postings.iter().map(|p| p.amount).sum::<i64>(), 0);
+    assert_eq!(
+        serde_urlencoded::to_string(LedgerQuery { limit: Some(10) }).unwrap(),
+        "limit=10"
+    );
+    assert_eq!(
+        serde_urlencoded::to_string(LedgerQuery::default()).unwrap(),
+        ""
+    );
+}
+
+#[test]
+fn test_account_balance_response_deserialization() {
+    let json = r#"{
+        "account": "desk-7",
+        "cash": 100000,
+        "reserved": 25050,
+        "available": 74950,
+        "buying_power_enforced": true,
+        "timestamp_ms": 1704067200000
+    }"#;
+
+    let response: AccountBalanceResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.cash - response.reserved, response.available);
+    assert!(response.buying_power_enforced);
+}
+
+#[test]
+fn test_instruments_list_response_deserialization() {
+    let json = r#"{
+        "instruments": [
+            {"symbol": "BTC", "quoting_enabled": true, "current_price": 87946.0,
+             "strategy": "layered", "ladder_levels": 3, "resting_orders": 1200},
+            {"symbol": "ETH", "quoting_enabled": false, "current_price": null,
+             "strategy": "single", "ladder_levels": 1, "resting_orders": 0}
+        ]
+    }"#;
+
+    let response: InstrumentsListResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.instruments[0].strategy, "layered");
+    assert_eq!(response.instruments[0].ladder_levels, 3);
+    assert!(response.instruments[1].current_price.is_none());
+}
+
+#[test]
+fn test_instrument_spec_response_deserialization() {
+    let json = r#"{
+        "symbol": "AAPL-20240329-150-C",
+        "underlying": "AAPL",
+        "expiration": "20240329",
+        "strike": 150,
+        "style": "call",
+        "contract_multiplier": 100,
+        "min_lot_size": 1,
+        "tick_bands": [{"below": 300, "tick": 5}, {"tick": 10}]
+    }"#;
+
+    let response: InstrumentSpecResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(response.contract_multiplier, 100);
+    assert!(response.max_order_size.is_none());
+    assert_eq!(response.tick_bands[0].below, Some(300));
+    assert!(response.tick_bands[1].below.is_none());
+}
@@ -71,6 +71,28 @@ pub enum WsMessage {
         /// Price in cents.
         price_cents: u64,
     },
+    /// Market-maker delta hedge in the underlying, broadcast like `fill`.
+    #[serde(rename = "hedge")]
+    Hedge {
+        /// Underlying symbol.
+        symbol: String,
+        /// Signed quantity of the underlying (positive = bought).
+        quantity: f64,
+        /// Execution price in cents.
+        price: u64,
+        /// Hedge position after the trade.
+        hedge_position: f64,
+        /// Net option delta plus hedge position after the trade.
+        residual_delta: f64,
+        /// Hedge P&L in cents.
+        hedge_pnl: i64,
+    },
+    /// Market-maker P&L attribution of a closed period, broadcast like `fill`.
+    #[serde(rename = "pnl")]
+    Pnl {
+        /// The closed period.
+        period: crate::types::PnlPeriodResponse,
+    },
     /// Connection established.
     #[serde(rename = "connected")]
     Connected {
@@ -715,4 +737,35 @@ mod tests {
             other => panic!("expected Fill, got {other:?}"),
         }
     }
+
+    #[test]
+    fn test_hedge_message_deserializes() {
+        let json = r#"{"type":"hedge","data":{"symbol":"BTC","quantity":-2.5,"price":5000000,"hedge_position":-2.5,"residual_delta":0.1,"hedge_pnl":-1200}}"#;
+        let msg: WsMessage = serde_json::from_str(json).expect("hedge deserializes");
+        match msg {
+            WsMessage::Hedge {
+                quantity,
+                hedge_pnl,
+                ..
+            } => {
+                assert_eq!(quantity, -2.5);
+                assert_eq!(hedge_pnl, -1200);
+            }
+            other => panic!("expected Hedge, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_pnl_message_deserializes() {
+        let json = r#"{"type":"pnl","data":{"period":{"start_ms":1000,"end_ms":2000,"underlyings":[{"underlying":"BTC","pnl":{"edge":500,"delta":0,"gamma":0,"vega":0,"theta":-120,"hedging":0,"total":380}}],"total":{"edge":500,"delta":0,"gamma":0,"vega":0,"theta":-120,"hedging":0,"total":380}}}}"#;
+        let msg: WsMessage = serde_json::from_str(json).expect("pnl deserializes");
+        match msg {
+            WsMessage::Pnl { period } => {
+                assert_eq!(period.end_ms, Some(2000));
+                assert_eq!(period.underlyings[0].pnl.theta, -120);
+                assert_eq!(period.total.total, 380);
+            }
+            other => panic!("expected Pnl, got {other:?}"),
+        }
+    }
 }
@@ -194,6 +194,7 @@ async fn obtain_token_slot(permissions: Vec<Permission>, slot: usize) -> Result<
             secret: get_bootstrap_secret(),
             permissions,
             ttl_secs: Some(ttl_secs),
+            account: None,
         })
         .await?;
     let expiry = now_secs() + ttl_secs;
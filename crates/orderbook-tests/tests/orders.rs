@@ -123,8 +123,8 @@ async fn test_modify_order() {
     assert_eq!(modified.new_price, Some(1450));
     assert_eq!(modified.new_quantity, Some(12));
     assert!(modified.priority_changed);
-    // Cancel-and-replace assigns a new order id.
-    assert_ne!(modified.order_id, order_id);
+    // The amend keeps the order id.
+    assert_eq!(modified.order_id, order_id);
 
     // The book reflects the modified resting order.
     let book = book.expect("book");
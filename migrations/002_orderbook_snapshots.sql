@@ -0,0 +1,12 @@
+-- Durable orderbook snapshots (POST /api/v1/admin/snapshot)
+
+CREATE TABLE IF NOT EXISTS orderbook_snapshots (
+    snapshot_id TEXT PRIMARY KEY,
+    created_at_ms BIGINT NOT NULL,
+    orderbook_count BIGINT NOT NULL,
+    total_orders BIGINT NOT NULL,
+    -- JSON array of the per-orderbook snapshot entries
+    data TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orderbook_snapshots_created_at ON orderbook_snapshots(created_at_ms DESC);
@@ -0,0 +1,4 @@
+-- Snapshot status: orderbooks left out of a partial snapshot and what took it
+
+ALTER TABLE orderbook_snapshots ADD COLUMN IF NOT EXISTS orderbooks_failed BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE orderbook_snapshots ADD COLUMN IF NOT EXISTS trigger TEXT NOT NULL DEFAULT 'manual';
@@ -0,0 +1,4 @@
+-- Whole-state snapshots: format version and the engine state captured with the books
+
+ALTER TABLE orderbook_snapshots ADD COLUMN IF NOT EXISTS format_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE orderbook_snapshots ADD COLUMN IF NOT EXISTS engine_state TEXT;
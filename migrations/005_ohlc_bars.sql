@@ -0,0 +1,22 @@
+-- Durable OHLC history: bars flushed from the in-memory aggregator, and the
+-- trade id of persisted executions so a re-flushed fill is written once
+
+CREATE TABLE IF NOT EXISTS ohlc_bars (
+    -- UNDERLYING-EXPIRATION-STRIKE-STYLE option key
+    symbol TEXT NOT NULL,
+    -- 1m, 5m, 15m, 1h, 4h or 1d
+    bar_interval TEXT NOT NULL,
+    -- Bar start in seconds since epoch
+    bar_start BIGINT NOT NULL,
+    open_cents BIGINT NOT NULL,
+    high_cents BIGINT NOT NULL,
+    low_cents BIGINT NOT NULL,
+    close_cents BIGINT NOT NULL,
+    volume BIGINT NOT NULL,
+    trade_count BIGINT NOT NULL,
+    PRIMARY KEY (symbol, bar_interval, bar_start)
+);
+
+ALTER TABLE executions ADD COLUMN IF NOT EXISTS trade_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_executions_trade_id ON executions(trade_id);
+CREATE INDEX IF NOT EXISTS idx_executions_instrument_executed_at ON executions(instrument, executed_at);
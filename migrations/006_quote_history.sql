@@ -0,0 +1,19 @@
+-- Top-of-book history: one row per observed change of an option's best bid
+-- or ask, appended by the quote recorder
+
+CREATE TABLE IF NOT EXISTS quote_history (
+    -- UNDERLYING-EXPIRATION-STRIKE-STYLE option key
+    symbol TEXT NOT NULL,
+    -- Sample time in milliseconds since epoch
+    recorded_at BIGINT NOT NULL,
+    -- Best bid price in cents (NULL when the bid side is empty)
+    bid_price BIGINT,
+    -- Quantity at the best bid
+    bid_size BIGINT NOT NULL,
+    -- Best ask price in cents (NULL when the ask side is empty)
+    ask_price BIGINT,
+    -- Quantity at the best ask
+    ask_size BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_quote_history_symbol_recorded_at ON quote_history(symbol, recorded_at);
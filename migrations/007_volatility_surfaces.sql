@@ -0,0 +1,15 @@
+-- Volatility surface history: surfaces captured on the [surface_history]
+-- schedule, with the skew metrics derived from each
+
+CREATE TABLE IF NOT EXISTS volatility_surfaces (
+    underlying TEXT NOT NULL,
+    -- Surface computation time in milliseconds since epoch
+    captured_at BIGINT NOT NULL,
+    -- Spot price in cents the surface was computed against
+    spot_price BIGINT,
+    -- JSON of the whole surface response
+    surface TEXT NOT NULL,
+    -- JSON array of the per-expiration ATM IV, risk reversal and butterfly
+    metrics TEXT NOT NULL,
+    PRIMARY KEY (underlying, captured_at)
+);
@@ -0,0 +1,114 @@
+-- Relational schema for the in-memory trading state: accounts, orders with
+-- their state history, fills (in the executions audit trail), per-account
+-- positions, last trades and the cash ledger, written by the repository on
+-- the [persistence] schedule. Snapshots and OHLC bars keep their own tables
+-- (orderbook_snapshots, ohlc_bars).
+
+CREATE TABLE IF NOT EXISTS accounts (
+    account_id TEXT PRIMARY KEY,
+    -- Pledged collateral in cents (NULL holds [risk.margin] default_collateral)
+    collateral_cents BIGINT,
+    -- Cash balance in the ledger, in cents
+    cash_cents BIGINT NOT NULL DEFAULT 0,
+    -- Milliseconds since epoch
+    created_at_ms BIGINT NOT NULL,
+    updated_at_ms BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS orders (
+    order_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id),
+    -- UNDERLYING-EXPIRATION-STRIKE-STYLE option key
+    symbol TEXT NOT NULL,
+    underlying TEXT NOT NULL,
+    expiration TEXT NOT NULL,
+    strike BIGINT NOT NULL,
+    -- call or put
+    style TEXT NOT NULL,
+    -- buy or sell
+    side TEXT NOT NULL,
+    -- Limit price in cents
+    price_cents BIGINT NOT NULL,
+    original_quantity BIGINT NOT NULL,
+    remaining_quantity BIGINT NOT NULL,
+    filled_quantity BIGINT NOT NULL,
+    -- pending, active, partial, filled or canceled
+    status TEXT NOT NULL,
+    -- GTC, IOC, FOK or GTD
+    time_in_force TEXT NOT NULL,
+    created_at_ms BIGINT NOT NULL,
+    updated_at_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_account_created_at ON orders(account_id, created_at_ms);
+
+-- One row per observed change of an order's status or filled quantity
+CREATE TABLE IF NOT EXISTS order_events (
+    id BIGSERIAL PRIMARY KEY,
+    order_id TEXT NOT NULL REFERENCES orders(order_id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    filled_quantity BIGINT NOT NULL,
+    remaining_quantity BIGINT NOT NULL,
+    recorded_at_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_events_order_id ON order_events(order_id, id);
+
+-- Fills extend the executions audit trail (001, 005): trade_id is the fill's
+-- execution id, order_id the taker and counterparty_order_id the maker,
+-- symbol the underlying and instrument the option key
+ALTER TABLE executions
+    ALTER COLUMN order_id TYPE TEXT,
+    ALTER COLUMN symbol TYPE TEXT,
+    ALTER COLUMN instrument TYPE TEXT,
+    ADD COLUMN IF NOT EXISTS counterparty_order_id TEXT,
+    ADD COLUMN IF NOT EXISTS fee_cents BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS is_maker BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_executions_counterparty_order_id ON executions(counterparty_order_id);
+
+CREATE TABLE IF NOT EXISTS positions (
+    account_id TEXT NOT NULL REFERENCES accounts(account_id),
+    symbol TEXT NOT NULL,
+    underlying TEXT NOT NULL,
+    -- Positive long, negative short
+    quantity BIGINT NOT NULL,
+    average_price_cents BIGINT NOT NULL,
+    realized_pnl_cents BIGINT NOT NULL,
+    multiplier BIGINT NOT NULL,
+    created_at_ms BIGINT NOT NULL,
+    updated_at_ms BIGINT NOT NULL,
+    PRIMARY KEY (account_id, symbol)
+);
+
+CREATE TABLE IF NOT EXISTS last_trades (
+    symbol TEXT PRIMARY KEY,
+    trade_id TEXT NOT NULL,
+    price_cents BIGINT NOT NULL,
+    quantity BIGINT NOT NULL,
+    -- Taker side
+    side TEXT NOT NULL,
+    traded_at_ms BIGINT NOT NULL
+);
+
+-- The double-entry cash ledger: one row per journal entry, one per posting
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    entry_id BIGINT PRIMARY KEY,
+    -- deposit, withdrawal, trade or settlement
+    kind TEXT NOT NULL,
+    -- Trade id, symbol or note the entry refers to
+    reference TEXT NOT NULL,
+    recorded_at_ms BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ledger_postings (
+    entry_id BIGINT NOT NULL REFERENCES ledger_entries(entry_id) ON DELETE CASCADE,
+    -- Position of the posting within its entry
+    leg INTEGER NOT NULL,
+    -- cash:<account>, external, fees or clearing
+    ledger_account TEXT NOT NULL,
+    amount_cents BIGINT NOT NULL,
+    PRIMARY KEY (entry_id, leg)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_postings_ledger_account ON ledger_postings(ledger_account);
@@ -0,0 +1,7 @@
+-- Insertion order of underlying prices, so prices recorded at the same
+-- instant keep their order in price history bars (the UUID id has none)
+
+ALTER TABLE underlying_prices ADD COLUMN IF NOT EXISTS seq BIGSERIAL;
+
+CREATE INDEX IF NOT EXISTS idx_underlying_prices_symbol_timestamp_seq
+    ON underlying_prices(symbol, timestamp, seq);
@@ -0,0 +1,48 @@
+-- Initial SQLite schema: the prices and market maker settings of
+-- ../001_initial_schema.sql. Executions come with the trading state
+-- (002_trading_schema.sql) and OHLC bars with 003_ohlc_bars.sql; snapshots,
+-- quote history and volatility surfaces are PostgreSQL only.
+
+-- Underlying prices table
+CREATE TABLE IF NOT EXISTS underlying_prices (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    price_cents INTEGER NOT NULL,
+    bid_cents INTEGER,
+    ask_cents INTEGER,
+    volume INTEGER,
+    -- Milliseconds since epoch
+    timestamp_ms INTEGER NOT NULL,
+    source TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_underlying_prices_symbol_timestamp ON underlying_prices(symbol, timestamp_ms DESC);
+
+-- Market maker configuration per symbol
+CREATE TABLE IF NOT EXISTS market_maker_configs (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL UNIQUE,
+    quoting_enabled BOOLEAN NOT NULL DEFAULT 1,
+    spread_multiplier REAL NOT NULL DEFAULT 1.0,
+    size_scalar REAL NOT NULL DEFAULT 1.0,
+    directional_skew REAL NOT NULL DEFAULT 0.0,
+    max_position INTEGER NOT NULL DEFAULT 1000,
+    max_delta REAL NOT NULL DEFAULT 100.0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- System-wide control settings (singleton table)
+CREATE TABLE IF NOT EXISTS system_control (
+    id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    master_enabled BOOLEAN NOT NULL DEFAULT 1,
+    global_spread_multiplier REAL NOT NULL DEFAULT 1.0,
+    global_size_scalar REAL NOT NULL DEFAULT 1.0,
+    global_directional_skew REAL NOT NULL DEFAULT 0.0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Insert default system control row
+INSERT INTO system_control (id, master_enabled, global_spread_multiplier, global_size_scalar, global_directional_skew)
+VALUES (1, 1, 1.0, 1.0, 0.0)
+ON CONFLICT (id) DO NOTHING;
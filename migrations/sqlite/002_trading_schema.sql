@@ -0,0 +1,126 @@
+-- SQLite version of ../008_trading_schema.sql: accounts, orders with their
+-- state history, executions, per-account positions, last trades and the cash
+-- ledger, written by the repository on the [persistence] schedule.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    account_id TEXT PRIMARY KEY,
+    -- Pledged collateral in cents (NULL holds [risk.margin] default_collateral)
+    collateral_cents INTEGER,
+    -- Cash balance in the ledger, in cents
+    cash_cents INTEGER NOT NULL DEFAULT 0,
+    -- Milliseconds since epoch
+    created_at_ms INTEGER NOT NULL,
+    updated_at_ms INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS orders (
+    order_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(account_id),
+    -- UNDERLYING-EXPIRATION-STRIKE-STYLE option key
+    symbol TEXT NOT NULL,
+    underlying TEXT NOT NULL,
+    expiration TEXT NOT NULL,
+    strike INTEGER NOT NULL,
+    -- call or put
+    style TEXT NOT NULL,
+    -- buy or sell
+    side TEXT NOT NULL,
+    -- Limit price in cents
+    price_cents INTEGER NOT NULL,
+    original_quantity INTEGER NOT NULL,
+    remaining_quantity INTEGER NOT NULL,
+    filled_quantity INTEGER NOT NULL,
+    -- pending, active, partial, filled or canceled
+    status TEXT NOT NULL,
+    -- GTC, IOC, FOK or GTD
+    time_in_force TEXT NOT NULL,
+    created_at_ms INTEGER NOT NULL,
+    updated_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_account_created_at ON orders(account_id, created_at_ms);
+
+-- One row per observed change of an order's status or filled quantity
+CREATE TABLE IF NOT EXISTS order_events (
+    id INTEGER PRIMARY KEY,
+    order_id TEXT NOT NULL REFERENCES orders(order_id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    filled_quantity INTEGER NOT NULL,
+    remaining_quantity INTEGER NOT NULL,
+    recorded_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_events_order_id ON order_events(order_id, id);
+
+-- Execution audit trail, as ../001_initial_schema.sql with the fill columns
+-- of ../008_trading_schema.sql: one row per trade; trade_id is the fill's
+-- execution id, order_id the taker and counterparty_order_id the maker,
+-- symbol the underlying and instrument the option key
+CREATE TABLE IF NOT EXISTS executions (
+    id INTEGER PRIMARY KEY,
+    trade_id TEXT UNIQUE,
+    order_id TEXT NOT NULL,
+    counterparty_order_id TEXT,
+    symbol TEXT NOT NULL,
+    instrument TEXT NOT NULL,
+    side TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    price_cents INTEGER NOT NULL,
+    fee_cents INTEGER NOT NULL DEFAULT 0,
+    theo_value_cents INTEGER,
+    edge_cents INTEGER,
+    latency_us INTEGER,
+    is_maker BOOLEAN NOT NULL DEFAULT 0,
+    -- Milliseconds since epoch
+    executed_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_executions_order_id ON executions(order_id);
+CREATE INDEX IF NOT EXISTS idx_executions_counterparty_order_id ON executions(counterparty_order_id);
+CREATE INDEX IF NOT EXISTS idx_executions_instrument_executed_at ON executions(instrument, executed_at_ms);
+
+CREATE TABLE IF NOT EXISTS positions (
+    account_id TEXT NOT NULL REFERENCES accounts(account_id),
+    symbol TEXT NOT NULL,
+    underlying TEXT NOT NULL,
+    -- Positive long, negative short
+    quantity INTEGER NOT NULL,
+    average_price_cents INTEGER NOT NULL,
+    realized_pnl_cents INTEGER NOT NULL,
+    multiplier INTEGER NOT NULL,
+    created_at_ms INTEGER NOT NULL,
+    updated_at_ms INTEGER NOT NULL,
+    PRIMARY KEY (account_id, symbol)
+);
+
+CREATE TABLE IF NOT EXISTS last_trades (
+    symbol TEXT PRIMARY KEY,
+    trade_id TEXT NOT NULL,
+    price_cents INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    -- Taker side
+    side TEXT NOT NULL,
+    traded_at_ms INTEGER NOT NULL
+);
+
+-- The double-entry cash ledger: one row per journal entry, one per posting
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    entry_id INTEGER PRIMARY KEY,
+    -- deposit, withdrawal, trade or settlement
+    kind TEXT NOT NULL,
+    -- Trade id, symbol or note the entry refers to
+    reference TEXT NOT NULL,
+    recorded_at_ms INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ledger_postings (
+    entry_id INTEGER NOT NULL REFERENCES ledger_entries(entry_id) ON DELETE CASCADE,
+    -- Position of the posting within its entry
+    leg INTEGER NOT NULL,
+    -- cash:<account>, external, fees or clearing
+    ledger_account TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL,
+    PRIMARY KEY (entry_id, leg)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_postings_ledger_account ON ledger_postings(ledger_account);
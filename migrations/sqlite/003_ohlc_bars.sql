@@ -0,0 +1,18 @@
+-- Durable OHLC history: the ohlc_bars table of ../005_ohlc_bars.sql. Bars
+-- are backfilled from the executions table of 002_trading_schema.sql.
+
+CREATE TABLE IF NOT EXISTS ohlc_bars (
+    -- UNDERLYING-EXPIRATION-STRIKE-STYLE option key
+    symbol TEXT NOT NULL,
+    -- 1m, 5m, 15m, 1h, 4h or 1d
+    bar_interval TEXT NOT NULL,
+    -- Bar start in seconds since epoch
+    bar_start INTEGER NOT NULL,
+    open_cents INTEGER NOT NULL,
+    high_cents INTEGER NOT NULL,
+    low_cents INTEGER NOT NULL,
+    close_cents INTEGER NOT NULL,
+    volume INTEGER NOT NULL,
+    trade_count INTEGER NOT NULL,
+    PRIMARY KEY (symbol, bar_interval, bar_start)
+);
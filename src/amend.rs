@@ -0,0 +1,322 @@
+//! Amend-in-place for resting limit orders and queue position lookups.
+//!
+//! Shared by the REST `modify_order` handler and the market maker's requote
+//! loop. An amend keeps the order id and, where the book allows, its place in
+//! the queue:
+//!
+//! * a quantity decrease at the same price keeps time priority;
+//! * a quantity increase at the same price moves the order to the back of its
+//!   level;
+//! * a price change reprices: the new price and quantity are validated before
+//!   the resting order is touched, then the order is cancelled and re-entered
+//!   at the new price. A new price that crosses the book matches like a fresh
+//!   order; its trades come back in [`Amendment::trade_result`] for the caller
+//!   to record.
+
+use option_chain_orderbook::orderbook::{OptionOrderBook, TradeResult};
+use orderbook_rs::{OrderId, Side};
+use pricelevel::{OrderUpdate, Quantity};
+
+/// Errors raised when amending an order.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AmendError {
+    /// The order is not resting in the book.
+    #[error("order not found: {0}")]
+    NotFound(OrderId),
+    /// The instrument is not accepting order changes (halted, expired, ...).
+    #[error("instrument {0} is not accepting orders")]
+    InstrumentNotActive(String),
+    /// Zero price or quantity.
+    #[error("{0} must be greater than zero")]
+    InvalidValue(&'static str),
+    /// The book rejected the amend; the original order is left in place.
+    #[error("order book rejected the amend: {0}")]
+    Rejected(String),
+}
+
+/// Outcome of a successful amend.
+#[derive(Debug, Clone)]
+pub struct Amendment {
+    /// Side of the order.
+    pub side: Side,
+    /// Price before the amend.
+    pub old_price: u128,
+    /// Resting quantity before the amend.
+    pub old_quantity: u64,
+    /// Price after the amend.
+    pub new_price: u128,
+    /// Resting quantity after the amend.
+    pub new_quantity: u64,
+    /// Whether the order kept its place in the queue.
+    pub priority_kept: bool,
+    /// Match result of a reprice, `None` when the price did not change. Its
+    /// trades, if any, were executed with the amended order as the taker; the
+    /// order rests only with the unfilled remainder.
+    pub trade_result: Option<TradeResult>,
+}
+
+impl Amendment {
+    /// Quantity executed by a crossing reprice.
+    #[must_use]
+    pub fn filled_quantity(&self) -> u64 {
+        self.trade_result.as_ref().map_or(0, |result| {
+            result
+                .match_result
+                .executed_quantity()
+                .map_or(0, |q| q.as_u64())
+        })
+    }
+
+    /// Quantity resting after the amend.
+    #[must_use]
+    pub fn resting_quantity(&self) -> u64 {
+        self.new_quantity.saturating_sub(self.filled_quantity())
+    }
+}
+
+/// Amends `order_id` in `book` to `new_price` and `new_quantity`, keeping the
+/// order id. An amend that changes nothing touches nothing.
+///
+/// A price change cancels the order and adds it back at the new price with
+/// the same side and time in force, so a price that crosses the book trades
+/// immediately (see [`Amendment::trade_result`]).
+///
+/// # Errors
+/// Returns [`AmendError::NotFound`] when the order is not resting,
+/// [`AmendError::InvalidValue`] for a zero price or quantity,
+/// [`AmendError::InstrumentNotActive`] when the instrument does not accept
+/// orders and [`AmendError::Rejected`] when the book refuses the change. In
+/// every error case the original order is left as it was, except that a
+/// reprice the book refuses after the cancel puts it back at its old price
+/// and quantity, at the back of its level.
+pub fn amend_order(
+    book: &OptionOrderBook,
+    order_id: OrderId,
+    new_price: u128,
+    new_quantity: u64,
+) -> Result<Amendment, AmendError> {
+    if new_price == 0 {
+        return Err(AmendError::InvalidValue("price"));
+    }
+    if new_quantity == 0 {
+        return Err(AmendError::InvalidValue("quantity"));
+    }
+    let order = book
+        .inner()
+        .get_order(order_id)
+        .ok_or(AmendError::NotFound(order_id))?;
+    let mut amendment = Amendment {
+        side: order.side(),
+        old_price: order.price().as_u128(),
+        old_quantity: order.visible_quantity().as_u64() + order.hidden_quantity().as_u64(),
+        new_price,
+        new_quantity,
+        priority_kept: true,
+        trade_result: None,
+    };
+    if new_price == amendment.old_price && new_quantity == amendment.old_quantity {
+        return Ok(amendment);
+    }
+    if !book.status().is_accepting_orders() {
+        return Err(AmendError::InstrumentNotActive(book.symbol().to_string()));
+    }
+
+    if new_price == amendment.old_price {
+        amendment.priority_kept = new_quantity < amendment.old_quantity;
+        let update = OrderUpdate::UpdateQuantity {
+            order_id,
+            new_quantity: Quantity::new(new_quantity),
+        };
+        return match book.inner().update_order(update) {
+            Ok(Some(_)) => Ok(amendment),
+            Ok(None) => Err(AmendError::NotFound(order_id)),
+            Err(e) => Err(AmendError::Rejected(e.to_string())),
+        };
+    }
+
+    amendment.priority_kept = false;
+    let tif = order.time_in_force();
+    match book.cancel_order(order_id) {
+        Ok(true) => {}
+        Ok(false) => return Err(AmendError::NotFound(order_id)),
+        Err(e) => return Err(AmendError::Rejected(e.to_string())),
+    }
+    match book.add_limit_order_with_tif_full(order_id, amendment.side, new_price, new_quantity, tif)
+    {
+        Ok(result) => {
+            amendment.trade_result = Some(result);
+            Ok(amendment)
+        }
+        Err(e) => {
+            // The original rested at its old price, so putting it back cannot
+            // cross.
+            let _ = book.add_limit_order_with_tif_full(
+                order_id,
+                amendment.side,
+                amendment.old_price,
+                amendment.old_quantity,
+                tif,
+            );
+            Err(AmendError::Rejected(e.to_string()))
+        }
+    }
+}
+
+/// Place of a resting order in its price level's queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuePosition {
+    /// Side of the order.
+    pub side: Side,
+    /// Price level of the order.
+    pub price: u128,
+    /// Resting quantity of the order.
+    pub quantity: u64,
+    /// Orders ahead of it at the same price.
+    pub orders_ahead: usize,
+    /// Quantity ahead of it at the same price.
+    pub quantity_ahead: u64,
+    /// Total quantity resting at the price.
+    pub level_quantity: u64,
+}
+
+/// Queue position of `order_id` in `book`, or `None` when it is not resting.
+#[must_use]
+pub fn queue_position(book: &OptionOrderBook, order_id: OrderId) -> Option<QueuePosition> {
+    let inner = book.inner();
+    let order = inner.get_order(order_id)?;
+    let side = order.side();
+    let price = order.price().as_u128();
+    let levels = match side {
+        Side::Buy => inner.get_bids(),
+        Side::Sell => inner.get_asks(),
+    };
+    let level = levels.get(&price)?.value().clone();
+
+    let mut position = QueuePosition {
+        side,
+        price,
+        quantity: 0,
+        orders_ahead: 0,
+        quantity_ahead: 0,
+        level_quantity: 0,
+    };
+    let mut found = false;
+    // Consumption order: the front of the queue fills first.
+    for resting in level.snapshot_by_insertion_seq() {
+        let quantity = resting.visible_quantity().as_u64() + resting.hidden_quantity().as_u64();
+        position.level_quantity += quantity;
+        if resting.id() == order_id {
+            position.quantity = quantity;
+            found = true;
+        } else if !found {
+            position.orders_ahead += 1;
+            position.quantity_ahead += quantity;
+        }
+    }
+    found.then_some(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+
+    fn book_with(orders: &[(OrderId, u128, u64)]) -> OptionOrderBook {
+        let book = OptionOrderBook::new("BTC-20351231-5000000-C", OptionStyle::Call);
+        for &(id, price, quantity) in orders {
+            book.add_limit_order(id, Side::Buy, price, quantity)
+                .expect("order rests");
+        }
+        book
+    }
+
+    #[test]
+    fn test_size_reduction_keeps_priority() {
+        let (first, second) = (OrderId::new(), OrderId::new());
+        let book = book_with(&[(first, 100, 10), (second, 100, 5)]);
+
+        let amendment = amend_order(&book, first, 100, 4).expect("amend ok");
+        assert!(amendment.priority_kept);
+        assert_eq!(amendment.old_quantity, 10);
+
+        let position = queue_position(&book, first).expect("resting");
+        assert_eq!(position.orders_ahead, 0, "still at the front");
+        assert_eq!(position.quantity, 4);
+        assert_eq!(position.level_quantity, 9);
+        let behind = queue_position(&book, second).expect("resting");
+        assert_eq!(behind.orders_ahead, 1);
+        assert_eq!(behind.quantity_ahead, 4);
+    }
+
+    #[test]
+    fn test_size_increase_and_reprice_lose_priority() {
+        let (first, second) = (OrderId::new(), OrderId::new());
+        let book = book_with(&[(first, 100, 10), (second, 100, 5)]);
+
+        let amendment = amend_order(&book, first, 100, 12).expect("amend ok");
+        assert!(!amendment.priority_kept);
+        assert_eq!(
+            queue_position(&book, first).map(|p| p.orders_ahead),
+            Some(1)
+        );
+
+        let amendment = amend_order(&book, second, 101, 5).expect("amend ok");
+        assert!(!amendment.priority_kept);
+        let position = queue_position(&book, second).expect("same id rests");
+        assert_eq!(position.price, 101);
+        assert_eq!(position.orders_ahead, 0);
+        assert_eq!(book.order_count(), 2, "no order added or lost");
+    }
+
+    #[test]
+    fn test_noop_and_rejected_amends_leave_the_order() {
+        let id = OrderId::new();
+        let book = book_with(&[(id, 100, 10)]);
+
+        assert!(amend_order(&book, id, 100, 10).expect("noop").priority_kept);
+        assert_eq!(
+            amend_order(&book, id, 100, 0).expect_err("zero quantity"),
+            AmendError::InvalidValue("quantity")
+        );
+        let missing = OrderId::new();
+        assert_eq!(
+            amend_order(&book, missing, 100, 1).expect_err("not resting"),
+            AmendError::NotFound(missing)
+        );
+
+        book.halt().expect("halt from active");
+        assert!(matches!(
+            amend_order(&book, id, 105, 10),
+            Err(AmendError::InstrumentNotActive(_))
+        ));
+        assert_eq!(queue_position(&book, id).map(|p| p.price), Some(100));
+        assert_eq!(queue_position(&book, OrderId::new()), None);
+    }
+
+    #[test]
+    fn test_crossing_reprice_trades_and_rests_the_remainder() {
+        let (bid, ask) = (OrderId::new(), OrderId::new());
+        let book = book_with(&[(bid, 100, 10)]);
+        book.add_limit_order(ask, Side::Sell, 105, 4)
+            .expect("ask rests");
+
+        let amendment = amend_order(&book, bid, 105, 10).expect("amend ok");
+        assert!(!amendment.priority_kept);
+        assert_eq!(amendment.filled_quantity(), 4);
+        assert_eq!(amendment.resting_quantity(), 6);
+        let trades = amendment
+            .trade_result
+            .as_ref()
+            .expect("reprice result")
+            .match_result
+            .trades()
+            .as_vec();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].maker_order_id(), ask);
+        assert_eq!(trades[0].price().as_u128(), 105);
+
+        let position = queue_position(&book, bid).expect("remainder rests");
+        assert_eq!((position.price, position.quantity), (105, 6));
+        assert_eq!(queue_position(&book, ask), None, "ask consumed");
+    }
+}
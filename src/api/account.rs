@@ -0,0 +1,573 @@
+//! Account cash: balances, the ledger, funding and expiry settlement.
+
+use crate::api::controls::dollars_to_cents;
+use crate::api::margin::{account_collateral, initial_margin_with_open_orders};
+use crate::api::risk::{risk_position_from_info, spot_price};
+use crate::auth::{Claims, validate_account_id};
+use crate::clock;
+use crate::config::LedgerConfig;
+use crate::contract::ContractSpec;
+use crate::error::{ApiError, ErrorResponse};
+use crate::ledger::{EntryKind, JournalEntry, LedgerAccount, Posting};
+use crate::models::{OrderSide, OrderStatus};
+use crate::state::AppState;
+use axum::extract::{Path, Query, State};
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use optionstratlib::{ExpirationDate, OptionStyle};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[cfg(test)]
+mod tests;
+
+/// Default number of ledger entries returned.
+const DEFAULT_LEDGER_LIMIT: usize = 100;
+
+/// Maximum number of ledger entries returned.
+const MAX_LEDGER_LIMIT: usize = 1_000;
+
+// ============================================================================
+// Request/Response DTOs
+// ============================================================================
+
+/// Request body for a deposit or withdrawal.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct FundsRequest {
+    /// Amount in dollars.
+    pub amount: f64,
+}
+
+/// Query parameters for the ledger endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LedgerQuery {
+    /// Maximum number of entries, newest first (default 100, max 1000).
+    pub limit: Option<usize>,
+}
+
+/// Cash balance of an account.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountBalanceResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Cash balance in cents.
+    pub cash: i64,
+    /// Cash reserved by open buy orders (premium plus taker fee), in cents.
+    pub reserved: i64,
+    /// Cash free for new buy orders and withdrawals, in cents.
+    pub available: i64,
+    /// Whether order entry is checked against available cash.
+    pub buying_power_enforced: bool,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// One leg of a ledger entry.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LedgerPostingResponse {
+    /// Ledger account (`cash:<account>`, `external`, `fees` or `clearing`).
+    pub account: String,
+    /// Signed amount in cents.
+    pub amount: i64,
+}
+
+/// A ledger entry touching an account.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LedgerEntryResponse {
+    /// Entry sequence number.
+    pub entry_id: u64,
+    /// What the entry records.
+    pub kind: EntryKind,
+    /// Trade id, symbol or note the entry refers to.
+    pub reference: String,
+    /// Net change to the account's cash, in cents.
+    pub amount: i64,
+    /// Every leg of the entry; they sum to zero.
+    pub postings: Vec<LedgerPostingResponse>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Ledger entries of an account.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LedgerEntriesResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Cash balance in cents.
+    pub cash: i64,
+    /// Entries, newest first.
+    pub entries: Vec<LedgerEntryResponse>,
+}
+
+impl LedgerEntryResponse {
+    fn new(entry: JournalEntry, account: &LedgerAccount) -> Self {
+        Self {
+            entry_id: entry.entry_id,
+            kind: entry.kind,
+            amount: entry.amount_for(account),
+            reference: entry.reference,
+            postings: entry
+                .postings
+                .into_iter()
+                .map(|p| LedgerPostingResponse {
+                    account: p.account.to_string(),
+                    amount: p.amount_cents,
+                })
+                .collect(),
+            timestamp_ms: entry.timestamp_ms,
+        }
+    }
+}
+
+// ============================================================================
+// Cash Accounting
+// ============================================================================
+
+/// The `[ledger]` settings, or the defaults when running without config.
+fn ledger_config(state: &AppState) -> LedgerConfig {
+    state
+        .config
+        .as_ref()
+        .map(|c| c.ledger.clone())
+        .unwrap_or_default()
+}
+
+/// Fee on `premium_cents` at `bps` basis points, rounded to the cent.
+fn fee_cents(premium_cents: u128, bps: f64) -> u128 {
+    (premium_cents as f64 * bps / 10_000.0).round() as u128
+}
+
+/// Premium of `quantity` contracts of `spec` at `price` cents plus the taker
+/// fee.
+fn cost_with_fee(spec: &ContractSpec, price: u128, quantity: u64, config: &LedgerConfig) -> u128 {
+    let premium = spec.scale(price, quantity);
+    premium.saturating_add(fee_cents(premium, config.taker_fee_bps))
+}
+
+/// Cash reserved by `account`'s open buy orders, skipping `excluding`.
+fn reserved_cents(state: &AppState, account: &str, excluding: Option<&str>) -> u128 {
+    let config = ledger_config(state);
+    state
+        .orders
+        .account_orders(account)
+        .into_iter()
+        .filter(|order| {
+            order.side == OrderSide::Buy
+                && excluding != Some(order.order_id.as_str())
+                && matches!(order.status, OrderStatus::Active | OrderStatus::Partial)
+        })
+        .map(|order| {
+            let spec = state.market_maker.contract_spec(&order.underlying);
+            cost_with_fee(&spec, order.price, order.remaining_quantity, &config)
+        })
+        .fold(0, u128::saturating_add)
+}
+
+/// Saturating conversion of unsigned cents for the signed DTO fields.
+fn signed(cents: u128) -> i64 {
+    i64::try_from(cents).unwrap_or(i64::MAX)
+}
+
+/// Cash of `account` not reserved by open buy orders (other than
+/// `excluding`), in cents.
+fn available_cents(state: &AppState, account: &str, excluding: Option<&str>) -> i64 {
+    state
+        .ledger
+        .cash_balance(account)
+        .saturating_sub(signed(reserved_cents(state, account, excluding)))
+}
+
+/// Whether `[ledger] enforce_buying_power` is set.
+pub(crate) fn buying_power_enforced(state: &AppState) -> bool {
+    ledger_config(state).enforce_buying_power
+}
+
+/// Pre-trade buying-power check for a buy costing `premium_cents` in total,
+/// contract multiplier included.
+///
+/// The premium plus the taker fee must fit the account's cash less what its
+/// other open buy orders reserve; `replacing` names a resting order the new
+/// one replaces, whose reservation is released. Sells never need cash. A
+/// no-op unless `[ledger] enforce_buying_power` is set.
+///
+/// # Errors
+/// Returns [`ApiError::InsufficientFunds`] when the buy is not covered.
+pub(crate) fn check_buying_power(
+    state: &AppState,
+    account: &str,
+    side: OrderSide,
+    premium_cents: u128,
+    replacing: Option<&str>,
+) -> Result<(), ApiError> {
+    let config = ledger_config(state);
+    if !config.enforce_buying_power || side != OrderSide::Buy {
+        return Ok(());
+    }
+    let required = premium_cents.saturating_add(fee_cents(premium_cents, config.taker_fee_bps));
+    let available = available_cents(state, account, replacing);
+    if i128::try_from(required).unwrap_or(i128::MAX) <= i128::from(available) {
+        return Ok(());
+    }
+    tracing::debug!(
+        account,
+        required = %required,
+        available,
+        "order rejected by buying-power check"
+    );
+    Err(ApiError::InsufficientFunds(format!(
+        "order requires {:.2} but available cash is {:.2}",
+        required as f64 / 100.0,
+        available as f64 / 100.0
+    )))
+}
+
+/// Premium of a market buy of `quantity` sweeping `asks` (price, quantity)
+/// from the best price up. Quantity beyond the book's depth cannot fill and
+/// costs nothing.
+pub(crate) fn sweep_cost(mut asks: Vec<(u128, u64)>, quantity: u64) -> u128 {
+    asks.sort_by_key(|(price, _)| *price);
+    let mut remaining = quantity;
+    let mut cost: u128 = 0;
+    for (price, available) in asks {
+        if remaining == 0 {
+            break;
+        }
+        let take = remaining.min(available);
+        cost = cost.saturating_add(price.saturating_mul(u128::from(take)));
+        remaining -= take;
+    }
+    cost
+}
+
+/// Posts the premium and fees of one fill to the ledger and returns the
+/// taker's fee in cents.
+///
+/// The buyer pays `price × quantity × multiplier` to the seller, with the
+/// multiplier of `underlying`'s contract specification. A maker without an account
+/// (a market maker quote) is represented by [`LedgerAccount::Clearing`] and
+/// pays no fee. A fill whose premium does not fit the ledger is skipped with a
+/// warning.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn post_fill(
+    state: &AppState,
+    trade_id: &str,
+    taker_account: &str,
+    taker_side: OrderSide,
+    maker_account: Option<&str>,
+    underlying: &str,
+    price: u128,
+    quantity: u64,
+    timestamp_ms: u64,
+) -> u64 {
+    let config = ledger_config(state);
+    let multiplier = state.market_maker.contract_spec(underlying).multiplier;
+    let Some(premium) = price
+        .checked_mul(u128::from(quantity))
+        .and_then(|p| p.checked_mul(u128::from(multiplier)))
+        .and_then(|p| i64::try_from(p).ok())
+    else {
+        tracing::warn!(trade_id, "fill premium exceeds ledger range; not posted");
+        return 0;
+    };
+    let taker_fee = fee_cents(premium as u128, config.taker_fee_bps) as i64;
+    let maker_fee = match maker_account {
+        Some(_) => fee_cents(premium as u128, config.maker_fee_bps) as i64,
+        None => 0,
+    };
+
+    let taker = LedgerAccount::Cash(taker_account.to_string());
+    let maker = maker_account.map_or(LedgerAccount::Clearing, |a| {
+        LedgerAccount::Cash(a.to_string())
+    });
+    let (buyer, seller) = match taker_side {
+        OrderSide::Buy => (taker.clone(), maker.clone()),
+        OrderSide::Sell => (maker.clone(), taker.clone()),
+    };
+    let postings = vec![
+        Posting::new(buyer, -premium),
+        Posting::new(seller, premium),
+        Posting::new(taker, -taker_fee),
+        Posting::new(maker, -maker_fee),
+        Posting::new(LedgerAccount::Fees, taker_fee + maker_fee),
+    ];
+    if let Err(e) = state
+        .ledger
+        .post(EntryKind::Trade, trade_id, postings, timestamp_ms)
+    {
+        tracing::warn!(trade_id, error = %e, "fill not posted to the ledger");
+        return 0;
+    }
+    taker_fee as u64
+}
+
+/// Intrinsic value per contract in cents of a `style` option struck at
+/// `strike` with the underlying at `spot`.
+fn intrinsic_cents(style: OptionStyle, strike: u64, spot: u64) -> u64 {
+    match style {
+        OptionStyle::Call => spot.saturating_sub(strike),
+        OptionStyle::Put => strike.saturating_sub(spot),
+    }
+}
+
+/// Cash-settles every account position whose expiration is at or before
+/// `now` at intrinsic value against the current spot, closing the position.
+///
+/// Holders receive (longs) or pay (shorts) `quantity × multiplier ×
+/// intrinsic` against
+/// [`LedgerAccount::Clearing`]; closing at intrinsic realizes the position's
+/// P&L. Positions on an underlying without a price are left for a later
+/// sweep. Returns the number of positions settled.
+pub fn settle_expired_positions(state: &AppState, now: DateTime<Utc>) -> usize {
+    let timestamp_ms = now.timestamp_millis() as u64;
+    let mut settled = 0;
+    for book in state.account_positions.iter() {
+        let account = book.key();
+        for mut position in book.value().iter_mut() {
+            if position.quantity == 0 {
+                continue;
+            }
+            let Some(option) = risk_position_from_info(&position) else {
+                continue;
+            };
+            let ExpirationDate::DateTime(expiry) = option.expiration else {
+                continue;
+            };
+            if expiry > now {
+                continue;
+            }
+            let Some(spot) = spot_price(state, &option.underlying) else {
+                tracing::warn!(
+                    symbol = %position.symbol,
+                    "expired position not settled: no price for the underlying"
+                );
+                continue;
+            };
+
+            let intrinsic = intrinsic_cents(option.style, option.strike, spot);
+            let Some(cash) = i64::try_from(intrinsic)
+                .ok()
+                .and_then(|i| i.checked_mul(position.quantity))
+                .and_then(|c| c.checked_mul(i64::try_from(position.multiplier).ok()?))
+            else {
+                tracing::warn!(symbol = %position.symbol, "settlement amount overflows; skipped");
+                continue;
+            };
+            if cash != 0
+                && let Err(e) = state.ledger.post(
+                    EntryKind::Settlement,
+                    position.symbol.clone(),
+                    vec![
+                        Posting::new(LedgerAccount::Cash(account.clone()), cash),
+                        Posting::new(LedgerAccount::Clearing, -cash),
+                    ],
+                    timestamp_ms,
+                )
+            {
+                tracing::warn!(symbol = %position.symbol, error = %e, "settlement not posted");
+                continue;
+            }
+            let quantity = position.quantity;
+            position.update(-quantity, u128::from(intrinsic), timestamp_ms);
+            settled += 1;
+            tracing::info!(
+                account = %account,
+                symbol = %position.symbol,
+                quantity,
+                intrinsic,
+                cash,
+                "expired position settled"
+            );
+        }
+    }
+    settled
+}
+
+/// Builds the balance of `account`.
+fn account_balance(state: &AppState, account: &str) -> AccountBalanceResponse {
+    let cash = state.ledger.cash_balance(account);
+    let reserved = signed(reserved_cents(state, account, None));
+    AccountBalanceResponse {
+        account: account.to_string(),
+        cash,
+        reserved,
+        available: cash.saturating_sub(reserved),
+        buying_power_enforced: buying_power_enforced(state),
+        timestamp_ms: clock::now_ms(),
+    }
+}
+
+/// Validates a funding request, returning the amount in cents.
+fn funding_amount(account: &str, body: &FundsRequest) -> Result<i64, ApiError> {
+    validate_account_id(account)?;
+    let cents = dollars_to_cents("amount", body.amount)?;
+    if cents == 0 {
+        return Err(ApiError::InvalidRequest(
+            "amount must be greater than zero".to_string(),
+        ));
+    }
+    i64::try_from(cents)
+        .map_err(|_| ApiError::InvalidRequest(format!("amount is too large: {}", body.amount)))
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Cash balance of the caller's account.
+///
+/// `reserved` is the premium plus taker fee of the account's open buy orders;
+/// `available` is what remains for new buys and withdrawals. Amounts are in
+/// cents.
+#[utoipa::path(
+    get,
+    path = "/api/v1/account/balance",
+    responses(
+        (status = 200, description = "Account balance", body = AccountBalanceResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse)
+    ),
+    tag = "Account"
+)]
+pub async fn get_account_balance(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Json<AccountBalanceResponse> {
+    Json(account_balance(&state, claims.account()))
+}
+
+/// Ledger entries of the caller's account, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/account/ledger",
+    params(
+        ("limit" = Option<usize>, Query, description = "Maximum number of entries (default 100, max 1000)")
+    ),
+    responses(
+        (status = 200, description = "Ledger entries", body = LedgerEntriesResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse)
+    ),
+    tag = "Account"
+)]
+pub async fn get_account_ledger(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<LedgerQuery>,
+) -> Json<LedgerEntriesResponse> {
+    let account = claims.account();
+    let ledger_account = LedgerAccount::Cash(account.to_string());
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LEDGER_LIMIT)
+        .min(MAX_LEDGER_LIMIT);
+    let entries = state
+        .ledger
+        .entries_for(&ledger_account, limit)
+        .into_iter()
+        .map(|entry| LedgerEntryResponse::new(entry, &ledger_account))
+        .collect();
+    Json(LedgerEntriesResponse {
+        account: account.to_string(),
+        cash: state.ledger.cash_balance(account),
+        entries,
+    })
+}
+
+/// Deposit cash into an account.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/accounts/{account}/deposit",
+    params(
+        ("account" = String, Path, description = "Account identifier")
+    ),
+    request_body = FundsRequest,
+    responses(
+        (status = 200, description = "Deposit posted", body = AccountBalanceResponse),
+        (status = 400, description = "Invalid account or amount", body = ErrorResponse),
+        (status = 403, description = "Admin permission required", body = ErrorResponse)
+    ),
+    tag = "Account"
+)]
+pub async fn deposit_funds(
+    State(state): State<Arc<AppState>>,
+    Path(account): Path<String>,
+    Json(body): Json<FundsRequest>,
+) -> Result<Json<AccountBalanceResponse>, ApiError> {
+    let amount = funding_amount(&account, &body)?;
+    state
+        .ledger
+        .post(
+            EntryKind::Deposit,
+            "deposit",
+            vec![
+                Posting::new(LedgerAccount::Cash(account.clone()), amount),
+                Posting::new(LedgerAccount::External, -amount),
+            ],
+            clock::now_ms(),
+        )
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    tracing::info!(account = %account, amount, "deposit posted");
+    Ok(Json(account_balance(&state, &account)))
+}
+
+/// Withdraw cash from an account.
+///
+/// Only available cash (not reserved by open buy orders) can be withdrawn,
+/// and only while the collateral left still covers the initial margin of the
+/// account's positions and open orders, since cash counts as collateral.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/accounts/{account}/withdraw",
+    params(
+        ("account" = String, Path, description = "Account identifier")
+    ),
+    request_body = FundsRequest,
+    responses(
+        (status = 200, description = "Withdrawal posted", body = AccountBalanceResponse),
+        (status = 400, description = "Invalid account or amount", body = ErrorResponse),
+        (status = 403, description = "Admin permission required", body = ErrorResponse),
+        (status = 422, description = "Amount exceeds available cash or would leave initial margin uncovered", body = ErrorResponse)
+    ),
+    tag = "Account"
+)]
+pub async fn withdraw_funds(
+    State(state): State<Arc<AppState>>,
+    Path(account): Path<String>,
+    Json(body): Json<FundsRequest>,
+) -> Result<Json<AccountBalanceResponse>, ApiError> {
+    let amount = funding_amount(&account, &body)?;
+    // Orders and fills for the account wait until the withdrawal is posted,
+    // so the margin checked below is the margin it leaves.
+    let _account_guard = state.account_guard(&account).await;
+    let available = available_cents(&state, &account, None);
+    if amount > available {
+        return Err(ApiError::InsufficientFunds(format!(
+            "withdrawal of {:.2} exceeds available cash of {:.2}",
+            amount as f64 / 100.0,
+            available as f64 / 100.0
+        )));
+    }
+    let initial_margin = initial_margin_with_open_orders(&state, &account);
+    let remaining = account_collateral(&state, &account).saturating_sub(amount.unsigned_abs());
+    if (remaining as f64) < initial_margin {
+        return Err(ApiError::InsufficientFunds(format!(
+            "withdrawal of {:.2} would leave collateral of {:.2} below initial margin of {:.2}",
+            amount as f64 / 100.0,
+            remaining as f64 / 100.0,
+            initial_margin / 100.0
+        )));
+    }
+    state
+        .ledger
+        .post(
+            EntryKind::Withdrawal,
+            "withdrawal",
+            vec![
+                Posting::new(LedgerAccount::Cash(account.clone()), -amount),
+                Posting::new(LedgerAccount::External, amount),
+            ],
+            clock::now_ms(),
+        )
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    tracing::info!(account = %account, amount, "withdrawal posted");
+    Ok(Json(account_balance(&state, &account)))
+}
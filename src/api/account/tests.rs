@@ -0,0 +1,306 @@
+//! Unit tests for the account module.
+
+use super::*;
+use crate::api::handlers::update_account_position_on_fill;
+use crate::api::margin::account_collateral;
+use crate::api::test_support::{btc_state, claims_for, limit_order};
+use crate::config::Config;
+use chrono::TimeZone;
+
+fn ledger_state(enforce: bool, taker_fee_bps: f64, maker_fee_bps: f64) -> Arc<AppState> {
+    let mut config = Config::default();
+    config.ledger.enforce_buying_power = enforce;
+    config.ledger.taker_fee_bps = taker_fee_bps;
+    config.ledger.maker_fee_bps = maker_fee_bps;
+    btc_state(config)
+}
+
+async fn deposit(state: &Arc<AppState>, account: &str, amount: f64) -> AccountBalanceResponse {
+    let Json(balance) = deposit_funds(
+        State(state.clone()),
+        Path(account.to_string()),
+        Json(FundsRequest { amount }),
+    )
+    .await
+    .expect("deposit posted");
+    balance
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw_move_cash_against_external() {
+    let state = ledger_state(false, 0.0, 0.0);
+    deposit(&state, "alice", 1_000.0).await;
+
+    let Json(balance) = withdraw_funds(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(FundsRequest { amount: 250.5 }),
+    )
+    .await
+    .expect("withdrawal posted");
+    assert_eq!(balance.cash, 74_950);
+    assert_eq!(balance.available, 74_950);
+    assert_eq!(state.ledger.balance(&LedgerAccount::External), -74_950);
+    assert_eq!(state.ledger.trial_balance(), 0);
+
+    let err = withdraw_funds(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(FundsRequest { amount: 750.0 }),
+    )
+    .await
+    .expect_err("more than the balance");
+    assert!(matches!(err, ApiError::InsufficientFunds(_)));
+
+    for (account, amount) in [("alice", 0.0), ("alice", -5.0), ("bad account", 1.0)] {
+        let result = deposit_funds(
+            State(state.clone()),
+            Path(account.to_string()),
+            Json(FundsRequest { amount }),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(ApiError::InvalidRequest(_))),
+            "{account} / {amount} must be rejected"
+        );
+    }
+    assert_eq!(state.ledger.len(), 2);
+}
+
+#[tokio::test]
+async fn test_fill_moves_premium_and_fees_between_accounts() {
+    let state = ledger_state(false, 10.0, 2.0);
+    limit_order(&state, "bob", OrderSide::Sell, 50_000, 2)
+        .await
+        .expect("ask rests");
+    limit_order(&state, "alice", OrderSide::Buy, 50_000, 2)
+        .await
+        .expect("bid crosses");
+
+    // 2 contracts at $500: alice pays $1,000 plus 10 bps, bob receives it
+    // less 2 bps.
+    assert_eq!(state.ledger.cash_balance("alice"), -100_100);
+    assert_eq!(state.ledger.cash_balance("bob"), 99_980);
+    assert_eq!(state.ledger.balance(&LedgerAccount::Fees), 120);
+    assert_eq!(state.ledger.trial_balance(), 0);
+
+    let execution = state.executions.iter().next().expect("execution recorded");
+    assert_eq!(execution.fee, 100);
+
+    let Json(ledger) = get_account_ledger(
+        State(state.clone()),
+        Extension(claims_for("alice")),
+        Query(LedgerQuery::default()),
+    )
+    .await;
+    assert_eq!(ledger.cash, -100_100);
+    assert_eq!(ledger.entries.len(), 1);
+    assert_eq!(ledger.entries[0].kind, EntryKind::Trade);
+    assert_eq!(ledger.entries[0].amount, -100_100);
+    assert_eq!(
+        ledger.entries[0]
+            .postings
+            .iter()
+            .map(|p| p.amount)
+            .sum::<i64>(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_buying_power_rejects_uncovered_buys_and_reserves_open_orders() {
+    let state = ledger_state(true, 0.0, 0.0);
+    let err = limit_order(&state, "alice", OrderSide::Buy, 50_000, 2)
+        .await
+        .expect_err("no cash");
+    assert!(matches!(err, ApiError::InsufficientFunds(_)));
+    assert!(state.orders.is_empty());
+
+    // Sells need no cash.
+    limit_order(&state, "alice", OrderSide::Sell, 90_000, 1)
+        .await
+        .expect("sell accepted");
+
+    deposit(&state, "alice", 1_500.0).await;
+    limit_order(&state, "alice", OrderSide::Buy, 50_000, 2)
+        .await
+        .expect("covered");
+    let Json(balance) =
+        get_account_balance(State(state.clone()), Extension(claims_for("alice"))).await;
+    assert_eq!(balance.reserved, 100_000);
+    assert_eq!(balance.available, 50_000);
+    assert!(balance.buying_power_enforced);
+
+    // The first bid's reservation leaves too little for a second one.
+    let err = limit_order(&state, "alice", OrderSide::Buy, 50_000, 2)
+        .await
+        .expect_err("reserved");
+    assert!(matches!(err, ApiError::InsufficientFunds(_)));
+}
+
+#[test]
+fn test_sweep_cost_walks_the_asks_from_the_best_price() {
+    let asks = vec![(300, 5), (100, 2), (200, 1)];
+    assert_eq!(sweep_cost(asks.clone(), 3), 2 * 100 + 200);
+    assert_eq!(sweep_cost(asks.clone(), 4), 2 * 100 + 200 + 300);
+    // Quantity beyond the book's depth cannot fill.
+    assert_eq!(sweep_cost(asks, 100), 2 * 100 + 200 + 5 * 300);
+    assert_eq!(sweep_cost(vec![], 10), 0);
+}
+
+#[test]
+fn test_settle_expired_positions_pays_intrinsic_and_closes() {
+    let state = ledger_state(false, 0.0, 0.0);
+    state.market_maker.update_price("BTC", 5_200_000);
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20240329-5000000-C",
+        "BTC",
+        OrderSide::Buy,
+        2,
+        150_000,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "bob",
+        "BTC-20240329-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        2,
+        150_000,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "bob",
+        "BTC-20351231-5000000-P",
+        "BTC",
+        OrderSide::Sell,
+        1,
+        150_000,
+        0,
+    );
+
+    let before_expiry = Utc
+        .with_ymd_and_hms(2024, 3, 29, 15, 0, 0)
+        .single()
+        .expect("valid time");
+    assert_eq!(settle_expired_positions(&state, before_expiry), 0);
+
+    let after_expiry = Utc
+        .with_ymd_and_hms(2024, 3, 29, 16, 0, 0)
+        .single()
+        .expect("valid time");
+    assert_eq!(settle_expired_positions(&state, after_expiry), 2);
+
+    // $2,000 intrinsic on 2 contracts.
+    assert_eq!(state.ledger.cash_balance("alice"), 400_000);
+    assert_eq!(state.ledger.cash_balance("bob"), -400_000);
+    assert_eq!(state.ledger.trial_balance(), 0);
+
+    let alice = state.account_positions.get("alice").expect("book");
+    let position = alice.get("BTC-20240329-5000000-C").expect("position");
+    assert_eq!(position.quantity, 0);
+    assert_eq!(position.realized_pnl, 100_000);
+    drop(position);
+    drop(alice);
+
+    // A second sweep finds nothing left to settle.
+    assert_eq!(settle_expired_positions(&state, after_expiry), 0);
+}
+
+#[test]
+fn test_settlement_and_premium_scale_with_the_contract_multiplier() {
+    let state = ledger_state(false, 0.0, 0.0);
+    state.market_maker.set_contract_spec(
+        "AAPL",
+        crate::contract::ContractSpec {
+            multiplier: 100,
+            ..Default::default()
+        },
+    );
+    state.market_maker.update_price("AAPL", 16_000);
+    let symbol = "AAPL-20240329-15000-C";
+    update_account_position_on_fill(&state, "alice", symbol, "AAPL", OrderSide::Buy, 2, 500, 0);
+    // Two contracts at $5.00 on 100 shares each cost $1,000.
+    post_fill(
+        &state,
+        "t-1",
+        "alice",
+        OrderSide::Buy,
+        None,
+        "AAPL",
+        500,
+        2,
+        0,
+    );
+    assert_eq!(state.ledger.cash_balance("alice"), -100_000);
+
+    let after_expiry = Utc
+        .with_ymd_and_hms(2024, 3, 30, 0, 0, 0)
+        .single()
+        .expect("valid time");
+    assert_eq!(settle_expired_positions(&state, after_expiry), 1);
+
+    // $10 intrinsic on 200 shares pays $2,000; the round trip nets $1,000.
+    assert_eq!(state.ledger.cash_balance("alice"), 100_000);
+    let alice = state.account_positions.get("alice").expect("book");
+    assert_eq!(alice.get(symbol).expect("position").realized_pnl, 100_000);
+}
+
+#[tokio::test]
+async fn test_cash_counts_as_margin_collateral() {
+    let state = ledger_state(false, 0.0, 0.0);
+    state.collateral.insert("alice".to_string(), 10_000);
+    deposit(&state, "alice", 50.0).await;
+    assert_eq!(account_collateral(&state, "alice"), 15_000);
+
+    let Json(balance) = withdraw_funds(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(FundsRequest { amount: 50.0 }),
+    )
+    .await
+    .expect("withdrawal posted");
+    assert_eq!(balance.cash, 0);
+    assert_eq!(account_collateral(&state, "alice"), 10_000);
+}
+
+#[tokio::test]
+async fn test_withdrawal_must_leave_initial_margin_covered() {
+    let state = ledger_state(false, 0.0, 0.0);
+    deposit(&state, "alice", 100_000.0).await;
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        1,
+        1,
+        0,
+    );
+    let initial_margin = crate::api::margin::initial_margin_with_open_orders(&state, "alice");
+    assert!(initial_margin > 0.0 && initial_margin < 10_000_000.0);
+
+    let err = withdraw_funds(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(FundsRequest { amount: 100_000.0 }),
+    )
+    .await
+    .expect_err("the short call needs the cash as margin");
+    assert!(matches!(err, ApiError::InsufficientFunds(_)));
+    assert_eq!(state.ledger.cash_balance("alice"), 10_000_000);
+
+    let Json(balance) = withdraw_funds(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(FundsRequest { amount: 1_000.0 }),
+    )
+    .await
+    .expect("margin stays covered");
+    assert_eq!(balance.cash, 9_900_000);
+}
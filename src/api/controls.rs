@@ -1,16 +1,22 @@
 //! Control and price API handlers.
 
-use crate::db::{InsertPriceRequest, UpdateParametersRequest};
+use crate::db::{
+    InsertPriceRequest, PriceBar, PriceHistoryRange, PriceRecord, UpdateParametersRequest,
+};
 use crate::error::{ApiError, ErrorResponse};
+use crate::journal::JournalHold;
 use crate::market_maker::{
-    DIRECTIONAL_SKEW_MAX, DIRECTIONAL_SKEW_MIN, SIZE_SCALAR_MAX, SIZE_SCALAR_MIN,
-    SPREAD_MULTIPLIER_MAX, SPREAD_MULTIPLIER_MIN, validate_control_value,
+    DIRECTIONAL_SKEW_MAX, DIRECTIONAL_SKEW_MIN, HedgeStatus, HedgeTrade, MAX_PNL_PERIODS,
+    MakerQueuePosition, PnlComponents, PnlPeriod, RequoteParams, RequoteStats, SIZE_SCALAR_MAX,
+    SIZE_SCALAR_MIN, SPREAD_MULTIPLIER_MAX, SPREAD_MULTIPLIER_MIN, validate_control_value,
 };
+use crate::models::{OhlcInterval, OrderSide};
 use crate::state::AppState;
 use axum::Json;
-use axum::extract::{Path, State};
-use chrono::Utc;
-use serde::Serialize;
+use axum::extract::{Extension, Path, Query, State};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
@@ -99,6 +105,345 @@ pub struct LatestPriceResponse {
     pub timestamp: String,
 }
 
+/// Query parameters for the price history endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PriceHistoryQuery {
+    /// Start timestamp in seconds (inclusive).
+    pub from: Option<u64>,
+    /// End timestamp in seconds (inclusive).
+    pub to: Option<u64>,
+    /// Maximum number of points (default 500, at most 1000).
+    pub limit: Option<usize>,
+    /// Downsampling interval (1m, 5m, 15m, 1h, 4h, 1d); raw prices when
+    /// omitted.
+    pub interval: Option<String>,
+}
+
+/// One point of an underlying's price history: a recorded price, or the
+/// OHLC bar of the prices recorded within one interval.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceHistoryPoint {
+    /// Time of the price, or start of the interval (RFC 3339).
+    pub timestamp: String,
+    /// First price in dollars.
+    pub open: f64,
+    /// Highest price in dollars.
+    pub high: f64,
+    /// Lowest price in dollars.
+    pub low: f64,
+    /// Last price in dollars.
+    pub close: f64,
+    /// Summed volume of the prices that carried one.
+    pub volume: Option<i64>,
+    /// Number of recorded prices in the point.
+    pub samples: u64,
+}
+
+/// Realized volatility of a price history, from the log returns between the
+/// closes of consecutive points.
+#[derive(Debug, Default, PartialEq, Serialize, ToSchema)]
+pub struct RealizedVolatilityStats {
+    /// Number of log returns.
+    pub returns: u64,
+    /// Log return from the first close to the last.
+    pub total_log_return: Option<f64>,
+    /// Sample standard deviation of the log returns, per point.
+    pub return_stddev: Option<f64>,
+    /// Annualized realized volatility: the square root of the summed squared
+    /// log returns scaled from the time spanned to a 365-day year.
+    pub annualized: Option<f64>,
+}
+
+/// Response for the price history endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceHistoryResponse {
+    /// Symbol.
+    pub symbol: String,
+    /// Downsampling interval; absent for raw prices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    /// Points, oldest first.
+    pub points: Vec<PriceHistoryPoint>,
+    /// Realized volatility of `points`.
+    pub realized_volatility: RealizedVolatilityStats,
+}
+
+/// Requote throttling parameters and counters.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequoteStatsResponse {
+    /// Smallest bid or ask move, in price ticks, that replaces a quote.
+    pub min_price_change_ticks: u64,
+    /// Smallest bid or ask size change, in percent, that replaces a quote.
+    pub min_size_change_pct: f64,
+    /// Maximum quote replacements per instrument per second (0 = unlimited).
+    pub max_updates_per_second: u32,
+    /// Quotes replaced since start-up.
+    pub sent: u64,
+    /// Requotes skipped since start-up, for any reason.
+    pub skipped: u64,
+    /// Requotes skipped because the quote was unchanged.
+    pub skipped_unchanged: u64,
+    /// Requotes skipped because the change was below the thresholds.
+    pub skipped_below_threshold: u64,
+    /// Requotes skipped by the per-instrument rate limit.
+    pub skipped_rate_limited: u64,
+}
+
+impl RequoteStatsResponse {
+    /// Builds the response from the engine's parameters and counters.
+    #[must_use]
+    pub fn new(params: RequoteParams, stats: RequoteStats) -> Self {
+        Self {
+            min_price_change_ticks: params.min_price_change_ticks,
+            min_size_change_pct: params.min_size_change_pct,
+            max_updates_per_second: params.max_updates_per_second,
+            sent: stats.sent,
+            skipped: stats.skipped(),
+            skipped_unchanged: stats.skipped_unchanged,
+            skipped_below_threshold: stats.skipped_below_threshold,
+            skipped_rate_limited: stats.skipped_rate_limited,
+        }
+    }
+}
+
+/// Query parameters for the maker queue-position endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct QueuePositionQuery {
+    /// Only report orders on this underlying.
+    pub underlying: Option<String>,
+}
+
+/// Queue position of one resting maker order.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MakerQueuePositionResponse {
+    /// Instrument identifier (`UNDERLYING-YYYYMMDD-STRIKE-STYLE`).
+    pub instrument: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Side of the maker order.
+    pub side: OrderSide,
+    /// Price in cents.
+    pub price: u128,
+    /// Resting quantity of the order.
+    pub quantity: u64,
+    /// Orders ahead of it at the same price.
+    pub orders_ahead: usize,
+    /// Quantity ahead of it at the same price.
+    pub quantity_ahead: u64,
+    /// Total quantity resting at the price.
+    pub level_quantity: u64,
+}
+
+impl From<MakerQueuePosition> for MakerQueuePositionResponse {
+    fn from(p: MakerQueuePosition) -> Self {
+        Self {
+            instrument: p.instrument,
+            underlying: p.underlying,
+            side: if p.is_buy {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            },
+            price: p.position.price,
+            quantity: p.position.quantity,
+            orders_ahead: p.position.orders_ahead,
+            quantity_ahead: p.position.quantity_ahead,
+            level_quantity: p.position.level_quantity,
+        }
+    }
+}
+
+/// Response for the maker queue-position endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueuePositionsResponse {
+    /// Resting maker orders, ordered by instrument with the bid first.
+    pub orders: Vec<MakerQueuePositionResponse>,
+}
+
+/// Number of recent hedge trades returned by the hedging endpoint.
+pub const HEDGE_TRADES_LIMIT: usize = 100;
+
+/// Hedge status of one underlying.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HedgeStatusResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents, if known.
+    pub spot: Option<u64>,
+    /// Net delta of the market maker's option inventory.
+    pub option_delta: f64,
+    /// Signed position in the underlying held as a hedge.
+    pub hedge_position: f64,
+    /// Option delta plus hedge position.
+    pub residual_delta: f64,
+    /// Hedge P&L in cents, marked at spot.
+    pub hedge_pnl: i64,
+    /// Number of hedge trades executed.
+    pub trades: u64,
+}
+
+/// One executed hedge trade.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HedgeTradeResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Signed quantity of the underlying (positive = bought).
+    pub quantity: f64,
+    /// Execution price in cents.
+    pub price: u64,
+    /// Net option delta that was hedged.
+    pub option_delta: f64,
+    /// Hedge position after the trade.
+    pub hedge_position: f64,
+    /// Option delta plus hedge position after the trade.
+    pub residual_delta: f64,
+    /// Execution time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Response for the hedging status endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HedgingResponse {
+    /// Whether delta hedging is enabled.
+    pub enabled: bool,
+    /// Absolute residual delta tolerated before hedging.
+    pub delta_band: f64,
+    /// Smallest hedge quantity of the underlying.
+    pub lot_size: f64,
+    /// Per-underlying hedge status, ordered by symbol.
+    pub underlyings: Vec<HedgeStatusResponse>,
+    /// Most recent hedge trades, newest first.
+    pub trades: Vec<HedgeTradeResponse>,
+}
+
+impl From<HedgeStatus> for HedgeStatusResponse {
+    fn from(s: HedgeStatus) -> Self {
+        Self {
+            underlying: s.underlying,
+            spot: s.spot,
+            option_delta: s.option_delta,
+            hedge_position: s.hedge_position,
+            residual_delta: s.residual_delta,
+            hedge_pnl: s.hedge_pnl,
+            trades: s.trades,
+        }
+    }
+}
+
+impl From<HedgeTrade> for HedgeTradeResponse {
+    fn from(t: HedgeTrade) -> Self {
+        Self {
+            underlying: t.underlying,
+            quantity: t.quantity,
+            price: t.price,
+            option_delta: t.option_delta,
+            hedge_position: t.hedge_position,
+            residual_delta: t.residual_delta,
+            timestamp_ms: t.timestamp_ms,
+        }
+    }
+}
+
+/// Market-maker P&L by source, in cents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, ToSchema)]
+pub struct PnlAttribution {
+    /// Spread captured on fills against the quote-time theo.
+    pub edge: i64,
+    /// First-order P&L from spot moves.
+    pub delta: i64,
+    /// Second-order P&L from spot moves.
+    pub gamma: i64,
+    /// P&L from marking-volatility moves.
+    pub vega: i64,
+    /// Time decay.
+    pub theta: i64,
+    /// P&L of the delta hedge.
+    pub hedging: i64,
+    /// Sum of every source.
+    pub total: i64,
+}
+
+/// P&L attribution of one underlying.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnderlyingPnlAttribution {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// P&L by source.
+    pub pnl: PnlAttribution,
+}
+
+/// P&L attribution over one period.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PnlPeriodResponse {
+    /// Period start in milliseconds.
+    pub start_ms: u64,
+    /// Period end in milliseconds, absent while the period is open.
+    pub end_ms: Option<u64>,
+    /// P&L per underlying, ordered by symbol.
+    pub underlyings: Vec<UnderlyingPnlAttribution>,
+    /// P&L across every underlying.
+    pub total: PnlAttribution,
+}
+
+/// Response for the P&L attribution endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PnlAttributionResponse {
+    /// P&L since the engine started.
+    pub since_start: PnlPeriodResponse,
+    /// The open period.
+    pub current_period: PnlPeriodResponse,
+    /// Closed periods, newest first.
+    pub periods: Vec<PnlPeriodResponse>,
+}
+
+impl From<PnlComponents> for PnlAttribution {
+    fn from(c: PnlComponents) -> Self {
+        Self {
+            edge: c.edge.round() as i64,
+            delta: c.delta.round() as i64,
+            gamma: c.gamma.round() as i64,
+            vega: c.vega.round() as i64,
+            theta: c.theta.round() as i64,
+            hedging: c.hedging.round() as i64,
+            total: c.total().round() as i64,
+        }
+    }
+}
+
+impl PnlPeriodResponse {
+    /// Builds the response for a period spanning `start_ms..end_ms`.
+    #[must_use]
+    pub fn new(
+        start_ms: u64,
+        end_ms: Option<u64>,
+        by_underlying: &BTreeMap<String, PnlComponents>,
+    ) -> Self {
+        let mut total = PnlComponents::default();
+        let underlyings = by_underlying
+            .iter()
+            .map(|(underlying, pnl)| {
+                total += *pnl;
+                UnderlyingPnlAttribution {
+                    underlying: underlying.clone(),
+                    pnl: (*pnl).into(),
+                }
+            })
+            .collect();
+        Self {
+            start_ms,
+            end_ms,
+            underlyings,
+            total: total.into(),
+        }
+    }
+}
+
+impl From<&PnlPeriod> for PnlPeriodResponse {
+    fn from(p: &PnlPeriod) -> Self {
+        Self::new(p.start_ms, p.end_ms, &p.by_underlying)
+    }
+}
+
 /// Instrument status.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct InstrumentStatus {
@@ -108,6 +453,12 @@ pub struct InstrumentStatus {
     pub quoting_enabled: bool,
     /// Current price (if available).
     pub current_price: Option<f64>,
+    /// Name of the quoting strategy (e.g. `single`, `layered`).
+    pub strategy: String,
+    /// Most price levels the strategy quotes per side.
+    pub ladder_levels: usize,
+    /// Maker orders resting on the underlying, over every level.
+    pub resting_orders: usize,
 }
 
 /// Response for listing instruments.
@@ -322,11 +673,15 @@ pub async fn list_instruments(State(state): State<Arc<AppState>>) -> Json<Instru
                 .market_maker
                 .get_price(&symbol)
                 .map(|p| p as f64 / 100.0);
+            let strategy = state.market_maker.strategy(&symbol);
 
             InstrumentStatus {
-                symbol,
                 quoting_enabled: enabled,
                 current_price: price,
+                strategy: strategy.name().to_string(),
+                ladder_levels: strategy.levels(),
+                resting_orders: state.market_maker.resting_order_count(&symbol),
+                symbol,
             }
         })
         .collect();
@@ -334,6 +689,109 @@ pub async fn list_instruments(State(state): State<Arc<AppState>>) -> Json<Instru
     Json(InstrumentsListResponse { instruments })
 }
 
+/// Get the queue position of every resting market-maker order.
+#[utoipa::path(
+    get,
+    path = "/api/v1/controls/queue",
+    params(
+        ("underlying" = Option<String>, Query, description = "Only report orders on this underlying")
+    ),
+    responses(
+        (status = 200, description = "Maker queue positions", body = QueuePositionsResponse)
+    ),
+    tag = "Controls"
+)]
+pub async fn get_queue_positions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<QueuePositionQuery>,
+) -> Json<QueuePositionsResponse> {
+    Json(QueuePositionsResponse {
+        orders: state
+            .market_maker
+            .queue_positions(query.underlying.as_deref())
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    })
+}
+
+/// Get the requote throttling parameters and the sent vs. skipped counters.
+#[utoipa::path(
+    get,
+    path = "/api/v1/controls/requotes",
+    responses(
+        (status = 200, description = "Requote throttling counters", body = RequoteStatsResponse)
+    ),
+    tag = "Controls"
+)]
+pub async fn get_requote_stats(State(state): State<Arc<AppState>>) -> Json<RequoteStatsResponse> {
+    Json(RequoteStatsResponse::new(
+        state.market_maker.requote_params(),
+        state.market_maker.requote_stats(),
+    ))
+}
+
+/// Get the market maker's P&L attributed to edge, greeks and hedging.
+///
+/// Reported since start-up, for the open period and for recently closed
+/// periods. Periods close on the `[market_maker.pnl]` schedule.
+#[utoipa::path(
+    get,
+    path = "/api/v1/controls/pnl",
+    responses(
+        (status = 200, description = "P&L attribution per underlying and period", body = PnlAttributionResponse)
+    ),
+    tag = "Controls"
+)]
+pub async fn get_pnl_attribution(
+    State(state): State<Arc<AppState>>,
+) -> Json<PnlAttributionResponse> {
+    let mm = &state.market_maker;
+    let cumulative = mm.pnl_cumulative();
+    let current = mm.pnl_current_period();
+
+    Json(PnlAttributionResponse {
+        since_start: PnlPeriodResponse::new(mm.pnl_started_ms(), None, &cumulative),
+        current_period: (&current).into(),
+        periods: mm
+            .pnl_periods(MAX_PNL_PERIODS)
+            .iter()
+            .map(Into::into)
+            .collect(),
+    })
+}
+
+/// Get the market maker's delta hedge status and recent hedge trades.
+#[utoipa::path(
+    get,
+    path = "/api/v1/controls/hedging",
+    responses(
+        (status = 200, description = "Hedge status per underlying", body = HedgingResponse)
+    ),
+    tag = "Controls"
+)]
+pub async fn get_hedging(State(state): State<Arc<AppState>>) -> Json<HedgingResponse> {
+    let params = state.market_maker.hedge_params();
+
+    Json(HedgingResponse {
+        enabled: params.enabled,
+        delta_band: params.delta_band,
+        lot_size: params.lot_size,
+        underlyings: state
+            .market_maker
+            .hedge_status()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        trades: state
+            .market_maker
+            .hedge_trades(HEDGE_TRADES_LIMIT)
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    })
+}
+
 // ============================================================================
 // Price Handlers
 // ============================================================================
@@ -363,7 +821,7 @@ const MAX_PRICE_DOLLARS: f64 = crate::config::MAX_INITIAL_PRICE;
 /// negative, or greater than [`MAX_PRICE_DOLLARS`].
 #[inline]
 #[must_use = "the validated cents value (or rejection) must be handled"]
-fn dollars_to_cents(field: &str, value: f64) -> Result<u64, ApiError> {
+pub(crate) fn dollars_to_cents(field: &str, value: f64) -> Result<u64, ApiError> {
     if !value.is_finite() {
         return Err(ApiError::InvalidRequest(format!(
             "{field} must be a finite number, got {value}"
@@ -414,6 +872,7 @@ fn cents_to_i64(field: &str, cents: u64) -> Result<i64, ApiError> {
 #[tracing::instrument(skip_all, fields(symbol = %body.symbol))]
 pub async fn insert_price(
     State(state): State<Arc<AppState>>,
+    journal: Option<Extension<JournalHold>>,
     Json(body): Json<InsertPriceRequest>,
 ) -> Result<Json<InsertPriceResponse>, ApiError> {
     // Validate and convert EVERY monetary input up front, before mutating any
@@ -428,12 +887,13 @@ pub async fn insert_price(
     let bid_cents = bid_cents_u64.map(|c| cents_to_i64("bid", c)).transpose()?;
     let ask_cents = ask_cents_u64.map(|c| cents_to_i64("ask", c)).transpose()?;
 
-    let timestamp = Utc::now();
+    let timestamp = crate::clock::now();
 
     // All inputs are valid: update the in-memory market maker first...
     state
         .market_maker
         .update_price(&body.symbol, price_cents_u64);
+    crate::api::margin::refresh_margin_for_underlying(&state, &body.symbol);
 
     tracing::debug!(
         symbol = %body.symbol,
@@ -441,36 +901,37 @@ pub async fn insert_price(
         "price inserted"
     );
 
-    // ...then persist the same validated values as a best-effort durability
-    // record. The in-memory market maker is authoritative for live prices (the DB
+    // The journal lock covers the in-memory update only; the database write
+    // below must not hold up the next input.
+    if let Some(Extension(hold)) = journal {
+        hold.release();
+    }
+
+    // ...then record the same validated values in the repository as a
+    // best-effort durability record. The in-memory market maker is authoritative for live prices (the DB
     // is optional and the server runs fully in-memory when `DATABASE_URL` is
     // unset), so a persistence failure is logged as a WARN and does NOT fail the
     // request — otherwise a transient DB error would reject an insert whose
     // in-memory effect already succeeded, diverging the response from reality.
-    if let Some(ref db) = state.db {
-        let write = sqlx::query(
-            r#"
-            INSERT INTO underlying_prices (symbol, price_cents, bid_cents, ask_cents, volume, timestamp, source)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            "#,
-        )
-        .bind(&body.symbol)
-        .bind(price_cents)
-        .bind(bid_cents)
-        .bind(ask_cents)
-        .bind(body.volume)
-        .bind(timestamp)
-        .bind(&body.source)
-        .execute(db.pool())
-        .await;
-
-        if let Err(e) = write {
-            tracing::warn!(
-                symbol = %body.symbol,
-                error = %e,
-                "failed to persist inserted price; in-memory update is authoritative and was kept"
-            );
-        }
+    let record = PriceRecord {
+        symbol: body.symbol.clone(),
+        price_cents,
+        bid_cents,
+        ask_cents,
+        volume: body.volume,
+        timestamp,
+        source: body.source.clone(),
+    };
+    if let Err(e) = state
+        .repository
+        .insert_prices(std::slice::from_ref(&record))
+        .await
+    {
+        tracing::warn!(
+            symbol = %body.symbol,
+            error = %e,
+            "failed to persist inserted price; in-memory update is authoritative and was kept"
+        );
     }
 
     Ok(Json(InsertPriceResponse {
@@ -511,39 +972,21 @@ pub async fn get_latest_price(
         }));
     }
 
-    // If we have a database, try to get from there
-    if let Some(ref db) = state.db {
-        type PriceRow = (
-            i64,
-            Option<i64>,
-            Option<i64>,
-            Option<i64>,
-            chrono::DateTime<Utc>,
-        );
-        let row: Option<PriceRow> = sqlx::query_as(
-            r#"
-                SELECT price_cents, bid_cents, ask_cents, volume, timestamp
-                FROM underlying_prices
-                WHERE symbol = $1
-                ORDER BY timestamp DESC
-                LIMIT 1
-                "#,
-        )
-        .bind(&symbol)
-        .fetch_optional(db.pool())
+    // Otherwise fall back to the last recorded price
+    let stored = state
+        .repository
+        .latest_price(&symbol)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
-
-        if let Some((price_cents, bid_cents, ask_cents, volume, timestamp)) = row {
-            return Ok(Json(LatestPriceResponse {
-                symbol,
-                price: price_cents as f64 / 100.0,
-                bid: bid_cents.map(|b| b as f64 / 100.0),
-                ask: ask_cents.map(|a| a as f64 / 100.0),
-                volume,
-                timestamp: timestamp.to_rfc3339(),
-            }));
-        }
+    if let Some(price) = stored {
+        return Ok(Json(LatestPriceResponse {
+            symbol,
+            price: price.price_cents as f64 / 100.0,
+            bid: price.bid_cents.map(|b| b as f64 / 100.0),
+            ask: price.ask_cents.map(|a| a as f64 / 100.0),
+            volume: price.volume,
+            timestamp: price.timestamp.to_rfc3339(),
+        }));
     }
 
     Err(ApiError::NotFound(format!(
@@ -552,6 +995,119 @@ pub async fn get_latest_price(
     )))
 }
 
+/// Seconds in the 365-day year realized volatility is annualized to.
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Realized volatility of `bars`, from the log returns between the closes of
+/// consecutive bars; a zero close yields no return.
+fn realized_volatility(bars: &[PriceBar]) -> RealizedVolatilityStats {
+    let returns: Vec<f64> = bars
+        .windows(2)
+        .filter(|pair| pair[0].close_cents != 0 && pair[1].close_cents != 0)
+        .map(|pair| (pair[1].close_cents as f64 / pair[0].close_cents as f64).ln())
+        .collect();
+    if returns.is_empty() {
+        return RealizedVolatilityStats::default();
+    }
+    let count = returns.len() as f64;
+    let total: f64 = returns.iter().sum();
+    let return_stddev = (returns.len() > 1).then(|| {
+        let mean = total / count;
+        let squared_deviations: f64 = returns.iter().map(|r| (r - mean).powi(2)).sum();
+        (squared_deviations / (count - 1.0)).sqrt()
+    });
+    let span_secs = match (bars.first(), bars.last()) {
+        (Some(first), Some(last)) => {
+            (last.start - first.start).num_microseconds().unwrap_or(0) as f64 / 1_000_000.0
+        }
+        _ => 0.0,
+    };
+    let squared_returns: f64 = returns.iter().map(|r| r * r).sum();
+    RealizedVolatilityStats {
+        returns: returns.len() as u64,
+        total_log_return: Some(total),
+        return_stddev,
+        annualized: (span_secs > 0.0)
+            .then(|| (squared_returns * SECONDS_PER_YEAR / span_secs).sqrt()),
+    }
+}
+
+/// Get the recorded price history of a symbol.
+///
+/// Returns the prices recorded in the repository (inserted through
+/// `POST /api/v1/prices` and, with `[simulation] record_prices`, simulator
+/// ticks), optionally downsampled to OHLC bars, together with their realized
+/// volatility. When more than `limit` points match, the newest are returned,
+/// or the oldest when both `from` and `to` are set. Without a database only
+/// the latest 10,000 prices of each symbol are kept.
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices/{symbol}/history",
+    params(
+        ("symbol" = String, Path, description = "Underlying symbol"),
+        ("from" = Option<u64>, Query, description = "Start timestamp in seconds (optional)"),
+        ("to" = Option<u64>, Query, description = "End timestamp in seconds (optional)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of points (default 500)"),
+        ("interval" = Option<String>, Query, description = "Downsampling interval: 1m, 5m, 15m, 1h, 4h, 1d (raw prices when omitted)")
+    ),
+    responses(
+        (status = 200, description = "Price history", body = PriceHistoryResponse),
+        (status = 400, description = "Invalid interval", body = ErrorResponse)
+    ),
+    tag = "Prices"
+)]
+#[tracing::instrument(skip_all, fields(symbol = %symbol))]
+pub async fn get_price_history(
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<PriceHistoryQuery>,
+) -> Result<Json<PriceHistoryResponse>, ApiError> {
+    let interval: Option<OhlcInterval> = query
+        .interval
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(ApiError::InvalidRequest)?;
+    let from_secs = |secs: u64| {
+        DateTime::from_timestamp(i64::try_from(secs).unwrap_or(i64::MAX), 0)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    };
+    let range = PriceHistoryRange {
+        from: query.from.map(from_secs),
+        to: query.to.map(from_secs),
+        bucket_secs: interval.map_or(0, |interval| interval.seconds()),
+        limit: query.limit.unwrap_or(500).min(1000),
+        oldest_first: query.from.is_some() && query.to.is_some(),
+    };
+
+    let bars = state
+        .repository
+        .price_history(&symbol, &range)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let realized_volatility = realized_volatility(&bars);
+    let points = bars
+        .into_iter()
+        .map(|bar| PriceHistoryPoint {
+            timestamp: bar.start.to_rfc3339(),
+            open: bar.open_cents as f64 / 100.0,
+            high: bar.high_cents as f64 / 100.0,
+            low: bar.low_cents as f64 / 100.0,
+            close: bar.close_cents as f64 / 100.0,
+            volume: bar.volume,
+            samples: bar.samples,
+        })
+        .collect();
+
+    Ok(Json(PriceHistoryResponse {
+        symbol,
+        interval: interval.map(|interval| interval.to_string()),
+        points,
+        realized_volatility,
+    }))
+}
+
 /// Get prices for all symbols.
 #[utoipa::path(
     get,
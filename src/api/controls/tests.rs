@@ -177,6 +177,203 @@ fn test_latest_price_response_minimal() {
     assert!(json.contains("\"volume\":null"));
 }
 
+// ============================================================================
+// PriceHistory Tests
+// ============================================================================
+
+#[test]
+fn test_price_history_response_serialization() {
+    let response = PriceHistoryResponse {
+        symbol: "BTC".to_string(),
+        interval: Some("1m".to_string()),
+        points: vec![PriceHistoryPoint {
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            open: 100.0,
+            high: 101.5,
+            low: 99.5,
+            close: 101.0,
+            volume: None,
+            samples: 4,
+        }],
+        realized_volatility: RealizedVolatilityStats {
+            returns: 3,
+            total_log_return: Some(0.01),
+            return_stddev: Some(0.002),
+            annualized: Some(0.45),
+        },
+    };
+
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"interval\":\"1m\""));
+    assert!(json.contains("\"high\":101.5"));
+    assert!(json.contains("\"samples\":4"));
+    assert!(json.contains("\"returns\":3"));
+    assert!(json.contains("\"annualized\":0.45"));
+}
+
+#[test]
+fn test_price_history_response_raw_omits_interval() {
+    let response = PriceHistoryResponse {
+        symbol: "BTC".to_string(),
+        interval: None,
+        points: Vec::new(),
+        realized_volatility: RealizedVolatilityStats::default(),
+    };
+
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(!json.contains("interval"));
+    assert!(json.contains("\"returns\":0"));
+    assert!(json.contains("\"annualized\":null"));
+}
+
+#[tokio::test]
+async fn test_price_history_rejects_invalid_interval() {
+    let state = Arc::new(AppState::new());
+    let query = PriceHistoryQuery {
+        interval: Some("2m".to_string()),
+        ..PriceHistoryQuery::default()
+    };
+
+    let err = get_price_history(State(state), Path("BTC".to_string()), Query(query))
+        .await
+        .expect_err("unknown interval must be rejected");
+    assert!(matches!(err, ApiError::InvalidRequest(ref msg) if msg.contains("2m")));
+}
+
+#[tokio::test]
+async fn test_price_history_reads_the_repository_without_a_database() {
+    let state = Arc::new(AppState::new());
+    let start = chrono::DateTime::from_timestamp(1_699_999_980, 0).unwrap();
+    let prices: Vec<PriceRecord> = [10_000, 10_100, 10_050]
+        .into_iter()
+        .enumerate()
+        .map(|(i, price_cents)| PriceRecord {
+            symbol: "BTC".to_string(),
+            price_cents,
+            bid_cents: None,
+            ask_cents: None,
+            volume: Some(1),
+            timestamp: start + chrono::Duration::seconds(20 * i as i64),
+            source: None,
+        })
+        .collect();
+    state
+        .repository
+        .insert_prices(&prices)
+        .await
+        .expect("inserted");
+
+    let query = PriceHistoryQuery {
+        interval: Some("1m".to_string()),
+        ..PriceHistoryQuery::default()
+    };
+    let Json(history) = get_price_history(State(state), Path("BTC".to_string()), Query(query))
+        .await
+        .expect("history");
+    assert_eq!(history.points.len(), 1);
+    assert_eq!(history.points[0].open, 100.0);
+    assert_eq!(history.points[0].high, 101.0);
+    assert_eq!(history.points[0].close, 100.5);
+    assert_eq!(history.points[0].volume, Some(3));
+    assert_eq!(history.realized_volatility.returns, 0);
+}
+
+#[tokio::test]
+async fn test_price_history_reads_a_sqlite_repository() {
+    let db = crate::db::DatabasePool::new("sqlite::memory:")
+        .await
+        .expect("connected");
+    db.run_migrations().await.expect("migrated");
+    let mut state = AppState::new();
+    state.repository = db.repository();
+    let state = Arc::new(state);
+
+    // Minute-aligned
+    let t0 = 1_700_000_040;
+    let prices: Vec<PriceRecord> = [(0, 100), (59, 90), (60, 110), (61, 115), (200, 107)]
+        .into_iter()
+        .map(|(offset, price_cents)| PriceRecord {
+            symbol: "BTC".to_string(),
+            price_cents,
+            bid_cents: None,
+            ask_cents: None,
+            volume: None,
+            timestamp: DateTime::from_timestamp(t0 + offset, 0).unwrap(),
+            source: None,
+        })
+        .collect();
+    state
+        .repository
+        .insert_prices(&prices)
+        .await
+        .expect("inserted");
+
+    let query = PriceHistoryQuery {
+        interval: Some("1m".to_string()),
+        ..PriceHistoryQuery::default()
+    };
+    let Json(history) = get_price_history(State(state), Path("BTC".to_string()), Query(query))
+        .await
+        .expect("history");
+    let closes: Vec<f64> = history.points.iter().map(|p| p.close).collect();
+    assert_eq!(closes, vec![0.9, 1.15, 1.07]);
+    assert_eq!(history.points[1].samples, 2);
+
+    // Closes 90, 115, 107 over three minutes.
+    let volatility = history.realized_volatility;
+    assert_eq!(volatility.returns, 2);
+    let close = |actual: Option<f64>, expected: f64| {
+        assert!(
+            (actual.unwrap() - expected).abs() < 1e-9,
+            "{actual:?} != {expected}"
+        );
+    };
+    close(volatility.total_log_return, (107.0_f64 / 90.0).ln());
+    close(volatility.return_stddev, 0.224_312_480_359_765_5);
+    close(volatility.annualized, 106.947_370_491_316_97);
+}
+
+#[test]
+fn test_realized_volatility_of_known_bars() {
+    let bar = |start: i64, close_cents: i64| PriceBar {
+        start: DateTime::from_timestamp(start, 0).unwrap(),
+        open_cents: close_cents,
+        high_cents: close_cents,
+        low_cents: close_cents,
+        close_cents,
+        volume: None,
+        samples: 1,
+    };
+    let close = |actual: Option<f64>, expected: f64| {
+        assert!(
+            (actual.unwrap() - expected).abs() < 1e-9,
+            "{actual:?} != {expected}"
+        );
+    };
+
+    // ln(1.1) and ln(0.9) over two minutes.
+    let stats = realized_volatility(&[bar(0, 100), bar(60, 110), bar(120, 99)]);
+    assert_eq!(stats.returns, 2);
+    close(stats.total_log_return, 0.99_f64.ln());
+    close(stats.return_stddev, 0.141_895_609_546_707_69);
+    close(stats.annualized, 72.832_571_539_245_78);
+
+    // A zero close has no return; one return has no standard deviation.
+    let stats = realized_volatility(&[bar(0, 100), bar(60, 0), bar(120, 110), bar(180, 121)]);
+    assert_eq!(stats.returns, 1);
+    close(stats.total_log_return, 1.1_f64.ln());
+    assert_eq!(stats.return_stddev, None);
+    close(
+        stats.annualized,
+        1.1_f64.ln() * (SECONDS_PER_YEAR / 180.0).sqrt(),
+    );
+
+    assert_eq!(
+        realized_volatility(&[bar(0, 100)]),
+        RealizedVolatilityStats::default()
+    );
+}
+
 // ============================================================================
 // InstrumentStatus Tests
 // ============================================================================
@@ -187,6 +384,9 @@ fn test_instrument_status_serialization() {
         symbol: "AAPL".to_string(),
         quoting_enabled: true,
         current_price: Some(150.50),
+        strategy: "single".to_string(),
+        ladder_levels: 1,
+        resting_orders: 0,
     };
 
     let json = serde_json::to_string(&status).unwrap();
@@ -201,6 +401,9 @@ fn test_instrument_status_no_price() {
         symbol: "NEW".to_string(),
         quoting_enabled: false,
         current_price: None,
+        strategy: "single".to_string(),
+        ladder_levels: 1,
+        resting_orders: 0,
     };
 
     let json = serde_json::to_string(&status).unwrap();
@@ -220,11 +423,17 @@ fn test_instruments_list_response_serialization() {
                 symbol: "AAPL".to_string(),
                 quoting_enabled: true,
                 current_price: Some(150.50),
+                strategy: "single".to_string(),
+                ladder_levels: 1,
+                resting_orders: 0,
             },
             InstrumentStatus {
                 symbol: "SPY".to_string(),
                 quoting_enabled: false,
                 current_price: Some(450.0),
+                strategy: "single".to_string(),
+                ladder_levels: 1,
+                resting_orders: 0,
             },
         ],
     };
@@ -332,7 +541,7 @@ async fn test_insert_price_valid_succeeds() {
     let state = Arc::new(AppState::new());
     let req = insert_price_request(123.45, Some(123.40), Some(123.50));
 
-    let resp = insert_price(State(Arc::clone(&state)), Json(req))
+    let resp = insert_price(State(Arc::clone(&state)), None, Json(req))
         .await
         .expect("valid positive price should succeed");
 
@@ -349,7 +558,7 @@ async fn test_insert_price_negative_rejected_no_update() {
     let state = Arc::new(AppState::new());
     let req = insert_price_request(-1.0, None, None);
 
-    let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+    let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
     assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
     // Market maker (and, by ordering, the DB) is never touched.
@@ -361,7 +570,7 @@ async fn test_insert_price_nan_rejected_no_update() {
     let state = Arc::new(AppState::new());
     let req = insert_price_request(f64::NAN, None, None);
 
-    let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+    let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
     assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
     assert_eq!(state.market_maker.get_price("TEST"), None);
@@ -372,7 +581,7 @@ async fn test_insert_price_infinite_rejected_no_update() {
     let state = Arc::new(AppState::new());
     let req = insert_price_request(f64::INFINITY, None, None);
 
-    let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+    let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
     assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
     assert_eq!(state.market_maker.get_price("TEST"), None);
@@ -383,7 +592,7 @@ async fn test_insert_price_absurdly_large_rejected_no_update() {
     let state = Arc::new(AppState::new());
     let req = insert_price_request(MAX_PRICE_DOLLARS * 100.0, None, None);
 
-    let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+    let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
     assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
     assert_eq!(state.market_maker.get_price("TEST"), None);
@@ -397,7 +606,7 @@ async fn test_insert_price_bad_bid_rejected_all_or_nothing() {
         let state = Arc::new(AppState::new());
         let req = insert_price_request(100.0, Some(bad_bid), None);
 
-        let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+        let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
         assert!(
             matches!(result, Err(ApiError::InvalidRequest(_))),
@@ -417,7 +626,7 @@ async fn test_insert_price_bad_ask_rejected_all_or_nothing() {
         let state = Arc::new(AppState::new());
         let req = insert_price_request(100.0, None, Some(bad_ask));
 
-        let result = insert_price(State(Arc::clone(&state)), Json(req)).await;
+        let result = insert_price(State(Arc::clone(&state)), None, Json(req)).await;
 
         assert!(
             matches!(result, Err(ApiError::InvalidRequest(_))),
@@ -583,3 +792,219 @@ async fn test_update_parameters_multi_field_one_bad_applies_none() {
         "no field may be applied when any field is invalid"
     );
 }
+
+// ============================================================================
+// Hedging Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_get_hedging_reports_disabled_by_default() {
+    let state = Arc::new(AppState::new());
+
+    let Json(response) = get_hedging(State(state)).await;
+
+    assert!(!response.enabled);
+    assert_eq!(response.delta_band, 1.0);
+    assert!(response.underlyings.is_empty());
+    assert!(response.trades.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_hedging_reports_status_and_trades() {
+    use crate::market_maker::{HedgeParams, InventoryPosition};
+    use chrono::TimeZone;
+    use optionstratlib::{ExpirationDate, OptionStyle};
+
+    let state = Arc::new(AppState::new());
+    state.market_maker.set_hedge_params(HedgeParams {
+        enabled: true,
+        delta_band: 0.5,
+        lot_size: 0.01,
+    });
+    state.market_maker.update_price("BTC", 5_000_000);
+    state.market_maker.take_inventory(InventoryPosition {
+        instrument: "BTC-20351231-5000000-P".to_string(),
+        underlying: "BTC".to_string(),
+        expiration: ExpirationDate::DateTime(
+            Utc.with_ymd_and_hms(2035, 12, 31, 16, 0, 0)
+                .single()
+                .expect("valid fixture datetime"),
+        ),
+        strike: 5_000_000,
+        style: OptionStyle::Put,
+        quantity: 5,
+        multiplier: 1,
+    });
+
+    let Json(response) = get_hedging(State(state)).await;
+
+    assert!(response.enabled);
+    assert_eq!(response.underlyings.len(), 1);
+    let btc = &response.underlyings[0];
+    assert_eq!(btc.underlying, "BTC");
+    assert_eq!(btc.spot, Some(5_000_000));
+    assert!(btc.option_delta < -0.5, "long puts are short delta");
+    assert!(btc.hedge_position > 0.5, "hedged by buying the underlying");
+    assert!(btc.residual_delta.abs() <= 0.5);
+    assert_eq!(btc.trades, 1);
+    assert_eq!(response.trades.len(), 1);
+    assert_eq!(response.trades[0].price, 5_000_000);
+
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"hedge_pnl\":0"));
+}
+
+// ============================================================================
+// P&L Attribution Tests
+// ============================================================================
+
+#[test]
+fn test_pnl_period_response_totals_underlyings() {
+    let mut by_underlying = BTreeMap::new();
+    by_underlying.insert(
+        "BTC".to_string(),
+        PnlComponents {
+            edge: 120.4,
+            delta: -50.0,
+            ..PnlComponents::default()
+        },
+    );
+    by_underlying.insert(
+        "ETH".to_string(),
+        PnlComponents {
+            theta: -10.0,
+            hedging: 30.0,
+            ..PnlComponents::default()
+        },
+    );
+
+    let period = PnlPeriodResponse::new(1, Some(2), &by_underlying);
+
+    assert_eq!(period.underlyings.len(), 2);
+    assert_eq!(period.underlyings[0].underlying, "BTC");
+    assert_eq!(period.underlyings[0].pnl.edge, 120);
+    assert_eq!(period.underlyings[0].pnl.total, 70);
+    assert_eq!(period.total.hedging, 30);
+    assert_eq!(period.total.total, 90);
+}
+
+#[tokio::test]
+async fn test_get_pnl_attribution_reports_periods() {
+    let state = Arc::new(AppState::new());
+    state.market_maker.update_price("BTC", 5_000_000);
+    state.market_maker.close_pnl_period();
+
+    let Json(response) = get_pnl_attribution(State(state)).await;
+
+    assert!(response.since_start.end_ms.is_none());
+    assert!(response.current_period.end_ms.is_none());
+    assert_eq!(response.periods.len(), 1);
+    assert!(response.periods[0].end_ms.is_some());
+    assert_eq!(response.since_start.total.total, 0);
+
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"current_period\""));
+    assert!(json.contains("\"hedging\":0"));
+}
+
+#[tokio::test]
+async fn test_get_requote_stats_reports_params_and_counters() {
+    use chrono::TimeZone;
+    use optionstratlib::ExpirationDate;
+
+    let state = Arc::new(AppState::new());
+    let Json(response) = get_requote_stats(State(Arc::clone(&state))).await;
+    assert_eq!(response.min_price_change_ticks, 1);
+    assert_eq!(response.max_updates_per_second, 5);
+    assert_eq!(response.sent, 0);
+    assert_eq!(response.skipped, 0);
+
+    let expiration = ExpirationDate::DateTime(
+        Utc.with_ymd_and_hms(2035, 12, 31, 16, 0, 0)
+            .single()
+            .expect("valid fixture datetime"),
+    );
+    state
+        .manager
+        .get_or_create("BTC")
+        .get_or_create_expiration(expiration)
+        .get_or_create_strike(5_000_000);
+    state.market_maker.update_price("BTC", 5_000_000);
+    state.market_maker.update_price("BTC", 5_000_000);
+
+    let Json(response) = get_requote_stats(State(state)).await;
+    assert_eq!(response.sent, 2);
+    assert_eq!(response.skipped_unchanged, 2);
+    assert_eq!(response.skipped, 2);
+}
+
+#[tokio::test]
+async fn test_get_queue_positions_reports_maker_orders() {
+    use chrono::TimeZone;
+    use optionstratlib::ExpirationDate;
+
+    let state = Arc::new(AppState::new());
+    let expiration = ExpirationDate::DateTime(
+        Utc.with_ymd_and_hms(2035, 12, 31, 16, 0, 0)
+            .single()
+            .expect("valid fixture datetime"),
+    );
+    state
+        .manager
+        .get_or_create("BTC")
+        .get_or_create_expiration(expiration)
+        .get_or_create_strike(5_000_000);
+    state.market_maker.update_price("BTC", 5_000_000);
+
+    let Json(response) = get_queue_positions(
+        State(Arc::clone(&state)),
+        Query(QueuePositionQuery {
+            underlying: Some("BTC".to_string()),
+        }),
+    )
+    .await;
+    assert_eq!(response.orders.len(), 4);
+    let bid = &response.orders[0];
+    assert_eq!(bid.side, OrderSide::Buy);
+    assert_eq!(bid.orders_ahead, 0);
+    assert_eq!(bid.quantity, bid.level_quantity);
+
+    let Json(response) = get_queue_positions(
+        State(state),
+        Query(QueuePositionQuery {
+            underlying: Some("ETH".to_string()),
+        }),
+    )
+    .await;
+    assert!(response.orders.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_instruments_reports_strategy_per_underlying() {
+    use crate::market_maker::LayeredStrategy;
+
+    let state = Arc::new(AppState::new());
+    state.manager.get_or_create("BTC");
+    state.manager.get_or_create("ETH");
+    state
+        .market_maker
+        .set_strategy("BTC", Some(Arc::new(LayeredStrategy::default())));
+
+    let Json(response) = list_instruments(State(state)).await;
+    let strategy = |symbol: &str| {
+        response
+            .instruments
+            .iter()
+            .find(|i| i.symbol == symbol)
+            .map(|i| i.strategy.clone())
+    };
+    assert_eq!(strategy("BTC").as_deref(), Some("layered"));
+    assert_eq!(strategy("ETH").as_deref(), Some("single"));
+    let btc = response
+        .instruments
+        .iter()
+        .find(|i| i.symbol == "BTC")
+        .expect("listed");
+    assert_eq!(btc.ladder_levels, 3);
+    assert_eq!(btc.resting_orders, 0, "no strikes, nothing quoted");
+}
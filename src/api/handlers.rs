@@ -1,31 +1,44 @@
 //! API request handlers.
 
+use crate::amend::{AmendError, amend_order};
+use crate::api::account::{buying_power_enforced, check_buying_power, post_fill, sweep_cost};
+use crate::api::margin::{check_order_margin, order_risk_position};
 use crate::api::websocket::{OrderbookDeltaEvent, PriceLevelChange, TradeEvent};
+use crate::auth::Claims;
+use crate::clock;
 use crate::error::{ApiError, ErrorResponse, RateLimitErrorResponse};
+use crate::journal::JournalEvent;
+use crate::market_maker::{ParityFit, ParityQuote, fit_implied_forward};
 use crate::models::{
     ATMTermStructurePoint, AddOrderRequest, AddOrderResponse, ApiTimeInForce, BulkCancelRequest,
     BulkCancelResponse, BulkCancelResultItem, BulkOrderItem, BulkOrderRequest, BulkOrderResponse,
     BulkOrderResultItem, BulkOrderStatus, CancelAllQuery, CancelAllResponse, CancelOrderResponse,
     ChainQuery, ChainStrikeRow, CreateSnapshotResponse, DeleteUnderlyingResponse, DepthMetrics,
     EnrichedSnapshotResponse, ExecutionInfo, ExecutionSummary, ExecutionsListResponse,
-    ExecutionsQuery, ExpirationSummary, ExpirationsListResponse, FillInfo, GlobalStatsResponse,
-    GreeksData, GreeksResponse, HealthResponse, ImpactMetrics, LastTradeInfo, LastTradeResponse,
+    ExecutionsQuery, ExpirationSummary, ExpirationsListResponse, FillInfo, ForwardQuery,
+    GlobalStatsResponse, GreeksData, GreeksResponse, HealthResponse, ImpactMetrics,
+    ImpliedForwardResponse, InstrumentSpecResponse, LastTradeInfo, LastTradeResponse,
     LimitOrderStatus, MarketImpactMetrics, MarketOrderRequest, MarketOrderResponse,
-    MarketOrderStatus, ModifyOrderRequest, ModifyOrderResponse, ModifyOrderStatus, OhlcInterval,
-    OhlcQuery, OhlcResponse, OptionChainResponse, OptionQuoteData, OrderBookSnapshotResponse,
-    OrderFillInfo, OrderInfo, OrderListQuery, OrderListResponse, OrderSide, OrderStatus,
-    OrderStatusResponse, OrderTimeInForce, OrderbookMetricsResponse, OrderbookSnapshotInfo,
-    PositionInfo, PositionQuery, PositionResponse, PositionSummary, PositionsListResponse,
-    PriceLevelInfo, PriceMetrics, QuoteResponse, RestoreSnapshotResponse, SnapshotDepth,
-    SnapshotQuery, SnapshotStats, SnapshotSummary, SnapshotsListResponse, SpreadMetrics, StrikeIV,
-    StrikeSummary, StrikesListResponse, TokenRequest, TokenResponse, UnderlyingSummary,
-    UnderlyingsListResponse, VolatilitySurfaceResponse,
+    MarketOrderStatus, ModifyOrderRequest, ModifyOrderResponse, ModifyOrderStatus,
+    OhlcBackfillRequest, OhlcBackfillResponse, OhlcInterval, OhlcQuery, OhlcResponse,
+    OptionChainResponse, OptionQuoteData, OrderBookSnapshotResponse, OrderFillInfo, OrderInfo,
+    OrderListQuery, OrderListResponse, OrderSide, OrderStatus, OrderStatusResponse,
+    OrderTimeInForce, OrderbookMetricsResponse, OrderbookSnapshotInfo, ParityStrikeDiagnostic,
+    Permission, PositionInfo, PositionQuery, PositionResponse, PositionSummary,
+    PositionsListResponse, PriceLevelInfo, PriceMetrics, QuoteHistoryQuery, QuoteHistoryResponse,
+    QuoteResponse, RestoreSnapshotResponse, SnapshotDepth, SnapshotQuery, SnapshotStats,
+    SnapshotSummary, SnapshotTrigger, SnapshotsListResponse, SpreadMetrics, StrikeIV,
+    StrikeSummary, StrikesListResponse, TickBandInfo, TokenRequest, TokenResponse,
+    UnderlyingSummary, UnderlyingsListResponse, VolatilitySurfaceHistoryQuery,
+    VolatilitySurfaceHistoryResponse, VolatilitySurfaceResponse,
 };
+use crate::ohlc::merge_bars;
+use crate::risk::RiskPosition;
 use crate::state::{AppState, StoredSnapshot};
-use axum::Json;
 use axum::extract::Query;
 use axum::extract::{Path, State};
-use option_chain_orderbook::orderbook::{OptionOrderBook, Quote};
+use axum::{Extension, Json};
+use option_chain_orderbook::orderbook::{ExpirationOrderBook, OptionOrderBook, Quote};
 use optionstratlib::prelude::Positive;
 use optionstratlib::{ExpirationDate, OptionStyle};
 use orderbook_rs::{OrderId, Side, TimeInForce};
@@ -63,7 +76,7 @@ fn parse_option_style(style: &str) -> Result<OptionStyle, ApiError> {
 }
 
 /// Formats ExpirationDate to YYYYMMDD string for API responses.
-fn format_expiration(exp: &ExpirationDate) -> String {
+pub(crate) fn format_expiration(exp: &ExpirationDate) -> String {
     match exp.get_date() {
         Ok(date) => date.format("%Y%m%d").to_string(),
         Err(_) => exp.to_string(),
@@ -102,7 +115,7 @@ fn find_expiration_by_str(
 }
 
 /// Parses expiration string to ExpirationDate.
-fn parse_expiration(exp_str: &str) -> Result<ExpirationDate, ApiError> {
+pub(crate) fn parse_expiration(exp_str: &str) -> Result<ExpirationDate, ApiError> {
     use optionstratlib::prelude::Positive;
 
     // An 8-ASCII-digit segment is ALWAYS a YYYYMMDD calendar date. This branch
@@ -168,6 +181,23 @@ fn parse_expiration(exp_str: &str) -> Result<ExpirationDate, ApiError> {
     )))
 }
 
+/// The deadline to hand the order book for a GTD order with deadline
+/// `expire_ms` admitted at event time `now_ms`.
+///
+/// The book checks the deadline against its own wall clock, not the event
+/// clock (see [`crate::clock`]). During a replay the wall clock can be past
+/// a deadline that was still ahead at the recorded time; the deadline is then
+/// moved on by the gap so the book admits the order as it did in the
+/// recorded run. A deadline still ahead of the wall clock is passed as is.
+fn book_gtd_deadline(expire_ms: u64, now_ms: u64) -> u64 {
+    let wall_ms = clock::wall_ms();
+    if expire_ms > wall_ms {
+        expire_ms
+    } else {
+        expire_ms.saturating_add(wall_ms.saturating_sub(now_ms))
+    }
+}
+
 /// Resolves the GTD (Good-Til-Date) expiration timestamp, in milliseconds, for a
 /// limit order.
 ///
@@ -206,6 +236,39 @@ fn parse_gtd_expire_at(expire_at: Option<&str>, now_ms: u64) -> Result<u64, ApiE
     Ok(expire_ms)
 }
 
+/// Rejects a limit `price` that is not on the tick grid of `underlying`.
+/// A zero price is left to the order-book validation.
+///
+/// # Errors
+/// Returns [`ApiError::InvalidTickSize`] naming the price and its tick.
+fn check_tick_size(state: &AppState, underlying: &str, price: u128) -> Result<(), ApiError> {
+    if price == 0 {
+        return Ok(());
+    }
+    state
+        .market_maker
+        .tick_table(underlying)
+        .check(price, state.market_maker.get_price(underlying))
+        .map_err(ApiError::InvalidTickSize)
+}
+
+/// Rejects a `quantity` that is not a whole number of lots of `underlying` or
+/// exceeds its maximum order size. A zero quantity is left to the order-book
+/// validation.
+///
+/// # Errors
+/// Returns [`ApiError::InvalidOrderSize`] naming the quantity and the limit.
+fn check_order_size(state: &AppState, underlying: &str, quantity: u64) -> Result<(), ApiError> {
+    if quantity == 0 {
+        return Ok(());
+    }
+    state
+        .market_maker
+        .contract_spec(underlying)
+        .check_quantity(quantity)
+        .map_err(ApiError::InvalidOrderSize)
+}
+
 // ============================================================================
 // Health Check
 // ============================================================================
@@ -286,9 +349,17 @@ pub async fn issue_token(
     let ttl_secs = request
         .ttl_secs
         .unwrap_or_else(|| state.auth.default_ttl_secs());
-    let (token, exp_secs) = state
-        .auth
-        .mint_token(request.permissions.clone(), ttl_secs)?;
+    let (token, exp_secs) = match request.account {
+        Some(ref account) => {
+            crate::auth::validate_account_id(account)?;
+            state
+                .auth
+                .mint_token_for(account.clone(), request.permissions.clone(), ttl_secs)?
+        }
+        None => state
+            .auth
+            .mint_token(request.permissions.clone(), ttl_secs)?,
+    };
 
     let expires_at = chrono::DateTime::<chrono::Utc>::from_timestamp(exp_secs as i64, 0)
         .map(|dt| dt.to_rfc3339())
@@ -296,6 +367,7 @@ pub async fn issue_token(
 
     tracing::info!(
         permissions = ?request.permissions,
+        account = ?request.account,
         ttl_secs,
         "issued JWT"
     );
@@ -458,11 +530,16 @@ pub async fn get_execution(
 
 /// Create a snapshot of all orderbooks.
 ///
-/// Saves the current state of all orderbooks for later recovery. At most
-/// [`AppState::MAX_RETAINED_SNAPSHOTS`] snapshots are retained; creating one
-/// beyond the cap evicts the oldest. An orderbook whose state fails to
-/// serialize is skipped (and counted in `orderbooks_failed`) instead of being
-/// stored empty.
+/// Saves the current state of all orderbooks, together with the engine
+/// state (orders, positions, executions, trades, OHLC bars and maker order
+/// tracking), for later recovery. Mutations are paused while it is captured
+/// so the two agree. At most
+/// [`AppState::MAX_RETAINED_SNAPSHOTS`] snapshots are retained in memory;
+/// creating one beyond the cap evicts the oldest. With a snapshot store
+/// configured the snapshot is also written to durable storage, which is then
+/// pruned by the `[snapshots]` retention policy. An orderbook whose state
+/// fails to serialize is skipped (and counted in `orderbooks_failed`) instead
+/// of being stored empty.
 #[utoipa::path(
     post,
     path = "/api/v1/admin/snapshot",
@@ -472,14 +549,85 @@ pub async fn get_execution(
     tag = "Admin"
 )]
 pub async fn create_snapshot(State(state): State<Arc<AppState>>) -> Json<CreateSnapshotResponse> {
+    Json(take_snapshot(&state, SnapshotTrigger::Manual).await)
+}
+
+/// Snapshots every non-empty orderbook, persists the snapshot and keeps it
+/// in memory. Shared by the admin endpoint and the scheduled snapshot task;
+/// `trigger` is recorded with the snapshot.
+pub async fn take_snapshot(state: &AppState, trigger: SnapshotTrigger) -> CreateSnapshotResponse {
     let snapshot_id = uuid::Uuid::new_v4().to_string();
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64;
 
-    let mut orderbooks_saved: u64 = 0;
-    let mut orders_saved: u64 = 0;
+    // Capture the books and the engine state under one pause so that they
+    // agree: no request, sweep or requote mutates either in between. Holding
+    // the journal pins the entry the capture follows.
+    let (snapshot_infos, orderbooks_failed, engine) = {
+        let _paused = state.pause_mutations().await;
+        let journal = match state.journal() {
+            Some(journal) => Some(journal.lock().await),
+            None => None,
+        };
+        let _quoting = state.market_maker.pause_quoting();
+        let (infos, failed) = capture_orderbooks(state, &snapshot_id, now);
+        let mut engine = state.engine_state();
+        engine.journal_seq = journal.as_ref().map_or(0, |journal| journal.last_seq());
+        (infos, failed, engine)
+    };
+    let orderbooks_saved = snapshot_infos.len() as u64;
+    let orders_saved: u64 = snapshot_infos.iter().map(|i| i.order_count).sum();
+    let journal_seq = engine.journal_seq;
+
+    let mut stored = StoredSnapshot::new(now, snapshot_infos);
+    stored.orderbooks_failed = orderbooks_failed;
+    stored.trigger = trigger;
+    stored.engine = Some(engine);
+    let persisted = persist_snapshot(state, &snapshot_id, &stored).await;
+    if persisted && orderbooks_failed == 0 {
+        compact_journal(state, journal_seq).await;
+    }
+
+    // Store the snapshot, evicting the oldest one past the retention cap.
+    state.insert_snapshot_bounded(snapshot_id.clone(), stored);
+
+    CreateSnapshotResponse {
+        success: orderbooks_failed == 0,
+        snapshot_id,
+        orderbooks_saved,
+        orders_saved,
+        orderbooks_failed,
+        persisted,
+        timestamp_ms: now,
+    }
+}
+
+/// Drops the journal entries a durable snapshot taken at `journal_seq`
+/// covers. Only done when startup restores that snapshot before replaying
+/// the journal; otherwise the whole journal is the only record of the state.
+async fn compact_journal(state: &AppState, journal_seq: u64) {
+    let restores_on_startup = state
+        .config
+        .as_ref()
+        .is_some_and(|config| config.snapshots.restore_on_startup);
+    let Some(journal) = state.journal().filter(|_| restores_on_startup) else {
+        return;
+    };
+    match journal.compact(journal_seq).await {
+        Ok(dropped) => tracing::debug!(dropped, journal_seq, "compacted the journal"),
+        Err(e) => tracing::warn!(error = %e, journal_seq, "failed to compact the journal"),
+    }
+}
+
+/// Serializes every non-empty orderbook. Returns the entries and the number
+/// of orderbooks skipped because their state failed to serialize.
+fn capture_orderbooks(
+    state: &AppState,
+    snapshot_id: &str,
+    now: u64,
+) -> (Vec<OrderbookSnapshotInfo>, u64) {
     let mut orderbooks_failed: u64 = 0;
     let mut snapshot_infos: Vec<OrderbookSnapshotInfo> = Vec::new();
 
@@ -533,7 +681,7 @@ pub async fn create_snapshot(State(state): State<Arc<AppState>>) -> Json<CreateS
                                     };
 
                                     let info = OrderbookSnapshotInfo {
-                                        snapshot_id: snapshot_id.clone(),
+                                        snapshot_id: snapshot_id.to_string(),
                                         underlying: underlying_symbol.clone(),
                                         expiration: exp_str.clone(),
                                         strike,
@@ -545,8 +693,6 @@ pub async fn create_snapshot(State(state): State<Arc<AppState>>) -> Json<CreateS
                                         created_at: now,
                                     };
 
-                                    orders_saved += order_count as u64;
-                                    orderbooks_saved += 1;
                                     snapshot_infos.push(info);
                                 }
                             }
@@ -557,23 +703,51 @@ pub async fn create_snapshot(State(state): State<Arc<AppState>>) -> Json<CreateS
         }
     }
 
-    // Store the snapshot, evicting the oldest one past the retention cap.
-    state.insert_snapshot_bounded(
-        snapshot_id.clone(),
-        StoredSnapshot::new(now, snapshot_infos),
-    );
+    (snapshot_infos, orderbooks_failed)
+}
 
-    Json(CreateSnapshotResponse {
-        success: orderbooks_failed == 0,
-        snapshot_id,
-        orderbooks_saved,
-        orders_saved,
-        orderbooks_failed,
-        timestamp_ms: now,
-    })
+/// Writes `snapshot` to the durable store, if any, and prunes the store.
+/// Returns whether the snapshot was written; failures are logged, since the
+/// in-memory copy is still usable.
+async fn persist_snapshot(state: &AppState, snapshot_id: &str, snapshot: &StoredSnapshot) -> bool {
+    let Some(store) = &state.snapshot_store else {
+        return false;
+    };
+    if let Err(e) = store.save(snapshot_id, snapshot).await {
+        tracing::warn!(snapshot_id, error = %e, "snapshot not written to durable storage");
+        return false;
+    }
+    match store
+        .prune(state.snapshot_retention(), snapshot.created_at_ms)
+        .await
+    {
+        Ok(0) => {}
+        Ok(pruned) => tracing::info!(pruned, "pruned durable snapshots past retention"),
+        Err(e) => tracing::warn!(error = %e, "durable snapshot retention failed"),
+    }
+    true
+}
+
+/// Looks up `snapshot_id` in memory, then in the durable store.
+async fn find_snapshot(state: &AppState, snapshot_id: &str) -> Result<StoredSnapshot, ApiError> {
+    if let Some(entry) = state.snapshots.get(snapshot_id) {
+        return Ok(entry.value().clone());
+    }
+    let not_found = || ApiError::NotFound(format!("Snapshot {} not found", snapshot_id));
+    let Some(store) = &state.snapshot_store else {
+        return Err(not_found());
+    };
+    store
+        .load(snapshot_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("snapshot store read failed: {e}")))?
+        .ok_or_else(not_found)
 }
 
 /// List all snapshots.
+///
+/// Lists the snapshots in memory together with those in durable storage,
+/// newest first.
 #[utoipa::path(
     get,
     path = "/api/v1/admin/snapshots",
@@ -583,10 +757,19 @@ pub async fn create_snapshot(State(state): State<Arc<AppState>>) -> Json<CreateS
     tag = "Admin"
 )]
 pub async fn list_snapshots(State(state): State<Arc<AppState>>) -> Json<SnapshotsListResponse> {
-    let mut snapshots: Vec<SnapshotSummary> = Vec::new();
+    let mut snapshots: Vec<SnapshotSummary> = match &state.snapshot_store {
+        Some(store) => store.list().await.unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "durable snapshots not listed");
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
 
     for entry in state.snapshots.iter() {
         let snapshot_id: String = entry.key().clone();
+        if snapshots.iter().any(|s| s.snapshot_id == snapshot_id) {
+            continue;
+        }
         let stored = entry.value();
         let total_orders: u64 = stored.infos.iter().map(|i| i.order_count).sum();
 
@@ -594,10 +777,13 @@ pub async fn list_snapshots(State(state): State<Arc<AppState>>) -> Json<Snapshot
             snapshot_id,
             orderbook_count: stored.infos.len() as u64,
             total_orders,
+            success: stored.orderbooks_failed == 0,
+            trigger: stored.trigger,
             created_at: stored.created_at_ms,
         });
     }
 
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
     let total = snapshots.len() as u64;
 
     Json(SnapshotsListResponse { snapshots, total })
@@ -620,19 +806,16 @@ pub async fn get_snapshot(
     State(state): State<Arc<AppState>>,
     Path(snapshot_id): Path<String>,
 ) -> Result<Json<Vec<OrderbookSnapshotInfo>>, ApiError> {
-    match state.snapshots.get(&snapshot_id) {
-        Some(entry) => {
-            let infos: Vec<OrderbookSnapshotInfo> = entry.value().infos.clone();
-            Ok(Json(infos))
-        }
-        None => Err(ApiError::NotFound(format!(
-            "Snapshot {} not found",
-            snapshot_id
-        ))),
-    }
+    Ok(Json(find_snapshot(&state, &snapshot_id).await?.infos))
 }
 
 /// Restore orderbooks from a snapshot.
+///
+/// A whole-state snapshot (format version 2) also replaces the orders,
+/// positions, executions, last trades, OHLC bars and the market maker's order
+/// tracking, and empties every book it does not hold. It is all or nothing:
+/// when any orderbook entry fails to parse, nothing is restored. A books-only
+/// snapshot restores what it can and skips the rest.
 #[utoipa::path(
     post,
     path = "/api/v1/admin/snapshots/{snapshot_id}/restore",
@@ -649,100 +832,223 @@ pub async fn restore_snapshot(
     State(state): State<Arc<AppState>>,
     Path(snapshot_id): Path<String>,
 ) -> Result<Json<RestoreSnapshotResponse>, ApiError> {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64;
+    Ok(Json(restore_snapshot_by_id(&state, snapshot_id).await?))
+}
 
-    let snapshot_infos: Vec<OrderbookSnapshotInfo> = match state.snapshots.get(&snapshot_id) {
-        Some(entry) => entry.value().infos.clone(),
-        None => {
-            return Err(ApiError::NotFound(format!(
-                "Snapshot {} not found",
-                snapshot_id
-            )));
+/// Restores snapshot `snapshot_id` under a pause of every mutation,
+/// journaling the restore first when a journal is attached.
+///
+/// # Errors
+/// Returns [`ApiError::NotFound`] for an unknown snapshot and
+/// [`ApiError::Internal`] when the store or the journal fails.
+pub(crate) async fn restore_snapshot_by_id(
+    state: &AppState,
+    snapshot_id: String,
+) -> Result<RestoreSnapshotResponse, ApiError> {
+    let snapshot = find_snapshot(state, &snapshot_id).await?;
+    let _paused = state.pause_mutations().await;
+    let event = JournalEvent::Restore {
+        snapshot_id: snapshot_id.clone(),
+    };
+    let response = state
+        .apply_journaled(event, || {
+            restore_stored_snapshot(state, snapshot_id, snapshot)
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("failed to journal the restore: {e}")))?;
+    Ok(response)
+}
+
+/// Restores the newest durable snapshot into the order books, or returns
+/// `None` when no store is configured or it holds no snapshot. Called at
+/// startup when `[snapshots] restore_on_startup` is set.
+///
+/// # Errors
+/// Returns the store error when the durable snapshots cannot be read.
+pub async fn restore_latest_snapshot(
+    state: &AppState,
+) -> Result<Option<RestoreSnapshotResponse>, crate::snapshots::SnapshotStoreError> {
+    let Some(store) = &state.snapshot_store else {
+        return Ok(None);
+    };
+    let Some((snapshot_id, snapshot)) = store.latest().await? else {
+        return Ok(None);
+    };
+    let response = {
+        let _paused = state.pause_mutations().await;
+        restore_stored_snapshot(state, snapshot_id.clone(), snapshot.clone())
+    };
+    state.insert_snapshot_bounded(snapshot_id, snapshot);
+    Ok(Some(response))
+}
+
+/// One orderbook entry of a snapshot, parsed and located.
+struct RestoreTarget {
+    underlying: String,
+    expiration: ExpirationDate,
+    strike: u64,
+    style: OptionStyle,
+    order_count: u64,
+    book: orderbook_rs::OrderBookSnapshot,
+}
+
+/// Parses one orderbook entry of snapshot `snapshot_id`, logging why when it
+/// cannot be restored.
+fn parse_restore_target(snapshot_id: &str, info: &OrderbookSnapshotInfo) -> Option<RestoreTarget> {
+    let book: orderbook_rs::OrderBookSnapshot = match serde_json::from_str(&info.data) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!(
+                snapshot_id = %snapshot_id,
+                underlying = %info.underlying,
+                expiration = %info.expiration,
+                strike = info.strike,
+                style = %info.style,
+                error = %e,
+                "skipping orderbook during restore: snapshot data failed to parse"
+            );
+            return None;
         }
     };
 
-    let mut orderbooks_restored: u64 = 0;
-    let mut orders_restored: u64 = 0;
-    let mut orderbooks_failed: u64 = 0;
+    let Ok(expiration) = parse_expiration(&info.expiration) else {
+        tracing::warn!(
+            snapshot_id = %snapshot_id,
+            underlying = %info.underlying,
+            expiration = %info.expiration,
+            "skipping orderbook during restore: invalid expiration"
+        );
+        return None;
+    };
 
-    for info in &snapshot_infos {
-        // Parse the snapshot data
-        let snapshot: orderbook_rs::OrderBookSnapshot = match serde_json::from_str(&info.data) {
-            Ok(s) => s,
-            Err(e) => {
-                orderbooks_failed = orderbooks_failed.saturating_add(1);
-                tracing::warn!(
-                    snapshot_id = %snapshot_id,
-                    underlying = %info.underlying,
-                    expiration = %info.expiration,
-                    strike = info.strike,
-                    style = %info.style,
-                    error = %e,
-                    "skipping orderbook during restore: snapshot data failed to parse"
-                );
-                continue;
-            }
-        };
+    let style = match info.style.as_str() {
+        "call" => OptionStyle::Call,
+        "put" => OptionStyle::Put,
+        _ => {
+            tracing::warn!(
+                snapshot_id = %snapshot_id,
+                underlying = %info.underlying,
+                style = %info.style,
+                "skipping orderbook during restore: unknown option style"
+            );
+            return None;
+        }
+    };
 
-        // Get or create the underlying
-        let underlying = state.manager.get_or_create(&info.underlying);
+    Some(RestoreTarget {
+        underlying: info.underlying.clone(),
+        expiration,
+        strike: info.strike,
+        style,
+        order_count: info.order_count,
+        book,
+    })
+}
 
-        // Parse expiration
-        let exp = match parse_expiration(&info.expiration) {
-            Ok(e) => e,
-            Err(_) => {
-                orderbooks_failed = orderbooks_failed.saturating_add(1);
-                tracing::warn!(
-                    snapshot_id = %snapshot_id,
-                    underlying = %info.underlying,
-                    expiration = %info.expiration,
-                    "skipping orderbook during restore: invalid expiration"
-                );
+/// Empties every order book, ahead of a whole-state restore.
+fn clear_orderbooks(state: &AppState) {
+    for underlying_symbol in state.manager.underlying_symbols() {
+        let Ok(underlying) = state.manager.get(&underlying_symbol) else {
+            continue;
+        };
+        for (exp, _) in underlying.expirations().iter() {
+            let Ok(exp_book) = underlying.get_expiration(&exp) else {
                 continue;
+            };
+            for strike in exp_book.strike_prices() {
+                let Ok(strike_book) = exp_book.get_strike(strike) else {
+                    continue;
+                };
+                for style in [OptionStyle::Call, OptionStyle::Put] {
+                    let book = strike_book.get(style).inner();
+                    if let Err(e) = book.restore_from_snapshot(book.create_snapshot(0)) {
+                        tracing::warn!(
+                            underlying = %underlying_symbol,
+                            strike,
+                            error = %e,
+                            "orderbook not cleared before restore"
+                        );
+                    }
+                }
             }
-        };
+        }
+    }
+}
 
-        // Get or create expiration book
-        let exp_book = underlying.get_or_create_expiration(exp);
+/// Restores snapshot `snapshot_id`. Call with mutations paused.
+///
+/// A books-only snapshot skips an entry that fails to parse or restore and
+/// counts it in `orderbooks_failed`. A whole-state snapshot is restored only
+/// when every entry parses; it then replaces the books and the engine state
+/// together, with price-driven quoting paused.
+fn restore_stored_snapshot(
+    state: &AppState,
+    snapshot_id: String,
+    snapshot: StoredSnapshot,
+) -> RestoreSnapshotResponse {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
 
-        // Get or create strike book
-        let strike_book = exp_book.get_or_create_strike(info.strike);
+    let mut orderbooks_restored: u64 = 0;
+    let mut orders_restored: u64 = 0;
+    let mut orderbooks_failed: u64 = 0;
 
-        // Get option style
-        let style = match info.style.as_str() {
-            "call" => OptionStyle::Call,
-            "put" => OptionStyle::Put,
-            _ => {
-                orderbooks_failed = orderbooks_failed.saturating_add(1);
-                tracing::warn!(
-                    snapshot_id = %snapshot_id,
-                    underlying = %info.underlying,
-                    style = %info.style,
-                    "skipping orderbook during restore: unknown option style"
-                );
-                continue;
-            }
+    let journal_seq = snapshot
+        .engine
+        .as_ref()
+        .map_or(0, |engine| engine.journal_seq);
+    let mut targets = Vec::with_capacity(snapshot.infos.len());
+    for info in &snapshot.infos {
+        match parse_restore_target(&snapshot_id, info) {
+            Some(target) => targets.push(target),
+            None => orderbooks_failed = orderbooks_failed.saturating_add(1),
+        }
+    }
+
+    if snapshot.engine.is_some() && orderbooks_failed > 0 {
+        tracing::warn!(
+            snapshot_id = %snapshot_id,
+            orderbooks_failed,
+            "whole-state restore abandoned: not every orderbook could be parsed"
+        );
+        return RestoreSnapshotResponse {
+            success: false,
+            snapshot_id,
+            orderbooks_restored,
+            orders_restored,
+            orderbooks_failed,
+            state_restored: false,
+            journal_seq,
+            timestamp_ms: now,
         };
+    }
+
+    let _quoting = state.market_maker.pause_quoting();
+    if snapshot.engine.is_some() {
+        clear_orderbooks(state);
+    }
 
-        let option_book = strike_book.get(style);
+    for target in targets {
+        let strike_book = state
+            .manager
+            .get_or_create(&target.underlying)
+            .get_or_create_expiration(target.expiration)
+            .get_or_create_strike(target.strike);
+        let option_book = strike_book.get(target.style);
 
-        // Restore the snapshot
-        match option_book.inner().restore_from_snapshot(snapshot) {
+        match option_book.inner().restore_from_snapshot(target.book) {
             Ok(()) => {
                 orderbooks_restored += 1;
-                orders_restored += info.order_count;
+                orders_restored += target.order_count;
             }
             Err(e) => {
                 orderbooks_failed = orderbooks_failed.saturating_add(1);
                 tracing::warn!(
                     snapshot_id = %snapshot_id,
-                    underlying = %info.underlying,
-                    expiration = %info.expiration,
-                    strike = info.strike,
-                    style = %info.style,
+                    underlying = %target.underlying,
+                    strike = target.strike,
                     error = %e,
                     "orderbook restore failed"
                 );
@@ -750,14 +1056,24 @@ pub async fn restore_snapshot(
         }
     }
 
-    Ok(Json(RestoreSnapshotResponse {
+    let state_restored = match snapshot.engine {
+        Some(engine) => {
+            state.restore_engine_state(engine);
+            true
+        }
+        None => false,
+    };
+
+    RestoreSnapshotResponse {
         success: orderbooks_failed == 0,
         snapshot_id,
         orderbooks_restored,
         orders_restored,
         orderbooks_failed,
+        state_restored,
+        journal_seq,
         timestamp_ms: now,
-    }))
+    }
 }
 
 // ============================================================================
@@ -1091,8 +1407,16 @@ pub async fn get_option_chain(
         .and_then(|sim| sim.get_price(&underlying))
         .map(|p| p as u128);
 
-    // Determine ATM strike (closest to spot price)
-    let atm_strike = spot_price.and_then(|spot| {
+    // The implied forward is fitted over the WHOLE expiration, not just the
+    // filtered strike window, so a narrow filter does not degrade the fit.
+    let forward_price =
+        fit_expiration_forward(&collect_strike_mids(&exp_book)).map(|fit| fit.forward_cents());
+
+    // Determine ATM strike: closest to the implied forward, falling back to
+    // spot when the books do not identify one (a dated underlying's ATM sits
+    // at its forward, not its spot).
+    let atm_reference = forward_price.map(u128::from).or(spot_price);
+    let atm_strike = atm_reference.and_then(|spot| {
         filtered_strikes
             .iter()
             .min_by_key(|&&strike| {
@@ -1129,6 +1453,7 @@ pub async fn get_option_chain(
         underlying: underlying.clone(),
         expiration: exp_str,
         spot_price,
+        forward_price,
         atm_strike,
         chain,
     }))
@@ -1165,6 +1490,146 @@ fn build_option_quote_data(
     }
 }
 
+// ============================================================================
+// Implied Forward / Put-Call Parity
+// ============================================================================
+
+/// Get the implied forward and put-call parity diagnostics for an expiration.
+///
+/// Regresses `C - P` on the strike across every strike whose call AND put
+/// books are two-sided (`C - P = D · (F - K)`), yielding the implied forward
+/// `F`, the discount factor `D`, the implied rate `-ln(D) / T` and the
+/// implied carry against spot `ln(F / S) / T`. Each strike's residual against
+/// the fitted line is reported, and a strike whose absolute residual exceeds
+/// `threshold_bps` of the forward is flagged as a parity violation. The
+/// forward fields are `null` when fewer than two strikes qualify or the fit
+/// is degenerate (e.g. crossed or stale books imply a non-positive discount
+/// factor) — no value is fabricated from spot.
+///
+/// # Errors
+/// Returns [`ApiError::InvalidRequest`] when `threshold_bps` is non-finite or
+/// not positive, and a 404 when the underlying or expiration does not exist.
+#[utoipa::path(
+    get,
+    path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/forward",
+    params(
+        ("underlying" = String, Path, description = "Underlying symbol"),
+        ("expiration" = String, Path, description = "Expiration date"),
+        ("threshold_bps" = Option<f64>, Query, description = "Parity violation threshold in bps of the forward (default 50)")
+    ),
+    responses(
+        (status = 200, description = "Implied forward and parity diagnostics", body = ImpliedForwardResponse),
+        (status = 400, description = "Invalid threshold", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    tag = "Chain"
+)]
+pub async fn get_implied_forward(
+    State(state): State<Arc<AppState>>,
+    Path((underlying, exp_str)): Path<(String, String)>,
+    Query(query): Query<ForwardQuery>,
+) -> Result<Json<ImpliedForwardResponse>, ApiError> {
+    let threshold_bps = query.threshold_bps.unwrap_or(DEFAULT_PARITY_THRESHOLD_BPS);
+    if !threshold_bps.is_finite() || threshold_bps <= 0.0 {
+        return Err(ApiError::InvalidRequest(format!(
+            "threshold_bps must be a positive finite number, got {threshold_bps}"
+        )));
+    }
+
+    let underlying_book = state
+        .manager
+        .get(&underlying)
+        .map_err(|_| ApiError::UnderlyingNotFound(underlying.clone()))?;
+
+    let expiration = find_expiration_by_str(&underlying_book, &exp_str)
+        .ok_or_else(|| ApiError::ExpirationNotFound(exp_str.clone()))?;
+
+    let exp_book = underlying_book
+        .get_expiration(&expiration)
+        .map_err(|_| ApiError::ExpirationNotFound(exp_str.clone()))?;
+
+    let spot_price = state
+        .price_simulator
+        .as_ref()
+        .and_then(|sim| sim.get_price(&underlying));
+
+    let days_to_expiry = days_to_expiry(&expiration, chrono::Utc::now());
+    let years = days_to_expiry as f64 / 365.0;
+
+    let rows = collect_strike_mids(&exp_book);
+    let quotes = parity_quotes(&rows);
+    let fit = fit_implied_forward(&quotes);
+
+    let strikes: Vec<ParityStrikeDiagnostic> = rows
+        .iter()
+        .map(|row| {
+            let quote = quotes.iter().find(|q| q.strike == row.strike);
+            let residual = fit.as_ref().and_then(|fit| {
+                fit.residuals
+                    .iter()
+                    .find(|(strike, _)| *strike == row.strike)
+                    .map(|(_, r)| *r)
+            });
+            let deviation_bps = fit
+                .as_ref()
+                .zip(residual)
+                .map(|(fit, r)| r.abs() / fit.forward * 10_000.0);
+
+            ParityStrikeDiagnostic {
+                strike: row.strike,
+                call_mid: row.call_mid,
+                put_mid: row.put_mid,
+                synthetic_forward: fit
+                    .as_ref()
+                    .zip(quote)
+                    .map(|(fit, q)| fit.synthetic_forward(q).round().max(0.0) as u64),
+                residual_cents: residual.map(|r| r.round() as i64),
+                deviation_bps,
+                violation: deviation_bps.is_some_and(|bps| bps > threshold_bps),
+            }
+        })
+        .collect();
+
+    let violations = strikes.iter().filter(|s| s.violation).count();
+    if violations > 0 {
+        tracing::debug!(
+            underlying = %underlying,
+            expiration = %exp_str,
+            violations,
+            threshold_bps,
+            "put-call parity violations detected"
+        );
+    }
+
+    let forward_price = fit.as_ref().map(ParityFit::forward_cents);
+    let implied_carry = fit
+        .as_ref()
+        .zip(spot_price)
+        .filter(|(_, spot)| *spot > 0)
+        .map(|(fit, spot)| (fit.forward / spot as f64).ln() / years);
+    let basis = forward_price
+        .zip(spot_price)
+        .map(|(forward, spot)| forward as i64 - spot as i64);
+
+    Ok(Json(ImpliedForwardResponse {
+        underlying,
+        expiration: exp_str,
+        spot_price,
+        days_to_expiry,
+        forward_price,
+        discount_factor: fit.as_ref().map(|fit| fit.discount_factor),
+        implied_rate: fit.as_ref().and_then(|fit| fit.implied_rate(years)),
+        implied_carry,
+        basis,
+        rmse_cents: fit.as_ref().map(|fit| fit.rmse),
+        strikes_used: quotes.len(),
+        threshold_bps,
+        violations,
+        strikes,
+        timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+    }))
+}
+
 // ============================================================================
 // Implied Volatility Surface
 // ============================================================================
@@ -1181,6 +1646,12 @@ fn build_option_quote_data(
 /// solver cannot produce a volatility for the observed mid (below intrinsic,
 /// above the no-volatility asymptote, or pinned to the grid ceiling) — no
 /// synthetic values are fabricated.
+///
+/// Where an expiration's books identify an implied forward (put-call parity
+/// over at least two strikes quoted two-sided on both legs), IVs are solved
+/// against that forward on the undiscounted mid (Black-76) and the ATM strike
+/// is the one closest to the forward; the fitted forwards are reported in
+/// `forwards`. Spot is only the fallback.
 #[utoipa::path(
     get,
     path = "/api/v1/underlyings/{underlying}/volatility-surface",
@@ -1197,6 +1668,16 @@ pub async fn get_volatility_surface(
     State(state): State<Arc<AppState>>,
     Path(underlying): Path<String>,
 ) -> Result<Json<VolatilitySurfaceResponse>, ApiError> {
+    volatility_surface(&state, underlying).await.map(Json)
+}
+
+/// Computes the volatility surface of `underlying`, or returns the cached
+/// one while the spot is unchanged and the entry is fresh. Shared by the
+/// surface endpoint and the scheduled surface history.
+pub async fn volatility_surface(
+    state: &AppState,
+    underlying: String,
+) -> Result<VolatilitySurfaceResponse, ApiError> {
     use std::collections::HashMap;
 
     let underlying_book = state
@@ -1219,7 +1700,7 @@ pub async fn get_volatility_surface(
         && cached.spot == spot_price
         && now_ms.saturating_sub(cached.response.timestamp_ms) < SURFACE_CACHE_TTL_MS
     {
-        return Ok(Json(cached.response.clone()));
+        return Ok(cached.response.clone());
     }
 
     // Phase 1 (cheap, on the async thread): walk the books and collect the
@@ -1228,6 +1709,7 @@ pub async fn get_volatility_surface(
     let expirations_map = underlying_book.expirations();
     let mut all_strikes: std::collections::BTreeSet<u64> = std::collections::BTreeSet::new();
     let mut collected: Vec<SurfaceExpirationInputs> = Vec::new();
+    let mut forwards: HashMap<String, u64> = HashMap::new();
 
     let now = chrono::Utc::now();
 
@@ -1243,38 +1725,35 @@ pub async fn get_volatility_surface(
             Err(_) => continue,
         };
 
-        // Calculate days to expiry
-        let days_to_expiry = match exp.get_date() {
-            Ok(d) => (d - now).num_days().max(1) as u64,
-            Err(_) => 1,
-        };
+        let days_to_expiry = days_to_expiry(exp, now);
 
         let strikes = exp_book.strike_prices();
+        all_strikes.extend(strikes.iter().copied());
 
-        // Find ATM strike for this expiration
-        let atm_strike =
-            spot_price.and_then(|spot| strikes.iter().min_by_key(|&&s| s.abs_diff(spot)).copied());
-
-        let mut rows: Vec<SurfaceStrikeMids> = Vec::with_capacity(strikes.len());
-        for strike in &strikes {
-            all_strikes.insert(*strike);
-
-            let strike_book = match exp_book.get_strike(*strike) {
-                Ok(book) => book,
-                Err(_) => continue,
-            };
+        let rows = collect_strike_mids(&exp_book);
 
-            rows.push(SurfaceStrikeMids {
-                strike: *strike,
-                call_mid: calculate_mid_price(&strike_book.call_quote()),
-                put_mid: calculate_mid_price(&strike_book.put_quote()),
-            });
+        // The implied forward (put-call parity over the two-sided books) is
+        // the reference for both the ATM strike and the IV solve; spot is only
+        // the fallback when the books cannot identify a forward.
+        let forward = fit_expiration_forward(&rows);
+        if let Some(ref fit) = forward {
+            forwards.insert(exp_str.clone(), fit.forward_cents());
         }
+        let reference = forward
+            .as_ref()
+            .map(ParityFit::forward_cents)
+            .or(spot_price);
+
+        // Find ATM strike for this expiration
+        let atm_strike =
+            reference.and_then(|r| strikes.iter().min_by_key(|&&s| s.abs_diff(r)).copied());
 
         collected.push(SurfaceExpirationInputs {
             exp_str,
             days_to_expiry,
             atm_strike,
+            reference,
+            discount_factor: forward.map_or(1.0, |fit| fit.discount_factor),
             rows,
         });
     }
@@ -1292,11 +1771,19 @@ pub async fn get_volatility_surface(
             exp_str,
             days_to_expiry,
             atm_strike,
+            reference,
+            discount_factor,
             rows,
         } in collected
         {
             let mut exp_surface: HashMap<u64, StrikeIV> = HashMap::new();
 
+            // Against a forward the solver's zero-rate Black-Scholes is
+            // Black-76 on the undiscounted premium, so each observed mid is
+            // rolled forward by the implied discount factor first (a no-op
+            // on the spot fallback, where the factor is 1.0).
+            let undiscount = |mid: u128| (mid as f64 / discount_factor).round() as u128;
+
             for SurfaceStrikeMids {
                 strike,
                 call_mid,
@@ -1305,9 +1792,9 @@ pub async fn get_volatility_surface(
             {
                 let call_iv = call_mid.and_then(|mid| {
                     derive_iv(
-                        mid,
+                        undiscount(mid),
                         strike,
-                        spot_price,
+                        reference,
                         days_to_expiry,
                         OptionStyle::Call,
                         &symbol_for_iv,
@@ -1315,9 +1802,9 @@ pub async fn get_volatility_surface(
                 });
                 let put_iv = put_mid.and_then(|mid| {
                     derive_iv(
-                        mid,
+                        undiscount(mid),
                         strike,
-                        spot_price,
+                        reference,
                         days_to_expiry,
                         OptionStyle::Put,
                         &symbol_for_iv,
@@ -1355,6 +1842,12 @@ pub async fn get_volatility_surface(
     let mut atm_term_structure = atm_term_structure;
     atm_term_structure.sort_by_key(|p| p.days);
 
+    // The front-month ATM vol is the market maker's marking vol, so surface
+    // moves show up as vega in its P&L attribution.
+    if let Some(front) = atm_term_structure.first() {
+        state.market_maker.set_mark_vol(&underlying, front.iv);
+    }
+
     let timestamp_ms = chrono::Utc::now().timestamp_millis() as u64;
 
     let response = VolatilitySurfaceResponse {
@@ -1363,6 +1856,7 @@ pub async fn get_volatility_surface(
         timestamp_ms,
         expirations,
         strikes: all_strikes.into_iter().collect(),
+        forwards,
         surface,
         atm_term_structure,
     };
@@ -1374,7 +1868,117 @@ pub async fn get_volatility_surface(
         },
     );
 
-    Ok(Json(response))
+    Ok(response)
+}
+
+/// Computes and stores the volatility surface of every underlying. Returns
+/// the number of surfaces stored; a surface that fails to compute or store
+/// is logged and skipped.
+pub async fn record_volatility_surfaces(state: &AppState) -> usize {
+    let Some(store) = &state.surface_store else {
+        return 0;
+    };
+    let mut stored = 0;
+    for underlying in state.manager.underlying_symbols() {
+        let surface = match volatility_surface(state, underlying.clone()).await {
+            Ok(surface) => surface,
+            Err(e) => {
+                tracing::warn!(underlying = %underlying, error = %e, "volatility surface not computed");
+                continue;
+            }
+        };
+        match store.record(&surface).await {
+            Ok(true) => stored += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(underlying = %underlying, error = %e, "volatility surface not stored");
+            }
+        }
+    }
+    stored
+}
+
+/// Most `at` times one surface history request may ask for.
+const MAX_SURFACE_HISTORY_TIMES: usize = 50;
+
+/// Get the stored volatility surface history of an underlying.
+///
+/// Returns, for each time in `at`, the latest surface stored at or before it
+/// (times with none are skipped), and the ATM IV, 25-delta risk reversal and
+/// 25-delta butterfly per expiration of every surface stored between `from`
+/// and `to`, oldest first. When more than `limit` surfaces match, the series
+/// holds the newest, or the oldest when both `from` and `to` are set.
+/// Surfaces are stored every `[surface_history] interval_seconds`; requires a
+/// PostgreSQL database connection.
+#[utoipa::path(
+    get,
+    path = "/api/v1/underlyings/{underlying}/volatility-surface/history",
+    params(
+        ("underlying" = String, Path, description = "Underlying symbol"),
+        ("from" = Option<u64>, Query, description = "Start of the series in milliseconds (optional)"),
+        ("to" = Option<u64>, Query, description = "End of the series in milliseconds (optional)"),
+        ("at" = Option<String>, Query, description = "Comma-separated times in milliseconds to return full surfaces at (optional)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of series points (default 500)")
+    ),
+    responses(
+        (status = 200, description = "Volatility surface history", body = VolatilitySurfaceHistoryResponse),
+        (status = 400, description = "Invalid times or no database connected", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse)
+    ),
+    tag = "Volatility"
+)]
+#[tracing::instrument(skip_all, fields(underlying = %underlying))]
+pub async fn get_volatility_surface_history(
+    State(state): State<Arc<AppState>>,
+    Path(underlying): Path<String>,
+    Query(query): Query<VolatilitySurfaceHistoryQuery>,
+) -> Result<Json<VolatilitySurfaceHistoryResponse>, ApiError> {
+    let at: Vec<u64> = query
+        .at
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|time| !time.is_empty())
+        .map(|time| {
+            time.parse().map_err(|_| {
+                ApiError::InvalidRequest(format!("invalid surface history time: {time}"))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    if at.len() > MAX_SURFACE_HISTORY_TIMES {
+        return Err(ApiError::InvalidRequest(format!(
+            "at most {MAX_SURFACE_HISTORY_TIMES} surface history times per request"
+        )));
+    }
+    let Some(store) = &state.surface_store else {
+        return Err(ApiError::InvalidRequest(
+            "volatility surface history requires a PostgreSQL database connection".to_string(),
+        ));
+    };
+
+    let mut surfaces = Vec::with_capacity(at.len());
+    for time in at {
+        if let Some(surface) = store
+            .load_at(&underlying, time)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+        {
+            surfaces.push(surface);
+        }
+    }
+    let limit = query.limit.unwrap_or(500).min(5000);
+    let anchor_at_from = query.from.is_some() && query.to.is_some();
+    let series = store
+        .load_series(&underlying, query.from, query.to, anchor_at_from, limit)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(VolatilitySurfaceHistoryResponse {
+        underlying,
+        surfaces,
+        series,
+    }))
 }
 
 /// Observed order-book mids for one strike, collected before the IV sweep.
@@ -1393,12 +1997,69 @@ struct SurfaceExpirationInputs {
     exp_str: String,
     /// Days until expiration (minimum 1).
     days_to_expiry: u64,
-    /// The strike closest to spot, when a spot price is known.
+    /// The strike closest to the IV reference price, when one is known.
     atm_strike: Option<u64>,
+    /// IV reference price in cents: the implied forward, else spot.
+    reference: Option<u64>,
+    /// Implied discount factor to expiry (1.0 on the spot fallback).
+    discount_factor: f64,
     /// Observed mids per strike.
     rows: Vec<SurfaceStrikeMids>,
 }
 
+/// Days until `expiration` from `now`, floored at 1 (an expired or
+/// unparseable date counts as one day so tenors stay positive).
+fn days_to_expiry(expiration: &ExpirationDate, now: chrono::DateTime<chrono::Utc>) -> u64 {
+    match expiration.get_date() {
+        Ok(d) => (d - now).num_days().max(1) as u64,
+        Err(_) => 1,
+    }
+}
+
+/// Collects the two-sided call and put mids for every strike of an
+/// expiration, ordered by strike.
+fn collect_strike_mids(exp_book: &ExpirationOrderBook) -> Vec<SurfaceStrikeMids> {
+    let mut strikes = exp_book.strike_prices();
+    strikes.sort_unstable();
+
+    strikes
+        .into_iter()
+        .filter_map(|strike| {
+            let strike_book = exp_book.get_strike(strike).ok()?;
+            Some(SurfaceStrikeMids {
+                strike,
+                call_mid: calculate_mid_price(&strike_book.call_quote()),
+                put_mid: calculate_mid_price(&strike_book.put_quote()),
+            })
+        })
+        .collect()
+}
+
+/// The put-call parity regression inputs: strikes where BOTH legs carry a
+/// two-sided mid.
+fn parity_quotes(rows: &[SurfaceStrikeMids]) -> Vec<ParityQuote> {
+    rows.iter()
+        .filter_map(|row| {
+            Some(ParityQuote {
+                strike: row.strike,
+                call_mid: row.call_mid?,
+                put_mid: row.put_mid?,
+            })
+        })
+        .collect()
+}
+
+/// Fits the implied forward of one expiration from its strike mids, or
+/// `None` when fewer than two strikes are two-sided on both legs or the fit
+/// is degenerate.
+fn fit_expiration_forward(rows: &[SurfaceStrikeMids]) -> Option<ParityFit> {
+    fit_implied_forward(&parity_quotes(rows))
+}
+
+/// Default parity deviation threshold, in basis points of the implied
+/// forward, for flagging a strike as a violation.
+const DEFAULT_PARITY_THRESHOLD_BPS: f64 = 50.0;
+
 /// Calculates the two-sided mid price of a quote for IV derivation.
 ///
 /// Requires BOTH sides (issue #125): a lone resting bid or ask is not a mid —
@@ -1434,8 +2095,10 @@ const IV_GRID_CEILING: f64 = 0.999;
 /// All monetary legs are passed in cents; Black-Scholes is homogeneous of
 /// degree one in (spot, strike, price), so the derived IV is identical to a
 /// dollar-denominated computation. The solver is a 0.1%-step grid search, so
-/// the derivable range is `[0.001, 0.999)`. Returns `None` when the spot
-/// price is unknown or an input cannot form a `Positive`; market-level
+/// the derivable range is `[0.001, 0.999)`. `spot_cents` is the reference
+/// price the option is solved against — the implied forward when one is
+/// known (with an undiscounted mid), else spot. Returns `None` when the
+/// reference price is unknown or an input cannot form a `Positive`; market-level
 /// omissions — a mid at/above the no-volatility asymptote, a mid the solver
 /// cannot match (e.g. below intrinsic), or a result pinned to the grid
 /// ceiling — are additionally logged at DEBUG. Callers must omit the field
@@ -1675,6 +2338,7 @@ pub async fn get_option_book(
 )]
 pub async fn add_order(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path((underlying, exp_str, strike, style)): Path<(String, String, u64, String)>,
     Json(body): Json<AddOrderRequest>,
 ) -> Result<Json<AddOrderResponse>, ApiError> {
@@ -1702,18 +2366,54 @@ pub async fn add_order(
             // pricelevel 0.8 sources). Passing seconds would make every GTD
             // order expire immediately. Locked by
             // `test_gtd_expiry_unit_is_milliseconds`.
-            let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+            let now_ms = clock::now_ms();
             let expire_ms = parse_gtd_expire_at(body.expire_at.as_deref(), now_ms)?;
-            TimeInForce::Gtd(expire_ms)
+            TimeInForce::Gtd(book_gtd_deadline(expire_ms, now_ms))
         }
     };
 
+    let style_char = match option_style {
+        OptionStyle::Call => "C",
+        OptionStyle::Put => "P",
+    };
+    // Store the canonical lowercase style and the YYYYMMDD-formatted expiration
+    // so the cancel-all / bulk-cancel book lookups (which match on these fields)
+    // resolve the order regardless of the inbound path's casing or date format.
+    let canonical_style = match option_style {
+        OptionStyle::Call => "call",
+        OptionStyle::Put => "put",
+    };
+    let exp_formatted = format_expiration(&expiration);
+    let symbol = format!("{}-{}-{}-{}", underlying, exp_formatted, strike, style_char);
+
+    check_tick_size(&state, &underlying, body.price)?;
+    check_order_size(&state, &underlying, body.quantity)?;
+    // Held until the order is tracked, so the margin and buying-power checks
+    // see every earlier order of the account.
+    let _account_guard = state.account_guard(claims.account()).await;
+    check_order_margin(
+        &state,
+        claims.account(),
+        order_risk_position(&state, &symbol, &underlying, body.side, body.quantity),
+        None,
+    )?;
+    check_buying_power(
+        &state,
+        claims.account(),
+        body.side,
+        state
+            .market_maker
+            .contract_spec(&underlying)
+            .scale(body.price, body.quantity),
+        None,
+    )?;
+
     let underlying_book = state.manager.get_or_create(&underlying);
     let exp_book = underlying_book.get_or_create_expiration(expiration);
     let strike_book = exp_book.get_or_create_strike(strike);
     let option_book = strike_book.get(option_style);
 
-    let order_id = OrderId::new();
+    let order_id = state.market_maker.order_ids().next_id();
 
     // Use the fill-capturing TIF variant so the tracked `OrderInfo` reflects the
     // real fill/remaining state (mirroring the bulk submit path in
@@ -1774,19 +2474,6 @@ pub async fn add_order(
     // Track the order in AppState so the single-order path is uniformly visible
     // to GET /orders, GET /orders/{id}, cancel-all, and bulk-cancel — identical
     // to the bulk submit path.
-    let style_char = match option_style {
-        OptionStyle::Call => "C",
-        OptionStyle::Put => "P",
-    };
-    // Store the canonical lowercase style and the YYYYMMDD-formatted expiration
-    // so the cancel-all / bulk-cancel book lookups (which match on these fields)
-    // resolve the order regardless of the inbound path's casing or date format.
-    let canonical_style = match option_style {
-        OptionStyle::Call => "call",
-        OptionStyle::Put => "put",
-    };
-    let exp_formatted = format_expiration(&expiration);
-    let symbol = format!("{}-{}-{}-{}", underlying, exp_formatted, strike, style_char);
     let order_side = match side {
         Side::Buy => OrderSide::Buy,
         Side::Sell => OrderSide::Sell,
@@ -1797,9 +2484,10 @@ pub async fn add_order(
         ApiTimeInForce::Fok => OrderTimeInForce::Fok,
         ApiTimeInForce::Gtd => OrderTimeInForce::Gtd,
     };
-    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let now = clock::now_ms();
     let order_info = OrderInfo {
         order_id: order_id.to_string(),
+        account: claims.account().to_string(),
         symbol,
         underlying: underlying.clone(),
         expiration: exp_formatted,
@@ -1838,7 +2526,14 @@ pub async fn add_order(
                 maker_order_id: t.maker_order_id().to_string(),
             });
         }
-        record_fills(&state, &record_symbol, &underlying, body.side, &executed);
+        record_fills(
+            &state,
+            &record_symbol,
+            &underlying,
+            claims.account(),
+            body.side,
+            &executed,
+        );
     }
 
     // Publish orderbook deltas so WS `orderbook` subscribers observe this
@@ -1884,7 +2579,32 @@ pub async fn add_order(
     }))
 }
 
+/// Account owning the tracked order `order_id`, which only that account or
+/// an Admin may change.
+///
+/// # Errors
+/// Returns [`ApiError::NotFound`] when the order is not tracked and
+/// [`ApiError::Forbidden`] when it belongs to another account and the caller
+/// is not an Admin.
+fn order_owner(state: &AppState, claims: &Claims, order_id: &str) -> Result<String, ApiError> {
+    let account = state
+        .orders
+        .get(order_id)
+        .map(|order| order.account.clone())
+        .ok_or_else(|| ApiError::NotFound(format!("Order not found: {}", order_id)))?;
+    if account != claims.account() && !claims.has_permission(Permission::Admin) {
+        return Err(ApiError::Forbidden(format!(
+            "order {} belongs to another account",
+            order_id
+        )));
+    }
+    Ok(account)
+}
+
 /// Cancel order from option book.
+///
+/// Only the account that placed the order may cancel it. An Admin may cancel
+/// any resting order, including market-maker quotes.
 #[utoipa::path(
     delete,
     path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/orders/{order_id}",
@@ -1897,6 +2617,7 @@ pub async fn add_order(
     ),
     responses(
         (status = 200, description = "Order canceled", body = CancelOrderResponse),
+        (status = 403, description = "Order belongs to another account", body = ErrorResponse),
         (status = 404, description = "Not found", body = ErrorResponse)
     ),
     tag = "Options"
@@ -1907,6 +2628,7 @@ pub async fn add_order(
 )]
 pub async fn cancel_order(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path((underlying, exp_str, strike, style, order_id_str)): Path<(
         String,
         String,
@@ -1939,6 +2661,9 @@ pub async fn cancel_order(
     let order_id: OrderId = order_id_str
         .parse()
         .map_err(|_| ApiError::InvalidRequest(format!("Invalid order ID: {}", order_id_str)))?;
+    if !claims.has_permission(Permission::Admin) {
+        order_owner(&state, &claims, &order_id_str)?;
+    }
 
     // Capture the resting order's side/price BEFORE cancelling so an orderbook
     // delta can be published for the affected level afterward (issue #129). This
@@ -1964,7 +2689,7 @@ pub async fn cancel_order(
         entry.filled_quantity = entry.original_quantity;
         entry.remaining_quantity = 0;
         entry.status = OrderStatus::Filled;
-        entry.updated_at_ms = chrono::Utc::now().timestamp_millis() as u64;
+        entry.updated_at_ms = clock::now_ms();
     }
 
     // A confirmed cancel removed the order's resting quantity; publish the
@@ -1991,9 +2716,12 @@ pub async fn cancel_order(
 
 /// Modify an existing order's price and/or quantity.
 ///
-/// This implements order modification using cancel-and-replace semantics.
-/// The order is canceled and a new order is placed with the updated parameters.
-/// This means the order will always lose time priority.
+/// The order is amended in place and keeps its id. A quantity decrease at the
+/// same price keeps time priority; a quantity increase or a price change moves
+/// the order to the back of its (new) price level. A new price that crosses
+/// the book trades immediately, with the order as the taker, and only the
+/// remainder rests. A rejected modification leaves the original order resting
+/// unchanged.
 #[utoipa::path(
     patch,
     path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/orders/{order_id}",
@@ -2008,6 +2736,7 @@ pub async fn cancel_order(
     responses(
         (status = 200, description = "Order modification result", body = ModifyOrderResponse),
         (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 403, description = "Order belongs to another account", body = ErrorResponse),
         (status = 404, description = "Order not found", body = ErrorResponse)
     ),
     tag = "Options"
@@ -2018,6 +2747,7 @@ pub async fn cancel_order(
 )]
 pub async fn modify_order(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path((underlying, exp_str, strike, style, order_id_str)): Path<(
         String,
         String,
@@ -2058,6 +2788,8 @@ pub async fn modify_order(
     let order_id: OrderId = order_id_str
         .parse()
         .map_err(|_| ApiError::InvalidRequest(format!("Invalid order ID: {}", order_id_str)))?;
+    // The amended order is margined to its owner.
+    let margin_account = order_owner(&state, &claims, &order_id_str)?;
 
     // Get the existing order from the order book
     let existing_order = option_book
@@ -2074,10 +2806,8 @@ pub async fn modify_order(
     let new_price = body.price.unwrap_or(current_price.as_u128());
     let new_quantity = body.quantity.unwrap_or(current_quantity.as_u64());
 
-    // Validate the resolved values BEFORE touching the book. modify is a
-    // cancel-and-replace, so it destroys the resting order first; rejecting a
-    // zero price or quantity here (400) preserves the existing order instead of
-    // cancelling it and then failing to place an invalid replacement.
+    // Validate the resolved values BEFORE touching the book: a zero price or
+    // quantity is a bad request (400), not a rejected amend.
     if new_price == 0 {
         return Err(ApiError::InvalidRequest(
             "new price must be greater than zero".to_string(),
@@ -2088,125 +2818,168 @@ pub async fn modify_order(
             "new quantity must be greater than zero".to_string(),
         ));
     }
-
-    // Cancel the existing order
-    let canceled = option_book
-        .cancel_order(order_id)
-        .map_err(|e| ApiError::OrderBook(e.to_string()))?;
-
-    if !canceled {
-        return Ok(Json(ModifyOrderResponse {
-            order_id: order_id_str,
-            status: ModifyOrderStatus::Rejected,
-            new_price: None,
-            new_quantity: None,
-            priority_changed: false,
-            message: "Failed to cancel existing order for modification".to_string(),
-        }));
+    if body.price.is_some() {
+        check_tick_size(&state, &underlying, new_price)?;
+    }
+    if body.quantity.is_some() {
+        check_order_size(&state, &underlying, new_quantity)?;
     }
 
-    // The original order has now been cancelled in the book, so drop its tracking
-    // entry unconditionally — it no longer exists regardless of whether it was
-    // tracked before (single-order, bulk, or untracked). Tracking is then
-    // reconciled below by the outcome of the replacement, so neither the
-    // replace-succeeded nor the cancel-ok/replace-fail path can leave a stale
-    // `Active` entry behind.
-    state.orders.remove(&order_id_str);
-
-    // Publish the delta for the OLD level (its quantity dropped by the cancelled
-    // order) to WS `orderbook` subscribers (issue #129). The NEW level is
-    // published after the replacement is placed below.
-    publish_level_delta(&state, option_book, side, current_price.as_u128());
-
-    // Create a new order with the updated parameters
-    let new_order_id = OrderId::new();
-
-    match option_book.add_limit_order(new_order_id, side, new_price, new_quantity) {
-        Ok(()) => {
-            // The replacement rests as a fresh order; track it under the new id
-            // using the authoritative path/book data so it is uniformly visible
-            // and cancellable. `add_limit_order` places a GTC order, so the
-            // tracked time-in-force reflects that book reality.
-            let order_side = match side {
-                Side::Buy => OrderSide::Buy,
-                Side::Sell => OrderSide::Sell,
-            };
-            let style_char = match option_style {
-                OptionStyle::Call => "C",
-                OptionStyle::Put => "P",
-            };
-            let canonical_style = match option_style {
-                OptionStyle::Call => "call",
-                OptionStyle::Put => "put",
-            };
-            // `exp_str` matched `find_expiration_by_str` above, so it is already
-            // the canonical YYYYMMDD form used by the cancel book lookups.
-            let symbol = format!("{}-{}-{}-{}", underlying, exp_str, strike, style_char);
-            let now = chrono::Utc::now().timestamp_millis() as u64;
-            let order_info = OrderInfo {
-                order_id: new_order_id.to_string(),
-                symbol,
-                underlying: underlying.clone(),
-                expiration: exp_str.clone(),
-                strike,
-                style: canonical_style.to_string(),
-                side: order_side,
-                price: new_price,
-                original_quantity: new_quantity,
-                remaining_quantity: new_quantity,
-                filled_quantity: 0,
-                status: OrderStatus::Active,
-                time_in_force: OrderTimeInForce::Gtc,
-                created_at_ms: now,
-                updated_at_ms: now,
-                fills: vec![],
-            };
-            state.orders.insert(new_order_id.to_string(), order_info);
-
-            // Publish the delta for the NEW level the replacement rests on
-            // (issue #129). Cancel-and-replace keeps the same side; a replacement
-            // that crosses and consumes opposite-side maker levels does NOT emit
-            // those deltas here (modify uses the non-fill-capturing add path and
-            // likewise does not record such fills) — a documented limitation.
-            publish_level_delta(&state, option_book, side, new_price);
-
-            tracing::debug!(
-                old_order_id = %order_id_str,
-                new_order_id = %new_order_id,
-                new_price = new_price,
-                new_quantity = new_quantity,
-                "order modified (cancel-and-replace)"
-            );
-
-            Ok(Json(ModifyOrderResponse {
-                order_id: new_order_id.to_string(),
-                status: ModifyOrderStatus::Modified,
-                new_price: Some(new_price),
-                new_quantity: Some(new_quantity),
-                priority_changed: true, // Cancel-and-replace always loses priority
-                message: "Order modified successfully (cancel-and-replace)".to_string(),
-            }))
+    // The amended order is margined in place of the original, so a modify
+    // that shrinks an order is never blocked.
+    let _account_guard = state.account_guard(&margin_account).await;
+    let margin_symbol = format!(
+        "{}-{}-{}-{}",
+        underlying,
+        exp_str,
+        strike,
+        match option_style {
+            OptionStyle::Call => "C",
+            OptionStyle::Put => "P",
+        }
+    );
+    let margin_side = match side {
+        Side::Buy => OrderSide::Buy,
+        Side::Sell => OrderSide::Sell,
+    };
+    check_order_margin(
+        &state,
+        &margin_account,
+        order_risk_position(
+            &state,
+            &margin_symbol,
+            &underlying,
+            margin_side,
+            new_quantity,
+        ),
+        Some(&order_id_str),
+    )?;
+    check_buying_power(
+        &state,
+        &margin_account,
+        margin_side,
+        state
+            .market_maker
+            .contract_spec(&underlying)
+            .scale(new_price, new_quantity),
+        Some(&order_id_str),
+    )?;
+
+    // Amend in place: the order keeps its id, and keeps its queue position
+    // when only its quantity decreases. A rejected amend leaves the original
+    // order resting untouched.
+    let amendment = match amend_order(option_book, order_id, new_price, new_quantity) {
+        Ok(amendment) => amendment,
+        Err(AmendError::NotFound(_)) => {
+            return Err(ApiError::NotFound(format!(
+                "Order not found: {}",
+                order_id_str
+            )));
         }
         Err(e) => {
-            // Cancel succeeded but the replacement failed: nothing is live. The
-            // old entry was already removed above, so no stale `Active` entry
-            // remains, and `new_order_id` is never tracked because no order was
-            // placed under it.
             tracing::warn!(
                 order_id = %order_id_str,
                 error = %e,
-                "order modify: original cancelled but replacement failed to place"
+                "order modify rejected; original order left in place"
             );
-            Ok(Json(ModifyOrderResponse {
+            return Ok(Json(ModifyOrderResponse {
                 order_id: order_id_str,
                 status: ModifyOrderStatus::Rejected,
                 new_price: None,
                 new_quantity: None,
                 priority_changed: false,
-                message: format!("Order canceled but failed to place replacement: {}", e),
-            }))
+                message: format!("Order modification rejected: {}", e),
+            }));
         }
+    };
+
+    // A reprice that crosses trades like a fresh order with the amended order
+    // as the taker; only the remainder rests.
+    let executed: Vec<ExecutedFill> = amendment
+        .trade_result
+        .as_ref()
+        .into_iter()
+        .flat_map(|result| result.match_result.trades().as_vec())
+        .map(|t| ExecutedFill {
+            price: t.price().as_u128(),
+            quantity: t.quantity().as_u64(),
+            timestamp_ms: t.timestamp().as_u64(),
+            trade_id: t.trade_id().to_string(),
+            taker_order_id: t.taker_order_id().to_string(),
+            maker_order_id: t.maker_order_id().to_string(),
+        })
+        .collect();
+    let filled_quantity = amendment.filled_quantity();
+    let resting_quantity = amendment.resting_quantity();
+
+    // Reconcile tracking in place: fills already recorded stay with the order,
+    // so the original quantity becomes what was filled before the amend plus
+    // the amended quantity.
+    if let Some(mut entry) = state.orders.get_mut(&order_id_str) {
+        entry.price = new_price;
+        entry.original_quantity = entry.filled_quantity.saturating_add(new_quantity);
+        entry.filled_quantity = entry.filled_quantity.saturating_add(filled_quantity);
+        entry.remaining_quantity = resting_quantity;
+        entry.fills.extend(executed.iter().map(|f| OrderFillInfo {
+            price: f.price,
+            quantity: f.quantity,
+            timestamp_ms: f.timestamp_ms,
+        }));
+        entry.status = if resting_quantity == 0 {
+            OrderStatus::Filled
+        } else if entry.filled_quantity > 0 {
+            OrderStatus::Partial
+        } else {
+            OrderStatus::Active
+        };
+        entry.updated_at_ms = clock::now_ms();
+    }
+    if !executed.is_empty() {
+        record_fills(
+            &state,
+            &margin_symbol,
+            &underlying,
+            &margin_account,
+            margin_side,
+            &executed,
+        );
+        let consumed: Vec<u128> = executed.iter().map(|f| f.price).collect();
+        publish_consumed_maker_deltas(&state, option_book, side, &consumed);
     }
+
+    // Publish the old and new levels to WS `orderbook` subscribers (issue
+    // #129); they are the same level when only the quantity changed.
+    publish_level_delta(&state, option_book, side, amendment.old_price);
+    if amendment.new_price != amendment.old_price {
+        publish_level_delta(&state, option_book, side, amendment.new_price);
+    }
+
+    tracing::debug!(
+        order_id = %order_id_str,
+        new_price = new_price,
+        new_quantity = new_quantity,
+        priority_kept = amendment.priority_kept,
+        filled_quantity,
+        "order amended in place"
+    );
+
+    Ok(Json(ModifyOrderResponse {
+        order_id: order_id_str,
+        status: ModifyOrderStatus::Modified,
+        new_price: Some(new_price),
+        new_quantity: Some(new_quantity),
+        priority_changed: !amendment.priority_kept,
+        message: if filled_quantity > 0 {
+            format!(
+                "Order repriced: {} filled on crossing, {} resting",
+                filled_quantity, resting_quantity
+            )
+        } else if amendment.priority_kept {
+            "Order amended in place (priority kept)".to_string()
+        } else {
+            "Order amended in place (priority lost)".to_string()
+        },
+    }))
 }
 
 /// Get option quote.
@@ -2257,6 +3030,81 @@ pub async fn get_option_quote(
     Ok(Json(quote_to_response(&quote)))
 }
 
+/// Get instrument reference data.
+///
+/// Returns the contract specification of an option: its contract multiplier,
+/// lot size, maximum order size and tick-size bands.
+#[utoipa::path(
+    get,
+    path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/spec",
+    params(
+        ("underlying" = String, Path, description = "Underlying symbol"),
+        ("expiration" = String, Path, description = "Expiration date"),
+        ("strike" = u64, Path, description = "Strike price"),
+        ("style" = String, Path, description = "Option style: 'call' or 'put'")
+    ),
+    responses(
+        (status = 200, description = "Instrument reference data", body = InstrumentSpecResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    tag = "Options"
+)]
+#[tracing::instrument(
+    skip_all,
+    fields(underlying = %underlying, expiration = %exp_str, strike = strike, style = %style)
+)]
+pub async fn get_instrument_spec(
+    State(state): State<Arc<AppState>>,
+    Path((underlying, exp_str, strike, style)): Path<(String, String, u64, String)>,
+) -> Result<Json<InstrumentSpecResponse>, ApiError> {
+    let option_style = parse_option_style(&style)?;
+
+    let underlying_book = state
+        .manager
+        .get(&underlying)
+        .map_err(|_| ApiError::UnderlyingNotFound(underlying.clone()))?;
+
+    let expiration = find_expiration_by_str(&underlying_book, &exp_str)
+        .ok_or_else(|| ApiError::ExpirationNotFound(exp_str.clone()))?;
+
+    let exp_book = underlying_book
+        .get_expiration(&expiration)
+        .map_err(|_| ApiError::ExpirationNotFound(exp_str))?;
+
+    exp_book
+        .get_strike(strike)
+        .map_err(|_| ApiError::StrikeNotFound(strike))?;
+
+    let expiration_key = format_expiration(&expiration);
+    let (style_char, style) = match option_style {
+        OptionStyle::Call => ("C", crate::models::OptionStyle::Call),
+        OptionStyle::Put => ("P", crate::models::OptionStyle::Put),
+    };
+    let spec = state.market_maker.contract_spec(&underlying);
+    let tick_bands = state
+        .market_maker
+        .tick_table(&underlying)
+        .bands_at(state.market_maker.get_price(&underlying))
+        .into_iter()
+        .map(|band| TickBandInfo {
+            below: band.below_cents,
+            tick: band.tick_cents,
+        })
+        .collect();
+
+    Ok(Json(InstrumentSpecResponse {
+        symbol: format!("{underlying}-{expiration_key}-{strike}-{style_char}"),
+        underlying,
+        expiration: expiration_key,
+        strike,
+        style,
+        contract_multiplier: spec.multiplier,
+        min_lot_size: spec.min_lot_size,
+        max_order_size: spec.max_order_size,
+        tick_bands,
+    }))
+}
+
 // ============================================================================
 // Greeks Calculation
 // ============================================================================
@@ -2702,6 +3550,7 @@ pub async fn get_orderbook_metrics(
 )]
 pub async fn submit_market_order(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path((underlying, exp_str, strike, style)): Path<(String, String, u64, String)>,
     Json(body): Json<MarketOrderRequest>,
 ) -> Result<Json<MarketOrderResponse>, ApiError> {
@@ -2714,13 +3563,68 @@ pub async fn submit_market_order(
     let expiration = parse_expiration(&exp_str)?;
     let option_style = parse_option_style(&style)?;
     let side = order_side_to_side(body.side);
+    check_order_size(&state, &underlying, body.quantity)?;
+
+    let margin_symbol = format!(
+        "{}-{}-{}-{}",
+        underlying,
+        format_expiration(&expiration),
+        strike,
+        match option_style {
+            OptionStyle::Call => "C",
+            OptionStyle::Put => "P",
+        }
+    );
+    // Held until the fills are booked, so the margin check sees every
+    // earlier order of the account.
+    let _account_guard = state.account_guard(claims.account()).await;
+    check_order_margin(
+        &state,
+        claims.account(),
+        order_risk_position(
+            &state,
+            &margin_symbol,
+            &underlying,
+            body.side,
+            body.quantity,
+        ),
+        None,
+    )?;
 
     let underlying_book = state.manager.get_or_create(&underlying);
     let exp_book = underlying_book.get_or_create_expiration(expiration);
     let strike_book = exp_book.get_or_create_strike(strike);
     let option_book = strike_book.get(option_style);
 
-    let order_id = OrderId::new();
+    // A market buy is charged whatever the asks it sweeps cost.
+    if body.side == OrderSide::Buy && buying_power_enforced(&state) {
+        let asks = option_book
+            .inner()
+            .create_snapshot(usize::MAX)
+            .asks
+            .iter()
+            .map(|level| {
+                (
+                    level.price().as_u128(),
+                    level
+                        .visible_quantity()
+                        .as_u64()
+                        .saturating_add(level.hidden_quantity().as_u64()),
+                )
+            })
+            .collect();
+        check_buying_power(
+            &state,
+            claims.account(),
+            body.side,
+            sweep_cost(asks, body.quantity).saturating_mul(u128::from(
+                state.market_maker.contract_spec(&underlying).multiplier,
+            )),
+            None,
+        )?;
+    }
+
+    let order_id = state.market_maker.order_ids().next_id();
 
     match option_book
         .inner()
@@ -2774,7 +3678,14 @@ pub async fn submit_market_order(
                         maker_order_id: t.maker_order_id().to_string(),
                     });
                 }
-                record_fills(&state, &symbol, &underlying, body.side, &executed);
+                record_fills(
+                    &state,
+                    &symbol,
+                    &underlying,
+                    claims.account(),
+                    body.side,
+                    &executed,
+                );
 
                 // Publish orderbook deltas for the maker levels this market order
                 // consumed (issue #129). A market order never rests, so there is
@@ -2860,6 +3771,9 @@ pub async fn get_last_trade(
 ///
 /// Returns OHLC bars aggregated from trades at the specified interval.
 /// Supports filtering by time range and limiting the number of bars returned.
+/// With a database connected, bars written to the OHLC history are merged
+/// with the in-memory ones, so history survives restarts and reaches past
+/// the in-memory window.
 #[utoipa::path(
     get,
     path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/ohlc",
@@ -2906,10 +3820,35 @@ pub async fn get_ohlc(
 
     // Get bars from aggregator
     let limit = query.limit.unwrap_or(500).min(1000); // Cap at 1000
-    let bars = state
+    let live = state
         .ohlc_aggregator
         .get_bars(&symbol, interval, query.from, query.to, limit);
 
+    // Merge the stored history; a failed read degrades to the in-memory bars.
+    let bars = match &state.ohlc_store {
+        Some(store) => {
+            let anchor_at_from = query.from.is_some() && query.to.is_some();
+            match store
+                .load_bars(
+                    &symbol,
+                    interval,
+                    query.from,
+                    query.to,
+                    anchor_at_from,
+                    limit,
+                )
+                .await
+            {
+                Ok(persisted) => merge_bars(persisted, live, anchor_at_from, limit),
+                Err(e) => {
+                    tracing::warn!(symbol = %symbol, error = %e, "OHLC history not read");
+                    live
+                }
+            }
+        }
+        None => live,
+    };
+
     Ok(Json(OhlcResponse {
         symbol,
         interval: interval.to_string(),
@@ -2917,17 +3856,146 @@ pub async fn get_ohlc(
     }))
 }
 
-// ============================================================================
-// Order Status and Query
-// ============================================================================
-
-/// Get the status of a single order by its ID.
+/// Rebuild stored OHLC bars from persisted executions.
 ///
-/// Returns detailed information about the order including its current status,
-/// fill history, and timestamps.
+/// Folds the executions in the `executions` table between `from` and `to`
+/// (widened to whole days) into bars of every interval and writes them over
+/// the stored ones. Requires a database connection.
 #[utoipa::path(
-    get,
-    path = "/api/v1/orders/{order_id}",
+    post,
+    path = "/api/v1/admin/ohlc/backfill",
+    request_body = OhlcBackfillRequest,
+    responses(
+        (status = 200, description = "Bars rebuilt", body = OhlcBackfillResponse),
+        (status = 400, description = "No database connected", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn backfill_ohlc(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<OhlcBackfillRequest>,
+) -> Result<Json<OhlcBackfillResponse>, ApiError> {
+    let Some(store) = &state.ohlc_store else {
+        return Err(ApiError::InvalidRequest(
+            "OHLC backfill requires a database connection".to_string(),
+        ));
+    };
+    let summary = store
+        .backfill(request.from, request.to, request.symbol.as_deref())
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    tracing::info!(
+        executions = summary.executions,
+        bars = summary.bars,
+        "OHLC bars backfilled"
+    );
+    Ok(Json(OhlcBackfillResponse {
+        executions: summary.executions,
+        bars: summary.bars,
+        from: summary.from,
+        to: summary.to,
+    }))
+}
+
+// ============================================================================
+// Quote History
+// ============================================================================
+
+/// Get the recorded top-of-book history of an option.
+///
+/// Returns every change of the option's best bid, best ask and their sizes
+/// sampled by the quote recorder between `from` and `to`, oldest first. When
+/// more than `limit` changes match, the newest are returned, or the oldest
+/// when both `from` and `to` are set. Requires a PostgreSQL database
+/// connection and `[quote_history] enabled`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/quotes/history",
+    params(
+        ("underlying" = String, Path, description = "Underlying symbol"),
+        ("expiration" = String, Path, description = "Expiration date"),
+        ("strike" = u64, Path, description = "Strike price"),
+        ("style" = String, Path, description = "Option style: 'call' or 'put'"),
+        ("from" = Option<u64>, Query, description = "Start timestamp in milliseconds (optional)"),
+        ("to" = Option<u64>, Query, description = "End timestamp in milliseconds (optional)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of quotes (default 1000)")
+    ),
+    responses(
+        (status = 200, description = "Quote history", body = QuoteHistoryResponse),
+        (status = 400, description = "Quote history not recorded", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse)
+    ),
+    tag = "Options"
+)]
+#[tracing::instrument(
+    skip_all,
+    fields(underlying = %underlying, expiration = %exp_str, strike = strike, style = %style)
+)]
+pub async fn get_quote_history(
+    State(state): State<Arc<AppState>>,
+    Path((underlying, exp_str, strike, style)): Path<(String, String, u64, String)>,
+    Query(query): Query<QuoteHistoryQuery>,
+) -> Result<Json<QuoteHistoryResponse>, ApiError> {
+    let option_style = parse_option_style(&style)?;
+    let Some(recorder) = &state.quote_recorder else {
+        return Err(ApiError::InvalidRequest(
+            "quote history requires a PostgreSQL database connection and [quote_history] enabled"
+                .to_string(),
+        ));
+    };
+
+    let underlying_book = state
+        .manager
+        .get(&underlying)
+        .map_err(|_| ApiError::UnderlyingNotFound(underlying))?;
+
+    let expiration = find_expiration_by_str(&underlying_book, &exp_str)
+        .ok_or_else(|| ApiError::ExpirationNotFound(exp_str.clone()))?;
+
+    let exp_book = underlying_book
+        .get_expiration(&expiration)
+        .map_err(|_| ApiError::ExpirationNotFound(exp_str))?;
+
+    let strike_book = exp_book
+        .get_strike(strike)
+        .map_err(|_| ApiError::StrikeNotFound(strike))?;
+
+    let symbol = strike_book.get(option_style).symbol().to_string();
+    let limit = query.limit.unwrap_or(1000).min(10_000);
+    let anchor_at_from = query.from.is_some() && query.to.is_some();
+    let ticks = recorder
+        .load(&symbol, query.from, query.to, anchor_at_from, limit)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(QuoteHistoryResponse {
+        symbol,
+        quotes: ticks
+            .into_iter()
+            .map(|tick| QuoteResponse {
+                bid_price: tick.top.bid_price,
+                bid_size: tick.top.bid_size,
+                ask_price: tick.top.ask_price,
+                ask_size: tick.top.ask_size,
+                timestamp_ms: tick.timestamp_ms,
+            })
+            .collect(),
+    }))
+}
+
+// ============================================================================
+// Order Status and Query
+// ============================================================================
+
+/// Get the status of a single order by its ID.
+///
+/// Returns detailed information about the order including its current status,
+/// fill history, and timestamps.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orders/{order_id}",
     params(
         ("order_id" = String, Path, description = "Order identifier")
     ),
@@ -3077,6 +4145,7 @@ struct AcceptedBulkOrder {
 /// real fill/remaining state rather than assuming the order rested untouched.
 fn submit_single_order(
     state: &Arc<AppState>,
+    account: &str,
     item: &BulkOrderItem,
 ) -> Result<AcceptedBulkOrder, String> {
     // Translate the typed DTO enums to the upstream newtypes. Invalid style/side
@@ -3105,13 +4174,44 @@ fn submit_single_order(
         .get_strike(item.strike)
         .map_err(|e| format!("Strike not found: {}", e))?;
 
+    // Build symbol for tracking
+    let style_char = match option_style {
+        OptionStyle::Call => "C",
+        OptionStyle::Put => "P",
+    };
+    let symbol = format!(
+        "{}-{}-{}-{}",
+        item.underlying, item.expiration, item.strike, style_char
+    );
+
+    check_tick_size(state, &item.underlying, item.price).map_err(|e| e.to_string())?;
+    check_order_size(state, &item.underlying, item.quantity).map_err(|e| e.to_string())?;
+    check_order_margin(
+        state,
+        account,
+        order_risk_position(state, &symbol, &item.underlying, item.side, item.quantity),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+    check_buying_power(
+        state,
+        account,
+        item.side,
+        state
+            .market_maker
+            .contract_spec(&item.underlying)
+            .scale(item.price, item.quantity),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
     // Get option book
     let option_book = strike_book.get(option_style);
 
     // Generate order ID and submit, capturing the trade result so we know what
     // (if anything) filled immediately. The fill is the source of truth for an
     // atomic rollback — a marketable limit order can fill on submit.
-    let order_id = OrderId::new();
+    let order_id = state.market_maker.order_ids().next_id();
     let trade_result = option_book
         .add_limit_order_full(order_id, side, item.price, item.quantity)
         .map_err(|e| format!("Failed to add order: {}", e))?;
@@ -3140,15 +4240,6 @@ fn submit_single_order(
         })
         .collect();
 
-    // Build symbol for tracking
-    let style_char = match option_style {
-        OptionStyle::Call => "C",
-        OptionStyle::Put => "P",
-    };
-    let symbol = format!(
-        "{}-{}-{}-{}",
-        item.underlying, item.expiration, item.strike, style_char
-    );
     let order_side = match side {
         Side::Buy => OrderSide::Buy,
         Side::Sell => OrderSide::Sell,
@@ -3172,7 +4263,14 @@ fn submit_single_order(
                 maker_order_id: t.maker_order_id().to_string(),
             });
         }
-        record_fills(state, &symbol, &item.underlying, order_side, &executed);
+        record_fills(
+            state,
+            &symbol,
+            &item.underlying,
+            account,
+            order_side,
+            &executed,
+        );
     }
 
     // Publish orderbook deltas so WS `orderbook` subscribers observe this bulk
@@ -3192,9 +4290,10 @@ fn submit_single_order(
     }
 
     // Track order in AppState with the REAL fill/remaining state.
-    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let now = clock::now_ms();
     let order_info = OrderInfo {
         order_id: order_id.to_string(),
+        account: account.to_string(),
         symbol,
         underlying: item.underlying.clone(),
         expiration: item.expiration.clone(),
@@ -3219,6 +4318,148 @@ fn submit_single_order(
     })
 }
 
+/// Places an immediate-or-cancel limit order for `account` against the book of
+/// `position` and records its fills, returning the filled quantity and the
+/// premium it traded in cents.
+///
+/// Used by the liquidation engine, which only ever reduces the account's
+/// risk: the pre-trade margin and buying-power checks are skipped. Fills are
+/// recorded under `position.instrument`, the account's own position key, so
+/// they close that position. Only the liquidity within `price` is taken; an
+/// order that finds none fills nothing.
+///
+/// # Errors
+/// Returns [`ApiError::NotFound`] when the instrument has no book and
+/// [`ApiError::OrderBook`] when the book rejects the order.
+pub(crate) fn submit_liquidation_order(
+    state: &AppState,
+    account: &str,
+    position: &RiskPosition,
+    side: OrderSide,
+    price: u128,
+    quantity: u64,
+) -> Result<(u64, u128), ApiError> {
+    let option_book = state
+        .manager
+        .get(&position.underlying)
+        .ok()
+        .and_then(|underlying_book| {
+            let expiration = find_expiration_by_str(&underlying_book, &position.expiration_key)?;
+            underlying_book
+                .get_expiration(&expiration)
+                .ok()?
+                .get_strike(position.strike)
+                .ok()
+        })
+        .ok_or_else(|| ApiError::NotFound(format!("no book for {}", position.instrument)))?;
+    let option_book = option_book.get(position.style);
+
+    // The book rejects an IOC it cannot fill completely even after executing
+    // part of it, so the order is sized to the liquidity within the limit.
+    let snapshot = option_book.inner().create_snapshot(usize::MAX);
+    let opposite = match side {
+        OrderSide::Buy => &snapshot.asks,
+        OrderSide::Sell => &snapshot.bids,
+    };
+    let available: u64 = opposite
+        .iter()
+        .filter(|level| match side {
+            OrderSide::Buy => level.price().as_u128() <= price,
+            OrderSide::Sell => level.price().as_u128() >= price,
+        })
+        .map(|level| {
+            level
+                .visible_quantity()
+                .as_u64()
+                .saturating_add(level.hidden_quantity().as_u64())
+        })
+        .sum();
+    let quantity = quantity.min(available);
+    if quantity == 0 {
+        return Ok((0, 0));
+    }
+
+    let order_id = state.market_maker.order_ids().next_id();
+    let book_side = order_side_to_side(side);
+    let trade_result = option_book
+        .add_limit_order_with_tif_full(order_id, book_side, price, quantity, TimeInForce::Ioc)
+        .map_err(|e| ApiError::OrderBook(e.to_string()))?;
+
+    let match_result = &trade_result.match_result;
+    let trades = match_result.trades().as_vec();
+    let executed: Vec<ExecutedFill> = trades
+        .iter()
+        .map(|t| ExecutedFill {
+            price: t.price().as_u128(),
+            quantity: t.quantity().as_u64(),
+            timestamp_ms: t.timestamp().as_u64(),
+            trade_id: t.trade_id().to_string(),
+            taker_order_id: t.taker_order_id().to_string(),
+            maker_order_id: t.maker_order_id().to_string(),
+        })
+        .collect();
+    let filled_quantity: u64 = executed.iter().map(|f| f.quantity).sum();
+    let premium: u128 = executed
+        .iter()
+        .map(|f| f.price.saturating_mul(u128::from(f.quantity)))
+        .sum();
+
+    // The unfilled remainder of an IOC never rests, so the tracked order is
+    // either filled or canceled.
+    let now = clock::now_ms();
+    let style = match position.style {
+        OptionStyle::Call => "call",
+        OptionStyle::Put => "put",
+    };
+    state.orders.insert(
+        order_id.to_string(),
+        OrderInfo {
+            order_id: order_id.to_string(),
+            account: account.to_string(),
+            symbol: position.instrument.clone(),
+            underlying: position.underlying.clone(),
+            expiration: position.expiration_key.clone(),
+            strike: position.strike,
+            style: style.to_string(),
+            side,
+            price,
+            original_quantity: quantity,
+            remaining_quantity: 0,
+            filled_quantity,
+            status: if filled_quantity == quantity {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::Canceled
+            },
+            time_in_force: OrderTimeInForce::Ioc,
+            created_at_ms: now,
+            updated_at_ms: now,
+            fills: executed
+                .iter()
+                .map(|f| OrderFillInfo {
+                    price: f.price,
+                    quantity: f.quantity,
+                    timestamp_ms: f.timestamp_ms,
+                })
+                .collect(),
+        },
+    );
+
+    if filled_quantity > 0 {
+        record_fills(
+            state,
+            &position.instrument,
+            &position.underlying,
+            account,
+            side,
+            &executed,
+        );
+        let consumed: Vec<u128> = executed.iter().map(|f| f.price).collect();
+        publish_consumed_maker_deltas(state, option_book, book_side, &consumed);
+    }
+    Ok((filled_quantity, premium))
+}
+
 /// Cancels a previously-accepted bulk order in the real order book.
 ///
 /// Resolves the order's option book via the originating [`BulkOrderItem`] path —
@@ -3333,7 +4574,7 @@ fn rollback_atomic_bulk(
     failed_index: usize,
     failed_error: &str,
 ) -> BulkOrderResponse {
-    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let now = clock::now_ms();
     let mut outcomes: std::collections::HashMap<usize, RollbackOutcome> =
         std::collections::HashMap::new();
 
@@ -3544,6 +4785,7 @@ fn rollback_atomic_bulk(
 )]
 pub async fn bulk_submit_orders(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Json(body): Json<BulkOrderRequest>,
 ) -> Result<Json<BulkOrderResponse>, ApiError> {
     if body.orders.is_empty() {
@@ -3569,8 +4811,11 @@ pub async fn bulk_submit_orders(
     // un-filled.
     let mut accepted: Vec<(usize, OrderId, u64)> = Vec::with_capacity(body.orders.len());
 
+    // Every item is margined against the ones before it, so hold the
+    // account's order entry for the whole batch.
+    let _account_guard = state.account_guard(claims.account()).await;
     for (index, item) in body.orders.iter().enumerate() {
-        match submit_single_order(&state, item) {
+        match submit_single_order(&state, claims.account(), item) {
             Ok(order) => {
                 accepted.push((index, order.order_id, order.filled_quantity));
                 results.push(BulkOrderResultItem {
@@ -3841,46 +5086,8 @@ pub async fn cancel_all_orders(
 
     // Cancel each matching order
     for (order_id_str, order_info) in orders_to_cancel {
-        if let Ok(order_id) = order_id_str.parse::<OrderId>() {
-            let option_style = match order_info.style.to_lowercase().as_str() {
-                "call" => OptionStyle::Call,
-                "put" => OptionStyle::Put,
-                _ => {
-                    failed_count += 1;
-                    continue;
-                }
-            };
-
-            if let Ok(underlying_book) = state.manager.get(&order_info.underlying)
-                && let Some(expiration) =
-                    find_expiration_by_str(&underlying_book, &order_info.expiration)
-                && let Ok(exp_book) = underlying_book.get_expiration(&expiration)
-                && let Ok(strike_book) = exp_book.get_strike(order_info.strike)
-            {
-                let option_book = strike_book.get(option_style);
-                match option_book.cancel_order(order_id) {
-                    Ok(true) => {
-                        state.orders.remove(&order_id_str);
-                        // Publish the affected level's resulting total to WS
-                        // `orderbook` subscribers (issue #129). `order_info` is an
-                        // owned clone here, so no tracking guard is held.
-                        publish_level_delta(
-                            &state,
-                            option_book,
-                            order_side_to_side(order_info.side),
-                            order_info.price,
-                        );
-                        canceled_count += 1;
-                        continue;
-                    }
-                    Ok(false) | Err(_) => {
-                        failed_count += 1;
-                        continue;
-                    }
-                }
-            }
-
-            failed_count += 1;
+        if cancel_tracked_order(&state, &order_id_str, &order_info) {
+            canceled_count += 1;
         } else {
             failed_count += 1;
         }
@@ -3892,6 +5099,55 @@ pub async fn cancel_all_orders(
     }))
 }
 
+/// Cancels a tracked order in its book and drops it from `state.orders`,
+/// publishing the affected level. Returns whether the book canceled it.
+fn cancel_tracked_order(state: &AppState, order_id_str: &str, order_info: &OrderInfo) -> bool {
+    let Ok(order_id) = order_id_str.parse::<OrderId>() else {
+        return false;
+    };
+    let option_style = match order_info.style.to_lowercase().as_str() {
+        "call" => OptionStyle::Call,
+        "put" => OptionStyle::Put,
+        _ => return false,
+    };
+
+    if let Ok(underlying_book) = state.manager.get(&order_info.underlying)
+        && let Some(expiration) = find_expiration_by_str(&underlying_book, &order_info.expiration)
+        && let Ok(exp_book) = underlying_book.get_expiration(&expiration)
+        && let Ok(strike_book) = exp_book.get_strike(order_info.strike)
+    {
+        let option_book = strike_book.get(option_style);
+        if let Ok(true) = option_book.cancel_order(order_id) {
+            state.orders.remove(order_id_str);
+            // Publish the affected level's resulting total to WS `orderbook`
+            // subscribers (issue #129). `order_info` is an owned clone here, so
+            // no tracking guard is held.
+            publish_level_delta(
+                state,
+                option_book,
+                order_side_to_side(order_info.side),
+                order_info.price,
+            );
+            return true;
+        }
+    }
+    false
+}
+
+/// Cancels every open order of `account`, returning how many were canceled.
+pub(crate) fn cancel_account_orders(state: &AppState, account: &str) -> usize {
+    let open: Vec<(String, OrderInfo)> = state
+        .orders
+        .account_orders(account)
+        .into_iter()
+        .filter(|order| matches!(order.status, OrderStatus::Active | OrderStatus::Partial))
+        .map(|order| (order.order_id.clone(), order))
+        .collect();
+    open.iter()
+        .filter(|(order_id_str, order_info)| cancel_tracked_order(state, order_id_str, order_info))
+        .count()
+}
+
 // ============================================================================
 // Position and Inventory Tracking
 // ============================================================================
@@ -4268,11 +5524,16 @@ fn publish_consumed_maker_deltas(
 ///   single position per symbol, so only the aggressor (operator/taker) side is
 ///   applied; the maker is the counterparty and is not double-booked into the
 ///   same key (doing so would net every fill to zero).
+/// * `state.account_positions` — the fill is booked to `taker_account` on
+///   `taker_side`, and to the resting order's account on the opposite side
+///   when the maker is a tracked order. A market-maker quote on the maker side
+///   is booked into the engine's own inventory instead (via `on_order_filled`).
 /// * `state.last_trades` — overwritten with the most recent fill for `symbol`;
 ///   `side` is the taker (aggressor) side per the DTO contract.
 /// * `state.ohlc_aggregator` — the fill is folded into every OHLC interval.
 /// * `state.executions` — one execution report per fill, keyed by the stable
-///   trade id.
+///   trade id, also buffered for the `executions` table when an OHLC store is
+///   attached.
 ///
 /// **Replay semantics:** this is intended to be called exactly once per match.
 /// `executions` (keyed by trade id) and `last_trades` (overwritten) are
@@ -4288,9 +5549,11 @@ fn record_fills(
     state: &AppState,
     symbol: &str,
     underlying: &str,
+    taker_account: &str,
     taker_side: OrderSide,
     fills: &[ExecutedFill],
 ) {
+    let mut margined_accounts = vec![taker_account.to_string()];
     for fill in fills {
         // The market-data DTOs carry prices as cents in `u64`; the order book
         // speaks `u128`. A price that does not fit `u64` is a structurally
@@ -4319,6 +5582,55 @@ fn record_fills(
             fill.timestamp_ms,
         );
 
+        // Account positions: both sides of the fill, each to its own account.
+        update_account_position_on_fill(
+            state,
+            taker_account,
+            symbol,
+            underlying,
+            taker_side,
+            fill.quantity,
+            fill.price,
+            fill.timestamp_ms,
+        );
+        let maker_account = state
+            .orders
+            .get(&fill.maker_order_id)
+            .map(|order| order.account.clone());
+
+        // Cash: premium from buyer to seller plus fees, as one ledger entry.
+        let taker_fee = post_fill(
+            state,
+            &fill.trade_id,
+            taker_account,
+            taker_side,
+            maker_account.as_deref(),
+            underlying,
+            fill.price,
+            fill.quantity,
+            fill.timestamp_ms,
+        );
+
+        if let Some(maker_account) = maker_account {
+            let maker_side = match taker_side {
+                OrderSide::Buy => OrderSide::Sell,
+                OrderSide::Sell => OrderSide::Buy,
+            };
+            update_account_position_on_fill(
+                state,
+                &maker_account,
+                symbol,
+                underlying,
+                maker_side,
+                fill.quantity,
+                fill.price,
+                fill.timestamp_ms,
+            );
+            if !margined_accounts.contains(&maker_account) {
+                margined_accounts.push(maker_account);
+            }
+        }
+
         // Last trade: the most recent fill for the symbol wins.
         state.last_trades.insert(
             symbol.to_string(),
@@ -4338,25 +5650,23 @@ fn record_fills(
             .record_trade(symbol, fill.timestamp_ms, fill.price, fill.quantity);
 
         // Execution report: one per fill, from the taker perspective, keyed by
-        // the stable trade id for idempotency. The market / crossing-limit paths
-        // do not compute per-fill fees or edge, so both stay at their
-        // zero / `None` defaults.
-        state.executions.insert(
-            fill.trade_id.clone(),
-            ExecutionInfo {
-                execution_id: fill.trade_id.clone(),
-                order_id: fill.taker_order_id.clone(),
-                symbol: symbol.to_string(),
-                side: taker_side,
-                price: price_u64,
-                quantity: fill.quantity,
-                timestamp_ms: fill.timestamp_ms,
-                counterparty_order_id: Some(fill.maker_order_id.clone()),
-                is_maker: false,
-                fee: 0,
-                edge: None,
-            },
-        );
+        // the stable trade id for idempotency. The fee is the taker fee posted
+        // to the ledger above; the market / crossing-limit paths do not compute
+        // edge, so it stays `None`.
+        let execution = ExecutionInfo {
+            execution_id: fill.trade_id.clone(),
+            order_id: fill.taker_order_id.clone(),
+            symbol: symbol.to_string(),
+            side: taker_side,
+            price: price_u64,
+            quantity: fill.quantity,
+            timestamp_ms: fill.timestamp_ms,
+            counterparty_order_id: Some(fill.maker_order_id.clone()),
+            is_maker: false,
+            fee: taker_fee,
+            edge: None,
+        };
+        state.executions.insert(fill.trade_id.clone(), execution);
 
         // Market-maker fill detection (issue #69): if the resting maker side
         // of this fill is one of the market maker's tracked quotes, the
@@ -4394,6 +5704,14 @@ fn record_fills(
             "fill recorded to market-data stores"
         );
     }
+
+    // Positions changed, so the stored margin of every account on either side
+    // of these fills is stale.
+    if !fills.is_empty() {
+        for account in &margined_accounts {
+            crate::api::margin::refresh_account_margin(state, account);
+        }
+    }
 }
 
 /// Updates position based on a fill.
@@ -4427,18 +5745,68 @@ pub fn update_position_on_fill(
                 price,
                 timestamp_ms,
             )
+            .with_multiplier(state.market_maker.contract_spec(underlying).multiplier)
+        });
+}
+
+/// Books a fill into `account`'s position for `symbol` in
+/// `state.account_positions`, with the same avg-price / realized-PnL contract
+/// as [`update_position_on_fill`].
+#[allow(clippy::too_many_arguments)]
+pub fn update_account_position_on_fill(
+    state: &AppState,
+    account: &str,
+    symbol: &str,
+    underlying: &str,
+    side: OrderSide,
+    quantity: u64,
+    price: u128,
+    timestamp_ms: u64,
+) {
+    let fill_quantity = match side {
+        OrderSide::Buy => quantity as i64,
+        OrderSide::Sell => -(quantity as i64),
+    };
+
+    state
+        .account_positions
+        .entry(account.to_string())
+        .or_default()
+        .entry(symbol.to_string())
+        .and_modify(|pos| {
+            pos.update(fill_quantity, price, timestamp_ms);
+        })
+        .or_insert_with(|| {
+            PositionInfo::new(
+                symbol.to_string(),
+                underlying.to_string(),
+                fill_quantity,
+                price,
+                timestamp_ms,
+            )
+            .with_multiplier(state.market_maker.contract_spec(underlying).multiplier)
         });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::test_support::claims_for;
     use crate::state::AppState;
 
     fn create_test_state() -> Arc<AppState> {
         Arc::new(AppState::new())
     }
 
+    /// Account every test order and fill is attributed to.
+    const TEST_ACCOUNT: &str = "test-account";
+
+    /// Trade-permissioned claims for [`TEST_ACCOUNT`], standing in for what
+    /// `auth_middleware` injects on a real request.
+    fn test_claims() -> Claims {
+        claims_for(TEST_ACCOUNT)
+    }
+
     /// Issue #69 seam test: `record_fills` must notify the market maker via
     /// the fill's `maker_order_id` STRING (the `to_string()` → `parse` round
     /// trip), producing a broadcast `OrderFilled` with the computed edge.
@@ -4455,6 +5823,7 @@ mod tests {
             &state,
             "BTC-20351231-100000-C",
             "BTC",
+            TEST_ACCOUNT,
             OrderSide::Sell,
             &[ExecutedFill {
                 price: 95,
@@ -4734,8 +6103,21 @@ mod tests {
         assert_eq!(resolved, expected_ms);
     }
 
-    #[tokio::test]
-    async fn test_add_order_gtd_without_expire_at_is_rejected() {
+    #[test]
+    fn test_book_gtd_deadline_shifts_only_a_deadline_the_wall_clock_passed() {
+        let wall_ms = clock::wall_ms();
+        let ahead = wall_ms + 60_000;
+        assert_eq!(book_gtd_deadline(ahead, wall_ms), ahead);
+
+        // Recorded an hour ago with a minute to run: still a minute to run.
+        let recorded_ms = wall_ms - 3_600_000;
+        let deadline = book_gtd_deadline(recorded_ms + 60_000, recorded_ms);
+        assert!(deadline >= clock::wall_ms() + 59_000);
+        assert!(deadline <= clock::wall_ms() + 60_000);
+    }
+
+    #[tokio::test]
+    async fn test_add_order_gtd_without_expire_at_is_rejected() {
         // Issue #57: GTD with no expire_at must be HTTP 400, not a dead Gtd(0).
         let state = create_test_state();
 
@@ -4749,6 +6131,7 @@ mod tests {
 
         let err = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -4780,6 +6163,7 @@ mod tests {
 
         let err = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -4813,6 +6197,7 @@ mod tests {
 
         let response = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -4853,6 +6238,7 @@ mod tests {
 
         let response = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -4906,6 +6292,7 @@ mod tests {
 
         let _ = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -4942,6 +6329,7 @@ mod tests {
 
         let _ = cancel_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp,
@@ -4982,6 +6370,7 @@ mod tests {
 
         let _ = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5041,6 +6430,7 @@ mod tests {
         };
         let order_id = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5160,6 +6550,7 @@ mod tests {
 
         let resp = cancel_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp,
@@ -5181,6 +6572,69 @@ mod tests {
         assert!(matches!(err, ApiError::NotFound(_)));
     }
 
+    #[tokio::test]
+    async fn test_only_the_owner_or_an_admin_changes_an_order() {
+        let state = create_test_state();
+        let (order_id, exp) = submit_tracked_gtc_order(&state).await;
+        let path = |id: &str| {
+            Path((
+                "TEST".to_string(),
+                exp.clone(),
+                100u64,
+                "call".to_string(),
+                id.to_string(),
+            ))
+        };
+        let reprice = || {
+            Json(ModifyOrderRequest {
+                price: Some(105),
+                quantity: None,
+            })
+        };
+
+        let err = modify_order(
+            State(state.clone()),
+            Extension(claims_for("mallory")),
+            path(&order_id),
+            reprice(),
+        )
+        .await
+        .expect_err("not the owner");
+        assert!(matches!(err, ApiError::Forbidden(_)));
+        let err = cancel_order(
+            State(state.clone()),
+            Extension(claims_for("mallory")),
+            path(&order_id),
+        )
+        .await
+        .expect_err("not the owner");
+        assert!(matches!(err, ApiError::Forbidden(_)));
+        assert_eq!(
+            state.orders.get(&order_id).map(|o| o.status),
+            Some(OrderStatus::Active)
+        );
+
+        // An order nobody tracks is not amended on the caller's account.
+        let untracked = OrderId::new().to_string();
+        let err = modify_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            path(&untracked),
+            reprice(),
+        )
+        .await
+        .expect_err("untracked");
+        assert!(matches!(err, ApiError::NotFound(_)));
+
+        let mut admin = claims_for("ops");
+        admin.permissions = vec![Permission::Admin];
+        let resp = cancel_order(State(state.clone()), Extension(admin), path(&order_id))
+            .await
+            .expect("admin cancels any order")
+            .0;
+        assert!(resp.success);
+    }
+
     #[tokio::test]
     async fn test_modify_order_updates_tracking() {
         let state = create_test_state();
@@ -5188,6 +6642,7 @@ mod tests {
 
         let resp = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp,
@@ -5207,16 +6662,12 @@ mod tests {
         assert_eq!(resp.status, ModifyOrderStatus::Modified);
         assert_eq!(resp.new_price, Some(120));
         assert_eq!(resp.new_quantity, Some(7));
-        let new_id = resp.order_id.clone();
-        assert_ne!(new_id, order_id, "modify must mint a new order id");
+        assert!(resp.priority_changed, "a reprice loses priority");
+        assert_eq!(resp.order_id, order_id, "amend keeps the order id");
 
-        // Old id removed; new id tracked with the new price/quantity.
-        assert!(
-            !state.orders.contains_key(&order_id),
-            "stale old entry must not remain"
-        );
+        // Same id tracked with the new price/quantity.
         {
-            let tracked = state.orders.get(&new_id).expect("new id must be tracked");
+            let tracked = state.orders.get(&order_id).expect("id must stay tracked");
             assert_eq!(tracked.price, 120);
             assert_eq!(tracked.original_quantity, 7);
             assert_eq!(tracked.remaining_quantity, 7);
@@ -5228,14 +6679,46 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_modify_order_cancel_ok_replace_fail_leaves_no_stale_entry() {
+    async fn test_modify_order_size_reduction_keeps_priority() {
+        let state = create_test_state();
+        let (order_id, exp) = submit_tracked_gtc_order(&state).await;
+
+        let resp = modify_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "TEST".to_string(),
+                exp,
+                100u64,
+                "call".to_string(),
+                order_id.clone(),
+            )),
+            Json(ModifyOrderRequest {
+                price: None,
+                quantity: Some(4),
+            }),
+        )
+        .await
+        .expect("modify ok")
+        .0;
+
+        assert_eq!(resp.status, ModifyOrderStatus::Modified);
+        assert_eq!(resp.order_id, order_id);
+        assert_eq!(resp.new_price, Some(100));
+        assert_eq!(resp.new_quantity, Some(4));
+        assert!(!resp.priority_changed, "a size reduction keeps priority");
+        let tracked = state.orders.get(&order_id).expect("still tracked");
+        assert_eq!(tracked.remaining_quantity, 4);
+        assert_eq!(tracked.original_quantity, 4);
+    }
+
+    #[tokio::test]
+    async fn test_modify_order_rejected_amend_leaves_original_resting() {
         let state = create_test_state();
         let (order_id, exp) = submit_tracked_gtc_order(&state).await;
 
-        // Halt the instrument: a resting order is left in place and `cancel_order`
-        // does not check the active status (so the cancel succeeds), but the
-        // replacement `add_limit_order` fails the active check. This deterministically
-        // drives the cancel-ok / replace-fail path.
+        // Halt the instrument: the amend is refused before the book is touched,
+        // so the original order keeps resting and stays tracked.
         let underlying_book = state.manager.get("TEST").expect("underlying exists");
         let expiration = find_expiration_by_str(&underlying_book, &exp).expect("expiration exists");
         let exp_book = underlying_book
@@ -5249,6 +6732,7 @@ mod tests {
 
         let resp = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp,
@@ -5267,21 +6751,322 @@ mod tests {
 
         assert_eq!(resp.status, ModifyOrderStatus::Rejected);
         assert!(
-            resp.message.contains("failed to place replacement"),
-            "message should describe the cancel-ok/replace-fail outcome: {}",
+            resp.message.contains("not accepting orders"),
+            "message should describe the rejection: {}",
             resp.message
         );
 
-        // No stale Active entry: the old id is gone and no new id was tracked.
+        // The original is untouched: still tracked, still Active, still resting.
+        let tracked = state.orders.get(&order_id).expect("original still tracked");
+        assert_eq!(tracked.price, 100);
+        assert_eq!(tracked.status, OrderStatus::Active);
+        assert_eq!(state.orders.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_modify_order_crossing_reprice_records_the_fills() {
+        let state = create_test_state();
+        let (order_id, exp) = submit_tracked_gtc_order(&state).await;
+        let ask = add_order(
+            State(state.clone()),
+            Extension(claims_for("bob")),
+            Path((
+                "TEST".to_string(),
+                "20251231".to_string(),
+                100u64,
+                "call".to_string(),
+            )),
+            Json(AddOrderRequest {
+                side: OrderSide::Sell,
+                price: 110,
+                quantity: 4,
+                time_in_force: None,
+                expire_at: None,
+            }),
+        )
+        .await
+        .expect("ask rests")
+        .0;
+
+        let resp = modify_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "TEST".to_string(),
+                exp,
+                100u64,
+                "call".to_string(),
+                order_id.clone(),
+            )),
+            Json(ModifyOrderRequest {
+                price: Some(110),
+                quantity: None,
+            }),
+        )
+        .await
+        .expect("modify succeeds")
+        .0;
+        assert_eq!(resp.status, ModifyOrderStatus::Modified);
+        assert!(resp.message.contains("4 filled"), "{}", resp.message);
+
+        let tracked = state.orders.get(&order_id).expect("tracked");
+        assert_eq!(tracked.status, OrderStatus::Partial);
+        assert_eq!(tracked.filled_quantity, 4);
+        assert_eq!(tracked.remaining_quantity, 6);
+        assert_eq!(tracked.original_quantity, 10);
+        assert_eq!(tracked.fills.len(), 1);
+        drop(tracked);
+
+        let quantity = |account: &str| {
+            state
+                .account_positions
+                .get(account)
+                .and_then(|book| book.iter().next().map(|p| p.quantity))
+        };
+        assert_eq!(quantity(TEST_ACCOUNT), Some(4));
+        assert_eq!(quantity("bob"), Some(-4));
+        assert_eq!(state.executions.len(), 1);
+        let execution = state.executions.iter().next().expect("one execution");
+        assert_eq!(execution.order_id, order_id);
+        assert_eq!(
+            execution.counterparty_order_id.as_deref(),
+            Some(ask.order_id.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_off_tick_prices_are_rejected_on_every_entry_path() {
+        use crate::ticks::{TickBand, TickTable};
+
+        let state = create_test_state();
+        state.market_maker.set_tick_table(
+            "TEST",
+            TickTable::Bands(vec![
+                TickBand {
+                    below_cents: Some(300),
+                    tick_cents: 5,
+                },
+                TickBand {
+                    below_cents: None,
+                    tick_cents: 10,
+                },
+            ]),
+        );
+        let (order_id, exp) = submit_tracked_gtc_order(&state).await;
+
+        let err = add_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "TEST".to_string(),
+                "20251231".to_string(),
+                100u64,
+                "call".to_string(),
+            )),
+            Json(AddOrderRequest {
+                side: OrderSide::Buy,
+                price: 102,
+                quantity: 1,
+                time_in_force: None,
+                expire_at: None,
+            }),
+        )
+        .await
+        .expect_err("102 is between nickels");
+        assert!(matches!(err, ApiError::InvalidTickSize(_)));
+
+        // 305 is a nickel, but above $3 the tick is a dime.
+        let err = modify_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "TEST".to_string(),
+                exp.clone(),
+                100u64,
+                "call".to_string(),
+                order_id.clone(),
+            )),
+            Json(ModifyOrderRequest {
+                price: Some(305),
+                quantity: None,
+            }),
+        )
+        .await
+        .expect_err("off-tick reprice");
+        assert!(matches!(err, ApiError::InvalidTickSize(_)));
+        assert_eq!(state.orders.get(&order_id).map(|o| o.price), Some(100));
+
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(BulkOrderRequest {
+                orders: vec![BulkOrderItem {
+                    underlying: "TEST".to_string(),
+                    expiration: exp,
+                    strike: 100,
+                    style: crate::models::OptionStyle::Call,
+                    side: OrderSide::Buy,
+                    price: 103,
+                    quantity: 1,
+                }],
+                atomic: false,
+            }),
+        )
+        .await
+        .expect("bulk submit returns a response");
+        assert_eq!(response.failure_count, 1);
         assert!(
-            !state.orders.contains_key(&order_id),
-            "the cancelled original must not linger as Active"
+            response.results[0]
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("5-cent tick"))
         );
+        assert_eq!(state.orders.len(), 1, "only the on-tick order rests");
+    }
+
+    #[tokio::test]
+    async fn test_off_lot_and_oversized_orders_are_rejected_on_every_entry_path() {
+        use crate::contract::ContractSpec;
+
+        let state = create_test_state();
+        state.market_maker.set_contract_spec(
+            "TEST",
+            ContractSpec {
+                multiplier: 100,
+                min_lot_size: 5,
+                max_order_size: Some(20),
+            },
+        );
+        let (order_id, exp) = submit_tracked_gtc_order(&state).await;
+        let option_path = || {
+            Path((
+                "TEST".to_string(),
+                "20251231".to_string(),
+                100u64,
+                "call".to_string(),
+            ))
+        };
+
+        let err = add_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            option_path(),
+            Json(AddOrderRequest {
+                side: OrderSide::Buy,
+                price: 100,
+                quantity: 7,
+                time_in_force: None,
+                expire_at: None,
+            }),
+        )
+        .await
+        .expect_err("7 is not a whole number of 5-lots");
+        assert!(matches!(err, ApiError::InvalidOrderSize(_)));
+
+        let err = modify_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "TEST".to_string(),
+                exp.clone(),
+                100u64,
+                "call".to_string(),
+                order_id.clone(),
+            )),
+            Json(ModifyOrderRequest {
+                price: None,
+                quantity: Some(25),
+            }),
+        )
+        .await
+        .expect_err("25 exceeds the maximum order size");
+        assert!(matches!(err, ApiError::InvalidOrderSize(_)));
         assert_eq!(
-            state.orders.len(),
-            0,
-            "no order should be tracked after a failed replacement"
+            state.orders.get(&order_id).map(|o| o.remaining_quantity),
+            Some(10)
         );
+
+        let err = submit_market_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            option_path(),
+            Json(MarketOrderRequest {
+                side: OrderSide::Sell,
+                quantity: 3,
+            }),
+        )
+        .await
+        .expect_err("3 is below the lot size");
+        assert!(matches!(err, ApiError::InvalidOrderSize(_)));
+
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(BulkOrderRequest {
+                orders: vec![BulkOrderItem {
+                    underlying: "TEST".to_string(),
+                    expiration: exp,
+                    strike: 100,
+                    style: crate::models::OptionStyle::Call,
+                    side: OrderSide::Buy,
+                    price: 100,
+                    quantity: 12,
+                }],
+                atomic: false,
+            }),
+        )
+        .await
+        .expect("bulk submit returns a response");
+        assert_eq!(response.failure_count, 1);
+        assert!(
+            response.results[0]
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("lot size 5"))
+        );
+        assert_eq!(state.orders.len(), 1, "only the whole-lot order rests");
+    }
+
+    #[tokio::test]
+    async fn test_get_instrument_spec_reports_contract_and_tick_rules() {
+        use crate::contract::ContractSpec;
+        use crate::ticks::TickTable;
+
+        let state = create_test_state();
+        state.market_maker.set_contract_spec(
+            "TEST",
+            ContractSpec {
+                multiplier: 100,
+                min_lot_size: 5,
+                max_order_size: Some(500),
+            },
+        );
+        let (_, exp) = submit_tracked_gtc_order(&state).await;
+        state
+            .market_maker
+            .set_tick_table("TEST", TickTable::UnderlyingFraction(0.001));
+        state.market_maker.update_price("TEST", 1_000_000);
+
+        let Json(spec) = get_instrument_spec(
+            State(state.clone()),
+            Path(("TEST".to_string(), exp.clone(), 100u64, "call".to_string())),
+        )
+        .await
+        .expect("instrument exists");
+        assert_eq!(spec.symbol, format!("TEST-{exp}-100-C"));
+        assert_eq!(spec.contract_multiplier, 100);
+        assert_eq!(spec.min_lot_size, 5);
+        assert_eq!(spec.max_order_size, Some(500));
+        assert_eq!(spec.tick_bands.len(), 1);
+        assert_eq!(spec.tick_bands[0].tick, 1_000);
+
+        let err = get_instrument_spec(
+            State(state),
+            Path(("TEST".to_string(), exp, 999u64, "call".to_string())),
+        )
+        .await
+        .expect_err("no such strike");
+        assert!(matches!(err, ApiError::StrikeNotFound(999)));
     }
 
     #[tokio::test]
@@ -5303,6 +7088,7 @@ mod tests {
         // modify (price only; quantity preserved at the current 10)
         let m = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp.clone(),
@@ -5322,8 +7108,9 @@ mod tests {
         assert_eq!(m.new_price, Some(105));
         assert_eq!(m.new_quantity, Some(10));
         let new_id = m.order_id.clone();
+        assert_eq!(new_id, order_id, "amend keeps the order id");
 
-        // status reflects the modified order; old id is gone
+        // status reflects the modified order
         let s2 = get_order_status(State(state.clone()), Path(new_id.clone()))
             .await
             .expect("status of modified order")
@@ -5331,17 +7118,11 @@ mod tests {
         assert_eq!(s2.price, 105);
         assert_eq!(s2.original_quantity, 10);
         assert_eq!(s2.status, OrderStatus::Active);
-        assert!(
-            get_order_status(State(state.clone()), Path(order_id.clone()))
-                .await
-                .is_err(),
-            "old id must no longer resolve"
-        );
 
-        // cancel the modified order (the replacement carries the same expiration
-        // path, so the single cancel endpoint resolves it end-to-end)
+        // cancel the modified order through the single cancel endpoint
         let c = cancel_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 exp,
@@ -5389,6 +7170,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5436,6 +7218,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5465,6 +7248,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5502,6 +7286,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5526,6 +7311,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5570,6 +7356,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5623,6 +7410,7 @@ mod tests {
 
         let result = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "TEST".to_string(),
                 "20251231".to_string(),
@@ -5649,6 +7437,52 @@ mod tests {
         assert!((response.average_price.unwrap() - 152.5).abs() < 0.01);
     }
 
+    #[tokio::test]
+    async fn test_market_order_walks_maker_ladder() {
+        use crate::market_maker::LayeredStrategy;
+
+        let state = create_test_state();
+        let expiration = parse_expiration("20351231").unwrap();
+        let strike_book = state
+            .manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(expiration)
+            .get_or_create_strike(5_000_000);
+        let option_book = strike_book.get(OptionStyle::Call);
+        state
+            .market_maker
+            .set_strategy("BTC", Some(Arc::new(LayeredStrategy::default())));
+        state.market_maker.update_price("BTC", 5_000_000);
+        let best_ask = option_book.best_ask().expect("maker ask");
+
+        // Larger than the best level: the rest fills one level deeper.
+        let request = MarketOrderRequest {
+            side: OrderSide::Buy,
+            quantity: 12,
+        };
+        let response = submit_market_order(
+            State(state.clone()),
+            Extension(test_claims()),
+            Path((
+                "BTC".to_string(),
+                "20351231".to_string(),
+                5_000_000u64,
+                "call".to_string(),
+            )),
+            Json(request),
+        )
+        .await
+        .expect("filled")
+        .0;
+
+        assert_eq!(response.status, MarketOrderStatus::Filled);
+        assert_eq!(response.fills.len(), 2);
+        assert_eq!(response.fills[0].price, best_ask);
+        assert_eq!(response.fills[0].quantity, 10);
+        assert!(response.fills[1].price > best_ask);
+        assert!(response.average_price.expect("filled") > best_ask as f64);
+    }
+
     // ========================================================================
     // Issue #54: executed fills must be recorded into the four market-data
     // stores on the live paths (market order + crossing limit), so the GET
@@ -5672,6 +7506,7 @@ mod tests {
         // Submit a market BUY for 40; it lifts 40 @ 150 from the resting ask.
         let response = submit_market_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "REC".to_string(),
                 "20251231".to_string(),
@@ -5770,6 +7605,60 @@ mod tests {
         assert_eq!(executions.summary.total_volume, 40);
     }
 
+    #[tokio::test]
+    async fn test_crossing_fill_books_both_accounts() {
+        let state = create_test_state();
+        let path = || {
+            Path((
+                "ACCT".to_string(),
+                "20351231".to_string(),
+                100u64,
+                "call".to_string(),
+            ))
+        };
+        let request = |side, price| {
+            Json(AddOrderRequest {
+                side,
+                price,
+                quantity: 10,
+                time_in_force: Some(ApiTimeInForce::Gtc),
+                expire_at: None,
+            })
+        };
+
+        // Maker rests a sell; a different account crosses it.
+        let _ = add_order(
+            State(state.clone()),
+            Extension(claims_for("maker-acct")),
+            path(),
+            request(OrderSide::Sell, 150),
+        )
+        .await
+        .expect("resting sell accepted");
+        let response = add_order(
+            State(state.clone()),
+            Extension(claims_for("taker-acct")),
+            path(),
+            request(OrderSide::Buy, 150),
+        )
+        .await
+        .expect("crossing buy accepted")
+        .0;
+        assert_eq!(response.filled_quantity, 10);
+
+        let symbol = "ACCT-20351231-100-C";
+        let quantity = |account: &str| {
+            state
+                .account_positions
+                .get(account)
+                .and_then(|book| book.get(symbol).map(|p| p.quantity))
+        };
+        assert_eq!(quantity("taker-acct"), Some(10));
+        assert_eq!(quantity("maker-acct"), Some(-10));
+        // The operator view stays taker-side only.
+        assert_eq!(state.positions.get(symbol).map(|p| p.quantity), Some(10));
+    }
+
     #[tokio::test]
     async fn test_crossing_limit_order_fill_records_all_stores() {
         let state = create_test_state();
@@ -5788,6 +7677,7 @@ mod tests {
         // the marketable portion fills 30 @ 150 (the maker price) as the taker.
         let response = add_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "REC2".to_string(),
                 "20251231".to_string(),
@@ -5899,7 +7789,14 @@ mod tests {
             maker_order_id: "maker-1".to_string(),
         }];
 
-        record_fills(&state, symbol, "REC3", OrderSide::Sell, &fills);
+        record_fills(
+            &state,
+            symbol,
+            "REC3",
+            TEST_ACCOUNT,
+            OrderSide::Sell,
+            &fills,
+        );
 
         // Position: a sell makes the taker short 10 @ 250.
         let position = state.positions.get(symbol).expect("position recorded");
@@ -5921,7 +7818,14 @@ mod tests {
         // Replay the same fills. `executions` is keyed by trade id and
         // `last_trades` is overwritten, so both stay stable; but `positions` and
         // OHLC are additive, so the quantity and bar volume/trade_count double.
-        record_fills(&state, symbol, "REC3", OrderSide::Sell, &fills);
+        record_fills(
+            &state,
+            symbol,
+            "REC3",
+            TEST_ACCOUNT,
+            OrderSide::Sell,
+            &fills,
+        );
 
         assert_eq!(state.executions.len(), 1, "execution is keyed by trade id");
         assert_eq!(state.last_trades.get(symbol).unwrap().quantity, 10);
@@ -6184,8 +8088,16 @@ mod tests {
         };
 
         let (a, b) = tokio::join!(
-            bulk_submit_orders(State(state.clone()), Json(mk_bulk(10))),
-            bulk_submit_orders(State(state.clone()), Json(mk_bulk(10)))
+            bulk_submit_orders(
+                State(state.clone()),
+                Extension(test_claims()),
+                Json(mk_bulk(10))
+            ),
+            bulk_submit_orders(
+                State(state.clone()),
+                Extension(test_claims()),
+                Json(mk_bulk(10))
+            )
         );
         let a = a.expect("bulk A succeeds").0;
         let b = b.expect("bulk B succeeds").0;
@@ -6226,6 +8138,7 @@ mod tests {
         };
         let Json(response) = bulk_submit_orders(
             State(state.clone()),
+            Extension(test_claims()),
             Json(BulkOrderRequest {
                 orders: vec![crossing_buy],
                 atomic: false,
@@ -6627,6 +8540,7 @@ mod tests {
         let order_id = "test-order-123".to_string();
         let order_info = OrderInfo {
             order_id: order_id.clone(),
+            account: TEST_ACCOUNT.to_string(),
             symbol: "AAPL-20251231-150-C".to_string(),
             underlying: "AAPL".to_string(),
             expiration: "20251231".to_string(),
@@ -6690,6 +8604,7 @@ mod tests {
         for i in 0..5 {
             let order_info = OrderInfo {
                 order_id: format!("order-{}", i),
+                account: TEST_ACCOUNT.to_string(),
                 symbol: format!("AAPL-20251231-{}-C", 150 + i * 5),
                 underlying: if i < 3 {
                     "AAPL".to_string()
@@ -6762,6 +8677,7 @@ mod tests {
         for i in 0..10 {
             let order_info = OrderInfo {
                 order_id: format!("order-{}", i),
+                account: TEST_ACCOUNT.to_string(),
                 symbol: format!("AAPL-20251231-{}-C", 150 + i * 5),
                 underlying: "AAPL".to_string(),
                 expiration: "20251231".to_string(),
@@ -7015,6 +8931,30 @@ mod tests {
         assert_eq!(notional, Some(60000));
     }
 
+    #[tokio::test]
+    async fn test_position_pnl_scales_with_the_contract_multiplier() {
+        use crate::contract::ContractSpec;
+
+        let state = create_test_state();
+        state.market_maker.set_contract_spec(
+            "AAPL",
+            ContractSpec {
+                multiplier: 100,
+                ..ContractSpec::default()
+            },
+        );
+        let symbol = "AAPL-20251231-150-C";
+        update_position_on_fill(&state, symbol, "AAPL", OrderSide::Buy, 2, 500, 0);
+        update_position_on_fill(&state, symbol, "AAPL", OrderSide::Sell, 1, 600, 0);
+
+        let position = state.positions.get(symbol).unwrap();
+        assert_eq!(position.multiplier, 100);
+        // One contract of 100 shares closed a dollar higher.
+        assert_eq!(position.realized_pnl, 10_000);
+        assert_eq!(position.unrealized_pnl(700), Some(20_000));
+        assert_eq!(position.notional_value(700), Some(70_000));
+    }
+
     #[tokio::test]
     async fn test_position_short_pnl() {
         let position = PositionInfo::new(
@@ -7574,6 +9514,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_get_quote_history_requires_recorder() {
+        let state = create_test_state();
+
+        let result = get_quote_history(
+            State(state.clone()),
+            Path((
+                "QH1".to_string(),
+                "20251231".to_string(),
+                100,
+                "call".to_string(),
+            )),
+            Query(QuoteHistoryQuery::default()),
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(ApiError::InvalidRequest(ref msg)) if msg.contains("quote_history"))
+        );
+    }
+
     // ========================================================================
     // Order Modification Tests
     // ========================================================================
@@ -7654,6 +9615,7 @@ mod tests {
         // Try to modify with no price or quantity - should fail validation
         let result = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "MOD1".to_string(),
                 "20251231".to_string(),
@@ -7683,6 +9645,7 @@ mod tests {
 
         let result = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "NONEXISTENT".to_string(),
                 "20251231".to_string(),
@@ -7709,6 +9672,7 @@ mod tests {
 
         let result = modify_order(
             State(state.clone()),
+            Extension(test_claims()),
             Path((
                 "MOD2".to_string(),
                 "20251231".to_string(),
@@ -7899,6 +9863,7 @@ mod tests {
 
         let result = bulk_submit_orders(
             State(state.clone()),
+            Extension(test_claims()),
             Json(BulkOrderRequest {
                 orders: vec![],
                 atomic: false,
@@ -7986,9 +9951,13 @@ mod tests {
             atomic: true,
         };
 
-        let Json(response) = bulk_submit_orders(State(state.clone()), Json(request))
-            .await
-            .expect("atomic bulk submit returns a response");
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(request),
+        )
+        .await
+        .expect("atomic bulk submit returns a response");
 
         // The rollback was performed and no order had filled, so nothing is live.
         assert!(response.rolled_back);
@@ -8043,9 +10012,13 @@ mod tests {
             atomic: false,
         };
 
-        let Json(response) = bulk_submit_orders(State(state.clone()), Json(request))
-            .await
-            .expect("non-atomic bulk submit returns a response");
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(request),
+        )
+        .await
+        .expect("non-atomic bulk submit returns a response");
 
         assert!(!response.rolled_back);
         assert_eq!(response.success_count, 2);
@@ -8086,9 +10059,13 @@ mod tests {
         // Sanity: nothing resting before the call.
         assert_eq!(call_order_count(&state, underlying, &exp, 12000), 0);
 
-        let Json(response) = bulk_submit_orders(State(state.clone()), Json(request))
-            .await
-            .expect("atomic bulk submit returns a response");
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(request),
+        )
+        .await
+        .expect("atomic bulk submit returns a response");
 
         assert!(response.rolled_back);
         assert_eq!(call_order_count(&state, underlying, &exp, 12000), 0);
@@ -8156,9 +10133,13 @@ mod tests {
             atomic: true,
         };
 
-        let Json(response) = bulk_submit_orders(State(state.clone()), Json(request))
-            .await
-            .expect("atomic bulk submit returns a response");
+        let Json(response) = bulk_submit_orders(
+            State(state.clone()),
+            Extension(test_claims()),
+            Json(request),
+        )
+        .await
+        .expect("atomic bulk submit returns a response");
 
         // The partially-filled order is un-rollbackable: counted, not a clean
         // rollback.
@@ -8412,6 +10393,7 @@ mod tests {
             underlying: "AAPL".to_string(),
             expiration: "20240329".to_string(),
             spot_price: Some(15000),
+            forward_price: Some(15100),
             atm_strike: Some(15000),
             chain: vec![ChainStrikeRow {
                 strike: 15000,
@@ -8424,6 +10406,7 @@ mod tests {
         assert!(json.contains("\"underlying\":\"AAPL\""));
         assert!(json.contains("\"expiration\":\"20240329\""));
         assert!(json.contains("\"spot_price\":15000"));
+        assert!(json.contains("\"forward_price\":15100"));
         assert!(json.contains("\"atm_strike\":15000"));
         assert!(json.contains("\"chain\""));
     }
@@ -8537,6 +10520,179 @@ mod tests {
         assert_eq!(response.chain[1].strike, 16000);
     }
 
+    /// Seeds two-sided call and put books (10-cent wide around each mid) whose
+    /// mids satisfy put-call parity for `forward` with a unit discount factor.
+    fn seed_parity_books(
+        state: &AppState,
+        underlying: &str,
+        forward: u64,
+        strikes: &[u64],
+    ) -> String {
+        let underlying_book = state.manager.get_or_create(underlying);
+        let exp = parse_expiration("20351231").expect("valid expiration");
+        let exp_book = underlying_book.get_or_create_expiration(exp);
+        for &strike in strikes {
+            let put_mid = 500 + strike.saturating_sub(forward) as u128;
+            let call_mid = put_mid + forward as u128 - strike as u128;
+            let strike_book = exp_book.get_or_create_strike(strike);
+            for (style, mid) in [(OptionStyle::Call, call_mid), (OptionStyle::Put, put_mid)] {
+                let book = strike_book.get(style);
+                book.add_limit_order(OrderId::new(), Side::Buy, mid - 5, 1)
+                    .expect("bid placed");
+                book.add_limit_order(OrderId::new(), Side::Sell, mid + 5, 1)
+                    .expect("ask placed");
+            }
+        }
+        "20351231".to_string()
+    }
+
+    #[tokio::test]
+    async fn test_get_implied_forward_recovers_parity_forward() {
+        let state = create_test_state();
+        let exp_str = seed_parity_books(&state, "FWD1", 15_200, &[14_000, 15_000, 16_000]);
+
+        let response = get_implied_forward(
+            State(state.clone()),
+            Path(("FWD1".to_string(), exp_str)),
+            Query(ForwardQuery {
+                threshold_bps: None,
+            }),
+        )
+        .await
+        .expect("forward resolved")
+        .0;
+
+        assert_eq!(response.strikes_used, 3);
+        assert_eq!(response.forward_price, Some(15_200));
+        let discount = response.discount_factor.expect("discount factor");
+        assert!((discount - 1.0).abs() < 1e-9, "discount {discount}");
+        assert_eq!(response.violations, 0);
+        assert_eq!(response.threshold_bps, DEFAULT_PARITY_THRESHOLD_BPS);
+        assert!(response.strikes.iter().all(|s| s.residual_cents == Some(0)));
+        assert_eq!(response.strikes[1].synthetic_forward, Some(15_200));
+    }
+
+    #[tokio::test]
+    async fn test_get_implied_forward_flags_parity_violation() {
+        let state = create_test_state();
+        let exp_str = seed_parity_books(
+            &state,
+            "FWD2",
+            15_000,
+            &[13_000, 14_000, 15_000, 16_000, 17_000],
+        );
+
+        // Requote the 15_000 call 300 cents higher: C - P is off the line.
+        let exp = parse_expiration(&exp_str).expect("valid expiration");
+        let strike_book = state
+            .manager
+            .get("FWD2")
+            .expect("underlying")
+            .get_expiration(&exp)
+            .expect("expiration")
+            .get_strike(15_000)
+            .expect("strike");
+        let call_book = strike_book.get(OptionStyle::Call);
+        let _ = call_book.cancel_all().expect("seed quotes cancelled");
+        call_book
+            .add_limit_order(OrderId::new(), Side::Buy, 795, 1)
+            .expect("bid placed");
+        call_book
+            .add_limit_order(OrderId::new(), Side::Sell, 805, 1)
+            .expect("ask placed");
+
+        let response = get_implied_forward(
+            State(state.clone()),
+            Path(("FWD2".to_string(), exp_str)),
+            Query(ForwardQuery {
+                threshold_bps: Some(50.0),
+            }),
+        )
+        .await
+        .expect("forward resolved")
+        .0;
+
+        assert_eq!(response.violations, 1);
+        let flagged: Vec<u64> = response
+            .strikes
+            .iter()
+            .filter(|s| s.violation)
+            .map(|s| s.strike)
+            .collect();
+        assert_eq!(flagged, vec![15_000]);
+    }
+
+    #[tokio::test]
+    async fn test_get_implied_forward_without_two_sided_books() {
+        let state = create_test_state();
+        let underlying = state.manager.get_or_create("FWD3");
+        let exp = parse_expiration("20351231").expect("valid expiration");
+        let exp_book = underlying.get_or_create_expiration(exp);
+        exp_book.get_or_create_strike(14_000);
+        exp_book.get_or_create_strike(15_000);
+
+        let response = get_implied_forward(
+            State(state.clone()),
+            Path(("FWD3".to_string(), "20351231".to_string())),
+            Query(ForwardQuery {
+                threshold_bps: None,
+            }),
+        )
+        .await
+        .expect("empty books still answer")
+        .0;
+
+        assert_eq!(response.strikes_used, 0);
+        assert!(response.forward_price.is_none());
+        assert!(response.discount_factor.is_none());
+        assert_eq!(response.strikes.len(), 2);
+        assert!(response.strikes.iter().all(|s| !s.violation));
+    }
+
+    #[tokio::test]
+    async fn test_get_implied_forward_rejects_bad_threshold() {
+        let state = create_test_state();
+        let exp_str = seed_parity_books(&state, "FWD4", 15_000, &[14_000, 16_000]);
+
+        for threshold in [0.0, -1.0, f64::NAN] {
+            let result = get_implied_forward(
+                State(state.clone()),
+                Path(("FWD4".to_string(), exp_str.clone())),
+                Query(ForwardQuery {
+                    threshold_bps: Some(threshold),
+                }),
+            )
+            .await;
+            assert!(
+                matches!(result, Err(ApiError::InvalidRequest(_))),
+                "threshold {threshold} must be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_option_chain_atm_uses_implied_forward() {
+        let state = create_test_state();
+        // Forward 16_100 sits far from any simulator spot: ATM must follow it.
+        let exp_str = seed_parity_books(&state, "FWD5", 16_100, &[14_000, 15_000, 16_000, 17_000]);
+
+        let response = get_option_chain(
+            State(state.clone()),
+            Path(("FWD5".to_string(), exp_str)),
+            Query(ChainQuery {
+                min_strike: Some(15_500),
+                max_strike: None,
+            }),
+        )
+        .await
+        .expect("chain resolved")
+        .0;
+
+        // The forward is fitted over every strike, not the filtered window.
+        assert_eq!(response.forward_price, Some(16_100));
+        assert_eq!(response.atm_strike, Some(16_000));
+    }
+
     // ========================================================================
     // Greeks Tests
     // ========================================================================
@@ -8788,6 +10944,34 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_volatility_surface_history_validates_times_then_requires_database() {
+        let state = create_test_state();
+        let query = |at: &str| VolatilitySurfaceHistoryQuery {
+            at: Some(at.to_string()),
+            ..VolatilitySurfaceHistoryQuery::default()
+        };
+
+        let invalid = get_volatility_surface_history(
+            State(Arc::clone(&state)),
+            Path("BTC".to_string()),
+            Query(query("1700000000000,noon")),
+        )
+        .await;
+        assert!(matches!(invalid, Err(ApiError::InvalidRequest(ref msg)) if msg.contains("noon")));
+
+        let no_db = get_volatility_surface_history(
+            State(Arc::clone(&state)),
+            Path("BTC".to_string()),
+            Query(query("1700000000000, 1700000060000")),
+        )
+        .await;
+        assert!(
+            matches!(no_db, Err(ApiError::InvalidRequest(ref msg)) if msg.contains("database"))
+        );
+        assert_eq!(record_volatility_surfaces(&state).await, 0);
+    }
+
     /// `derive_iv` must recover the volatility a Black-Scholes price was
     /// generated with (issue #56): price an option at a known IV via
     /// optionstratlib, round the price to whole cents like a real book mid,
@@ -8916,6 +11100,7 @@ mod tests {
             timestamp_ms: 1709123456789,
             expirations: vec!["20240329".to_string()],
             strikes: vec![14000, 15000, 16000],
+            forwards: HashMap::from([("20240329".to_string(), 15100)]),
             surface,
             atm_term_structure: vec![ATMTermStructurePoint {
                 expiration: "20240329".to_string(),
@@ -8929,6 +11114,7 @@ mod tests {
         assert!(json.contains("\"spot_price\":15000"));
         assert!(json.contains("\"expirations\""));
         assert!(json.contains("\"strikes\""));
+        assert!(json.contains("\"forwards\":{\"20240329\":15100}"));
         assert!(json.contains("\"surface\""));
         assert!(json.contains("\"atm_term_structure\""));
     }
@@ -9073,6 +11259,7 @@ mod tests {
                 secret: "anything".to_string(),
                 permissions: vec![Permission::Read],
                 ttl_secs: None,
+                account: None,
             }),
         )
         .await;
@@ -9094,6 +11281,7 @@ mod tests {
                 secret: "wrong-secret".to_string(),
                 permissions: vec![Permission::Read],
                 ttl_secs: None,
+                account: None,
             }),
         )
         .await;
@@ -9116,6 +11304,7 @@ mod tests {
                 secret: "correct-secret".to_string(),
                 permissions: vec![Permission::Read, Permission::Trade],
                 ttl_secs: Some(120),
+                account: None,
             }),
         )
         .await
@@ -9145,6 +11334,7 @@ mod tests {
                 secret: "correct-secret".to_string(),
                 permissions: vec![],
                 ttl_secs: None,
+                account: None,
             }),
         )
         .await;
@@ -9152,6 +11342,42 @@ mod tests {
         assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
     }
 
+    #[tokio::test]
+    async fn test_issue_token_for_account_sets_subject() {
+        use crate::models::{Permission, TokenRequest};
+
+        let mut state = (*create_test_state()).clone();
+        state.bootstrap_secret = Some("correct-secret".to_string());
+        let auth = Arc::clone(&state.auth);
+        let state = Arc::new(state);
+
+        let response = issue_token(
+            State(state.clone()),
+            Json(TokenRequest {
+                secret: "correct-secret".to_string(),
+                permissions: vec![Permission::Trade],
+                ttl_secs: None,
+                account: Some("desk-7".to_string()),
+            }),
+        )
+        .await
+        .expect("token issued");
+        let claims = auth.verify_token(&response.token).expect("verify");
+        assert_eq!(claims.account(), "desk-7");
+
+        let invalid = issue_token(
+            State(state),
+            Json(TokenRequest {
+                secret: "correct-secret".to_string(),
+                permissions: vec![Permission::Trade],
+                ttl_secs: None,
+                account: Some("not a valid id".to_string()),
+            }),
+        )
+        .await;
+        assert!(matches!(invalid, Err(ApiError::InvalidRequest(_))));
+    }
+
     // ========================================================================
     // Orderbook Metrics Tests
     // ========================================================================
@@ -9851,6 +12077,7 @@ mod tests {
             orderbooks_saved: 150,
             orders_saved: 5000,
             orderbooks_failed: 2,
+            persisted: false,
             timestamp_ms: 1704067200000,
         };
 
@@ -9864,12 +12091,14 @@ mod tests {
 
     #[test]
     fn test_snapshot_summary_serialization() {
-        use crate::models::SnapshotSummary;
+        use crate::models::{SnapshotSummary, SnapshotTrigger};
 
         let summary = SnapshotSummary {
             snapshot_id: "snap-abc".to_string(),
             orderbook_count: 100,
             total_orders: 2500,
+            success: false,
+            trigger: SnapshotTrigger::Scheduled,
             created_at: 1704067200000,
         };
 
@@ -9877,17 +12106,21 @@ mod tests {
         assert!(json.contains("\"snapshot_id\":\"snap-abc\""));
         assert!(json.contains("\"orderbook_count\":100"));
         assert!(json.contains("\"total_orders\":2500"));
+        assert!(json.contains("\"success\":false"));
+        assert!(json.contains("\"trigger\":\"scheduled\""));
     }
 
     #[test]
     fn test_snapshots_list_response_serialization() {
-        use crate::models::{SnapshotSummary, SnapshotsListResponse};
+        use crate::models::{SnapshotSummary, SnapshotTrigger, SnapshotsListResponse};
 
         let response = SnapshotsListResponse {
             snapshots: vec![SnapshotSummary {
                 snapshot_id: "snap-1".to_string(),
                 orderbook_count: 50,
                 total_orders: 1000,
+                success: true,
+                trigger: SnapshotTrigger::Manual,
                 created_at: 1704067200000,
             }],
             total: 1,
@@ -9908,6 +12141,8 @@ mod tests {
             orderbooks_restored: 75,
             orders_restored: 1500,
             orderbooks_failed: 0,
+            state_restored: true,
+            journal_seq: 42,
             timestamp_ms: 1704067200000,
         };
 
@@ -9917,6 +12152,8 @@ mod tests {
         assert!(json.contains("\"orderbooks_restored\":75"));
         assert!(json.contains("\"orders_restored\":1500"));
         assert!(json.contains("\"orderbooks_failed\":0"));
+        assert!(json.contains("\"state_restored\":true"));
+        assert!(json.contains("\"journal_seq\":42"));
     }
 
     #[tokio::test]
@@ -9987,4 +12224,355 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    /// A state whose snapshots are written to `dir`, keeping at most
+    /// `max_count` of them.
+    fn state_with_snapshot_dir(dir: &std::path::Path, max_count: usize) -> Arc<AppState> {
+        let mut config = crate::config::Config::default();
+        config.snapshots.max_count = max_count;
+        let mut state = AppState::new();
+        state.config = Some(config);
+        state.snapshot_store = Some(crate::snapshots::SnapshotStore::Directory(
+            dir.to_path_buf(),
+        ));
+        Arc::new(state)
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_survive_a_restart_in_the_snapshot_directory() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let state = state_with_snapshot_dir(&dir, 16);
+        submit_tracked_gtc_order(&state).await;
+        let Json(created) = create_snapshot(State(state)).await;
+        assert!(created.persisted);
+        assert_eq!(created.orders_saved, 1);
+
+        // A fresh process sees the snapshot only through the directory.
+        let restarted = state_with_snapshot_dir(&dir, 16);
+        let Json(list) = list_snapshots(State(restarted.clone())).await;
+        assert_eq!(list.total, 1);
+        assert_eq!(list.snapshots[0].snapshot_id, created.snapshot_id);
+        let Json(infos) = get_snapshot(State(restarted.clone()), Path(created.snapshot_id.clone()))
+            .await
+            .expect("loaded from the directory");
+        assert_eq!(infos.len(), 1);
+
+        let restored = restore_latest_snapshot(&restarted)
+            .await
+            .expect("directory readable")
+            .expect("a snapshot to restore");
+        assert_eq!(restored.snapshot_id, created.snapshot_id);
+        assert_eq!(restored.orders_restored, 1);
+        let book = restarted
+            .manager
+            .get("TEST")
+            .expect("underlying restored")
+            .get_expiration(&parse_expiration("20251231").expect("valid"))
+            .expect("expiration restored")
+            .get_strike(100)
+            .expect("strike restored");
+        let bids = book
+            .get(OptionStyle::Call)
+            .inner()
+            .create_snapshot(usize::MAX)
+            .bids;
+        assert_eq!(bids.len(), 1, "the resting bid is back in the book");
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_durable_snapshot_compacts_the_journal() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("journal.log");
+        let mut config = crate::config::Config::default();
+        config.snapshots.restore_on_startup = true;
+        let mut state = AppState::new();
+        state.config = Some(config);
+        state.snapshot_store = Some(crate::snapshots::SnapshotStore::Directory(dir.clone()));
+        let state = Arc::new(state);
+        let (journal, _) =
+            crate::journal::Journal::open(&path, &crate::config::JournalConfig::default(), 0)
+                .expect("open");
+        state.set_journal(Arc::new(journal));
+
+        for price_cents in [10_000_000, 10_100_000, 10_200_000] {
+            let event = JournalEvent::Price {
+                symbol: "BTC".to_string(),
+                price_cents,
+            };
+            let applied = state.apply_journaled(event, || {
+                state.market_maker.update_price("BTC", price_cents);
+            });
+            applied.await.expect("journaled");
+        }
+        let Json(created) = create_snapshot(State(Arc::clone(&state))).await;
+        assert!(created.persisted);
+
+        // Only the entry the snapshot was taken at is left, to carry the
+        // sequence on.
+        let left = crate::journal::Journal::read_after(&path, 0).expect("read");
+        assert_eq!(
+            left.iter().map(|entry| entry.seq).collect::<Vec<_>>(),
+            vec![3]
+        );
+        let restored = restore_latest_snapshot(&state)
+            .await
+            .expect("directory readable")
+            .expect("a snapshot to restore");
+        assert_eq!(restored.journal_seq, 3);
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_durable_snapshots_are_pruned_to_the_retention_count() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let state = state_with_snapshot_dir(&dir, 2);
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            ids.push(create_snapshot(State(state.clone())).await.0.snapshot_id);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        let Json(list) = list_snapshots(State(state_with_snapshot_dir(&dir, 2))).await;
+        let listed: Vec<&str> = list
+            .snapshots
+            .iter()
+            .map(|s| s.snapshot_id.as_str())
+            .collect();
+        assert_eq!(listed, vec![ids[2].as_str(), ids[1].as_str()]);
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    fn resting_bid_orders(state: &AppState) -> usize {
+        state
+            .manager
+            .get("TEST")
+            .expect("underlying exists")
+            .get_expiration(&parse_expiration("20251231").expect("valid"))
+            .expect("expiration exists")
+            .get_strike(100)
+            .expect("strike exists")
+            .get(OptionStyle::Call)
+            .inner()
+            .create_snapshot(usize::MAX)
+            .bids
+            .iter()
+            .map(|level| level.orders().len())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_whole_state_restore_rolls_back_orders_with_the_books() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let state = state_with_snapshot_dir(&dir, 16);
+        let (order_id, _) = submit_tracked_gtc_order(&state).await;
+        let Json(created) = create_snapshot(State(state.clone())).await;
+
+        // An order placed after the snapshot leaves the book and the order
+        // index together.
+        let (later_id, _) = submit_tracked_gtc_order(&state).await;
+        assert_eq!(resting_bid_orders(&state), 2);
+        let Json(restored) =
+            restore_snapshot(State(state.clone()), Path(created.snapshot_id.clone()))
+                .await
+                .expect("snapshot found");
+        assert!(restored.success);
+        assert!(restored.state_restored);
+        assert_eq!(resting_bid_orders(&state), 1);
+        assert!(state.orders.contains_key(&order_id));
+        assert!(!state.orders.contains_key(&later_id));
+
+        // A fresh process gets the order index back from the directory, so
+        // status lookups of restored orders keep working.
+        let restarted = state_with_snapshot_dir(&dir, 16);
+        let restored = restore_latest_snapshot(&restarted)
+            .await
+            .expect("directory readable")
+            .expect("a snapshot to restore");
+        assert!(restored.state_restored);
+        assert_eq!(resting_bid_orders(&restarted), 1);
+        let order = restarted.orders.get(&order_id).expect("order restored");
+        assert_eq!(order.status, OrderStatus::Active);
+        assert_eq!(order.remaining_quantity, 10);
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_whole_state_restore_brings_back_the_ledger_and_risk_books() {
+        use crate::api::liquidation::{Liquidation, LiquidationStatus};
+        use crate::ledger::{EntryKind, LedgerAccount, Posting};
+        use crate::market_maker::{HedgeParams, InventoryPosition};
+
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let state = state_with_snapshot_dir(&dir, 16);
+        let symbol = "TEST-20351231-100-C";
+        let deposit = |state: &AppState, cents: i64| {
+            state
+                .ledger
+                .post(
+                    EntryKind::Deposit,
+                    "deposit",
+                    vec![
+                        Posting::new(LedgerAccount::Cash("alice".to_string()), cents),
+                        Posting::new(LedgerAccount::External, -cents),
+                    ],
+                    1,
+                )
+                .expect("balanced");
+        };
+        let transfer = |state: &AppState, quantity: i64| {
+            state.market_maker.take_inventory(InventoryPosition {
+                instrument: symbol.to_string(),
+                underlying: "TEST".to_string(),
+                expiration: parse_expiration("20351231").expect("valid"),
+                strike: 100,
+                style: OptionStyle::Call,
+                quantity,
+                multiplier: 1,
+            });
+        };
+        state.market_maker.set_hedge_params(HedgeParams {
+            enabled: true,
+            delta_band: 0.0,
+            lot_size: 0.01,
+        });
+        state.market_maker.update_price("TEST", 100);
+
+        deposit(&state, 100_000);
+        update_account_position_on_fill(&state, "alice", symbol, "TEST", OrderSide::Buy, 3, 40, 1);
+        state.collateral.insert("alice".to_string(), 5_000);
+        state.liquidations.insert(
+            "bob".to_string(),
+            Liquidation {
+                account: "bob".to_string(),
+                status: LiquidationStatus::Liquidating,
+                started_at_ms: 1,
+                completed_at_ms: None,
+                steps: vec![],
+            },
+        );
+        transfer(&state, -4);
+        let hedges = state.market_maker.hedge_trades(10);
+        assert!(!hedges.is_empty(), "the transfer was hedged");
+        let pnl_started_ms = state.market_maker.pnl_started_ms();
+        let Json(created) = create_snapshot(State(state.clone())).await;
+
+        // Everything moves on after the snapshot.
+        deposit(&state, 7_000);
+        update_account_position_on_fill(&state, "alice", symbol, "TEST", OrderSide::Buy, 2, 40, 2);
+        state.collateral.insert("alice".to_string(), 9_000);
+        state.liquidations.clear();
+        transfer(&state, 4);
+
+        // A fresh process gets every store back from the directory.
+        let restarted = state_with_snapshot_dir(&dir, 16);
+        let restored = restore_latest_snapshot(&restarted)
+            .await
+            .expect("directory readable")
+            .expect("a snapshot to restore");
+        assert_eq!(restored.snapshot_id, created.snapshot_id);
+        assert!(restored.state_restored);
+
+        let alice = restarted.account_positions.get("alice").expect("book");
+        assert_eq!(alice.get(symbol).expect("position").quantity, 3);
+        drop(alice);
+        assert_eq!(restarted.ledger.cash_balance("alice"), 100_000);
+        assert_eq!(restarted.ledger.len(), 1);
+        assert_eq!(restarted.ledger.trial_balance(), 0);
+        assert_eq!(restarted.collateral.get("alice").map(|c| *c), Some(5_000));
+        assert_eq!(
+            restarted.liquidations.get("bob").map(|l| l.status),
+            Some(LiquidationStatus::Liquidating)
+        );
+        let inventory = restarted.market_maker.inventory();
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].quantity, -4);
+        assert_eq!(restarted.market_maker.hedge_trades(10), hedges);
+        assert_eq!(restarted.market_maker.pnl_started_ms(), pnl_started_ms);
+
+        // Restoring into the live state rolls the later changes back too.
+        let Json(restored) = restore_snapshot(State(state.clone()), Path(created.snapshot_id))
+            .await
+            .expect("snapshot found");
+        assert!(restored.state_restored);
+        assert_eq!(state.ledger.cash_balance("alice"), 100_000);
+        assert_eq!(state.market_maker.inventory()[0].quantity, -4);
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_whole_state_restore_is_all_or_nothing() {
+        let state = Arc::new(AppState::new());
+        let (order_id, _) = submit_tracked_gtc_order(&state).await;
+        let mut snapshot = StoredSnapshot::new(
+            1,
+            vec![OrderbookSnapshotInfo {
+                snapshot_id: "corrupt".to_string(),
+                underlying: "TEST".to_string(),
+                expiration: "20251231".to_string(),
+                strike: 100,
+                style: "call".to_string(),
+                order_count: 1,
+                bid_levels: 1,
+                ask_levels: 0,
+                data: "not json".to_string(),
+                created_at: 1,
+            }],
+        );
+        snapshot.engine = Some(crate::snapshots::EngineState::default());
+
+        let restored = restore_stored_snapshot(&state, "corrupt".to_string(), snapshot);
+        assert!(!restored.success);
+        assert!(!restored.state_restored);
+        assert_eq!(restored.orderbooks_failed, 1);
+        assert_eq!(resting_bid_orders(&state), 1, "the book is untouched");
+        assert!(
+            state.orders.contains_key(&order_id),
+            "the index is untouched"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_automatic_snapshots_record_their_trigger() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        let state = state_with_snapshot_dir(&dir, 16);
+        submit_tracked_gtc_order(&state).await;
+        let scheduled = take_snapshot(&state, SnapshotTrigger::Scheduled).await;
+        assert!(scheduled.success);
+        assert!(scheduled.persisted);
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        let shutdown = take_snapshot(&state, SnapshotTrigger::Shutdown).await;
+
+        // The trigger and status come back from memory and from the directory.
+        for state in [state.clone(), state_with_snapshot_dir(&dir, 16)] {
+            let Json(list) = list_snapshots(State(state)).await;
+            let listed: Vec<(&str, SnapshotTrigger, bool)> = list
+                .snapshots
+                .iter()
+                .map(|s| (s.snapshot_id.as_str(), s.trigger, s.success))
+                .collect();
+            assert_eq!(
+                listed,
+                vec![
+                    (
+                        shutdown.snapshot_id.as_str(),
+                        SnapshotTrigger::Shutdown,
+                        true
+                    ),
+                    (
+                        scheduled.snapshot_id.as_str(),
+                        SnapshotTrigger::Scheduled,
+                        true
+                    ),
+                ]
+            );
+        }
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
 }
@@ -0,0 +1,510 @@
+//! Liquidation of accounts whose equity falls below maintenance margin.
+//!
+//! An account's equity is its pledged collateral plus its ledger cash plus the
+//! mark-to-model value of its option positions; its maintenance requirement is
+//! the risk-array margin of [`crate::api::margin`], which revalues the
+//! positions with the quoter's pricer under adverse price and vol moves.
+//!
+//! When equity drops below maintenance, a sweep cancels the account's resting
+//! orders, blocks risk-increasing orders (see
+//! [`check_order_margin`](crate::api::margin::check_order_margin)) and works
+//! down its positions, shorts and the largest first: each is closed with an
+//! IOC order priced off the model value, and whatever the books cannot absorb
+//! is transferred to the market maker at a penalty. Positions are closed until
+//! equity covers maintenance again. Every step is logged, kept on the
+//! account's [`Liquidation`] record and pushed to the account's WebSocket
+//! connections.
+
+use crate::api::handlers::{
+    cancel_account_orders, submit_liquidation_order, update_account_position_on_fill,
+};
+use crate::api::margin::{pledged_collateral, refresh_account_margin};
+use crate::api::risk::{account_risk_positions, spot_price, to_cents};
+use crate::api::websocket::{AccountEvent, WsMessage};
+use crate::auth::Claims;
+use crate::clock;
+use crate::config::LiquidationConfig;
+use crate::error::{ApiError, ErrorResponse};
+use crate::ledger::{EntryKind, LedgerAccount, Posting};
+use crate::market_maker::InventoryPosition;
+use crate::models::OrderSide;
+use crate::risk::{RiskPosition, ScenarioBook};
+use crate::state::AppState;
+use axum::extract::State;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[cfg(test)]
+mod tests;
+
+// ============================================================================
+// Request/Response DTOs
+// ============================================================================
+
+/// Whether a liquidation is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidationStatus {
+    /// Positions are being worked down; risk-increasing orders are blocked.
+    Liquidating,
+    /// Equity covers maintenance again, or no position is left.
+    Completed,
+}
+
+/// What a liquidation step did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidationAction {
+    /// Equity fell below maintenance margin.
+    MarginBreach,
+    /// The account's resting orders were canceled.
+    OrdersCanceled,
+    /// A position was reduced by an IOC order against the book.
+    PositionReduced,
+    /// A position was transferred to the market maker.
+    TransferredToMarketMaker,
+    /// The liquidation finished.
+    Completed,
+}
+
+/// One step of a liquidation.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiquidationStep {
+    /// What the step did.
+    pub action: LiquidationAction,
+    /// Instrument traded, for position steps.
+    pub instrument: Option<String>,
+    /// Side the account traded, for position steps.
+    pub side: Option<OrderSide>,
+    /// Contracts traded, or orders canceled.
+    pub quantity: u64,
+    /// Average price in cents, for position steps.
+    pub price: Option<u128>,
+    /// Account equity after the step, in cents.
+    pub equity: i64,
+    /// Maintenance margin after the step, in cents.
+    pub maintenance_margin: i64,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Liquidation record of an account.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Liquidation {
+    /// Account identifier.
+    pub account: String,
+    /// Current status.
+    pub status: LiquidationStatus,
+    /// When the breach was detected, in milliseconds.
+    pub started_at_ms: u64,
+    /// When the liquidation finished, in milliseconds.
+    pub completed_at_ms: Option<u64>,
+    /// Steps taken, oldest first.
+    pub steps: Vec<LiquidationStep>,
+}
+
+/// Liquidations of every account.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LiquidationsResponse {
+    /// Latest liquidation per account, most recent first.
+    pub liquidations: Vec<Liquidation>,
+}
+
+// ============================================================================
+// Liquidation Engine
+// ============================================================================
+
+/// The `[risk.liquidation]` settings, or the defaults when running without
+/// config.
+fn liquidation_config(state: &AppState) -> LiquidationConfig {
+    state
+        .config
+        .as_ref()
+        .map(|c| c.risk.liquidation.clone())
+        .unwrap_or_default()
+}
+
+/// Equity and maintenance margin of an account, in cents.
+#[derive(Debug, Clone, Copy)]
+struct Assessment {
+    equity: f64,
+    maintenance: f64,
+}
+
+impl Assessment {
+    fn breached(&self) -> bool {
+        self.equity < self.maintenance
+    }
+}
+
+/// Values `account`: pledged collateral plus cash plus the model value of its
+/// positions, against a fresh maintenance margin.
+fn assess(state: &AppState, account: &str) -> Assessment {
+    let margin = refresh_account_margin(state, account);
+    let (positions, _) = account_risk_positions(state, account, None);
+    let book = ScenarioBook::new(state.market_maker.pricer(), positions, |u| {
+        spot_price(state, u)
+    });
+    Assessment {
+        equity: pledged_collateral(state, account) as f64
+            + state.ledger.cash_balance(account) as f64
+            + book.base_value_cents(),
+        maintenance: margin.maintenance_cents,
+    }
+}
+
+/// Whether `account` is being liquidated.
+#[must_use]
+pub fn is_liquidating(state: &AppState, account: &str) -> bool {
+    state
+        .liquidations
+        .get(account)
+        .is_some_and(|l| l.status == LiquidationStatus::Liquidating)
+}
+
+/// Appends a step to `account`'s liquidation, logs it and pushes it to the
+/// account's WebSocket connections.
+fn record_step(
+    state: &AppState,
+    account: &str,
+    action: LiquidationAction,
+    trade: Option<(&str, OrderSide, u128)>,
+    quantity: u64,
+    assessment: Assessment,
+) {
+    let step = LiquidationStep {
+        action,
+        instrument: trade.map(|(instrument, _, _)| instrument.to_string()),
+        side: trade.map(|(_, side, _)| side),
+        quantity,
+        price: trade.map(|(_, _, price)| price),
+        equity: to_cents(assessment.equity),
+        maintenance_margin: to_cents(assessment.maintenance),
+        timestamp_ms: clock::now_ms(),
+    };
+    tracing::warn!(
+        account,
+        action = ?step.action,
+        instrument = step.instrument.as_deref().unwrap_or(""),
+        quantity,
+        equity = step.equity,
+        maintenance_margin = step.maintenance_margin,
+        "liquidation step"
+    );
+
+    let Some(mut liquidation) = state.liquidations.get_mut(account) else {
+        return;
+    };
+    if action == LiquidationAction::Completed {
+        liquidation.status = LiquidationStatus::Completed;
+        liquidation.completed_at_ms = Some(step.timestamp_ms);
+    }
+    liquidation.steps.push(step.clone());
+    let status = liquidation.status;
+    drop(liquidation);
+
+    state
+        .orderbook_subscriptions
+        .broadcast_account_event(AccountEvent {
+            account: account.to_string(),
+            message: WsMessage::Liquidation {
+                account: account.to_string(),
+                status,
+                step,
+            },
+        });
+}
+
+/// `price` moved `pct` percent against the liquidated account: down when it
+/// sells, up when it buys. Never below one cent.
+fn adverse_price(theo_cents: f64, side: OrderSide, pct: f64) -> u128 {
+    let price = match side {
+        OrderSide::Sell => (theo_cents * (1.0 - pct / 100.0)).floor(),
+        OrderSide::Buy => (theo_cents * (1.0 + pct / 100.0)).ceil(),
+    };
+    price.max(1.0) as u128
+}
+
+/// Transfers `quantity` contracts of `position` from `account` to the market
+/// maker at `price` cents per unit of the underlying. The premium (price ×
+/// quantity × contract multiplier) moves between the account's cash and
+/// [`LedgerAccount::Clearing`], the market maker's side of the ledger.
+fn transfer_to_market_maker(
+    state: &AppState,
+    account: &str,
+    position: &RiskPosition,
+    side: OrderSide,
+    quantity: u64,
+    price: u128,
+) -> bool {
+    let timestamp_ms = clock::now_ms();
+    let Some(premium) = price
+        .checked_mul(u128::from(quantity))
+        .and_then(|p| p.checked_mul(u128::from(position.multiplier)))
+        .and_then(|p| i64::try_from(p).ok())
+    else {
+        tracing::warn!(instrument = %position.instrument, "transfer premium overflows; skipped");
+        return false;
+    };
+    let cash = match side {
+        OrderSide::Sell => premium,
+        OrderSide::Buy => -premium,
+    };
+    if cash != 0
+        && let Err(e) = state.ledger.post(
+            EntryKind::Trade,
+            format!("liquidation:{}", position.instrument),
+            vec![
+                Posting::new(LedgerAccount::Cash(account.to_string()), cash),
+                Posting::new(LedgerAccount::Clearing, -cash),
+            ],
+            timestamp_ms,
+        )
+    {
+        tracing::warn!(instrument = %position.instrument, error = %e, "transfer not posted");
+        return false;
+    }
+
+    update_account_position_on_fill(
+        state,
+        account,
+        &position.instrument,
+        &position.underlying,
+        side,
+        quantity,
+        price,
+        timestamp_ms,
+    );
+    let signed = quantity as i64;
+    state.market_maker.take_inventory(InventoryPosition {
+        instrument: position.instrument.clone(),
+        underlying: position.underlying.clone(),
+        expiration: position.expiration,
+        strike: position.strike,
+        style: position.style,
+        // The market maker takes the other side of the account's trade.
+        quantity: match side {
+            OrderSide::Sell => signed,
+            OrderSide::Buy => -signed,
+        },
+        multiplier: position.multiplier,
+    });
+    true
+}
+
+/// Closes `position` of `account`: an IOC order against the book first, the
+/// remainder transferred to the market maker when configured.
+fn close_position(
+    state: &AppState,
+    account: &str,
+    position: &RiskPosition,
+    config: &LiquidationConfig,
+) {
+    let Some(spot) = spot_price(state, &position.underlying) else {
+        tracing::warn!(instrument = %position.instrument, "cannot liquidate: no spot price");
+        return;
+    };
+    let theo_cents = state.market_maker.pricer().theoretical_value(
+        spot as f64 / 100.0,
+        position.strike as f64 / 100.0,
+        &position.expiration,
+        position.style,
+        None,
+    ) * 100.0;
+    if !theo_cents.is_finite() {
+        tracing::warn!(instrument = %position.instrument, "cannot liquidate: no model price");
+        return;
+    }
+
+    let side = if position.quantity > 0 {
+        OrderSide::Sell
+    } else {
+        OrderSide::Buy
+    };
+    let quantity = position.quantity.unsigned_abs();
+    let limit = adverse_price(theo_cents, side, config.max_slippage_pct);
+    let filled = match submit_liquidation_order(state, account, position, side, limit, quantity) {
+        Ok((filled, premium)) => {
+            if filled > 0 {
+                record_step(
+                    state,
+                    account,
+                    LiquidationAction::PositionReduced,
+                    Some((&position.instrument, side, premium / u128::from(filled))),
+                    filled,
+                    assess(state, account),
+                );
+            }
+            filled
+        }
+        Err(e) => {
+            tracing::warn!(instrument = %position.instrument, error = %e, "liquidation order failed");
+            0
+        }
+    };
+
+    let remaining = quantity.saturating_sub(filled);
+    if remaining > 0 && config.transfer_to_market_maker {
+        let price = adverse_price(theo_cents, side, config.penalty_pct);
+        if transfer_to_market_maker(state, account, position, side, remaining, price) {
+            record_step(
+                state,
+                account,
+                LiquidationAction::TransferredToMarketMaker,
+                Some((&position.instrument, side, price)),
+                remaining,
+                assess(state, account),
+            );
+        }
+    }
+}
+
+/// Runs one liquidation pass over `account`. Returns whether the account is
+/// (or was) under liquidation in this pass.
+fn liquidate_account(state: &AppState, account: &str, config: &LiquidationConfig) -> bool {
+    let mut assessment = assess(state, account);
+    if !is_liquidating(state, account) {
+        if !assessment.breached() {
+            return false;
+        }
+        state.liquidations.insert(
+            account.to_string(),
+            Liquidation {
+                account: account.to_string(),
+                status: LiquidationStatus::Liquidating,
+                started_at_ms: clock::now_ms(),
+                completed_at_ms: None,
+                steps: Vec::new(),
+            },
+        );
+        record_step(
+            state,
+            account,
+            LiquidationAction::MarginBreach,
+            None,
+            0,
+            assessment,
+        );
+        let canceled = cancel_account_orders(state, account);
+        record_step(
+            state,
+            account,
+            LiquidationAction::OrdersCanceled,
+            None,
+            canceled as u64,
+            assessment,
+        );
+    }
+
+    let (mut positions, _) = account_risk_positions(state, account, None);
+    positions.sort_by_key(|p| {
+        (
+            p.quantity > 0,
+            Reverse(p.quantity.unsigned_abs()),
+            p.instrument.clone(),
+        )
+    });
+    for position in &positions {
+        if !assessment.breached() {
+            break;
+        }
+        close_position(state, account, position, config);
+        assessment = assess(state, account);
+    }
+
+    let flat = account_risk_positions(state, account, None).0.is_empty();
+    if !assessment.breached() || flat {
+        record_step(
+            state,
+            account,
+            LiquidationAction::Completed,
+            None,
+            0,
+            assessment,
+        );
+    }
+    true
+}
+
+/// Checks every account holding positions (and every account still under
+/// liquidation) and liquidates those below maintenance margin. Returns the
+/// number of accounts under liquidation in this sweep.
+///
+/// Each account is liquidated under its account guard, so orders and
+/// withdrawals of the account never interleave with its liquidation.
+pub async fn run_liquidations(state: &AppState) -> usize {
+    let config = liquidation_config(state);
+    let mut accounts: Vec<String> = state
+        .account_positions
+        .iter()
+        .filter(|book| book.value().iter().any(|p| p.quantity != 0))
+        .map(|book| book.key().clone())
+        .chain(
+            state
+                .liquidations
+                .iter()
+                .filter(|l| l.status == LiquidationStatus::Liquidating)
+                .map(|l| l.key().clone()),
+        )
+        .collect();
+    accounts.sort();
+    accounts.dedup();
+    let mut liquidating = 0;
+    for account in &accounts {
+        let _account_guard = state.account_guard(account).await;
+        if liquidate_account(state, account, &config) {
+            liquidating += 1;
+        }
+    }
+    liquidating
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Latest liquidation of the caller's account.
+///
+/// While the status is `liquidating`, orders that would increase the
+/// account's margin are rejected.
+#[utoipa::path(
+    get,
+    path = "/api/v1/risk/liquidation",
+    responses(
+        (status = 200, description = "Latest liquidation", body = Liquidation),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse),
+        (status = 404, description = "The account was never liquidated", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn get_liquidation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Liquidation>, ApiError> {
+    state
+        .liquidations
+        .get(claims.account())
+        .map(|l| Json(l.clone()))
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("no liquidation for account {}", claims.account()))
+        })
+}
+
+/// Latest liquidation of every account, most recent first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/liquidations",
+    responses(
+        (status = 200, description = "Liquidations", body = LiquidationsResponse),
+        (status = 403, description = "Admin permission required", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn list_liquidations(State(state): State<Arc<AppState>>) -> Json<LiquidationsResponse> {
+    let mut liquidations: Vec<Liquidation> = state.liquidations.iter().map(|l| l.clone()).collect();
+    liquidations.sort_by_key(|l| Reverse(l.started_at_ms));
+    Json(LiquidationsResponse { liquidations })
+}
@@ -0,0 +1,250 @@
+//! Unit tests for the liquidation module.
+
+use super::*;
+use crate::api::test_support::{btc_state, claims_for, limit_order};
+use crate::config::Config;
+use crate::models::OrderStatus;
+
+const CALL: &str = "BTC-20351231-5000000-C";
+
+/// A state with BTC at $50,000 and the given liquidation settings.
+fn liquidation_state(transfer_to_market_maker: bool) -> Arc<AppState> {
+    let mut config = Config::default();
+    config.risk.liquidation.transfer_to_market_maker = transfer_to_market_maker;
+    btc_state(config)
+}
+
+/// Model value of the BTC call in cents.
+fn call_theo(state: &AppState) -> f64 {
+    let (positions, _) = account_risk_positions(state, "alice", None);
+    let position = positions.first().expect("alice holds the call");
+    state.market_maker.pricer().theoretical_value(
+        50_000.0,
+        50_000.0,
+        &position.expiration,
+        position.style,
+        None,
+    ) * 100.0
+}
+
+fn short_calls(state: &AppState, account: &str, quantity: u64) {
+    update_account_position_on_fill(state, account, CALL, "BTC", OrderSide::Sell, quantity, 1, 0);
+}
+
+fn actions(state: &AppState, account: &str) -> Vec<LiquidationAction> {
+    state
+        .liquidations
+        .get(account)
+        .expect("liquidation recorded")
+        .steps
+        .iter()
+        .map(|s| s.action)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_liquidation_cancels_orders_trades_and_transfers_the_rest() {
+    let state = liquidation_state(true);
+    let mut events = state.orderbook_subscriptions.subscribe_account_events();
+    limit_order(&state, "alice", OrderSide::Sell, 9_000_000, 1)
+        .await
+        .expect("ask rests");
+    short_calls(&state, "alice", 5);
+    let theo = call_theo(&state);
+    // Bob can carry the short he sells into the liquidation.
+    state.collateral.insert("bob".to_string(), 10_000_000_000);
+    limit_order(&state, "bob", OrderSide::Sell, theo.round() as u128, 2)
+        .await
+        .expect("liquidity rests");
+
+    assert_eq!(run_liquidations(&state).await, 1);
+
+    assert_eq!(
+        actions(&state, "alice"),
+        vec![
+            LiquidationAction::MarginBreach,
+            LiquidationAction::OrdersCanceled,
+            LiquidationAction::PositionReduced,
+            LiquidationAction::TransferredToMarketMaker,
+            LiquidationAction::Completed,
+        ]
+    );
+    let liquidation = state.liquidations.get("alice").expect("record").clone();
+    assert_eq!(liquidation.status, LiquidationStatus::Completed);
+    assert!(liquidation.completed_at_ms.is_some());
+    assert_eq!(liquidation.steps[1].quantity, 1);
+    let reduced = &liquidation.steps[2];
+    assert_eq!(reduced.quantity, 2);
+    assert_eq!(reduced.side, Some(OrderSide::Buy));
+    assert_eq!(reduced.price, Some(theo.round() as u128));
+    let transferred = &liquidation.steps[3];
+    assert_eq!(transferred.quantity, 3);
+    assert_eq!(
+        transferred.price,
+        Some((theo * 1.1).ceil() as u128),
+        "transfers pay the penalty"
+    );
+
+    // Alice is flat, her resting ask is gone and the market maker holds the
+    // transferred short.
+    assert!(account_risk_positions(&state, "alice", None).0.is_empty());
+    assert!(
+        !state
+            .orders
+            .iter()
+            .any(|o| o.account == "alice" && o.status == OrderStatus::Active)
+    );
+    let inventory = state.market_maker.inventory();
+    assert_eq!(inventory.len(), 1);
+    assert_eq!(inventory[0].quantity, -3);
+    assert_eq!(state.ledger.trial_balance(), 0);
+    assert!(state.ledger.cash_balance("alice") < 0);
+
+    // Every step reached the account's private channel.
+    for expected in actions(&state, "alice") {
+        let event = events.recv().await.expect("step pushed");
+        assert_eq!(event.account, "alice");
+        match event.message {
+            WsMessage::Liquidation { step, .. } => assert_eq!(step.action, expected),
+            other => panic!("expected a liquidation message, got {other:?}"),
+        }
+    }
+
+    // A finished liquidation is not restarted while the account is flat.
+    assert_eq!(run_liquidations(&state).await, 0);
+}
+
+#[tokio::test]
+async fn test_liquidating_account_may_only_reduce_risk() {
+    let state = liquidation_state(false);
+    short_calls(&state, "alice", 5);
+
+    // Without liquidity or a transfer the short cannot be closed, so the
+    // liquidation stays open.
+    assert_eq!(run_liquidations(&state).await, 1);
+    assert!(is_liquidating(&state, "alice"));
+    assert_eq!(
+        actions(&state, "alice"),
+        vec![
+            LiquidationAction::MarginBreach,
+            LiquidationAction::OrdersCanceled
+        ]
+    );
+
+    let err = limit_order(&state, "alice", OrderSide::Sell, 500_000, 1)
+        .await
+        .expect_err("adding to the short is blocked");
+    assert!(matches!(err, ApiError::InsufficientMargin(_)));
+    limit_order(&state, "alice", OrderSide::Buy, 1, 1)
+        .await
+        .expect("buying back is allowed");
+
+    // Fresh collateral restores the account on the next sweep.
+    state.collateral.insert("alice".to_string(), 10_000_000_000);
+    assert_eq!(run_liquidations(&state).await, 1);
+    assert!(!is_liquidating(&state, "alice"));
+    assert_eq!(
+        actions(&state, "alice").last(),
+        Some(&LiquidationAction::Completed)
+    );
+    limit_order(&state, "alice", OrderSide::Sell, 500_000, 1)
+        .await
+        .expect("no longer blocked");
+}
+
+#[tokio::test]
+async fn test_sweep_waits_for_the_account_guard() {
+    let state = liquidation_state(false);
+    short_calls(&state, "alice", 5);
+
+    // An order of the account is in flight: the sweep must not cancel or
+    // close anything until it completes.
+    let account_guard = state.account_guard("alice").await;
+    let sweep = tokio::spawn({
+        let state = state.clone();
+        async move { run_liquidations(&state).await }
+    });
+    tokio::task::yield_now().await;
+    assert!(!sweep.is_finished());
+    assert!(state.liquidations.is_empty());
+
+    drop(account_guard);
+    assert_eq!(sweep.await.expect("sweep ran"), 1);
+    assert!(is_liquidating(&state, "alice"));
+}
+
+#[tokio::test]
+async fn test_well_collateralised_accounts_are_left_alone() {
+    let state = liquidation_state(true);
+    state.collateral.insert("alice".to_string(), 10_000_000_000);
+    short_calls(&state, "alice", 5);
+
+    assert_eq!(run_liquidations(&state).await, 0);
+    assert!(state.liquidations.is_empty());
+}
+
+#[test]
+fn test_adverse_price_moves_against_the_account() {
+    assert_eq!(adverse_price(1_000.0, OrderSide::Sell, 5.0), 950);
+    assert_eq!(adverse_price(1_000.0, OrderSide::Buy, 5.0), 1_050);
+    assert_eq!(adverse_price(1_000.4, OrderSide::Buy, 0.0), 1_001);
+    // A near-worthless option still trades at a cent.
+    assert_eq!(adverse_price(0.2, OrderSide::Sell, 10.0), 1);
+}
+
+#[test]
+fn test_transfer_premium_scales_with_the_contract_multiplier() {
+    let state = liquidation_state(true);
+    state.market_maker.set_contract_spec(
+        "AAPL",
+        crate::contract::ContractSpec {
+            multiplier: 100,
+            ..Default::default()
+        },
+    );
+    state.market_maker.update_price("AAPL", 16_000);
+    let symbol = "AAPL-20351231-15000-C";
+    update_account_position_on_fill(&state, "alice", symbol, "AAPL", OrderSide::Sell, 2, 1, 0);
+    let (positions, _) = account_risk_positions(&state, "alice", None);
+    let position = positions.first().expect("alice holds the call");
+    assert_eq!(position.multiplier, 100);
+
+    assert!(transfer_to_market_maker(
+        &state,
+        "alice",
+        position,
+        OrderSide::Buy,
+        2,
+        500
+    ));
+
+    // Buying back two contracts at $5.00 on 100 shares each costs $1,000.
+    assert_eq!(state.ledger.cash_balance("alice"), -100_000);
+    assert_eq!(state.ledger.trial_balance(), 0);
+    assert!(account_risk_positions(&state, "alice", None).0.is_empty());
+    let inventory = state.market_maker.inventory();
+    assert_eq!(inventory[0].quantity, -2);
+    assert_eq!(inventory[0].multiplier, 100);
+}
+
+#[tokio::test]
+async fn test_liquidation_endpoints() {
+    let state = liquidation_state(true);
+    let err = get_liquidation(State(state.clone()), Extension(claims_for("alice")))
+        .await
+        .expect_err("never liquidated");
+    assert!(matches!(err, ApiError::NotFound(_)));
+
+    short_calls(&state, "alice", 1);
+    run_liquidations(&state).await;
+
+    let Json(liquidation) = get_liquidation(State(state.clone()), Extension(claims_for("alice")))
+        .await
+        .expect("liquidated");
+    assert_eq!(liquidation.account, "alice");
+    assert_eq!(liquidation.status, LiquidationStatus::Completed);
+
+    let Json(all) = list_liquidations(State(state.clone())).await;
+    assert_eq!(all.liquidations.len(), 1);
+    assert_eq!(all.liquidations[0].account, "alice");
+}
@@ -0,0 +1,366 @@
+//! Portfolio margin endpoints and the pre-trade margin check.
+
+use crate::api::controls::dollars_to_cents;
+use crate::api::liquidation::is_liquidating;
+use crate::api::risk::{account_risk_positions, risk_position_from_info, spot_price, to_cents};
+use crate::auth::{Claims, validate_account_id};
+use crate::config::MarginConfig;
+use crate::error::{ApiError, ErrorResponse};
+use crate::models::{OrderSide, OrderStatus, PositionInfo};
+use crate::risk::{
+    MarginParams, MarginRequirement, RiskPosition, UnderlyingMargin, compute_margin,
+};
+use crate::state::AppState;
+use axum::extract::{Path, State};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[cfg(test)]
+mod tests;
+
+// ============================================================================
+// Request/Response DTOs
+// ============================================================================
+
+/// Request body for setting an account's pledged collateral.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetCollateralRequest {
+    /// Pledged collateral in dollars.
+    pub collateral: f64,
+}
+
+/// Margin of one underlying.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnderlyingMarginResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Worst loss across the risk array, in cents.
+    pub scan_risk: i64,
+    /// Index of the risk-array scenario that set the scan risk (0-15).
+    pub worst_scenario: usize,
+    /// Scan risk saved by netting positions within the underlying, in cents.
+    pub spread_credit: i64,
+    /// Short option minimum, in cents.
+    pub short_option_minimum: i64,
+    /// Requirement in cents: the larger of scan risk and the minimum.
+    pub requirement: i64,
+}
+
+/// Margin status of an account.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountMarginResponse {
+    /// Account identifier.
+    pub account: String,
+    /// Whether the pre-trade margin check is enforced.
+    pub enforced: bool,
+    /// Collateral in cents: pledged collateral plus cash.
+    pub collateral: i64,
+    /// Initial margin in cents.
+    pub initial_margin: i64,
+    /// Maintenance margin in cents.
+    pub maintenance_margin: i64,
+    /// Collateral left after initial margin, in cents (negative = shortfall).
+    pub excess_collateral: i64,
+    /// Whether collateral is below maintenance margin.
+    pub margin_call: bool,
+    /// Per-underlying breakdown.
+    pub underlyings: Vec<UnderlyingMarginResponse>,
+    /// Instruments that could not be valued and are not margined.
+    pub unpriced: Vec<String>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl From<UnderlyingMargin> for UnderlyingMarginResponse {
+    fn from(m: UnderlyingMargin) -> Self {
+        Self {
+            underlying: m.underlying,
+            scan_risk: to_cents(m.scan_risk_cents),
+            worst_scenario: m.worst_scenario,
+            spread_credit: to_cents(m.spread_credit_cents),
+            short_option_minimum: to_cents(m.short_option_minimum_cents),
+            requirement: to_cents(m.requirement_cents),
+        }
+    }
+}
+
+// ============================================================================
+// Margin Computation
+// ============================================================================
+
+/// The `[risk.margin]` settings, or the defaults when running without config.
+fn margin_config(state: &AppState) -> MarginConfig {
+    state
+        .config
+        .as_ref()
+        .map(|c| c.risk.margin.clone())
+        .unwrap_or_default()
+}
+
+fn margin_params(config: &MarginConfig) -> MarginParams {
+    MarginParams {
+        price_scan_pct: config.price_scan_pct,
+        vol_scan_pts: config.vol_scan_pts,
+        short_option_minimum_pct: config.short_option_minimum_pct,
+        maintenance_ratio: config.maintenance_ratio,
+    }
+}
+
+/// Pledged collateral of `account` in cents: the recorded amount, or the
+/// configured default for an account that has none.
+pub(crate) fn pledged_collateral(state: &AppState, account: &str) -> u64 {
+    state.collateral.get(account).map_or_else(
+        || crate::config::dollars_to_cents(margin_config(state).default_collateral).unwrap_or(0),
+        |c| *c,
+    )
+}
+
+/// Collateral of `account` in cents: its pledged collateral (the recorded
+/// amount, or the configured default for an account that has none) plus its
+/// cash balance in the ledger. A cash deficit reduces it, down to zero.
+#[must_use]
+pub fn account_collateral(state: &AppState, account: &str) -> u64 {
+    let pledged = pledged_collateral(state, account);
+    let cash = state.ledger.cash_balance(account);
+    if cash >= 0 {
+        pledged.saturating_add(cash.unsigned_abs())
+    } else {
+        pledged.saturating_sub(cash.unsigned_abs())
+    }
+}
+
+/// Resolves an order on `symbol` into the signed position it would leave if
+/// fully filled, in contracts of the underlying's multiplier. Returns `None`
+/// for a symbol that does not parse.
+pub(crate) fn order_risk_position(
+    state: &AppState,
+    symbol: &str,
+    underlying: &str,
+    side: OrderSide,
+    quantity: u64,
+) -> Option<RiskPosition> {
+    let quantity = i64::try_from(quantity).ok()?;
+    let signed = match side {
+        OrderSide::Buy => quantity,
+        OrderSide::Sell => -quantity,
+    };
+    risk_position_from_info(
+        &PositionInfo::new(symbol.to_string(), underlying.to_string(), signed, 0, 0)
+            .with_multiplier(state.market_maker.contract_spec(underlying).multiplier),
+    )
+}
+
+/// `account`'s positions plus every resting order as if it filled, skipping
+/// the order `excluding` (one about to be replaced).
+fn exposure_with_open_orders(
+    state: &AppState,
+    account: &str,
+    excluding: Option<&str>,
+) -> Vec<RiskPosition> {
+    let (mut exposure, _) = account_risk_positions(state, account, None);
+    for order in state.orders.account_orders(account) {
+        if excluding == Some(order.order_id.as_str())
+            || !matches!(order.status, OrderStatus::Active | OrderStatus::Partial)
+            || order.remaining_quantity == 0
+        {
+            continue;
+        }
+        exposure.extend(order_risk_position(
+            state,
+            &order.symbol,
+            &order.underlying,
+            order.side,
+            order.remaining_quantity,
+        ));
+    }
+    exposure
+}
+
+/// Initial margin of `account` in cents on its positions plus every resting
+/// order as if filled: what its collateral has to keep covering.
+pub(crate) fn initial_margin_with_open_orders(state: &AppState, account: &str) -> f64 {
+    compute_margin(
+        state.market_maker.pricer(),
+        exposure_with_open_orders(state, account, None),
+        |u| spot_price(state, u),
+        &margin_params(&margin_config(state)),
+    )
+    .initial_cents
+}
+
+/// Recomputes `account`'s margin on its current positions and stores it as
+/// the account's latest.
+pub fn refresh_account_margin(state: &AppState, account: &str) -> MarginRequirement {
+    let params = margin_params(&margin_config(state));
+    let (positions, mut unparsed) = account_risk_positions(state, account, None);
+    let mut margin = compute_margin(
+        state.market_maker.pricer(),
+        positions,
+        |u| spot_price(state, u),
+        &params,
+    );
+    unparsed.append(&mut margin.unpriced);
+    unparsed.sort();
+    margin.unpriced = unparsed;
+    state
+        .margin_reports
+        .insert(account.to_string(), margin.clone());
+    margin
+}
+
+/// Recomputes margin for every account holding a position in `underlying`.
+/// Called on price updates; returns the number of accounts refreshed.
+pub fn refresh_margin_for_underlying(state: &AppState, underlying: &str) -> usize {
+    let accounts: Vec<String> = state
+        .account_positions
+        .iter()
+        .filter(|book| {
+            book.value()
+                .iter()
+                .any(|p| p.underlying == underlying && p.quantity != 0)
+        })
+        .map(|book| book.key().clone())
+        .collect();
+    for account in &accounts {
+        refresh_account_margin(state, account);
+    }
+    accounts.len()
+}
+
+/// Pre-trade margin check for an order that would leave `candidate` if fully
+/// filled.
+///
+/// The account's initial margin is computed on its positions plus every
+/// resting order as if filled, with and without the candidate. The order is
+/// accepted when the resulting margin fits the account's collateral, or when
+/// it does not increase the margin (risk-reducing orders are never blocked).
+/// `replacing` names a resting order the candidate replaces, whose exposure is
+/// not counted. While the account is being liquidated only orders that do not
+/// increase its margin are accepted, whatever its collateral. Otherwise a
+/// no-op unless `[risk.margin] enabled` is set.
+///
+/// # Errors
+/// Returns [`ApiError::InsufficientMargin`] when the order would take initial
+/// margin above collateral, or would increase the margin of an account under
+/// liquidation.
+pub(crate) fn check_order_margin(
+    state: &AppState,
+    account: &str,
+    candidate: Option<RiskPosition>,
+    replacing: Option<&str>,
+) -> Result<(), ApiError> {
+    let config = margin_config(state);
+    let liquidating = is_liquidating(state, account);
+    let Some(candidate) = candidate.filter(|_| config.enabled || liquidating) else {
+        return Ok(());
+    };
+    let params = margin_params(&config);
+    let pricer = state.market_maker.pricer();
+    let spot = |u: &str| spot_price(state, u);
+
+    let exposure = exposure_with_open_orders(state, account, replacing);
+    let before = compute_margin(pricer, exposure.clone(), spot, &params).initial_cents;
+    let mut with_order = exposure;
+    with_order.push(candidate);
+    let after = compute_margin(pricer, with_order, spot, &params).initial_cents;
+
+    if after <= before {
+        return Ok(());
+    }
+    if liquidating {
+        return Err(ApiError::InsufficientMargin(format!(
+            "account {account} is being liquidated; only risk-reducing orders are accepted"
+        )));
+    }
+    let collateral = account_collateral(state, account) as f64;
+    if after <= collateral {
+        return Ok(());
+    }
+    tracing::debug!(
+        account,
+        initial_margin = after,
+        collateral,
+        "order rejected by pre-trade margin check"
+    );
+    Err(ApiError::InsufficientMargin(format!(
+        "order requires initial margin of {:.2} but account collateral is {:.2}",
+        after / 100.0,
+        collateral / 100.0
+    )))
+}
+
+/// Builds the margin status of `account` from a fresh computation.
+fn account_margin_response(state: &AppState, account: &str) -> AccountMarginResponse {
+    let margin = refresh_account_margin(state, account);
+    let collateral = account_collateral(state, account) as f64;
+    AccountMarginResponse {
+        account: account.to_string(),
+        enforced: margin_config(state).enabled,
+        collateral: to_cents(collateral),
+        initial_margin: to_cents(margin.initial_cents),
+        maintenance_margin: to_cents(margin.maintenance_cents),
+        excess_collateral: to_cents(collateral - margin.initial_cents),
+        margin_call: collateral < margin.maintenance_cents,
+        underlyings: margin.underlyings.into_iter().map(Into::into).collect(),
+        unpriced: margin.unpriced,
+        timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Portfolio margin of the caller's account.
+///
+/// Each underlying is scanned with a SPAN-style sixteen-scenario risk array
+/// (price and vol scan ranges from `[risk.margin]`); offsetting positions net
+/// within the array and the saving is reported as a spread credit. Amounts
+/// are in cents.
+#[utoipa::path(
+    get,
+    path = "/api/v1/risk/margin",
+    responses(
+        (status = 200, description = "Account margin", body = AccountMarginResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn get_account_margin(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Json<AccountMarginResponse> {
+    Json(account_margin_response(&state, claims.account()))
+}
+
+/// Set an account's pledged collateral.
+///
+/// Pledged collateral is non-cash; the account's ledger cash counts on top of
+/// it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/accounts/{account}/collateral",
+    params(
+        ("account" = String, Path, description = "Account identifier")
+    ),
+    request_body = SetCollateralRequest,
+    responses(
+        (status = 200, description = "Collateral updated", body = AccountMarginResponse),
+        (status = 400, description = "Invalid account or amount", body = ErrorResponse),
+        (status = 403, description = "Admin permission required", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn set_account_collateral(
+    State(state): State<Arc<AppState>>,
+    Path(account): Path<String>,
+    Json(body): Json<SetCollateralRequest>,
+) -> Result<Json<AccountMarginResponse>, ApiError> {
+    validate_account_id(&account)?;
+    let collateral = dollars_to_cents("collateral", body.collateral)?;
+    state.collateral.insert(account.clone(), collateral);
+    tracing::info!(account = %account, collateral, "account collateral set");
+    Ok(Json(account_margin_response(&state, &account)))
+}
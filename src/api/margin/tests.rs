@@ -0,0 +1,178 @@
+//! Unit tests for the margin module.
+
+use super::*;
+use crate::api::handlers::update_account_position_on_fill;
+use crate::api::test_support::{btc_state, claims_for, limit_order};
+use crate::config::Config;
+
+const CALL: &str = "BTC-20351231-5000000-C";
+
+/// A state with margin enforced, BTC at $50,000 and `collateral` dollars of
+/// default collateral.
+fn margined_state(collateral: f64) -> Arc<AppState> {
+    let mut config = Config::default();
+    config.risk.margin.enabled = true;
+    config.risk.margin.default_collateral = collateral;
+    btc_state(config)
+}
+
+fn sell_call(state: &AppState, quantity: u64) -> Option<RiskPosition> {
+    order_risk_position(state, CALL, "BTC", OrderSide::Sell, quantity)
+}
+
+#[test]
+fn test_check_order_margin_is_a_no_op_when_disabled() {
+    let state = btc_state(Config::default());
+    assert!(check_order_margin(&state, "alice", sell_call(&state, 1_000), None).is_ok());
+}
+
+#[test]
+fn test_check_order_margin_rejects_a_naked_short_beyond_collateral() {
+    let state = margined_state(0.0);
+    let err = check_order_margin(&state, "alice", sell_call(&state, 10), None)
+        .expect_err("no collateral to cover a naked short");
+    assert!(matches!(err, ApiError::InsufficientMargin(_)));
+
+    // Enough collateral covers the same order.
+    state.collateral.insert("alice".to_string(), 10_000_000_000);
+    assert!(check_order_margin(&state, "alice", sell_call(&state, 10), None).is_ok());
+}
+
+#[test]
+fn test_check_order_margin_accepts_risk_reducing_orders() {
+    let state = margined_state(0.0);
+    update_account_position_on_fill(&state, "alice", CALL, "BTC", OrderSide::Sell, 5, 500, 0);
+
+    // Buying back part of the short lowers margin, so it passes even though
+    // the account is already under-collateralised.
+    let buy_back = order_risk_position(&state, CALL, "BTC", OrderSide::Buy, 2);
+    assert!(check_order_margin(&state, "alice", buy_back, None).is_ok());
+    // Adding to it does not.
+    assert!(check_order_margin(&state, "alice", sell_call(&state, 1), None).is_err());
+}
+
+#[tokio::test]
+async fn test_add_order_is_rejected_with_insufficient_margin() {
+    let state = margined_state(0.0);
+    let result = limit_order(&state, "alice", OrderSide::Sell, 500_000, 10).await;
+    assert!(matches!(result, Err(ApiError::InsufficientMargin(_))));
+    assert!(state.orders.is_empty(), "a rejected order is never placed");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_orders_of_one_account_cannot_share_collateral() {
+    let state = margined_state(0.0);
+    let params = margin_params(&margin_config(&state));
+    let one_order = compute_margin(
+        state.market_maker.pricer(),
+        sell_call(&state, 10).into_iter().collect(),
+        |u| spot_price(&state, u),
+        &params,
+    )
+    .initial_cents;
+    // Room for one order of 10, not two.
+    state
+        .collateral
+        .insert("alice".to_string(), (one_order * 1.5) as u64);
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let state = Arc::clone(&state);
+            tokio::spawn(
+                async move { limit_order(&state, "alice", OrderSide::Sell, 500_000, 10).await },
+            )
+        })
+        .collect();
+    let mut accepted = 0;
+    for task in tasks {
+        match task.await.expect("order task panicked") {
+            Ok(_) => accepted += 1,
+            Err(err) => assert!(matches!(err, ApiError::InsufficientMargin(_))),
+        }
+    }
+
+    assert_eq!(accepted, 1);
+    assert_eq!(state.orders.account_orders("alice").len(), 1);
+}
+
+#[tokio::test]
+async fn test_open_orders_of_other_accounts_are_not_margined() {
+    let state = margined_state(0.0);
+    state.collateral.insert("alice".to_string(), 10_000_000_000);
+    let placed = limit_order(&state, "alice", OrderSide::Sell, 500_000, 10).await;
+    assert!(placed.is_ok(), "alice's order is covered");
+
+    assert_eq!(exposure_with_open_orders(&state, "alice", None).len(), 1);
+    assert!(exposure_with_open_orders(&state, "bob", None).is_empty());
+}
+
+#[tokio::test]
+async fn test_account_margin_reports_collateral_and_margin_call() {
+    let state = margined_state(100.0);
+    update_account_position_on_fill(&state, "alice", CALL, "BTC", OrderSide::Sell, 5, 500, 0);
+
+    let Json(margin) =
+        get_account_margin(State(state.clone()), Extension(claims_for("alice"))).await;
+    assert!(margin.enforced);
+    assert_eq!(margin.collateral, 10_000);
+    assert!(margin.initial_margin > margin.collateral);
+    assert!((margin.maintenance_margin - margin.initial_margin * 3 / 4).abs() <= 1);
+    assert_eq!(
+        margin.excess_collateral,
+        margin.collateral - margin.initial_margin
+    );
+    assert!(margin.margin_call);
+    assert_eq!(margin.underlyings[0].underlying, "BTC");
+    assert!(state.margin_reports.contains_key("alice"));
+
+    let Json(funded) = set_account_collateral(
+        State(state.clone()),
+        Path("alice".to_string()),
+        Json(SetCollateralRequest {
+            collateral: 1_000_000.0,
+        }),
+    )
+    .await
+    .expect("collateral set");
+    assert_eq!(funded.collateral, 100_000_000);
+    assert!(!funded.margin_call);
+    assert_eq!(account_collateral(&state, "alice"), 100_000_000);
+}
+
+#[tokio::test]
+async fn test_set_account_collateral_validates_input() {
+    let state = margined_state(0.0);
+    for (account, collateral) in [("alice", -1.0), ("alice", f64::NAN), ("bad account", 1.0)] {
+        let result = set_account_collateral(
+            State(state.clone()),
+            Path(account.to_string()),
+            Json(SetCollateralRequest { collateral }),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(ApiError::InvalidRequest(_))),
+            "{account} / {collateral} must be rejected"
+        );
+    }
+    assert!(state.collateral.is_empty());
+}
+
+#[test]
+fn test_refresh_margin_for_underlying_only_touches_holders() {
+    let state = margined_state(0.0);
+    update_account_position_on_fill(&state, "alice", CALL, "BTC", OrderSide::Sell, 1, 500, 0);
+    update_account_position_on_fill(
+        &state,
+        "bob",
+        "ETH-20351231-300000-C",
+        "ETH",
+        OrderSide::Buy,
+        1,
+        500,
+        0,
+    );
+
+    assert_eq!(refresh_margin_for_underlying(&state, "BTC"), 1);
+    assert!(state.margin_reports.contains_key("alice"));
+    assert!(!state.margin_reports.contains_key("bob"));
+}
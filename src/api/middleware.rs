@@ -1,14 +1,20 @@
-//! API middleware: JWT authentication, per-route permission enforcement, and
-//! sliding-window rate limiting keyed by the JWT `sub`.
+//! API middleware: JWT authentication, per-route permission enforcement,
+//! sliding-window rate limiting keyed by the JWT `sub`, and the mutation gate
+//! that whole-state snapshots pause and that journals every mutation.
 
-use crate::auth::RateLimitDecision;
+use crate::auth::{Claims, RateLimitDecision};
+use crate::clock;
 use crate::error::ApiError;
+use crate::journal::{JournalEvent, JournalHold};
 use crate::models::Permission;
 use crate::state::AppState;
 use axum::{
     body::Body,
     extract::{ConnectInfo, State},
-    http::{HeaderValue, Method, Request, header::AUTHORIZATION},
+    http::{
+        HeaderValue, Method, Request,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -28,6 +34,17 @@ const HEALTH_PATH: &str = "/health";
 /// Token-issuance path (exempt from authentication, IP rate-limited).
 const TOKEN_PATH: &str = "/api/v1/auth/token";
 
+/// Prefix of the snapshot admin paths, which pause mutations themselves.
+const SNAPSHOT_PATH_PREFIX: &str = "/api/v1/admin/snapshot";
+
+/// OHLC backfill path: it rewrites stored history only and is neither gated
+/// nor journaled.
+const OHLC_BACKFILL_PATH: &str = "/api/v1/admin/ohlc/backfill";
+
+/// Largest request body the journal buffers; the same as axum's default
+/// body limit, which every JSON handler already enforces.
+const JOURNAL_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
 /// Returns the current Unix time in whole seconds, rounded UP from
 /// milliseconds — the same rounding as [`RateLimitDecision::reset_secs`], so
 /// `reset - now` never overstates `Retry-After` by a second.
@@ -44,7 +61,7 @@ fn now_secs() -> u64 {
 /// Extracts a JWT from `Authorization: Bearer <jwt>` (REST and non-browser WS) or
 /// the `?token=<jwt>` query parameter (the browser `/ws` upgrade), verifies it,
 /// enforces the per-route required [`Permission`], rate-limits by the JWT `sub`,
-/// and injects [`Claims`](crate::auth::Claims) into the request extensions. `/health` and
+/// and injects [`Claims`] into the request extensions. `/health` and
 /// `POST /api/v1/auth/token` are exempt (the latter is IP rate-limited).
 ///
 /// Returns `401` for a missing/invalid/expired token, `403` for insufficient
@@ -111,6 +128,82 @@ pub async fn auth_middleware(
     response
 }
 
+/// Mutation gate middleware.
+///
+/// Every request that can mutate state (anything but `GET`, `HEAD` and
+/// `OPTIONS`) holds [`AppState::mutation_guard`] while it runs, so a
+/// whole-state snapshot or restore waits for in-flight mutations and holds
+/// new ones until it is done. The snapshot endpoints take the gate
+/// exclusively themselves and are passed straight through, as is the OHLC
+/// backfill, which touches no in-memory state.
+///
+/// With a journal attached, the request is appended to it before the
+/// handler runs, and the journal stays locked until the handler returns so
+/// entries are applied in journal order, at the entry's time (see
+/// [`crate::clock`]). The handler finds the lock in its request extensions
+/// as a [`JournalHold`] and may release it earlier, once its in-memory
+/// effect is applied. A request that cannot be journaled is not run. Token issuance changes no trading state and is not journaled.
+pub async fn mutation_gate(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    let path = request.uri().path();
+    if read_only || path.starts_with(SNAPSHOT_PATH_PREFIX) || path == OHLC_BACKFILL_PATH {
+        return next.run(request).await;
+    }
+    let _guard = state.mutation_guard().await;
+    let journal = match state.journal() {
+        Some(journal) if request.uri().path() != TOKEN_PATH => journal,
+        _ => return next.run(request).await,
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, JOURNAL_BODY_LIMIT).await {
+        Ok(body) => body,
+        Err(_) => {
+            return ApiError::InvalidRequest("request body is too large".to_string())
+                .into_response();
+        }
+    };
+    let event = JournalEvent::Request {
+        method: parts.method.to_string(),
+        uri: parts
+            .uri
+            .path_and_query()
+            .map_or_else(|| parts.uri.path().to_string(), ToString::to_string),
+        content_type: parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        claims: parts.extensions.get::<Claims>().cloned(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    };
+
+    let mut journal = journal.lock().await;
+    let appended = match journal
+        .append(event, state.market_maker.order_ids().position())
+        .await
+    {
+        Ok(appended) => appended,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to journal a request; rejecting it");
+            return ApiError::Internal("failed to journal the request".to_string()).into_response();
+        }
+    };
+    let hold = JournalHold::new(journal);
+    let mut request = Request::from_parts(parts, Body::from(body));
+    request.extensions_mut().insert(hold.clone());
+    let response = clock::at(appended.timestamp_ms, next.run(request)).await;
+    hold.release();
+    response
+}
+
 /// Determines the [`Permission`] required for a method + path.
 ///
 /// GETs require `Read`; mutations require `Trade`; market-maker controls, admin
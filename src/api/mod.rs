@@ -6,14 +6,21 @@ use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::warn;
 
+pub mod account;
 pub mod controls;
 pub mod handlers;
+pub mod liquidation;
+pub mod margin;
 pub mod middleware;
+pub mod replay;
+pub mod risk;
 pub mod routes;
+#[cfg(test)]
+mod test_support;
 pub mod websocket;
 
 pub use middleware::auth_middleware;
-pub use routes::create_router;
+pub use routes::{create_replay_router, create_router};
 
 /// Parses raw CORS origin strings into HTTP `HeaderValue`s for an allowlist.
 ///
@@ -0,0 +1,356 @@
+//! Journal replay.
+//!
+//! Rebuilds the application state by applying journal entries in order on
+//! top of a restored snapshot (or an empty state). Recorded requests go
+//! through the same routes and handlers as live traffic, carrying the claims
+//! they were recorded with; prices, sweeps and restores call the engine
+//! directly. Before each entry the order ID source is moved to the position
+//! recorded with it, so every order gets the ID it had in the live run.
+//!
+//! Each entry is applied at the time it was journaled at (see
+//! [`crate::clock`]), as in the live run, so GTD admission, order and fill
+//! timestamps, requote throttling and pricing see the recorded times, not the
+//! time of the replay. The order books check a GTD deadline against their own
+//! wall clock, which cannot be swapped, so an order whose deadline has passed
+//! on the wall clock by the time it is replayed enters its book with the
+//! deadline moved on by the same amount; nothing evicts resting GTD orders,
+//! so it rests and fills exactly as it did.
+
+use crate::api::account::settle_expired_positions;
+use crate::api::handlers::restore_snapshot_by_id;
+use crate::api::liquidation::run_liquidations;
+use crate::api::routes::create_replay_router;
+use crate::clock;
+use crate::journal::{JournalEntry, JournalEvent};
+use crate::state::AppState;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header::CONTENT_TYPE};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing::{debug, warn};
+
+/// Outcome of a replay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    /// Entries applied.
+    pub applied: u64,
+    /// Applied entries that failed again: requests answered with an error
+    /// status, and restores of snapshots that could not be found.
+    pub rejected: u64,
+    /// Sequence of the last entry applied (`after_seq` when none was).
+    pub last_seq: u64,
+}
+
+/// Applies every entry of `entries` with a sequence above `after_seq`, in
+/// order. Run it before the server accepts traffic and before the journal is
+/// attached, so replayed inputs are not journaled a second time.
+pub async fn replay_journal(
+    state: &Arc<AppState>,
+    entries: &[JournalEntry],
+    after_seq: u64,
+) -> ReplaySummary {
+    let router = create_replay_router(Arc::clone(state));
+    let mut summary = ReplaySummary {
+        last_seq: after_seq,
+        ..ReplaySummary::default()
+    };
+
+    for entry in entries.iter().filter(|entry| entry.seq > after_seq) {
+        state.market_maker.order_ids().seek(entry.order_ids);
+        let accepted = clock::at(entry.timestamp_ms, apply_entry(state, &router, entry)).await;
+        summary.applied += 1;
+        if !accepted {
+            summary.rejected += 1;
+        }
+        summary.last_seq = entry.seq;
+    }
+    summary
+}
+
+/// Applies one entry, returning whether it was accepted.
+async fn apply_entry(state: &Arc<AppState>, router: &Router, entry: &JournalEntry) -> bool {
+    match &entry.event {
+        JournalEvent::Request {
+            method,
+            uri,
+            content_type,
+            claims,
+            body,
+        } => {
+            let mut builder = Request::builder().method(method.as_str()).uri(uri.as_str());
+            if let Some(content_type) = content_type {
+                builder = builder.header(CONTENT_TYPE, content_type.as_str());
+            }
+            if let Some(claims) = claims {
+                builder = builder.extension(claims.clone());
+            }
+            match builder.body(Body::from(body.clone())) {
+                Ok(request) => {
+                    let status = match router.clone().oneshot(request).await {
+                        Ok(response) => response.status(),
+                        Err(infallible) => match infallible {},
+                    };
+                    debug!(seq = entry.seq, %method, %uri, %status, "replayed request");
+                    status.is_success()
+                }
+                Err(e) => {
+                    warn!(seq = entry.seq, error = %e, "journaled request is malformed");
+                    false
+                }
+            }
+        }
+        JournalEvent::Price {
+            symbol,
+            price_cents,
+        } => {
+            state.market_maker.update_price(symbol, *price_cents);
+            true
+        }
+        JournalEvent::Settlement { now_ms } => {
+            match chrono::DateTime::from_timestamp_millis(*now_ms) {
+                Some(now) => {
+                    settle_expired_positions(state, now);
+                    true
+                }
+                None => false,
+            }
+        }
+        JournalEvent::Liquidation => {
+            run_liquidations(state).await;
+            true
+        }
+        JournalEvent::OrderCleanup {
+            now_ms,
+            max_age_secs,
+        } => match chrono::DateTime::from_timestamp_millis(*now_ms) {
+            Some(now) => {
+                state.cleanup_old_orders(now, *max_age_secs);
+                true
+            }
+            None => false,
+        },
+        JournalEvent::Restore { snapshot_id } => {
+            match restore_snapshot_by_id(state, snapshot_id.clone()).await {
+                Ok(_) => true,
+                Err(e) => {
+                    warn!(seq = entry.seq, snapshot_id = %snapshot_id, error = %e, "journaled restore failed");
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{middleware, routes};
+    use crate::auth::Claims;
+    use crate::config::JournalConfig;
+    use crate::journal::Journal;
+    use crate::models::{OrderInfo, OrderStatus, OrderTimeInForce, Permission};
+    use crate::order_ids::OrderIdPosition;
+    use axum::Router;
+    use axum::http::Method;
+    use axum::middleware::{Next, from_fn, from_fn_with_state};
+    use axum::response::Response;
+
+    const ORDERS_PATH: &str =
+        "/api/v1/underlyings/BTC/expirations/20351231/strikes/100000/options/call/orders";
+
+    /// Stands in for `auth_middleware`: every request acts as an admin.
+    async fn admin_claims(mut request: Request<Body>, next: Next) -> Response {
+        request.extensions_mut().insert(Claims {
+            sub: "replay-account".to_string(),
+            iss: "test".to_string(),
+            iat: 0,
+            exp: u64::MAX,
+            permissions: vec![Permission::Admin],
+        });
+        next.run(request).await
+    }
+
+    /// The live router minus authentication: the mutation gate journals.
+    fn live_router(state: &Arc<AppState>) -> Router {
+        routes::api_routes()
+            .layer(from_fn_with_state(
+                Arc::clone(state),
+                middleware::mutation_gate,
+            ))
+            .layer(from_fn(admin_claims))
+            .with_state(Arc::clone(state))
+    }
+
+    fn order(price: u64, content_type: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().method(Method::POST).uri(ORDERS_PATH);
+        if let Some(content_type) = content_type {
+            builder = builder.header(CONTENT_TYPE, content_type);
+        }
+        builder
+            .body(Body::from(format!(
+                r#"{{"side":"buy","price":{price},"quantity":1}}"#
+            )))
+            .expect("request")
+    }
+
+    fn order_ids(state: &AppState) -> Vec<String> {
+        let mut ids: Vec<String> = state.orders.iter().map(|e| e.key().clone()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_replay_rebuilds_orders_with_their_ids() {
+        let path = std::env::temp_dir()
+            .join(format!("replay-test-{}", uuid::Uuid::new_v4()))
+            .join("journal.log");
+        let live = Arc::new(AppState::new());
+        let (journal, _) = Journal::open(&path, &JournalConfig::default(), 0).expect("open");
+        live.set_journal(Arc::new(journal));
+
+        let router = live_router(&live);
+        for request in [
+            order(100, Some("application/json")),
+            order(101, None),
+            order(102, Some("application/json")),
+        ] {
+            router.clone().oneshot(request).await.expect("infallible");
+        }
+        let event = JournalEvent::Price {
+            symbol: "BTC".to_string(),
+            price_cents: 10_000_000,
+        };
+        let price = live.apply_journaled(event, || {
+            live.market_maker.update_price("BTC", 10_000_000);
+        });
+        price.await.expect("journaled");
+        assert_eq!(live.orders.len(), 2);
+
+        let entries = Journal::read_after(&path, 0).expect("read");
+        assert_eq!(entries.len(), 4);
+        let replayed = Arc::new(AppState::new());
+        let summary = replay_journal(&replayed, &entries, 0).await;
+        assert_eq!(
+            summary,
+            ReplaySummary {
+                applied: 4,
+                rejected: 1,
+                last_seq: 4
+            }
+        );
+        assert_eq!(order_ids(&replayed), order_ids(&live));
+        assert_eq!(
+            replayed.market_maker.order_ids().position(),
+            live.market_maker.order_ids().position()
+        );
+
+        // Only the tail after the given sequence is applied.
+        let tail = Arc::new(AppState::new());
+        let summary = replay_journal(&tail, &entries, 2).await;
+        assert_eq!(summary.applied, 2);
+        assert_eq!(tail.orders.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_repeats_order_cleanup_at_the_recorded_time() {
+        let path = std::env::temp_dir()
+            .join(format!("replay-test-{}", uuid::Uuid::new_v4()))
+            .join("journal.log");
+        let live = Arc::new(AppState::new());
+        let (journal, _) = Journal::open(&path, &JournalConfig::default(), 0).expect("open");
+        live.set_journal(Arc::new(journal));
+
+        let router = live_router(&live);
+        let resting = order(100, Some("application/json"));
+        router.clone().oneshot(resting).await.expect("infallible");
+        let sell = Request::builder()
+            .method(Method::POST)
+            .uri(ORDERS_PATH)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"side":"sell","price":100,"quantity":1}"#))
+            .expect("request");
+        router.clone().oneshot(sell).await.expect("infallible");
+        let filled = live
+            .orders
+            .iter()
+            .find(|entry| entry.status == OrderStatus::Filled)
+            .map(|entry| entry.key().clone())
+            .expect("filled order");
+        let resting = order(90, Some("application/json"));
+        router.clone().oneshot(resting).await.expect("infallible");
+
+        let now = chrono::Utc::now() + chrono::Duration::hours(1);
+        let event = JournalEvent::OrderCleanup {
+            now_ms: now.timestamp_millis(),
+            max_age_secs: 60,
+        };
+        let cleanup = live.apply_journaled(event, || live.cleanup_old_orders(now, 60));
+        assert!(cleanup.await.expect("journaled") > 0);
+        assert!(!live.orders.contains_key(&filled));
+        assert!(!live.orders.is_empty());
+
+        let entries = Journal::read_after(&path, 0).expect("read");
+        let replayed = Arc::new(AppState::new());
+        let summary = replay_journal(&replayed, &entries, 0).await;
+        assert_eq!(summary.rejected, 0);
+        assert_eq!(order_ids(&replayed), order_ids(&live));
+        assert!(!replayed.orders.contains_key(&filled));
+    }
+
+    #[tokio::test]
+    async fn test_replay_applies_entries_at_their_recorded_time() {
+        let recorded_ms = clock::wall_ms() - 3_600_000;
+        let expire_at = chrono::DateTime::from_timestamp_millis(recorded_ms as i64 + 60_000)
+            .expect("valid timestamp")
+            .to_rfc3339();
+        let order_ids = AppState::new().market_maker.order_ids().position();
+        let claims = Claims {
+            sub: "replay-account".to_string(),
+            iss: "test".to_string(),
+            iat: 0,
+            exp: u64::MAX,
+            permissions: vec![Permission::Admin],
+        };
+        let request = |body: String| JournalEvent::Request {
+            method: "POST".to_string(),
+            uri: ORDERS_PATH.to_string(),
+            content_type: Some("application/json".to_string()),
+            claims: Some(claims.clone()),
+            body,
+        };
+        let entries = vec![
+            JournalEntry {
+                seq: 1,
+                timestamp_ms: recorded_ms,
+                order_ids,
+                event: request(format!(
+                    r#"{{"side":"buy","price":100,"quantity":2,"time_in_force":"GTD","expire_at":"{expire_at}"}}"#
+                )),
+            },
+            JournalEntry {
+                seq: 2,
+                timestamp_ms: recorded_ms + 1_000,
+                order_ids: OrderIdPosition {
+                    next: order_ids.next + 1,
+                    ..order_ids
+                },
+                event: request(r#"{"side":"sell","price":100,"quantity":1}"#.to_string()),
+            },
+        ];
+
+        // The GTD deadline passed an hour before the replay, yet the order is
+        // admitted as it was at the recorded time.
+        let replayed = Arc::new(AppState::new());
+        let summary = replay_journal(&replayed, &entries, 0).await;
+        assert_eq!(summary.rejected, 0);
+        let mut orders: Vec<OrderInfo> = replayed.orders.iter().map(|e| e.clone()).collect();
+        orders.sort_by_key(|order| order.created_at_ms);
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].created_at_ms, recorded_ms);
+        assert_eq!(orders[0].time_in_force, OrderTimeInForce::Gtd);
+        assert_eq!(orders[1].created_at_ms, recorded_ms + 1_000);
+        assert_eq!(orders[1].status, OrderStatus::Filled);
+        assert_eq!(orders[1].updated_at_ms, recorded_ms + 1_000);
+    }
+}
@@ -0,0 +1,844 @@
+//! Portfolio risk API handlers.
+
+use crate::api::handlers::{format_expiration, parse_expiration};
+use crate::auth::Claims;
+use crate::config::{MAX_SCENARIO_DAYS, StressScenarioConfig, WalkTypeConfig};
+use crate::error::{ApiError, ErrorResponse};
+use crate::market_maker::InventoryPosition;
+use crate::models::PositionInfo;
+use crate::risk::{
+    ExpirationGreeks, GreekTotals, MarketShock, PortfolioGreeks, RiskPosition, ScenarioBook,
+    ScenarioPnl, UnderlyingGreeks, UnderlyingModel, VarEstimate, VarReport, aggregate_greeks,
+    historical_returns, monte_carlo_returns, scenario_pnls, var_es,
+};
+use crate::state::AppState;
+use axum::extract::{Query, State};
+use axum::{Extension, Json};
+use chrono::NaiveDate;
+use optionstratlib::OptionStyle;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[cfg(test)]
+mod tests;
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Query parameters for the portfolio greeks endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct RiskGreeksQuery {
+    /// Restrict both books to a single underlying symbol.
+    pub underlying: Option<String>,
+}
+
+/// Net greeks of a set of positions.
+///
+/// `delta` is in underlying units, `gamma` per $1 move, `vega` in dollars per
+/// vol point, `theta` in dollars per day and `rho` in dollars per 1% rate
+/// move. `dollar_delta` is Σ q·Δ·S and `dollar_gamma` is Σ q·Γ·S²/100 (the
+/// dollar-delta change for a 1% move).
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct NetGreeks {
+    /// Net delta.
+    pub delta: f64,
+    /// Net gamma.
+    pub gamma: f64,
+    /// Net vega.
+    pub vega: f64,
+    /// Net daily theta.
+    pub theta: f64,
+    /// Net rho.
+    pub rho: f64,
+    /// Net dollar delta.
+    pub dollar_delta: f64,
+    /// Net dollar gamma.
+    pub dollar_gamma: f64,
+}
+
+/// Net greeks of one expiration bucket.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExpirationGreeksResponse {
+    /// Expiration date (YYYYMMDD).
+    pub expiration: String,
+    /// Number of open positions in the bucket.
+    pub positions: usize,
+    /// Net greeks of the bucket.
+    pub greeks: NetGreeks,
+}
+
+/// Net greeks of one underlying with its expiration breakdown.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnderlyingGreeksResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents the positions were valued against.
+    pub spot: u64,
+    /// Net greeks across the underlying.
+    pub greeks: NetGreeks,
+    /// Per-expiration breakdown, ordered by expiration.
+    pub expirations: Vec<ExpirationGreeksResponse>,
+}
+
+/// Aggregated greeks of one book.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GreekBookResponse {
+    /// Per-underlying breakdown, ordered by symbol.
+    pub underlyings: Vec<UnderlyingGreeksResponse>,
+    /// Net greeks across the whole book.
+    pub total: NetGreeks,
+    /// Instruments that could not be valued (no spot price for the underlying,
+    /// unparseable symbol, or a degenerate pricer result).
+    pub unpriced: Vec<String>,
+}
+
+/// Response for the portfolio greeks endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortfolioGreeksResponse {
+    /// Account the `positions` book belongs to (the caller's token subject).
+    pub account: String,
+    /// Greeks of the caller's positions.
+    pub positions: GreekBookResponse,
+    /// Greeks of the market maker's own inventory, reported as a separate book.
+    pub market_maker: GreekBookResponse,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Default spot shocks (percent) for the scenario grid.
+pub const DEFAULT_SPOT_SHOCKS_PCT: [f64; 9] =
+    [-30.0, -20.0, -10.0, -5.0, 0.0, 5.0, 10.0, 20.0, 30.0];
+
+/// Default vol shocks (vol points) for the scenario grid.
+pub const DEFAULT_VOL_SHOCKS_PTS: [f64; 3] = [-10.0, 0.0, 10.0];
+
+/// Maximum number of spot or vol shocks on one grid axis.
+pub const MAX_SCENARIO_AXIS: usize = 41;
+
+/// Maximum number of time horizons in one scenario request.
+pub const MAX_SCENARIO_HORIZONS: usize = 10;
+
+/// Query parameters for the scenario endpoint. Lists are comma-separated.
+#[derive(Debug, Default, Deserialize)]
+pub struct ScenarioQuery {
+    /// Restrict the book to a single underlying symbol.
+    pub underlying: Option<String>,
+    /// Spot shocks in percent (e.g. `-20,-10,0,10,20`).
+    pub spot_shocks: Option<String>,
+    /// Vol shocks in vol points (e.g. `-5,0,5`).
+    pub vol_shocks: Option<String>,
+    /// Horizons in days to roll forward (e.g. `0,1,7`). Defaults to `0`.
+    pub days: Option<String>,
+}
+
+/// P&L matrix for one time horizon.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScenarioGridSlice {
+    /// Days rolled forward before revaluing.
+    pub days_forward: u32,
+    /// P&L in cents: `pnl[i][j]` is for `spot_shocks_pct[i]` and
+    /// `vol_shocks_pts[j]`.
+    pub pnl: Vec<Vec<i64>>,
+}
+
+/// P&L attributed to one underlying.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnderlyingPnl {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// P&L in cents.
+    pub pnl: i64,
+}
+
+/// Result of one named stress scenario.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StressScenarioResult {
+    /// Scenario name from configuration.
+    pub name: String,
+    /// Scenario description from configuration.
+    pub description: String,
+    /// Days rolled forward before revaluing.
+    pub days_forward: u32,
+    /// Total P&L in cents.
+    pub pnl: i64,
+    /// P&L in cents per underlying.
+    pub by_underlying: Vec<UnderlyingPnl>,
+}
+
+/// Response for the scenario endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScenarioAnalysisResponse {
+    /// Account the positions belong to (the caller's token subject).
+    pub account: String,
+    /// Number of positions revalued.
+    pub positions: usize,
+    /// Current mark-to-model value of the book in cents.
+    pub base_value: i64,
+    /// Spot shock axis of the grid, in percent.
+    pub spot_shocks_pct: Vec<f64>,
+    /// Vol shock axis of the grid, in vol points.
+    pub vol_shocks_pts: Vec<f64>,
+    /// One P&L matrix per requested horizon.
+    pub grid: Vec<ScenarioGridSlice>,
+    /// Named stress scenarios from configuration.
+    pub stress: Vec<StressScenarioResult>,
+    /// Instruments excluded from revaluation (no spot price, unparseable
+    /// symbol, or a degenerate model value).
+    pub unpriced: Vec<String>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Query parameters for the VaR endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct VarQuery {
+    /// Recompute now instead of returning the latest scheduled report.
+    pub refresh: Option<bool>,
+}
+
+/// VaR and expected shortfall of one method.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VarEstimateResponse {
+    /// Value-at-Risk in cents (positive = loss).
+    pub var: i64,
+    /// Expected shortfall in cents (positive = loss).
+    pub expected_shortfall: i64,
+    /// Number of scenarios the estimate is based on.
+    pub scenarios: usize,
+}
+
+/// Response for the VaR endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VarResponse {
+    /// Account the positions belong to (the caller's token subject).
+    pub account: String,
+    /// Confidence level (e.g. `0.99`).
+    pub confidence: f64,
+    /// Holding period in days.
+    pub horizon_days: u32,
+    /// Number of positions revalued.
+    pub positions: usize,
+    /// Current mark-to-model value of the book in cents.
+    pub base_value: i64,
+    /// Monte Carlo estimate, absent when it could not be computed.
+    pub monte_carlo: Option<VarEstimateResponse>,
+    /// Historical-simulation estimate, absent without enough stored prices.
+    pub historical: Option<VarEstimateResponse>,
+    /// Instruments excluded from revaluation.
+    pub unpriced: Vec<String>,
+    /// Why an estimate is missing, if one is.
+    pub notes: Vec<String>,
+    /// When the report was computed, in milliseconds.
+    pub computed_at_ms: u64,
+}
+
+impl From<VarEstimate> for VarEstimateResponse {
+    fn from(e: VarEstimate) -> Self {
+        Self {
+            var: to_cents(e.var_cents),
+            expected_shortfall: to_cents(e.expected_shortfall_cents),
+            scenarios: e.scenarios,
+        }
+    }
+}
+
+impl From<VarReport> for VarResponse {
+    fn from(r: VarReport) -> Self {
+        Self {
+            account: r.account,
+            confidence: r.confidence,
+            horizon_days: r.horizon_days,
+            positions: r.positions,
+            base_value: to_cents(r.base_value_cents),
+            monte_carlo: r.monte_carlo.map(Into::into),
+            historical: r.historical.map(Into::into),
+            unpriced: r.unpriced,
+            notes: r.notes,
+            computed_at_ms: r.computed_at_ms,
+        }
+    }
+}
+
+impl From<GreekTotals> for NetGreeks {
+    fn from(g: GreekTotals) -> Self {
+        Self {
+            delta: g.delta,
+            gamma: g.gamma,
+            vega: g.vega,
+            theta: g.theta,
+            rho: g.rho,
+            dollar_delta: g.dollar_delta,
+            dollar_gamma: g.dollar_gamma,
+        }
+    }
+}
+
+impl From<ExpirationGreeks> for ExpirationGreeksResponse {
+    fn from(e: ExpirationGreeks) -> Self {
+        Self {
+            expiration: e.expiration,
+            positions: e.positions,
+            greeks: e.greeks.into(),
+        }
+    }
+}
+
+impl From<UnderlyingGreeks> for UnderlyingGreeksResponse {
+    fn from(u: UnderlyingGreeks) -> Self {
+        Self {
+            underlying: u.underlying,
+            spot: u.spot,
+            greeks: u.greeks.into(),
+            expirations: u.expirations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<PortfolioGreeks> for GreekBookResponse {
+    fn from(p: PortfolioGreeks) -> Self {
+        Self {
+            underlyings: p.underlyings.into_iter().map(Into::into).collect(),
+            total: p.total.into(),
+            unpriced: p.unpriced,
+        }
+    }
+}
+
+// ============================================================================
+// Position Resolution
+// ============================================================================
+
+/// Resolves a tracked position into a [`RiskPosition`] by parsing its
+/// `UNDERLYING-EXPIRATION-STRIKE-STYLE` symbol.
+///
+/// The expiration segment is whatever the order path carried (YYYYMMDD or a
+/// day count), so it is parsed with the same rules as the REST paths and
+/// bucketed by its formatted date. Returns `None` for a symbol that does not
+/// follow the canonical layout.
+pub(crate) fn risk_position_from_info(position: &PositionInfo) -> Option<RiskPosition> {
+    let rest = position
+        .symbol
+        .strip_prefix(position.underlying.as_str())?
+        .strip_prefix('-')?;
+    let mut parts = rest.rsplitn(3, '-');
+    let style = match parts.next()? {
+        "C" | "c" => OptionStyle::Call,
+        "P" | "p" => OptionStyle::Put,
+        _ => return None,
+    };
+    let strike: u64 = parts.next()?.parse().ok()?;
+    let expiration = parse_expiration(parts.next()?).ok()?;
+
+    Some(RiskPosition {
+        instrument: position.symbol.clone(),
+        underlying: position.underlying.clone(),
+        expiration_key: format_expiration(&expiration),
+        expiration,
+        strike,
+        style,
+        quantity: position.quantity,
+        multiplier: position.multiplier,
+    })
+}
+
+impl From<InventoryPosition> for RiskPosition {
+    fn from(p: InventoryPosition) -> Self {
+        Self {
+            expiration_key: format_expiration(&p.expiration),
+            instrument: p.instrument,
+            underlying: p.underlying,
+            expiration: p.expiration,
+            strike: p.strike,
+            style: p.style,
+            quantity: p.quantity,
+            multiplier: p.multiplier,
+        }
+    }
+}
+
+/// Collects `account`'s open positions (optionally for one underlying) as
+/// [`RiskPosition`]s, returning the symbols that could not be parsed alongside.
+pub(crate) fn account_risk_positions(
+    state: &AppState,
+    account: &str,
+    underlying: Option<&str>,
+) -> (Vec<RiskPosition>, Vec<String>) {
+    let mut positions = Vec::new();
+    let mut unparsed = Vec::new();
+    if let Some(book) = state.account_positions.get(account) {
+        for entry in book.iter() {
+            let info = entry.value();
+            if info.quantity == 0 || underlying.is_some_and(|u| u != info.underlying) {
+                continue;
+            }
+            match risk_position_from_info(info) {
+                Some(position) => positions.push(position),
+                None => unparsed.push(info.symbol.clone()),
+            }
+        }
+    }
+    (positions, unparsed)
+}
+
+/// Parses a comma-separated list of shocks, falling back to `default` when the
+/// parameter is absent.
+///
+/// # Errors
+/// Returns [`ApiError::InvalidRequest`] for an empty list, an unparseable or
+/// non-finite entry, an entry rejected by `valid`, or more than `max` entries.
+fn parse_shock_list<T: std::str::FromStr + Copy>(
+    param: &str,
+    raw: Option<&str>,
+    default: &[T],
+    max: usize,
+    valid: impl Fn(T) -> bool,
+) -> Result<Vec<T>, ApiError> {
+    let Some(raw) = raw else {
+        return Ok(default.to_vec());
+    };
+    let values = raw
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse::<T>()
+                .ok()
+                .filter(|v| valid(*v))
+                .ok_or_else(|| {
+                    ApiError::InvalidRequest(format!("invalid {param} entry: {:?}", item.trim()))
+                })
+        })
+        .collect::<Result<Vec<T>, ApiError>>()?;
+    if values.is_empty() || values.len() > max {
+        return Err(ApiError::InvalidRequest(format!(
+            "{param} must list between 1 and {max} values"
+        )));
+    }
+    Ok(values)
+}
+
+/// Resolves the shock a stress scenario applies to `underlying`: its own
+/// shock, else the scenario-wide shock, else none.
+fn stress_shock(scenario: &StressScenarioConfig, underlying: &str) -> MarketShock {
+    let specific = scenario
+        .shocks
+        .iter()
+        .find(|s| s.underlying.as_deref() == Some(underlying));
+    let shock = specific.or_else(|| scenario.shocks.iter().find(|s| s.underlying.is_none()));
+    shock.map_or_else(MarketShock::default, |s| MarketShock {
+        spot_pct: s.spot_shock_pct,
+        vol_pts: s.vol_shock_pts,
+    })
+}
+
+/// Rounds a cents amount from the f64 revaluation to integer cents.
+pub(crate) fn to_cents(value: f64) -> i64 {
+    value.round() as i64
+}
+
+impl From<(&StressScenarioConfig, ScenarioPnl)> for StressScenarioResult {
+    fn from((scenario, pnl): (&StressScenarioConfig, ScenarioPnl)) -> Self {
+        Self {
+            name: scenario.name.clone(),
+            description: scenario.description.clone(),
+            days_forward: scenario.days_forward,
+            pnl: to_cents(pnl.total_cents),
+            by_underlying: pnl
+                .by_underlying
+                .into_iter()
+                .map(|(underlying, cents)| UnderlyingPnl {
+                    underlying,
+                    pnl: to_cents(cents),
+                })
+                .collect(),
+        }
+    }
+}
+
+// ============================================================================
+// Value-at-Risk
+// ============================================================================
+
+/// Loads the last stored close per UTC day for `symbols` over the past
+/// `lookback_days` days from the repository.
+async fn load_daily_closes(
+    state: &AppState,
+    symbols: &[String],
+    lookback_days: u32,
+) -> Result<BTreeMap<String, BTreeMap<NaiveDate, f64>>, ApiError> {
+    let rows = state
+        .repository
+        .daily_closes(symbols, lookback_days)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut closes: BTreeMap<String, BTreeMap<NaiveDate, f64>> = BTreeMap::new();
+    for close in rows.into_iter().filter(|c| c.price_cents > 0) {
+        closes
+            .entry(close.symbol)
+            .or_default()
+            .insert(close.day, close.price_cents as f64 / 100.0);
+    }
+    Ok(closes)
+}
+
+/// Monte Carlo dynamics for `underlying`: the configured asset's volatility
+/// and drift, or the pricer's default volatility with no drift.
+fn underlying_model(state: &AppState, underlying: &str) -> UnderlyingModel {
+    let asset = state
+        .config
+        .as_ref()
+        .and_then(|c| c.assets.iter().find(|a| a.symbol == underlying));
+    UnderlyingModel {
+        underlying: underlying.to_string(),
+        volatility: asset.map_or_else(
+            || state.market_maker.pricer().default_iv(),
+            |a| a.volatility,
+        ),
+        drift: asset.map_or(0.0, |a| a.drift),
+    }
+}
+
+/// Computes a fresh VaR report for `account` with the `[risk.var]` settings
+/// and stores it as the account's latest.
+///
+/// Monte Carlo and historical simulation each fail soft: an estimate that
+/// cannot be produced is left out and the reason recorded in `notes`.
+pub async fn refresh_account_var(state: &Arc<AppState>, account: &str) -> VarReport {
+    let settings = state
+        .config
+        .as_ref()
+        .map(|c| c.risk.var.clone())
+        .unwrap_or_default();
+    let walk_type = state
+        .config
+        .as_ref()
+        .map_or(WalkTypeConfig::GeometricBrownian, |c| {
+            c.simulation.walk_type.clone()
+        });
+
+    let (positions, mut unpriced) = account_risk_positions(state, account, None);
+    let book = ScenarioBook::new(state.market_maker.pricer(), positions, |u| {
+        spot_price(state, u)
+    });
+    let underlyings = book.underlyings();
+    let models: Vec<UnderlyingModel> = underlyings
+        .iter()
+        .map(|u| underlying_model(state, u))
+        .collect();
+
+    let mut notes = Vec::new();
+    // `None` when historical VaR cannot be attempted at all (reason noted).
+    let closes = match load_daily_closes(state, &underlyings, settings.lookback_days).await {
+        Ok(closes) => Some(closes),
+        Err(e) => {
+            tracing::warn!(account, error = %e, "failed to load price history for VaR");
+            notes.push(format!("historical VaR unavailable: {e}"));
+            None
+        }
+    };
+
+    // Walk generation and repricing are CPU-bound: keep them off the runtime.
+    let worker_state = Arc::clone(state);
+    let worker_book = book.clone();
+    let horizon = settings.horizon_days;
+    let (paths, confidence) = (settings.paths, settings.confidence);
+    let joined = tokio::task::spawn_blocking(move || {
+        let pricer = worker_state.market_maker.pricer();
+        let pnl_var = |returns: &BTreeMap<String, Vec<f64>>| {
+            var_es(
+                &scenario_pnls(&worker_book, pricer, returns, horizon),
+                confidence,
+            )
+        };
+        let monte_carlo =
+            monte_carlo_returns(&models, &walk_type, horizon, paths).map(|r| pnl_var(&r));
+        let historical = closes.map(|c| pnl_var(&historical_returns(&c, horizon)));
+        (monte_carlo, historical)
+    })
+    .await;
+
+    let (monte_carlo, historical) = match joined {
+        Ok((monte_carlo, historical)) => {
+            let monte_carlo = monte_carlo.unwrap_or_else(|e| {
+                notes.push(format!("Monte Carlo VaR unavailable: {e}"));
+                None
+            });
+            if matches!(historical, Some(None)) && !book.is_empty() {
+                notes.push(format!(
+                    "historical VaR unavailable: fewer than {} common daily closes in the last {} days",
+                    horizon + 1,
+                    settings.lookback_days
+                ));
+            }
+            (monte_carlo, historical.flatten())
+        }
+        Err(e) => {
+            tracing::error!(account, error = %e, "VaR worker panicked");
+            notes.push("VaR computation failed".to_string());
+            (None, None)
+        }
+    };
+    if book.is_empty() {
+        notes.push("no priced positions".to_string());
+    }
+
+    unpriced.extend(book.unpriced().iter().cloned());
+    unpriced.sort();
+    let report = VarReport {
+        account: account.to_string(),
+        confidence,
+        horizon_days: horizon,
+        positions: book.len(),
+        base_value_cents: book.base_value_cents(),
+        monte_carlo,
+        historical,
+        unpriced,
+        notes,
+        computed_at_ms: chrono::Utc::now().timestamp_millis() as u64,
+    };
+    state
+        .var_reports
+        .insert(account.to_string(), report.clone());
+    report
+}
+
+/// Recomputes VaR for every account holding positions. Called on the
+/// `[risk.var] interval_seconds` schedule; returns the number of accounts.
+pub async fn refresh_all_var(state: &Arc<AppState>) -> usize {
+    let accounts: Vec<String> = state
+        .account_positions
+        .iter()
+        .map(|entry| entry.key().clone())
+        .collect();
+    for account in &accounts {
+        refresh_account_var(state, account).await;
+    }
+    accounts.len()
+}
+
+/// Current spot for `underlying` in cents: the market maker's price (what the
+/// quoter values against), falling back to the price simulator.
+pub(crate) fn spot_price(state: &AppState, underlying: &str) -> Option<u64> {
+    state.market_maker.get_price(underlying).or_else(|| {
+        state
+            .price_simulator
+            .as_ref()
+            .and_then(|sim| sim.get_price(underlying))
+    })
+}
+
+/// Values `positions` with the quoter's pricer. `unparsed` instruments are
+/// reported as unpriced alongside any the aggregation could not value.
+fn greek_book(
+    state: &AppState,
+    positions: &[RiskPosition],
+    unparsed: Vec<String>,
+) -> PortfolioGreeks {
+    let mut book = aggregate_greeks(state.market_maker.pricer(), positions, |u| {
+        spot_price(state, u)
+    });
+    if !unparsed.is_empty() {
+        book.unpriced.extend(unparsed);
+        book.unpriced.sort();
+    }
+    book
+}
+
+// ============================================================================
+// Risk Handlers
+// ============================================================================
+
+/// Get net portfolio greeks for the caller's account and the market maker.
+///
+/// Aggregates delta, gamma, vega, theta, rho and dollar greeks per underlying,
+/// per expiration and in total, valuing every position with the same pricer the
+/// quoter uses. The caller's positions and the market maker's own inventory are
+/// reported as separate books.
+#[utoipa::path(
+    get,
+    path = "/api/v1/risk/greeks",
+    params(
+        ("underlying" = Option<String>, Query, description = "Filter by underlying symbol")
+    ),
+    responses(
+        (status = 200, description = "Portfolio greeks", body = PortfolioGreeksResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn get_portfolio_greeks(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<RiskGreeksQuery>,
+) -> Result<Json<PortfolioGreeksResponse>, ApiError> {
+    let filter = query.underlying.as_deref();
+    let wanted = |underlying: &str| filter.is_none_or(|f| f == underlying);
+    let (positions, unparsed) = account_risk_positions(&state, claims.account(), filter);
+
+    let inventory: Vec<RiskPosition> = state
+        .market_maker
+        .inventory()
+        .into_iter()
+        .filter(|p| wanted(&p.underlying))
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(PortfolioGreeksResponse {
+        account: claims.account().to_string(),
+        positions: greek_book(&state, &positions, unparsed).into(),
+        market_maker: greek_book(&state, &inventory, Vec::new()).into(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+    }))
+}
+
+/// Revalue the caller's positions over shock grids and stress scenarios.
+///
+/// Every position is fully repriced (not approximated from greeks) with the
+/// quoter's pricer under each combination of spot shock, vol shock and time
+/// horizon, producing one P&L matrix per horizon. Named stress scenarios from
+/// the `[[risk.stress_scenarios]]` configuration are evaluated alongside. P&L
+/// is in cents against the current mark-to-model value.
+#[utoipa::path(
+    get,
+    path = "/api/v1/risk/scenarios",
+    params(
+        ("underlying" = Option<String>, Query, description = "Filter by underlying symbol"),
+        ("spot_shocks" = Option<String>, Query, description = "Comma-separated spot shocks in percent (default -30,-20,-10,-5,0,5,10,20,30)"),
+        ("vol_shocks" = Option<String>, Query, description = "Comma-separated vol shocks in vol points (default -10,0,10)"),
+        ("days" = Option<String>, Query, description = "Comma-separated horizons in days (default 0)")
+    ),
+    responses(
+        (status = 200, description = "Scenario P&L", body = ScenarioAnalysisResponse),
+        (status = 400, description = "Invalid shock list", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse),
+        (status = 500, description = "Scenario computation failed", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn get_risk_scenarios(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ScenarioQuery>,
+) -> Result<Json<ScenarioAnalysisResponse>, ApiError> {
+    let spot_shocks = parse_shock_list(
+        "spot_shocks",
+        query.spot_shocks.as_deref(),
+        &DEFAULT_SPOT_SHOCKS_PCT,
+        MAX_SCENARIO_AXIS,
+        |v: f64| v.is_finite() && v > -100.0,
+    )?;
+    let vol_shocks = parse_shock_list(
+        "vol_shocks",
+        query.vol_shocks.as_deref(),
+        &DEFAULT_VOL_SHOCKS_PTS,
+        MAX_SCENARIO_AXIS,
+        f64::is_finite,
+    )?;
+    let horizons = parse_shock_list(
+        "days",
+        query.days.as_deref(),
+        &[0u32],
+        MAX_SCENARIO_HORIZONS,
+        |d| d <= MAX_SCENARIO_DAYS,
+    )?;
+
+    let (positions, mut unparsed) =
+        account_risk_positions(&state, claims.account(), query.underlying.as_deref());
+    let pricer = state.market_maker.pricer();
+    let book = ScenarioBook::new(pricer, positions, |u| spot_price(&state, u));
+
+    // Full repricing over the grid is CPU-bound: keep it off the runtime.
+    let worker_state = Arc::clone(&state);
+    let worker_book = book.clone();
+    let (worker_spot, worker_vol) = (spot_shocks.clone(), vol_shocks.clone());
+    let (grid, stress) = tokio::task::spawn_blocking(move || {
+        let pricer = worker_state.market_maker.pricer();
+        let grid: Vec<ScenarioGridSlice> = horizons
+            .iter()
+            .map(|&days_forward| ScenarioGridSlice {
+                days_forward,
+                pnl: worker_book
+                    .pnl_grid(pricer, &worker_spot, &worker_vol, days_forward)
+                    .into_iter()
+                    .map(|row| row.into_iter().map(to_cents).collect())
+                    .collect(),
+            })
+            .collect();
+        let stress: Vec<StressScenarioResult> = worker_state
+            .config
+            .as_ref()
+            .map(|config| config.risk.stress_scenarios.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|scenario| {
+                let pnl = worker_book.revalue(
+                    pricer,
+                    |u| stress_shock(scenario, u),
+                    scenario.days_forward,
+                );
+                StressScenarioResult::from((scenario, pnl))
+            })
+            .collect();
+        (grid, stress)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!(account = claims.account(), error = %e, "scenario worker panicked");
+        ApiError::Internal("scenario computation failed".to_string())
+    })?;
+
+    unparsed.extend(book.unpriced().iter().cloned());
+    unparsed.sort();
+
+    Ok(Json(ScenarioAnalysisResponse {
+        account: claims.account().to_string(),
+        positions: book.len(),
+        base_value: to_cents(book.base_value_cents()),
+        spot_shocks_pct: spot_shocks,
+        vol_shocks_pts: vol_shocks,
+        grid,
+        stress,
+        unpriced: unparsed,
+        timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+    }))
+}
+
+/// Value-at-Risk and expected shortfall of the caller's positions.
+///
+/// Returns the latest scheduled report (see `[risk.var] interval_seconds`),
+/// computing one on first use or when `refresh=true`. Monte Carlo VaR reprices
+/// the book under horizon returns drawn from the configured price walk;
+/// historical VaR replays overlapping horizon returns from the daily closes
+/// stored in `underlying_prices`. Losses are positive cents.
+#[utoipa::path(
+    get,
+    path = "/api/v1/risk/var",
+    params(
+        ("refresh" = Option<bool>, Query, description = "Recompute instead of returning the latest report")
+    ),
+    responses(
+        (status = 200, description = "VaR report", body = VarResponse),
+        (status = 401, description = "Missing or invalid authentication token", body = ErrorResponse)
+    ),
+    tag = "Risk"
+)]
+pub async fn get_value_at_risk(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<VarQuery>,
+) -> Result<Json<VarResponse>, ApiError> {
+    let account = claims.account();
+    let cached = if query.refresh.unwrap_or(false) {
+        None
+    } else {
+        state.var_reports.get(account).map(|r| r.value().clone())
+    };
+    let report = match cached {
+        Some(report) => report,
+        None => refresh_account_var(&state, account).await,
+    };
+    Ok(Json(report.into()))
+}
@@ -0,0 +1,542 @@
+//! Unit tests for the risk module.
+
+use super::*;
+use crate::api::handlers::update_account_position_on_fill;
+use crate::api::test_support::{btc_state, claims_for};
+use crate::config::{Config, ScenarioShockConfig};
+use crate::models::OrderSide;
+
+fn position_info(symbol: &str, underlying: &str, quantity: i64) -> PositionInfo {
+    PositionInfo::new(symbol.to_string(), underlying.to_string(), quantity, 500, 0)
+}
+
+// ============================================================================
+// Position Resolution Tests
+// ============================================================================
+
+#[test]
+fn test_risk_position_from_info_parses_canonical_symbol() {
+    let position = risk_position_from_info(&position_info("BTC-20351231-5000000-P", "BTC", -3))
+        .expect("canonical symbol parses");
+
+    assert_eq!(position.underlying, "BTC");
+    assert_eq!(position.expiration_key, "20351231");
+    assert_eq!(position.strike, 5_000_000);
+    assert_eq!(position.style, OptionStyle::Put);
+    assert_eq!(position.quantity, -3);
+}
+
+#[test]
+fn test_risk_position_from_info_handles_hyphenated_underlying() {
+    let position = risk_position_from_info(&position_info("BTC-USD-20351231-100-C", "BTC-USD", 1))
+        .expect("underlying prefix is stripped before splitting");
+    assert_eq!(position.underlying, "BTC-USD");
+    assert_eq!(position.strike, 100);
+    assert_eq!(position.style, OptionStyle::Call);
+}
+
+#[test]
+fn test_risk_position_from_info_rejects_malformed_symbols() {
+    for symbol in [
+        "BTC-20351231-100-X",
+        "BTC-20351231-abc-C",
+        "ETH-20351231-100-C",
+        "BTC-C",
+    ] {
+        assert!(
+            risk_position_from_info(&position_info(symbol, "BTC", 1)).is_none(),
+            "{symbol} must not parse"
+        );
+    }
+}
+
+// ============================================================================
+// Handler Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_portfolio_greeks_reports_only_the_callers_account() {
+    let state = btc_state(Config::default());
+    state.market_maker.update_price("ETH", 300_000);
+
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Buy,
+        2,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20361231-5000000-P",
+        "BTC",
+        OrderSide::Sell,
+        1,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "ETH-20351231-300000-C",
+        "ETH",
+        OrderSide::Buy,
+        1,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "bob",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        9,
+        500,
+        0,
+    );
+
+    let Json(response) = get_portfolio_greeks(
+        State(state.clone()),
+        Extension(claims_for("alice")),
+        Query(RiskGreeksQuery::default()),
+    )
+    .await
+    .expect("greeks computed");
+
+    assert_eq!(response.account, "alice");
+    let book = &response.positions;
+    assert!(book.unpriced.is_empty());
+    assert_eq!(book.underlyings.len(), 2);
+
+    let btc = &book.underlyings[0];
+    assert_eq!(btc.underlying, "BTC");
+    assert_eq!(btc.spot, 5_000_000);
+    assert_eq!(btc.expirations.len(), 2);
+    // Long calls and short puts are both long delta.
+    assert!(btc.greeks.delta > 0.0);
+    let delta_sum: f64 = book.underlyings.iter().map(|u| u.greeks.delta).sum();
+    assert!((delta_sum - book.total.delta).abs() < 1e-9);
+
+    // No maker fills yet: the market-maker book is empty.
+    assert!(response.market_maker.underlyings.is_empty());
+    assert_eq!(response.market_maker.total.delta, 0.0);
+}
+
+#[tokio::test]
+async fn test_portfolio_greeks_filters_by_underlying_and_flags_unpriced() {
+    let state = btc_state(Config::default());
+
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Buy,
+        1,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "XYZ-20351231-100-C",
+        "XYZ",
+        OrderSide::Buy,
+        1,
+        500,
+        0,
+    );
+
+    let Json(all) = get_portfolio_greeks(
+        State(state.clone()),
+        Extension(claims_for("alice")),
+        Query(RiskGreeksQuery::default()),
+    )
+    .await
+    .expect("greeks computed");
+    assert_eq!(all.positions.underlyings.len(), 1);
+    assert_eq!(
+        all.positions.unpriced,
+        vec!["XYZ-20351231-100-C".to_string()]
+    );
+
+    let Json(filtered) = get_portfolio_greeks(
+        State(state),
+        Extension(claims_for("alice")),
+        Query(RiskGreeksQuery {
+            underlying: Some("XYZ".to_string()),
+        }),
+    )
+    .await
+    .expect("greeks computed");
+    assert!(filtered.positions.underlyings.is_empty());
+    assert_eq!(filtered.positions.unpriced.len(), 1);
+}
+
+#[tokio::test]
+async fn test_portfolio_greeks_reports_market_maker_inventory_separately() {
+    let state = Arc::new(AppState::new());
+    state.market_maker.update_price("BTC", 10_000_000);
+
+    // A filled maker bid leaves the engine long one BTC call.
+    let maker_id = state.market_maker.track_order_for_test(true, 100, 1);
+    state.market_maker.on_order_filled(maker_id, 100, 1);
+
+    let Json(response) = get_portfolio_greeks(
+        State(state),
+        Extension(claims_for("alice")),
+        Query(RiskGreeksQuery::default()),
+    )
+    .await
+    .expect("greeks computed");
+
+    assert!(response.positions.underlyings.is_empty());
+    let mm = &response.market_maker;
+    assert_eq!(mm.underlyings.len(), 1);
+    assert_eq!(mm.underlyings[0].expirations[0].expiration, "20351231");
+    assert!(mm.total.delta > 0.0);
+}
+
+#[test]
+fn test_parse_shock_list_defaults_and_validation() {
+    let default = parse_shock_list("spot_shocks", None, &DEFAULT_SPOT_SHOCKS_PCT, 5, |_| true)
+        .expect("default used");
+    assert_eq!(default, DEFAULT_SPOT_SHOCKS_PCT.to_vec());
+
+    let parsed = parse_shock_list("vol_shocks", Some(" -5, 0 ,5"), &[0.0], 5, f64::is_finite)
+        .expect("valid list");
+    assert_eq!(parsed, vec![-5.0, 0.0, 5.0]);
+
+    for raw in ["", "1,,2", "abc", "NaN", "1,2,3,4,5,6"] {
+        assert!(
+            parse_shock_list("vol_shocks", Some(raw), &[0.0], 5, f64::is_finite).is_err(),
+            "{raw:?} must be rejected"
+        );
+    }
+    assert!(parse_shock_list("spot_shocks", Some("-100"), &[0.0], 5, |v: f64| v > -100.0).is_err());
+}
+
+#[test]
+fn test_stress_shock_prefers_underlying_over_wildcard() {
+    let scenario = StressScenarioConfig {
+        name: "mixed".to_string(),
+        description: String::new(),
+        days_forward: 0,
+        shocks: vec![
+            ScenarioShockConfig {
+                underlying: None,
+                spot_shock_pct: -10.0,
+                vol_shock_pts: 0.0,
+            },
+            ScenarioShockConfig {
+                underlying: Some("BTC".to_string()),
+                spot_shock_pct: -30.0,
+                vol_shock_pts: 20.0,
+            },
+        ],
+    };
+    assert_eq!(stress_shock(&scenario, "BTC").spot_pct, -30.0);
+    assert_eq!(stress_shock(&scenario, "ETH").spot_pct, -10.0);
+
+    let only_btc = StressScenarioConfig {
+        shocks: vec![scenario.shocks[1].clone()],
+        ..scenario
+    };
+    assert_eq!(stress_shock(&only_btc, "ETH"), MarketShock::default());
+}
+
+#[tokio::test]
+async fn test_risk_scenarios_grid_and_configured_stress() {
+    let mut config = Config::default();
+    config.risk.stress_scenarios = vec![StressScenarioConfig {
+        name: "btc-crash".to_string(),
+        description: "BTC -30%".to_string(),
+        days_forward: 0,
+        shocks: vec![ScenarioShockConfig {
+            underlying: Some("BTC".to_string()),
+            spot_shock_pct: -30.0,
+            vol_shock_pts: 0.0,
+        }],
+    }];
+    let state = btc_state(config);
+    state.market_maker.update_price("ETH", 300_000);
+
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Buy,
+        2,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "ETH-20351231-300000-P",
+        "ETH",
+        OrderSide::Buy,
+        1,
+        500,
+        0,
+    );
+    update_account_position_on_fill(
+        &state,
+        "bob",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        5,
+        500,
+        0,
+    );
+
+    let Json(response) = get_risk_scenarios(
+        State(state),
+        Extension(claims_for("alice")),
+        Query(ScenarioQuery {
+            underlying: None,
+            spot_shocks: Some("-20,0,20".to_string()),
+            vol_shocks: Some("0".to_string()),
+            days: Some("0,30".to_string()),
+        }),
+    )
+    .await
+    .expect("scenarios computed");
+
+    assert_eq!(response.account, "alice");
+    assert_eq!(response.positions, 2);
+    assert!(response.base_value > 0);
+    assert!(response.unpriced.is_empty());
+    assert_eq!(response.grid.len(), 2);
+
+    let today = &response.grid[0];
+    assert_eq!(today.days_forward, 0);
+    assert_eq!(today.pnl.len(), 3);
+    assert_eq!(today.pnl[1][0], 0);
+    // Long options: time decay costs money at an unchanged spot.
+    assert!(response.grid[1].pnl[1][0] < 0);
+
+    let crash = &response.stress[0];
+    assert_eq!(crash.name, "btc-crash");
+    assert!(crash.pnl < 0);
+    // Only BTC is shocked: the ETH put is unchanged.
+    assert_eq!(crash.by_underlying.len(), 2);
+    assert_eq!(crash.by_underlying[1].underlying, "ETH");
+    assert_eq!(crash.by_underlying[1].pnl, 0);
+    assert_eq!(crash.pnl, crash.by_underlying[0].pnl);
+}
+
+#[tokio::test]
+async fn test_risk_scenarios_rejects_invalid_shocks() {
+    let state = Arc::new(AppState::new());
+    for query in [
+        ScenarioQuery {
+            spot_shocks: Some("-150".to_string()),
+            ..ScenarioQuery::default()
+        },
+        ScenarioQuery {
+            vol_shocks: Some("inf".to_string()),
+            ..ScenarioQuery::default()
+        },
+        ScenarioQuery {
+            days: Some(format!("{}", MAX_SCENARIO_DAYS + 1)),
+            ..ScenarioQuery::default()
+        },
+        ScenarioQuery {
+            days: Some("-1".to_string()),
+            ..ScenarioQuery::default()
+        },
+    ] {
+        let result = get_risk_scenarios(
+            State(state.clone()),
+            Extension(claims_for("alice")),
+            Query(query),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_risk_scenarios_empty_book_uses_defaults() {
+    let state = Arc::new(AppState::new());
+    let Json(response) = get_risk_scenarios(
+        State(state),
+        Extension(claims_for("nobody")),
+        Query(ScenarioQuery::default()),
+    )
+    .await
+    .expect("scenarios computed");
+
+    assert_eq!(response.positions, 0);
+    assert_eq!(response.spot_shocks_pct, DEFAULT_SPOT_SHOCKS_PCT.to_vec());
+    assert_eq!(response.vol_shocks_pts, DEFAULT_VOL_SHOCKS_PTS.to_vec());
+    assert_eq!(response.grid.len(), 1);
+    assert!(response.grid[0].pnl.iter().flatten().all(|p| *p == 0));
+    assert!(response.stress.is_empty());
+}
+
+#[tokio::test]
+async fn test_value_at_risk_monte_carlo_without_database() {
+    let mut config = Config::default();
+    config.risk.var.paths = 200;
+    config.risk.var.horizon_days = 5;
+    let state = btc_state(config);
+
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        3,
+        500,
+        0,
+    );
+
+    let Json(response) = get_value_at_risk(
+        State(state.clone()),
+        Extension(claims_for("alice")),
+        Query(VarQuery::default()),
+    )
+    .await
+    .expect("VaR computed");
+
+    assert_eq!(response.account, "alice");
+    assert_eq!(response.horizon_days, 5);
+    assert_eq!(response.positions, 1);
+    let mc = response.monte_carlo.as_ref().expect("Monte Carlo estimate");
+    assert_eq!(mc.scenarios, 200);
+    // A short call loses on rallies: the 99% tail is a real loss.
+    assert!(mc.var > 0);
+    assert!(mc.expected_shortfall >= mc.var);
+    assert!(response.historical.is_none());
+    // No prices were inserted, so the in-memory repository has no closes.
+    assert!(
+        response
+            .notes
+            .iter()
+            .any(|n| n.contains("fewer than 6 common daily closes"))
+    );
+
+    // The report is cached until a refresh is requested.
+    let Json(cached) = get_value_at_risk(
+        State(state.clone()),
+        Extension(claims_for("alice")),
+        Query(VarQuery::default()),
+    )
+    .await
+    .expect("VaR computed");
+    assert_eq!(cached.computed_at_ms, response.computed_at_ms);
+    assert_eq!(cached.monte_carlo.expect("cached").var, mc.var);
+
+    state.var_reports.clear();
+    assert_eq!(refresh_all_var(&state).await, 1);
+    assert!(state.var_reports.contains_key("alice"));
+}
+
+#[tokio::test]
+async fn test_value_at_risk_historical_from_in_memory_prices() {
+    let mut config = Config::default();
+    config.risk.var.paths = 200;
+    config.risk.var.horizon_days = 5;
+    let state = btc_state(config);
+
+    // Thirty daily closes alternating around 50,000 in the repository.
+    let now = chrono::Utc::now();
+    for day in 0..30 {
+        let price_cents = if day % 2 == 0 { 5_000_000 } else { 5_100_000 };
+        let record = crate::db::PriceRecord {
+            symbol: "BTC".to_string(),
+            price_cents,
+            bid_cents: None,
+            ask_cents: None,
+            volume: None,
+            timestamp: now - chrono::Duration::days(day),
+            source: None,
+        };
+        state
+            .repository
+            .insert_prices(&[record])
+            .await
+            .expect("stored");
+    }
+    update_account_position_on_fill(
+        &state,
+        "alice",
+        "BTC-20351231-5000000-C",
+        "BTC",
+        OrderSide::Sell,
+        3,
+        500,
+        0,
+    );
+
+    let Json(response) = get_value_at_risk(
+        State(state),
+        Extension(claims_for("alice")),
+        Query(VarQuery::default()),
+    )
+    .await
+    .expect("VaR computed");
+
+    let historical = response.historical.expect("historical estimate");
+    assert!(historical.scenarios > 0);
+    assert!(response.notes.iter().all(|n| !n.starts_with("historical")));
+}
+
+#[tokio::test]
+async fn test_value_at_risk_empty_book_reports_no_estimate() {
+    let state = Arc::new(AppState::new());
+    let Json(response) = get_value_at_risk(
+        State(state),
+        Extension(claims_for("nobody")),
+        Query(VarQuery {
+            refresh: Some(true),
+        }),
+    )
+    .await
+    .expect("VaR computed");
+
+    assert_eq!(response.positions, 0);
+    assert!(response.monte_carlo.is_none());
+    assert!(response.historical.is_none());
+    assert!(response.notes.iter().any(|n| n == "no priced positions"));
+    assert_eq!(response.confidence, 0.99);
+}
+
+#[test]
+fn test_portfolio_greeks_response_serialization() {
+    let greeks = NetGreeks {
+        delta: 1.5,
+        gamma: 0.01,
+        vega: 2.0,
+        theta: -0.5,
+        rho: 0.25,
+        dollar_delta: 150.0,
+        dollar_gamma: 1.0,
+    };
+    let book = GreekBookResponse {
+        underlyings: vec![],
+        total: greeks,
+        unpriced: vec![],
+    };
+    let response = PortfolioGreeksResponse {
+        account: "desk-1".to_string(),
+        positions: book.clone(),
+        market_maker: book,
+        timestamp_ms: 1,
+    };
+
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"account\":\"desk-1\""));
+    assert!(json.contains("\"market_maker\":{"));
+    assert!(json.contains("\"dollar_gamma\":1.0"));
+}
@@ -1,6 +1,6 @@
 //! Route configuration.
 
-use crate::api::{controls, handlers, middleware, websocket};
+use crate::api::{account, controls, handlers, liquidation, margin, middleware, risk, websocket};
 use crate::state::AppState;
 use axum::Router;
 use axum::middleware as axum_middleware;
@@ -9,6 +9,29 @@ use std::sync::Arc;
 
 /// Creates the API router.
 pub fn create_router(state: Arc<AppState>) -> Router {
+    api_routes()
+        // Hold mutations off while a whole-state snapshot is taken or restored
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::mutation_gate,
+        ))
+        // Apply authentication + rate-limiting middleware
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth_middleware,
+        ))
+        .with_state(state)
+}
+
+/// Creates the router journal replay feeds recorded requests through: the
+/// API routes without authentication, rate limiting or the mutation gate.
+/// Each request carries the claims recorded with it.
+pub fn create_replay_router(state: Arc<AppState>) -> Router {
+    api_routes().with_state(state)
+}
+
+/// Every API route, without middleware or state.
+pub(crate) fn api_routes() -> Router<Arc<AppState>> {
     Router::new()
         // Health check
         .route("/health", get(handlers::health_check))
@@ -24,6 +47,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/api/v1/controls/enable", post(controls::enable_quoting))
         .route("/api/v1/controls/parameters", post(controls::update_parameters))
         .route("/api/v1/controls/instruments", get(controls::list_instruments))
+        .route("/api/v1/controls/hedging", get(controls::get_hedging))
+        .route("/api/v1/controls/pnl", get(controls::get_pnl_attribution))
+        .route("/api/v1/controls/requotes", get(controls::get_requote_stats))
+        .route("/api/v1/controls/queue", get(controls::get_queue_positions))
         .route(
             "/api/v1/controls/instrument/{symbol}/toggle",
             post(controls::toggle_instrument),
@@ -31,6 +58,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Prices
         .route("/api/v1/prices", get(controls::get_all_prices).post(controls::insert_price))
         .route("/api/v1/prices/{symbol}", get(controls::get_latest_price))
+        .route(
+            "/api/v1/prices/{symbol}/history",
+            get(controls::get_price_history),
+        )
         // Underlyings
         .route("/api/v1/underlyings", get(handlers::list_underlyings))
         .route(
@@ -53,11 +84,20 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/v1/underlyings/{underlying}/volatility-surface",
             get(handlers::get_volatility_surface),
         )
+        .route(
+            "/api/v1/underlyings/{underlying}/volatility-surface/history",
+            get(handlers::get_volatility_surface_history),
+        )
         // Option Chain Matrix
         .route(
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/chain",
             get(handlers::get_option_chain),
         )
+        // Implied Forward / Put-Call Parity
+        .route(
+            "/api/v1/underlyings/{underlying}/expirations/{expiration}/forward",
+            get(handlers::get_implied_forward),
+        )
         // Strikes
         .route(
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes",
@@ -88,6 +128,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/quote",
             get(handlers::get_option_quote),
         )
+        .route(
+            "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/spec",
+            get(handlers::get_instrument_spec),
+        )
         .route(
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/greeks",
             get(handlers::get_option_greeks),
@@ -104,6 +148,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/ohlc",
             get(handlers::get_ohlc),
         )
+        .route(
+            "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/quotes/history",
+            get(handlers::get_quote_history),
+        )
         .route(
             "/api/v1/underlyings/{underlying}/expirations/{expiration}/strikes/{strike}/options/{style}/metrics",
             get(handlers::get_orderbook_metrics),
@@ -123,6 +171,18 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Position tracking
         .route("/api/v1/positions", get(handlers::list_positions))
         .route("/api/v1/positions/{symbol}", get(handlers::get_position))
+        // Portfolio risk
+        .route("/api/v1/risk/greeks", get(risk::get_portfolio_greeks))
+        .route("/api/v1/risk/scenarios", get(risk::get_risk_scenarios))
+        .route("/api/v1/risk/var", get(risk::get_value_at_risk))
+        .route("/api/v1/risk/margin", get(margin::get_account_margin))
+        .route(
+            "/api/v1/risk/liquidation",
+            get(liquidation::get_liquidation),
+        )
+        // Account cash
+        .route("/api/v1/account/balance", get(account::get_account_balance))
+        .route("/api/v1/account/ledger", get(account::get_account_ledger))
         // Execution reports
         .route("/api/v1/executions", get(handlers::list_executions))
         .route(
@@ -130,6 +190,23 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             get(handlers::get_execution),
         )
         // Admin - Orderbook persistence
+        .route(
+            "/api/v1/admin/accounts/{account}/collateral",
+            post(margin::set_account_collateral),
+        )
+        .route(
+            "/api/v1/admin/accounts/{account}/deposit",
+            post(account::deposit_funds),
+        )
+        .route(
+            "/api/v1/admin/accounts/{account}/withdraw",
+            post(account::withdraw_funds),
+        )
+        .route(
+            "/api/v1/admin/liquidations",
+            get(liquidation::list_liquidations),
+        )
+        .route("/api/v1/admin/ohlc/backfill", post(handlers::backfill_ohlc))
         .route("/api/v1/admin/snapshot", post(handlers::create_snapshot))
         .route("/api/v1/admin/snapshots", get(handlers::list_snapshots))
         .route(
@@ -140,10 +217,4 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/v1/admin/snapshots/{snapshot_id}/restore",
             post(handlers::restore_snapshot),
         )
-        // Apply authentication + rate-limiting middleware
-        .layer(axum_middleware::from_fn_with_state(
-            state.clone(),
-            middleware::auth_middleware,
-        ))
-        .with_state(state)
 }
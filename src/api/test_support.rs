@@ -0,0 +1,61 @@
+//! Fixtures shared by the API handler unit tests.
+
+use crate::api::handlers::add_order;
+use crate::auth::Claims;
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::{AddOrderRequest, AddOrderResponse, OrderSide, Permission};
+use crate::state::AppState;
+use axum::extract::{Path, State};
+use axum::{Extension, Json};
+use std::sync::Arc;
+
+/// Trade-permissioned claims for `account`, standing in for what
+/// `auth_middleware` injects on a real request.
+pub(crate) fn claims_for(account: &str) -> Claims {
+    Claims {
+        sub: account.to_string(),
+        iss: "test".to_string(),
+        iat: 0,
+        exp: u64::MAX,
+        permissions: vec![Permission::Trade],
+    }
+}
+
+/// A state running with `config` and BTC at $50,000.
+pub(crate) fn btc_state(config: Config) -> Arc<AppState> {
+    let mut state = AppState::new();
+    state.config = Some(config);
+    let state = Arc::new(state);
+    state.market_maker.update_price("BTC", 5_000_000);
+    state
+}
+
+/// Places a GTC limit order for `account` on the BTC 2035-12-31 $50,000 call.
+pub(crate) async fn limit_order(
+    state: &Arc<AppState>,
+    account: &str,
+    side: OrderSide,
+    price: u128,
+    quantity: u64,
+) -> Result<AddOrderResponse, ApiError> {
+    add_order(
+        State(state.clone()),
+        Extension(claims_for(account)),
+        Path((
+            "BTC".to_string(),
+            "20351231".to_string(),
+            5_000_000,
+            "call".to_string(),
+        )),
+        Json(AddOrderRequest {
+            side,
+            price,
+            quantity,
+            time_in_force: None,
+            expire_at: None,
+        }),
+    )
+    .await
+    .map(|Json(response)| response)
+}
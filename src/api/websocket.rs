@@ -1,5 +1,7 @@
 //! WebSocket handler for real-time updates.
 
+use crate::api::controls::PnlPeriodResponse;
+use crate::api::liquidation::{LiquidationStatus, LiquidationStep};
 use crate::auth::Claims;
 use crate::error::ErrorResponse;
 use crate::market_maker::{
@@ -107,6 +109,34 @@ pub enum WsMessage {
         /// Price in cents.
         price_cents: u64,
     },
+    /// Market-maker delta hedge in the underlying.
+    ///
+    /// Broadcast like [`WsMessage::Fill`]; REST `/api/v1/controls/hedging`
+    /// is authoritative.
+    #[serde(rename = "hedge")]
+    Hedge {
+        /// Underlying symbol.
+        symbol: String,
+        /// Signed quantity of the underlying (positive = bought).
+        quantity: f64,
+        /// Execution price in cents.
+        price: u64,
+        /// Hedge position after the trade.
+        hedge_position: f64,
+        /// Net option delta plus hedge position after the trade.
+        residual_delta: f64,
+        /// Hedge P&L in cents.
+        hedge_pnl: i64,
+    },
+    /// Market-maker P&L attribution of a period that just closed.
+    ///
+    /// Broadcast like [`WsMessage::Fill`]; REST `/api/v1/controls/pnl` keeps
+    /// recent periods.
+    #[serde(rename = "pnl")]
+    Pnl {
+        /// The closed period.
+        period: PnlPeriodResponse,
+    },
     /// Connection established.
     #[serde(rename = "connected")]
     Connected {
@@ -222,6 +252,20 @@ pub enum WsMessage {
         /// Active subscriptions.
         active: Vec<ActiveSubscription>,
     },
+    /// Liquidation step of the connection's own account.
+    ///
+    /// Private: delivered only to connections authenticated as the liquidated
+    /// account, without a subscription. REST `/api/v1/risk/liquidation` keeps
+    /// the full record.
+    #[serde(rename = "liquidation")]
+    Liquidation {
+        /// Account identifier.
+        account: String,
+        /// Status after the step.
+        status: LiquidationStatus,
+        /// The step taken.
+        step: LiquidationStep,
+    },
 }
 
 /// Subscription channel types.
@@ -352,6 +396,15 @@ pub struct TradeEvent {
     pub taker_order_id: String,
 }
 
+/// Event for one account, delivered only to that account's connections.
+#[derive(Debug, Clone)]
+pub struct AccountEvent {
+    /// Account the event belongs to.
+    pub account: String,
+    /// Message sent to the account's connections.
+    pub message: WsMessage,
+}
+
 /// Manages orderbook and trade subscriptions.
 pub struct OrderbookSubscriptionManager {
     /// Sequence counters per symbol.
@@ -360,6 +413,8 @@ pub struct OrderbookSubscriptionManager {
     delta_tx: broadcast::Sender<OrderbookDeltaEvent>,
     /// Broadcast channel for trade events.
     trade_tx: broadcast::Sender<TradeEvent>,
+    /// Broadcast channel for private account events.
+    account_tx: broadcast::Sender<AccountEvent>,
 }
 
 impl OrderbookSubscriptionManager {
@@ -368,10 +423,12 @@ impl OrderbookSubscriptionManager {
     pub fn new() -> Self {
         let (delta_tx, _) = broadcast::channel(1000);
         let (trade_tx, _) = broadcast::channel(1000);
+        let (account_tx, _) = broadcast::channel(1000);
         Self {
             sequences: DashMap::new(),
             delta_tx,
             trade_tx,
+            account_tx,
         }
     }
 
@@ -419,6 +476,17 @@ impl OrderbookSubscriptionManager {
     pub fn subscribe_trades(&self) -> broadcast::Receiver<TradeEvent> {
         self.trade_tx.subscribe()
     }
+
+    /// Broadcasts a private account event.
+    pub fn broadcast_account_event(&self, event: AccountEvent) {
+        let _ = self.account_tx.send(event);
+    }
+
+    /// Subscribes to private account events.
+    #[must_use]
+    pub fn subscribe_account_events(&self) -> broadcast::Receiver<AccountEvent> {
+        self.account_tx.subscribe()
+    }
 }
 
 impl Default for OrderbookSubscriptionManager {
@@ -471,6 +539,9 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, claims: Claims)
     // Subscribe to trade events
     let mut trade_rx = state.orderbook_subscriptions.subscribe_trades();
 
+    // Subscribe to private account events
+    let mut account_rx = state.orderbook_subscriptions.subscribe_account_events();
+
     // Track this client's orderbook subscriptions
     let subscribed_symbols: Arc<tokio::sync::RwLock<HashSet<String>>> =
         Arc::new(tokio::sync::RwLock::new(HashSet::new()));
@@ -535,6 +606,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, claims: Claims)
     let sender_clone = Arc::clone(&sender);
     let subscribed_symbols_clone = Arc::clone(&subscribed_symbols);
     let subscribed_trades_clone = Arc::clone(&subscribed_trades);
+    let account = subject.clone();
     // Graceful shutdown (issue #118): when `main.rs` wired the watch signal,
     // the send task observes it and closes the connection promptly; without
     // the wiring (unit tests) the branch never fires.
@@ -653,6 +725,25 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, claims: Claims)
                         }
                     }
                 }
+                // Handle private account events: only the account's own
+                // connections receive them.
+                account_event = account_rx.recv() => {
+                    match account_event {
+                        Ok(account_event) => {
+                            if account_event.account == account
+                                && let Ok(json) = serde_json::to_string(&account_event.message)
+                                    && sender_clone.lock().await.send(Message::Text(json.into())).await.is_err() {
+                                        break;
+                                    }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Account events lagged {} messages", n);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            break;
+                        }
+                    }
+                }
                 // Send periodic heartbeat on a fixed wall-clock cadence
                 _ = heartbeat.tick() => {
                     let heartbeat_msg = WsMessage::Heartbeat {
@@ -760,6 +851,24 @@ fn event_to_ws_message(event: MarketMakerEvent) -> Option<WsMessage> {
             symbol,
             price_cents,
         }),
+        MarketMakerEvent::HedgeExecuted {
+            symbol,
+            quantity,
+            price,
+            hedge_position,
+            residual_delta,
+            hedge_pnl,
+        } => Some(WsMessage::Hedge {
+            symbol,
+            quantity,
+            price,
+            hedge_position,
+            residual_delta,
+            hedge_pnl,
+        }),
+        MarketMakerEvent::PnlPeriodClosed { period } => Some(WsMessage::Pnl {
+            period: (&period).into(),
+        }),
     }
 }
 
@@ -1786,6 +1895,88 @@ mod tests {
         assert!(json.contains("\"taker_order_id\":\"taker-789\""));
     }
 
+    #[test]
+    fn test_hedge_event_to_ws_message() {
+        let msg = event_to_ws_message(MarketMakerEvent::HedgeExecuted {
+            symbol: "BTC".to_string(),
+            quantity: -2.5,
+            price: 5_000_000,
+            hedge_position: -2.5,
+            residual_delta: 0.1,
+            hedge_pnl: 0,
+        })
+        .expect("hedges are forwarded");
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"hedge\""));
+        assert!(json.contains("\"quantity\":-2.5"));
+        assert!(json.contains("\"price\":5000000"));
+    }
+
+    #[test]
+    fn test_pnl_event_to_ws_message() {
+        let mut by_underlying = std::collections::BTreeMap::new();
+        by_underlying.insert(
+            "BTC".to_string(),
+            crate::market_maker::PnlComponents {
+                edge: 500.0,
+                theta: -120.0,
+                ..Default::default()
+            },
+        );
+        let msg = event_to_ws_message(MarketMakerEvent::PnlPeriodClosed {
+            period: crate::market_maker::PnlPeriod {
+                start_ms: 1_000,
+                end_ms: Some(2_000),
+                by_underlying,
+            },
+        })
+        .expect("closed periods are forwarded");
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"pnl\""));
+        assert!(json.contains("\"end_ms\":2000"));
+        assert!(json.contains("\"edge\":500"));
+        assert!(json.contains("\"total\":380"));
+    }
+
+    #[test]
+    fn test_ws_message_liquidation_serialization() {
+        let msg = WsMessage::Liquidation {
+            account: "alice".to_string(),
+            status: LiquidationStatus::Liquidating,
+            step: LiquidationStep {
+                action: crate::api::liquidation::LiquidationAction::PositionReduced,
+                instrument: Some("BTC-20351231-5000000-C".to_string()),
+                side: Some(crate::models::OrderSide::Buy),
+                quantity: 2,
+                price: Some(52_500),
+                equity: -1_000,
+                maintenance_margin: 75_000,
+                timestamp_ms: 1704067200000,
+            },
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"liquidation\""));
+        assert!(json.contains("\"status\":\"liquidating\""));
+        assert!(json.contains("\"action\":\"position_reduced\""));
+        assert!(json.contains("\"side\":\"buy\""));
+    }
+
+    #[tokio::test]
+    async fn test_account_events_reach_subscribers() {
+        let manager = OrderbookSubscriptionManager::new();
+        let mut rx = manager.subscribe_account_events();
+        manager.broadcast_account_event(AccountEvent {
+            account: "alice".to_string(),
+            message: WsMessage::Heartbeat { timestamp: 1 },
+        });
+        let event = rx.recv().await.expect("event delivered");
+        assert_eq!(event.account, "alice");
+        assert!(matches!(
+            event.message,
+            WsMessage::Heartbeat { timestamp: 1 }
+        ));
+    }
+
     #[test]
     fn test_trade_event_creation() {
         let event = TradeEvent {
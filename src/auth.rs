@@ -28,6 +28,9 @@ const CLOCK_SKEW_LEEWAY_SECS: u64 = 60;
 /// Sliding rate-limit window length in milliseconds (60s, per issue #48).
 const RATE_LIMIT_WINDOW_MS: u64 = 60_000;
 
+/// Maximum length of a caller-chosen account id (the JWT `sub`).
+pub const MAX_ACCOUNT_ID_LEN: usize = 64;
+
 /// Hard upper bound on the number of distinct keys the [`RateLimiter`] tracks at
 /// once (issue #48: bound the window map against memory-exhaustion DoS from many
 /// distinct subjects / peer IPs). When the map is full, a sweep of fully-expired
@@ -93,7 +96,8 @@ pub fn constant_time_eq(secret: &[u8], candidate: &[u8]) -> bool {
 /// the [`Permission`] enum (Admin implies all — see [`Claims::has_permission`]).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Claims {
-    /// Subject — the token identity, used as the rate-limit key.
+    /// Subject — the token identity, used as the rate-limit key and as the
+    /// trading account every order, fill, and position is attributed to.
     pub sub: String,
     /// Issuer (the `iss` claim).
     pub iss: String,
@@ -111,6 +115,40 @@ impl Claims {
     pub fn has_permission(&self, permission: Permission) -> bool {
         self.permissions.contains(&permission) || self.permissions.contains(&Permission::Admin)
     }
+
+    /// The trading account this token acts for (the `sub` claim).
+    ///
+    /// Tokens minted without an explicit account carry a random UUID subject,
+    /// so each such token is its own account.
+    #[must_use]
+    pub fn account(&self) -> &str {
+        &self.sub
+    }
+}
+
+/// Validates a caller-chosen account id before it is embedded as a JWT `sub`.
+///
+/// Accepts 1..=[`MAX_ACCOUNT_ID_LEN`] ASCII alphanumerics plus `-`, `_` and `.`,
+/// so an account id is always safe as a map key, a log field, and a URL path
+/// segment.
+///
+/// # Errors
+/// Returns [`ApiError::InvalidRequest`] naming the violated rule.
+pub fn validate_account_id(account: &str) -> Result<(), ApiError> {
+    if account.is_empty() || account.len() > MAX_ACCOUNT_ID_LEN {
+        return Err(ApiError::InvalidRequest(format!(
+            "account must be 1..={MAX_ACCOUNT_ID_LEN} characters"
+        )));
+    }
+    if !account
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+    {
+        return Err(ApiError::InvalidRequest(
+            "account may only contain ASCII letters, digits, '-', '_' and '.'".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 /// Outcome of a rate-limit check: the admission decision plus the sliding
@@ -528,6 +566,23 @@ impl JwtAuth {
         &self,
         permissions: Vec<Permission>,
         ttl_secs: u64,
+    ) -> Result<(String, u64), ApiError> {
+        self.mint_token_for(Uuid::new_v4().to_string(), permissions, ttl_secs)
+    }
+
+    /// Mints and signs a JWT whose `sub` is the given `account`.
+    ///
+    /// Every token minted for the same account shares its positions and its
+    /// rate-limit budget. The caller is responsible for validating `account`
+    /// (see [`validate_account_id`]).
+    ///
+    /// # Errors
+    /// Same as [`JwtAuth::mint_token`].
+    pub fn mint_token_for(
+        &self,
+        account: String,
+        permissions: Vec<Permission>,
+        ttl_secs: u64,
     ) -> Result<(String, u64), ApiError> {
         let now = now_secs();
         let exp = now
@@ -535,7 +590,7 @@ impl JwtAuth {
             .ok_or_else(|| ApiError::InvalidRequest("token ttl overflow".to_string()))?;
 
         let claims = Claims {
-            sub: Uuid::new_v4().to_string(),
+            sub: account,
             iss: self.issuer.clone(),
             iat: now,
             exp,
@@ -625,6 +680,33 @@ mod tests {
         assert!(!claims.has_permission(Permission::Admin));
     }
 
+    #[test]
+    fn test_mint_token_for_account_sets_subject() {
+        let auth = dev_auth();
+        let (token, _) = auth
+            .mint_token_for("desk-1".to_string(), vec![Permission::Trade], 3600)
+            .expect("mint");
+
+        let claims = auth.verify_token(&token).expect("verify");
+        assert_eq!(claims.sub, "desk-1");
+        assert_eq!(claims.account(), "desk-1");
+    }
+
+    #[test]
+    fn test_validate_account_id() {
+        assert!(validate_account_id("desk-1").is_ok());
+        assert!(validate_account_id("a.b_c-9").is_ok());
+        assert!(validate_account_id(&"x".repeat(MAX_ACCOUNT_ID_LEN)).is_ok());
+
+        for bad in ["", "has space", "slash/inside", "ünicode"] {
+            assert!(
+                matches!(validate_account_id(bad), Err(ApiError::InvalidRequest(_))),
+                "{bad:?} must be rejected"
+            );
+        }
+        assert!(validate_account_id(&"x".repeat(MAX_ACCOUNT_ID_LEN + 1)).is_err());
+    }
+
     #[test]
     fn test_expired_token_rejected() {
         let auth = dev_auth();
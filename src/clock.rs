@@ -0,0 +1,72 @@
+//! Event time.
+//!
+//! Code that changes state reads the current time through [`now_ms`] and
+//! [`now`] instead of the system clock. While a journaled input is applied,
+//! they return the time the input was journaled at
+//! ([`JournalEntry::timestamp_ms`](crate::journal::JournalEntry::timestamp_ms)),
+//! so replaying the journal sees exactly the times of the recorded run: GTD
+//! admission, order and fill timestamps, requote throttling and pricing all
+//! come out the same. Outside an input they return the wall-clock time.
+//!
+//! The time is scoped to the task applying the input ([`at`], [`at_sync`]);
+//! work handed to another task or thread sees the wall clock again.
+
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+tokio::task_local! {
+    static EVENT_TIME_MS: u64;
+}
+
+/// Current time in milliseconds since the Unix epoch: the time of the input
+/// being applied, or the wall-clock time outside one.
+#[must_use]
+pub fn now_ms() -> u64 {
+    EVENT_TIME_MS
+        .try_with(|ms| *ms)
+        .unwrap_or_else(|_| wall_ms())
+}
+
+/// Current time, as [`now_ms`].
+#[must_use]
+pub fn now() -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(i64::try_from(now_ms()).unwrap_or(i64::MAX)).unwrap_or_default()
+}
+
+/// Wall-clock time in milliseconds since the Unix epoch, whatever input is
+/// being applied.
+#[must_use]
+pub fn wall_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Runs `future` with the current time fixed at `time_ms`.
+pub async fn at<F: Future>(time_ms: u64, future: F) -> F::Output {
+    EVENT_TIME_MS.scope(time_ms, future).await
+}
+
+/// Runs `apply` with the current time fixed at `time_ms`.
+pub fn at_sync<R>(time_ms: u64, apply: impl FnOnce() -> R) -> R {
+    EVENT_TIME_MS.sync_scope(time_ms, apply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scoped_time_overrides_the_wall_clock() {
+        let before = wall_ms();
+        assert!(now_ms() >= before);
+
+        assert_eq!(at_sync(1_000, now_ms), 1_000);
+        assert_eq!(at(2_000, async { now_ms() }).await, 2_000);
+        assert_eq!(at_sync(3_000, now).timestamp_millis(), 3_000);
+        assert_eq!(at(4_000, async { at_sync(5_000, now_ms) }).await, 5_000);
+
+        assert!(now_ms() >= before);
+    }
+}
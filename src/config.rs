@@ -1,6 +1,7 @@
 //! Configuration module for loading and parsing TOML configuration files.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
@@ -141,6 +142,33 @@ pub struct Config {
     /// and built-in dev defaults fill any gaps (see [`AuthConfig::resolved`]).
     #[serde(default)]
     pub auth: Option<AuthConfig>,
+    /// Risk analytics configuration (stress scenarios).
+    #[serde(default)]
+    pub risk: RiskConfig,
+    /// Cash ledger configuration (fees, buying power, expiry settlement).
+    #[serde(default)]
+    pub ledger: LedgerConfig,
+    /// Durable orderbook snapshot storage and retention.
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+    /// Write-ahead event journal and startup replay.
+    #[serde(default)]
+    pub journal: JournalConfig,
+    /// Durable OHLC history.
+    #[serde(default)]
+    pub ohlc: OhlcConfig,
+    /// Top-of-book history.
+    #[serde(default)]
+    pub quote_history: QuoteHistoryConfig,
+    /// Volatility surface history.
+    #[serde(default)]
+    pub surface_history: SurfaceHistoryConfig,
+    /// Trading state persistence.
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    /// Market maker settings (delta hedging, P&L attribution).
+    #[serde(default)]
+    pub market_maker: MakerConfig,
     /// List of configured assets.
     pub assets: Vec<AssetConfig>,
 }
@@ -324,76 +352,1115 @@ fn resolve_cors_origins_from(env: Option<&str>, config_origins: &[String]) -> Co
         }
     }
 
-    let from_config: Vec<String> = config_origins
-        .iter()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-    if !from_config.is_empty() {
-        return CorsOrigins {
-            origins: from_config,
-            source: CorsOriginsSource::Config,
-        };
-    }
+    let from_config: Vec<String> = config_origins
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !from_config.is_empty() {
+        return CorsOrigins {
+            origins: from_config,
+            source: CorsOriginsSource::Config,
+        };
+    }
+
+    CorsOrigins {
+        origins: DEFAULT_CORS_ALLOWED_ORIGINS
+            .iter()
+            .map(|s| (*s).to_string())
+            .collect(),
+        source: CorsOriginsSource::Default,
+    }
+}
+
+/// Resolves the effective CORS allowlist, layering (highest precedence first):
+/// the `CORS_ALLOWED_ORIGINS` environment variable (comma-separated), the
+/// `[server] cors_allowed_origins` config field, then the built-in dev defaults
+/// ([`DEFAULT_CORS_ALLOWED_ORIGINS`]). Never falls back to a permissive wildcard.
+#[must_use]
+pub fn resolved_cors_origins(config: Option<&Config>) -> CorsOrigins {
+    let env = std::env::var(ENV_CORS_ALLOWED_ORIGINS).ok();
+    let config_origins: &[String] = config
+        .map(|c| c.server.cors_allowed_origins.as_slice())
+        .unwrap_or(&[]);
+    resolve_cors_origins_from(env.as_deref(), config_origins)
+}
+
+/// Price simulation configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationConfig {
+    /// Whether price simulation is enabled.
+    pub enabled: bool,
+    /// Update interval in milliseconds.
+    pub interval_ms: u64,
+    /// Type of random walk to use.
+    pub walk_type: WalkTypeConfig,
+    /// Record every simulated price into `underlying_prices` (with a
+    /// database connected), next to the inserted ones.
+    #[serde(default)]
+    pub record_prices: bool,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 1000,
+            walk_type: WalkTypeConfig::GeometricBrownian,
+            record_prices: false,
+        }
+    }
+}
+
+/// Cleanup configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleanupConfig {
+    /// Interval in seconds between cleanup runs.
+    pub interval_seconds: u64,
+    /// Age in seconds after which filled/canceled orders are removed.
+    pub retention_seconds: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 60,
+            retention_seconds: 300, // 5 minutes
+        }
+    }
+}
+
+/// Maximum accepted stress-scenario horizon, in days.
+pub const MAX_SCENARIO_DAYS: u32 = 3_650;
+
+/// Risk analytics configuration.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RiskConfig {
+    /// Named stress scenarios evaluated by `GET /api/v1/risk/scenarios`.
+    #[serde(default)]
+    pub stress_scenarios: Vec<StressScenarioConfig>,
+    /// Value-at-Risk settings for `GET /api/v1/risk/var`.
+    #[serde(default)]
+    pub var: VarConfig,
+    /// Portfolio margin settings for `GET /api/v1/risk/margin` and the
+    /// pre-trade margin check.
+    #[serde(default)]
+    pub margin: MarginConfig,
+    /// Automated liquidation of accounts below maintenance margin.
+    #[serde(default)]
+    pub liquidation: LiquidationConfig,
+}
+
+/// Portfolio margin configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarginConfig {
+    /// Reject orders whose initial margin would exceed the account's
+    /// collateral. Margin is still computed and reported when disabled.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Price scan range in percent of spot.
+    #[serde(default = "default_price_scan_pct")]
+    pub price_scan_pct: f64,
+    /// Volatility scan range in vol points.
+    #[serde(default = "default_vol_scan_pts")]
+    pub vol_scan_pts: f64,
+    /// Short option minimum per short contract, in percent of spot.
+    #[serde(default = "default_short_option_minimum_pct")]
+    pub short_option_minimum_pct: f64,
+    /// Maintenance margin as a fraction of initial margin, in `(0, 1]`.
+    #[serde(default = "default_maintenance_ratio")]
+    pub maintenance_ratio: f64,
+    /// Collateral in dollars credited to an account that has none recorded.
+    #[serde(default)]
+    pub default_collateral: f64,
+}
+
+fn default_price_scan_pct() -> f64 {
+    15.0
+}
+
+fn default_vol_scan_pts() -> f64 {
+    10.0
+}
+
+fn default_short_option_minimum_pct() -> f64 {
+    1.0
+}
+
+fn default_maintenance_ratio() -> f64 {
+    0.75
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            price_scan_pct: default_price_scan_pct(),
+            vol_scan_pts: default_vol_scan_pts(),
+            short_option_minimum_pct: default_short_option_minimum_pct(),
+            maintenance_ratio: default_maintenance_ratio(),
+            default_collateral: 0.0,
+        }
+    }
+}
+
+impl MarginConfig {
+    /// Validates the margin settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a price scan outside
+    /// `(0, 50)` percent, a negative or non-finite vol scan or short option
+    /// minimum, a maintenance ratio outside `(0, 1]`, or a default collateral
+    /// that is not a valid dollar amount.
+    fn validate(&self) -> Result<(), ConfigError> {
+        // Extreme moves scan twice the range, which must stay above -100%.
+        if !(self.price_scan_pct > 0.0 && self.price_scan_pct < 50.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.margin price_scan_pct must be between 0 and 50 (exclusive), got {}",
+                self.price_scan_pct
+            )));
+        }
+        for (name, value) in [
+            ("vol_scan_pts", self.vol_scan_pts),
+            ("short_option_minimum_pct", self.short_option_minimum_pct),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ConfigError::InvalidValue(format!(
+                    "risk.margin {name} must be finite and non-negative, got {value}"
+                )));
+            }
+        }
+        if !(self.maintenance_ratio > 0.0 && self.maintenance_ratio <= 1.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.margin maintenance_ratio must be in (0, 1], got {}",
+                self.maintenance_ratio
+            )));
+        }
+        if dollars_to_cents(self.default_collateral).is_none() {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.margin default_collateral must be a finite, non-negative dollar amount, got {}",
+                self.default_collateral
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Liquidation engine configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LiquidationConfig {
+    /// Liquidate accounts whose equity falls below maintenance margin.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between liquidation sweeps.
+    #[serde(default = "default_liquidation_interval_seconds")]
+    pub interval_seconds: u64,
+    /// How far from the model price a closing IOC order may trade, in percent.
+    #[serde(default = "default_max_slippage_pct")]
+    pub max_slippage_pct: f64,
+    /// Transfer what the books cannot absorb to the market maker.
+    #[serde(default = "default_transfer_to_market_maker")]
+    pub transfer_to_market_maker: bool,
+    /// Penalty against the model price charged on a transfer, in percent.
+    #[serde(default = "default_penalty_pct")]
+    pub penalty_pct: f64,
+}
+
+fn default_liquidation_interval_seconds() -> u64 {
+    5
+}
+
+fn default_max_slippage_pct() -> f64 {
+    5.0
+}
+
+fn default_transfer_to_market_maker() -> bool {
+    true
+}
+
+fn default_penalty_pct() -> f64 {
+    10.0
+}
+
+impl Default for LiquidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: default_liquidation_interval_seconds(),
+            max_slippage_pct: default_max_slippage_pct(),
+            transfer_to_market_maker: default_transfer_to_market_maker(),
+            penalty_pct: default_penalty_pct(),
+        }
+    }
+}
+
+impl LiquidationConfig {
+    /// Validates the liquidation settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a zero interval or a slippage
+    /// or penalty outside `[0, 100)` percent.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.interval_seconds == 0 {
+            return Err(ConfigError::InvalidValue(
+                "risk.liquidation interval_seconds must be positive".to_string(),
+            ));
+        }
+        for (name, value) in [
+            ("max_slippage_pct", self.max_slippage_pct),
+            ("penalty_pct", self.penalty_pct),
+        ] {
+            if !(value.is_finite() && (0.0..100.0).contains(&value)) {
+                return Err(ConfigError::InvalidValue(format!(
+                    "risk.liquidation {name} must be in [0, 100), got {value}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maximum accepted number of Monte Carlo paths per VaR run.
+pub const MAX_VAR_PATHS: usize = 100_000;
+
+/// Value-at-Risk and expected-shortfall configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VarConfig {
+    /// Confidence level, strictly between 0.5 and 1 (e.g. `0.99`).
+    #[serde(default = "default_var_confidence")]
+    pub confidence: f64,
+    /// Holding period in days.
+    #[serde(default = "default_var_horizon_days")]
+    pub horizon_days: u32,
+    /// Number of Monte Carlo paths per underlying.
+    #[serde(default = "default_var_paths")]
+    pub paths: usize,
+    /// Days of recorded price history used by historical VaR.
+    #[serde(default = "default_var_lookback_days")]
+    pub lookback_days: u32,
+    /// Interval in seconds between scheduled recomputations (0 disables).
+    #[serde(default = "default_var_interval_seconds")]
+    pub interval_seconds: u64,
+}
+
+fn default_var_confidence() -> f64 {
+    0.99
+}
+
+fn default_var_horizon_days() -> u32 {
+    1
+}
+
+fn default_var_paths() -> usize {
+    1_000
+}
+
+fn default_var_lookback_days() -> u32 {
+    250
+}
+
+fn default_var_interval_seconds() -> u64 {
+    300
+}
+
+impl Default for VarConfig {
+    fn default() -> Self {
+        Self {
+            confidence: default_var_confidence(),
+            horizon_days: default_var_horizon_days(),
+            paths: default_var_paths(),
+            lookback_days: default_var_lookback_days(),
+            interval_seconds: default_var_interval_seconds(),
+        }
+    }
+}
+
+impl VarConfig {
+    /// Validates the VaR settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a confidence outside
+    /// `(0.5, 1)`, a zero or out-of-range horizon, a path count outside
+    /// `1..=MAX_VAR_PATHS`, or a lookback no longer than the horizon.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.confidence > 0.5 && self.confidence < 1.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.var confidence must be between 0.5 and 1 (exclusive), got {}",
+                self.confidence
+            )));
+        }
+        if self.horizon_days == 0 || self.horizon_days > MAX_SCENARIO_DAYS {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.var horizon_days must be between 1 and {}, got {}",
+                MAX_SCENARIO_DAYS, self.horizon_days
+            )));
+        }
+        if self.paths == 0 || self.paths > MAX_VAR_PATHS {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.var paths must be between 1 and {}, got {}",
+                MAX_VAR_PATHS, self.paths
+            )));
+        }
+        if self.lookback_days <= self.horizon_days {
+            return Err(ConfigError::InvalidValue(format!(
+                "risk.var lookback_days ({}) must exceed horizon_days ({})",
+                self.lookback_days, self.horizon_days
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A named stress scenario: a set of market shocks applied together, optionally
+/// after rolling time forward.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StressScenarioConfig {
+    /// Unique scenario name.
+    pub name: String,
+    /// Human-readable description (e.g. "BTC -30%, vol +20pts").
+    #[serde(default)]
+    pub description: String,
+    /// Days to roll forward before revaluing (time decay).
+    #[serde(default)]
+    pub days_forward: u32,
+    /// Shocks applied in this scenario. A shock without an `underlying` applies
+    /// to every underlying without a shock of its own.
+    pub shocks: Vec<ScenarioShockConfig>,
+}
+
+/// A market shock for one underlying (or all, when `underlying` is omitted).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioShockConfig {
+    /// Underlying symbol; `None` applies the shock to every underlying.
+    #[serde(default)]
+    pub underlying: Option<String>,
+    /// Relative spot move in percent (e.g. `-30.0`). Must be above `-100`.
+    #[serde(default)]
+    pub spot_shock_pct: f64,
+    /// Additive implied-volatility move in vol points (e.g. `20.0`).
+    #[serde(default)]
+    pub vol_shock_pts: f64,
+}
+
+impl RiskConfig {
+    /// Validates the configured stress scenarios and VaR settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for invalid VaR, margin or
+    /// liquidation settings (see [`VarConfig`], [`MarginConfig`] and
+    /// [`LiquidationConfig`]), or for an empty or duplicate name, an
+    /// empty shock list, a horizon beyond [`MAX_SCENARIO_DAYS`], a non-finite
+    /// shock, a spot shock at or below `-100%`, or two shocks for the same
+    /// underlying within one scenario.
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.var.validate()?;
+        self.margin.validate()?;
+        self.liquidation.validate()?;
+        let mut names = std::collections::HashSet::new();
+        for scenario in &self.stress_scenarios {
+            if scenario.name.trim().is_empty() {
+                return Err(ConfigError::InvalidValue(
+                    "stress scenario name cannot be empty".to_string(),
+                ));
+            }
+            if !names.insert(scenario.name.as_str()) {
+                return Err(ConfigError::InvalidValue(format!(
+                    "duplicate stress scenario name: {}",
+                    scenario.name
+                )));
+            }
+            if scenario.shocks.is_empty() {
+                return Err(ConfigError::InvalidValue(format!(
+                    "stress scenario {} must have at least one shock",
+                    scenario.name
+                )));
+            }
+            if scenario.days_forward > MAX_SCENARIO_DAYS {
+                return Err(ConfigError::InvalidValue(format!(
+                    "stress scenario {} days_forward must be at most {}, got {}",
+                    scenario.name, MAX_SCENARIO_DAYS, scenario.days_forward
+                )));
+            }
+            let mut targets = std::collections::HashSet::new();
+            for shock in &scenario.shocks {
+                if !shock.spot_shock_pct.is_finite() || shock.spot_shock_pct <= -100.0 {
+                    return Err(ConfigError::InvalidValue(format!(
+                        "stress scenario {} spot_shock_pct must be finite and above -100, got {}",
+                        scenario.name, shock.spot_shock_pct
+                    )));
+                }
+                if !shock.vol_shock_pts.is_finite() {
+                    return Err(ConfigError::InvalidValue(format!(
+                        "stress scenario {} vol_shock_pts must be finite, got {}",
+                        scenario.name, shock.vol_shock_pts
+                    )));
+                }
+                if !targets.insert(shock.underlying.as_deref()) {
+                    return Err(ConfigError::InvalidValue(format!(
+                        "stress scenario {} has more than one shock for {}",
+                        scenario.name,
+                        shock.underlying.as_deref().unwrap_or("all underlyings")
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maximum accepted fee rate in basis points (10%).
+pub const MAX_FEE_BPS: f64 = 1_000.0;
+
+/// Durable orderbook snapshot configuration.
+///
+/// Snapshots are written to the `orderbook_snapshots` table when a database is
+/// connected, otherwise to gzip-compressed files in `directory`. With neither,
+/// they live in memory only and are lost on restart.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotConfig {
+    /// Directory of snapshot files, used when no database is connected.
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// Most durable snapshots kept; the oldest are deleted first.
+    #[serde(default = "default_snapshot_max_count")]
+    pub max_count: usize,
+    /// Age in seconds past which durable snapshots are deleted (0 keeps
+    /// them regardless of age).
+    #[serde(default)]
+    pub max_age_seconds: u64,
+    /// Restore the latest durable snapshot when the server starts.
+    #[serde(default)]
+    pub restore_on_startup: bool,
+    /// Seconds between scheduled snapshots (0 disables the schedule).
+    #[serde(default)]
+    pub interval_seconds: u64,
+    /// Take a snapshot during graceful shutdown.
+    #[serde(default)]
+    pub on_shutdown: bool,
+}
+
+fn default_snapshot_max_count() -> usize {
+    16
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            max_count: default_snapshot_max_count(),
+            max_age_seconds: 0,
+            restore_on_startup: false,
+            interval_seconds: 0,
+            on_shutdown: false,
+        }
+    }
+}
+
+impl SnapshotConfig {
+    /// Validates the snapshot settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a zero `max_count` or an
+    /// empty `directory`.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_count == 0 {
+            return Err(ConfigError::InvalidValue(
+                "snapshots max_count must be positive".to_string(),
+            ));
+        }
+        if self
+            .directory
+            .as_deref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidValue(
+                "snapshots directory must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// When the journal file is flushed to stable storage.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalFsync {
+    /// After every entry: nothing acknowledged is ever lost.
+    #[default]
+    Always,
+    /// At most once per `fsync_interval_ms`: a crash can lose the entries
+    /// of the last interval.
+    Interval,
+    /// Never: the operating system decides when the file reaches disk.
+    Never,
+}
+
+/// Write-ahead event journal configuration.
+///
+/// With a `path`, every state-changing input is appended to the journal
+/// before it is applied, and startup replays the journal on top of the
+/// latest snapshot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JournalConfig {
+    /// Journal file; `None` disables the journal.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// When appended entries are flushed to disk.
+    #[serde(default)]
+    pub fsync: JournalFsync,
+    /// Milliseconds between flushes under the `interval` policy.
+    #[serde(default = "default_journal_fsync_interval_ms")]
+    pub fsync_interval_ms: u64,
+    /// Replay the journal tail after the latest snapshot on startup.
+    #[serde(default = "default_journal_replay_on_startup")]
+    pub replay_on_startup: bool,
+}
+
+fn default_journal_fsync_interval_ms() -> u64 {
+    100
+}
+
+fn default_journal_replay_on_startup() -> bool {
+    true
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            fsync: JournalFsync::default(),
+            fsync_interval_ms: default_journal_fsync_interval_ms(),
+            replay_on_startup: default_journal_replay_on_startup(),
+        }
+    }
+}
+
+impl JournalConfig {
+    /// Validates the journal settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for an empty `path` or a zero
+    /// `fsync_interval_ms` under the `interval` policy.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self
+            .path
+            .as_deref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidValue(
+                "journal path must not be empty".to_string(),
+            ));
+        }
+        if self.fsync == JournalFsync::Interval && self.fsync_interval_ms == 0 {
+            return Err(ConfigError::InvalidValue(
+                "journal fsync_interval_ms must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Durable OHLC history configuration.
+///
+/// With a database connected, OHLC bars changed since the last flush and the
+/// executions recorded since are written every `flush_interval_seconds` and
+/// once more during graceful shutdown.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OhlcConfig {
+    /// Seconds between flushes (0 leaves only the shutdown flush).
+    #[serde(default = "default_ohlc_flush_interval_seconds")]
+    pub flush_interval_seconds: u64,
+}
+
+fn default_ohlc_flush_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for OhlcConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_seconds: default_ohlc_flush_interval_seconds(),
+        }
+    }
+}
+
+/// Top-of-book history configuration.
+///
+/// With a database connected and `enabled`, the best bid and ask of every
+/// option book are sampled every `sample_interval_ms`; each change is
+/// appended to `quote_history` every `flush_interval_seconds` and once more
+/// during graceful shutdown.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteHistoryConfig {
+    /// Record top-of-book changes.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Milliseconds between samples.
+    #[serde(default = "default_quote_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+    /// Seconds between flushes (0 leaves only the shutdown flush).
+    #[serde(default = "default_quote_flush_interval_seconds")]
+    pub flush_interval_seconds: u64,
+}
+
+fn default_quote_sample_interval_ms() -> u64 {
+    250
+}
+
+fn default_quote_flush_interval_seconds() -> u64 {
+    5
+}
+
+impl Default for QuoteHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_interval_ms: default_quote_sample_interval_ms(),
+            flush_interval_seconds: default_quote_flush_interval_seconds(),
+        }
+    }
+}
+
+impl QuoteHistoryConfig {
+    /// Validates the quote history settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a zero `sample_interval_ms`.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.sample_interval_ms == 0 {
+            return Err(ConfigError::InvalidValue(
+                "quote_history sample_interval_ms must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Volatility surface history configuration.
+///
+/// With a database connected, the volatility surface of every underlying is
+/// computed and stored every `interval_seconds`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SurfaceHistoryConfig {
+    /// Seconds between stored surfaces (0 disables the schedule).
+    #[serde(default)]
+    pub interval_seconds: u64,
+}
+
+/// Trading state persistence configuration.
+///
+/// With a database connected, the orders, fills, account positions, accounts
+/// and last trades changed since the last save are written to their tables
+/// every `flush_interval_seconds` and once more during graceful shutdown.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PersistenceConfig {
+    /// Seconds between saves (0 leaves only the shutdown save).
+    #[serde(default = "default_persistence_flush_interval_seconds")]
+    pub flush_interval_seconds: u64,
+}
+
+fn default_persistence_flush_interval_seconds() -> u64 {
+    10
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_seconds: default_persistence_flush_interval_seconds(),
+        }
+    }
+}
+
+/// Cash ledger configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LedgerConfig {
+    /// Reject buy orders whose premium (plus taker fee) exceeds the account's
+    /// available cash.
+    #[serde(default)]
+    pub enforce_buying_power: bool,
+    /// Fee charged to the taker of a fill, in basis points of premium.
+    #[serde(default)]
+    pub taker_fee_bps: f64,
+    /// Fee charged to the maker of a fill, in basis points of premium.
+    #[serde(default)]
+    pub maker_fee_bps: f64,
+    /// Seconds between expiry settlement sweeps (0 disables the schedule).
+    #[serde(default = "default_settlement_interval_seconds")]
+    pub settlement_interval_seconds: u64,
+}
+
+fn default_settlement_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            enforce_buying_power: false,
+            taker_fee_bps: 0.0,
+            maker_fee_bps: 0.0,
+            settlement_interval_seconds: default_settlement_interval_seconds(),
+        }
+    }
+}
+
+impl LedgerConfig {
+    /// Validates the ledger settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a fee rate that is negative,
+    /// non-finite or above [`MAX_FEE_BPS`].
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (name, value) in [
+            ("taker_fee_bps", self.taker_fee_bps),
+            ("maker_fee_bps", self.maker_fee_bps),
+        ] {
+            if !(value.is_finite() && (0.0..=MAX_FEE_BPS).contains(&value)) {
+                return Err(ConfigError::InvalidValue(format!(
+                    "ledger {name} must be between 0 and {MAX_FEE_BPS}, got {value}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Market maker configuration.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MakerConfig {
+    /// Delta hedging of the maker's option inventory.
+    #[serde(default)]
+    pub hedging: HedgingConfig,
+    /// P&L attribution periods.
+    #[serde(default)]
+    pub pnl: PnlConfig,
+    /// Requote throttling.
+    #[serde(default)]
+    pub requote: RequoteConfig,
+    /// Quoting strategy selection.
+    #[serde(default)]
+    pub strategy: StrategyConfig,
+}
+
+/// Maximum number of price levels per side of a quote ladder.
+pub const MAX_LADDER_LEVELS: usize = 10;
+
+/// Built-in quoting strategies.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// One bid and one ask around the theoretical value.
+    #[default]
+    Single,
+    /// A ladder of price levels per side (see [`LadderConfig`]).
+    Layered,
+}
+
+/// Quoting strategy configuration.
+///
+/// Every underlying is quoted with `default` unless `underlyings` names a
+/// strategy for it. Layered underlyings quote the `layered` ladder unless
+/// `ladders` has one of their own.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StrategyConfig {
+    /// Strategy for underlyings without their own.
+    #[serde(default)]
+    pub default: StrategyKind,
+    /// Strategy per underlying symbol.
+    #[serde(default)]
+    pub underlyings: HashMap<String, StrategyKind>,
+    /// Ladder of the layered strategy.
+    #[serde(default)]
+    pub layered: LadderConfig,
+    /// Ladder per underlying symbol, overriding `layered`.
+    #[serde(default)]
+    pub ladders: HashMap<String, LadderConfig>,
+}
+
+impl StrategyConfig {
+    /// The strategy configured for `underlying`.
+    #[must_use]
+    pub fn kind_for(&self, underlying: &str) -> StrategyKind {
+        self.underlyings
+            .get(underlying)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// The ladder configured for `underlying`.
+    #[must_use]
+    pub fn ladder_for(&self, underlying: &str) -> &LadderConfig {
+        self.ladders.get(underlying).unwrap_or(&self.layered)
+    }
+
+    /// Validates every ladder.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] naming the first invalid ladder.
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.layered.validate("market_maker.strategy.layered")?;
+        for (underlying, ladder) in &self.ladders {
+            ladder.validate(&format!("market_maker.strategy.ladders.{underlying}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Distance between consecutive ladder levels.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LadderSpacing {
+    /// A fixed number of price ticks.
+    Ticks(u64),
+    /// Basis points of the theoretical value, rounded to whole ticks (at
+    /// least one).
+    Bps(f64),
+}
+
+/// How the size of a ladder level falls away from the best level.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeProfile {
+    /// Every level has the best level's size.
+    Flat,
+    /// Level `i` of `n` has `(n - i) / n` of the best level's size.
+    Linear,
+    /// Each level has `size_decay` times the size of the level before it.
+    #[default]
+    Geometric,
+}
+
+/// Quote ladder configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LadderConfig {
+    /// Price levels per side, including the best one.
+    #[serde(default = "default_ladder_levels")]
+    pub levels: usize,
+    /// Distance between consecutive levels, e.g. `{ ticks = 5 }` or
+    /// `{ bps = 50.0 }`.
+    #[serde(default = "default_ladder_spacing")]
+    pub spacing: LadderSpacing,
+    /// Size of the deeper levels relative to the best one.
+    #[serde(default)]
+    pub size_profile: SizeProfile,
+    /// Level-to-level size ratio of the geometric profile.
+    #[serde(default = "default_ladder_size_decay")]
+    pub size_decay: f64,
+}
+
+fn default_ladder_levels() -> usize {
+    3
+}
+
+fn default_ladder_spacing() -> LadderSpacing {
+    LadderSpacing::Bps(50.0)
+}
+
+fn default_ladder_size_decay() -> f64 {
+    0.5
+}
+
+impl Default for LadderConfig {
+    fn default() -> Self {
+        Self {
+            levels: default_ladder_levels(),
+            spacing: default_ladder_spacing(),
+            size_profile: SizeProfile::default(),
+            size_decay: default_ladder_size_decay(),
+        }
+    }
+}
+
+impl LadderConfig {
+    /// Validates the ladder settings; `section` names it in errors.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a level count outside
+    /// `1..=MAX_LADDER_LEVELS`, a zero tick spacing, a bps spacing that is not
+    /// finite and positive, or a size decay outside `(0, 1]`.
+    fn validate(&self, section: &str) -> Result<(), ConfigError> {
+        if !(1..=MAX_LADDER_LEVELS).contains(&self.levels) {
+            return Err(ConfigError::InvalidValue(format!(
+                "{section} levels must be between 1 and {MAX_LADDER_LEVELS}, got {}",
+                self.levels
+            )));
+        }
+        match self.spacing {
+            LadderSpacing::Ticks(0) => {
+                return Err(ConfigError::InvalidValue(format!(
+                    "{section} spacing must be at least 1 tick"
+                )));
+            }
+            LadderSpacing::Bps(bps) if !(bps.is_finite() && bps > 0.0) => {
+                return Err(ConfigError::InvalidValue(format!(
+                    "{section} spacing in bps must be finite and positive, got {bps}"
+                )));
+            }
+            _ => {}
+        }
+        if !(self.size_decay > 0.0 && self.size_decay <= 1.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "{section} size_decay must be in (0, 1], got {}",
+                self.size_decay
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Requote throttling configuration.
+///
+/// A price tick only replaces an instrument's resting quote when a price moves
+/// by at least `min_price_change_ticks` or a size changes by at least
+/// `min_size_change_pct`, and never more than `max_updates_per_second` times a
+/// second per instrument. A quote missing a leg (e.g. after a full fill) is
+/// always replaced, subject to the rate limit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequoteConfig {
+    /// Smallest bid or ask move, in price ticks, that replaces a quote.
+    #[serde(default = "default_min_price_change_ticks")]
+    pub min_price_change_ticks: u64,
+    /// Smallest bid or ask size change, in percent of the resting size, that
+    /// replaces a quote.
+    #[serde(default = "default_min_size_change_pct")]
+    pub min_size_change_pct: f64,
+    /// Maximum quote replacements per instrument per second (0 = unlimited).
+    #[serde(default = "default_max_updates_per_second")]
+    pub max_updates_per_second: u32,
+}
 
-    CorsOrigins {
-        origins: DEFAULT_CORS_ALLOWED_ORIGINS
-            .iter()
-            .map(|s| (*s).to_string())
-            .collect(),
-        source: CorsOriginsSource::Default,
+fn default_min_price_change_ticks() -> u64 {
+    1
+}
+
+fn default_min_size_change_pct() -> f64 {
+    10.0
+}
+
+fn default_max_updates_per_second() -> u32 {
+    5
+}
+
+impl Default for RequoteConfig {
+    fn default() -> Self {
+        Self {
+            min_price_change_ticks: default_min_price_change_ticks(),
+            min_size_change_pct: default_min_size_change_pct(),
+            max_updates_per_second: default_max_updates_per_second(),
+        }
     }
 }
 
-/// Resolves the effective CORS allowlist, layering (highest precedence first):
-/// the `CORS_ALLOWED_ORIGINS` environment variable (comma-separated), the
-/// `[server] cors_allowed_origins` config field, then the built-in dev defaults
-/// ([`DEFAULT_CORS_ALLOWED_ORIGINS`]). Never falls back to a permissive wildcard.
-#[must_use]
-pub fn resolved_cors_origins(config: Option<&Config>) -> CorsOrigins {
-    let env = std::env::var(ENV_CORS_ALLOWED_ORIGINS).ok();
-    let config_origins: &[String] = config
-        .map(|c| c.server.cors_allowed_origins.as_slice())
-        .unwrap_or(&[]);
-    resolve_cors_origins_from(env.as_deref(), config_origins)
+impl RequoteConfig {
+    /// Validates the requote settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a zero price threshold or a
+    /// negative or non-finite size threshold.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.min_price_change_ticks == 0 {
+            return Err(ConfigError::InvalidValue(
+                "market_maker.requote min_price_change_ticks must be at least 1".to_string(),
+            ));
+        }
+        if !(self.min_size_change_pct.is_finite() && self.min_size_change_pct >= 0.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "market_maker.requote min_size_change_pct must be finite and non-negative, got {}",
+                self.min_size_change_pct
+            )));
+        }
+        Ok(())
+    }
 }
 
-/// Price simulation configuration.
+/// P&L attribution configuration.
 #[derive(Debug, Clone, Deserialize)]
-pub struct SimulationConfig {
-    /// Whether price simulation is enabled.
-    pub enabled: bool,
-    /// Update interval in milliseconds.
-    pub interval_ms: u64,
-    /// Type of random walk to use.
-    pub walk_type: WalkTypeConfig,
+pub struct PnlConfig {
+    /// Seconds per attribution period; each closed period is broadcast over
+    /// WebSocket (0 disables the schedule).
+    #[serde(default = "default_pnl_period_seconds")]
+    pub period_seconds: u64,
 }
 
-impl Default for SimulationConfig {
+fn default_pnl_period_seconds() -> u64 {
+    300
+}
+
+impl Default for PnlConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            interval_ms: 1000,
-            walk_type: WalkTypeConfig::GeometricBrownian,
+            period_seconds: default_pnl_period_seconds(),
         }
     }
 }
 
-/// Cleanup configuration.
+impl MakerConfig {
+    /// Validates the market maker settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for invalid hedging, requote or
+    /// strategy settings (see [`HedgingConfig`], [`RequoteConfig`] and
+    /// [`LadderConfig`]).
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.hedging.validate()?;
+        self.requote.validate()?;
+        self.strategy.validate()
+    }
+}
+
+/// Delta hedging configuration.
 #[derive(Debug, Clone, Deserialize)]
-pub struct CleanupConfig {
-    /// Interval in seconds between cleanup runs.
-    pub interval_seconds: u64,
-    /// Age in seconds after which filled/canceled orders are removed.
-    pub retention_seconds: u64,
+pub struct HedgingConfig {
+    /// Hedge the maker's net option delta in the underlying.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Absolute net delta per underlying, in underlying units, tolerated
+    /// before a hedge is placed.
+    #[serde(default = "default_delta_band")]
+    pub delta_band: f64,
+    /// Smallest tradable quantity of the underlying.
+    #[serde(default = "default_hedge_lot_size")]
+    pub lot_size: f64,
 }
 
-impl Default for CleanupConfig {
+fn default_delta_band() -> f64 {
+    1.0
+}
+
+fn default_hedge_lot_size() -> f64 {
+    0.01
+}
+
+impl Default for HedgingConfig {
     fn default() -> Self {
         Self {
-            interval_seconds: 60,
-            retention_seconds: 300, // 5 minutes
+            enabled: false,
+            delta_band: default_delta_band(),
+            lot_size: default_hedge_lot_size(),
+        }
+    }
+}
+
+impl HedgingConfig {
+    /// Validates the hedging settings.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for a negative or non-finite delta
+    /// band, or a lot size that is not finite and positive.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.delta_band.is_finite() && self.delta_band >= 0.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "market_maker.hedging delta_band must be finite and non-negative, got {}",
+                self.delta_band
+            )));
+        }
+        if !(self.lot_size.is_finite() && self.lot_size > 0.0) {
+            return Err(ConfigError::InvalidValue(format!(
+                "market_maker.hedging lot_size must be finite and positive, got {}",
+                self.lot_size
+            )));
         }
+        Ok(())
     }
 }
 
@@ -428,6 +1495,121 @@ pub struct AssetConfig {
     pub num_strikes: u32,
     /// Strike spacing in dollars.
     pub strike_spacing: f64,
+    /// Minimum price increment of the asset's options (whole cents when
+    /// unset).
+    #[serde(default)]
+    pub tick_size: Option<TickSizeConfig>,
+    /// Units of the underlying one contract delivers (e.g. 100 for listed
+    /// equity options). Notional, P&L, margin, greeks and settlement scale by
+    /// it.
+    #[serde(default = "default_contract_multiplier")]
+    pub contract_multiplier: u64,
+    /// Contracts per lot: order quantities must be a multiple of it.
+    #[serde(default = "default_min_lot_size")]
+    pub min_lot_size: u64,
+    /// Largest quantity of a single order (unlimited when unset).
+    #[serde(default)]
+    pub max_order_size: Option<u64>,
+}
+
+fn default_contract_multiplier() -> u64 {
+    1
+}
+
+fn default_min_lot_size() -> u64 {
+    1
+}
+
+/// Tick-size rule of an asset's options.
+///
+/// ```toml
+/// tick_size = { bands = [{ below = 3.0, tick = 0.05 }, { tick = 0.10 }] }
+/// tick_size = { underlying_fraction = 0.0005 }
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TickSizeConfig {
+    /// Price bands in ascending order, each with its own tick. The last band
+    /// has no upper bound.
+    Bands(Vec<TickBandConfig>),
+    /// A tick of this fraction of the underlying price, rounded to whole cents
+    /// (at least one).
+    UnderlyingFraction(f64),
+}
+
+/// One band of a tick-size table.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct TickBandConfig {
+    /// Exclusive upper bound of the band in dollars; `None` for the last band.
+    #[serde(default)]
+    pub below: Option<f64>,
+    /// Tick in dollars, a whole number of cents.
+    pub tick: f64,
+}
+
+/// Whether `dollars` is a positive whole number of cents.
+fn is_whole_cents(dollars: f64) -> bool {
+    let cents = dollars * 100.0;
+    dollars.is_finite() && dollars > 0.0 && (cents - cents.round()).abs() < 1e-6
+}
+
+impl TickSizeConfig {
+    /// Validates the rule; `symbol` names the asset in errors.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidValue`] for an empty band table, a tick
+    /// that is not a positive whole number of cents, band bounds that are not
+    /// positive and ascending, a bounded last band or an unbounded band before
+    /// the last, or a fraction outside `(0, 0.1]`.
+    fn validate(&self, symbol: &str) -> Result<(), ConfigError> {
+        match self {
+            Self::Bands(bands) => {
+                if bands.is_empty() {
+                    return Err(ConfigError::InvalidValue(format!(
+                        "asset {symbol} tick_size bands cannot be empty"
+                    )));
+                }
+                let mut floor = 0.0;
+                for (i, band) in bands.iter().enumerate() {
+                    if !is_whole_cents(band.tick) {
+                        return Err(ConfigError::InvalidValue(format!(
+                            "asset {symbol} tick_size tick must be a positive whole number of cents, got {}",
+                            band.tick
+                        )));
+                    }
+                    let last = i + 1 == bands.len();
+                    match band.below {
+                        None if !last => {
+                            return Err(ConfigError::InvalidValue(format!(
+                                "asset {symbol} tick_size: only the last band may omit `below`"
+                            )));
+                        }
+                        Some(_) if last => {
+                            return Err(ConfigError::InvalidValue(format!(
+                                "asset {symbol} tick_size: the last band must omit `below`"
+                            )));
+                        }
+                        Some(below) if !(below.is_finite() && below > floor) => {
+                            return Err(ConfigError::InvalidValue(format!(
+                                "asset {symbol} tick_size bounds must be positive and ascending, got {below}"
+                            )));
+                        }
+                        Some(below) => floor = below,
+                        None => {}
+                    }
+                }
+                Ok(())
+            }
+            Self::UnderlyingFraction(fraction) => {
+                if !(fraction.is_finite() && *fraction > 0.0 && *fraction <= 0.1) {
+                    return Err(ConfigError::InvalidValue(format!(
+                        "asset {symbol} tick_size underlying_fraction must be in (0, 0.1], got {fraction}"
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 impl AssetConfig {
@@ -525,6 +1707,13 @@ impl Config {
             ));
         }
 
+        self.risk.validate()?;
+        self.ledger.validate()?;
+        self.snapshots.validate()?;
+        self.journal.validate()?;
+        self.quote_history.validate()?;
+        self.market_maker.validate()?;
+
         for asset in &self.assets {
             if asset.symbol.is_empty() {
                 return Err(ConfigError::InvalidValue(
@@ -595,6 +1784,29 @@ impl Config {
                     asset.symbol, asset.strike_spacing
                 )));
             }
+            if let Some(tick_size) = &asset.tick_size {
+                tick_size.validate(&asset.symbol)?;
+            }
+            if asset.contract_multiplier == 0 {
+                return Err(ConfigError::InvalidValue(format!(
+                    "asset {} contract_multiplier must be positive",
+                    asset.symbol
+                )));
+            }
+            if asset.min_lot_size == 0 {
+                return Err(ConfigError::InvalidValue(format!(
+                    "asset {} min_lot_size must be positive",
+                    asset.symbol
+                )));
+            }
+            if let Some(max) = asset.max_order_size
+                && max < asset.min_lot_size
+            {
+                return Err(ConfigError::InvalidValue(format!(
+                    "asset {} max_order_size ({max}) must be at least min_lot_size ({})",
+                    asset.symbol, asset.min_lot_size
+                )));
+            }
         }
 
         Ok(())
@@ -607,6 +1819,15 @@ impl Default for Config {
             server: ServerConfig::default(),
             simulation: SimulationConfig::default(),
             cleanup: CleanupConfig::default(),
+            risk: RiskConfig::default(),
+            ledger: LedgerConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            journal: JournalConfig::default(),
+            ohlc: OhlcConfig::default(),
+            quote_history: QuoteHistoryConfig::default(),
+            surface_history: SurfaceHistoryConfig::default(),
+            persistence: PersistenceConfig::default(),
+            market_maker: MakerConfig::default(),
             auth: None,
             assets: vec![AssetConfig {
                 symbol: "BTC".to_string(),
@@ -617,6 +1838,10 @@ impl Default for Config {
                 expirations: vec!["20251231".to_string()],
                 num_strikes: 50,
                 strike_spacing: 1000.0,
+                tick_size: None,
+                contract_multiplier: 1,
+                min_lot_size: 1,
+                max_order_size: None,
             }],
         }
     }
@@ -674,6 +1899,10 @@ strike_spacing = 1000.0
             expirations: vec!["20251231".to_string()],
             num_strikes: 5,
             strike_spacing: 10.0,
+            tick_size: None,
+            contract_multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
         };
 
         let strikes = asset.generate_strikes();
@@ -697,6 +1926,10 @@ strike_spacing = 1000.0
             expirations: vec!["20251231".to_string()],
             num_strikes: 5,
             strike_spacing: 10.0,
+            tick_size: None,
+            contract_multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
         };
 
         // Raw offsets: -20,-10,0,10,20 -> 15+offset = -5,5,15,25,35 -> floored at
@@ -720,6 +1953,10 @@ strike_spacing = 1000.0
             expirations: vec!["20251231".to_string()],
             num_strikes: 1,
             strike_spacing: 1.0,
+            tick_size: None,
+            contract_multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
         };
 
         let strikes = asset.generate_strikes();
@@ -806,12 +2043,526 @@ strike_spacing = 1000.0
         assert!(!auth.is_dev());
     }
 
+    const SCENARIO_BASE: &str = r#"
+[server]
+host = "127.0.0.1"
+port = 3000
+
+[simulation]
+enabled = true
+interval_ms = 500
+walk_type = "geometric_brownian"
+
+[[assets]]
+symbol = "BTC"
+name = "Bitcoin"
+initial_price = 100000.0
+volatility = 0.65
+drift = 0.05
+expirations = ["20251231"]
+num_strikes = 10
+strike_spacing = 1000.0
+"#;
+
+    #[test]
+    fn test_parse_config_with_stress_scenarios() {
+        let toml_content = format!(
+            r#"{SCENARIO_BASE}
+[[risk.stress_scenarios]]
+name = "btc-crash"
+description = "BTC -30%, vol +20pts"
+days_forward = 1
+shocks = [{{ underlying = "BTC", spot_shock_pct = -30.0, vol_shock_pts = 20.0 }}]
+
+[[risk.stress_scenarios]]
+name = "broad-rally"
+shocks = [{{ spot_shock_pct = 10.0 }}]
+"#
+        );
+
+        let config = Config::parse(&toml_content).expect("should parse");
+        let scenarios = &config.risk.stress_scenarios;
+        assert_eq!(scenarios.len(), 2);
+        assert_eq!(scenarios[0].description, "BTC -30%, vol +20pts");
+        assert_eq!(scenarios[0].days_forward, 1);
+        assert_eq!(scenarios[0].shocks[0].underlying.as_deref(), Some("BTC"));
+        assert_eq!(scenarios[0].shocks[0].vol_shock_pts, 20.0);
+        assert!(scenarios[1].shocks[0].underlying.is_none());
+        assert_eq!(scenarios[1].shocks[0].vol_shock_pts, 0.0);
+    }
+
+    #[test]
+    fn test_parse_config_without_risk_section_has_no_scenarios() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(config.risk.stress_scenarios.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_var_defaults_and_overrides() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(config.risk.var.confidence, 0.99);
+        assert_eq!(config.risk.var.horizon_days, 1);
+        assert_eq!(config.risk.var.paths, 1_000);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[risk.var]\nconfidence = 0.975\nhorizon_days = 10\npaths = 5000\nlookback_days = 500\ninterval_seconds = 0\n"
+        );
+        let config = Config::parse(&toml_content).expect("should parse");
+        let var = &config.risk.var;
+        assert_eq!(var.confidence, 0.975);
+        assert_eq!(var.horizon_days, 10);
+        assert_eq!(var.paths, 5_000);
+        assert_eq!(var.lookback_days, 500);
+        assert_eq!(var.interval_seconds, 0);
+    }
+
+    #[test]
+    fn test_validation_rejects_invalid_var_settings() {
+        for section in [
+            "confidence = 1.0",
+            "confidence = 0.4",
+            "horizon_days = 0",
+            "paths = 0",
+            "paths = 1000000",
+            "horizon_days = 30\nlookback_days = 30",
+        ] {
+            let toml_content = format!("{SCENARIO_BASE}\n[risk.var]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_margin_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.risk.margin.enabled);
+        assert_eq!(config.risk.margin.maintenance_ratio, 0.75);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[risk.margin]\nenabled = true\nprice_scan_pct = 20.0\ndefault_collateral = 50000.0\n"
+        );
+        let margin = Config::parse(&toml_content)
+            .expect("should parse")
+            .risk
+            .margin;
+        assert!(margin.enabled);
+        assert_eq!(margin.price_scan_pct, 20.0);
+        assert_eq!(margin.vol_scan_pts, 10.0);
+        assert_eq!(margin.default_collateral, 50_000.0);
+
+        for section in [
+            "price_scan_pct = 0.0",
+            "price_scan_pct = 50.0",
+            "vol_scan_pts = -1.0",
+            "maintenance_ratio = 0.0",
+            "maintenance_ratio = 1.5",
+            "default_collateral = -1.0",
+        ] {
+            let toml_content = format!("{SCENARIO_BASE}\n[risk.margin]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_liquidation_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.risk.liquidation.enabled);
+        assert!(config.risk.liquidation.transfer_to_market_maker);
+
+        let toml_content =
+            format!("{SCENARIO_BASE}\n[risk.liquidation]\nenabled = true\npenalty_pct = 20.0\n");
+        let liquidation = Config::parse(&toml_content)
+            .expect("should parse")
+            .risk
+            .liquidation;
+        assert!(liquidation.enabled);
+        assert_eq!(liquidation.penalty_pct, 20.0);
+        assert_eq!(liquidation.max_slippage_pct, 5.0);
+
+        for section in [
+            "interval_seconds = 0",
+            "max_slippage_pct = -1.0",
+            "penalty_pct = 100.0",
+        ] {
+            let toml_content = format!("{SCENARIO_BASE}\n[risk.liquidation]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_ledger_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.ledger.enforce_buying_power);
+        assert_eq!(config.ledger.taker_fee_bps, 0.0);
+        assert_eq!(config.ledger.settlement_interval_seconds, 60);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[ledger]\nenforce_buying_power = true\ntaker_fee_bps = 5.0\n"
+        );
+        let ledger = Config::parse(&toml_content).expect("should parse").ledger;
+        assert!(ledger.enforce_buying_power);
+        assert_eq!(ledger.taker_fee_bps, 5.0);
+        assert_eq!(ledger.maker_fee_bps, 0.0);
+
+        for section in ["taker_fee_bps = -1.0", "maker_fee_bps = 1001.0"] {
+            let toml_content = format!("{SCENARIO_BASE}\n[ledger]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_snapshots_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(config.snapshots.directory.is_none());
+        assert_eq!(config.snapshots.max_count, 16);
+        assert_eq!(config.snapshots.max_age_seconds, 0);
+        assert!(!config.snapshots.restore_on_startup);
+        assert_eq!(config.snapshots.interval_seconds, 0);
+        assert!(!config.snapshots.on_shutdown);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[snapshots]\ndirectory = \"/var/lib/snapshots\"\nmax_age_seconds = 86400\nrestore_on_startup = true\ninterval_seconds = 300\non_shutdown = true\n"
+        );
+        let snapshots = Config::parse(&toml_content)
+            .expect("should parse")
+            .snapshots;
+        assert_eq!(snapshots.directory.as_deref(), Some("/var/lib/snapshots"));
+        assert_eq!(snapshots.max_age_seconds, 86_400);
+        assert!(snapshots.restore_on_startup);
+        assert_eq!(snapshots.interval_seconds, 300);
+        assert!(snapshots.on_shutdown);
+
+        for section in ["max_count = 0", "directory = \" \""] {
+            let toml_content = format!("{SCENARIO_BASE}\n[snapshots]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_journal_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(config.journal.path.is_none());
+        assert_eq!(config.journal.fsync, JournalFsync::Always);
+        assert_eq!(config.journal.fsync_interval_ms, 100);
+        assert!(config.journal.replay_on_startup);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[journal]\npath = \"/var/lib/journal.log\"\nfsync = \"interval\"\nfsync_interval_ms = 250\nreplay_on_startup = false\n"
+        );
+        let journal = Config::parse(&toml_content).expect("should parse").journal;
+        assert_eq!(journal.path.as_deref(), Some("/var/lib/journal.log"));
+        assert_eq!(journal.fsync, JournalFsync::Interval);
+        assert_eq!(journal.fsync_interval_ms, 250);
+        assert!(!journal.replay_on_startup);
+
+        for section in [
+            "path = \" \"",
+            "fsync = \"interval\"\nfsync_interval_ms = 0",
+            "fsync = \"sometimes\"",
+        ] {
+            let toml_content = format!("{SCENARIO_BASE}\n[journal]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_ohlc_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(config.ohlc.flush_interval_seconds, 60);
+
+        let toml_content = format!("{SCENARIO_BASE}\n[ohlc]\nflush_interval_seconds = 0\n");
+        let ohlc = Config::parse(&toml_content).expect("should parse").ohlc;
+        assert_eq!(ohlc.flush_interval_seconds, 0);
+    }
+
+    #[test]
+    fn test_parse_config_quote_history_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.quote_history.enabled);
+        assert_eq!(config.quote_history.sample_interval_ms, 250);
+        assert_eq!(config.quote_history.flush_interval_seconds, 5);
+
+        let toml_content =
+            format!("{SCENARIO_BASE}\n[quote_history]\nenabled = true\nsample_interval_ms = 100\n");
+        let quote_history = Config::parse(&toml_content)
+            .expect("should parse")
+            .quote_history;
+        assert!(quote_history.enabled);
+        assert_eq!(quote_history.sample_interval_ms, 100);
+
+        let toml_content = format!("{SCENARIO_BASE}\n[quote_history]\nsample_interval_ms = 0\n");
+        assert!(Config::parse(&toml_content).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_surface_history_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(config.surface_history.interval_seconds, 0);
+
+        let toml_content = format!("{SCENARIO_BASE}\n[surface_history]\ninterval_seconds = 300\n");
+        let surface_history = Config::parse(&toml_content)
+            .expect("should parse")
+            .surface_history;
+        assert_eq!(surface_history.interval_seconds, 300);
+    }
+
+    #[test]
+    fn test_parse_config_persistence_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(config.persistence.flush_interval_seconds, 10);
+
+        let toml_content = format!("{SCENARIO_BASE}\n[persistence]\nflush_interval_seconds = 0\n");
+        let persistence = Config::parse(&toml_content)
+            .expect("should parse")
+            .persistence;
+        assert_eq!(persistence.flush_interval_seconds, 0);
+    }
+
+    #[test]
+    fn test_parse_config_simulation_record_prices() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.simulation.record_prices);
+
+        let toml_content = SCENARIO_BASE.replace(
+            "walk_type = \"geometric_brownian\"",
+            "walk_type = \"geometric_brownian\"\nrecord_prices = true",
+        );
+        let simulation = Config::parse(&toml_content)
+            .expect("should parse")
+            .simulation;
+        assert!(simulation.record_prices);
+    }
+
+    #[test]
+    fn test_parse_config_hedging_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert!(!config.market_maker.hedging.enabled);
+        assert_eq!(config.market_maker.hedging.delta_band, 1.0);
+        assert_eq!(config.market_maker.pnl.period_seconds, 300);
+
+        let toml_content =
+            format!("{SCENARIO_BASE}\n[market_maker.hedging]\nenabled = true\ndelta_band = 2.5\n");
+        let hedging = Config::parse(&toml_content)
+            .expect("should parse")
+            .market_maker
+            .hedging;
+        assert!(hedging.enabled);
+        assert_eq!(hedging.delta_band, 2.5);
+        assert_eq!(hedging.lot_size, 0.01);
+
+        for section in ["delta_band = -1.0", "lot_size = 0.0"] {
+            let toml_content = format!("{SCENARIO_BASE}\n[market_maker.hedging]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_requote_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(config.market_maker.requote.min_price_change_ticks, 1);
+        assert_eq!(config.market_maker.requote.max_updates_per_second, 5);
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[market_maker.requote]\nmin_price_change_ticks = 3\nmax_updates_per_second = 0\n"
+        );
+        let requote = Config::parse(&toml_content)
+            .expect("should parse")
+            .market_maker
+            .requote;
+        assert_eq!(requote.min_price_change_ticks, 3);
+        assert_eq!(requote.min_size_change_pct, 10.0);
+        assert_eq!(requote.max_updates_per_second, 0);
+
+        for section in ["min_price_change_ticks = 0", "min_size_change_pct = -1.0"] {
+            let toml_content = format!("{SCENARIO_BASE}\n[market_maker.requote]\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_strategy_section() {
+        let config = Config::parse(SCENARIO_BASE).expect("should parse");
+        assert_eq!(
+            config.market_maker.strategy.kind_for("BTC"),
+            StrategyKind::Single
+        );
+
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[market_maker.strategy]\ndefault = \"single\"\nunderlyings = {{ BTC = \"layered\" }}\n\n[market_maker.strategy.layered]\nlevels = 5\n"
+        );
+        let strategy = Config::parse(&toml_content)
+            .expect("should parse")
+            .market_maker
+            .strategy;
+        assert_eq!(strategy.kind_for("BTC"), StrategyKind::Layered);
+        assert_eq!(strategy.kind_for("ETH"), StrategyKind::Single);
+        assert_eq!(strategy.layered.levels, 5);
+        assert_eq!(strategy.layered.size_decay, 0.5);
+        assert_eq!(strategy.layered.spacing, LadderSpacing::Bps(50.0));
+
+        for section in [
+            "[market_maker.strategy]\ndefault = \"martingale\"",
+            "[market_maker.strategy.layered]\nlevels = 0",
+            "[market_maker.strategy.layered]\nspacing = { bps = 0.0 }",
+            "[market_maker.strategy.layered]\nspacing = { ticks = 0 }",
+            "[market_maker.strategy.layered]\nsize_decay = 1.5",
+            "[market_maker.strategy.ladders.BTC]\nlevels = 11",
+        ] {
+            let toml_content = format!("{SCENARIO_BASE}\n{section}\n");
+            assert!(
+                Config::parse(&toml_content).is_err(),
+                "{section:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_config_ladder_per_underlying() {
+        let toml_content = format!(
+            "{SCENARIO_BASE}\n[market_maker.strategy.ladders.BTC]\nlevels = 5\nspacing = {{ ticks = 10 }}\nsize_profile = \"linear\"\n"
+        );
+        let strategy = Config::parse(&toml_content)
+            .expect("should parse")
+            .market_maker
+            .strategy;
+        let btc = strategy.ladder_for("BTC");
+        assert_eq!(btc.levels, 5);
+        assert_eq!(btc.spacing, LadderSpacing::Ticks(10));
+        assert_eq!(btc.size_profile, SizeProfile::Linear);
+        let eth = strategy.ladder_for("ETH");
+        assert_eq!(eth.levels, 3);
+        assert_eq!(eth.size_profile, SizeProfile::Geometric);
+    }
+
+    #[test]
+    fn test_parse_config_tick_size() {
+        let toml_content = SCENARIO_BASE.replace(
+            "strike_spacing = 1000.0\n",
+            "strike_spacing = 1000.0\ntick_size = { bands = [{ below = 3.0, tick = 0.05 }, { tick = 0.10 }] }\n",
+        );
+        let config = Config::parse(&toml_content).expect("should parse");
+        assert_eq!(
+            config.assets[0].tick_size,
+            Some(TickSizeConfig::Bands(vec![
+                TickBandConfig {
+                    below: Some(3.0),
+                    tick: 0.05,
+                },
+                TickBandConfig {
+                    below: None,
+                    tick: 0.10,
+                },
+            ]))
+        );
+
+        let toml_content = SCENARIO_BASE.replace(
+            "strike_spacing = 1000.0\n",
+            "strike_spacing = 1000.0\ntick_size = { underlying_fraction = 0.0005 }\n",
+        );
+        let config = Config::parse(&toml_content).expect("should parse");
+        assert_eq!(
+            config.assets[0].tick_size,
+            Some(TickSizeConfig::UnderlyingFraction(0.0005))
+        );
+    }
+
+    #[test]
+    fn test_validation_rejects_invalid_tick_sizes() {
+        let band = |below, tick| TickBandConfig { below, tick };
+        let invalid = [
+            TickSizeConfig::Bands(vec![]),
+            TickSizeConfig::Bands(vec![band(None, 0.0)]),
+            TickSizeConfig::Bands(vec![band(None, 0.025)]),
+            TickSizeConfig::Bands(vec![band(Some(3.0), 0.05)]),
+            TickSizeConfig::Bands(vec![band(None, 0.05), band(None, 0.10)]),
+            TickSizeConfig::Bands(vec![
+                band(Some(3.0), 0.05),
+                band(Some(2.0), 0.10),
+                band(None, 0.25),
+            ]),
+            TickSizeConfig::UnderlyingFraction(0.0),
+            TickSizeConfig::UnderlyingFraction(0.5),
+            TickSizeConfig::UnderlyingFraction(f64::NAN),
+        ];
+        for tick_size in invalid {
+            let asset = AssetConfig {
+                tick_size: Some(tick_size.clone()),
+                ..valid_asset()
+            };
+            assert_invalid(&config_with(asset), "tick_size");
+        }
+    }
+
+    #[test]
+    fn test_validation_rejects_invalid_stress_scenarios() {
+        let invalid = [
+            r#"name = ""
+shocks = [{ spot_shock_pct = -10.0 }]"#,
+            r#"name = "wipeout"
+shocks = [{ spot_shock_pct = -100.0 }]"#,
+            r#"name = "empty"
+shocks = []"#,
+            r#"name = "twice"
+shocks = [{ underlying = "BTC", spot_shock_pct = 1.0 }, { underlying = "BTC", spot_shock_pct = 2.0 }]"#,
+            r#"name = "far"
+days_forward = 100000
+shocks = [{ spot_shock_pct = 1.0 }]"#,
+        ];
+        for body in invalid {
+            let toml_content = format!("{SCENARIO_BASE}\n[[risk.stress_scenarios]]\n{body}\n");
+            assert!(
+                matches!(
+                    Config::parse(&toml_content),
+                    Err(ConfigError::InvalidValue(_))
+                ),
+                "must reject: {body}"
+            );
+        }
+
+        let duplicate = format!(
+            "{SCENARIO_BASE}\n[[risk.stress_scenarios]]\nname = \"a\"\nshocks = [{{ spot_shock_pct = 1.0 }}]\n\n[[risk.stress_scenarios]]\nname = \"a\"\nshocks = [{{ spot_shock_pct = 2.0 }}]\n"
+        );
+        assert!(Config::parse(&duplicate).is_err());
+    }
+
     #[test]
     fn test_validation_rejects_zero_ttl() {
         let config = Config {
             server: ServerConfig::default(),
             simulation: SimulationConfig::default(),
             cleanup: CleanupConfig::default(),
+            risk: RiskConfig::default(),
+            ledger: LedgerConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            journal: JournalConfig::default(),
+            ohlc: OhlcConfig::default(),
+            quote_history: QuoteHistoryConfig::default(),
+            surface_history: SurfaceHistoryConfig::default(),
+            persistence: PersistenceConfig::default(),
+            market_maker: MakerConfig::default(),
             auth: Some(AuthConfig {
                 default_ttl_secs: 0,
                 ..AuthConfig::default()
@@ -825,6 +2576,10 @@ strike_spacing = 1000.0
                 expirations: vec!["20251231".to_string()],
                 num_strikes: 2,
                 strike_spacing: 10.0,
+                tick_size: None,
+                contract_multiplier: 1,
+                min_lot_size: 1,
+                max_order_size: None,
             }],
         };
         assert!(config.validate().is_err());
@@ -896,6 +2651,15 @@ strike_spacing = 1000.0
             server: ServerConfig::default(),
             simulation: SimulationConfig::default(),
             cleanup: CleanupConfig::default(),
+            risk: RiskConfig::default(),
+            ledger: LedgerConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            journal: JournalConfig::default(),
+            ohlc: OhlcConfig::default(),
+            quote_history: QuoteHistoryConfig::default(),
+            surface_history: SurfaceHistoryConfig::default(),
+            persistence: PersistenceConfig::default(),
+            market_maker: MakerConfig::default(),
             auth: None,
             assets: vec![],
         };
@@ -913,6 +2677,10 @@ strike_spacing = 1000.0
             expirations: vec!["20251231".to_string()],
             num_strikes: 4,
             strike_spacing: 10.0,
+            tick_size: None,
+            contract_multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
         }
     }
 
@@ -922,6 +2690,15 @@ strike_spacing = 1000.0
             server: ServerConfig::default(),
             simulation: SimulationConfig::default(),
             cleanup: CleanupConfig::default(),
+            risk: RiskConfig::default(),
+            ledger: LedgerConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            journal: JournalConfig::default(),
+            ohlc: OhlcConfig::default(),
+            quote_history: QuoteHistoryConfig::default(),
+            surface_history: SurfaceHistoryConfig::default(),
+            persistence: PersistenceConfig::default(),
+            market_maker: MakerConfig::default(),
             auth: None,
             assets: vec![asset],
         }
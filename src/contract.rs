@@ -0,0 +1,136 @@
+//! Contract specifications: how much of the underlying one option contract
+//! delivers and which order sizes an underlying's options accept.
+//!
+//! Prices are quoted per unit of the underlying, so every money amount of a
+//! position — premium paid, notional, P&L, margin, dollar greeks and
+//! settlement — is `price × quantity × multiplier`. Order quantities must be
+//! whole lots and no larger than the maximum order size.
+
+use crate::config::AssetConfig;
+
+/// Contract specification of one underlying's options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractSpec {
+    /// Units of the underlying per contract.
+    pub multiplier: u64,
+    /// Contracts per lot; order quantities are whole lots.
+    pub min_lot_size: u64,
+    /// Largest quantity of a single order, if limited.
+    pub max_order_size: Option<u64>,
+}
+
+impl Default for ContractSpec {
+    /// One unit per contract, single-contract lots, no size limit.
+    fn default() -> Self {
+        Self {
+            multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
+        }
+    }
+}
+
+impl From<&AssetConfig> for ContractSpec {
+    fn from(asset: &AssetConfig) -> Self {
+        Self {
+            multiplier: asset.contract_multiplier.max(1),
+            min_lot_size: asset.min_lot_size.max(1),
+            max_order_size: asset.max_order_size,
+        }
+    }
+}
+
+impl ContractSpec {
+    /// Checks that `quantity` is a positive number of whole lots within the
+    /// maximum order size.
+    ///
+    /// # Errors
+    /// Returns a message naming the quantity and the violated limit.
+    pub fn check_quantity(&self, quantity: u64) -> Result<(), String> {
+        if quantity == 0 || !quantity.is_multiple_of(self.min_lot_size) {
+            return Err(format!(
+                "quantity {quantity} is not a multiple of the lot size {}",
+                self.min_lot_size
+            ));
+        }
+        if let Some(max) = self.max_order_size
+            && quantity > max
+        {
+            return Err(format!(
+                "quantity {quantity} exceeds the maximum order size {max}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// The nearest size the spec accepts: rounded up to whole lots (at least
+    /// one), then capped at the largest whole-lot size within the maximum.
+    #[must_use]
+    pub fn normalize_size(&self, size: u64) -> u64 {
+        let lot = self.min_lot_size;
+        let size = size.max(1).div_ceil(lot).saturating_mul(lot);
+        match self.max_order_size {
+            Some(max) => size.min((max / lot).max(1) * lot),
+            None => size,
+        }
+    }
+
+    /// `amount` per unit of the underlying scaled to `quantity` contracts.
+    #[must_use]
+    pub fn scale(&self, amount: u128, quantity: u64) -> u128 {
+        amount
+            .saturating_mul(u128::from(quantity))
+            .saturating_mul(u128::from(self.multiplier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(multiplier: u64, lot: u64, max: Option<u64>) -> ContractSpec {
+        ContractSpec {
+            multiplier,
+            min_lot_size: lot,
+            max_order_size: max,
+        }
+    }
+
+    #[test]
+    fn test_default_accepts_any_positive_quantity() {
+        let spec = ContractSpec::default();
+        assert!(spec.check_quantity(1).is_ok());
+        assert!(spec.check_quantity(u64::MAX).is_ok());
+        assert!(spec.check_quantity(0).is_err());
+        assert_eq!(spec.scale(250, 4), 1_000);
+    }
+
+    #[test]
+    fn test_lot_and_max_size_checks() {
+        let spec = spec(100, 5, Some(50));
+        assert!(spec.check_quantity(10).is_ok());
+        assert_eq!(
+            spec.check_quantity(7),
+            Err("quantity 7 is not a multiple of the lot size 5".to_string())
+        );
+        assert_eq!(
+            spec.check_quantity(55),
+            Err("quantity 55 exceeds the maximum order size 50".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_size_rounds_to_lots_within_max() {
+        let spec = spec(1, 5, Some(52));
+        assert_eq!(spec.normalize_size(0), 5);
+        assert_eq!(spec.normalize_size(6), 10);
+        assert_eq!(spec.normalize_size(80), 50);
+        assert_eq!(spec.normalize_size(3), 5);
+    }
+
+    #[test]
+    fn test_scale_applies_the_multiplier() {
+        // 2 contracts of 100 shares at $1.50.
+        assert_eq!(spec(100, 1, None).scale(150, 2), 30_000);
+    }
+}
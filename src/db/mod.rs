@@ -1,7 +1,9 @@
-//! Database module for PostgreSQL connection and operations.
+//! Database module for PostgreSQL and SQLite connections and operations.
 
 mod pool;
+mod repository;
 mod schema;
 
 pub use pool::DatabasePool;
+pub use repository::*;
 pub use schema::*;
@@ -1,48 +1,119 @@
 //! Database connection pool management.
 
+use super::{PostgresRepository, Repository, SqliteRepository};
 use sqlx::PgPool;
+use sqlx::SqlitePool;
 use sqlx::postgres::PgPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::info;
 
 /// Database connection pool wrapper.
+///
+/// PostgreSQL backs every store. SQLite backs the [`Repository`] (prices, the
+/// trading state and the market maker controls) and OHLC bars; snapshots,
+/// quote history and surface history need PostgreSQL.
 #[derive(Clone)]
-pub struct DatabasePool {
-    pool: PgPool,
+pub enum DatabasePool {
+    /// A PostgreSQL pool.
+    Postgres(PgPool),
+    /// A SQLite pool.
+    Sqlite(SqlitePool),
 }
 
 impl DatabasePool {
     /// Creates a new database pool from the connection string.
     ///
+    /// A `sqlite:` URL opens a SQLite database, creating the file if it does
+    /// not exist (`sqlite::memory:` keeps it in memory for the life of the
+    /// pool). Any other URL connects to PostgreSQL.
+    ///
     /// # Arguments
-    /// * `database_url` - PostgreSQL connection string
+    /// * `database_url` - PostgreSQL or SQLite connection string
     ///
     /// # Errors
     /// Returns an error if the connection cannot be established.
     pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
-        let pool = PgPoolOptions::new()
-            .max_connections(10)
-            .acquire_timeout(Duration::from_secs(5))
-            .connect(database_url)
-            .await?;
+        let db = if database_url.starts_with("sqlite:") {
+            let options = SqliteConnectOptions::from_str(database_url)?
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .busy_timeout(Duration::from_secs(5));
+            // Every connection to an in-memory database opens a new, empty
+            // one, so keep a single connection open for the pool's lifetime.
+            let in_memory =
+                database_url.contains(":memory:") || database_url.contains("mode=memory");
+            let pool_options = if in_memory {
+                SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .idle_timeout(None)
+                    .max_lifetime(None)
+            } else {
+                SqlitePoolOptions::new().max_connections(10)
+            };
+            Self::Sqlite(
+                pool_options
+                    .acquire_timeout(Duration::from_secs(5))
+                    .connect_with(options)
+                    .await?,
+            )
+        } else {
+            Self::Postgres(
+                PgPoolOptions::new()
+                    .max_connections(10)
+                    .acquire_timeout(Duration::from_secs(5))
+                    .connect(database_url)
+                    .await?,
+            )
+        };
+
+        info!(
+            backend = db.backend_name(),
+            "Database connection pool established"
+        );
 
-        info!("Database connection pool established");
+        Ok(db)
+    }
 
-        Ok(Self { pool })
+    /// Returns the PostgreSQL pool, or `None` for SQLite.
+    #[must_use]
+    pub fn postgres(&self) -> Option<&PgPool> {
+        match self {
+            Self::Postgres(pool) => Some(pool),
+            Self::Sqlite(_) => None,
+        }
+    }
+
+    /// Returns the name of the backend, for logs.
+    #[must_use]
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            Self::Postgres(_) => "postgres",
+            Self::Sqlite(_) => "sqlite",
+        }
     }
 
-    /// Returns a reference to the underlying pool.
+    /// Returns a repository reading and writing this database.
     #[must_use]
-    pub fn pool(&self) -> &PgPool {
-        &self.pool
+    pub fn repository(&self) -> Arc<dyn Repository> {
+        match self {
+            Self::Postgres(pool) => Arc::new(PostgresRepository::new(pool.clone())),
+            Self::Sqlite(pool) => Arc::new(SqliteRepository::new(pool.clone())),
+        }
     }
 
-    /// Runs database migrations.
+    /// Runs database migrations: `migrations` for PostgreSQL,
+    /// `migrations/sqlite` for SQLite.
     ///
     /// # Errors
     /// Returns an error if migrations fail.
     pub async fn run_migrations(&self) -> Result<(), sqlx::migrate::MigrateError> {
-        sqlx::migrate!("./migrations").run(&self.pool).await?;
+        match self {
+            Self::Postgres(pool) => sqlx::migrate!("./migrations").run(pool).await?,
+            Self::Sqlite(pool) => sqlx::migrate!("./migrations/sqlite").run(pool).await?,
+        }
         info!("Database migrations completed");
         Ok(())
     }
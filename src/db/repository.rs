@@ -0,0 +1,2296 @@
+//! Storage interface for prices and the trading state.
+//!
+//! [`Repository`] is what `AppState` reads and writes through, whether or not
+//! a database is connected: [`PostgresRepository`] and [`SqliteRepository`]
+//! keep everything in the relational tables, [`InMemoryRepository`] in process
+//! memory for the life of the server. Handlers call the trait instead of
+//! branching on `state.db`.
+
+mod sqlite;
+
+pub use sqlite::SqliteRepository;
+
+use crate::ledger::{EntryKind, JournalEntry as LedgerEntry, LedgerError, Posting};
+use crate::models::{
+    ExecutionInfo, LastTradeInfo, OrderFillInfo, OrderInfo, OrderStatus, PositionInfo,
+};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use dashmap::DashMap;
+use futures::future::{BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sqlx::{FromRow, PgPool};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use thiserror::Error;
+
+/// Days of closing prices the in-memory repository keeps per symbol.
+const MAX_DAILY_CLOSES: usize = 1_000;
+
+/// Latest prices the in-memory repository keeps per symbol for
+/// [`Repository::price_history`].
+const MAX_RECENT_PRICES: usize = 10_000;
+
+/// Future returned by every [`Repository`] method.
+pub type RepositoryFuture<'a, T> = BoxFuture<'a, Result<T, RepositoryError>>;
+
+/// Errors from a repository.
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    /// A database query failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// A stored value could not be decoded.
+    #[error("invalid stored {column}: {value}")]
+    Decode {
+        /// Column holding the value.
+        column: &'static str,
+        /// The offending value.
+        value: String,
+    },
+    /// The stored ledger entries do not balance.
+    #[error("invalid stored ledger: {0}")]
+    Ledger(#[from] LedgerError),
+}
+
+/// One recorded underlying price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceRecord {
+    /// Underlying symbol.
+    pub symbol: String,
+    /// Price in cents.
+    pub price_cents: i64,
+    /// Bid price in cents.
+    pub bid_cents: Option<i64>,
+    /// Ask price in cents.
+    pub ask_cents: Option<i64>,
+    /// Volume.
+    pub volume: Option<i64>,
+    /// When the price was observed.
+    pub timestamp: DateTime<Utc>,
+    /// Source of the price.
+    pub source: Option<String>,
+}
+
+/// The last recorded price of a symbol on one UTC day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyClose {
+    /// Underlying symbol.
+    pub symbol: String,
+    /// UTC day.
+    pub day: NaiveDate,
+    /// Price in cents.
+    pub price_cents: i64,
+}
+
+/// Which recorded prices of a symbol [`Repository::price_history`] reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PriceHistoryRange {
+    /// Earliest price, inclusive.
+    pub from: Option<DateTime<Utc>>,
+    /// Latest price, inclusive.
+    pub to: Option<DateTime<Utc>>,
+    /// Width of each bar in seconds; 0 keeps every price, prices observed at
+    /// the same instant sharing a bar.
+    pub bucket_secs: u64,
+    /// Maximum number of bars.
+    pub limit: usize,
+    /// Keep the oldest `limit` bars instead of the newest.
+    pub oldest_first: bool,
+}
+
+/// The prices of a symbol recorded within one bar of a price history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceBar {
+    /// Start of the bar, or the time of the price for raw prices.
+    pub start: DateTime<Utc>,
+    /// First price in cents.
+    pub open_cents: i64,
+    /// Highest price in cents.
+    pub high_cents: i64,
+    /// Lowest price in cents.
+    pub low_cents: i64,
+    /// Last price in cents.
+    pub close_cents: i64,
+    /// Summed volume of the prices that carried one.
+    pub volume: Option<i64>,
+    /// Number of prices in the bar.
+    pub samples: u64,
+}
+
+/// A trading account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountRecord {
+    /// Account id (the token subject).
+    pub account_id: String,
+    /// Pledged collateral in cents; `None` holds the configured default.
+    pub collateral: Option<u64>,
+    /// Cash balance in the ledger, in cents.
+    pub cash_cents: i64,
+    /// First time the account was stored, in milliseconds.
+    pub created_at_ms: u64,
+    /// Last time the account was stored, in milliseconds.
+    pub updated_at_ms: u64,
+}
+
+/// The market maker controls set through the API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlsRecord {
+    /// Master switch; `false` is the kill switch.
+    pub master_enabled: bool,
+    /// Global spread multiplier.
+    pub spread_multiplier: f64,
+    /// Global size scalar (0.0 to 1.0).
+    pub size_scalar: f64,
+    /// Global directional skew (-1.0 to 1.0).
+    pub directional_skew: f64,
+    /// Quoting switch of each underlying toggled.
+    pub symbol_enabled: BTreeMap<String, bool>,
+}
+
+/// What one [`save_changes`] wrote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveSummary {
+    /// Accounts stored.
+    pub accounts: usize,
+    /// Orders stored.
+    pub orders: usize,
+    /// Order events appended.
+    pub order_events: u64,
+    /// Fills not stored before.
+    pub fills: u64,
+    /// Positions stored.
+    pub positions: usize,
+    /// Last trades stored.
+    pub last_trades: usize,
+    /// Ledger entries not stored before.
+    pub ledger_entries: u64,
+}
+
+/// What one [`load_state`] read back into the state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadSummary {
+    /// Accounts with pledged collateral.
+    pub accounts: usize,
+    /// Filled and canceled orders.
+    pub orders: usize,
+    /// Fills of those orders.
+    pub fills: usize,
+    /// Account positions.
+    pub positions: usize,
+    /// Last trades.
+    pub last_trades: usize,
+    /// Ledger entries.
+    pub ledger_entries: usize,
+}
+
+/// Reads and writes prices and the trading state.
+///
+/// Every `save_*` method is idempotent: saving an unchanged row again leaves
+/// the stored state as it was, so callers may resave overlapping batches.
+pub trait Repository: Send + Sync {
+    /// Records underlying prices.
+    fn insert_prices<'a>(&'a self, prices: &'a [PriceRecord]) -> RepositoryFuture<'a, ()>;
+
+    /// The most recent price of `symbol`.
+    fn latest_price<'a>(&'a self, symbol: &'a str) -> RepositoryFuture<'a, Option<PriceRecord>>;
+
+    /// The last price per UTC day of each of `symbols` over the past
+    /// `lookback_days` days.
+    fn daily_closes<'a>(
+        &'a self,
+        symbols: &'a [String],
+        lookback_days: u32,
+    ) -> RepositoryFuture<'a, Vec<DailyClose>>;
+
+    /// The prices of `symbol` within `range`, grouped into bars of
+    /// `range.bucket_secs` seconds aligned to the Unix epoch, oldest first.
+    fn price_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        range: &'a PriceHistoryRange,
+    ) -> RepositoryFuture<'a, Vec<PriceBar>>;
+
+    /// Stores `accounts`, keeping each one's original creation time.
+    fn save_accounts<'a>(&'a self, accounts: &'a [AccountRecord]) -> RepositoryFuture<'a, ()>;
+
+    /// Every stored account, by id.
+    fn load_accounts(&self) -> RepositoryFuture<'_, Vec<AccountRecord>>;
+
+    /// Stores `orders`, creating their accounts as needed, and appends an
+    /// order event for each order that is new or whose status, filled or
+    /// remaining quantity differs from the stored one. Returns the number of
+    /// events appended. Order ids must be unique within the batch.
+    fn save_orders<'a>(&'a self, orders: &'a [OrderInfo]) -> RepositoryFuture<'a, u64>;
+
+    /// Marks stored open orders whose id is not in `tracked` as canceled at
+    /// `now_ms`, appending an order event for each. Returns the number of
+    /// orders closed.
+    fn cancel_untracked_orders<'a>(
+        &'a self,
+        tracked: &'a [String],
+        now_ms: u64,
+    ) -> RepositoryFuture<'a, u64>;
+
+    /// The stored orders of `account`, oldest first, with the fills they
+    /// took part in.
+    fn load_orders<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<OrderInfo>>;
+
+    /// Stores `fills`, skipping those already stored. Returns the number
+    /// written.
+    fn save_fills<'a>(&'a self, fills: &'a [ExecutionInfo]) -> RepositoryFuture<'a, u64>;
+
+    /// The stored fills any of `order_ids` took part in, as taker or maker,
+    /// oldest first.
+    fn load_fills<'a>(
+        &'a self,
+        order_ids: &'a [String],
+    ) -> RepositoryFuture<'a, Vec<ExecutionInfo>>;
+
+    /// Stores `(account, position)` pairs, creating the accounts as needed.
+    fn save_positions<'a>(
+        &'a self,
+        positions: &'a [(String, PositionInfo)],
+    ) -> RepositoryFuture<'a, ()>;
+
+    /// The stored positions of `account`, by symbol.
+    fn load_positions<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<PositionInfo>>;
+
+    /// Stores `trades` as the last trade of their symbols, unless a later
+    /// one is already stored.
+    fn save_last_trades<'a>(&'a self, trades: &'a [LastTradeInfo]) -> RepositoryFuture<'a, ()>;
+
+    /// The stored last trade of every symbol, by symbol.
+    fn load_last_trades(&self) -> RepositoryFuture<'_, Vec<LastTradeInfo>>;
+
+    /// Stores `entries` of the cash ledger, skipping those already stored.
+    /// Returns the number written.
+    fn save_ledger_entries<'a>(&'a self, entries: &'a [LedgerEntry]) -> RepositoryFuture<'a, u64>;
+
+    /// Every stored ledger entry, oldest first.
+    fn load_ledger_entries(&self) -> RepositoryFuture<'_, Vec<LedgerEntry>>;
+
+    /// Stores the market maker `controls`, replacing those stored.
+    fn save_controls<'a>(&'a self, controls: &'a ControlsRecord) -> RepositoryFuture<'a, ()>;
+
+    /// The stored market maker controls, if any.
+    fn load_controls(&self) -> RepositoryFuture<'_, Option<ControlsRecord>>;
+}
+
+/// Writes everything in `state` that changed at or after `since_ms` to its
+/// repository: the orders, account positions and last trades updated since,
+/// the fills executed and ledger entries posted since, every account with
+/// pledged collateral or a cash balance, and the market maker controls.
+///
+/// # Errors
+/// Returns [`RepositoryError`] when a write fails; the batches written
+/// before it stay stored, and the next call with the same `since_ms` writes
+/// the rest.
+pub async fn save_changes(
+    state: &crate::state::AppState,
+    since_ms: u64,
+    now_ms: u64,
+) -> Result<SaveSummary, RepositoryError> {
+    let repository = state.repository.as_ref();
+    let mut cash: BTreeMap<String, i64> = state.ledger.cash_balances().into_iter().collect();
+    for entry in state.collateral.iter() {
+        cash.entry(entry.key().clone()).or_insert(0);
+    }
+    let accounts: Vec<AccountRecord> = cash
+        .into_iter()
+        .map(|(account_id, cash_cents)| AccountRecord {
+            collateral: state.collateral.get(&account_id).map(|c| *c),
+            account_id,
+            cash_cents,
+            created_at_ms: now_ms,
+            updated_at_ms: now_ms,
+        })
+        .collect();
+    let ledger_entries = state.ledger.entries_since(since_ms);
+    let orders: Vec<OrderInfo> = state
+        .orders
+        .iter()
+        .filter(|entry| entry.updated_at_ms >= since_ms)
+        .map(|entry| entry.value().clone())
+        .collect();
+    let fills: Vec<ExecutionInfo> = state
+        .executions
+        .iter()
+        .filter(|entry| entry.timestamp_ms >= since_ms)
+        .map(|entry| entry.value().clone())
+        .collect();
+    let positions: Vec<(String, PositionInfo)> = state
+        .account_positions
+        .iter()
+        .flat_map(|account| {
+            account
+                .value()
+                .iter()
+                .filter(|entry| entry.updated_at_ms >= since_ms)
+                .map(|entry| (account.key().clone(), entry.value().clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let last_trades: Vec<LastTradeInfo> = state
+        .last_trades
+        .iter()
+        .filter(|entry| entry.timestamp_ms >= since_ms)
+        .map(|entry| entry.value().clone())
+        .collect();
+
+    if !accounts.is_empty() {
+        repository.save_accounts(&accounts).await?;
+    }
+    let mut order_events = if orders.is_empty() {
+        0
+    } else {
+        repository.save_orders(&orders).await?
+    };
+    // Canceled orders leave the in-memory map, so close their stored rows
+    let tracked: Vec<String> = state.orders.iter().map(|e| e.key().clone()).collect();
+    order_events += repository.cancel_untracked_orders(&tracked, now_ms).await?;
+    let written_fills = if fills.is_empty() {
+        0
+    } else {
+        repository.save_fills(&fills).await?
+    };
+    if !positions.is_empty() {
+        repository.save_positions(&positions).await?;
+    }
+    if !last_trades.is_empty() {
+        repository.save_last_trades(&last_trades).await?;
+    }
+    let written_ledger_entries = if ledger_entries.is_empty() {
+        0
+    } else {
+        repository.save_ledger_entries(&ledger_entries).await?
+    };
+    let config = state.market_maker.get_config();
+    repository
+        .save_controls(&ControlsRecord {
+            master_enabled: config.enabled,
+            spread_multiplier: config.spread_multiplier,
+            size_scalar: config.size_scalar,
+            directional_skew: config.directional_skew,
+            symbol_enabled: config.symbol_enabled.into_iter().collect(),
+        })
+        .await?;
+    Ok(SaveSummary {
+        accounts: accounts.len(),
+        orders: orders.len(),
+        order_events,
+        fills: written_fills,
+        positions: positions.len(),
+        last_trades: last_trades.len(),
+        ledger_entries: written_ledger_entries,
+    })
+}
+
+/// Reads the trading state stored in `state`'s repository back into `state`:
+/// the cash ledger, each account's pledged collateral and positions, the last
+/// trades, and the filled and canceled orders with their fills, so the order
+/// status and fill endpoints keep answering for them.
+///
+/// Stored open orders are left out: their books do not survive a restart,
+/// and the next [`save_changes`] marks them canceled. Used on startup when
+/// no snapshot or journal rebuilds the state.
+///
+/// # Errors
+/// Returns [`RepositoryError`] when a read fails; what was read before it
+/// stays in `state`.
+pub async fn load_state(state: &crate::state::AppState) -> Result<LoadSummary, RepositoryError> {
+    let repository = state.repository.as_ref();
+    let mut summary = LoadSummary::default();
+    if state.ledger.is_empty() {
+        let entries = repository.load_ledger_entries().await?;
+        summary.ledger_entries = entries.len();
+        state.ledger.restore(entries)?;
+    }
+    for trade in repository.load_last_trades().await? {
+        state.last_trades.insert(trade.symbol.clone(), trade);
+        summary.last_trades += 1;
+    }
+    let mut order_ids = Vec::new();
+    for account in repository.load_accounts().await? {
+        if let Some(collateral) = account.collateral {
+            state
+                .collateral
+                .insert(account.account_id.clone(), collateral);
+            summary.accounts += 1;
+        }
+        let positions = repository.load_positions(&account.account_id).await?;
+        if !positions.is_empty() {
+            summary.positions += positions.len();
+            let book = state
+                .account_positions
+                .entry(account.account_id.clone())
+                .or_default();
+            for position in positions {
+                book.insert(position.symbol.clone(), position);
+            }
+        }
+        for order in repository.load_orders(&account.account_id).await? {
+            if matches!(order.status, OrderStatus::Filled | OrderStatus::Canceled) {
+                order_ids.push(order.order_id.clone());
+                state.orders.insert(order.order_id.clone(), order);
+            }
+        }
+    }
+    summary.orders = order_ids.len();
+    if !order_ids.is_empty() {
+        for fill in repository.load_fills(&order_ids).await? {
+            state.executions.insert(fill.execution_id.clone(), fill);
+            summary.fills += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Applies the market maker controls stored in `state`'s repository to its
+/// market maker: the kill switch, the global quoting parameters and each
+/// underlying's quoting switch. Returns whether any were stored.
+///
+/// Snapshots and the journal do not carry the controls, so this runs on
+/// every startup with a database.
+///
+/// # Errors
+/// Returns [`RepositoryError`] when the read fails.
+pub async fn restore_controls(state: &crate::state::AppState) -> Result<bool, RepositoryError> {
+    let Some(controls) = state.repository.load_controls().await? else {
+        return Ok(false);
+    };
+    let market_maker = &state.market_maker;
+    market_maker.set_enabled(controls.master_enabled);
+    market_maker.set_spread_multiplier(controls.spread_multiplier);
+    market_maker.set_size_scalar(controls.size_scalar);
+    market_maker.set_directional_skew(controls.directional_skew);
+    for (symbol, enabled) in &controls.symbol_enabled {
+        market_maker.set_symbol_enabled(symbol, *enabled);
+    }
+    Ok(true)
+}
+
+// ============================================================================
+// In-memory
+// ============================================================================
+
+/// Repository kept in process memory; used when no database is connected.
+///
+/// Prices are reduced to the last one per UTC day, up to
+/// 1,000 days per symbol; price histories read the latest 10,000 prices
+/// of each symbol.
+#[derive(Default)]
+pub struct InMemoryRepository {
+    prices: DashMap<String, BTreeMap<NaiveDate, PriceRecord>>,
+    recent_prices: DashMap<String, VecDeque<PriceRecord>>,
+    accounts: DashMap<String, AccountRecord>,
+    orders: DashMap<String, OrderInfo>,
+    fills: DashMap<String, ExecutionInfo>,
+    positions: DashMap<String, HashMap<String, PositionInfo>>,
+    last_trades: DashMap<String, LastTradeInfo>,
+    ledger_entries: DashMap<u64, LedgerEntry>,
+    controls: Mutex<Option<ControlsRecord>>,
+}
+
+impl InMemoryRepository {
+    /// Creates an empty repository.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `account` with no pledged collateral unless it exists.
+    fn ensure_account(&self, account: &str, now_ms: u64) {
+        self.accounts
+            .entry(account.to_string())
+            .or_insert_with(|| AccountRecord {
+                account_id: account.to_string(),
+                collateral: None,
+                cash_cents: 0,
+                created_at_ms: now_ms,
+                updated_at_ms: now_ms,
+            });
+    }
+}
+
+impl Repository for InMemoryRepository {
+    fn insert_prices<'a>(&'a self, prices: &'a [PriceRecord]) -> RepositoryFuture<'a, ()> {
+        for price in prices {
+            let mut days = self.prices.entry(price.symbol.clone()).or_default();
+            let day = price.timestamp.date_naive();
+            if days
+                .get(&day)
+                .is_none_or(|stored| stored.timestamp <= price.timestamp)
+            {
+                days.insert(day, price.clone());
+            }
+            while days.len() > MAX_DAILY_CLOSES {
+                days.pop_first();
+            }
+            let mut recent = self.recent_prices.entry(price.symbol.clone()).or_default();
+            recent.push_back(price.clone());
+            if recent.len() > MAX_RECENT_PRICES {
+                recent.pop_front();
+            }
+        }
+        futures::future::ready(Ok(())).boxed()
+    }
+
+    fn latest_price<'a>(&'a self, symbol: &'a str) -> RepositoryFuture<'a, Option<PriceRecord>> {
+        let latest = self
+            .prices
+            .get(symbol)
+            .and_then(|days| days.last_key_value().map(|(_, price)| price.clone()));
+        futures::future::ready(Ok(latest)).boxed()
+    }
+
+    fn daily_closes<'a>(
+        &'a self,
+        symbols: &'a [String],
+        lookback_days: u32,
+    ) -> RepositoryFuture<'a, Vec<DailyClose>> {
+        let now = Utc::now();
+        let cutoff = now
+            .checked_sub_days(Days::new(lookback_days.into()))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let closes = symbols
+            .iter()
+            .filter_map(|symbol| self.prices.get(symbol))
+            .flat_map(|days| {
+                days.values()
+                    .filter(|price| price.timestamp >= cutoff)
+                    .map(|price| DailyClose {
+                        symbol: price.symbol.clone(),
+                        day: price.timestamp.date_naive(),
+                        price_cents: price.price_cents,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        futures::future::ready(Ok(closes)).boxed()
+    }
+
+    fn price_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        range: &'a PriceHistoryRange,
+    ) -> RepositoryFuture<'a, Vec<PriceBar>> {
+        let mut prices: Vec<PriceRecord> = self
+            .recent_prices
+            .get(symbol)
+            .map(|recent| {
+                recent
+                    .iter()
+                    .filter(|price| range.from.is_none_or(|from| price.timestamp >= from))
+                    .filter(|price| range.to.is_none_or(|to| price.timestamp <= to))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Stable, so prices observed at the same instant keep their order
+        prices.sort_by_key(|price| price.timestamp);
+        let mut bars: Vec<PriceBar> = Vec::new();
+        for price in prices {
+            let start = bar_start(price.timestamp, range.bucket_secs);
+            match bars.last_mut() {
+                Some(bar) if bar.start == start => {
+                    bar.high_cents = bar.high_cents.max(price.price_cents);
+                    bar.low_cents = bar.low_cents.min(price.price_cents);
+                    bar.close_cents = price.price_cents;
+                    bar.volume = match (bar.volume, price.volume) {
+                        (Some(summed), Some(volume)) => Some(summed + volume),
+                        (summed, volume) => summed.or(volume),
+                    };
+                    bar.samples += 1;
+                }
+                _ => bars.push(PriceBar {
+                    start,
+                    open_cents: price.price_cents,
+                    high_cents: price.price_cents,
+                    low_cents: price.price_cents,
+                    close_cents: price.price_cents,
+                    volume: price.volume,
+                    samples: 1,
+                }),
+            }
+        }
+        if bars.len() > range.limit {
+            if range.oldest_first {
+                bars.truncate(range.limit);
+            } else {
+                bars.drain(..bars.len() - range.limit);
+            }
+        }
+        futures::future::ready(Ok(bars)).boxed()
+    }
+
+    fn save_accounts<'a>(&'a self, accounts: &'a [AccountRecord]) -> RepositoryFuture<'a, ()> {
+        for account in accounts {
+            self.accounts
+                .entry(account.account_id.clone())
+                .and_modify(|stored| {
+                    stored.collateral = account.collateral;
+                    stored.cash_cents = account.cash_cents;
+                    stored.updated_at_ms = account.updated_at_ms;
+                })
+                .or_insert_with(|| account.clone());
+        }
+        futures::future::ready(Ok(())).boxed()
+    }
+
+    fn load_accounts(&self) -> RepositoryFuture<'_, Vec<AccountRecord>> {
+        let mut accounts: Vec<AccountRecord> = self
+            .accounts
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        accounts.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        futures::future::ready(Ok(accounts)).boxed()
+    }
+
+    fn save_orders<'a>(&'a self, orders: &'a [OrderInfo]) -> RepositoryFuture<'a, u64> {
+        let mut appended = 0;
+        for order in orders {
+            self.ensure_account(&order.account, order.created_at_ms);
+            let changed = self.orders.get(&order.order_id).is_none_or(|stored| {
+                stored.status != order.status
+                    || stored.filled_quantity != order.filled_quantity
+                    || stored.remaining_quantity != order.remaining_quantity
+            });
+            self.orders.insert(order.order_id.clone(), order.clone());
+            if changed {
+                appended += 1;
+            }
+        }
+        futures::future::ready(Ok(appended)).boxed()
+    }
+
+    fn cancel_untracked_orders<'a>(
+        &'a self,
+        tracked: &'a [String],
+        now_ms: u64,
+    ) -> RepositoryFuture<'a, u64> {
+        let tracked: HashSet<&str> = tracked.iter().map(String::as_str).collect();
+        let mut closed = 0;
+        for mut order in self.orders.iter_mut() {
+            let open = matches!(
+                order.status,
+                OrderStatus::Pending | OrderStatus::Active | OrderStatus::Partial
+            );
+            if !open || tracked.contains(order.order_id.as_str()) {
+                continue;
+            }
+            order.status = OrderStatus::Canceled;
+            order.updated_at_ms = now_ms;
+            closed += 1;
+        }
+        futures::future::ready(Ok(closed)).boxed()
+    }
+
+    fn load_orders<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<OrderInfo>> {
+        let mut orders: Vec<OrderInfo> = self
+            .orders
+            .iter()
+            .filter(|entry| entry.account == account)
+            .map(|entry| entry.value().clone())
+            .collect();
+        orders.sort_by(|a, b| (a.created_at_ms, &a.order_id).cmp(&(b.created_at_ms, &b.order_id)));
+        // Like the relational repositories, rebuild each order's fills from
+        // the stored ones.
+        let mut fills: Vec<ExecutionInfo> = self
+            .fills
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        fills.sort_by(|a, b| {
+            (a.timestamp_ms, &a.execution_id).cmp(&(b.timestamp_ms, &b.execution_id))
+        });
+        let mut by_order = fills_by_order(fills);
+        for order in &mut orders {
+            order.fills = by_order.remove(&order.order_id).unwrap_or_default();
+        }
+        futures::future::ready(Ok(orders)).boxed()
+    }
+
+    fn save_fills<'a>(&'a self, fills: &'a [ExecutionInfo]) -> RepositoryFuture<'a, u64> {
+        let mut written = 0;
+        for fill in fills {
+            if !self.fills.contains_key(&fill.execution_id) {
+                self.fills.insert(fill.execution_id.clone(), fill.clone());
+                written += 1;
+            }
+        }
+        futures::future::ready(Ok(written)).boxed()
+    }
+
+    fn load_fills<'a>(
+        &'a self,
+        order_ids: &'a [String],
+    ) -> RepositoryFuture<'a, Vec<ExecutionInfo>> {
+        let order_ids: HashSet<&str> = order_ids.iter().map(String::as_str).collect();
+        let mut fills: Vec<ExecutionInfo> = self
+            .fills
+            .iter()
+            .filter(|entry| {
+                order_ids.contains(entry.order_id.as_str())
+                    || entry
+                        .counterparty_order_id
+                        .as_deref()
+                        .is_some_and(|maker| order_ids.contains(maker))
+            })
+            .map(|entry| entry.value().clone())
+            .collect();
+        fills.sort_by(|a, b| {
+            (a.timestamp_ms, &a.execution_id).cmp(&(b.timestamp_ms, &b.execution_id))
+        });
+        futures::future::ready(Ok(fills)).boxed()
+    }
+
+    fn save_positions<'a>(
+        &'a self,
+        positions: &'a [(String, PositionInfo)],
+    ) -> RepositoryFuture<'a, ()> {
+        for (account, position) in positions {
+            self.ensure_account(account, position.created_at_ms);
+            self.positions
+                .entry(account.clone())
+                .or_default()
+                .insert(position.symbol.clone(), position.clone());
+        }
+        futures::future::ready(Ok(())).boxed()
+    }
+
+    fn load_positions<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<PositionInfo>> {
+        let mut positions: Vec<PositionInfo> = self
+            .positions
+            .get(account)
+            .map(|book| book.values().cloned().collect())
+            .unwrap_or_default();
+        positions.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        futures::future::ready(Ok(positions)).boxed()
+    }
+
+    fn save_last_trades<'a>(&'a self, trades: &'a [LastTradeInfo]) -> RepositoryFuture<'a, ()> {
+        for trade in trades {
+            self.last_trades
+                .entry(trade.symbol.clone())
+                .and_modify(|stored| {
+                    if stored.timestamp_ms <= trade.timestamp_ms {
+                        *stored = trade.clone();
+                    }
+                })
+                .or_insert_with(|| trade.clone());
+        }
+        futures::future::ready(Ok(())).boxed()
+    }
+
+    fn load_last_trades(&self) -> RepositoryFuture<'_, Vec<LastTradeInfo>> {
+        let mut trades: Vec<LastTradeInfo> = self
+            .last_trades
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        trades.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        futures::future::ready(Ok(trades)).boxed()
+    }
+
+    fn save_ledger_entries<'a>(&'a self, entries: &'a [LedgerEntry]) -> RepositoryFuture<'a, u64> {
+        let mut written = 0;
+        for entry in entries {
+            if !self.ledger_entries.contains_key(&entry.entry_id) {
+                self.ledger_entries.insert(entry.entry_id, entry.clone());
+                written += 1;
+            }
+        }
+        futures::future::ready(Ok(written)).boxed()
+    }
+
+    fn load_ledger_entries(&self) -> RepositoryFuture<'_, Vec<LedgerEntry>> {
+        let mut entries: Vec<LedgerEntry> = self
+            .ledger_entries
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        entries.sort_by_key(|entry| entry.entry_id);
+        futures::future::ready(Ok(entries)).boxed()
+    }
+
+    fn save_controls<'a>(&'a self, controls: &'a ControlsRecord) -> RepositoryFuture<'a, ()> {
+        *self.controls.lock() = Some(controls.clone());
+        futures::future::ready(Ok(())).boxed()
+    }
+
+    fn load_controls(&self) -> RepositoryFuture<'_, Option<ControlsRecord>> {
+        futures::future::ready(Ok(self.controls.lock().clone())).boxed()
+    }
+}
+
+// ============================================================================
+// Postgres
+// ============================================================================
+
+const INSERT_PRICES: &str = r#"
+    INSERT INTO underlying_prices (symbol, price_cents, bid_cents, ask_cents, volume, timestamp, source)
+    SELECT * FROM UNNEST(
+        $1::text[], $2::bigint[], $3::bigint[], $4::bigint[], $5::bigint[],
+        $6::timestamptz[], $7::text[]
+    )
+"#;
+
+const LATEST_PRICE: &str = r#"
+    SELECT symbol, price_cents, bid_cents, ask_cents, volume, timestamp, source
+    FROM underlying_prices
+    WHERE symbol = $1
+    ORDER BY timestamp DESC, seq DESC
+    LIMIT 1
+"#;
+
+const DAILY_CLOSES: &str = r#"
+    SELECT DISTINCT ON (symbol, (timestamp AT TIME ZONE 'UTC')::date)
+        symbol, (timestamp AT TIME ZONE 'UTC')::date, price_cents
+    FROM underlying_prices
+    WHERE symbol = ANY($1) AND timestamp >= NOW() - make_interval(days => $2)
+    ORDER BY symbol, (timestamp AT TIME ZONE 'UTC')::date, timestamp DESC, seq DESC
+"#;
+
+/// The prices of `$1` between `$2` and `$3`, grouped into bars of `$4`
+/// seconds (0 keeps every price). `$5` keeps the oldest `$6` bars instead of
+/// the newest.
+const PRICE_HISTORY: &str = r#"
+    SELECT * FROM (
+        SELECT * FROM (
+            SELECT
+                CASE WHEN $4::BIGINT = 0 THEN timestamp
+                     ELSE to_timestamp(floor(extract(epoch FROM timestamp) / $4::BIGINT) * $4::BIGINT)
+                END AS bucket,
+                (array_agg(price_cents ORDER BY timestamp, seq))[1] AS open_cents,
+                max(price_cents) AS high_cents,
+                min(price_cents) AS low_cents,
+                (array_agg(price_cents ORDER BY timestamp DESC, seq DESC))[1] AS close_cents,
+                sum(volume)::BIGINT AS volume,
+                count(*) AS samples
+            FROM underlying_prices
+            WHERE symbol = $1
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            GROUP BY 1
+        ) grouped
+        ORDER BY CASE WHEN $5::BOOLEAN THEN bucket END ASC, bucket DESC
+        LIMIT $6
+    ) bars
+    ORDER BY bucket
+"#;
+
+const UPSERT_ACCOUNTS: &str = r#"
+    INSERT INTO accounts (account_id, collateral_cents, cash_cents, created_at_ms, updated_at_ms)
+    SELECT * FROM UNNEST($1::text[], $2::bigint[], $3::bigint[], $4::bigint[], $5::bigint[])
+    ON CONFLICT (account_id) DO UPDATE SET
+        collateral_cents = EXCLUDED.collateral_cents,
+        cash_cents = EXCLUDED.cash_cents,
+        updated_at_ms = EXCLUDED.updated_at_ms
+"#;
+
+/// Creates the accounts of `$1` that do not exist yet, stamped `$2`.
+const ENSURE_ACCOUNTS: &str = r#"
+    INSERT INTO accounts (account_id, created_at_ms, updated_at_ms)
+    SELECT DISTINCT account_id, $2::bigint, $2::bigint FROM UNNEST($1::text[]) AS t(account_id)
+    ON CONFLICT (account_id) DO NOTHING
+"#;
+
+const LOAD_ACCOUNTS: &str = r#"
+    SELECT account_id, collateral_cents, cash_cents, created_at_ms, updated_at_ms
+    FROM accounts
+    ORDER BY account_id
+"#;
+
+/// Upserts the orders and, comparing against the rows as they were before
+/// the statement, appends an event for every new or changed order.
+const UPSERT_ORDERS: &str = r#"
+    WITH input AS (
+        SELECT * FROM UNNEST(
+            $1::text[], $2::text[], $3::text[], $4::text[], $5::text[], $6::bigint[],
+            $7::text[], $8::text[], $9::bigint[], $10::bigint[], $11::bigint[],
+            $12::bigint[], $13::text[], $14::text[], $15::bigint[], $16::bigint[]
+        ) AS t(
+            order_id, account_id, symbol, underlying, expiration, strike,
+            style, side, price_cents, original_quantity, remaining_quantity,
+            filled_quantity, status, time_in_force, created_at_ms, updated_at_ms
+        )
+    ),
+    changed AS (
+        SELECT input.order_id, input.status, input.filled_quantity,
+            input.remaining_quantity, input.updated_at_ms
+        FROM input LEFT JOIN orders ON orders.order_id = input.order_id
+        WHERE orders.order_id IS NULL
+            OR orders.status <> input.status
+            OR orders.filled_quantity <> input.filled_quantity
+            OR orders.remaining_quantity <> input.remaining_quantity
+    ),
+    upserted AS (
+        INSERT INTO orders SELECT * FROM input
+        ON CONFLICT (order_id) DO UPDATE SET
+            price_cents = EXCLUDED.price_cents,
+            original_quantity = EXCLUDED.original_quantity,
+            remaining_quantity = EXCLUDED.remaining_quantity,
+            filled_quantity = EXCLUDED.filled_quantity,
+            status = EXCLUDED.status,
+            updated_at_ms = EXCLUDED.updated_at_ms
+    )
+    INSERT INTO order_events (order_id, status, filled_quantity, remaining_quantity, recorded_at_ms)
+    SELECT * FROM changed
+"#;
+
+const CANCEL_UNTRACKED_ORDERS: &str = r#"
+    WITH closed AS (
+        UPDATE orders SET status = 'canceled', updated_at_ms = $2
+        WHERE status IN ('pending', 'active', 'partial') AND NOT (order_id = ANY($1))
+        RETURNING order_id, status, filled_quantity, remaining_quantity, updated_at_ms
+    )
+    INSERT INTO order_events (order_id, status, filled_quantity, remaining_quantity, recorded_at_ms)
+    SELECT * FROM closed
+"#;
+
+const LOAD_ORDERS: &str = r#"
+    SELECT order_id, account_id, symbol, underlying, expiration, strike, style, side,
+        price_cents, original_quantity, remaining_quantity, filled_quantity, status,
+        time_in_force, created_at_ms, updated_at_ms
+    FROM orders
+    WHERE account_id = $1
+    ORDER BY created_at_ms, order_id
+"#;
+
+const INSERT_FILLS: &str = r#"
+    INSERT INTO executions (
+        trade_id, order_id, counterparty_order_id, symbol, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker, executed_at
+    )
+    SELECT trade_id, order_id, counterparty_order_id, symbol, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker, to_timestamp(executed_at_ms / 1000.0)
+    FROM UNNEST(
+        $1::text[], $2::text[], $3::text[], $4::text[], $5::text[], $6::text[], $7::bigint[],
+        $8::bigint[], $9::bigint[], $10::bigint[], $11::boolean[], $12::bigint[]
+    ) AS t(
+        trade_id, order_id, counterparty_order_id, symbol, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker, executed_at_ms
+    )
+    ON CONFLICT (trade_id) DO NOTHING
+"#;
+
+/// The fills any of the orders in `$1` took part in, as taker or maker.
+const LOAD_FILLS: &str = r#"
+    SELECT trade_id, order_id, counterparty_order_id, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker,
+        (EXTRACT(EPOCH FROM executed_at) * 1000)::BIGINT AS executed_at_ms
+    FROM executions
+    WHERE trade_id IS NOT NULL AND (order_id = ANY($1) OR counterparty_order_id = ANY($1))
+    ORDER BY executed_at, trade_id
+"#;
+
+const UPSERT_POSITIONS: &str = r#"
+    INSERT INTO positions (
+        account_id, symbol, underlying, quantity, average_price_cents,
+        realized_pnl_cents, multiplier, created_at_ms, updated_at_ms
+    )
+    SELECT * FROM UNNEST(
+        $1::text[], $2::text[], $3::text[], $4::bigint[], $5::bigint[],
+        $6::bigint[], $7::bigint[], $8::bigint[], $9::bigint[]
+    )
+    ON CONFLICT (account_id, symbol) DO UPDATE SET
+        quantity = EXCLUDED.quantity,
+        average_price_cents = EXCLUDED.average_price_cents,
+        realized_pnl_cents = EXCLUDED.realized_pnl_cents,
+        multiplier = EXCLUDED.multiplier,
+        updated_at_ms = EXCLUDED.updated_at_ms
+"#;
+
+const LOAD_POSITIONS: &str = r#"
+    SELECT symbol, underlying, quantity, average_price_cents, realized_pnl_cents,
+        multiplier, created_at_ms, updated_at_ms
+    FROM positions
+    WHERE account_id = $1
+    ORDER BY symbol
+"#;
+
+const UPSERT_LAST_TRADES: &str = r#"
+    INSERT INTO last_trades (symbol, trade_id, price_cents, quantity, side, traded_at_ms)
+    SELECT * FROM UNNEST(
+        $1::text[], $2::text[], $3::bigint[], $4::bigint[], $5::text[], $6::bigint[]
+    )
+    ON CONFLICT (symbol) DO UPDATE SET
+        trade_id = EXCLUDED.trade_id,
+        price_cents = EXCLUDED.price_cents,
+        quantity = EXCLUDED.quantity,
+        side = EXCLUDED.side,
+        traded_at_ms = EXCLUDED.traded_at_ms
+    WHERE last_trades.traded_at_ms <= EXCLUDED.traded_at_ms
+"#;
+
+const LOAD_LAST_TRADES: &str = r#"
+    SELECT symbol, trade_id, price_cents, quantity, side, traded_at_ms
+    FROM last_trades
+    ORDER BY symbol
+"#;
+
+const INSERT_LEDGER_ENTRIES: &str = r#"
+    INSERT INTO ledger_entries (entry_id, kind, reference, recorded_at_ms)
+    SELECT * FROM UNNEST($1::bigint[], $2::text[], $3::text[], $4::bigint[])
+    ON CONFLICT (entry_id) DO NOTHING
+"#;
+
+const INSERT_LEDGER_POSTINGS: &str = r#"
+    INSERT INTO ledger_postings (entry_id, leg, ledger_account, amount_cents)
+    SELECT * FROM UNNEST($1::bigint[], $2::integer[], $3::text[], $4::bigint[])
+    ON CONFLICT (entry_id, leg) DO NOTHING
+"#;
+
+const UPSERT_SYSTEM_CONTROL: &str = r#"
+    INSERT INTO system_control (
+        id, master_enabled, global_spread_multiplier, global_size_scalar, global_directional_skew
+    )
+    VALUES (1, $1, $2, $3, $4)
+    ON CONFLICT (id) DO UPDATE SET
+        master_enabled = EXCLUDED.master_enabled,
+        global_spread_multiplier = EXCLUDED.global_spread_multiplier,
+        global_size_scalar = EXCLUDED.global_size_scalar,
+        global_directional_skew = EXCLUDED.global_directional_skew,
+        updated_at = NOW()
+    WHERE (
+        system_control.master_enabled, system_control.global_spread_multiplier,
+        system_control.global_size_scalar, system_control.global_directional_skew
+    ) IS DISTINCT FROM (
+        EXCLUDED.master_enabled, EXCLUDED.global_spread_multiplier,
+        EXCLUDED.global_size_scalar, EXCLUDED.global_directional_skew
+    )
+"#;
+
+const UPSERT_SYMBOL_CONTROLS: &str = r#"
+    INSERT INTO market_maker_configs (symbol, quoting_enabled)
+    SELECT * FROM UNNEST($1::text[], $2::boolean[])
+    ON CONFLICT (symbol) DO UPDATE SET
+        quoting_enabled = EXCLUDED.quoting_enabled,
+        updated_at = NOW()
+    WHERE market_maker_configs.quoting_enabled <> EXCLUDED.quoting_enabled
+"#;
+
+const LOAD_SYSTEM_CONTROL: &str = r#"
+    SELECT master_enabled, global_spread_multiplier, global_size_scalar, global_directional_skew
+    FROM system_control
+    WHERE id = 1
+"#;
+
+const LOAD_SYMBOL_CONTROLS: &str = r#"
+    SELECT symbol, quoting_enabled FROM market_maker_configs ORDER BY symbol
+"#;
+
+/// Every posting with its entry, in entry and leg order.
+const LOAD_LEDGER: &str = r#"
+    SELECT ledger_entries.entry_id, kind, reference, recorded_at_ms, ledger_account, amount_cents
+    FROM ledger_entries JOIN ledger_postings ON ledger_postings.entry_id = ledger_entries.entry_id
+    ORDER BY ledger_entries.entry_id, leg
+"#;
+
+type AccountRow = (String, Option<i64>, i64, i64, i64);
+
+type PriceBarRow<T> = (T, i64, i64, i64, i64, Option<i64>, i64);
+
+type LastTradeRow = (String, String, i64, i64, String, i64);
+
+type LedgerRow = (i64, String, String, i64, String, i64);
+
+type SystemControlRow = (bool, f64, f64, f64);
+
+#[derive(FromRow)]
+struct PriceRow {
+    symbol: String,
+    price_cents: i64,
+    bid_cents: Option<i64>,
+    ask_cents: Option<i64>,
+    volume: Option<i64>,
+    timestamp: DateTime<Utc>,
+    source: Option<String>,
+}
+
+#[derive(FromRow)]
+struct OrderRow {
+    order_id: String,
+    account_id: String,
+    symbol: String,
+    underlying: String,
+    expiration: String,
+    strike: i64,
+    style: String,
+    side: String,
+    price_cents: i64,
+    original_quantity: i64,
+    remaining_quantity: i64,
+    filled_quantity: i64,
+    status: String,
+    time_in_force: String,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+}
+
+#[derive(FromRow)]
+struct FillRow {
+    trade_id: String,
+    order_id: String,
+    counterparty_order_id: Option<String>,
+    instrument: String,
+    side: String,
+    price_cents: i64,
+    quantity: i64,
+    fee_cents: i64,
+    edge_cents: Option<i64>,
+    is_maker: bool,
+    executed_at_ms: i64,
+}
+
+#[derive(FromRow)]
+struct PositionRow {
+    symbol: String,
+    underlying: String,
+    quantity: i64,
+    average_price_cents: i64,
+    realized_pnl_cents: i64,
+    multiplier: i64,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+}
+
+/// Repository backed by the Postgres tables.
+pub struct PostgresRepository {
+    pool: PgPool,
+}
+
+impl PostgresRepository {
+    /// Creates a repository reading and writing `pool`.
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn fills_of(&self, order_ids: &[String]) -> Result<Vec<ExecutionInfo>, RepositoryError> {
+        let rows: Vec<FillRow> = sqlx::query_as(LOAD_FILLS)
+            .bind(order_ids)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.into_iter().map(fill_from_row).collect()
+    }
+}
+
+impl Repository for PostgresRepository {
+    fn insert_prices<'a>(&'a self, prices: &'a [PriceRecord]) -> RepositoryFuture<'a, ()> {
+        async move {
+            sqlx::query(INSERT_PRICES)
+                .bind(column(prices, |p| p.symbol.clone()))
+                .bind(column(prices, |p| p.price_cents))
+                .bind(column(prices, |p| p.bid_cents))
+                .bind(column(prices, |p| p.ask_cents))
+                .bind(column(prices, |p| p.volume))
+                .bind(column(prices, |p| p.timestamp))
+                .bind(column(prices, |p| p.source.clone()))
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn latest_price<'a>(&'a self, symbol: &'a str) -> RepositoryFuture<'a, Option<PriceRecord>> {
+        async move {
+            let row: Option<PriceRow> = sqlx::query_as(LATEST_PRICE)
+                .bind(symbol)
+                .fetch_optional(&self.pool)
+                .await?;
+            Ok(row.map(|row| PriceRecord {
+                symbol: row.symbol,
+                price_cents: row.price_cents,
+                bid_cents: row.bid_cents,
+                ask_cents: row.ask_cents,
+                volume: row.volume,
+                timestamp: row.timestamp,
+                source: row.source,
+            }))
+        }
+        .boxed()
+    }
+
+    fn daily_closes<'a>(
+        &'a self,
+        symbols: &'a [String],
+        lookback_days: u32,
+    ) -> RepositoryFuture<'a, Vec<DailyClose>> {
+        async move {
+            let rows: Vec<(String, NaiveDate, i64)> = sqlx::query_as(DAILY_CLOSES)
+                .bind(symbols)
+                .bind(i32::try_from(lookback_days).unwrap_or(i32::MAX))
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows
+                .into_iter()
+                .map(|(symbol, day, price_cents)| DailyClose {
+                    symbol,
+                    day,
+                    price_cents,
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn price_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        range: &'a PriceHistoryRange,
+    ) -> RepositoryFuture<'a, Vec<PriceBar>> {
+        async move {
+            let rows: Vec<PriceBarRow<DateTime<Utc>>> = sqlx::query_as(PRICE_HISTORY)
+                .bind(symbol)
+                .bind(range.from)
+                .bind(range.to)
+                .bind(to_i64(range.bucket_secs))
+                .bind(range.oldest_first)
+                .bind(to_i64(range.limit))
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows.into_iter().map(price_bar_from_row).collect())
+        }
+        .boxed()
+    }
+
+    fn save_accounts<'a>(&'a self, accounts: &'a [AccountRecord]) -> RepositoryFuture<'a, ()> {
+        async move {
+            sqlx::query(UPSERT_ACCOUNTS)
+                .bind(column(accounts, |a| a.account_id.clone()))
+                .bind(column(accounts, |a| a.collateral.map(to_i64)))
+                .bind(column(accounts, |a| a.cash_cents))
+                .bind(column(accounts, |a| to_i64(a.created_at_ms)))
+                .bind(column(accounts, |a| to_i64(a.updated_at_ms)))
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_accounts(&self) -> RepositoryFuture<'_, Vec<AccountRecord>> {
+        async move {
+            let rows: Vec<AccountRow> = sqlx::query_as(LOAD_ACCOUNTS).fetch_all(&self.pool).await?;
+            Ok(rows.into_iter().map(account_from_row).collect())
+        }
+        .boxed()
+    }
+
+    fn save_orders<'a>(&'a self, orders: &'a [OrderInfo]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let now_ms = orders.iter().map(|o| o.created_at_ms).min().unwrap_or(0);
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(ENSURE_ACCOUNTS)
+                .bind(column(orders, |o| o.account.clone()))
+                .bind(to_i64(now_ms))
+                .execute(&mut *tx)
+                .await?;
+            let events = sqlx::query(UPSERT_ORDERS)
+                .bind(column(orders, |o| o.order_id.clone()))
+                .bind(column(orders, |o| o.account.clone()))
+                .bind(column(orders, |o| o.symbol.clone()))
+                .bind(column(orders, |o| o.underlying.clone()))
+                .bind(column(orders, |o| o.expiration.clone()))
+                .bind(column(orders, |o| to_i64(o.strike)))
+                .bind(column(orders, |o| o.style.clone()))
+                .bind(column(orders, |o| o.side.to_string()))
+                .bind(column(orders, |o| to_i64(o.price)))
+                .bind(column(orders, |o| to_i64(o.original_quantity)))
+                .bind(column(orders, |o| to_i64(o.remaining_quantity)))
+                .bind(column(orders, |o| to_i64(o.filled_quantity)))
+                .bind(column(orders, |o| o.status.to_string()))
+                .bind(column(orders, |o| o.time_in_force.to_string()))
+                .bind(column(orders, |o| to_i64(o.created_at_ms)))
+                .bind(column(orders, |o| to_i64(o.updated_at_ms)))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            tx.commit().await?;
+            Ok(events)
+        }
+        .boxed()
+    }
+
+    fn cancel_untracked_orders<'a>(
+        &'a self,
+        tracked: &'a [String],
+        now_ms: u64,
+    ) -> RepositoryFuture<'a, u64> {
+        async move {
+            Ok(sqlx::query(CANCEL_UNTRACKED_ORDERS)
+                .bind(tracked)
+                .bind(to_i64(now_ms))
+                .execute(&self.pool)
+                .await?
+                .rows_affected())
+        }
+        .boxed()
+    }
+
+    fn load_orders<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<OrderInfo>> {
+        async move {
+            let rows: Vec<OrderRow> = sqlx::query_as(LOAD_ORDERS)
+                .bind(account)
+                .fetch_all(&self.pool)
+                .await?;
+            let order_ids: Vec<String> = rows.iter().map(|row| row.order_id.clone()).collect();
+            let fills = self.fills_of(&order_ids).await?;
+            orders_from_rows(rows, fills)
+        }
+        .boxed()
+    }
+
+    fn save_fills<'a>(&'a self, fills: &'a [ExecutionInfo]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let written = sqlx::query(INSERT_FILLS)
+                .bind(column(fills, |f| f.execution_id.clone()))
+                .bind(column(fills, |f| f.order_id.clone()))
+                .bind(column(fills, |f| f.counterparty_order_id.clone()))
+                .bind(column(fills, |f| underlying_of(&f.symbol).to_string()))
+                .bind(column(fills, |f| f.symbol.clone()))
+                .bind(column(fills, |f| f.side.to_string()))
+                .bind(column(fills, |f| to_i64(f.price)))
+                .bind(column(fills, |f| to_i64(f.quantity)))
+                .bind(column(fills, |f| to_i64(f.fee)))
+                .bind(column(fills, |f| f.edge))
+                .bind(column(fills, |f| f.is_maker))
+                .bind(column(fills, |f| to_i64(f.timestamp_ms)))
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+            Ok(written)
+        }
+        .boxed()
+    }
+
+    fn load_fills<'a>(
+        &'a self,
+        order_ids: &'a [String],
+    ) -> RepositoryFuture<'a, Vec<ExecutionInfo>> {
+        async move { self.fills_of(order_ids).await }.boxed()
+    }
+
+    fn save_positions<'a>(
+        &'a self,
+        positions: &'a [(String, PositionInfo)],
+    ) -> RepositoryFuture<'a, ()> {
+        async move {
+            let now_ms = positions
+                .iter()
+                .map(|(_, p)| p.created_at_ms)
+                .min()
+                .unwrap_or(0);
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(ENSURE_ACCOUNTS)
+                .bind(column(positions, |(a, _)| a.clone()))
+                .bind(to_i64(now_ms))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(UPSERT_POSITIONS)
+                .bind(column(positions, |(a, _)| a.clone()))
+                .bind(column(positions, |(_, p)| p.symbol.clone()))
+                .bind(column(positions, |(_, p)| p.underlying.clone()))
+                .bind(column(positions, |(_, p)| p.quantity))
+                .bind(column(positions, |(_, p)| to_i64(p.average_price)))
+                .bind(column(positions, |(_, p)| p.realized_pnl))
+                .bind(column(positions, |(_, p)| to_i64(p.multiplier)))
+                .bind(column(positions, |(_, p)| to_i64(p.created_at_ms)))
+                .bind(column(positions, |(_, p)| to_i64(p.updated_at_ms)))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_positions<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<PositionInfo>> {
+        async move {
+            let rows: Vec<PositionRow> = sqlx::query_as(LOAD_POSITIONS)
+                .bind(account)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows.into_iter().map(position_from_row).collect())
+        }
+        .boxed()
+    }
+
+    fn save_last_trades<'a>(&'a self, trades: &'a [LastTradeInfo]) -> RepositoryFuture<'a, ()> {
+        async move {
+            sqlx::query(UPSERT_LAST_TRADES)
+                .bind(column(trades, |t| t.symbol.clone()))
+                .bind(column(trades, |t| t.trade_id.clone()))
+                .bind(column(trades, |t| to_i64(t.price)))
+                .bind(column(trades, |t| to_i64(t.quantity)))
+                .bind(column(trades, |t| t.side.to_string()))
+                .bind(column(trades, |t| to_i64(t.timestamp_ms)))
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_last_trades(&self) -> RepositoryFuture<'_, Vec<LastTradeInfo>> {
+        async move {
+            let rows: Vec<LastTradeRow> = sqlx::query_as(LOAD_LAST_TRADES)
+                .fetch_all(&self.pool)
+                .await?;
+            rows.into_iter().map(last_trade_from_row).collect()
+        }
+        .boxed()
+    }
+
+    fn save_ledger_entries<'a>(&'a self, entries: &'a [LedgerEntry]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let postings: Vec<(u64, i32, &Posting)> = ledger_postings(entries);
+            let mut tx = self.pool.begin().await?;
+            let written = sqlx::query(INSERT_LEDGER_ENTRIES)
+                .bind(column(entries, |e| to_i64(e.entry_id)))
+                .bind(column(entries, |e| wire_name(&e.kind)))
+                .bind(column(entries, |e| e.reference.clone()))
+                .bind(column(entries, |e| to_i64(e.timestamp_ms)))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            sqlx::query(INSERT_LEDGER_POSTINGS)
+                .bind(column(&postings, |(id, _, _)| to_i64(*id)))
+                .bind(column(&postings, |(_, leg, _)| *leg))
+                .bind(column(&postings, |(_, _, p)| p.account.to_string()))
+                .bind(column(&postings, |(_, _, p)| p.amount_cents))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            Ok(written)
+        }
+        .boxed()
+    }
+
+    fn load_ledger_entries(&self) -> RepositoryFuture<'_, Vec<LedgerEntry>> {
+        async move {
+            let rows: Vec<LedgerRow> = sqlx::query_as(LOAD_LEDGER).fetch_all(&self.pool).await?;
+            ledger_from_rows(rows)
+        }
+        .boxed()
+    }
+
+    fn save_controls<'a>(&'a self, controls: &'a ControlsRecord) -> RepositoryFuture<'a, ()> {
+        async move {
+            let symbols: Vec<(&String, &bool)> = controls.symbol_enabled.iter().collect();
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(UPSERT_SYSTEM_CONTROL)
+                .bind(controls.master_enabled)
+                .bind(controls.spread_multiplier)
+                .bind(controls.size_scalar)
+                .bind(controls.directional_skew)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(UPSERT_SYMBOL_CONTROLS)
+                .bind(column(&symbols, |(symbol, _)| (*symbol).clone()))
+                .bind(column(&symbols, |(_, enabled)| **enabled))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_controls(&self) -> RepositoryFuture<'_, Option<ControlsRecord>> {
+        async move {
+            let row: Option<SystemControlRow> = sqlx::query_as(LOAD_SYSTEM_CONTROL)
+                .fetch_optional(&self.pool)
+                .await?;
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let symbols: Vec<(String, bool)> = sqlx::query_as(LOAD_SYMBOL_CONTROLS)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(Some(controls_from_rows(row, symbols)))
+        }
+        .boxed()
+    }
+}
+
+fn price_bar_from_row(
+    (start, open_cents, high_cents, low_cents, close_cents, volume, samples): PriceBarRow<
+        DateTime<Utc>,
+    >,
+) -> PriceBar {
+    PriceBar {
+        start,
+        open_cents,
+        high_cents,
+        low_cents,
+        close_cents,
+        volume,
+        samples: to_u64(samples),
+    }
+}
+
+fn controls_from_rows(
+    (master_enabled, spread_multiplier, size_scalar, directional_skew): SystemControlRow,
+    symbols: Vec<(String, bool)>,
+) -> ControlsRecord {
+    ControlsRecord {
+        master_enabled,
+        spread_multiplier,
+        size_scalar,
+        directional_skew,
+        symbol_enabled: symbols.into_iter().collect(),
+    }
+}
+
+fn account_from_row(
+    (account_id, collateral, cash_cents, created_at_ms, updated_at_ms): AccountRow,
+) -> AccountRecord {
+    AccountRecord {
+        account_id,
+        collateral: collateral.map(to_u64),
+        cash_cents,
+        created_at_ms: to_u64(created_at_ms),
+        updated_at_ms: to_u64(updated_at_ms),
+    }
+}
+
+/// Builds the orders of `rows`, each with the `fills` it took part in.
+fn orders_from_rows(
+    rows: Vec<OrderRow>,
+    fills: Vec<ExecutionInfo>,
+) -> Result<Vec<OrderInfo>, RepositoryError> {
+    let mut by_order = fills_by_order(fills);
+    rows.into_iter()
+        .map(|row| {
+            let order_fills = by_order.remove(&row.order_id).unwrap_or_default();
+            order_from_row(row, order_fills)
+        })
+        .collect()
+}
+
+/// The entries of `fills` per order, taker and maker alike.
+fn fills_by_order(
+    fills: impl IntoIterator<Item = ExecutionInfo>,
+) -> HashMap<String, Vec<OrderFillInfo>> {
+    let mut by_order: HashMap<String, Vec<OrderFillInfo>> = HashMap::new();
+    for fill in fills {
+        let entry = OrderFillInfo {
+            price: fill.price.into(),
+            quantity: fill.quantity,
+            timestamp_ms: fill.timestamp_ms,
+        };
+        if let Some(maker) = fill.counterparty_order_id {
+            by_order.entry(maker).or_default().push(entry.clone());
+        }
+        by_order.entry(fill.order_id).or_default().push(entry);
+    }
+    by_order
+}
+
+fn order_from_row(row: OrderRow, fills: Vec<OrderFillInfo>) -> Result<OrderInfo, RepositoryError> {
+    Ok(OrderInfo {
+        order_id: row.order_id,
+        account: row.account_id,
+        symbol: row.symbol,
+        underlying: row.underlying,
+        expiration: row.expiration,
+        strike: to_u64(row.strike),
+        style: row.style,
+        side: decode("orders.side", row.side)?,
+        price: to_u64(row.price_cents).into(),
+        original_quantity: to_u64(row.original_quantity),
+        remaining_quantity: to_u64(row.remaining_quantity),
+        filled_quantity: to_u64(row.filled_quantity),
+        status: row.status.parse().map_err(|_| RepositoryError::Decode {
+            column: "orders.status",
+            value: row.status,
+        })?,
+        time_in_force: decode("orders.time_in_force", row.time_in_force)?,
+        created_at_ms: to_u64(row.created_at_ms),
+        updated_at_ms: to_u64(row.updated_at_ms),
+        fills,
+    })
+}
+
+fn fill_from_row(row: FillRow) -> Result<ExecutionInfo, RepositoryError> {
+    Ok(ExecutionInfo {
+        execution_id: row.trade_id,
+        order_id: row.order_id,
+        symbol: row.instrument,
+        side: decode("executions.side", row.side)?,
+        price: to_u64(row.price_cents),
+        quantity: to_u64(row.quantity),
+        timestamp_ms: to_u64(row.executed_at_ms),
+        counterparty_order_id: row.counterparty_order_id,
+        is_maker: row.is_maker,
+        fee: to_u64(row.fee_cents),
+        edge: row.edge_cents,
+    })
+}
+
+fn position_from_row(row: PositionRow) -> PositionInfo {
+    PositionInfo {
+        symbol: row.symbol,
+        underlying: row.underlying,
+        quantity: row.quantity,
+        average_price: to_u64(row.average_price_cents).into(),
+        realized_pnl: row.realized_pnl_cents,
+        multiplier: to_u64(row.multiplier),
+        created_at_ms: to_u64(row.created_at_ms),
+        updated_at_ms: to_u64(row.updated_at_ms),
+    }
+}
+
+fn last_trade_from_row(
+    (symbol, trade_id, price, quantity, side, traded_at_ms): LastTradeRow,
+) -> Result<LastTradeInfo, RepositoryError> {
+    Ok(LastTradeInfo {
+        symbol,
+        price: to_u64(price),
+        quantity: to_u64(quantity),
+        side: decode("last_trades.side", side)?,
+        timestamp_ms: to_u64(traded_at_ms),
+        trade_id,
+    })
+}
+
+/// Start of the bar of `bucket_secs` seconds, aligned to the Unix epoch,
+/// holding a price observed at `timestamp`; 0 gives every instant its own bar.
+fn bar_start(timestamp: DateTime<Utc>, bucket_secs: u64) -> DateTime<Utc> {
+    let Ok(secs) = i64::try_from(bucket_secs) else {
+        return timestamp;
+    };
+    if secs == 0 {
+        return timestamp;
+    }
+    DateTime::from_timestamp(timestamp.timestamp().div_euclid(secs) * secs, 0).unwrap_or(timestamp)
+}
+
+/// The postings of `entries` with their entry id and leg.
+fn ledger_postings(entries: &[LedgerEntry]) -> Vec<(u64, i32, &Posting)> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            entry.postings.iter().enumerate().map(|(leg, posting)| {
+                (
+                    entry.entry_id,
+                    i32::try_from(leg).unwrap_or(i32::MAX),
+                    posting,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Builds the ledger entries of `rows`, one row per posting in entry and leg
+/// order.
+fn ledger_from_rows(rows: Vec<LedgerRow>) -> Result<Vec<LedgerEntry>, RepositoryError> {
+    let mut entries: Vec<LedgerEntry> = Vec::new();
+    for (entry_id, kind, reference, recorded_at_ms, account, amount_cents) in rows {
+        let posting = Posting::new(
+            account.parse().map_err(|_| RepositoryError::Decode {
+                column: "ledger_postings.ledger_account",
+                value: account,
+            })?,
+            amount_cents,
+        );
+        let entry_id = to_u64(entry_id);
+        match entries.last_mut() {
+            Some(entry) if entry.entry_id == entry_id => entry.postings.push(posting),
+            _ => entries.push(LedgerEntry {
+                entry_id,
+                kind: decode::<EntryKind>("ledger_entries.kind", kind)?,
+                reference,
+                postings: vec![posting],
+                timestamp_ms: to_u64(recorded_at_ms),
+            }),
+        }
+    }
+    Ok(entries)
+}
+
+/// Underlying of the `UNDERLYING-EXPIRATION-STRIKE-STYLE` option key
+/// `symbol`.
+fn underlying_of(symbol: &str) -> &str {
+    symbol.rsplitn(4, '-').last().unwrap_or(symbol)
+}
+
+/// Wire name of an enum, the form [`decode`] reads back.
+fn wire_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// Decodes an enum stored as its wire name.
+fn decode<T: DeserializeOwned>(column: &'static str, value: String) -> Result<T, RepositoryError> {
+    serde_json::from_value(serde_json::Value::String(value.clone()))
+        .map_err(|_| RepositoryError::Decode { column, value })
+}
+
+/// One bound array column of a batch: `value` of every row.
+fn column<T, U>(rows: &[T], value: impl Fn(&T) -> U) -> Vec<U> {
+    rows.iter().map(value).collect()
+}
+
+/// Saturates `value` into a `BIGINT` column.
+fn to_i64(value: impl TryInto<i64>) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
+/// Reads an unsigned value from a `BIGINT` column, clamping negatives to 0.
+fn to_u64(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerAccount;
+    use crate::models::{OrderSide, OrderTimeInForce};
+
+    pub(super) fn order(
+        order_id: &str,
+        status: OrderStatus,
+        filled: u64,
+        updated_at_ms: u64,
+    ) -> OrderInfo {
+        OrderInfo {
+            order_id: order_id.to_string(),
+            account: "alice".to_string(),
+            symbol: "BTC-20251231-100000-C".to_string(),
+            underlying: "BTC".to_string(),
+            expiration: "20251231".to_string(),
+            strike: 100_000,
+            style: "call".to_string(),
+            side: OrderSide::Buy,
+            price: 500,
+            original_quantity: 10,
+            remaining_quantity: 10 - filled,
+            filled_quantity: filled,
+            status,
+            time_in_force: OrderTimeInForce::Gtc,
+            created_at_ms: 1_000,
+            updated_at_ms,
+            fills: Vec::new(),
+        }
+    }
+
+    fn price(symbol: &str, price_cents: i64, timestamp: DateTime<Utc>) -> PriceRecord {
+        PriceRecord {
+            symbol: symbol.to_string(),
+            price_cents,
+            bid_cents: None,
+            ask_cents: None,
+            volume: None,
+            timestamp,
+            source: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_save_orders_appends_events_only_on_change() {
+        check_save_orders_appends_events_only_on_change(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_save_orders_appends_events_only_on_change(
+        repository: &dyn Repository,
+    ) {
+        let events = repository
+            .save_orders(&[order("o1", OrderStatus::Active, 0, 1_000)])
+            .await
+            .expect("saved");
+        assert_eq!(events, 1);
+        // Resaving an unchanged order records nothing new.
+        let events = repository
+            .save_orders(&[order("o1", OrderStatus::Active, 0, 1_000)])
+            .await
+            .expect("saved");
+        assert_eq!(events, 0);
+        let events = repository
+            .save_orders(&[order("o1", OrderStatus::Partial, 4, 2_000)])
+            .await
+            .expect("saved");
+        assert_eq!(events, 1);
+
+        let orders = repository.load_orders("alice").await.expect("loaded");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].filled_quantity, 4);
+        // Saving an order creates its account.
+        let accounts = repository.load_accounts().await.expect("loaded");
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].account_id, "alice");
+        assert!(accounts[0].collateral.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_cancel_untracked_orders_closes_open_orders_once() {
+        check_cancel_untracked_orders_closes_open_orders_once(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_cancel_untracked_orders_closes_open_orders_once(
+        repository: &dyn Repository,
+    ) {
+        repository
+            .save_orders(&[
+                order("o1", OrderStatus::Active, 0, 1_000),
+                order("o2", OrderStatus::Partial, 4, 1_000),
+                order("o3", OrderStatus::Filled, 10, 1_000),
+            ])
+            .await
+            .expect("saved");
+
+        let closed = repository
+            .cancel_untracked_orders(&["o1".to_string()], 5_000)
+            .await
+            .expect("canceled");
+        assert_eq!(closed, 1);
+        let closed = repository
+            .cancel_untracked_orders(&["o1".to_string()], 6_000)
+            .await
+            .expect("canceled");
+        assert_eq!(closed, 0);
+
+        let orders = repository.load_orders("alice").await.expect("loaded");
+        let statuses: Vec<_> = orders
+            .iter()
+            .map(|o| (o.order_id.as_str(), o.status, o.updated_at_ms))
+            .collect();
+        assert!(statuses.contains(&("o1", OrderStatus::Active, 1_000)));
+        assert!(statuses.contains(&("o2", OrderStatus::Canceled, 5_000)));
+        assert!(statuses.contains(&("o3", OrderStatus::Filled, 1_000)));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_controls_round_trip() {
+        check_controls_round_trip(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_controls_round_trip(repository: &dyn Repository) {
+        let mut controls = ControlsRecord {
+            master_enabled: false,
+            spread_multiplier: 1.5,
+            size_scalar: 0.5,
+            directional_skew: -0.2,
+            symbol_enabled: BTreeMap::from([("BTC".to_string(), false), ("ETH".to_string(), true)]),
+        };
+        repository.save_controls(&controls).await.expect("saved");
+        assert_eq!(
+            repository.load_controls().await.expect("loaded"),
+            Some(controls.clone())
+        );
+
+        controls.master_enabled = true;
+        controls.spread_multiplier = 2.0;
+        controls.symbol_enabled.insert("BTC".to_string(), true);
+        repository.save_controls(&controls).await.expect("saved");
+        assert_eq!(
+            repository.load_controls().await.expect("loaded"),
+            Some(controls)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_controls_are_restored_after_a_restart() {
+        let state = crate::state::AppState::new();
+        state.market_maker.set_enabled(false);
+        state.market_maker.set_spread_multiplier(2.0);
+        state.market_maker.set_symbol_enabled("BTC", false);
+        save_changes(&state, 0, 1_000).await.expect("saved");
+
+        let mut restarted = crate::state::AppState::new();
+        assert!(!restore_controls(&restarted).await.expect("restored"));
+        restarted.repository = std::sync::Arc::clone(&state.repository);
+        assert!(restore_controls(&restarted).await.expect("restored"));
+        let config = restarted.market_maker.get_config();
+        assert!(!config.enabled);
+        assert_eq!(config.spread_multiplier, 2.0);
+        assert!(!restarted.market_maker.is_symbol_enabled("BTC"));
+        assert!(restarted.market_maker.is_symbol_enabled("ETH"));
+    }
+
+    #[tokio::test]
+    async fn test_load_state_reads_back_what_was_saved() {
+        let state = crate::state::AppState::new();
+        let repository = state.repository.as_ref();
+        repository
+            .save_orders(&[
+                order("open", OrderStatus::Active, 0, 1_000),
+                order("done", OrderStatus::Filled, 10, 2_000),
+            ])
+            .await
+            .expect("saved");
+        repository
+            .save_accounts(&[AccountRecord {
+                account_id: "alice".to_string(),
+                collateral: Some(50_000),
+                cash_cents: 0,
+                created_at_ms: 1_000,
+                updated_at_ms: 1_000,
+            }])
+            .await
+            .expect("saved");
+        let position = PositionInfo {
+            symbol: "BTC-20251231-100000-C".to_string(),
+            underlying: "BTC".to_string(),
+            quantity: 10,
+            average_price: 500,
+            realized_pnl: 0,
+            multiplier: 1,
+            created_at_ms: 2_000,
+            updated_at_ms: 2_000,
+        };
+        repository
+            .save_positions(&[("alice".to_string(), position)])
+            .await
+            .expect("saved");
+        let fill = ExecutionInfo {
+            execution_id: "t1".to_string(),
+            order_id: "done".to_string(),
+            symbol: "BTC-20251231-100000-C".to_string(),
+            side: OrderSide::Buy,
+            price: 500,
+            quantity: 10,
+            timestamp_ms: 2_000,
+            counterparty_order_id: Some("maker".to_string()),
+            is_maker: false,
+            fee: 0,
+            edge: None,
+        };
+        repository.save_fills(&[fill]).await.expect("saved");
+        repository
+            .save_last_trades(&[LastTradeInfo {
+                symbol: "BTC-20251231-100000-C".to_string(),
+                price: 500,
+                quantity: 10,
+                side: OrderSide::Buy,
+                timestamp_ms: 2_000,
+                trade_id: "t1".to_string(),
+            }])
+            .await
+            .expect("saved");
+
+        repository
+            .save_ledger_entries(&ledger_entries())
+            .await
+            .expect("saved");
+
+        let summary = load_state(&state).await.expect("loaded");
+        assert_eq!(
+            summary,
+            LoadSummary {
+                accounts: 1,
+                orders: 1,
+                fills: 1,
+                positions: 1,
+                last_trades: 1,
+                ledger_entries: 2,
+            }
+        );
+        assert_eq!(state.ledger.cash_balance("alice"), 9_500);
+        assert_eq!(state.ledger.trial_balance(), 0);
+        // The open order's book is gone, so only the filled one comes back.
+        assert!(state.orders.get("open").is_none());
+        let done = state.orders.get("done").expect("loaded");
+        assert_eq!(done.fills.len(), 1);
+        assert!(state.executions.contains_key("t1"));
+        assert_eq!(state.collateral.get("alice").map(|c| *c), Some(50_000));
+        let quantity = state
+            .account_positions
+            .get("alice")
+            .and_then(|book| book.get("BTC-20251231-100000-C").map(|p| p.quantity));
+        assert_eq!(quantity, Some(10));
+        assert!(state.last_trades.contains_key("BTC-20251231-100000-C"));
+    }
+
+    pub(super) fn ledger_entries() -> Vec<LedgerEntry> {
+        let ledger = crate::ledger::Ledger::new();
+        let cash = |account: &str| LedgerAccount::Cash(account.to_string());
+        ledger
+            .post(
+                EntryKind::Deposit,
+                "wire",
+                vec![
+                    Posting::new(cash("alice"), 10_000),
+                    Posting::new(LedgerAccount::External, -10_000),
+                ],
+                1_000,
+            )
+            .expect("balanced");
+        ledger
+            .post(
+                EntryKind::Trade,
+                "t1",
+                vec![
+                    Posting::new(cash("alice"), -500),
+                    Posting::new(LedgerAccount::Clearing, 495),
+                    Posting::new(LedgerAccount::Fees, 5),
+                ],
+                2_000,
+            )
+            .expect("balanced");
+        ledger.entries()
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_ledger_entries_round_trip_once() {
+        check_ledger_entries_round_trip_once(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_ledger_entries_round_trip_once(repository: &dyn Repository) {
+        let entries = ledger_entries();
+        assert_eq!(
+            repository
+                .save_ledger_entries(&entries[..1])
+                .await
+                .expect("saved"),
+            1
+        );
+        // Resaving an overlapping batch writes only the new entry.
+        assert_eq!(
+            repository
+                .save_ledger_entries(&entries)
+                .await
+                .expect("saved"),
+            1
+        );
+        assert_eq!(
+            repository.load_ledger_entries().await.expect("loaded"),
+            entries
+        );
+
+        repository
+            .save_accounts(&[AccountRecord {
+                account_id: "alice".to_string(),
+                collateral: None,
+                cash_cents: 9_500,
+                created_at_ms: 2_000,
+                updated_at_ms: 2_000,
+            }])
+            .await
+            .expect("saved");
+        let accounts = repository.load_accounts().await.expect("loaded");
+        assert_eq!(accounts[0].cash_cents, 9_500);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_prices_keep_the_last_close_per_day() {
+        check_prices_keep_the_last_close_per_day(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_prices_keep_the_last_close_per_day(repository: &dyn Repository) {
+        let now = Utc::now();
+        let yesterday = now - chrono::Duration::days(1);
+        let long_ago = now - chrono::Duration::days(40);
+        repository
+            .insert_prices(&[
+                price("BTC", 100, long_ago),
+                price("BTC", 200, yesterday - chrono::Duration::minutes(1)),
+                price("BTC", 210, yesterday),
+                price("BTC", 300, now),
+                price("ETH", 50, now),
+            ])
+            .await
+            .expect("inserted");
+
+        let latest = repository.latest_price("BTC").await.expect("loaded");
+        assert_eq!(latest.map(|p| p.price_cents), Some(300));
+        assert!(
+            repository
+                .latest_price("SOL")
+                .await
+                .expect("loaded")
+                .is_none()
+        );
+
+        let closes = repository
+            .daily_closes(&["BTC".to_string()], 30)
+            .await
+            .expect("loaded");
+        let prices: Vec<i64> = closes.iter().map(|c| c.price_cents).collect();
+        assert_eq!(prices, vec![210, 300]);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_price_history_buckets_and_limits() {
+        check_price_history_buckets_and_limits(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_price_history_buckets_and_limits(repository: &dyn Repository) {
+        // Minute-aligned
+        let t0 = DateTime::from_timestamp(1_700_000_040, 0).expect("valid");
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let with_volume = |mut price: PriceRecord, volume: i64| {
+            price.volume = Some(volume);
+            price
+        };
+        repository
+            .insert_prices(&[
+                price("BTC", 99, at(-1)),
+                with_volume(price("BTC", 100, at(0)), 1),
+                price("BTC", 120, at(30)),
+                with_volume(price("BTC", 90, at(59)), 2),
+                price("BTC", 110, at(60)),
+                price("BTC", 115, at(61)),
+                with_volume(price("BTC", 105, at(200)), 5),
+                price("BTC", 107, at(200)),
+                price("ETH", 1, at(0)),
+            ])
+            .await
+            .expect("inserted");
+
+        let history = |range: PriceHistoryRange| async move {
+            repository
+                .price_history("BTC", &range)
+                .await
+                .expect("loaded")
+        };
+        let minutes = PriceHistoryRange {
+            bucket_secs: 60,
+            limit: 10,
+            ..PriceHistoryRange::default()
+        };
+        let bar = |start: i64, ohlc: [i64; 4], volume: Option<i64>, samples: u64| PriceBar {
+            start: at(start),
+            open_cents: ohlc[0],
+            high_cents: ohlc[1],
+            low_cents: ohlc[2],
+            close_cents: ohlc[3],
+            volume,
+            samples,
+        };
+        assert_eq!(
+            history(minutes.clone()).await,
+            vec![
+                bar(-60, [99, 99, 99, 99], None, 1),
+                bar(0, [100, 120, 90, 90], Some(3), 3),
+                bar(60, [110, 115, 110, 115], None, 2),
+                bar(180, [105, 107, 105, 107], Some(5), 2),
+            ]
+        );
+
+        // `from` and `to` are inclusive and cut prices, not bars.
+        let starts = |bars: Vec<PriceBar>| -> Vec<i64> {
+            bars.iter()
+                .map(|bar| (bar.start - t0).num_seconds())
+                .collect()
+        };
+        let bounded = history(PriceHistoryRange {
+            from: Some(at(30)),
+            to: Some(at(60)),
+            ..minutes.clone()
+        })
+        .await;
+        assert_eq!(
+            bounded,
+            vec![
+                bar(0, [120, 120, 90, 90], Some(2), 2),
+                bar(60, [110, 110, 110, 110], None, 1),
+            ]
+        );
+
+        let limited = |oldest_first: bool| PriceHistoryRange {
+            limit: 2,
+            oldest_first,
+            ..minutes.clone()
+        };
+        assert_eq!(starts(history(limited(false)).await), vec![60, 180]);
+        assert_eq!(starts(history(limited(true)).await), vec![-60, 0]);
+
+        // Raw prices: one bar each, those observed at the same instant shared.
+        let raw = history(PriceHistoryRange {
+            from: Some(at(59)),
+            limit: 10,
+            ..PriceHistoryRange::default()
+        })
+        .await;
+        assert_eq!(
+            raw,
+            vec![
+                bar(59, [90, 90, 90, 90], Some(2), 1),
+                bar(60, [110, 110, 110, 110], None, 1),
+                bar(61, [115, 115, 115, 115], None, 1),
+                bar(200, [105, 107, 105, 107], Some(5), 2),
+            ]
+        );
+
+        assert!(
+            repository
+                .price_history("SOL", &minutes)
+                .await
+                .expect("loaded")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_last_trades_and_fills_are_idempotent() {
+        check_last_trades_and_fills_are_idempotent(&InMemoryRepository::new()).await;
+    }
+
+    pub(super) async fn check_last_trades_and_fills_are_idempotent(repository: &dyn Repository) {
+        let trade = |trade_id: &str, price: u64, timestamp_ms: u64| LastTradeInfo {
+            symbol: "BTC-20251231-100000-C".to_string(),
+            price,
+            quantity: 1,
+            side: OrderSide::Buy,
+            timestamp_ms,
+            trade_id: trade_id.to_string(),
+        };
+        repository
+            .save_last_trades(&[trade("t2", 510, 2_000)])
+            .await
+            .expect("saved");
+        // An older trade never replaces a newer one.
+        repository
+            .save_last_trades(&[trade("t1", 500, 1_000)])
+            .await
+            .expect("saved");
+        let trades = repository.load_last_trades().await.expect("loaded");
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].trade_id, "t2");
+
+        let fill = ExecutionInfo {
+            execution_id: "t2".to_string(),
+            order_id: "taker".to_string(),
+            symbol: "BTC-20251231-100000-C".to_string(),
+            side: OrderSide::Buy,
+            price: 510,
+            quantity: 1,
+            timestamp_ms: 2_000,
+            counterparty_order_id: Some("maker".to_string()),
+            is_maker: false,
+            fee: 0,
+            edge: None,
+        };
+        assert_eq!(
+            repository
+                .save_fills(std::slice::from_ref(&fill))
+                .await
+                .expect("saved"),
+            1
+        );
+        assert_eq!(repository.save_fills(&[fill]).await.expect("saved"), 0);
+        assert_eq!(
+            repository
+                .load_fills(&["maker".to_string()])
+                .await
+                .expect("loaded")
+                .len(),
+            1
+        );
+        assert!(
+            repository
+                .load_fills(&["other".to_string()])
+                .await
+                .expect("loaded")
+                .is_empty()
+        );
+    }
+}
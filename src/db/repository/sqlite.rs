@@ -0,0 +1,848 @@
+//! [`Repository`] on a SQLite database (`migrations/sqlite`).
+//!
+//! SQLite has no array binds, so batches are written one row per statement
+//! inside a transaction and id lists are bound as JSON arrays read back with
+//! `json_each`. The `LOAD_*` queries are plain SQL shared with the Postgres
+//! repository.
+
+use super::{
+    AccountRecord, AccountRow, ControlsRecord, DailyClose, FillRow, LOAD_ACCOUNTS,
+    LOAD_LAST_TRADES, LOAD_LEDGER, LOAD_ORDERS, LOAD_POSITIONS, LOAD_SYMBOL_CONTROLS,
+    LOAD_SYSTEM_CONTROL, LastTradeRow, LedgerEntry, LedgerRow, OrderRow, PositionRow, PriceBar,
+    PriceBarRow, PriceHistoryRange, PriceRecord, Repository, RepositoryError, RepositoryFuture,
+    SystemControlRow, account_from_row, controls_from_rows, fill_from_row, last_trade_from_row,
+    ledger_from_rows, ledger_postings, orders_from_rows, position_from_row, price_bar_from_row,
+    to_i64, underlying_of, wire_name,
+};
+use crate::models::{ExecutionInfo, LastTradeInfo, OrderInfo, PositionInfo};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use futures::future::FutureExt;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const INSERT_PRICE: &str = r#"
+    INSERT INTO underlying_prices (symbol, price_cents, bid_cents, ask_cents, volume, timestamp_ms, source)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)
+"#;
+
+const LATEST_PRICE: &str = r#"
+    SELECT symbol, price_cents, bid_cents, ask_cents, volume, timestamp_ms, source
+    FROM underlying_prices
+    WHERE symbol = $1
+    ORDER BY timestamp_ms DESC, id DESC
+    LIMIT 1
+"#;
+
+const DAILY_CLOSES: &str = r#"
+    SELECT symbol, day, price_cents FROM (
+        SELECT symbol, date(timestamp_ms / 1000, 'unixepoch') AS day, price_cents,
+            ROW_NUMBER() OVER (
+                PARTITION BY symbol, date(timestamp_ms / 1000, 'unixepoch')
+                ORDER BY timestamp_ms DESC, id DESC
+            ) AS latest
+        FROM underlying_prices
+        WHERE symbol IN (SELECT value FROM json_each($1)) AND timestamp_ms >= $2
+    )
+    WHERE latest = 1
+    ORDER BY symbol, day
+"#;
+
+/// The prices of `$1` between `$2` and `$3` milliseconds, grouped into bars
+/// of `$4` milliseconds (0 keeps every price). `$5` keeps the oldest `$6`
+/// bars instead of the newest.
+const PRICE_HISTORY: &str = r#"
+    SELECT * FROM (
+        SELECT bucket_ms,
+            max(CASE WHEN first = 1 THEN price_cents END) AS open_cents,
+            max(price_cents) AS high_cents,
+            min(price_cents) AS low_cents,
+            max(CASE WHEN last = 1 THEN price_cents END) AS close_cents,
+            sum(volume) AS volume,
+            count(*) AS samples
+        FROM (
+            SELECT bucket_ms, price_cents, volume,
+                ROW_NUMBER() OVER (PARTITION BY bucket_ms ORDER BY timestamp_ms, id) AS first,
+                ROW_NUMBER() OVER (
+                    PARTITION BY bucket_ms ORDER BY timestamp_ms DESC, id DESC
+                ) AS last
+            FROM (
+                SELECT id, price_cents, volume, timestamp_ms,
+                    CASE WHEN $4 = 0 THEN timestamp_ms
+                         ELSE timestamp_ms - ((timestamp_ms % $4) + $4) % $4
+                    END AS bucket_ms
+                FROM underlying_prices
+                WHERE symbol = $1
+                    AND ($2 IS NULL OR timestamp_ms >= $2)
+                    AND ($3 IS NULL OR timestamp_ms <= $3)
+            )
+        )
+        GROUP BY bucket_ms
+        ORDER BY CASE WHEN $5 THEN bucket_ms END ASC, bucket_ms DESC
+        LIMIT $6
+    )
+    ORDER BY bucket_ms
+"#;
+
+const UPSERT_ACCOUNT: &str = r#"
+    INSERT INTO accounts (account_id, collateral_cents, cash_cents, created_at_ms, updated_at_ms)
+    VALUES ($1, $2, $3, $4, $5)
+    ON CONFLICT (account_id) DO UPDATE SET
+        collateral_cents = excluded.collateral_cents,
+        cash_cents = excluded.cash_cents,
+        updated_at_ms = excluded.updated_at_ms
+"#;
+
+/// Creates account `$1` if it does not exist yet, stamped `$2`.
+const ENSURE_ACCOUNT: &str = r#"
+    INSERT INTO accounts (account_id, created_at_ms, updated_at_ms)
+    VALUES ($1, $2, $2)
+    ON CONFLICT (account_id) DO NOTHING
+"#;
+
+/// Whether order `$1` is new or stored with another status or quantities.
+const ORDER_CHANGED: &str = r#"
+    SELECT NOT EXISTS (
+        SELECT 1 FROM orders
+        WHERE order_id = $1 AND status = $2 AND filled_quantity = $3
+            AND remaining_quantity = $4
+    )
+"#;
+
+const UPSERT_ORDER: &str = r#"
+    INSERT INTO orders (
+        order_id, account_id, symbol, underlying, expiration, strike, style, side,
+        price_cents, original_quantity, remaining_quantity, filled_quantity, status,
+        time_in_force, created_at_ms, updated_at_ms
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+    ON CONFLICT (order_id) DO UPDATE SET
+        price_cents = excluded.price_cents,
+        original_quantity = excluded.original_quantity,
+        remaining_quantity = excluded.remaining_quantity,
+        filled_quantity = excluded.filled_quantity,
+        status = excluded.status,
+        updated_at_ms = excluded.updated_at_ms
+"#;
+
+const INSERT_ORDER_EVENT: &str = r#"
+    INSERT INTO order_events (order_id, status, filled_quantity, remaining_quantity, recorded_at_ms)
+    VALUES ($1, $2, $3, $4, $5)
+"#;
+
+/// Appends a `canceled` event, stamped `$2`, for every open order not in
+/// the JSON array `$1`; run before [`CANCEL_UNTRACKED_ORDERS`].
+const LOG_UNTRACKED_ORDERS: &str = r#"
+    INSERT INTO order_events (order_id, status, filled_quantity, remaining_quantity, recorded_at_ms)
+    SELECT order_id, 'canceled', filled_quantity, remaining_quantity, $2
+    FROM orders
+    WHERE status IN ('pending', 'active', 'partial')
+        AND order_id NOT IN (SELECT value FROM json_each($1))
+"#;
+
+const CANCEL_UNTRACKED_ORDERS: &str = r#"
+    UPDATE orders SET status = 'canceled', updated_at_ms = $2
+    WHERE status IN ('pending', 'active', 'partial')
+        AND order_id NOT IN (SELECT value FROM json_each($1))
+"#;
+
+const INSERT_FILL: &str = r#"
+    INSERT INTO executions (
+        trade_id, order_id, counterparty_order_id, symbol, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker, executed_at_ms
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+    ON CONFLICT (trade_id) DO NOTHING
+"#;
+
+/// The fills any of the orders in the JSON array `$1` took part in, as
+/// taker or maker.
+const LOAD_FILLS: &str = r#"
+    SELECT trade_id, order_id, counterparty_order_id, instrument, side, price_cents,
+        quantity, fee_cents, edge_cents, is_maker, executed_at_ms
+    FROM executions
+    WHERE trade_id IS NOT NULL
+        AND (order_id IN (SELECT value FROM json_each($1))
+            OR counterparty_order_id IN (SELECT value FROM json_each($1)))
+    ORDER BY executed_at_ms, trade_id
+"#;
+
+const UPSERT_POSITION: &str = r#"
+    INSERT INTO positions (
+        account_id, symbol, underlying, quantity, average_price_cents,
+        realized_pnl_cents, multiplier, created_at_ms, updated_at_ms
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    ON CONFLICT (account_id, symbol) DO UPDATE SET
+        quantity = excluded.quantity,
+        average_price_cents = excluded.average_price_cents,
+        realized_pnl_cents = excluded.realized_pnl_cents,
+        multiplier = excluded.multiplier,
+        updated_at_ms = excluded.updated_at_ms
+"#;
+
+const UPSERT_LAST_TRADE: &str = r#"
+    INSERT INTO last_trades (symbol, trade_id, price_cents, quantity, side, traded_at_ms)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (symbol) DO UPDATE SET
+        trade_id = excluded.trade_id,
+        price_cents = excluded.price_cents,
+        quantity = excluded.quantity,
+        side = excluded.side,
+        traded_at_ms = excluded.traded_at_ms
+    WHERE last_trades.traded_at_ms <= excluded.traded_at_ms
+"#;
+
+const INSERT_LEDGER_ENTRY: &str = r#"
+    INSERT INTO ledger_entries (entry_id, kind, reference, recorded_at_ms)
+    VALUES ($1, $2, $3, $4)
+    ON CONFLICT (entry_id) DO NOTHING
+"#;
+
+const INSERT_LEDGER_POSTING: &str = r#"
+    INSERT INTO ledger_postings (entry_id, leg, ledger_account, amount_cents)
+    VALUES ($1, $2, $3, $4)
+    ON CONFLICT (entry_id, leg) DO NOTHING
+"#;
+
+const UPSERT_SYSTEM_CONTROL: &str = r#"
+    INSERT INTO system_control (
+        id, master_enabled, global_spread_multiplier, global_size_scalar, global_directional_skew
+    )
+    VALUES (1, $1, $2, $3, $4)
+    ON CONFLICT (id) DO UPDATE SET
+        master_enabled = excluded.master_enabled,
+        global_spread_multiplier = excluded.global_spread_multiplier,
+        global_size_scalar = excluded.global_size_scalar,
+        global_directional_skew = excluded.global_directional_skew,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE (
+        system_control.master_enabled, system_control.global_spread_multiplier,
+        system_control.global_size_scalar, system_control.global_directional_skew
+    ) IS NOT (
+        excluded.master_enabled, excluded.global_spread_multiplier,
+        excluded.global_size_scalar, excluded.global_directional_skew
+    )
+"#;
+
+const UPSERT_SYMBOL_CONTROL: &str = r#"
+    INSERT INTO market_maker_configs (symbol, quoting_enabled)
+    VALUES ($1, $2)
+    ON CONFLICT (symbol) DO UPDATE SET
+        quoting_enabled = excluded.quoting_enabled,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE market_maker_configs.quoting_enabled <> excluded.quoting_enabled
+"#;
+
+type PriceRow = (
+    String,
+    i64,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    i64,
+    Option<String>,
+);
+
+/// Repository backed by the SQLite tables.
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    /// Creates a repository reading and writing `pool`.
+    #[must_use]
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    async fn fills_of(&self, order_ids: &[String]) -> Result<Vec<ExecutionInfo>, RepositoryError> {
+        let rows: Vec<FillRow> = sqlx::query_as(LOAD_FILLS)
+            .bind(json_array(order_ids))
+            .fetch_all(&self.pool)
+            .await?;
+        rows.into_iter().map(fill_from_row).collect()
+    }
+}
+
+impl Repository for SqliteRepository {
+    fn insert_prices<'a>(&'a self, prices: &'a [PriceRecord]) -> RepositoryFuture<'a, ()> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            for price in prices {
+                sqlx::query(INSERT_PRICE)
+                    .bind(&price.symbol)
+                    .bind(price.price_cents)
+                    .bind(price.bid_cents)
+                    .bind(price.ask_cents)
+                    .bind(price.volume)
+                    .bind(price.timestamp.timestamp_millis())
+                    .bind(&price.source)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn latest_price<'a>(&'a self, symbol: &'a str) -> RepositoryFuture<'a, Option<PriceRecord>> {
+        async move {
+            let row: Option<PriceRow> = sqlx::query_as(LATEST_PRICE)
+                .bind(symbol)
+                .fetch_optional(&self.pool)
+                .await?;
+            row.map(
+                |(symbol, price_cents, bid_cents, ask_cents, volume, timestamp_ms, source)| {
+                    Ok(PriceRecord {
+                        symbol,
+                        price_cents,
+                        bid_cents,
+                        ask_cents,
+                        volume,
+                        timestamp: DateTime::from_timestamp_millis(timestamp_ms).ok_or(
+                            RepositoryError::Decode {
+                                column: "underlying_prices.timestamp_ms",
+                                value: timestamp_ms.to_string(),
+                            },
+                        )?,
+                        source,
+                    })
+                },
+            )
+            .transpose()
+        }
+        .boxed()
+    }
+
+    fn daily_closes<'a>(
+        &'a self,
+        symbols: &'a [String],
+        lookback_days: u32,
+    ) -> RepositoryFuture<'a, Vec<DailyClose>> {
+        async move {
+            let since = Utc::now()
+                .checked_sub_days(Days::new(lookback_days.into()))
+                .map_or(0, |since| since.timestamp_millis());
+            let rows: Vec<(String, NaiveDate, i64)> = sqlx::query_as(DAILY_CLOSES)
+                .bind(json_array(symbols))
+                .bind(since)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows
+                .into_iter()
+                .map(|(symbol, day, price_cents)| DailyClose {
+                    symbol,
+                    day,
+                    price_cents,
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn price_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        range: &'a PriceHistoryRange,
+    ) -> RepositoryFuture<'a, Vec<PriceBar>> {
+        async move {
+            let rows: Vec<PriceBarRow<i64>> = sqlx::query_as(PRICE_HISTORY)
+                .bind(symbol)
+                .bind(range.from.map(|from| from.timestamp_millis()))
+                .bind(range.to.map(|to| to.timestamp_millis()))
+                .bind(to_i64(range.bucket_secs.saturating_mul(1_000)))
+                .bind(range.oldest_first)
+                .bind(to_i64(range.limit))
+                .fetch_all(&self.pool)
+                .await?;
+            rows.into_iter()
+                .map(|(start_ms, open, high, low, close, volume, samples)| {
+                    let start = DateTime::from_timestamp_millis(start_ms).ok_or(
+                        RepositoryError::Decode {
+                            column: "underlying_prices.timestamp_ms",
+                            value: start_ms.to_string(),
+                        },
+                    )?;
+                    Ok(price_bar_from_row((
+                        start, open, high, low, close, volume, samples,
+                    )))
+                })
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn save_accounts<'a>(&'a self, accounts: &'a [AccountRecord]) -> RepositoryFuture<'a, ()> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            for account in accounts {
+                sqlx::query(UPSERT_ACCOUNT)
+                    .bind(&account.account_id)
+                    .bind(account.collateral.map(to_i64))
+                    .bind(account.cash_cents)
+                    .bind(to_i64(account.created_at_ms))
+                    .bind(to_i64(account.updated_at_ms))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_accounts(&self) -> RepositoryFuture<'_, Vec<AccountRecord>> {
+        async move {
+            let rows: Vec<AccountRow> = sqlx::query_as(LOAD_ACCOUNTS).fetch_all(&self.pool).await?;
+            Ok(rows.into_iter().map(account_from_row).collect())
+        }
+        .boxed()
+    }
+
+    fn save_orders<'a>(&'a self, orders: &'a [OrderInfo]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let now_ms = orders.iter().map(|o| o.created_at_ms).min().unwrap_or(0);
+            let mut tx = self.pool.begin().await?;
+            let mut events = 0;
+            for order in orders {
+                ensure_account(&mut tx, &order.account, now_ms).await?;
+                let status = order.status.to_string();
+                let changed: bool = sqlx::query_scalar(ORDER_CHANGED)
+                    .bind(&order.order_id)
+                    .bind(&status)
+                    .bind(to_i64(order.filled_quantity))
+                    .bind(to_i64(order.remaining_quantity))
+                    .fetch_one(&mut *tx)
+                    .await?;
+                sqlx::query(UPSERT_ORDER)
+                    .bind(&order.order_id)
+                    .bind(&order.account)
+                    .bind(&order.symbol)
+                    .bind(&order.underlying)
+                    .bind(&order.expiration)
+                    .bind(to_i64(order.strike))
+                    .bind(&order.style)
+                    .bind(order.side.to_string())
+                    .bind(to_i64(order.price))
+                    .bind(to_i64(order.original_quantity))
+                    .bind(to_i64(order.remaining_quantity))
+                    .bind(to_i64(order.filled_quantity))
+                    .bind(&status)
+                    .bind(order.time_in_force.to_string())
+                    .bind(to_i64(order.created_at_ms))
+                    .bind(to_i64(order.updated_at_ms))
+                    .execute(&mut *tx)
+                    .await?;
+                if changed {
+                    sqlx::query(INSERT_ORDER_EVENT)
+                        .bind(&order.order_id)
+                        .bind(&status)
+                        .bind(to_i64(order.filled_quantity))
+                        .bind(to_i64(order.remaining_quantity))
+                        .bind(to_i64(order.updated_at_ms))
+                        .execute(&mut *tx)
+                        .await?;
+                    events += 1;
+                }
+            }
+            tx.commit().await?;
+            Ok(events)
+        }
+        .boxed()
+    }
+
+    fn cancel_untracked_orders<'a>(
+        &'a self,
+        tracked: &'a [String],
+        now_ms: u64,
+    ) -> RepositoryFuture<'a, u64> {
+        async move {
+            let tracked = json_array(tracked);
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(LOG_UNTRACKED_ORDERS)
+                .bind(&tracked)
+                .bind(to_i64(now_ms))
+                .execute(&mut *tx)
+                .await?;
+            let closed = sqlx::query(CANCEL_UNTRACKED_ORDERS)
+                .bind(&tracked)
+                .bind(to_i64(now_ms))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            tx.commit().await?;
+            Ok(closed)
+        }
+        .boxed()
+    }
+
+    fn load_orders<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<OrderInfo>> {
+        async move {
+            let rows: Vec<OrderRow> = sqlx::query_as(LOAD_ORDERS)
+                .bind(account)
+                .fetch_all(&self.pool)
+                .await?;
+            let order_ids: Vec<String> = rows.iter().map(|row| row.order_id.clone()).collect();
+            let fills = self.fills_of(&order_ids).await?;
+            orders_from_rows(rows, fills)
+        }
+        .boxed()
+    }
+
+    fn save_fills<'a>(&'a self, fills: &'a [ExecutionInfo]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            let mut written = 0;
+            for fill in fills {
+                written += sqlx::query(INSERT_FILL)
+                    .bind(&fill.execution_id)
+                    .bind(&fill.order_id)
+                    .bind(&fill.counterparty_order_id)
+                    .bind(underlying_of(&fill.symbol))
+                    .bind(&fill.symbol)
+                    .bind(fill.side.to_string())
+                    .bind(to_i64(fill.price))
+                    .bind(to_i64(fill.quantity))
+                    .bind(to_i64(fill.fee))
+                    .bind(fill.edge)
+                    .bind(fill.is_maker)
+                    .bind(to_i64(fill.timestamp_ms))
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+            tx.commit().await?;
+            Ok(written)
+        }
+        .boxed()
+    }
+
+    fn load_fills<'a>(
+        &'a self,
+        order_ids: &'a [String],
+    ) -> RepositoryFuture<'a, Vec<ExecutionInfo>> {
+        async move { self.fills_of(order_ids).await }.boxed()
+    }
+
+    fn save_positions<'a>(
+        &'a self,
+        positions: &'a [(String, PositionInfo)],
+    ) -> RepositoryFuture<'a, ()> {
+        async move {
+            let now_ms = positions
+                .iter()
+                .map(|(_, p)| p.created_at_ms)
+                .min()
+                .unwrap_or(0);
+            let mut tx = self.pool.begin().await?;
+            for (account, position) in positions {
+                ensure_account(&mut tx, account, now_ms).await?;
+                sqlx::query(UPSERT_POSITION)
+                    .bind(account)
+                    .bind(&position.symbol)
+                    .bind(&position.underlying)
+                    .bind(position.quantity)
+                    .bind(to_i64(position.average_price))
+                    .bind(position.realized_pnl)
+                    .bind(to_i64(position.multiplier))
+                    .bind(to_i64(position.created_at_ms))
+                    .bind(to_i64(position.updated_at_ms))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_positions<'a>(&'a self, account: &'a str) -> RepositoryFuture<'a, Vec<PositionInfo>> {
+        async move {
+            let rows: Vec<PositionRow> = sqlx::query_as(LOAD_POSITIONS)
+                .bind(account)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows.into_iter().map(position_from_row).collect())
+        }
+        .boxed()
+    }
+
+    fn save_last_trades<'a>(&'a self, trades: &'a [LastTradeInfo]) -> RepositoryFuture<'a, ()> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            for trade in trades {
+                sqlx::query(UPSERT_LAST_TRADE)
+                    .bind(&trade.symbol)
+                    .bind(&trade.trade_id)
+                    .bind(to_i64(trade.price))
+                    .bind(to_i64(trade.quantity))
+                    .bind(trade.side.to_string())
+                    .bind(to_i64(trade.timestamp_ms))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_last_trades(&self) -> RepositoryFuture<'_, Vec<LastTradeInfo>> {
+        async move {
+            let rows: Vec<LastTradeRow> = sqlx::query_as(LOAD_LAST_TRADES)
+                .fetch_all(&self.pool)
+                .await?;
+            rows.into_iter().map(last_trade_from_row).collect()
+        }
+        .boxed()
+    }
+
+    fn save_ledger_entries<'a>(&'a self, entries: &'a [LedgerEntry]) -> RepositoryFuture<'a, u64> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            let mut written = 0;
+            for entry in entries {
+                written += sqlx::query(INSERT_LEDGER_ENTRY)
+                    .bind(to_i64(entry.entry_id))
+                    .bind(wire_name(&entry.kind))
+                    .bind(&entry.reference)
+                    .bind(to_i64(entry.timestamp_ms))
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+            for (entry_id, leg, posting) in ledger_postings(entries) {
+                sqlx::query(INSERT_LEDGER_POSTING)
+                    .bind(to_i64(entry_id))
+                    .bind(leg)
+                    .bind(posting.account.to_string())
+                    .bind(posting.amount_cents)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(written)
+        }
+        .boxed()
+    }
+
+    fn load_ledger_entries(&self) -> RepositoryFuture<'_, Vec<LedgerEntry>> {
+        async move {
+            let rows: Vec<LedgerRow> = sqlx::query_as(LOAD_LEDGER).fetch_all(&self.pool).await?;
+            ledger_from_rows(rows)
+        }
+        .boxed()
+    }
+
+    fn save_controls<'a>(&'a self, controls: &'a ControlsRecord) -> RepositoryFuture<'a, ()> {
+        async move {
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(UPSERT_SYSTEM_CONTROL)
+                .bind(controls.master_enabled)
+                .bind(controls.spread_multiplier)
+                .bind(controls.size_scalar)
+                .bind(controls.directional_skew)
+                .execute(&mut *tx)
+                .await?;
+            for (symbol, enabled) in &controls.symbol_enabled {
+                sqlx::query(UPSERT_SYMBOL_CONTROL)
+                    .bind(symbol)
+                    .bind(enabled)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn load_controls(&self) -> RepositoryFuture<'_, Option<ControlsRecord>> {
+        async move {
+            let row: Option<SystemControlRow> = sqlx::query_as(LOAD_SYSTEM_CONTROL)
+                .fetch_optional(&self.pool)
+                .await?;
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let symbols: Vec<(String, bool)> = sqlx::query_as(LOAD_SYMBOL_CONTROLS)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(Some(controls_from_rows(row, symbols)))
+        }
+        .boxed()
+    }
+}
+
+async fn ensure_account(
+    conn: &mut SqliteConnection,
+    account: &str,
+    now_ms: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(ENSURE_ACCOUNT)
+        .bind(account)
+        .bind(to_i64(now_ms))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Binds `values` as a JSON array for `json_each`.
+fn json_array(values: &[String]) -> String {
+    serde_json::Value::from(values).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{
+        check_cancel_untracked_orders_closes_open_orders_once, check_controls_round_trip,
+        check_last_trades_and_fills_are_idempotent, check_ledger_entries_round_trip_once,
+        check_price_history_buckets_and_limits, check_prices_keep_the_last_close_per_day,
+        check_save_orders_appends_events_only_on_change, order,
+    };
+    use super::*;
+    use crate::db::DatabasePool;
+    use crate::models::OrderStatus;
+
+    async fn repository() -> SqliteRepository {
+        let db = DatabasePool::new("sqlite::memory:")
+            .await
+            .expect("connected");
+        db.run_migrations().await.expect("migrated");
+        let DatabasePool::Sqlite(pool) = db else {
+            panic!("expected a SQLite pool");
+        };
+        SqliteRepository::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_save_orders_appends_events_only_on_change() {
+        check_save_orders_appends_events_only_on_change(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cancel_untracked_orders_closes_open_orders_once() {
+        check_cancel_untracked_orders_closes_open_orders_once(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_controls_round_trip() {
+        let repository = repository().await;
+        // The migration stores the defaults.
+        let defaults = repository.load_controls().await.expect("loaded");
+        assert_eq!(
+            defaults,
+            Some(ControlsRecord {
+                master_enabled: true,
+                spread_multiplier: 1.0,
+                size_scalar: 1.0,
+                directional_skew: 0.0,
+                symbol_enabled: Default::default(),
+            })
+        );
+        check_controls_round_trip(&repository).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_price_history_buckets_and_limits() {
+        check_price_history_buckets_and_limits(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_prices_keep_the_last_close_per_day() {
+        check_prices_keep_the_last_close_per_day(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_last_trades_and_fills_are_idempotent() {
+        check_last_trades_and_fills_are_idempotent(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_ledger_entries_round_trip_once() {
+        check_ledger_entries_round_trip_once(&repository().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_positions_and_order_fills_round_trip() {
+        let repository = repository().await;
+        let position = PositionInfo {
+            symbol: "BTC-20251231-100000-C".to_string(),
+            underlying: "BTC".to_string(),
+            quantity: -6,
+            average_price: 500,
+            realized_pnl: -120,
+            multiplier: 1,
+            created_at_ms: 1_000,
+            updated_at_ms: 2_000,
+        };
+        repository
+            .save_positions(&[("bob".to_string(), position)])
+            .await
+            .expect("saved");
+        let positions = repository.load_positions("bob").await.expect("loaded");
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, -6);
+        assert_eq!(positions[0].realized_pnl, -120);
+        assert_eq!(positions[0].updated_at_ms, 2_000);
+        // Saving a position creates its account.
+        let accounts = repository.load_accounts().await.expect("loaded");
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].account_id, "bob");
+
+        repository
+            .save_orders(&[order("o1", OrderStatus::Partial, 4, 2_000)])
+            .await
+            .expect("saved");
+        let fill = ExecutionInfo {
+            execution_id: "t1".to_string(),
+            order_id: "taker".to_string(),
+            symbol: "BTC-20251231-100000-C".to_string(),
+            side: crate::models::OrderSide::Sell,
+            price: 500,
+            quantity: 4,
+            timestamp_ms: 2_000,
+            counterparty_order_id: Some("o1".to_string()),
+            is_maker: false,
+            fee: 0,
+            edge: Some(-3),
+        };
+        repository.save_fills(&[fill]).await.expect("saved");
+        let orders = repository.load_orders("alice").await.expect("loaded");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].fills.len(), 1);
+        assert_eq!(orders[0].fills[0].quantity, 4);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_order_events_record_each_change() {
+        let repository = repository().await;
+        for order in [
+            order("o1", OrderStatus::Active, 0, 1_000),
+            order("o1", OrderStatus::Active, 0, 1_000),
+            order("o1", OrderStatus::Partial, 4, 2_000),
+        ] {
+            repository.save_orders(&[order]).await.expect("saved");
+        }
+        repository
+            .cancel_untracked_orders(&[], 5_000)
+            .await
+            .expect("canceled");
+
+        let events: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT status, filled_quantity, recorded_at_ms FROM order_events \
+             WHERE order_id = 'o1' ORDER BY id",
+        )
+        .fetch_all(&repository.pool)
+        .await
+        .expect("loaded");
+        assert_eq!(
+            events,
+            vec![
+                ("active".to_string(), 0, 1_000),
+                ("partial".to_string(), 4, 2_000),
+                ("canceled".to_string(), 4, 5_000),
+            ]
+        );
+    }
+}
@@ -78,6 +78,23 @@ pub enum ApiError {
     #[error("forbidden: {0}")]
     Forbidden(String),
 
+    /// The order would take the account's initial margin above its collateral.
+    #[error("insufficient margin: {0}")]
+    InsufficientMargin(String),
+
+    /// The account's available cash does not cover the request.
+    #[error("insufficient funds: {0}")]
+    InsufficientFunds(String),
+
+    /// The price is not on the instrument's tick grid.
+    #[error("invalid tick size: {0}")]
+    InvalidTickSize(String),
+
+    /// The quantity is not a whole number of lots or exceeds the maximum
+    /// order size.
+    #[error("invalid order size: {0}")]
+    InvalidOrderSize(String),
+
     /// Rate limit exceeded.
     #[error("rate limit exceeded")]
     RateLimitExceeded {
@@ -156,6 +173,26 @@ impl IntoResponse for ApiError {
                     ApiError::Forbidden(_) => {
                         (StatusCode::FORBIDDEN, "FORBIDDEN", self.to_string())
                     }
+                    ApiError::InsufficientMargin(_) => (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "INSUFFICIENT_MARGIN",
+                        self.to_string(),
+                    ),
+                    ApiError::InsufficientFunds(_) => (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "INSUFFICIENT_FUNDS",
+                        self.to_string(),
+                    ),
+                    ApiError::InvalidTickSize(_) => (
+                        StatusCode::BAD_REQUEST,
+                        "INVALID_TICK_SIZE",
+                        self.to_string(),
+                    ),
+                    ApiError::InvalidOrderSize(_) => (
+                        StatusCode::BAD_REQUEST,
+                        "INVALID_ORDER_SIZE",
+                        self.to_string(),
+                    ),
                     ApiError::Internal(_) => {
                         tracing::error!(
                             code = "INTERNAL_ERROR",
@@ -261,6 +261,68 @@ fn test_api_error_forbidden_into_response() {
     assert_eq!(response.status(), StatusCode::FORBIDDEN);
 }
 
+#[tokio::test]
+async fn test_api_error_insufficient_margin_into_response() {
+    let error = ApiError::InsufficientMargin("requires 120.00, collateral 100.00".to_string());
+    assert_eq!(
+        format!("{}", error),
+        "insufficient margin: requires 120.00, collateral 100.00"
+    );
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        body_to_string(response)
+            .await
+            .contains("INSUFFICIENT_MARGIN")
+    );
+}
+
+#[tokio::test]
+async fn test_api_error_insufficient_funds_into_response() {
+    let error = ApiError::InsufficientFunds("needs 25.05, available 10.00".to_string());
+    assert_eq!(
+        format!("{}", error),
+        "insufficient funds: needs 25.05, available 10.00"
+    );
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        body_to_string(response)
+            .await
+            .contains("INSUFFICIENT_FUNDS")
+    );
+}
+
+#[tokio::test]
+async fn test_api_error_invalid_tick_size_into_response() {
+    let error =
+        ApiError::InvalidTickSize("price 302 is not a multiple of the 5-cent tick".to_string());
+    assert_eq!(
+        format!("{}", error),
+        "invalid tick size: price 302 is not a multiple of the 5-cent tick"
+    );
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(body_to_string(response).await.contains("INVALID_TICK_SIZE"));
+}
+
+#[tokio::test]
+async fn test_api_error_invalid_order_size_into_response() {
+    let error =
+        ApiError::InvalidOrderSize("quantity 7 is not a multiple of the lot size 5".to_string());
+    assert_eq!(
+        format!("{}", error),
+        "invalid order size: quantity 7 is not a multiple of the lot size 5"
+    );
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(
+        body_to_string(response)
+            .await
+            .contains("INVALID_ORDER_SIZE")
+    );
+}
+
 #[test]
 fn test_api_error_unauthorized_display() {
     let error = ApiError::Unauthorized("missing token".to_string());
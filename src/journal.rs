@@ -0,0 +1,751 @@
+//! Write-ahead event journal.
+//!
+//! With a `[journal]` path configured, every state-changing input — each
+//! mutating API request (order entry, cancels, amends, bulk operations,
+//! control changes), each simulated underlying price, each settlement,
+//! liquidation or order cleanup sweep and each snapshot restore — is appended
+//! to the journal before it is applied. An entry carries a sequence number,
+//! the wall-clock time, the position of the
+//! [`OrderIdSource`](crate::order_ids::OrderIdSource) and the event itself.
+//!
+//! The file holds one entry per line: the CRC-32 of the JSON payload as
+//! eight hex digits, a space, then the payload. A final line cut short by a
+//! crash is dropped when the journal is opened; a bad line anywhere else is
+//! an error, never silently skipped.
+//!
+//! Appending and applying happen under one lock ([`Journal::lock`]), so the
+//! journal order is the order the inputs were applied in and a replay in
+//! that order rebuilds the same state. Writes and flushes run on blocking
+//! threads, and a request handler can release the lock through its
+//! [`JournalHold`] once its in-memory effect is applied.
+//!
+//! Reads stream the file and keep only the tail that is asked for. Once a
+//! durable snapshot is taken, [`Journal::compact`] drops the entries it
+//! already covers.
+
+use crate::auth::Claims;
+use crate::clock;
+use crate::config::{JournalConfig, JournalFsync};
+use crate::order_ids::OrderIdPosition;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::warn;
+
+/// One state-changing input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// A mutating API request, as the handler received it after
+    /// authentication. A body that is not UTF-8 is stored lossily; every
+    /// handler that reads a body rejects such input anyway.
+    Request {
+        /// HTTP method.
+        method: String,
+        /// Path and query string.
+        uri: String,
+        /// `Content-Type` header, which the JSON handlers check.
+        #[serde(default)]
+        content_type: Option<String>,
+        /// The caller's verified claims.
+        claims: Option<Claims>,
+        /// Request body.
+        body: String,
+    },
+    /// A simulated underlying price.
+    Price {
+        /// Underlying symbol.
+        symbol: String,
+        /// Price in cents.
+        price_cents: u64,
+    },
+    /// An expiry settlement sweep.
+    Settlement {
+        /// Sweep time in milliseconds since the Unix epoch.
+        now_ms: i64,
+    },
+    /// A liquidation sweep.
+    Liquidation,
+    /// A sweep dropping filled and canceled orders from the order index.
+    OrderCleanup {
+        /// Sweep time in milliseconds since the Unix epoch.
+        now_ms: i64,
+        /// Age in seconds past which a finished order is dropped.
+        max_age_secs: u64,
+    },
+    /// A restore of a durable snapshot through the admin API.
+    Restore {
+        /// Snapshot ID.
+        snapshot_id: String,
+    },
+}
+
+/// A sequenced journal entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Sequence number, starting at 1 and increasing by one per entry.
+    pub seq: u64,
+    /// Append time in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Order ID source position before the event was applied.
+    pub order_ids: OrderIdPosition,
+    /// The input.
+    pub event: JournalEvent,
+}
+
+/// Where an appended entry landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Appended {
+    /// Sequence number of the entry.
+    pub seq: u64,
+    /// Its append time, which is the time the event is applied at (see
+    /// [`crate::clock`]).
+    pub timestamp_ms: u64,
+}
+
+/// Errors from the journal.
+#[derive(Debug, Error)]
+pub enum JournalError {
+    /// The journal file could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// An entry could not be encoded or decoded.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// A line before the last one is damaged.
+    #[error("journal line {line} is corrupt")]
+    Corrupt {
+        /// One-based line number.
+        line: usize,
+    },
+    /// Sequence numbers are not consecutive.
+    #[error("journal line {line} has sequence {found}, expected {expected}")]
+    Sequence {
+        /// One-based line number.
+        line: usize,
+        /// Sequence number the line should carry.
+        expected: u64,
+        /// Sequence number it carries.
+        found: u64,
+    },
+}
+
+/// An append-only journal file.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    /// Orders appends with the application of their events.
+    order: Arc<Mutex<()>>,
+    /// The file itself, only touched on blocking threads.
+    writer: Arc<parking_lot::Mutex<JournalWriter>>,
+}
+
+#[derive(Debug)]
+struct JournalWriter {
+    file: File,
+    next_seq: u64,
+    fsync: JournalFsync,
+    fsync_interval: Duration,
+    last_sync: Instant,
+    unsynced: bool,
+}
+
+impl Journal {
+    /// Opens the journal at `path`, creating it if needed, and returns it
+    /// with the entries in it that follow `after_seq`. The file is streamed,
+    /// so only those entries are held in memory. A torn final line is cut
+    /// off so new entries follow the last intact one.
+    ///
+    /// # Errors
+    /// Returns a [`JournalError`] if the file cannot be opened or an entry
+    /// before the last line is damaged.
+    pub fn open(
+        path: impl AsRef<Path>,
+        config: &JournalConfig,
+        after_seq: u64,
+    ) -> Result<(Self, Vec<JournalEntry>), JournalError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut reader = EntryReader::new(BufReader::new(&file));
+        let entries = reader.collect_after(after_seq)?;
+        let length = file.metadata()?.len();
+        if reader.intact < length {
+            warn!(
+                path = %path.display(),
+                dropped_bytes = length - reader.intact,
+                "Dropping a torn final journal entry"
+            );
+            file.set_len(reader.intact)?;
+            file.sync_data()?;
+        }
+        let next_seq = reader.last_seq.map_or(1, |seq| seq + 1);
+        let journal = Self {
+            path,
+            order: Arc::new(Mutex::new(())),
+            writer: Arc::new(parking_lot::Mutex::new(JournalWriter {
+                file,
+                next_seq,
+                fsync: config.fsync,
+                fsync_interval: Duration::from_millis(config.fsync_interval_ms),
+                last_sync: Instant::now(),
+                unsynced: false,
+            })),
+        };
+        Ok((journal, entries))
+    }
+
+    /// Reads the intact entries of the journal at `path` that follow
+    /// `after_seq`, without opening it for writing. The file is streamed, so
+    /// only those entries are held in memory.
+    ///
+    /// # Errors
+    /// Returns a [`JournalError`] if the file cannot be read or an entry
+    /// before the last line is damaged.
+    pub fn read_after(
+        path: impl AsRef<Path>,
+        after_seq: u64,
+    ) -> Result<Vec<JournalEntry>, JournalError> {
+        EntryReader::new(BufReader::new(File::open(path)?)).collect_after(after_seq)
+    }
+
+    /// Reads the first entry of the journal at `path`, if it has one.
+    ///
+    /// # Errors
+    /// Returns a [`JournalError`] if the file cannot be read or its first
+    /// line is damaged.
+    pub fn first(path: impl AsRef<Path>) -> Result<Option<JournalEntry>, JournalError> {
+        EntryReader::new(BufReader::new(File::open(path)?)).next_entry()
+    }
+
+    /// Returns the journal file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Takes the journal lock. Hold it from the append until the event has
+    /// been applied so no other input slips in between.
+    pub async fn lock(&self) -> JournalGuard {
+        JournalGuard {
+            _order: Arc::clone(&self.order).lock_owned().await,
+            writer: Arc::clone(&self.writer),
+        }
+    }
+
+    /// Flushes entries appended under the `interval` policy that have not
+    /// reached disk yet. Does not wait for the journal lock.
+    ///
+    /// # Errors
+    /// Returns [`JournalError::Io`] if the flush fails.
+    pub async fn sync(&self) -> Result<(), JournalError> {
+        let writer = Arc::clone(&self.writer);
+        blocking(move || {
+            let mut writer = writer.lock();
+            if writer.unsynced {
+                writer.sync()?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Drops the entries before `through_seq` from the file, once a durable
+    /// snapshot covers every entry up to `through_seq`. The entry at
+    /// `through_seq` itself is kept so the sequence carries on from it after
+    /// a restart. Returns the number of entries dropped.
+    ///
+    /// The kept entries are written to a new file that then replaces the
+    /// journal, so a crash part-way leaves the old journal in place.
+    ///
+    /// # Errors
+    /// Returns a [`JournalError`] if the file cannot be rewritten; the
+    /// journal is then left as it was.
+    pub async fn compact(&self, through_seq: u64) -> Result<u64, JournalError> {
+        let writer = Arc::clone(&self.writer);
+        let path = self.path.clone();
+        blocking(move || writer.lock().compact(&path, through_seq)).await
+    }
+}
+
+/// Exclusive access to the journal, from [`Journal::lock`].
+#[derive(Debug)]
+pub struct JournalGuard {
+    _order: OwnedMutexGuard<()>,
+    writer: Arc<parking_lot::Mutex<JournalWriter>>,
+}
+
+impl JournalGuard {
+    /// Appends `event`, flushing it as the fsync policy requires, and
+    /// returns its sequence number and time. The write and the flush run on
+    /// a blocking thread.
+    ///
+    /// # Errors
+    /// Returns a [`JournalError`] if the entry cannot be written; the event
+    /// must then not be applied.
+    pub async fn append(
+        &mut self,
+        event: JournalEvent,
+        order_ids: OrderIdPosition,
+    ) -> Result<Appended, JournalError> {
+        let writer = Arc::clone(&self.writer);
+        blocking(move || writer.lock().append(event, order_ids)).await
+    }
+
+    /// Returns the sequence number of the last entry (0 for an empty
+    /// journal).
+    #[must_use]
+    pub fn last_seq(&self) -> u64 {
+        self.writer.lock().next_seq - 1
+    }
+}
+
+/// The journal lock held by one request, shared between
+/// [`mutation_gate`](crate::api::middleware::mutation_gate) and the handler
+/// through the request extensions. A handler that has applied its effect in
+/// memory and still has slow work ahead, such as a database write, releases
+/// it early so later inputs do not queue behind that work.
+#[derive(Debug, Clone)]
+pub struct JournalHold(Arc<parking_lot::Mutex<Option<JournalGuard>>>);
+
+impl JournalHold {
+    /// Wraps `guard`.
+    #[must_use]
+    pub fn new(guard: JournalGuard) -> Self {
+        Self(Arc::new(parking_lot::Mutex::new(Some(guard))))
+    }
+
+    /// Releases the journal lock. Later calls do nothing.
+    pub fn release(&self) {
+        drop(self.0.lock().take());
+    }
+}
+
+impl JournalWriter {
+    fn append(
+        &mut self,
+        event: JournalEvent,
+        order_ids: OrderIdPosition,
+    ) -> Result<Appended, JournalError> {
+        let entry = JournalEntry {
+            seq: self.next_seq,
+            timestamp_ms: clock::wall_ms(),
+            order_ids,
+            event,
+        };
+        self.file.write_all(encode(&entry)?.as_bytes())?;
+        self.next_seq += 1;
+        self.unsynced = true;
+        match self.fsync {
+            JournalFsync::Always => self.sync()?,
+            JournalFsync::Interval if self.last_sync.elapsed() >= self.fsync_interval => {
+                self.sync()?;
+            }
+            JournalFsync::Interval | JournalFsync::Never => {}
+        }
+        Ok(Appended {
+            seq: entry.seq,
+            timestamp_ms: entry.timestamp_ms,
+        })
+    }
+
+    fn sync(&mut self) -> Result<(), JournalError> {
+        self.file.sync_data()?;
+        self.last_sync = Instant::now();
+        self.unsynced = false;
+        Ok(())
+    }
+
+    fn compact(&mut self, path: &Path, through_seq: u64) -> Result<u64, JournalError> {
+        let through_seq = through_seq.min(self.next_seq - 1);
+        let mut temp_name = path.as_os_str().to_owned();
+        temp_name.push(".compact");
+        let temp = PathBuf::from(temp_name);
+
+        let mut reader = EntryReader::new(BufReader::new(File::open(path)?));
+        let mut out = BufWriter::new(File::create(&temp)?);
+        let mut dropped = 0;
+        while let Some(entry) = reader.next_entry()? {
+            if entry.seq < through_seq {
+                dropped += 1;
+            } else {
+                out.write_all(&reader.line)?;
+            }
+        }
+        if dropped == 0 {
+            drop(out);
+            std::fs::remove_file(&temp)?;
+            return Ok(0);
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&temp, path)?;
+        if let Some(parent) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            File::open(parent)?.sync_all()?;
+        }
+        self.file = OpenOptions::new().read(true).append(true).open(path)?;
+        self.last_sync = Instant::now();
+        self.unsynced = false;
+        Ok(dropped)
+    }
+}
+
+/// Runs blocking journal I/O off the async runtime.
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, JournalError> + Send + 'static,
+) -> Result<T, JournalError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| JournalError::Io(std::io::Error::other(e)))?
+}
+
+/// Encodes `entry` as one journal line.
+fn encode(entry: &JournalEntry) -> Result<String, JournalError> {
+    let payload = serde_json::to_string(entry)?;
+    Ok(format!(
+        "{:08x} {payload}\n",
+        crc32fast::hash(payload.as_bytes())
+    ))
+}
+
+/// Decodes one journal line (without its newline), checking the checksum.
+fn decode_line(line: &[u8]) -> Option<JournalEntry> {
+    let line = std::str::from_utf8(line).ok()?;
+    let (crc, payload) = line.split_once(' ')?;
+    let crc = u32::from_str_radix(crc, 16).ok()?;
+    if crc != crc32fast::hash(payload.as_bytes()) {
+        return None;
+    }
+    serde_json::from_str(payload).ok()
+}
+
+/// Streams journal entries one line at a time. A damaged or unterminated
+/// final line ends the intact prefix; a damaged line before it is an error.
+struct EntryReader<R> {
+    reader: R,
+    /// The line of the last entry read, with its newline.
+    line: Vec<u8>,
+    line_number: usize,
+    /// Length of the intact prefix read so far.
+    intact: u64,
+    /// Sequence of the last entry read.
+    last_seq: Option<u64>,
+}
+
+impl<R: BufRead> EntryReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            line_number: 0,
+            intact: 0,
+            last_seq: None,
+        }
+    }
+
+    /// Reads the next entry, or `None` at the end of the intact prefix.
+    fn next_entry(&mut self) -> Result<Option<JournalEntry>, JournalError> {
+        self.line.clear();
+        let read = self.reader.read_until(b'\n', &mut self.line)?;
+        if read == 0 || self.line.last() != Some(&b'\n') {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        let Some(entry) = decode_line(&self.line[..read - 1]) else {
+            if self.reader.fill_buf()?.is_empty() {
+                return Ok(None);
+            }
+            return Err(JournalError::Corrupt {
+                line: self.line_number,
+            });
+        };
+        if let Some(previous) = self.last_seq
+            && entry.seq != previous + 1
+        {
+            return Err(JournalError::Sequence {
+                line: self.line_number,
+                expected: previous + 1,
+                found: entry.seq,
+            });
+        }
+        self.intact += read as u64;
+        self.last_seq = Some(entry.seq);
+        Ok(Some(entry))
+    }
+
+    /// Reads to the end of the intact prefix, keeping the entries that
+    /// follow `after_seq`.
+    fn collect_after(&mut self, after_seq: u64) -> Result<Vec<JournalEntry>, JournalError> {
+        let mut entries = Vec::new();
+        while let Some(entry) = self.next_entry()? {
+            if entry.seq > after_seq {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("journal-test-{}", Uuid::new_v4()))
+            .join(name)
+    }
+
+    fn price(price_cents: u64) -> JournalEvent {
+        JournalEvent::Price {
+            symbol: "BTC".to_string(),
+            price_cents,
+        }
+    }
+
+    fn position(next: u64) -> OrderIdPosition {
+        OrderIdPosition {
+            namespace: Uuid::nil(),
+            next,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_and_reopen() {
+        let path = temp_path("journal.log");
+        let config = JournalConfig::default();
+        {
+            let (journal, entries) = Journal::open(&path, &config, 0).expect("open");
+            assert!(entries.is_empty());
+            let mut guard = journal.lock().await;
+            assert_eq!(guard.last_seq(), 0);
+            assert_eq!(
+                guard
+                    .append(price(100), position(0))
+                    .await
+                    .expect("append")
+                    .seq,
+                1
+            );
+            assert_eq!(
+                guard
+                    .append(JournalEvent::Liquidation, position(4))
+                    .await
+                    .expect("append")
+                    .seq,
+                2
+            );
+        }
+
+        let (journal, entries) = Journal::open(&path, &config, 0).expect("reopen");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, price(100));
+        assert_eq!(entries[1].order_ids, position(4));
+        assert_eq!(
+            journal
+                .lock()
+                .await
+                .append(price(101), position(4))
+                .await
+                .expect("append")
+                .seq,
+            3
+        );
+        assert_eq!(Journal::read_after(&path, 0).expect("read").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_reads_keep_only_the_tail() {
+        let path = temp_path("journal.log");
+        let config = JournalConfig::default();
+        {
+            let (journal, _) = Journal::open(&path, &config, 0).expect("open");
+            let mut guard = journal.lock().await;
+            for price_cents in 100..105 {
+                guard
+                    .append(price(price_cents), position(0))
+                    .await
+                    .expect("append");
+            }
+        }
+
+        let tail = Journal::read_after(&path, 3).expect("read");
+        assert_eq!(
+            tail.iter().map(|entry| entry.seq).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        let (journal, tail) = Journal::open(&path, &config, 5).expect("reopen");
+        assert!(tail.is_empty());
+        assert_eq!(journal.lock().await.last_seq(), 5);
+        let first = Journal::first(&path).expect("read").expect("first entry");
+        assert_eq!(first.event, price(100));
+    }
+
+    #[tokio::test]
+    async fn test_compaction_drops_the_entries_a_snapshot_covers() {
+        let path = temp_path("journal.log");
+        let config = JournalConfig::default();
+        let (journal, _) = Journal::open(&path, &config, 0).expect("open");
+        {
+            let mut guard = journal.lock().await;
+            for price_cents in 100..105 {
+                guard
+                    .append(price(price_cents), position(0))
+                    .await
+                    .expect("append");
+            }
+        }
+
+        assert_eq!(journal.compact(3).await.expect("compact"), 2);
+        assert_eq!(journal.compact(3).await.expect("compact"), 0);
+        assert_eq!(
+            journal
+                .lock()
+                .await
+                .append(price(105), position(0))
+                .await
+                .expect("append")
+                .seq,
+            6
+        );
+        let seqs = |entries: Vec<JournalEntry>| -> Vec<u64> {
+            entries.into_iter().map(|entry| entry.seq).collect()
+        };
+        assert_eq!(
+            seqs(Journal::read_after(&path, 0).expect("read")),
+            vec![3, 4, 5, 6]
+        );
+
+        // The kept entry carries the sequence across a restart.
+        drop(journal);
+        let (journal, tail) = Journal::open(&path, &config, 3).expect("reopen");
+        assert_eq!(seqs(tail), vec![4, 5, 6]);
+        assert_eq!(journal.compact(u64::MAX).await.expect("compact"), 3);
+        assert_eq!(
+            journal
+                .lock()
+                .await
+                .append(price(106), position(0))
+                .await
+                .expect("append")
+                .seq,
+            7
+        );
+    }
+
+    #[tokio::test]
+    async fn test_released_hold_frees_the_lock() {
+        let path = temp_path("journal.log");
+        let (journal, _) = Journal::open(&path, &JournalConfig::default(), 0).expect("open");
+        let hold = JournalHold::new(journal.lock().await);
+        let waiting = tokio::time::timeout(Duration::from_millis(50), journal.lock()).await;
+        assert!(waiting.is_err());
+
+        hold.clone().release();
+        hold.release();
+        let mut guard = tokio::time::timeout(Duration::from_secs(5), journal.lock())
+            .await
+            .expect("lock released");
+        assert_eq!(
+            guard
+                .append(price(100), position(0))
+                .await
+                .expect("append")
+                .seq,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_torn_tail_is_dropped() {
+        let path = temp_path("journal.log");
+        let config = JournalConfig::default();
+        {
+            let (journal, _) = Journal::open(&path, &config, 0).expect("open");
+            let mut guard = journal.lock().await;
+            guard.append(price(100), position(0)).await.expect("append");
+            guard.append(price(101), position(0)).await.expect("append");
+        }
+        let mut contents = std::fs::read(&path).expect("read");
+        let intact = contents.len();
+        contents.truncate(intact - 7);
+        std::fs::write(&path, &contents).expect("write");
+
+        let (journal, entries) = Journal::open(&path, &config, 0).expect("reopen");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            journal
+                .lock()
+                .await
+                .append(price(102), position(0))
+                .await
+                .expect("append")
+                .seq,
+            2
+        );
+        let entries = Journal::read_after(&path, 0).expect("read");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].event, price(102));
+    }
+
+    #[tokio::test]
+    async fn test_corruption_before_the_tail_is_an_error() {
+        let path = temp_path("journal.log");
+        {
+            let (journal, _) = Journal::open(&path, &JournalConfig::default(), 0).expect("open");
+            let mut guard = journal.lock().await;
+            guard.append(price(100), position(0)).await.expect("append");
+            guard.append(price(101), position(0)).await.expect("append");
+        }
+        let contents = std::fs::read_to_string(&path).expect("read");
+        std::fs::write(
+            &path,
+            contents.replacen("\"price_cents\":100", "\"price_cents\":900", 1),
+        )
+        .expect("write");
+
+        assert!(matches!(
+            Journal::read_after(&path, 0),
+            Err(JournalError::Corrupt { line: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_sequence_gaps_are_rejected() {
+        let first = JournalEntry {
+            seq: 1,
+            timestamp_ms: 0,
+            order_ids: position(0),
+            event: price(100),
+        };
+        let third = JournalEntry {
+            seq: 3,
+            ..first.clone()
+        };
+        let contents = format!(
+            "{}{}{}",
+            encode(&first).expect("encode"),
+            encode(&third).expect("encode"),
+            encode(&first).expect("encode")
+        );
+        assert!(matches!(
+            EntryReader::new(contents.as_bytes()).collect_after(0),
+            Err(JournalError::Sequence {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+}
@@ -0,0 +1,478 @@
+//! Double-entry cash ledger.
+//!
+//! Every movement of cash is a [`JournalEntry`] whose postings sum to zero:
+//! a deposit credits the account's cash and debits [`LedgerAccount::External`],
+//! a fill moves premium from the buyer to the seller and fees to
+//! [`LedgerAccount::Fees`], and an expiry pays intrinsic value between the
+//! holder and [`LedgerAccount::Clearing`]. Balances are the running sum of an
+//! account's postings, so the ledger as a whole always balances to zero.
+//!
+//! Amounts are signed cents: a positive posting increases the balance of the
+//! ledger account it is posted to.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Errors raised when posting to the ledger.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LedgerError {
+    /// The entry had no non-zero postings.
+    #[error("journal entry has no postings")]
+    Empty,
+    /// The postings did not sum to zero.
+    #[error("journal entry is unbalanced by {0} cents")]
+    Unbalanced(i128),
+    /// A balance would overflow.
+    #[error("balance of {0} would overflow")]
+    Overflow(String),
+}
+
+/// A ledger account.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LedgerAccount {
+    /// Cash of a trading account.
+    Cash(String),
+    /// Money outside the exchange: the other side of deposits and withdrawals.
+    External,
+    /// Fees collected by the exchange.
+    Fees,
+    /// Counterparty of fills against orders that belong to no account (market
+    /// maker quotes) and of expiry settlements.
+    Clearing,
+}
+
+impl fmt::Display for LedgerAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cash(account) => write!(f, "cash:{account}"),
+            Self::External => f.write_str("external"),
+            Self::Fees => f.write_str("fees"),
+            Self::Clearing => f.write_str("clearing"),
+        }
+    }
+}
+
+impl FromStr for LedgerAccount {
+    type Err = String;
+
+    /// Parses the [`Display`](fmt::Display) form of an account.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "external" => Ok(Self::External),
+            "fees" => Ok(Self::Fees),
+            "clearing" => Ok(Self::Clearing),
+            _ => s
+                .strip_prefix("cash:")
+                .map(|account| Self::Cash(account.to_string()))
+                .ok_or_else(|| format!("unknown ledger account: {s}")),
+        }
+    }
+}
+
+/// What a journal entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Cash paid in from outside.
+    Deposit,
+    /// Cash paid out.
+    Withdrawal,
+    /// Premium and fees of a fill.
+    Trade,
+    /// Cash settlement of an expired position.
+    Settlement,
+}
+
+/// One leg of a journal entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Posting {
+    /// Account posted to.
+    pub account: LedgerAccount,
+    /// Signed amount in cents.
+    pub amount_cents: i64,
+}
+
+impl Posting {
+    /// Creates a posting of `amount_cents` to `account`.
+    #[must_use]
+    pub fn new(account: LedgerAccount, amount_cents: i64) -> Self {
+        Self {
+            account,
+            amount_cents,
+        }
+    }
+}
+
+/// A balanced set of postings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Sequence number, starting at 1.
+    pub entry_id: u64,
+    /// What the entry records.
+    pub kind: EntryKind,
+    /// Trade id, symbol or note the entry refers to.
+    pub reference: String,
+    /// Postings, summing to zero.
+    pub postings: Vec<Posting>,
+    /// Timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl JournalEntry {
+    /// Net amount the entry posts to `account`, in cents.
+    #[must_use]
+    pub fn amount_for(&self, account: &LedgerAccount) -> i64 {
+        self.postings
+            .iter()
+            .filter(|p| &p.account == account)
+            .map(|p| p.amount_cents)
+            .sum()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LedgerInner {
+    entries: Vec<JournalEntry>,
+    balances: HashMap<LedgerAccount, i64>,
+}
+
+/// Append-only double-entry journal with running balances.
+#[derive(Debug, Default)]
+pub struct Ledger {
+    inner: RwLock<LedgerInner>,
+}
+
+impl Ledger {
+    /// Creates an empty ledger.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Posts a journal entry, returning its id.
+    ///
+    /// Zero postings are dropped. The entry is applied atomically: either every
+    /// balance moves or none does.
+    ///
+    /// # Errors
+    /// Returns [`LedgerError::Empty`] when nothing is left to post,
+    /// [`LedgerError::Unbalanced`] when the postings do not sum to zero and
+    /// [`LedgerError::Overflow`] when a balance would overflow.
+    pub fn post(
+        &self,
+        kind: EntryKind,
+        reference: impl Into<String>,
+        postings: Vec<Posting>,
+        timestamp_ms: u64,
+    ) -> Result<u64, LedgerError> {
+        let postings: Vec<Posting> = postings
+            .into_iter()
+            .filter(|p| p.amount_cents != 0)
+            .collect();
+        if postings.is_empty() {
+            return Err(LedgerError::Empty);
+        }
+        let imbalance: i128 = postings.iter().map(|p| i128::from(p.amount_cents)).sum();
+        if imbalance != 0 {
+            return Err(LedgerError::Unbalanced(imbalance));
+        }
+
+        let mut inner = self.inner.write();
+        let mut updated: HashMap<&LedgerAccount, i64> = HashMap::new();
+        for posting in &postings {
+            let current = updated
+                .get(&posting.account)
+                .copied()
+                .unwrap_or_else(|| inner.balances.get(&posting.account).copied().unwrap_or(0));
+            let next = current
+                .checked_add(posting.amount_cents)
+                .ok_or_else(|| LedgerError::Overflow(posting.account.to_string()))?;
+            updated.insert(&posting.account, next);
+        }
+        let updated: Vec<(LedgerAccount, i64)> = updated
+            .into_iter()
+            .map(|(account, balance)| (account.clone(), balance))
+            .collect();
+        inner.balances.extend(updated);
+
+        let entry_id = inner.entries.len() as u64 + 1;
+        inner.entries.push(JournalEntry {
+            entry_id,
+            kind,
+            reference: reference.into(),
+            postings,
+            timestamp_ms,
+        });
+        Ok(entry_id)
+    }
+
+    /// Balance of `account` in cents.
+    #[must_use]
+    pub fn balance(&self, account: &LedgerAccount) -> i64 {
+        self.inner
+            .read()
+            .balances
+            .get(account)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Cash balance of trading account `account` in cents.
+    #[must_use]
+    pub fn cash_balance(&self, account: &str) -> i64 {
+        self.balance(&LedgerAccount::Cash(account.to_string()))
+    }
+
+    /// The most recent `limit` entries touching `account`, newest first.
+    #[must_use]
+    pub fn entries_for(&self, account: &LedgerAccount, limit: usize) -> Vec<JournalEntry> {
+        self.inner
+            .read()
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| e.postings.iter().any(|p| &p.account == account))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Every journal entry, oldest first.
+    #[must_use]
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.inner.read().entries.clone()
+    }
+
+    /// The journal entries timestamped at or after `since_ms`, oldest first.
+    #[must_use]
+    pub fn entries_since(&self, since_ms: u64) -> Vec<JournalEntry> {
+        self.inner
+            .read()
+            .entries
+            .iter()
+            .filter(|e| e.timestamp_ms >= since_ms)
+            .cloned()
+            .collect()
+    }
+
+    /// Cash balance of every trading account that has one, by account.
+    #[must_use]
+    pub fn cash_balances(&self) -> Vec<(String, i64)> {
+        let mut balances: Vec<(String, i64)> = self
+            .inner
+            .read()
+            .balances
+            .iter()
+            .filter_map(|(account, balance)| match account {
+                LedgerAccount::Cash(account) => Some((account.clone(), *balance)),
+                _ => None,
+            })
+            .collect();
+        balances.sort();
+        balances
+    }
+
+    /// Replaces the ledger with `entries`, rebuilding the balances from their
+    /// postings. Nothing changes when an entry is unbalanced or a balance
+    /// would overflow.
+    ///
+    /// # Errors
+    /// Returns [`LedgerError::Unbalanced`] or [`LedgerError::Overflow`].
+    pub fn restore(&self, entries: Vec<JournalEntry>) -> Result<(), LedgerError> {
+        let mut balances: HashMap<LedgerAccount, i64> = HashMap::new();
+        for entry in &entries {
+            let imbalance: i128 = entry
+                .postings
+                .iter()
+                .map(|p| i128::from(p.amount_cents))
+                .sum();
+            if imbalance != 0 {
+                return Err(LedgerError::Unbalanced(imbalance));
+            }
+            for posting in &entry.postings {
+                let balance = balances.entry(posting.account.clone()).or_insert(0);
+                *balance = balance
+                    .checked_add(posting.amount_cents)
+                    .ok_or_else(|| LedgerError::Overflow(posting.account.to_string()))?;
+            }
+        }
+        *self.inner.write() = LedgerInner { entries, balances };
+        Ok(())
+    }
+
+    /// Number of journal entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.read().entries.len()
+    }
+
+    /// Whether nothing has been posted.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().entries.is_empty()
+    }
+
+    /// Sum of every balance; zero for a consistent ledger.
+    #[must_use]
+    pub fn trial_balance(&self) -> i128 {
+        self.inner
+            .read()
+            .balances
+            .values()
+            .map(|b| i128::from(*b))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cash(account: &str) -> LedgerAccount {
+        LedgerAccount::Cash(account.to_string())
+    }
+
+    #[test]
+    fn test_post_moves_balances_and_keeps_the_ledger_balanced() {
+        let ledger = Ledger::new();
+        ledger
+            .post(
+                EntryKind::Deposit,
+                "wire",
+                vec![
+                    Posting::new(cash("alice"), 10_000),
+                    Posting::new(LedgerAccount::External, -10_000),
+                ],
+                1,
+            )
+            .expect("balanced");
+        ledger
+            .post(
+                EntryKind::Trade,
+                "trade-1",
+                vec![
+                    Posting::new(cash("alice"), -2_505),
+                    Posting::new(cash("bob"), 2_500),
+                    Posting::new(LedgerAccount::Fees, 5),
+                    Posting::new(LedgerAccount::Clearing, 0),
+                ],
+                2,
+            )
+            .expect("balanced");
+
+        assert_eq!(ledger.cash_balance("alice"), 7_495);
+        assert_eq!(ledger.cash_balance("bob"), 2_500);
+        assert_eq!(ledger.balance(&LedgerAccount::Fees), 5);
+        assert_eq!(ledger.trial_balance(), 0);
+        assert_eq!(ledger.len(), 2);
+
+        let entries = ledger.entries_for(&cash("alice"), 10);
+        assert_eq!(entries[0].kind, EntryKind::Trade);
+        assert_eq!(entries[0].amount_for(&cash("alice")), -2_505);
+        // The zero posting was dropped.
+        assert_eq!(entries[0].postings.len(), 3);
+        assert_eq!(ledger.entries_for(&cash("bob"), 10).len(), 1);
+        assert_eq!(ledger.entries_since(2).len(), 1);
+        assert_eq!(
+            ledger.cash_balances(),
+            vec![("alice".to_string(), 7_495), ("bob".to_string(), 2_500)]
+        );
+    }
+
+    #[test]
+    fn test_accounts_parse_back_from_their_display_form() {
+        for account in [
+            cash("alice"),
+            cash("a:b"),
+            LedgerAccount::External,
+            LedgerAccount::Fees,
+            LedgerAccount::Clearing,
+        ] {
+            assert_eq!(account.to_string().parse(), Ok(account));
+        }
+        assert!("bank".parse::<LedgerAccount>().is_err());
+    }
+
+    #[test]
+    fn test_post_rejects_unbalanced_or_empty_entries() {
+        let ledger = Ledger::new();
+        assert_eq!(
+            ledger.post(
+                EntryKind::Deposit,
+                "oops",
+                vec![Posting::new(cash("alice"), 100)],
+                1
+            ),
+            Err(LedgerError::Unbalanced(100))
+        );
+        assert_eq!(
+            ledger.post(EntryKind::Deposit, "nothing", vec![], 1),
+            Err(LedgerError::Empty)
+        );
+        assert!(ledger.is_empty());
+        assert_eq!(ledger.cash_balance("alice"), 0);
+    }
+
+    #[test]
+    fn test_restore_rebuilds_balances_from_entries() {
+        let ledger = Ledger::new();
+        ledger
+            .post(
+                EntryKind::Deposit,
+                "in",
+                vec![
+                    Posting::new(cash("alice"), 500),
+                    Posting::new(LedgerAccount::External, -500),
+                ],
+                1,
+            )
+            .expect("balanced");
+        let entries = ledger.entries();
+
+        let restored = Ledger::new();
+        restored.restore(entries.clone()).expect("balanced entries");
+        assert_eq!(restored.entries(), entries);
+        assert_eq!(restored.cash_balance("alice"), 500);
+        assert_eq!(restored.trial_balance(), 0);
+
+        // An unbalanced entry leaves the ledger as it was.
+        let mut broken = entries;
+        broken[0].postings.pop();
+        assert_eq!(restored.restore(broken), Err(LedgerError::Unbalanced(500)));
+        assert_eq!(restored.cash_balance("alice"), 500);
+    }
+
+    #[test]
+    fn test_post_is_atomic_on_overflow() {
+        let ledger = Ledger::new();
+        ledger
+            .post(
+                EntryKind::Deposit,
+                "max",
+                vec![
+                    Posting::new(cash("alice"), i64::MAX),
+                    Posting::new(LedgerAccount::External, -i64::MAX),
+                ],
+                1,
+            )
+            .expect("balanced");
+        let err = ledger
+            .post(
+                EntryKind::Deposit,
+                "more",
+                vec![
+                    Posting::new(LedgerAccount::External, -1),
+                    Posting::new(cash("alice"), 1),
+                ],
+                2,
+            )
+            .expect_err("overflows");
+        assert_eq!(err, LedgerError::Overflow("cash:alice".to_string()));
+        assert_eq!(ledger.balance(&LedgerAccount::External), -i64::MAX);
+        assert_eq!(ledger.len(), 1);
+    }
+}
@@ -78,14 +78,24 @@
 //! |--------|-------------|
 //! | [`api`] | Route handlers, WebSocket, and router configuration |
 //! | [`auth`] | JWT (x509) authentication, claims, and rate limiting |
+//! | [`clock`] | Event time, fixed to the journal time while an input is applied |
 //! | [`config`] | Server and market maker configuration |
-//! | [`db`] | Database connection pool and schema |
+//! | [`contract`] | Contract multiplier and lot-size specifications |
+//! | [`db`] | Database connection pool (PostgreSQL or SQLite), schema and repository |
 //! | [`error`] | API error types with `IntoResponse` implementation |
+//! | [`journal`] | Write-ahead event journal |
 //! | [`market_maker`] | Market making engine with pricing and quoting |
 //! | [`models`] | Request/response DTOs with OpenAPI schemas |
 //! | [`ohlc`] | OHLC candlestick aggregation |
+//! | [`ohlc_store`] | Durable OHLC bar history |
+//! | [`quote_history`] | Top-of-book history recorder |
+//! | [`order_ids`] | Deterministic order ID sequence |
+//! | [`order_store`] | Tracked orders indexed by account |
 //! | [`simulation`] | Price simulation for testing |
+//! | [`snapshots`] | Durable orderbook snapshot storage |
 //! | [`state`] | Application state management |
+//! | [`surface_history`] | Volatility surface history |
+//! | [`ticks`] | Per-underlying tick-size tables |
 //!
 //! ## API Endpoints
 //!
@@ -108,6 +118,10 @@
 //! option-chain-orderbook-backend mint-token --permissions read,trade --ttl 3600
 //! ```
 //!
+//! Pass `--account <id>` (or `account` in the token request body) to bind the
+//! token to a named trading account; positions and risk are tracked per account,
+//! keyed by the token subject.
+//!
 //! ### Controls (Market Maker)
 //!
 //! | Method | Endpoint | Description |
@@ -116,8 +130,54 @@
 //! | POST | `/api/v1/controls/kill-switch` | Disable all quoting |
 //! | POST | `/api/v1/controls/enable` | Enable quoting |
 //! | POST | `/api/v1/controls/parameters` | Update spread/size/skew |
-//! | GET | `/api/v1/controls/instruments` | List instruments |
+//! | GET | `/api/v1/controls/instruments` | List instruments with their quoting strategy, ladder depth and resting maker orders |
 //! | POST | `/api/v1/controls/instrument/{symbol}/toggle` | Toggle instrument |
+//! | GET | `/api/v1/controls/hedging` | Delta hedge status, hedge P&L and recent hedges |
+//! | GET | `/api/v1/controls/pnl` | Market-maker P&L attribution per underlying and period |
+//! | GET | `/api/v1/controls/requotes` | Requote throttling settings and sent vs. skipped counters |
+//! | GET | `/api/v1/controls/queue` | Queue position of each resting maker order (`?underlying=`) |
+//!
+//! With `[market_maker.hedging] enabled = true` the market maker hedges its net
+//! option delta per underlying in the underlying itself. Whenever a fill or a
+//! price move leaves the residual delta outside `delta_band`, it trades the
+//! underlying at spot (in multiples of `lot_size`) to bring it back to zero.
+//! Hedges book into an internal hedge ledger, not an order book.
+//!
+//! The market maker's P&L is attributed per underlying to captured edge (fills
+//! against quote-time theo), delta, gamma, vega (moves in the front-month ATM
+//! vol of the volatility surface), theta and hedging. The inventory is
+//! re-marked on every price update. Attribution is reported since start-up, for
+//! the open period and for recent closed periods. Periods close every
+//! `[market_maker.pnl] period_seconds`.
+//!
+//! Requotes are throttled per instrument. A price tick leaves the resting quote
+//! in place unless a price moved by at least `min_price_change_ticks` or a size
+//! changed by at least `min_size_change_pct`. No instrument is requoted more than
+//! `max_updates_per_second` times a second. A quote that lost a leg to a fill is
+//! always replaced. Skipped requotes send no `quote` WebSocket message. Configure
+//! it under `[market_maker.requote]`.
+//!
+//! Quotes come from a quoting strategy chosen per underlying. The `single`
+//! strategy (the default) quotes one bid and one ask around the theoretical
+//! value. The `layered` strategy quotes a ladder of `levels` price levels per
+//! side. Levels are `spacing` apart, either `{ ticks = N }` or `{ bps = X }` of
+//! theo. Sizes fall away from the best level by `size_profile`: `flat`,
+//! `linear` or `geometric` (by `size_decay`). Select strategies under
+//! `[market_maker.strategy]` (`default` plus an `underlyings` map). The ladder
+//! is `[market_maker.strategy.layered]`, overridden per underlying under
+//! `[market_maker.strategy.ladders.<SYMBOL>]`. Custom strategies implement the
+//! `QuotingStrategy` trait and are installed with
+//! `MarketMakerEngine::set_strategy`. The requote throttle compares the best
+//! level of each side; a requote amends every level in place and cancels levels
+//! the new ladder no longer has.
+//!
+//! Requotes and order modifications amend the resting order in place. The order
+//! keeps its id, and keeps its queue priority when only its quantity decreases.
+//! A price change or a quantity increase moves it to the back of its level. A
+//! modification whose new price crosses the book trades like a new order, with
+//! the fills recorded as for any other order, and rests only the remainder. The
+//! market maker never quotes a level that would cross. A rejected amend leaves
+//! the original order resting.
 //!
 //! ### Prices
 //!
@@ -126,6 +186,14 @@
 //! | POST | `/api/v1/prices` | Insert underlying price |
 //! | GET | `/api/v1/prices` | Get all prices |
 //! | GET | `/api/v1/prices/{symbol}` | Get latest price |
+//! | GET | `/api/v1/prices/{symbol}/history` | Get price history and realized volatility |
+//!
+//! `GET /api/v1/prices/{symbol}/history` returns the recorded prices, raw
+//! or downsampled with `interval` (1m, 5m, 15m, 1h, 4h, 1d) to OHLC points,
+//! together with the realized volatility of the returned points. Without a
+//! `DATABASE_URL` only the latest 10,000 prices of each symbol are kept. Set
+//! `record_prices = true` in `[simulation]` to store simulator ticks alongside
+//! inserted prices.
 //!
 //! ### Underlyings
 //!
@@ -149,6 +217,7 @@
 //! | Method | Endpoint | Description |
 //! |--------|----------|-------------|
 //! | GET | `/api/v1/underlyings/{underlying}/volatility-surface` | Get IV surface |
+//! | GET | `/api/v1/underlyings/{underlying}/volatility-surface/history` | Stored surfaces and skew series |
 //!
 //! IVs are derived from the observed TWO-SIDED order-book mid prices (a
 //! one-sided book omits the leg) via
@@ -160,11 +229,30 @@
 //! matched by any volatility (e.g. below intrinsic or above the no-volatility
 //! asymptote), or the result pins to the grid ceiling.
 //!
+//! With a PostgreSQL `DATABASE_URL` and `[surface_history] interval_seconds`
+//! above 0, the surface of every underlying is computed on that schedule and
+//! stored in the `volatility_surfaces` table with its skew metrics: per
+//! expiration, the ATM IV, the 25-delta risk reversal (call IV minus put IV)
+//! and the 25-delta butterfly (wing IV average minus ATM IV). The wings are the
+//! strikes whose delta is closest to ±0.25 within 0.10; without one the
+//! expiration reports only its ATM IV. The `history` endpoint returns the
+//! latest stored surface at or before each of the comma-separated `at` times
+//! (milliseconds) and the metrics of every surface stored between `from` and
+//! `to`, oldest first.
+//!
 //! ### Option Chain
 //!
 //! | Method | Endpoint | Description |
 //! |--------|----------|-------------|
 //! | GET | `.../expirations/{exp}/chain` | Get option chain matrix |
+//! | GET | `.../expirations/{exp}/forward` | Implied forward and put-call parity diagnostics |
+//!
+//! The implied forward is fitted by regressing `C - P` on the strike across
+//! every strike whose call and put books are both two-sided (`C - P = D·(F - K)`),
+//! yielding the forward, discount factor, implied rate and carry. Strikes whose
+//! parity residual exceeds `threshold_bps` of the forward (default 50) are
+//! flagged. The chain's ATM strike and the volatility surface's IVs use the
+//! implied forward where one is derivable, falling back to spot otherwise.
 //!
 //! ### Strikes
 //!
@@ -182,14 +270,31 @@
 //! | POST | `.../options/{style}/orders` | Add limit order |
 //! | POST | `.../options/{style}/orders/market` | Submit market order |
 //! | DELETE | `.../options/{style}/orders/{id}` | Cancel order |
-//! | PATCH | `.../options/{style}/orders/{id}` | Modify order |
+//! | PATCH | `.../options/{style}/orders/{id}` | Modify order in place (same id) |
 //! | GET | `.../options/{style}/quote` | Get quote |
+//! | GET | `.../options/{style}/spec` | Get contract and tick-size reference data |
 //! | GET | `.../options/{style}/greeks` | Get option greeks |
 //! | GET | `.../options/{style}/snapshot` | Get enriched snapshot |
 //! | GET | `.../options/{style}/last-trade` | Get last trade |
 //! | GET | `.../options/{style}/ohlc` | Get OHLC bars |
+//! | GET | `.../options/{style}/quotes/history` | Get recorded top-of-book history |
 //! | GET | `.../options/{style}/metrics` | Get orderbook metrics |
 //!
+//! Limit prices must sit on the tick grid of their underlying, set per asset by
+//! `tick_size`. A table of price bands gives each band its own tick, e.g.
+//! `{ bands = [{ below = 3.0, tick = 0.05 }, { tick = 0.10 }] }`. Alternatively
+//! `{ underlying_fraction = 0.0005 }` makes the tick a fraction of spot, rounded
+//! to whole cents. Add, modify and bulk orders off the grid are rejected with
+//! `400 INVALID_TICK_SIZE`. The market maker rounds its bids down and asks up
+//! onto the grid. Assets without `tick_size` trade in whole cents.
+//!
+//! Prices are per unit of the underlying; one contract delivers
+//! `contract_multiplier` units (default 1, 100 for listed equity options).
+//! Notional, P&L, margin, dollar greeks, premium cash flows and settlement all
+//! scale by it. Order quantities must be whole multiples of `min_lot_size` and at
+//! most `max_order_size`; other sizes are rejected with `400 INVALID_ORDER_SIZE`,
+//! and the market maker rounds its quote sizes to whole lots.
+//!
 //! ### Orders
 //!
 //! | Method | Endpoint | Description |
@@ -207,6 +312,82 @@
 //! | GET | `/api/v1/positions` | List all positions |
 //! | GET | `/api/v1/positions/{symbol}` | Get position |
 //!
+//! ### Risk
+//!
+//! | Method | Endpoint | Description |
+//! |--------|----------|-------------|
+//! | GET | `/api/v1/risk/greeks` | Net portfolio greeks for the caller's account and the market maker |
+//! | GET | `/api/v1/risk/scenarios` | Scenario P&L grid and configured stress tests for the caller's positions |
+//! | GET | `/api/v1/risk/var` | Monte Carlo and historical VaR / expected shortfall for the caller's positions |
+//! | GET | `/api/v1/risk/margin` | Portfolio margin, collateral and margin-call status of the caller's account |
+//! | GET | `/api/v1/risk/liquidation` | Latest liquidation of the caller's account |
+//!
+//! Greeks are aggregated per underlying, per expiration and in total, using the
+//! quoter's pricer: delta, gamma, vega (per vol point), theta (per day), rho (per
+//! 1% rate) plus dollar delta (Σ q·Δ·S) and dollar gamma (Σ q·Γ·S²/100). The
+//! market maker's own quote fills are reported as a separate `market_maker` book.//!
+//! Scenarios fully reprice every position (no greek approximation) over a grid of
+//! spot shocks (`spot_shocks`, percent), vol shocks (`vol_shocks`, vol points) and
+//! horizons (`days`), each a comma-separated query list, and return one P&L matrix
+//! in cents per horizon. Named stress scenarios are configured in `config.toml`:
+//!
+//! ```toml
+//! [[risk.stress_scenarios]]
+//! name = "btc-crash"
+//! description = "BTC -30%, vol +20 points"
+//! days_forward = 0
+//! shocks = [{ underlying = "BTC", spot_shock_pct = -30.0, vol_shock_pts = 20.0 }]
+//! ```
+//!
+//! A shock without `underlying` applies to every underlying not shocked explicitly.//!
+//!
+//! VaR and expected shortfall are computed per account by full repricing at the
+//! `[risk.var]` confidence and horizon: Monte Carlo draws horizon returns from the
+//! configured simulation walk, and historical simulation replays overlapping
+//! returns from the daily closes of the recorded prices (`underlying_prices`
+//! with a database, the prices inserted since startup otherwise). Reports are recomputed every `interval_seconds`;
+//! `GET /api/v1/risk/var?refresh=true` forces a fresh run.
+//!
+//! Margin uses a SPAN-style risk array: every underlying is revalued under
+//! sixteen scenarios (seven spot moves across `price_scan_pct`, each with vol
+//! up and down by `vol_scan_pts`, plus two extreme moves at twice the range
+//! counted at 35%). The worst loss is the scan risk, floored by a short option
+//! minimum; offsetting positions net within an underlying and the saving is
+//! reported as a spread credit. With `[risk.margin] enabled = true`, an order
+//! is rejected with `422 INSUFFICIENT_MARGIN` when the account's positions plus
+//! resting orders, including the new one, would need more initial margin than
+//! its collateral — unless the order reduces margin. Collateral is the
+//! account's cash plus pledged collateral, which defaults to
+//! `default_collateral` and is set per account with
+//! `POST /api/v1/admin/accounts/{account}/collateral`.
+//!
+//! With `[risk.liquidation] enabled = true`, every `interval_seconds` the
+//! accounts whose equity (collateral plus the model value of their positions)
+//! is below maintenance margin are liquidated: their resting orders are
+//! canceled, orders that would increase their margin are rejected, and their
+//! positions (shorts and the largest first) are closed with IOC orders within
+//! `max_slippage_pct` of the model price until equity covers maintenance
+//! again. What the books cannot absorb is transferred to the market maker at
+//! `penalty_pct` against the model price. Each step is kept on the account's
+//! liquidation record and pushed to its WebSocket connections.
+//!
+//! ### Account
+//!
+//! | Method | Endpoint | Description |
+//! |--------|----------|-------------|
+//! | GET | `/api/v1/account/balance` | Cash, reserved and available funds of the caller's account |
+//! | GET | `/api/v1/account/ledger` | Ledger entries of the caller's account, newest first |
+//!
+//! Cash is kept in a double-entry ledger: every entry's postings sum to zero
+//! across the account cash, `external` (deposits and withdrawals), `fees` and
+//! `clearing` (market maker quotes and expiry settlement) ledger accounts. Each
+//! fill moves premium from buyer to seller and charges `[ledger]` taker/maker
+//! fees; positions whose expiration has passed are cash-settled at intrinsic
+//! value every `settlement_interval_seconds`. Open buy orders reserve their
+//! premium plus taker fee; with `enforce_buying_power = true`, a buy whose cost
+//! exceeds available cash is rejected with `422 INSUFFICIENT_FUNDS`. Cash also
+//! counts as margin collateral.
+//!
 //! ### Executions
 //!
 //! | Method | Endpoint | Description |
@@ -222,6 +403,97 @@
 //! | GET | `/api/v1/admin/snapshots` | List snapshots |
 //! | GET | `/api/v1/admin/snapshots/{id}` | Get snapshot |
 //! | POST | `/api/v1/admin/snapshots/{id}/restore` | Restore snapshot |
+//! | POST | `/api/v1/admin/ohlc/backfill` | Rebuild stored OHLC bars from executions |
+//! | POST | `/api/v1/admin/accounts/{account}/collateral` | Set an account's pledged margin collateral |
+//! | POST | `/api/v1/admin/accounts/{account}/deposit` | Deposit cash into an account |
+//! | POST | `/api/v1/admin/accounts/{account}/withdraw` | Withdraw available cash from an account |
+//! | GET | `/api/v1/admin/liquidations` | Latest liquidation of every account |
+//!
+//! Snapshots are written to the `orderbook_snapshots` table when `DATABASE_URL`
+//! points to PostgreSQL, otherwise to gzip-compressed files in
+//! `[snapshots] directory`; with neither they live in memory only. Durable
+//! snapshots are listed and restored after a restart and pruned to `max_count`
+//! and `max_age_seconds` on every write. `restore_on_startup = true` restores
+//! the newest one when the server starts. `interval_seconds` takes a snapshot
+//! on a schedule and `on_shutdown = true` takes one during graceful shutdown;
+//! each listed snapshot records its `trigger` (`manual`, `scheduled` or
+//! `shutdown`) and whether it was complete (`success`).
+//!
+//! Snapshots capture the whole engine state, not only the books: tracked
+//! orders, positions, executions, last trades, OHLC bars, the cash ledger,
+//! pledged collateral, liquidation records and the market maker's order
+//! tracking, inventory, hedges and P&L attribution are taken together while
+//! mutating requests, sweeps and requotes are paused, and are written in
+//! snapshot format version 3. Restoring
+//! one replaces all of them and empties books the snapshot does not hold, or
+//! changes nothing when any orderbook fails to parse (`state_restored` reports
+//! it). Older books-only snapshots still restore as before.
+//!
+//! The `[journal]` section turns on a write-ahead event journal: every mutating
+//! request (order entry, cancels, amends, bulk operations, control changes),
+//! every simulated price, every settlement, liquidation and order cleanup sweep
+//! and every snapshot restore is appended to `path` before it is applied, one CRC-32
+//! checked line per entry, flushed per `fsync` (`always`, `interval` or
+//! `never`). Order IDs come from a reproducible sequence recorded with each
+//! entry. On startup the journal tail after the restored snapshot is replayed
+//! (`replay_on_startup`), so a crash loses nothing that was acknowledged. The
+//! `replay [--journal <path>] [--until <seq>]` subcommand rebuilds the state the
+//! same way, prints a JSON summary and exits, to reproduce an incident offline.
+//! Writes and flushes run off the async runtime, and reads stream the file,
+//! keeping only the tail after the snapshot. With `restore_on_startup` set, each
+//! complete durable snapshot drops the journal entries it covers.
+//! Every input is applied at the time it was journaled at, live and in replay,
+//! so a replay sees the times of the recorded run (GTD admission, order and
+//! fill timestamps, requote throttling, pricing). Replayed price inserts are
+//! recorded to the database again.
+//!
+//! OHLC bars are kept in memory per series up to a fixed cap. With a
+//! `DATABASE_URL`, the bars changed since the last flush are written to the
+//! `ohlc_bars` table every `[ohlc] flush_interval_seconds` (default 60) and
+//! during graceful shutdown.
+//! The `ohlc` endpoint merges the stored bars with the in-memory ones, so
+//! charts keep their history across restarts and reach past the in-memory
+//! window. The bars still open when the server stopped are resumed on startup.
+//! `POST /api/v1/admin/ohlc/backfill` rebuilds the stored bars from the
+//! `executions` table (written with the trading state below), for one `symbol`
+//! or all, between `from` and `to` (seconds, widened to whole days).
+//!
+//! With a PostgreSQL `DATABASE_URL` and `[quote_history] enabled`, the best
+//! bid, best ask and their sizes of every option book are sampled every
+//! `sample_interval_ms` (default 250); each change is appended to the
+//! `quote_history` table every `flush_interval_seconds` (default 5) and during
+//! graceful shutdown. The `quotes/history` endpoint returns the changes of one
+//! option between `from` and `to` (milliseconds), oldest first.
+//!
+//! Prices and the trading state go through the `db::Repository` trait: the
+//! database when `DATABASE_URL` is set, process memory otherwise. With a
+//! database, the orders, executions, account positions, accounts with their
+//! cash balance, last trades and cash ledger entries changed since the last
+//! save are written to the `orders`, `executions`, `positions`, `accounts`,
+//! `last_trades`, `ledger_entries` and `ledger_postings` tables every
+//! `[persistence] flush_interval_seconds` (default 10) and during graceful
+//! shutdown. Every change of an order's status, filled or remaining quantity
+//! seen by a save is appended to `order_events`, so an order's state history
+//! survives it leaving memory. Stored open orders no longer held in memory,
+//! such as cancelled ones, are marked `canceled`.
+//! When neither a snapshot nor the journal rebuilds the state on startup, the
+//! stored cash ledger, collateral, account positions, last trades and filled
+//! and canceled orders with their fills are read back into memory; stored open
+//! orders lost their books with the restart and are marked `canceled` by the
+//! first save. Each save also stores the market maker controls (kill switch,
+//! global parameters and per-underlying quoting switches) in `system_control`
+//! and `market_maker_configs`; neither snapshots nor the journal carry them,
+//! so they are restored on every startup with a database.
+//!
+//! `DATABASE_URL` selects the backend by scheme: `postgres://` connects to
+//! PostgreSQL (migrations in `migrations/`), `sqlite:` opens a SQLite file,
+//! creating it if needed (migrations in `migrations/sqlite/`), e.g.
+//! `DATABASE_URL=sqlite://data/orderbook.db`, or `sqlite::memory:` for a
+//! throwaway database. SQLite stores prices (inserted and, with
+//! `record_prices`, simulated), the trading state and controls above and OHLC
+//! bars, so a single box keeps them without a database server; quote and
+//! surface history need PostgreSQL, and snapshots go to
+//! `[snapshots] directory`.
 //!
 //! ### WebSocket
 //!
@@ -242,6 +514,12 @@
 //! - `fill` messages - market-maker fills with the captured per-contract
 //!   edge; broadcast to every connected client (not subscription-gated) and
 //!   best-effort — REST executions are authoritative
+//! - `liquidation` messages - steps of a liquidation of the connection's own
+//!   account; private and not subscription-gated
+//! - `hedge` messages - market-maker delta hedges with the resulting hedge
+//!   position, residual delta and hedge P&L; broadcast like `fill`
+//! - `pnl` messages - market-maker P&L attribution of each closed period;
+//!   broadcast like `fill`
 //!
 //! ## Example Usage
 //!
@@ -379,19 +657,32 @@
 //! - **utoipa** (5.4): OpenAPI documentation generation
 //! - **utoipa-swagger-ui** (9.0): Swagger UI integration
 //! - **tokio** (1.49): Async runtime
-//! - **sqlx** (0.8): Database connectivity (PostgreSQL)
+//! - **sqlx** (0.8): Database connectivity (PostgreSQL, SQLite)
 //! - **dashmap** (6.1): Concurrent hash maps
 //! - **serde** (1.0): Serialization/deserialization
 //! - **tracing** (0.1): Structured logging
 //! - **jsonwebtoken** (10.4): JWT signing/verification (RS256, x509 PEM)
 
+pub mod amend;
 pub mod api;
 pub mod auth;
+pub mod clock;
 pub mod config;
+pub mod contract;
 pub mod db;
 pub mod error;
+pub mod journal;
+pub mod ledger;
 pub mod market_maker;
 pub mod models;
 pub mod ohlc;
+pub mod ohlc_store;
+pub mod order_ids;
+pub mod order_store;
+pub mod quote_history;
+pub mod risk;
 pub mod simulation;
+pub mod snapshots;
 pub mod state;
+pub mod surface_history;
+pub mod ticks;
@@ -3,13 +3,21 @@
 //! REST API server for interacting with the Option Chain OrderBook library.
 
 use anyhow::Context;
+use option_chain_orderbook_backend::api::handlers::{
+    record_volatility_surfaces, restore_latest_snapshot, take_snapshot,
+};
+use option_chain_orderbook_backend::api::replay::replay_journal;
 use option_chain_orderbook_backend::api::{build_cors_layer, create_router};
-use option_chain_orderbook_backend::auth::JwtAuth;
+use option_chain_orderbook_backend::auth::{JwtAuth, validate_account_id};
 use option_chain_orderbook_backend::config::{
-    AuthConfig, Config, CorsOriginsSource, resolved_cors_origins,
+    AuthConfig, Config, CorsOriginsSource, JournalFsync, resolved_cors_origins,
+};
+use option_chain_orderbook_backend::db::{
+    DatabasePool, load_state, restore_controls, save_changes,
 };
-use option_chain_orderbook_backend::db::DatabasePool;
+use option_chain_orderbook_backend::journal::{Journal, JournalEvent};
 use option_chain_orderbook_backend::models::Permission;
+use option_chain_orderbook_backend::order_ids::OrderIdPosition;
 use option_chain_orderbook_backend::state::AppState;
 use std::io::Write;
 use std::net::SocketAddr;
@@ -18,34 +26,58 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use option_chain_orderbook_backend::api::account::{
+    AccountBalanceResponse, FundsRequest, LedgerEntriesResponse, LedgerEntryResponse,
+    LedgerPostingResponse, settle_expired_positions,
+};
 use option_chain_orderbook_backend::api::controls::{
-    InsertPriceResponse, InstrumentStatus, InstrumentToggleResponse, InstrumentsListResponse,
-    KillSwitchResponse, LatestPriceResponse, SystemControlResponse, UpdateParametersResponse,
+    HedgeStatusResponse, HedgeTradeResponse, HedgingResponse, InsertPriceResponse,
+    InstrumentStatus, InstrumentToggleResponse, InstrumentsListResponse, KillSwitchResponse,
+    LatestPriceResponse, MakerQueuePositionResponse, PnlAttribution, PnlAttributionResponse,
+    PnlPeriodResponse, PriceHistoryPoint, PriceHistoryResponse, QueuePositionsResponse,
+    RealizedVolatilityStats, RequoteStatsResponse, SystemControlResponse, UnderlyingPnlAttribution,
+    UpdateParametersResponse,
+};
+use option_chain_orderbook_backend::api::liquidation::{
+    Liquidation, LiquidationAction, LiquidationStatus, LiquidationStep, LiquidationsResponse,
+    run_liquidations,
+};
+use option_chain_orderbook_backend::api::margin::{
+    AccountMarginResponse, SetCollateralRequest, UnderlyingMarginResponse,
+    refresh_margin_for_underlying,
+};
+use option_chain_orderbook_backend::api::risk::{
+    ExpirationGreeksResponse, GreekBookResponse, NetGreeks, PortfolioGreeksResponse,
+    ScenarioAnalysisResponse, ScenarioGridSlice, StressScenarioResult, UnderlyingGreeksResponse,
+    UnderlyingPnl, VarEstimateResponse, VarResponse, refresh_all_var,
 };
 use option_chain_orderbook_backend::db::{InsertPriceRequest, UpdateParametersRequest};
 use option_chain_orderbook_backend::error::{ErrorResponse, RateLimitErrorResponse};
+use option_chain_orderbook_backend::ledger::EntryKind;
 use option_chain_orderbook_backend::models::{
     ATMTermStructurePoint, AddOrderRequest, AddOrderResponse, BulkCancelRequest,
     BulkCancelResponse, BulkCancelResultItem, BulkOrderItem, BulkOrderRequest, BulkOrderResponse,
     BulkOrderResultItem, BulkOrderStatus, CancelAllResponse, CancelOrderResponse, ChainStrikeRow,
     CreateSnapshotResponse, DeleteUnderlyingResponse, DepthMetrics, EnrichedSnapshotResponse,
-    ExecutionInfo, ExecutionSummary, ExecutionsListResponse, ExpirationSummary,
+    ExecutionInfo, ExecutionSummary, ExecutionsListResponse, ExpirationSkew, ExpirationSummary,
     ExpirationsListResponse, FillInfo, GlobalStatsResponse, GreeksData, GreeksResponse,
-    HealthResponse, ImpactMetrics, LastTradeResponse, MarketImpactMetrics, MarketOrderRequest,
-    MarketOrderResponse, MarketOrderStatus, ModifyOrderRequest, ModifyOrderResponse,
-    ModifyOrderStatus, OhlcBar, OhlcInterval, OhlcResponse, OptionChainResponse, OptionQuoteData,
-    OptionStyle, OrderBookSnapshotResponse, OrderFillInfo, OrderListResponse, OrderSide,
-    OrderStatus, OrderStatusResponse, OrderTimeInForce, OrderbookMetricsResponse,
-    OrderbookSnapshotInfo, PositionResponse, PositionSummary, PositionsListResponse,
-    PriceLevelInfo, PriceMetrics, QuoteResponse, RestoreSnapshotResponse, SnapshotStats,
-    SnapshotSummary, SnapshotsListResponse, SpreadMetrics, StrikeIV, StrikeSummary,
-    StrikesListResponse, TokenRequest, TokenResponse, UnderlyingSummary, UnderlyingsListResponse,
-    VolatilitySurfaceResponse,
+    HealthResponse, ImpactMetrics, ImpliedForwardResponse, InstrumentSpecResponse,
+    LastTradeResponse, MarketImpactMetrics, MarketOrderRequest, MarketOrderResponse,
+    MarketOrderStatus, ModifyOrderRequest, ModifyOrderResponse, ModifyOrderStatus,
+    OhlcBackfillRequest, OhlcBackfillResponse, OhlcBar, OhlcInterval, OhlcResponse,
+    OptionChainResponse, OptionQuoteData, OptionStyle, OrderBookSnapshotResponse, OrderFillInfo,
+    OrderListResponse, OrderSide, OrderStatus, OrderStatusResponse, OrderTimeInForce,
+    OrderbookMetricsResponse, OrderbookSnapshotInfo, ParityStrikeDiagnostic, PositionResponse,
+    PositionSummary, PositionsListResponse, PriceLevelInfo, PriceMetrics, QuoteHistoryResponse,
+    QuoteResponse, RestoreSnapshotResponse, SnapshotStats, SnapshotSummary, SnapshotTrigger,
+    SnapshotsListResponse, SpreadMetrics, StrikeIV, StrikeSummary, StrikesListResponse,
+    SurfaceSkewPoint, TickBandInfo, TokenRequest, TokenResponse, UnderlyingSummary,
+    UnderlyingsListResponse, VolatilitySurfaceHistoryResponse, VolatilitySurfaceResponse,
 };
 
 /// Interval between background sweeps of expired rate-limit window buckets
@@ -109,17 +141,21 @@ impl utoipa::Modify for SecurityAddon {
         option_chain_orderbook_backend::api::handlers::create_strike,
         option_chain_orderbook_backend::api::handlers::get_strike,
         option_chain_orderbook_backend::api::handlers::get_option_chain,
+        option_chain_orderbook_backend::api::handlers::get_implied_forward,
         option_chain_orderbook_backend::api::handlers::get_volatility_surface,
+        option_chain_orderbook_backend::api::handlers::get_volatility_surface_history,
         option_chain_orderbook_backend::api::handlers::get_option_book,
         option_chain_orderbook_backend::api::handlers::add_order,
         option_chain_orderbook_backend::api::handlers::submit_market_order,
         option_chain_orderbook_backend::api::handlers::cancel_order,
         option_chain_orderbook_backend::api::handlers::modify_order,
         option_chain_orderbook_backend::api::handlers::get_option_quote,
+        option_chain_orderbook_backend::api::handlers::get_instrument_spec,
         option_chain_orderbook_backend::api::handlers::get_option_greeks,
         option_chain_orderbook_backend::api::handlers::get_option_snapshot,
         option_chain_orderbook_backend::api::handlers::get_last_trade,
         option_chain_orderbook_backend::api::handlers::get_ohlc,
+        option_chain_orderbook_backend::api::handlers::get_quote_history,
         option_chain_orderbook_backend::api::handlers::get_orderbook_metrics,
         option_chain_orderbook_backend::api::handlers::list_orders,
         option_chain_orderbook_backend::api::handlers::get_order_status,
@@ -128,8 +164,20 @@ impl utoipa::Modify for SecurityAddon {
         option_chain_orderbook_backend::api::handlers::cancel_all_orders,
         option_chain_orderbook_backend::api::handlers::list_positions,
         option_chain_orderbook_backend::api::handlers::get_position,
+        option_chain_orderbook_backend::api::risk::get_portfolio_greeks,
+        option_chain_orderbook_backend::api::risk::get_risk_scenarios,
+        option_chain_orderbook_backend::api::risk::get_value_at_risk,
+        option_chain_orderbook_backend::api::margin::get_account_margin,
+        option_chain_orderbook_backend::api::margin::set_account_collateral,
+        option_chain_orderbook_backend::api::liquidation::get_liquidation,
+        option_chain_orderbook_backend::api::liquidation::list_liquidations,
+        option_chain_orderbook_backend::api::account::get_account_balance,
+        option_chain_orderbook_backend::api::account::get_account_ledger,
+        option_chain_orderbook_backend::api::account::deposit_funds,
+        option_chain_orderbook_backend::api::account::withdraw_funds,
         option_chain_orderbook_backend::api::handlers::list_executions,
         option_chain_orderbook_backend::api::handlers::get_execution,
+        option_chain_orderbook_backend::api::handlers::backfill_ohlc,
         option_chain_orderbook_backend::api::handlers::create_snapshot,
         option_chain_orderbook_backend::api::handlers::list_snapshots,
         option_chain_orderbook_backend::api::handlers::get_snapshot,
@@ -140,8 +188,13 @@ impl utoipa::Modify for SecurityAddon {
         option_chain_orderbook_backend::api::controls::update_parameters,
         option_chain_orderbook_backend::api::controls::toggle_instrument,
         option_chain_orderbook_backend::api::controls::list_instruments,
+        option_chain_orderbook_backend::api::controls::get_hedging,
+        option_chain_orderbook_backend::api::controls::get_pnl_attribution,
+        option_chain_orderbook_backend::api::controls::get_requote_stats,
+        option_chain_orderbook_backend::api::controls::get_queue_positions,
         option_chain_orderbook_backend::api::controls::insert_price,
         option_chain_orderbook_backend::api::controls::get_latest_price,
+        option_chain_orderbook_backend::api::controls::get_price_history,
         option_chain_orderbook_backend::api::controls::get_all_prices,
         option_chain_orderbook_backend::api::websocket::ws_handler,
     ),
@@ -160,6 +213,8 @@ impl utoipa::Modify for SecurityAddon {
             StrikeSummary,
             OrderBookSnapshotResponse,
             QuoteResponse,
+            InstrumentSpecResponse,
+            TickBandInfo,
             AddOrderRequest,
             AddOrderResponse,
             MarketOrderRequest,
@@ -189,21 +244,54 @@ impl utoipa::Modify for SecurityAddon {
             PositionResponse,
             PositionsListResponse,
             PositionSummary,
+            PortfolioGreeksResponse,
+            GreekBookResponse,
+            UnderlyingGreeksResponse,
+            ExpirationGreeksResponse,
+            NetGreeks,
+            ScenarioAnalysisResponse,
+            ScenarioGridSlice,
+            StressScenarioResult,
+            UnderlyingPnl,
+            VarEstimateResponse,
+            VarResponse,
+            SetCollateralRequest,
+            UnderlyingMarginResponse,
+            AccountMarginResponse,
+            Liquidation,
+            LiquidationStatus,
+            LiquidationAction,
+            LiquidationStep,
+            LiquidationsResponse,
+            FundsRequest,
+            AccountBalanceResponse,
+            LedgerPostingResponse,
+            LedgerEntryResponse,
+            LedgerEntriesResponse,
+            EntryKind,
             EnrichedSnapshotResponse,
             PriceLevelInfo,
             SnapshotStats,
             LastTradeResponse,
             OhlcResponse,
             OhlcBar,
+            OhlcBackfillRequest,
+            OhlcBackfillResponse,
             OhlcInterval,
+            QuoteHistoryResponse,
             OptionChainResponse,
             ChainStrikeRow,
             OptionQuoteData,
+            ImpliedForwardResponse,
+            ParityStrikeDiagnostic,
             GreeksResponse,
             GreeksData,
             VolatilitySurfaceResponse,
             StrikeIV,
             ATMTermStructurePoint,
+            VolatilitySurfaceHistoryResponse,
+            SurfaceSkewPoint,
+            ExpirationSkew,
             OrderbookMetricsResponse,
             SpreadMetrics,
             DepthMetrics,
@@ -220,12 +308,26 @@ impl utoipa::Modify for SecurityAddon {
             InstrumentToggleResponse,
             InstrumentsListResponse,
             InstrumentStatus,
+            HedgingResponse,
+            HedgeStatusResponse,
+            HedgeTradeResponse,
+            PnlAttributionResponse,
+            PnlPeriodResponse,
+            UnderlyingPnlAttribution,
+            PnlAttribution,
+            RequoteStatsResponse,
+            QueuePositionsResponse,
+            MakerQueuePositionResponse,
             InsertPriceRequest,
             InsertPriceResponse,
             LatestPriceResponse,
+            PriceHistoryResponse,
+            PriceHistoryPoint,
+            RealizedVolatilityStats,
             CreateSnapshotResponse,
             SnapshotsListResponse,
             SnapshotSummary,
+            SnapshotTrigger,
             OrderbookSnapshotInfo,
             RestoreSnapshotResponse,
             ErrorResponse,
@@ -249,6 +351,8 @@ impl utoipa::Modify for SecurityAddon {
         (name = "Metrics", description = "Order book metrics and market impact"),
         (name = "Orders", description = "Order status, listing, and bulk operations"),
         (name = "Positions", description = "Position and inventory tracking"),
+        (name = "Risk", description = "Portfolio risk analytics"),
+        (name = "Account", description = "Cash balances and the account ledger"),
         (name = "Executions", description = "Execution reports"),
         (name = "Admin", description = "Administrative endpoints (orderbook snapshots)"),
     ),
@@ -284,6 +388,12 @@ async fn main() -> anyhow::Result<()> {
     if args.get(1).map(String::as_str) == Some("mint-token") {
         return run_mint_token(&args);
     }
+    // CLI subcommand: `replay` rebuilds the state from the latest snapshot and
+    // the journal, prints a summary and exits (no server).
+    let replay_args = match args.get(1).map(String::as_str) {
+        Some("replay") => Some(parse_replay_args(&args)?),
+        _ => None,
+    };
 
     // Load configuration
     let config_path = std::env::var("CONFIG_PATH").unwrap_or_else(|_| "config.toml".to_string());
@@ -311,6 +421,12 @@ async fn main() -> anyhow::Result<()> {
                     warn!("Failed to run migrations: {}", e);
                 }
                 info!("Database connected successfully");
+                if db.postgres().is_none() {
+                    info!(
+                        "SQLite stores prices, the trading state and OHLC bars; quote and \
+                         surface history need PostgreSQL and snapshots a [snapshots] directory"
+                    );
+                }
                 Some(db)
             }
             Err(e) => {
@@ -337,9 +453,31 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
+    // A journal replayed from its start must hand the quotes placed while
+    // the state is built the IDs they had in the recorded run, so start from
+    // the namespace of its first entry.
+    let journal_start = config.as_ref().and_then(|cfg| {
+        let path = match &replay_args {
+            Some(args) => args.journal.clone().or_else(|| cfg.journal.path.clone()),
+            None => cfg
+                .journal
+                .path
+                .clone()
+                .filter(|_| cfg.journal.replay_on_startup),
+        }?;
+        let first = Journal::first(path).ok()??;
+        Some(OrderIdPosition {
+            namespace: first.order_ids.namespace,
+            next: 0,
+        })
+    });
+
     // Create application state and inject the auth core.
     let mut app_state = match config {
-        Some(cfg) => AppState::from_config(cfg, db),
+        Some(cfg) => match journal_start {
+            Some(order_ids) => AppState::from_config_with_order_ids(cfg, db, order_ids),
+            None => AppState::from_config(cfg, db),
+        },
         None => match db {
             Some(database) => AppState::with_database(database),
             None => AppState::new(),
@@ -357,6 +495,132 @@ async fn main() -> anyhow::Result<()> {
     }
     let state = Arc::new(app_state);
 
+    // The market maker controls live only in the database.
+    if state.db.is_some() {
+        match restore_controls(&state).await {
+            Ok(true) => info!(
+                master_enabled = state.market_maker.is_enabled(),
+                "Restored the market maker controls"
+            ),
+            Ok(false) => {}
+            Err(e) => warn!("Failed to restore the market maker controls: {}", e),
+        }
+    }
+
+    // Restore the order books from the newest durable snapshot, if asked to.
+    // The `replay` subcommand always starts from it.
+    let mut restored_snapshot = None;
+    if replay_args.is_some()
+        || state
+            .config
+            .as_ref()
+            .is_some_and(|config| config.snapshots.restore_on_startup)
+    {
+        match restore_latest_snapshot(&state).await {
+            Ok(Some(restored)) => {
+                info!(
+                    snapshot_id = %restored.snapshot_id,
+                    orderbooks = restored.orderbooks_restored,
+                    orders = restored.orders_restored,
+                    failed = restored.orderbooks_failed,
+                    journal_seq = restored.journal_seq,
+                    "Restored the latest snapshot"
+                );
+                restored_snapshot = Some(restored);
+            }
+            Ok(None) => info!("No durable snapshot to restore"),
+            Err(e) => warn!("Failed to read durable snapshots: {}", e),
+        }
+    }
+    let snapshot_seq = restored_snapshot
+        .as_ref()
+        .map_or(0, |restored| restored.journal_seq);
+
+    let journal_config = state
+        .config
+        .as_ref()
+        .map(|config| config.journal.clone())
+        .unwrap_or_default();
+
+    if let Some(replay_args) = replay_args {
+        let path = replay_args
+            .journal
+            .or(journal_config.path)
+            .context("replay needs a journal: set [journal] path or pass --journal")?;
+        let mut entries = Journal::read_after(&path, snapshot_seq)
+            .with_context(|| format!("failed to read journal {path}"))?;
+        if let Some(until) = replay_args.until {
+            entries.retain(|entry| entry.seq <= until);
+        }
+        let summary = replay_journal(&state, &entries, snapshot_seq).await;
+        let report = serde_json::json!({
+            "snapshot_id": restored_snapshot.map(|restored| restored.snapshot_id),
+            "snapshot_journal_seq": snapshot_seq,
+            "applied": summary.applied,
+            "rejected": summary.rejected,
+            "last_seq": summary.last_seq,
+            "orders": state.orders.len(),
+            "executions": state.executions.len(),
+            "positions": state.positions.len(),
+        });
+        // The summary is this command's primary output; logs go to stderr.
+        let mut stdout = std::io::stdout();
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
+
+    // Replay the journal tail after the snapshot, then journal every input
+    // from here on.
+    let mut replayed = false;
+    if let Some(path) = journal_config.path.as_deref() {
+        let (journal, entries) = Journal::open(path, &journal_config, snapshot_seq)
+            .with_context(|| format!("failed to open journal {path}"))?;
+        if journal_config.replay_on_startup {
+            replayed = !entries.is_empty();
+            let summary = replay_journal(&state, &entries, snapshot_seq).await;
+            info!(
+                applied = summary.applied,
+                rejected = summary.rejected,
+                last_seq = summary.last_seq,
+                "Replayed the journal"
+            );
+        }
+        let journal = Arc::new(journal);
+        state.set_journal(Arc::clone(&journal));
+        if let Some(simulator) = &state.price_simulator {
+            simulator.set_journal(journal);
+        }
+        info!(path, fsync = ?journal_config.fsync, "Journal attached");
+    }
+
+    // Without a snapshot or journal to rebuild it from, read the trading state
+    // back from the database.
+    if state.db.is_some() && restored_snapshot.is_none() && !replayed {
+        match load_state(&state).await {
+            Ok(loaded) => info!(
+                accounts = loaded.accounts,
+                orders = loaded.orders,
+                fills = loaded.fills,
+                positions = loaded.positions,
+                last_trades = loaded.last_trades,
+                "Loaded the stored trading state"
+            ),
+            Err(e) => warn!("Failed to load the stored trading state: {}", e),
+        }
+    }
+
+    // Resume the OHLC bars still open when the previous run stopped.
+    if let Some(store) = &state.ohlc_store {
+        let now_secs = u64::try_from(chrono::Utc::now().timestamp()).unwrap_or(0);
+        match store
+            .resume_open_bars(&state.ohlc_aggregator, now_secs)
+            .await
+        {
+            Ok(bars) => info!(bars, "Resumed open OHLC bars"),
+            Err(e) => warn!("Failed to resume open OHLC bars: {}", e),
+        }
+    }
+
     // Shutdown signal shared with every spawned background task. A `watch`
     // channel (tokio is already a dependency) lets each loop `select!` between its
     // periodic work and `changed()`; flipping it to `true` after the server stops
@@ -383,6 +647,37 @@ async fn main() -> anyhow::Result<()> {
         info!("Price simulation started");
     }
 
+    // Flush the journal on a schedule under the interval fsync policy
+    if let Some(journal) = state.journal()
+        && journal_config.fsync == JournalFsync::Interval
+    {
+        let journal = Arc::clone(journal);
+        let mut journal_shutdown = shutdown_rx.clone();
+        let interval_ms = journal_config.fsync_interval_ms;
+        task_handles.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+            loop {
+                tokio::select! {
+                    // Shutdown requested: flush once more, then break.
+                    _ = journal_shutdown.changed() => {
+                        if let Err(e) = journal.sync().await {
+                            error!("failed to flush the journal: {e}");
+                        }
+                        info!("journal flush task shutting down");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        if let Err(e) = journal.sync().await {
+                            error!("failed to flush the journal: {e}");
+                        }
+                    }
+                }
+            }
+        }));
+        info!("Journal flush task started (interval: {}ms)", interval_ms);
+    }
+
     // Start order cleanup task
     if let Some(ref config) = state.config {
         let state_clone = Arc::clone(&state);
@@ -405,7 +700,20 @@ async fn main() -> anyhow::Result<()> {
                             break;
                         }
                         _ = interval.tick() => {
-                            state_clone.cleanup_old_orders(retention_secs);
+                            let _guard = state_clone.mutation_guard().await;
+                            let now = chrono::Utc::now();
+                            let event = JournalEvent::OrderCleanup {
+                                now_ms: now.timestamp_millis(),
+                                max_age_secs: retention_secs,
+                            };
+                            if let Err(e) = state_clone
+                                .apply_journaled(event, || {
+                                    state_clone.cleanup_old_orders(now, retention_secs)
+                                })
+                                .await
+                            {
+                                error!("failed to journal an order cleanup sweep: {e}");
+                            }
                         }
                     }
                 }
@@ -417,6 +725,372 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Take scheduled snapshots, and a final one on shutdown
+    if let Some(ref config) = state.config {
+        let interval_secs = config.snapshots.interval_seconds;
+        let on_shutdown = config.snapshots.on_shutdown;
+        if interval_secs > 0 || on_shutdown {
+            let state_clone = Arc::clone(&state);
+            let mut snapshot_shutdown = shutdown_rx.clone();
+            task_handles.push(tokio::spawn(async move {
+                // A zero interval leaves only the shutdown snapshot.
+                let mut interval = (interval_secs > 0)
+                    .then(|| tokio::time::interval(Duration::from_secs(interval_secs)));
+                // Skip the first immediate tick
+                if let Some(interval) = interval.as_mut() {
+                    interval.tick().await;
+                }
+
+                loop {
+                    tokio::select! {
+                        // Shutdown requested: snapshot the final state, then break.
+                        _ = snapshot_shutdown.changed() => {
+                            if on_shutdown {
+                                let taken =
+                                    take_snapshot(&state_clone, SnapshotTrigger::Shutdown).await;
+                                log_snapshot(SnapshotTrigger::Shutdown, &taken);
+                            }
+                            info!("snapshot task shutting down");
+                            break;
+                        }
+                        Some(_) = async { Some(interval.as_mut()?.tick().await) },
+                            if interval.is_some() => {
+                            let taken =
+                                take_snapshot(&state_clone, SnapshotTrigger::Scheduled).await;
+                            log_snapshot(SnapshotTrigger::Scheduled, &taken);
+                        }
+                    }
+                }
+            }));
+            info!(
+                "Snapshot task started (interval: {}s, on shutdown: {})",
+                interval_secs, on_shutdown
+            );
+        }
+    }
+
+    // Flush OHLC history on a schedule, and once more on shutdown
+    if let (Some(store), Some(config)) = (&state.ohlc_store, &state.config) {
+        let store = Arc::clone(store);
+        let aggregator = Arc::clone(&state.ohlc_aggregator);
+        let interval_secs = config.ohlc.flush_interval_seconds;
+        let mut ohlc_shutdown = shutdown_rx.clone();
+        task_handles.push(tokio::spawn(async move {
+            // A zero interval leaves only the shutdown flush.
+            let mut interval = (interval_secs > 0)
+                .then(|| tokio::time::interval(Duration::from_secs(interval_secs)));
+            // Skip the first immediate tick
+            if let Some(interval) = interval.as_mut() {
+                interval.tick().await;
+            }
+
+            loop {
+                tokio::select! {
+                    // Shutdown requested: flush once more, then break.
+                    _ = ohlc_shutdown.changed() => {
+                        if let Err(e) = store.flush(&aggregator).await {
+                            error!("failed to flush OHLC history: {e}");
+                        }
+                        info!("OHLC flush task shutting down");
+                        break;
+                    }
+                    Some(_) = async { Some(interval.as_mut()?.tick().await) },
+                        if interval.is_some() => {
+                        match store.flush(&aggregator).await {
+                            Ok(flushed) => debug!(bars = flushed.bars, "flushed OHLC history"),
+                            Err(e) => error!("failed to flush OHLC history: {e}"),
+                        }
+                    }
+                }
+            }
+        }));
+        info!("OHLC flush task started (interval: {}s)", interval_secs);
+    }
+
+    // Save the changed trading state on a schedule, and once more on shutdown
+    if let (Some(_), Some(config)) = (&state.db, &state.config) {
+        let state_clone = Arc::clone(&state);
+        let interval_secs = config.persistence.flush_interval_seconds;
+        let mut persistence_shutdown = shutdown_rx.clone();
+        task_handles.push(tokio::spawn(async move {
+            let mut since_ms = 0;
+            // A zero interval leaves only the shutdown save.
+            let mut interval = (interval_secs > 0)
+                .then(|| tokio::time::interval(Duration::from_secs(interval_secs)));
+
+            loop {
+                tokio::select! {
+                    // Shutdown requested: save once more, then break.
+                    _ = persistence_shutdown.changed() => {
+                        save_trading_state(&state_clone, &mut since_ms).await;
+                        info!("persistence task shutting down");
+                        break;
+                    }
+                    Some(_) = async { Some(interval.as_mut()?.tick().await) },
+                        if interval.is_some() => {
+                        save_trading_state(&state_clone, &mut since_ms).await;
+                    }
+                }
+            }
+        }));
+        info!("Persistence task started (interval: {}s)", interval_secs);
+    }
+
+    // Sample top-of-book changes, flushing them on a schedule and once more on
+    // shutdown
+    if let (Some(recorder), Some(config)) = (&state.quote_recorder, &state.config) {
+        let recorder = Arc::clone(recorder);
+        let manager = Arc::clone(&state.manager);
+        let sample_ms = config.quote_history.sample_interval_ms;
+        let flush_secs = config.quote_history.flush_interval_seconds;
+        let mut quote_shutdown = shutdown_rx.clone();
+        task_handles.push(tokio::spawn(async move {
+            let mut sample = tokio::time::interval(Duration::from_millis(sample_ms));
+            sample.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            // A zero flush interval leaves only the shutdown flush.
+            let mut flush =
+                (flush_secs > 0).then(|| tokio::time::interval(Duration::from_secs(flush_secs)));
+            // Skip the first immediate flush tick
+            if let Some(flush) = flush.as_mut() {
+                flush.tick().await;
+            }
+
+            loop {
+                tokio::select! {
+                    // Shutdown requested: take a last sample, flush, then break.
+                    _ = quote_shutdown.changed() => {
+                        recorder.sample(&manager);
+                        if let Err(e) = recorder.flush().await {
+                            error!("failed to flush quote history: {e}");
+                        }
+                        info!("quote history task shutting down");
+                        break;
+                    }
+                    _ = sample.tick() => {
+                        recorder.sample(&manager);
+                    }
+                    Some(_) = async { Some(flush.as_mut()?.tick().await) },
+                        if flush.is_some() => {
+                        match recorder.flush().await {
+                            Ok(rows) => debug!(rows, "flushed quote history"),
+                            Err(e) => error!("failed to flush quote history: {e}"),
+                        }
+                    }
+                }
+            }
+        }));
+        info!(
+            "Quote history task started (sample: {}ms, flush: {}s)",
+            sample_ms, flush_secs
+        );
+    }
+
+    // Store the volatility surface of every underlying on a schedule
+    if let (Some(_), Some(config)) = (&state.surface_store, &state.config) {
+        let interval_secs = config.surface_history.interval_seconds;
+        if interval_secs > 0 {
+            let state_clone = Arc::clone(&state);
+            let mut surface_shutdown = shutdown_rx.clone();
+            task_handles.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+                loop {
+                    tokio::select! {
+                        // Shutdown requested: break so the task can be awaited.
+                        _ = surface_shutdown.changed() => {
+                            info!("surface history task shutting down");
+                            break;
+                        }
+                        _ = interval.tick() => {
+                            // A sweep over many strikes can take a while, so a
+                            // shutdown abandons it instead of waiting.
+                            tokio::select! {
+                                stored = record_volatility_surfaces(&state_clone) => {
+                                    debug!(stored, "stored volatility surfaces");
+                                }
+                                _ = surface_shutdown.changed() => {
+                                    info!("surface history task shutting down");
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+            }));
+            info!(
+                "Surface history task started (interval: {}s)",
+                interval_secs
+            );
+        }
+    }
+
+    // Close market-maker P&L attribution periods on a schedule
+    if let Some(ref config) = state.config {
+        let period_secs = config.market_maker.pnl.period_seconds;
+        if period_secs > 0 {
+            let mm = Arc::clone(&state.market_maker);
+            let mut pnl_shutdown = shutdown_rx.clone();
+            task_handles.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(period_secs));
+                // The first tick fires immediately; skip it so the first
+                // period spans a full interval.
+                interval.tick().await;
+
+                loop {
+                    tokio::select! {
+                        // Shutdown requested: break so the task can be awaited.
+                        _ = pnl_shutdown.changed() => {
+                            info!("P&L attribution task shutting down");
+                            break;
+                        }
+                        _ = interval.tick() => {
+                            mm.close_pnl_period();
+                        }
+                    }
+                }
+            }));
+            info!("P&L attribution task started (period: {}s)", period_secs);
+        }
+    }
+
+    // Start the expiry settlement task
+    if let Some(ref config) = state.config {
+        let interval_secs = config.ledger.settlement_interval_seconds;
+        if interval_secs > 0 {
+            let state_clone = Arc::clone(&state);
+            let mut settlement_shutdown = shutdown_rx.clone();
+            task_handles.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+                loop {
+                    tokio::select! {
+                        // Shutdown requested: break so the task can be awaited.
+                        _ = settlement_shutdown.changed() => {
+                            info!("expiry settlement task shutting down");
+                            break;
+                        }
+                        _ = interval.tick() => {
+                            let _guard = state_clone.mutation_guard().await;
+                            let now = chrono::Utc::now();
+                            let event = JournalEvent::Settlement {
+                                now_ms: now.timestamp_millis(),
+                            };
+                            match state_clone
+                                .apply_journaled(event, || settle_expired_positions(&state_clone, now))
+                                .await
+                            {
+                                Ok(0) => {}
+                                Ok(settled) => info!(settled, "settled expired positions"),
+                                Err(e) => error!("failed to journal a settlement sweep: {e}"),
+                            }
+                        }
+                    }
+                }
+            }));
+            info!(
+                "Expiry settlement task started (interval: {}s)",
+                interval_secs
+            );
+        }
+    }
+
+    // Re-margin accounts as simulated prices move
+    if let (Some(config), Some(simulator)) = (&state.config, &state.price_simulator)
+        && config.risk.margin.enabled
+    {
+        let state_clone = Arc::clone(&state);
+        let mut price_rx = simulator.subscribe();
+        let mut margin_shutdown = shutdown_rx.clone();
+        task_handles.push(tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    // Shutdown requested: break so the task can be awaited.
+                    _ = margin_shutdown.changed() => {
+                        info!("margin refresh task shutting down");
+                        break;
+                    }
+                    update = price_rx.recv() => match update {
+                        Ok(update) => {
+                            refresh_margin_for_underlying(&state_clone, &update.symbol);
+                        }
+                        // A lagging receiver only skipped intermediate prices;
+                        // the next update re-margins on the latest one.
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            }
+        }));
+        info!("Margin refresh task started");
+    }
+
+    // Start the liquidation task
+    if let Some(ref config) = state.config
+        && config.risk.liquidation.enabled
+    {
+        let interval_secs = config.risk.liquidation.interval_seconds;
+        let state_clone = Arc::clone(&state);
+        let mut liquidation_shutdown = shutdown_rx.clone();
+        task_handles.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+            loop {
+                tokio::select! {
+                    // Shutdown requested: break so the task can be awaited.
+                    _ = liquidation_shutdown.changed() => {
+                        info!("liquidation task shutting down");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        let _guard = state_clone.mutation_guard().await;
+                        match state_clone
+                            .apply_journaled_async(JournalEvent::Liquidation, run_liquidations(&state_clone))
+                            .await
+                        {
+                            Ok(0) => {}
+                            Ok(liquidating) => warn!(liquidating, "accounts under liquidation"),
+                            Err(e) => error!("failed to journal a liquidation sweep: {e}"),
+                        }
+                    }
+                }
+            }
+        }));
+        info!("Liquidation task started (interval: {}s)", interval_secs);
+    }
+
+    // Start the scheduled VaR recomputation task
+    if let Some(ref config) = state.config {
+        let interval_secs = config.risk.var.interval_seconds;
+        if interval_secs > 0 {
+            let state_clone = Arc::clone(&state);
+            let mut var_shutdown = shutdown_rx.clone();
+            task_handles.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+                // Skip the first immediate tick
+                interval.tick().await;
+
+                loop {
+                    tokio::select! {
+                        // Shutdown requested: break so the task can be awaited.
+                        _ = var_shutdown.changed() => {
+                            info!("VaR recomputation task shutting down");
+                            break;
+                        }
+                        _ = interval.tick() => {
+                            let accounts = refresh_all_var(&state_clone).await;
+                            tracing::debug!(accounts, "recomputed VaR");
+                        }
+                    }
+                }
+            }));
+            info!(
+                "VaR recomputation task started (interval: {}s)",
+                interval_secs
+            );
+        }
+    }
+
     // Start the rate-limit window sweep task. It periodically reaps fully-expired
     // buckets so the window map cannot accumulate one entry per distinct subject
     // or peer IP forever (issue #48). It now shares the same `watch` shutdown
@@ -531,6 +1205,53 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Logs the outcome of an automatic snapshot, warning when it is partial.
+fn log_snapshot(trigger: SnapshotTrigger, taken: &CreateSnapshotResponse) {
+    if taken.success {
+        info!(
+            trigger = trigger.as_str(),
+            snapshot_id = %taken.snapshot_id,
+            orderbooks = taken.orderbooks_saved,
+            orders = taken.orders_saved,
+            persisted = taken.persisted,
+            "snapshot taken"
+        );
+    } else {
+        warn!(
+            trigger = trigger.as_str(),
+            snapshot_id = %taken.snapshot_id,
+            orderbooks = taken.orderbooks_saved,
+            failed = taken.orderbooks_failed,
+            persisted = taken.persisted,
+            "partial snapshot taken"
+        );
+    }
+}
+
+/// Saves the trading state changed at or after `since_ms` to the repository,
+/// advancing `since_ms` once the save succeeds.
+async fn save_trading_state(state: &AppState, since_ms: &mut u64) {
+    let now_ms = u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or(0);
+    match save_changes(state, *since_ms, now_ms).await {
+        Ok(saved) => {
+            // Overlap the next save by a second so a change stamped just
+            // before this one started but written after it was read is not
+            // missed; saves are idempotent.
+            *since_ms = now_ms.saturating_sub(1_000);
+            debug!(
+                orders = saved.orders,
+                order_events = saved.order_events,
+                fills = saved.fills,
+                positions = saved.positions,
+                accounts = saved.accounts,
+                last_trades = saved.last_trades,
+                "saved trading state"
+            );
+        }
+        Err(e) => error!("failed to save trading state: {e}"),
+    }
+}
+
 /// Resolves when the process is asked to shut down: Ctrl-C (SIGINT) on any
 /// platform, or SIGTERM on Unix. Passed to `axum::serve(...).with_graceful_shutdown`
 /// so in-flight requests can drain before the listener stops accepting.
@@ -610,13 +1331,59 @@ fn parse_permissions(spec: &str) -> anyhow::Result<Vec<Permission>> {
         .collect()
 }
 
+/// Arguments of the `replay` CLI subcommand.
+struct ReplayArgs {
+    /// Journal to replay instead of the configured one.
+    journal: Option<String>,
+    /// Last sequence to apply; the whole tail when `None`.
+    until: Option<u64>,
+}
+
+/// Parses the `replay` CLI subcommand.
+///
+/// Usage: `replay [--journal <path>] [--until <seq>]`.
+fn parse_replay_args(args: &[String]) -> anyhow::Result<ReplayArgs> {
+    let mut replay = ReplayArgs {
+        journal: None,
+        until: None,
+    };
+
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--journal" | "-j" => {
+                i += 1;
+                replay.journal = match args.get(i) {
+                    Some(v) => Some(v.clone()),
+                    None => return Err(anyhow::anyhow!("--journal requires a value")),
+                };
+            }
+            "--until" | "-u" => {
+                i += 1;
+                replay.until = match args.get(i) {
+                    Some(v) => Some(
+                        v.parse()
+                            .map_err(|_| anyhow::anyhow!("invalid --until value: {v}"))?,
+                    ),
+                    None => return Err(anyhow::anyhow!("--until requires a value")),
+                };
+            }
+            other => return Err(anyhow::anyhow!("unknown argument: {other}")),
+        }
+        i += 1;
+    }
+    Ok(replay)
+}
+
 /// Runs the `mint-token` CLI subcommand: signs a JWT offline using the private
 /// key and writes it to stdout, without starting the server.
 ///
-/// Usage: `mint-token [--permissions read,trade,admin] [--ttl <seconds>]`.
+/// Usage: `mint-token [--permissions read,trade,admin] [--ttl <seconds>]
+/// [--account <id>]`.
 fn run_mint_token(args: &[String]) -> anyhow::Result<()> {
     let mut permissions_arg: Option<String> = None;
     let mut ttl_arg: Option<u64> = None;
+    let mut account_arg: Option<String> = None;
 
     let mut i = 2;
     while i < args.len() {
@@ -635,6 +1402,13 @@ fn run_mint_token(args: &[String]) -> anyhow::Result<()> {
                     None => return Err(anyhow::anyhow!("--ttl requires a value")),
                 };
             }
+            "--account" | "-a" => {
+                i += 1;
+                account_arg = match args.get(i) {
+                    Some(v) => Some(v.clone()),
+                    None => return Err(anyhow::anyhow!("--account requires a value")),
+                };
+            }
             other => return Err(anyhow::anyhow!("unknown argument: {other}")),
         }
         i += 1;
@@ -648,9 +1422,15 @@ fn run_mint_token(args: &[String]) -> anyhow::Result<()> {
     let auth = load_jwt_auth(config.as_ref())?;
 
     let ttl_secs = ttl_arg.unwrap_or_else(|| auth.default_ttl_secs());
-    let (token, _exp) = auth
-        .mint_token(permissions, ttl_secs)
-        .map_err(|e| anyhow::anyhow!("failed to mint token: {e}"))?;
+    let minted = match account_arg {
+        Some(account) => {
+            validate_account_id(&account)
+                .map_err(|e| anyhow::anyhow!("invalid --account value: {e}"))?;
+            auth.mint_token_for(account, permissions, ttl_secs)
+        }
+        None => auth.mint_token(permissions, ttl_secs),
+    };
+    let (token, _exp) = minted.map_err(|e| anyhow::anyhow!("failed to mint token: {e}"))?;
 
     // The minted token is this command's primary output (intended provisioning
     // output, not logging) — write it to stdout.
@@ -0,0 +1,345 @@
+//! P&L attribution of the market maker's book.
+//!
+//! Every fill contributes its captured edge against the quote-time theo. The
+//! option inventory is re-marked whenever spot moves (and before the inventory
+//! changes), and the move since the previous mark is explained with a
+//! second-order Taylor expansion using the greeks at the previous mark:
+//!
+//! * `delta` — Σ q·Δ·dS
+//! * `gamma` — Σ ½·q·Γ·dS²
+//! * `vega` — Σ q·vega·dσ (dσ in vol points, from marking-vol moves)
+//! * `theta` — Σ q·θ·dt (dt in calendar days)
+//!
+//! `hedging` is the change in the delta hedge's marked P&L, so `delta` plus
+//! `hedging` is the directional P&L left after hedging. All amounts are cents.
+
+use crate::market_maker::OptionPricer;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ops::AddAssign;
+
+/// Number of closed attribution periods kept in memory.
+pub const MAX_PNL_PERIODS: usize = 96;
+
+/// Milliseconds per calendar day, for theta accrual.
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// P&L broken down by source, in cents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnlComponents {
+    /// Spread captured on fills against the quote-time theo.
+    pub edge: f64,
+    /// First-order P&L from spot moves.
+    pub delta: f64,
+    /// Second-order P&L from spot moves.
+    pub gamma: f64,
+    /// P&L from marking-vol moves.
+    pub vega: f64,
+    /// Time decay.
+    pub theta: f64,
+    /// P&L of the delta hedge.
+    pub hedging: f64,
+}
+
+impl PnlComponents {
+    /// Sum of every component.
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.edge + self.delta + self.gamma + self.vega + self.theta + self.hedging
+    }
+}
+
+impl AddAssign for PnlComponents {
+    fn add_assign(&mut self, other: Self) {
+        self.edge += other.edge;
+        self.delta += other.delta;
+        self.gamma += other.gamma;
+        self.vega += other.vega;
+        self.theta += other.theta;
+        self.hedging += other.hedging;
+    }
+}
+
+/// Attributed P&L over one period, per underlying.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnlPeriod {
+    /// Period start in milliseconds.
+    pub start_ms: u64,
+    /// Period end in milliseconds, `None` while the period is open.
+    pub end_ms: Option<u64>,
+    /// P&L per underlying, ordered by symbol.
+    pub by_underlying: BTreeMap<String, PnlComponents>,
+}
+
+/// Market state the inventory of one underlying was last marked at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlMark {
+    /// Spot price in cents.
+    pub spot: u64,
+    /// Marking volatility (e.g. `0.3` for 30%).
+    pub vol: f64,
+    /// Mark time in milliseconds.
+    pub at_ms: u64,
+    /// Hedge P&L in cents at the mark.
+    pub hedge_pnl: f64,
+}
+
+/// One signed option position to be attributed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttributedPosition {
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Expiration of the option.
+    pub expiration: ExpirationDate,
+    /// Call or Put.
+    pub style: OptionStyle,
+    /// Net quantity (positive = long, negative = short).
+    pub quantity: i64,
+    /// Units of the underlying per contract.
+    pub multiplier: u64,
+}
+
+/// Explains the move from `from` to `to` for `positions` with the greeks at
+/// `from`. Positions whose greeks are non-finite are skipped, and so is the
+/// hedge term, which is filled in by the caller.
+#[must_use]
+pub fn greek_pnl(
+    pricer: &OptionPricer,
+    positions: &[AttributedPosition],
+    from: &PnlMark,
+    to: &PnlMark,
+) -> PnlComponents {
+    let s = from.spot as f64 / 100.0;
+    let ds = (to.spot as f64 - from.spot as f64) / 100.0;
+    let dvol_pts = (to.vol - from.vol) * 100.0;
+    let dt_days = to.at_ms.saturating_sub(from.at_ms) as f64 / MS_PER_DAY;
+    let iv = Some(from.vol);
+
+    let mut pnl = PnlComponents::default();
+    for p in positions.iter().filter(|p| p.quantity != 0) {
+        let k = p.strike as f64 / 100.0;
+        let q = p.quantity as f64 * p.multiplier as f64;
+        let delta = pricer.delta(s, k, &p.expiration, p.style, iv);
+        let gamma = pricer.gamma(s, k, &p.expiration, iv);
+        let vega = pricer.vega(s, k, &p.expiration, iv);
+        let theta = pricer.theta(s, k, &p.expiration, p.style, iv);
+        if ![delta, gamma, vega, theta].iter().all(|g| g.is_finite()) {
+            continue;
+        }
+        // Greeks are in dollars; attribution is in cents.
+        pnl.delta += q * delta * ds * 100.0;
+        pnl.gamma += 0.5 * q * gamma * ds * ds * 100.0;
+        pnl.vega += q * vega * dvol_pts * 100.0;
+        pnl.theta += q * theta * dt_days * 100.0;
+    }
+    pnl
+}
+
+/// Accumulates attributed P&L since start-up and per period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlAttributor {
+    /// When attribution started, in milliseconds.
+    started_ms: u64,
+    /// Last mark per underlying.
+    marks: HashMap<String, PnlMark>,
+    /// P&L since start-up per underlying.
+    cumulative: BTreeMap<String, PnlComponents>,
+    /// The open period.
+    current: PnlPeriod,
+    /// Closed periods, oldest first, capped at [`MAX_PNL_PERIODS`].
+    history: VecDeque<PnlPeriod>,
+}
+
+impl PnlAttributor {
+    /// Starts attribution at `now_ms` with an empty open period.
+    #[must_use]
+    pub fn new(now_ms: u64) -> Self {
+        Self {
+            started_ms: now_ms,
+            marks: HashMap::new(),
+            cumulative: BTreeMap::new(),
+            current: PnlPeriod {
+                start_ms: now_ms,
+                ..PnlPeriod::default()
+            },
+            history: VecDeque::new(),
+        }
+    }
+
+    /// When attribution started, in milliseconds.
+    #[must_use]
+    pub fn started_ms(&self) -> u64 {
+        self.started_ms
+    }
+
+    /// Last mark of `underlying`, if it was ever marked.
+    #[must_use]
+    pub fn mark(&self, underlying: &str) -> Option<PnlMark> {
+        self.marks.get(underlying).copied()
+    }
+
+    /// Records `mark` as the latest state of `underlying`.
+    pub fn set_mark(&mut self, underlying: &str, mark: PnlMark) {
+        self.marks.insert(underlying.to_string(), mark);
+    }
+
+    /// Adds `pnl` to `underlying` in both the cumulative totals and the open
+    /// period.
+    pub fn record(&mut self, underlying: &str, pnl: PnlComponents) {
+        *self.cumulative.entry(underlying.to_string()).or_default() += pnl;
+        *self
+            .current
+            .by_underlying
+            .entry(underlying.to_string())
+            .or_default() += pnl;
+    }
+
+    /// P&L since start-up per underlying.
+    #[must_use]
+    pub fn cumulative(&self) -> &BTreeMap<String, PnlComponents> {
+        &self.cumulative
+    }
+
+    /// The open period.
+    #[must_use]
+    pub fn current(&self) -> &PnlPeriod {
+        &self.current
+    }
+
+    /// Up to `limit` closed periods, newest first.
+    #[must_use]
+    pub fn history(&self, limit: usize) -> Vec<PnlPeriod> {
+        self.history.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Closes the open period at `now_ms`, starts the next one and returns
+    /// the closed period.
+    pub fn close_period(&mut self, now_ms: u64) -> PnlPeriod {
+        let next = PnlPeriod {
+            start_ms: now_ms,
+            ..PnlPeriod::default()
+        };
+        let mut closed = std::mem::replace(&mut self.current, next);
+        closed.end_ms = Some(now_ms);
+        if self.history.len() == MAX_PNL_PERIODS {
+            self.history.pop_front();
+        }
+        self.history.push_back(closed.clone());
+        closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn call(quantity: i64) -> AttributedPosition {
+        AttributedPosition {
+            strike: 5_000_000,
+            expiration: ExpirationDate::DateTime(
+                Utc.with_ymd_and_hms(2035, 12, 31, 16, 0, 0)
+                    .single()
+                    .expect("valid fixture datetime"),
+            ),
+            style: OptionStyle::Call,
+            quantity,
+            multiplier: 1,
+        }
+    }
+
+    fn mark(spot: u64, vol: f64, at_ms: u64) -> PnlMark {
+        PnlMark {
+            spot,
+            vol,
+            at_ms,
+            hedge_pnl: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_greek_pnl_explains_spot_vol_and_time() {
+        let pricer = OptionPricer::default();
+        let from = mark(5_000_000, 0.3, 0);
+        let to = mark(5_100_000, 0.32, 86_400_000);
+
+        let long = greek_pnl(&pricer, &[call(2)], &from, &to);
+        assert!(long.delta > 0.0, "long calls gain on a rally");
+        assert!(long.gamma > 0.0, "long options are long gamma");
+        assert!(long.vega > 0.0, "long options are long vega");
+        assert!(long.theta < 0.0, "long options decay");
+        assert_eq!(long.edge, 0.0);
+        assert_eq!(long.hedging, 0.0);
+
+        // The expansion approximates a full revaluation.
+        let exp = &call(1).expiration;
+        let value =
+            |s: f64, v: f64| pricer.theoretical_value(s, 50_000.0, exp, OptionStyle::Call, Some(v));
+        let actual = 2.0 * (value(51_000.0, 0.3) - value(50_000.0, 0.3)) * 100.0;
+        let explained = long.delta + long.gamma;
+        assert!(
+            (actual - explained).abs() < actual.abs() * 0.01,
+            "explained {explained} vs actual {actual}"
+        );
+
+        let short = greek_pnl(&pricer, &[call(-2)], &from, &to);
+        assert!((short.total() + long.total()).abs() < 1e-6);
+
+        let hundreds = AttributedPosition {
+            multiplier: 100,
+            ..call(2)
+        };
+        let scaled = greek_pnl(&pricer, &[hundreds], &from, &to);
+        assert!((scaled.total() - 100.0 * long.total()).abs() < 1e-6 * scaled.total().abs());
+    }
+
+    #[test]
+    fn test_unchanged_market_attributes_nothing() {
+        let pricer = OptionPricer::default();
+        let at = mark(5_000_000, 0.3, 1_000);
+        assert_eq!(
+            greek_pnl(&pricer, &[call(5)], &at, &at),
+            PnlComponents::default()
+        );
+    }
+
+    #[test]
+    fn test_periods_roll_and_cumulative_persists() {
+        let mut attributor = PnlAttributor::new(100);
+        attributor.record(
+            "BTC",
+            PnlComponents {
+                edge: 50.0,
+                ..PnlComponents::default()
+            },
+        );
+        let closed = attributor.close_period(200);
+        assert_eq!(closed.start_ms, 100);
+        assert_eq!(closed.end_ms, Some(200));
+        assert_eq!(closed.by_underlying["BTC"].edge, 50.0);
+
+        attributor.record(
+            "BTC",
+            PnlComponents {
+                delta: -20.0,
+                ..PnlComponents::default()
+            },
+        );
+        assert_eq!(attributor.current().start_ms, 200);
+        assert_eq!(attributor.current().by_underlying["BTC"].total(), -20.0);
+        assert_eq!(attributor.cumulative()["BTC"].total(), 30.0);
+        assert_eq!(attributor.history(10), vec![closed]);
+    }
+
+    #[test]
+    fn test_period_history_is_capped() {
+        let mut attributor = PnlAttributor::new(0);
+        for i in 1..=MAX_PNL_PERIODS as u64 + 3 {
+            attributor.close_period(i);
+        }
+        let history = attributor.history(usize::MAX);
+        assert_eq!(history.len(), MAX_PNL_PERIODS);
+        assert_eq!(history[0].end_ms, Some(MAX_PNL_PERIODS as u64 + 3));
+    }
+}
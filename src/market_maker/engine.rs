@@ -1,13 +1,26 @@
 //! Market maker engine that coordinates quoting across all instruments.
 
+use crate::amend::{QueuePosition, amend_order, queue_position};
+use crate::clock;
+use crate::contract::ContractSpec;
 use crate::db::DatabasePool;
-use crate::market_maker::{OptionPricer, QuoteInput, Quoter};
+use crate::market_maker::strategy::BookState;
+use crate::market_maker::{
+    AttributedPosition, DeltaHedger, HedgeBook, HedgeParams, HedgeTrade, LadderLevel, OptionPricer,
+    PnlAttributor, PnlComponents, PnlMark, PnlPeriod, Quoter, QuotingStrategy, RequoteDecision,
+    RequoteParams, RequoteStats, RequoteThrottle, SingleLevelStrategy, StrategyInput, greek_pnl,
+    hedge_quantity,
+};
+use crate::order_ids::OrderIdSource;
+use crate::ticks::TickTable;
 use chrono::{DateTime, Utc};
-use option_chain_orderbook::orderbook::UnderlyingOrderBookManager;
+use option_chain_orderbook::orderbook::{OptionOrderBook, UnderlyingOrderBookManager};
 use optionstratlib::prelude::Positive;
 use optionstratlib::{ExpirationDate, OptionStyle};
 use orderbook_rs::{OrderId, Side};
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::broadcast;
@@ -144,11 +157,86 @@ pub enum MarketMakerEvent {
         /// Price in cents.
         price_cents: u64,
     },
+    /// Delta hedge executed in the underlying.
+    HedgeExecuted {
+        /// Underlying symbol.
+        symbol: String,
+        /// Signed quantity of the underlying (positive = bought).
+        quantity: f64,
+        /// Execution price in cents.
+        price: u64,
+        /// Hedge position after the trade.
+        hedge_position: f64,
+        /// Net option delta plus hedge position after the trade.
+        residual_delta: f64,
+        /// Hedge P&L in cents, marked at the execution price.
+        hedge_pnl: i64,
+    },
+    /// A P&L attribution period was closed.
+    PnlPeriodClosed {
+        /// The closed period.
+        period: PnlPeriod,
+    },
+}
+
+/// Hedge status of one underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeStatus {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents, if known.
+    pub spot: Option<u64>,
+    /// Net delta of the maker's option inventory.
+    pub option_delta: f64,
+    /// Signed position in the underlying held as a hedge.
+    pub hedge_position: f64,
+    /// Option delta plus hedge position.
+    pub residual_delta: f64,
+    /// Hedge P&L in cents, marked at spot (zero without a spot price).
+    pub hedge_pnl: i64,
+    /// Number of hedge trades executed.
+    pub trades: u64,
+}
+
+/// Queue position of one resting maker order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MakerQueuePosition {
+    /// Canonical instrument identifier (`UNDERLYING-YYYYMMDD-STRIKE-STYLE`).
+    pub instrument: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// True for the bid leg, false for the ask leg.
+    pub is_buy: bool,
+    /// Place of the order in its price level.
+    pub position: QueuePosition,
+}
+
+/// Net position the market maker holds in one instrument, accumulated from
+/// fills on its own quotes.
+///
+/// This is the maker's book, kept separate from any trading account so risk
+/// views can report it on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryPosition {
+    /// Canonical `UNDERLYING-YYYYMMDD-STRIKE-STYLE` identifier.
+    pub instrument: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Structural expiration of the instrument's book.
+    pub expiration: ExpirationDate,
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Call or Put.
+    pub style: OptionStyle,
+    /// Net quantity (positive = long, negative = short).
+    pub quantity: i64,
+    /// Units of the underlying per contract.
+    pub multiplier: u64,
 }
 
 /// A market-maker order resting on a book, tracked for cancel-on-requote and
 /// fill detection (issue #69).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ActiveOrderInfo {
     /// Underlying symbol.
     symbol: String,
@@ -187,7 +275,7 @@ struct ActiveOrderInfo {
 /// `ExpKey` mirrors the two `ExpirationDate` representations structurally: the
 /// relative day count (`Positive`, `NaN`-free by construction) or the absolute
 /// UTC instant. Both are `Eq + Hash` and never touch the clock.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 enum ExpKey {
     /// Relative expiration measured in fractional days.
     Days(Positive),
@@ -212,7 +300,7 @@ impl From<&ExpirationDate> for ExpKey {
 ///
 /// Clock-independent by construction (see [`ExpKey`]), so a `Days`-variant
 /// expiration keys the same on every tick.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 struct InstrumentKey {
     /// Underlying symbol.
     symbol: String,
@@ -269,15 +357,82 @@ struct RequoteContext<'a> {
     spot_cents: u64,
     /// Market-maker configuration snapshot for this requote pass.
     config: &'a MarketMakerConfig,
+    /// Strategy quoting the underlying, resolved once per pass.
+    strategy: &'a dyn QuotingStrategy,
+    /// Volatility the underlying is marked at, if marked.
+    surface_vol: Option<f64>,
+    /// The maker's net position per instrument of the underlying.
+    inventory: &'a HashMap<String, i64>,
+    /// Tick-size table of the underlying.
+    ticks: &'a TickTable,
+    /// Contract specification of the underlying; quote sizes are whole lots.
+    contract: ContractSpec,
 }
 
-/// Maps `is_buy` to its reverse-index slot: the bid leg occupies slot 0, the ask
-/// leg slot 1.
-#[inline]
-const fn leg_slot(is_buy: bool) -> usize {
-    // is_buy == true  -> bid -> 0
-    // is_buy == false -> ask -> 1
-    !is_buy as usize
+/// Reverse-index entry of one instrument: the resting maker order id of each
+/// ladder level per side, best level first. A `None` slot is a level whose
+/// order has gone (filled or cancelled) since it was placed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LadderSlots {
+    /// Bid levels.
+    bids: Vec<Option<OrderId>>,
+    /// Ask levels.
+    asks: Vec<Option<OrderId>>,
+}
+
+impl LadderSlots {
+    /// The slots of one side.
+    fn side_mut(&mut self, is_buy: bool) -> &mut Vec<Option<OrderId>> {
+        if is_buy {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        }
+    }
+
+    /// Whether no level of either side still rests.
+    fn is_empty(&self) -> bool {
+        self.bids.iter().chain(&self.asks).all(Option::is_none)
+    }
+
+    /// Whether every level placed on both sides still rests.
+    fn is_complete(&self) -> bool {
+        !self.bids.is_empty()
+            && !self.asks.is_empty()
+            && self.bids.iter().chain(&self.asks).all(Option::is_some)
+    }
+}
+
+/// The engine's order tracking — every resting maker order and the quote
+/// ladder index over them — captured for a whole-state snapshot and handed
+/// back on restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MakerOrderState {
+    /// Tracked maker orders.
+    orders: Vec<(OrderId, ActiveOrderInfo)>,
+    /// Ladder slots per instrument.
+    ladders: Vec<(InstrumentKey, LadderSlots)>,
+}
+
+impl MakerOrderState {
+    /// Number of tracked maker orders.
+    #[must_use]
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+}
+
+/// The engine's own book — inventory from quote fills and transferred
+/// positions, the hedge ledger and the P&L attribution — captured for a
+/// whole-state snapshot and handed back on restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MakerBookState {
+    /// Net inventory per instrument.
+    inventory: Vec<InventoryPosition>,
+    /// Hedge positions and recent hedge trades.
+    hedger: DeltaHedger,
+    /// P&L attribution; `None` keeps the running attribution on restore.
+    pnl: Option<PnlAttributor>,
 }
 
 /// The market maker engine coordinates all quoting activity.
@@ -287,11 +442,20 @@ pub struct MarketMakerEngine {
     /// Database pool for persistence (reserved for future use).
     #[allow(dead_code)]
     db: Option<DatabasePool>,
-    /// Option pricer (reserved for future use).
-    #[allow(dead_code)]
+    /// Option pricer for risk, hedging and attribution; the built-in
+    /// strategies price with the same default model.
     pricer: OptionPricer,
-    /// Quoter for generating quotes.
-    quoter: Quoter,
+    /// Strategy quoting underlyings without one of their own.
+    default_strategy: Arc<RwLock<Arc<dyn QuotingStrategy>>>,
+    /// Quoting strategy per underlying. Read once per requote pass, never
+    /// held across a book call.
+    strategies: Arc<RwLock<HashMap<String, Arc<dyn QuotingStrategy>>>>,
+    /// Tick-size table per underlying; underlyings without one quote in
+    /// whole cents.
+    tick_tables: Arc<RwLock<HashMap<String, TickTable>>>,
+    /// Contract specification per underlying; underlyings without one trade
+    /// single-unit contracts of any size.
+    contract_specs: Arc<RwLock<HashMap<String, ContractSpec>>>,
     /// Current configuration.
     config: Arc<RwLock<MarketMakerConfig>>,
     /// Latest underlying prices (symbol -> price in cents).
@@ -299,12 +463,12 @@ pub struct MarketMakerEngine {
     /// Active orders (order_id -> order info). The source of truth for order
     /// metadata.
     active_orders: Arc<RwLock<HashMap<OrderId, ActiveOrderInfo>>>,
-    /// Reverse index from an instrument's structural identity to its ≤2 resting
-    /// maker order ids — slot 0 the bid leg, slot 1 the ask leg. A pure lookup
+    /// Reverse index from an instrument's structural identity to the resting
+    /// maker order ids of its quote ladder, one slot per level. A pure lookup
     /// accelerator over `active_orders`: it turns the per-requote stale-order
     /// lookup from an O(total_orders) scan into an O(1) probe (issue #107 P2-01).
     ///
-    /// Invariant: every tracked order's leg is recorded here, and every id
+    /// Invariant: every tracked order's level is recorded here, and every id
     /// recorded here is present in `active_orders` — or is a transiently
     /// dangling id that a concurrent full fill removed between the two
     /// (sequential, never-nested) updates; such an id is benign because
@@ -317,9 +481,30 @@ pub struct MarketMakerEngine {
     /// acquiring the other, and neither guard is ever held across a book call or
     /// a broadcast send. Should a future change ever need both at once, acquire
     /// `active_orders` before `instrument_orders`.
-    instrument_orders: Arc<RwLock<HashMap<InstrumentKey, [Option<OrderId>; 2]>>>,
+    instrument_orders: Arc<RwLock<HashMap<InstrumentKey, LadderSlots>>>,
+    /// Net inventory from the engine's own quote fills, keyed by instrument.
+    /// Locked independently of the order maps and never held across a
+    /// broadcast send.
+    inventory: Arc<RwLock<HashMap<String, InventoryPosition>>>,
+    /// Delta hedging parameters.
+    hedge_params: Arc<RwLock<HedgeParams>>,
+    /// Hedge positions and trades in the underlyings. Locked after (never
+    /// while holding) the inventory lock.
+    hedger: Arc<RwLock<DeltaHedger>>,
+    /// P&L attribution. Locked last, after the inventory and hedger locks
+    /// have been released.
+    pnl: Arc<RwLock<PnlAttributor>>,
+    /// Requote throttle keyed by instrument. Locked briefly, never across a
+    /// book call.
+    throttle: Arc<RwLock<RequoteThrottle<InstrumentKey>>>,
     /// Event broadcaster.
     event_tx: broadcast::Sender<MarketMakerEvent>,
+    /// Held for reading by every price update (and the requote it drives);
+    /// [`Self::pause_quoting`] takes it for writing so a whole-state snapshot
+    /// sees the order maps and the books at rest.
+    quote_gate: Arc<RwLock<()>>,
+    /// Source of every order ID, shared with the API handlers.
+    order_ids: Arc<OrderIdSource>,
 }
 
 impl MarketMakerEngine {
@@ -336,28 +521,49 @@ impl MarketMakerEngine {
             manager,
             db,
             pricer: OptionPricer::default(),
-            quoter: Quoter::default(),
+            default_strategy: Arc::new(RwLock::new(Arc::new(SingleLevelStrategy::default()))),
+            strategies: Arc::new(RwLock::new(HashMap::new())),
+            tick_tables: Arc::new(RwLock::new(HashMap::new())),
+            contract_specs: Arc::new(RwLock::new(HashMap::new())),
             config: Arc::new(RwLock::new(MarketMakerConfig::default())),
             prices: Arc::new(RwLock::new(HashMap::new())),
             active_orders: Arc::new(RwLock::new(HashMap::new())),
             instrument_orders: Arc::new(RwLock::new(HashMap::new())),
+            inventory: Arc::new(RwLock::new(HashMap::new())),
+            hedge_params: Arc::new(RwLock::new(HedgeParams::default())),
+            hedger: Arc::new(RwLock::new(DeltaHedger::default())),
+            pnl: Arc::new(RwLock::new(PnlAttributor::new(clock::now_ms()))),
+            throttle: Arc::new(RwLock::new(RequoteThrottle::default())),
             event_tx,
+            quote_gate: Arc::new(RwLock::new(())),
+            order_ids: Arc::new(OrderIdSource::new()),
         }
     }
 
-    /// Clears one leg (`is_buy` selects the bid or ask slot) from the
-    /// reverse-index entry for `key`, dropping the whole entry once both legs
-    /// are gone. Acquires only the `instrument_orders` lock, briefly.
-    fn clear_instrument_slot(&self, key: &InstrumentKey, is_buy: bool) {
+    /// Clears the slot of `order_id` (on the side `is_buy` selects) from the
+    /// reverse-index entry for `key`, dropping the whole entry once no level
+    /// rests. Acquires only the `instrument_orders` lock, briefly.
+    fn clear_instrument_slot(&self, key: &InstrumentKey, order_id: OrderId, is_buy: bool) {
         let mut index = self.instrument_orders.write();
         if let Some(slots) = index.get_mut(key) {
-            slots[leg_slot(is_buy)] = None;
-            if slots[0].is_none() && slots[1].is_none() {
+            for slot in slots.side_mut(is_buy).iter_mut() {
+                if *slot == Some(order_id) {
+                    *slot = None;
+                }
+            }
+            if slots.is_empty() {
                 index.remove(key);
             }
         }
     }
 
+    /// Returns the source of order IDs, shared by the maker's quotes and the
+    /// API's order entry.
+    #[must_use]
+    pub fn order_ids(&self) -> &OrderIdSource {
+        &self.order_ids
+    }
+
     /// Returns a receiver for market maker events.
     #[must_use]
     pub fn subscribe(&self) -> broadcast::Receiver<MarketMakerEvent> {
@@ -370,6 +576,7 @@ impl MarketMakerEngine {
     /// * `symbol` - Underlying symbol
     /// * `price_cents` - Price in cents
     pub fn update_price(&self, symbol: &str, price_cents: u64) {
+        let _gate = self.quote_gate.read();
         {
             let mut prices = self.prices.write();
             prices.insert(symbol.to_string(), price_cents);
@@ -382,10 +589,15 @@ impl MarketMakerEngine {
 
         debug!("Updated price for {}: {} cents", symbol, price_cents);
 
+        self.mark_pnl(symbol, None);
+
         // Trigger requote if enabled
         if self.is_enabled() && self.is_symbol_enabled(symbol) {
             self.requote_symbol(symbol);
         }
+
+        // The inventory's delta moves with spot even when quoting is off.
+        self.rehedge(symbol);
     }
 
     /// Gets the current price for a symbol.
@@ -394,6 +606,409 @@ impl MarketMakerEngine {
         self.prices.read().get(symbol).copied()
     }
 
+    /// The pricer theoretical values are computed with. The built-in
+    /// strategies price with the same default model, so risk views value
+    /// positions exactly as the engine quotes them.
+    #[must_use]
+    pub fn pricer(&self) -> &OptionPricer {
+        &self.pricer
+    }
+
+    /// The strategy quoting `underlying`.
+    #[must_use]
+    pub fn strategy(&self, underlying: &str) -> Arc<dyn QuotingStrategy> {
+        if let Some(strategy) = self.strategies.read().get(underlying) {
+            return Arc::clone(strategy);
+        }
+        Arc::clone(&self.default_strategy.read())
+    }
+
+    /// Number of maker orders resting on `underlying`, over every level of
+    /// every instrument.
+    #[must_use]
+    pub fn resting_order_count(&self, underlying: &str) -> usize {
+        self.active_orders
+            .read()
+            .values()
+            .filter(|order| order.symbol == underlying)
+            .count()
+    }
+
+    /// Replaces the strategy of underlyings without one of their own.
+    pub fn set_default_strategy(&self, strategy: Arc<dyn QuotingStrategy>) {
+        *self.default_strategy.write() = strategy;
+    }
+
+    /// Quotes `underlying` with `strategy` from its next requote on; `None`
+    /// reverts it to the default strategy.
+    pub fn set_strategy(&self, underlying: &str, strategy: Option<Arc<dyn QuotingStrategy>>) {
+        let mut strategies = self.strategies.write();
+        match strategy {
+            Some(strategy) => {
+                strategies.insert(underlying.to_string(), strategy);
+            }
+            None => {
+                strategies.remove(underlying);
+            }
+        }
+    }
+
+    /// Tick-size table of `underlying`: one cent when none was set.
+    #[must_use]
+    pub fn tick_table(&self, underlying: &str) -> TickTable {
+        self.tick_tables
+            .read()
+            .get(underlying)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Rounds the quotes of `underlying` onto `ticks` from its next requote on.
+    pub fn set_tick_table(&self, underlying: &str, ticks: TickTable) {
+        self.tick_tables
+            .write()
+            .insert(underlying.to_string(), ticks);
+    }
+
+    /// Contract specification of `underlying`: the default when none was set.
+    #[must_use]
+    pub fn contract_spec(&self, underlying: &str) -> ContractSpec {
+        self.contract_specs
+            .read()
+            .get(underlying)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets the contract specification of `underlying`. Quote sizes follow it
+    /// from the next requote on; inventory already held keeps the multiplier
+    /// it was booked with.
+    pub fn set_contract_spec(&self, underlying: &str, spec: ContractSpec) {
+        self.contract_specs
+            .write()
+            .insert(underlying.to_string(), spec);
+    }
+
+    /// Snapshot of the engine's net inventory, sorted by instrument. Flat
+    /// instruments are omitted.
+    #[must_use]
+    pub fn inventory(&self) -> Vec<InventoryPosition> {
+        let mut positions: Vec<InventoryPosition> = self
+            .inventory
+            .read()
+            .values()
+            .filter(|p| p.quantity != 0)
+            .cloned()
+            .collect();
+        positions.sort_by(|a, b| a.instrument.cmp(&b.instrument));
+        positions
+    }
+
+    /// Books a position transferred to the market maker (e.g. from a
+    /// liquidated account) into its inventory, netting against any position it
+    /// already holds in the instrument.
+    pub fn take_inventory(&self, position: InventoryPosition) {
+        let underlying = position.underlying.clone();
+        self.mark_pnl(&underlying, None);
+        {
+            let mut inventory = self.inventory.write();
+            match inventory.get_mut(&position.instrument) {
+                Some(held) => held.quantity += position.quantity,
+                None => {
+                    inventory.insert(position.instrument.clone(), position);
+                }
+            }
+        }
+        self.rehedge(&underlying);
+    }
+
+    /// Current delta hedging parameters.
+    #[must_use]
+    pub fn hedge_params(&self) -> HedgeParams {
+        *self.hedge_params.read()
+    }
+
+    /// Replaces the delta hedging parameters and hedges every underlying
+    /// against them.
+    pub fn set_hedge_params(&self, params: HedgeParams) {
+        *self.hedge_params.write() = params;
+        for symbol in self.manager.underlying_symbols() {
+            self.rehedge(&symbol);
+        }
+    }
+
+    /// Net delta of the option inventory in `underlying` at `spot_cents`, in
+    /// units of the underlying.
+    ///
+    /// Positions the pricer cannot value (non-finite delta) are left out.
+    fn inventory_delta(&self, underlying: &str, spot_cents: u64) -> f64 {
+        let pricer = self.pricer();
+        let spot = spot_cents as f64 / 100.0;
+        self.inventory
+            .read()
+            .values()
+            .filter(|p| p.underlying == underlying && p.quantity != 0)
+            .map(|p| {
+                let strike = p.strike as f64 / 100.0;
+                p.quantity as f64
+                    * p.multiplier as f64
+                    * pricer.delta(spot, strike, &p.expiration, p.style, None)
+            })
+            .filter(|d| d.is_finite())
+            .sum()
+    }
+
+    /// Hedges `underlying` if hedging is enabled and its residual delta is
+    /// outside the band, broadcasting a [`MarketMakerEvent::HedgeExecuted`].
+    ///
+    /// The hedge trades the full quantity at the current spot price. Returns
+    /// the trade, or `None` when nothing was hedged (disabled, no price, or
+    /// inside the band).
+    pub fn rehedge(&self, underlying: &str) -> Option<HedgeTrade> {
+        let params = self.hedge_params();
+        if !params.enabled {
+            return None;
+        }
+        let spot = self.get_price(underlying).filter(|p| *p > 0)?;
+        let option_delta = self.inventory_delta(underlying, spot);
+
+        // Size and book under one write lock so concurrent callers never
+        // hedge the same residual twice.
+        let (trade, book) = {
+            let mut hedger = self.hedger.write();
+            let quantity = hedge_quantity(option_delta, hedger.book(underlying).position, &params)?;
+            let trade = hedger.execute(underlying, quantity, spot, option_delta, clock::now_ms());
+            (trade, hedger.book(underlying))
+        };
+
+        info!(
+            underlying,
+            quantity = trade.quantity,
+            price = trade.price,
+            residual_delta = trade.residual_delta,
+            "delta hedge executed"
+        );
+        let _ = self.event_tx.send(MarketMakerEvent::HedgeExecuted {
+            symbol: underlying.to_string(),
+            quantity: trade.quantity,
+            price: trade.price,
+            hedge_position: trade.hedge_position,
+            residual_delta: trade.residual_delta,
+            hedge_pnl: book.pnl_cents(spot).round() as i64,
+        });
+        Some(trade)
+    }
+
+    /// Hedge status of every underlying with option inventory or a hedge
+    /// position, ordered by symbol.
+    #[must_use]
+    pub fn hedge_status(&self) -> Vec<HedgeStatus> {
+        let mut underlyings: Vec<String> = self
+            .inventory
+            .read()
+            .values()
+            .filter(|p| p.quantity != 0)
+            .map(|p| p.underlying.clone())
+            .collect();
+        let books: HashMap<String, HedgeBook> = self
+            .hedger
+            .read()
+            .books()
+            .map(|(u, b)| (u.to_string(), *b))
+            .collect();
+        underlyings.extend(books.keys().cloned());
+        underlyings.sort();
+        underlyings.dedup();
+
+        underlyings
+            .into_iter()
+            .map(|underlying| {
+                let book = books.get(&underlying).copied().unwrap_or_default();
+                let spot = self.get_price(&underlying).filter(|p| *p > 0);
+                let option_delta = spot.map_or(0.0, |s| self.inventory_delta(&underlying, s));
+                HedgeStatus {
+                    spot,
+                    option_delta,
+                    hedge_position: book.position,
+                    residual_delta: option_delta + book.position,
+                    hedge_pnl: spot.map_or(0, |s| book.pnl_cents(s).round() as i64),
+                    trades: book.trades,
+                    underlying,
+                }
+            })
+            .collect()
+    }
+
+    /// Up to `limit` most recent hedge trades, newest first.
+    #[must_use]
+    pub fn hedge_trades(&self, limit: usize) -> Vec<HedgeTrade> {
+        self.hedger.read().recent_trades(limit)
+    }
+
+    /// Re-marks the inventory of `underlying` at the current spot and
+    /// attributes the P&L since the previous mark (see
+    /// [`greek_pnl`](crate::market_maker::greek_pnl)).
+    ///
+    /// `vol` replaces the marking volatility; otherwise the previous mark's
+    /// (initially the pricer's default) is kept. Called before every
+    /// inventory change so each move is explained by the position that was
+    /// actually held. The first mark of an underlying only records the
+    /// starting point.
+    fn mark_pnl(&self, underlying: &str, vol: Option<f64>) {
+        let Some(spot) = self.get_price(underlying).filter(|p| *p > 0) else {
+            return;
+        };
+        let positions: Vec<AttributedPosition> = self
+            .inventory
+            .read()
+            .values()
+            .filter(|p| p.underlying == underlying && p.quantity != 0)
+            .map(|p| AttributedPosition {
+                strike: p.strike,
+                expiration: p.expiration,
+                style: p.style,
+                quantity: p.quantity,
+                multiplier: p.multiplier,
+            })
+            .collect();
+        let hedge_pnl = self.hedger.read().book(underlying).pnl_cents(spot);
+
+        // Attribute and re-mark under one write lock so a move is never
+        // counted twice by concurrent callers.
+        let mut attributor = self.pnl.write();
+        let previous = attributor.mark(underlying);
+        let mark = PnlMark {
+            spot,
+            vol: vol
+                .or(previous.map(|m| m.vol))
+                .unwrap_or_else(|| self.pricer().default_iv()),
+            at_ms: clock::now_ms(),
+            hedge_pnl,
+        };
+        if let Some(from) = previous {
+            let mut pnl = greek_pnl(self.pricer(), &positions, &from, &mark);
+            pnl.hedging = hedge_pnl - from.hedge_pnl;
+            if pnl != PnlComponents::default() {
+                attributor.record(underlying, pnl);
+            }
+        }
+        attributor.set_mark(underlying, mark);
+    }
+
+    /// Re-marks every priced underlying so attribution includes the latest
+    /// theta accrual.
+    fn mark_all_pnl(&self) {
+        let symbols: Vec<String> = self.prices.read().keys().cloned().collect();
+        for symbol in symbols {
+            self.mark_pnl(&symbol, None);
+        }
+    }
+
+    /// Sets the volatility `underlying` is marked at (e.g. the ATM vol of a
+    /// freshly derived surface), attributing the change to vega. Non-finite or
+    /// non-positive values are ignored.
+    pub fn set_mark_vol(&self, underlying: &str, vol: f64) {
+        if !(vol.is_finite() && vol > 0.0) {
+            warn!(underlying, vol, "ignoring invalid marking volatility");
+            return;
+        }
+        self.mark_pnl(underlying, Some(vol));
+    }
+
+    /// When P&L attribution started, in milliseconds.
+    #[must_use]
+    pub fn pnl_started_ms(&self) -> u64 {
+        self.pnl.read().started_ms()
+    }
+
+    /// Attributed P&L since start-up per underlying, re-marked to now.
+    #[must_use]
+    pub fn pnl_cumulative(&self) -> BTreeMap<String, PnlComponents> {
+        self.mark_all_pnl();
+        self.pnl.read().cumulative().clone()
+    }
+
+    /// The open attribution period, re-marked to now.
+    #[must_use]
+    pub fn pnl_current_period(&self) -> PnlPeriod {
+        self.mark_all_pnl();
+        self.pnl.read().current().clone()
+    }
+
+    /// Up to `limit` closed attribution periods, newest first.
+    #[must_use]
+    pub fn pnl_periods(&self, limit: usize) -> Vec<PnlPeriod> {
+        self.pnl.read().history(limit)
+    }
+
+    /// Closes the open attribution period, broadcasting it as a
+    /// [`MarketMakerEvent::PnlPeriodClosed`], and starts the next one.
+    pub fn close_pnl_period(&self) -> PnlPeriod {
+        self.mark_all_pnl();
+        let period = self.pnl.write().close_period(clock::now_ms());
+        let _ = self.event_tx.send(MarketMakerEvent::PnlPeriodClosed {
+            period: period.clone(),
+        });
+        period
+    }
+
+    /// Returns the queue position of every resting maker order, optionally
+    /// limited to one underlying, ordered by instrument with the bid first.
+    #[must_use]
+    pub fn queue_positions(&self, underlying: Option<&str>) -> Vec<MakerQueuePosition> {
+        let orders: Vec<(OrderId, ActiveOrderInfo)> = self
+            .active_orders
+            .read()
+            .iter()
+            .filter(|(_, order)| underlying.is_none_or(|u| order.symbol == u))
+            .map(|(id, order)| (*id, order.clone()))
+            .collect();
+
+        let mut positions: Vec<MakerQueuePosition> = orders
+            .into_iter()
+            .filter_map(|(id, order)| {
+                let strike_book = self
+                    .manager
+                    .get(&order.symbol)
+                    .ok()?
+                    .get_expiration(&order.expiration)
+                    .ok()?
+                    .get_strike(order.strike)
+                    .ok()?;
+                let position = queue_position(strike_book.get(order.style), id)?;
+                Some(MakerQueuePosition {
+                    instrument: order.instrument,
+                    underlying: order.symbol,
+                    is_buy: order.is_buy,
+                    position,
+                })
+            })
+            .collect();
+        positions.sort_by(|a, b| {
+            a.instrument
+                .cmp(&b.instrument)
+                .then(b.is_buy.cmp(&a.is_buy))
+        });
+        positions
+    }
+
+    /// Returns the current requote throttling parameters.
+    #[must_use]
+    pub fn requote_params(&self) -> RequoteParams {
+        self.throttle.read().params()
+    }
+
+    /// Replaces the requote throttling parameters.
+    pub fn set_requote_params(&self, params: RequoteParams) {
+        self.throttle.write().set_params(params);
+    }
+
+    /// Returns the counters of sent and skipped requotes since start-up.
+    #[must_use]
+    pub fn requote_stats(&self) -> RequoteStats {
+        self.throttle.read().stats()
+    }
+
     /// Checks if the market maker is globally enabled.
     #[must_use]
     pub fn is_enabled(&self) -> bool {
@@ -508,6 +1123,79 @@ impl MarketMakerEngine {
         self.config.read().clone()
     }
 
+    /// Blocks price updates, and the requotes they drive, until the returned
+    /// guard drops. Never hold it across an `.await`.
+    pub fn pause_quoting(&self) -> RwLockWriteGuard<'_, ()> {
+        self.quote_gate.write()
+    }
+
+    /// Captures the tracked maker orders and the ladder index. The two maps
+    /// are read one after the other, so pause quoting first for a consistent
+    /// pair.
+    #[must_use]
+    pub fn order_state(&self) -> MakerOrderState {
+        let orders = self
+            .active_orders
+            .read()
+            .iter()
+            .map(|(id, info)| (*id, info.clone()))
+            .collect();
+        let ladders = self
+            .instrument_orders
+            .read()
+            .iter()
+            .map(|(key, slots)| (key.clone(), slots.clone()))
+            .collect();
+        MakerOrderState { orders, ladders }
+    }
+
+    /// Replaces the tracked maker orders and the ladder index with `state`,
+    /// dropping any ladder slot whose order is not tracked so the index never
+    /// points past `active_orders`.
+    pub fn restore_order_state(&self, state: MakerOrderState) {
+        let orders: HashMap<OrderId, ActiveOrderInfo> = state.orders.into_iter().collect();
+        let mut ladders: HashMap<InstrumentKey, LadderSlots> = HashMap::new();
+        for (key, mut slots) in state.ladders {
+            for slot in slots.bids.iter_mut().chain(slots.asks.iter_mut()) {
+                if slot.is_some_and(|id| !orders.contains_key(&id)) {
+                    *slot = None;
+                }
+            }
+            if !slots.is_empty() {
+                ladders.insert(key, slots);
+            }
+        }
+        *self.active_orders.write() = orders;
+        *self.instrument_orders.write() = ladders;
+    }
+
+    /// Captures the inventory, hedge ledger and P&L attribution. Each is
+    /// locked on its own, in the engine's lock order.
+    #[must_use]
+    pub fn book_state(&self) -> MakerBookState {
+        let inventory = self.inventory.read().values().cloned().collect();
+        let hedger = self.hedger.read().clone();
+        let pnl = Some(self.pnl.read().clone());
+        MakerBookState {
+            inventory,
+            hedger,
+            pnl,
+        }
+    }
+
+    /// Replaces the inventory, hedge ledger and P&L attribution with `state`.
+    pub fn restore_book_state(&self, state: MakerBookState) {
+        *self.inventory.write() = state
+            .inventory
+            .into_iter()
+            .map(|position| (position.instrument.clone(), position))
+            .collect();
+        *self.hedger.write() = state.hedger;
+        if let Some(pnl) = state.pnl {
+            *self.pnl.write() = pnl;
+        }
+    }
+
     /// Cancels all active orders.
     pub fn cancel_all_orders(&self) {
         let orders: Vec<_> = self.active_orders.read().keys().copied().collect();
@@ -547,7 +1235,7 @@ impl MarketMakerEngine {
         if let Some(order) = order_info {
             // Drop this leg from the reverse index (sequential with the
             // `active_orders` lock above — the two maps are never held at once).
-            self.clear_instrument_slot(&InstrumentKey::from_order(&order), order.is_buy);
+            self.clear_instrument_slot(&InstrumentKey::from_order(&order), order_id, order.is_buy);
 
             // Cancel on the book using the stored structural expiration (no
             // string reparse), so the lookup keys the same book that placed the
@@ -617,9 +1305,34 @@ impl MarketMakerEngine {
         // after releasing the `active_orders` lock — the two maps are never held
         // at once.
         if fully_filled {
-            self.clear_instrument_slot(&InstrumentKey::from_order(&order), order.is_buy);
+            self.clear_instrument_slot(&InstrumentKey::from_order(&order), order_id, order.is_buy);
         }
 
+        // Attribute the move up to now to the inventory held before the fill.
+        self.mark_pnl(&order.symbol, None);
+
+        // Book the fill into the maker inventory: a filled bid adds to the
+        // position, a filled ask reduces it.
+        let signed_qty = if order.is_buy {
+            reported_qty as i64
+        } else {
+            -(reported_qty as i64)
+        };
+        let multiplier = self.contract_spec(&order.symbol).multiplier;
+        self.inventory
+            .write()
+            .entry(order.instrument.clone())
+            .or_insert_with(|| InventoryPosition {
+                instrument: order.instrument.clone(),
+                underlying: order.symbol.clone(),
+                expiration: order.expiration,
+                strike: order.strike,
+                style: order.style,
+                quantity: 0,
+                multiplier,
+            })
+            .quantity += signed_qty;
+
         // The market-data DTOs carry prices as u64 cents; a fill price beyond
         // that range is structurally impossible — log and skip rather than
         // truncating money.
@@ -637,16 +1350,25 @@ impl MarketMakerEngine {
 
         let edge = Quoter::calculate_edge(fill_u64, order.theo_cents, order.is_buy);
         let side = if order.is_buy { "buy" } else { "sell" };
+        self.pnl.write().record(
+            &order.symbol,
+            PnlComponents {
+                edge: edge as f64 * reported_qty as f64 * multiplier as f64,
+                ..PnlComponents::default()
+            },
+        );
 
         let _ = self.event_tx.send(MarketMakerEvent::OrderFilled {
             order_id: order_id.to_string(),
-            symbol: order.symbol,
+            symbol: order.symbol.clone(),
             instrument: order.instrument,
             side: side.to_string(),
             quantity: reported_qty,
             price: fill_price_cents,
             edge,
         });
+
+        self.rehedge(&order.symbol);
     }
 
     /// Test-only: registers a tracked market-maker order directly (bypassing
@@ -681,7 +1403,9 @@ impl MarketMakerEngine {
         self.instrument_orders
             .write()
             .entry(key)
-            .or_insert([None, None])[leg_slot(is_buy)] = Some(id);
+            .or_default()
+            .side_mut(is_buy)
+            .push(Some(id));
         id
     }
 
@@ -696,6 +1420,17 @@ impl MarketMakerEngine {
         };
 
         let config = self.get_config();
+        let strategy = self.strategy(symbol);
+        let surface_vol = self.pnl.read().mark(symbol).map(|m| m.vol);
+        let ticks = self.tick_table(symbol);
+        let contract = self.contract_spec(symbol);
+        let inventory: HashMap<String, i64> = self
+            .inventory
+            .read()
+            .values()
+            .filter(|p| p.underlying == symbol && p.quantity != 0)
+            .map(|p| (p.instrument.clone(), p.quantity))
+            .collect();
 
         if let Ok(underlying_book) = self.manager.get(symbol) {
             for (expiration, exp_book) in underlying_book.expirations().iter() {
@@ -716,6 +1451,11 @@ impl MarketMakerEngine {
                     exp_canonical: &exp_canonical,
                     spot_cents: price_cents,
                     config: &config,
+                    strategy: strategy.as_ref(),
+                    surface_vol,
+                    inventory: &inventory,
+                    ticks: &ticks,
+                    contract,
                 };
                 for strike in exp_book.strike_prices() {
                     if exp_book.get_strike(strike).is_ok() {
@@ -737,10 +1477,11 @@ impl MarketMakerEngine {
         }
     }
 
-    /// Updates quotes for a specific option `strike`/`style`.
+    /// Updates quotes for a specific option `strike`/`style` to the ladder the
+    /// underlying's strategy returns.
     ///
     /// The loop-invariant inputs (symbol, structural expiration, its pre-built
-    /// `Display` string, spot, config) are carried in `ctx` so the hot inner
+    /// `Display` string, spot, config, strategy) are carried in `ctx` so the hot inner
     /// loop borrows them. `ctx.expiration` is the structural book key (used for
     /// the book lookup and the reverse-index key, both clock-independent);
     /// `ctx.exp_display` is needed only for the broadcast event (issue #107).
@@ -758,163 +1499,262 @@ impl MarketMakerEngine {
         };
         let instrument = format!("{symbol}-{exp_canonical}-{strike}-{style_char}");
 
-        let input = QuoteInput {
+        // Structural, clock-independent identity of this exact instrument, used
+        // for the O(1) reverse-index lookup below (issue #107 P2-01).
+        let instrument_key = InstrumentKey::new(symbol, expiration, strike, style);
+
+        let Ok(underlying_book) = self.manager.get(symbol) else {
+            return;
+        };
+        let Ok(exp_book) = underlying_book.get_expiration(expiration) else {
+            return;
+        };
+        let Ok(strike_book) = exp_book.get_strike(strike) else {
+            return;
+        };
+        let option_book = strike_book.get(style);
+
+        let input = StrategyInput {
+            symbol,
             spot_cents: ctx.spot_cents,
             strike_cents: strike,
             expiration,
             style,
-            spread_multiplier: ctx.config.spread_multiplier,
-            size_scalar: ctx.config.size_scalar,
-            directional_skew: ctx.config.directional_skew,
-            iv: None,
+            ticks: ctx.ticks,
+            surface_vol: ctx.surface_vol,
+            inventory: ctx.inventory.get(&instrument).copied().unwrap_or(0),
+            book: BookState::of(option_book),
+            config: ctx.config,
         };
 
-        // Skip the instrument when the theoretical value is non-finite: the
-        // quoter returns `None` rather than producing a poisoned bid/ask. The
-        // requote loop continues with the next strike/style.
-        let quote_params = match self.quoter.generate_quote(&input) {
-            Some(params) => params,
-            None => {
+        // Skip the instrument when the strategy declines to quote it (the
+        // built-in ones do on a non-finite theoretical value) or returns a
+        // ladder that cannot be placed. The requote loop continues with the
+        // next strike/style.
+        let mut ladder = match ctx.strategy.quote(&input) {
+            Some(ladder) if ladder.is_valid() => ladder,
+            Some(_) => {
                 warn!(
                     symbol = %symbol,
                     expiration = %ctx.exp_display,
                     strike,
                     style = ?style,
-                    "skipping quote: non-finite theoretical value"
+                    strategy = ctx.strategy.name(),
+                    "skipping quote: invalid quote ladder"
                 );
                 return;
             }
-        };
-
-        // Structural, clock-independent identity of this exact instrument, used
-        // for the O(1) reverse-index lookup below (issue #107 P2-01).
-        let instrument_key = InstrumentKey::new(symbol, expiration, strike, style);
-
-        // Cancel existing orders and place new ones.
-        if let Ok(underlying_book) = self.manager.get(symbol)
-            && let Ok(exp_book) = underlying_book.get_expiration(expiration)
-            && let Ok(strike_book) = exp_book.get_strike(strike)
-        {
-            let option_book = strike_book.get(style);
-
-            // Replace, don't accumulate: look up this exact instrument's ≤2
-            // previously-resting maker orders in O(1) via the reverse index
-            // (was an O(total_orders) scan over every tracked order; issue #107
-            // P2-01). Copy the fixed-size slot array out under a short read lock,
-            // drop the lock, cancel each id on the option book (never holding a
-            // lock across the cancel), then prune both maps.
-            let stale: [Option<OrderId>; 2] = self
-                .instrument_orders
-                .read()
-                .get(&instrument_key)
-                .copied()
-                .unwrap_or_default();
-            if stale.iter().any(Option::is_some) {
-                for stale_id in stale.into_iter().flatten() {
-                    // Ok(true) = cancelled, Ok(false) = already filled/gone; both
-                    // mean the order should leave tracking. Delegate the actual
-                    // cancel to the upstream book.
-                    let _ = option_book.cancel_order(stale_id);
-                }
-                // Sequential locks (never nested): prune `active_orders`, release
-                // it, then remove the reverse-index entry.
-                {
-                    let mut orders = self.active_orders.write();
-                    for stale_id in stale.into_iter().flatten() {
-                        orders.remove(&stale_id);
-                    }
-                }
-                self.instrument_orders.write().remove(&instrument_key);
-                debug!(
+            None => {
+                warn!(
                     symbol = %symbol,
                     expiration = %ctx.exp_display,
                     strike,
                     style = ?style,
-                    cancelled = stale.iter().filter(|s| s.is_some()).count(),
-                    "cancelled stale quote before requote"
+                    strategy = ctx.strategy.name(),
+                    "skipping quote: no quote from strategy"
                 );
+                return;
             }
+        };
+        for level in ladder.bids.iter_mut().chain(ladder.asks.iter_mut()) {
+            level.size = ctx.contract.normalize_size(level.size);
+        }
 
-            // Place the fresh bid/ask, recording each leg's id for the reverse
-            // index (slot 0 = bid, slot 1 = ask). The instrument string is built
-            // once and moved into the ask leg (the bid clones it).
-            let mut placed: [Option<OrderId>; 2] = [None, None];
-
-            let bid_id = OrderId::new();
-            if option_book
-                .add_limit_order(
-                    bid_id,
-                    Side::Buy,
-                    quote_params.bid_price,
-                    quote_params.bid_size,
-                )
-                .is_ok()
-            {
-                self.active_orders.write().insert(
-                    bid_id,
-                    ActiveOrderInfo {
-                        symbol: symbol.to_string(),
-                        expiration: *expiration,
-                        instrument: instrument.clone(),
-                        strike,
-                        style,
-                        is_buy: true,
-                        theo_cents: quote_params.theo_price,
-                        quantity: quote_params.bid_size,
-                    },
-                );
-                placed[leg_slot(true)] = Some(bid_id);
-            }
+        // Look up this exact instrument's previously-resting maker orders in
+        // O(1) via the reverse index (was an O(total_orders) scan over every
+        // tracked order; issue #107 P2-01). Clone the slots out under a short
+        // read lock and drop the lock before any book call.
+        let stale: LadderSlots = self
+            .instrument_orders
+            .read()
+            .get(&instrument_key)
+            .cloned()
+            .unwrap_or_default();
+
+        // Throttle: leave the resting ladder alone unless its best level moved
+        // enough and the instrument is under its message rate. A ladder missing
+        // a level (filled or rejected) or changing depth is always replaced.
+        let top = ladder.top();
+        let now = clock::now_ms();
+        let resting = stale.is_complete()
+            && stale.bids.len() == ladder.bids.len()
+            && stale.asks.len() == ladder.asks.len();
+        let tick = ctx.ticks.tick_at(top.bid_price, Some(ctx.spot_cents));
+        let decision = self
+            .throttle
+            .write()
+            .check(&instrument_key, &top, resting, tick, now);
+        if decision != RequoteDecision::Send {
+            debug!(
+                symbol = %symbol,
+                expiration = %ctx.exp_display,
+                strike,
+                style = ?style,
+                ?decision,
+                "requote skipped"
+            );
+            return;
+        }
 
-            let ask_id = OrderId::new();
-            if option_book
-                .add_limit_order(
-                    ask_id,
-                    Side::Sell,
-                    quote_params.ask_price,
-                    quote_params.ask_size,
-                )
-                .is_ok()
-            {
-                self.active_orders.write().insert(
-                    ask_id,
+        // Amend, don't cancel/replace: each resting level is amended in place
+        // (same id, queue priority kept on a size-only decrease). A level that
+        // is gone or cannot be amended is cancelled and placed afresh.
+        let template = ActiveOrderInfo {
+            symbol: symbol.to_string(),
+            expiration: *expiration,
+            instrument,
+            strike,
+            style,
+            is_buy: true,
+            theo_cents: ladder.theo_price,
+            quantity: 0,
+        };
+        // Move the side that travels away from the other first, so an amended
+        // level can never cross the maker's own not-yet-amended ones: on a
+        // rising best bid the asks move up first, otherwise the bids move down
+        // first.
+        let bid_rising = stale
+            .bids
+            .first()
+            .copied()
+            .flatten()
+            .and_then(|id| option_book.inner().get_order(id))
+            .is_some_and(|order| top.bid_price > order.price().as_u128());
+        let placed = if bid_rising {
+            let asks = self.requote_side(option_book, &stale.asks, &ladder.asks, &template, false);
+            let bids = self.requote_side(option_book, &stale.bids, &ladder.bids, &template, true);
+            LadderSlots { bids, asks }
+        } else {
+            let bids = self.requote_side(option_book, &stale.bids, &ladder.bids, &template, true);
+            let asks = self.requote_side(option_book, &stale.asks, &ladder.asks, &template, false);
+            LadderSlots { bids, asks }
+        };
+
+        // Record the ladder in the reverse index under one write lock, or drop
+        // the entry when no level rests any more.
+        if !placed.is_empty() {
+            self.instrument_orders
+                .write()
+                .insert(instrument_key.clone(), placed);
+            self.throttle.write().record_sent(instrument_key, top, now);
+        } else if !stale.is_empty() {
+            self.instrument_orders.write().remove(&instrument_key);
+        }
+
+        // Broadcast quote update.
+        let _ = self.event_tx.send(MarketMakerEvent::QuoteUpdated {
+            symbol: symbol.to_string(),
+            expiration: ctx.exp_display.to_string(),
+            strike,
+            style: match style {
+                OptionStyle::Call => "call".to_string(),
+                OptionStyle::Put => "put".to_string(),
+            },
+            bid_price: top.bid_price,
+            ask_price: top.ask_price,
+            bid_size: top.bid_size,
+            ask_size: top.ask_size,
+        });
+    }
+
+    /// Moves one side of an instrument's quote to `levels`: level `i` amends
+    /// the order resting in `stale[i]` (see [`Self::requote_level`]) and stale
+    /// levels beyond the new ladder are cancelled. Tracking of the whole side
+    /// is then updated under a single `active_orders` write lock. Returns the
+    /// slot of each new level.
+    fn requote_side(
+        &self,
+        option_book: &OptionOrderBook,
+        stale: &[Option<OrderId>],
+        levels: &[LadderLevel],
+        template: &ActiveOrderInfo,
+        is_buy: bool,
+    ) -> Vec<Option<OrderId>> {
+        let mut gone = Vec::new();
+        let placed: Vec<Option<OrderId>> = levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| {
+                let stale = stale.get(i).copied().flatten();
+                self.requote_level(option_book, stale, level, is_buy, &mut gone)
+            })
+            .collect();
+        for &id in stale.iter().skip(levels.len()).flatten() {
+            // Ok(true) = cancelled, Ok(false) = already filled/gone; both
+            // mean the order should leave tracking.
+            let _ = option_book.cancel_order(id);
+            gone.push(id);
+        }
+
+        let mut active = self.active_orders.write();
+        for id in &gone {
+            active.remove(id);
+        }
+        for (id, level) in placed.iter().zip(levels) {
+            if let Some(id) = id {
+                active.insert(
+                    *id,
                     ActiveOrderInfo {
-                        symbol: symbol.to_string(),
-                        expiration: *expiration,
-                        instrument,
-                        strike,
-                        style,
-                        is_buy: false,
-                        theo_cents: quote_params.theo_price,
-                        quantity: quote_params.ask_size,
+                        is_buy,
+                        quantity: level.size,
+                        ..template.clone()
                     },
                 );
-                placed[leg_slot(false)] = Some(ask_id);
             }
+        }
+        placed
+    }
 
-            // Record both fresh legs in the reverse index under one write lock,
-            // moving the key in (no clone) when at least one leg rested.
-            if placed.iter().any(Option::is_some) {
-                self.instrument_orders
-                    .write()
-                    .insert(instrument_key, placed);
+    /// Moves one ladder level to `level`: amends the resting order `stale` in
+    /// place when possible (an unchanged level is left untouched), otherwise
+    /// cancels it, pushing its id to `gone`, and places a fresh order. Returns
+    /// the id resting afterwards, if any. Takes no lock.
+    ///
+    /// A level that would cross the opposite side of the book is not quoted
+    /// and its stale order is cancelled: the engine cannot book the resting
+    /// side of such a trade to its account, so the quote must never match on
+    /// entry.
+    fn requote_level(
+        &self,
+        option_book: &OptionOrderBook,
+        stale: Option<OrderId>,
+        level: &LadderLevel,
+        is_buy: bool,
+        gone: &mut Vec<OrderId>,
+    ) -> Option<OrderId> {
+        let crosses = if is_buy {
+            option_book.best_ask().is_some_and(|ask| level.price >= ask)
+        } else {
+            option_book.best_bid().is_some_and(|bid| level.price <= bid)
+        };
+        if crosses {
+            debug!(
+                price = level.price,
+                is_buy, "quote level would cross the book, not quoted"
+            );
+            if let Some(id) = stale {
+                let _ = option_book.cancel_order(id);
+                gone.push(id);
             }
-
-            // Broadcast quote update.
-            let _ = self.event_tx.send(MarketMakerEvent::QuoteUpdated {
-                symbol: symbol.to_string(),
-                expiration: ctx.exp_display.to_string(),
-                strike,
-                style: match style {
-                    OptionStyle::Call => "call".to_string(),
-                    OptionStyle::Put => "put".to_string(),
-                },
-                bid_price: quote_params.bid_price,
-                ask_price: quote_params.ask_price,
-                bid_size: quote_params.bid_size,
-                ask_size: quote_params.ask_size,
-            });
+            return None;
         }
+        if let Some(id) = stale {
+            match amend_order(option_book, id, level.price, level.size) {
+                Ok(_) => return Some(id),
+                Err(e) => {
+                    debug!(order_id = %id, error = %e, "amend failed, replacing quote level");
+                    let _ = option_book.cancel_order(id);
+                    gone.push(id);
+                }
+            }
+        }
+
+        let id = self.order_ids.next_id();
+        let side = if is_buy { Side::Buy } else { Side::Sell };
+        option_book
+            .add_limit_order(id, side, level.price, level.size)
+            .ok()?;
+        Some(id)
     }
 
     /// Broadcasts a configuration change event.
@@ -983,6 +1823,31 @@ mod tests {
         engine.track_order_for_test(is_buy, theo_cents, qty)
     }
 
+    #[test]
+    fn test_order_state_round_trip() {
+        let engine = test_engine();
+        let bid = track_order(&engine, true, 100, 10);
+        let ask = track_order(&engine, false, 100, 10);
+        let state = engine.order_state();
+        assert_eq!(state.order_count(), 2);
+
+        // The ladder index survives serialization alongside the orders.
+        let json = serde_json::to_string(&state).expect("serializes");
+        let state: MakerOrderState = serde_json::from_str(&json).expect("deserializes");
+
+        let restored = test_engine();
+        restored.track_order_for_test(true, 50, 1);
+        restored.restore_order_state(state);
+        assert_eq!(
+            instrument_ids(&restored, "BTC", 100_000, OptionStyle::Call),
+            [bid, ask].into_iter().collect()
+        );
+        let index = restored.instrument_orders.read();
+        let slots = index.values().next().expect("one instrument indexed");
+        assert_eq!(slots.bids, vec![Some(bid)]);
+        assert_eq!(slots.asks, vec![Some(ask)]);
+    }
+
     /// Issue #69: a fill on a tracked maker order broadcasts OrderFilled with
     /// the edge computed against the quote-time theo, and a full fill removes
     /// the order from tracking.
@@ -1021,6 +1886,160 @@ mod tests {
         assert!(!engine.active_orders.read().contains_key(&id));
     }
 
+    /// Maker fills accumulate into the engine inventory: bids add, asks
+    /// subtract, and a flat instrument drops out of the snapshot.
+    #[test]
+    fn test_on_order_filled_books_inventory() {
+        let engine = test_engine();
+        assert!(engine.inventory().is_empty());
+
+        let bid = track_order(&engine, true, 100, 10);
+        engine.on_order_filled(bid, 95, 10);
+        let ask = track_order(&engine, false, 105, 10);
+        engine.on_order_filled(ask, 110, 4);
+
+        let inventory = engine.inventory();
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].instrument, "BTC-20351231-100000-C");
+        assert_eq!(inventory[0].underlying, "BTC");
+        assert_eq!(inventory[0].quantity, 6);
+
+        engine.on_order_filled(ask, 110, 6);
+        assert!(engine.inventory().is_empty(), "flat inventory is omitted");
+    }
+
+    /// A transferred position nets against the inventory from quote fills.
+    #[test]
+    fn test_take_inventory_nets_against_quote_fills() {
+        let engine = test_engine();
+        let bid = track_order(&engine, true, 100, 10);
+        engine.on_order_filled(bid, 95, 10);
+
+        let mut transferred = engine.inventory()[0].clone();
+        transferred.quantity = -4;
+        engine.take_inventory(transferred.clone());
+        assert_eq!(engine.inventory()[0].quantity, 6);
+
+        transferred.instrument = "BTC-20351231-100000-P".to_string();
+        transferred.style = OptionStyle::Put;
+        engine.take_inventory(transferred);
+        let inventory = engine.inventory();
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[1].quantity, -4);
+    }
+
+    /// A fill that pushes the inventory delta outside the band is hedged in
+    /// the underlying at spot, and the hedge P&L follows spot afterwards.
+    #[test]
+    fn test_fill_outside_band_is_delta_hedged() {
+        let engine = test_engine();
+        engine.set_hedge_params(HedgeParams {
+            enabled: true,
+            delta_band: 1.0,
+            lot_size: 0.01,
+        });
+        engine.update_price("BTC", 5_000_000);
+        let mut events = engine.subscribe();
+
+        // Ten deep in-the-money calls carry a delta of about +10.
+        let bid = track_order(&engine, true, 100, 10);
+        engine.on_order_filled(bid, 95, 10);
+
+        assert!(matches!(
+            events.try_recv(),
+            Ok(MarketMakerEvent::OrderFilled { .. })
+        ));
+        match events.try_recv().expect("hedge must be broadcast") {
+            MarketMakerEvent::HedgeExecuted {
+                symbol,
+                quantity,
+                price,
+                residual_delta,
+                ..
+            } => {
+                assert_eq!(symbol, "BTC");
+                assert!(quantity < -9.0, "sells the underlying, got {quantity}");
+                assert_eq!(price, 5_000_000);
+                assert!(residual_delta.abs() <= 0.01);
+            }
+            other => panic!("expected HedgeExecuted, got {other:?}"),
+        }
+
+        // A 2% rally barely moves the delta, so no new hedge; the short
+        // underlying loses.
+        engine.update_price("BTC", 5_100_000);
+        assert_eq!(engine.hedge_trades(10).len(), 1);
+        let status = engine.hedge_status();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].trades, 1);
+        assert!(status[0].residual_delta.abs() <= 1.0);
+        assert!(
+            status[0].hedge_pnl < -900_000,
+            "got {}",
+            status[0].hedge_pnl
+        );
+    }
+
+    /// Fills book their edge, later spot moves are explained by the greeks
+    /// of the inventory, and closing a period broadcasts it.
+    #[test]
+    fn test_pnl_attribution_of_edge_and_spot_moves() {
+        let engine = test_engine();
+        engine.update_price("BTC", 5_000_000);
+
+        // Bought 10 deep in-the-money calls 5 cents under theo.
+        let bid = track_order(&engine, true, 100, 10);
+        engine.on_order_filled(bid, 95, 10);
+        let pnl = engine.pnl_current_period().by_underlying["BTC"];
+        assert_eq!(pnl.edge, 50.0);
+        assert_eq!(pnl.delta, 0.0);
+
+        // A $100 rally earns about 10 × $100 of delta P&L.
+        engine.update_price("BTC", 5_010_000);
+        let pnl = engine.pnl_cumulative()["BTC"];
+        assert!((pnl.delta - 100_000.0).abs() < 2_000.0, "got {}", pnl.delta);
+        assert!(pnl.gamma >= 0.0);
+        assert_eq!(pnl.hedging, 0.0, "nothing hedged");
+
+        // A higher marking vol is vega P&L for a long option position.
+        engine.set_mark_vol("BTC", engine.pricer().default_iv() + 0.05);
+        assert!(engine.pnl_cumulative()["BTC"].vega > 0.0);
+
+        let mut events = engine.subscribe();
+        let closed = engine.close_pnl_period();
+        assert!(closed.end_ms.is_some());
+        assert_eq!(closed.by_underlying["BTC"].edge, 50.0);
+        match events.try_recv().expect("closed period must be broadcast") {
+            MarketMakerEvent::PnlPeriodClosed { period } => assert_eq!(period, closed),
+            other => panic!("expected PnlPeriodClosed, got {other:?}"),
+        }
+        assert_eq!(engine.pnl_periods(10), vec![closed]);
+        assert!(
+            engine
+                .pnl_current_period()
+                .by_underlying
+                .get("BTC")
+                .is_none_or(|p| p.edge == 0.0)
+        );
+        assert_eq!(engine.pnl_cumulative()["BTC"].edge, 50.0);
+    }
+
+    /// Hedging is off by default: fills only move the inventory.
+    #[test]
+    fn test_hedging_disabled_by_default() {
+        let engine = test_engine();
+        assert!(!engine.hedge_params().enabled);
+        engine.update_price("BTC", 5_000_000);
+        let bid = track_order(&engine, true, 100, 10);
+        engine.on_order_filled(bid, 95, 10);
+
+        assert!(engine.rehedge("BTC").is_none());
+        assert!(engine.hedge_trades(10).is_empty());
+        let status = engine.hedge_status();
+        assert!(status[0].option_delta > 9.0);
+        assert_eq!(status[0].hedge_position, 0.0);
+    }
+
     /// A sell fill above theo captures positive edge; a partial fill keeps the
     /// order tracked with the remaining quantity.
     #[test]
@@ -1149,6 +2168,220 @@ mod tests {
         assert_eq!(engine.active_orders.read().len(), 4);
     }
 
+    #[test]
+    fn test_unchanged_requote_is_throttled() {
+        let engine = test_engine();
+        let expiration = future_expiration();
+        let underlying = engine.manager.get_or_create("BTC");
+        let exp_book = underlying.get_or_create_expiration(expiration);
+        let strike_book = exp_book.get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+        let mut events = engine.subscribe();
+
+        engine.update_price("BTC", 5_000_000);
+        let placed = instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call);
+        assert_eq!(placed.len(), 2);
+
+        // Same spot: identical quotes keep resting, no cancel/replace.
+        engine.update_price("BTC", 5_000_000);
+        assert_eq!(
+            instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call),
+            placed
+        );
+        assert_eq!(call_book.active_order_count(), 2);
+        let stats = engine.requote_stats();
+        assert_eq!(stats.sent, 2, "call + put quoted once");
+        assert_eq!(stats.skipped_unchanged, 2);
+
+        let quotes = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|e| matches!(e, MarketMakerEvent::QuoteUpdated { .. }))
+            .count();
+        assert_eq!(quotes, 2, "skipped requotes are not broadcast");
+
+        // A move below the price threshold is skipped too.
+        engine.set_requote_params(RequoteParams {
+            min_price_change_ticks: 1_000_000,
+            ..engine.requote_params()
+        });
+        engine.update_price("BTC", 5_010_000);
+        assert_eq!(
+            instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call),
+            placed
+        );
+        assert_eq!(engine.requote_stats().skipped_below_threshold, 2);
+    }
+
+    #[test]
+    fn test_requote_amends_in_place() {
+        let engine = test_engine();
+        let expiration = future_expiration();
+        let underlying = engine.manager.get_or_create("BTC");
+        let exp_book = underlying.get_or_create_expiration(expiration);
+        let strike_book = exp_book.get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+
+        engine.update_price("BTC", 5_000_000);
+        let placed = instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call);
+        let bid_before = call_book.best_bid();
+
+        // A real move reprices both legs, keeping the order ids.
+        engine.update_price("BTC", 5_100_000);
+        assert_eq!(
+            instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call),
+            placed,
+            "requote amends instead of minting new orders"
+        );
+        assert_eq!(call_book.active_order_count(), 2);
+        assert_ne!(call_book.best_bid(), bid_before, "bid repriced");
+
+        let positions = engine.queue_positions(Some("BTC"));
+        assert_eq!(positions.len(), 4, "bid and ask of the call and the put");
+        assert!(positions[0].is_buy, "bid first");
+        assert!(
+            positions
+                .iter()
+                .all(|p| p.position.orders_ahead == 0 && p.position.quantity > 0)
+        );
+        assert!(engine.queue_positions(Some("ETH")).is_empty());
+    }
+
+    #[test]
+    fn test_requote_never_crosses_a_resting_order() {
+        let engine = test_engine();
+        let expiration = future_expiration();
+        let underlying = engine.manager.get_or_create("BTC");
+        let exp_book = underlying.get_or_create_expiration(expiration);
+        let strike_book = exp_book.get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+
+        engine.update_price("BTC", 5_000_000);
+        let bid = call_book.best_bid().expect("maker bid");
+        let ask = call_book.best_ask().expect("maker ask");
+        assert!(ask > bid + 1);
+
+        // A user ask just above the maker bid, which the bid would reach
+        // after a rally.
+        let user_ask = OrderId::new();
+        call_book
+            .add_limit_order(user_ask, Side::Sell, bid + 1, 3)
+            .expect("user ask rests");
+        engine.update_price("BTC", 5_500_000);
+
+        let resting = call_book.inner().get_order(user_ask).expect("untouched");
+        assert_eq!(resting.visible_quantity().as_u64(), 3);
+        assert!(call_book.best_bid().is_none_or(|b| b < bid + 1));
+        assert!(engine.inventory().is_empty(), "the maker did not trade");
+    }
+
+    #[test]
+    fn test_layered_strategy_rests_a_ladder_per_underlying() {
+        use crate::market_maker::LayeredStrategy;
+
+        let engine = test_engine();
+        let expiration = future_expiration();
+        let strike_book = engine
+            .manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(expiration)
+            .get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+        engine.set_strategy("BTC", Some(Arc::new(LayeredStrategy::default())));
+        assert_eq!(engine.strategy("BTC").name(), "layered");
+        assert_eq!(engine.strategy("ETH").name(), "single");
+
+        engine.update_price("BTC", 5_000_000);
+        assert_eq!(call_book.active_order_count(), 6, "three levels per side");
+        assert_eq!(
+            engine.resting_order_count("BTC"),
+            12,
+            "call and put ladders"
+        );
+        let placed = instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call);
+        let key = InstrumentKey::new("BTC", &expiration, 5_000_000, OptionStyle::Call);
+        let slots = engine.instrument_orders.read().get(&key).cloned();
+        assert!(slots.is_some_and(|s| s.bids.len() == 3 && s.is_complete()));
+
+        // The whole ladder is amended in place on a move.
+        engine.update_price("BTC", 5_100_000);
+        assert_eq!(
+            instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call),
+            placed
+        );
+
+        // Back to the default strategy: the extra levels are cancelled.
+        engine.set_strategy("BTC", None);
+        engine.update_price("BTC", 5_200_000);
+        assert_eq!(call_book.active_order_count(), 2);
+        assert_eq!(
+            instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_quotes_rest_on_the_underlying_tick_grid() {
+        use crate::market_maker::LayeredStrategy;
+
+        let engine = test_engine();
+        let expiration = future_expiration();
+        let strike_book = engine
+            .manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(expiration)
+            .get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+        // 0.05% of a $50,000 spot: a $25 tick.
+        engine.set_tick_table("BTC", TickTable::UnderlyingFraction(0.0005));
+        engine.set_strategy("BTC", Some(Arc::new(LayeredStrategy::default())));
+
+        engine.update_price("BTC", 5_000_000);
+        assert_eq!(call_book.active_order_count(), 6);
+        let ticks = engine.tick_table("BTC");
+        for id in instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call) {
+            let price = call_book
+                .inner()
+                .get_order(id)
+                .expect("resting")
+                .price()
+                .as_u128();
+            assert!(ticks.is_on_tick(price, Some(5_000_000)), "{price} off grid");
+            assert_eq!(price % 2_500, 0);
+        }
+    }
+
+    #[test]
+    fn test_quote_sizes_are_whole_lots_within_the_max_order_size() {
+        let engine = test_engine();
+        let strike_book = engine
+            .manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(future_expiration())
+            .get_or_create_strike(5_000_000);
+        let call_book = strike_book.get(OptionStyle::Call);
+        engine.set_contract_spec(
+            "BTC",
+            ContractSpec {
+                multiplier: 1,
+                min_lot_size: 7,
+                max_order_size: Some(30),
+            },
+        );
+
+        engine.update_price("BTC", 5_000_000);
+        let ids = instrument_ids(&engine, "BTC", 5_000_000, OptionStyle::Call);
+        assert!(!ids.is_empty());
+        for id in ids {
+            let size = call_book
+                .inner()
+                .get_order(id)
+                .expect("resting")
+                .visible_quantity()
+                .as_u64();
+            assert!(size.is_multiple_of(7), "{size} is not whole lots");
+            assert!(size <= 28, "{size} exceeds the max order size");
+        }
+    }
+
     #[test]
     fn test_requote_days_expiration_does_not_accumulate_orders() {
         // Issue #107 (P2-03): a `Days`-variant expiration's `Display` string is
@@ -1258,6 +2491,9 @@ mod tests {
         let expiration = future_expiration();
         let exp_str = expiration.to_string();
         let config = engine.get_config();
+        let strategy = engine.strategy("ETH");
+        let inventory = HashMap::new();
+        let ticks = TickTable::default();
 
         let underlying = engine.manager.get_or_create("ETH");
         let exp_book = underlying.get_or_create_expiration(expiration);
@@ -1275,6 +2511,11 @@ mod tests {
             exp_canonical: &exp_str,
             spot_cents: 350_000,
             config: &config,
+            strategy: strategy.as_ref(),
+            surface_vol: None,
+            inventory: &inventory,
+            ticks: &ticks,
+            contract: ContractSpec::default(),
         };
         engine.update_quote(&ctx, 300_000, OptionStyle::Call);
         engine.update_quote(&ctx, 300_000, OptionStyle::Put);
@@ -1296,6 +2537,11 @@ mod tests {
             exp_canonical: &exp_str,
             spot_cents: 351_000,
             config: &config,
+            strategy: strategy.as_ref(),
+            surface_vol: None,
+            inventory: &inventory,
+            ticks: &ticks,
+            contract: ContractSpec::default(),
         };
         engine.update_quote(&requote_ctx, 300_000, OptionStyle::Call);
 
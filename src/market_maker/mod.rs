@@ -1,13 +1,32 @@
 //! Market maker algorithms and quoting engine.
 
+mod attribution;
 mod engine;
+mod hedger;
+mod parity;
 mod pricer;
 mod quoter;
+mod strategy;
+mod throttle;
 
+pub use attribution::{
+    AttributedPosition, MAX_PNL_PERIODS, PnlAttributor, PnlComponents, PnlMark, PnlPeriod,
+    greek_pnl,
+};
 pub use engine::{
-    DIRECTIONAL_SKEW_MAX, DIRECTIONAL_SKEW_MIN, MarketMakerConfig, MarketMakerEngine,
-    MarketMakerEvent, SIZE_SCALAR_MAX, SIZE_SCALAR_MIN, SPREAD_MULTIPLIER_MAX,
-    SPREAD_MULTIPLIER_MIN, validate_control_value,
+    DIRECTIONAL_SKEW_MAX, DIRECTIONAL_SKEW_MIN, HedgeStatus, InventoryPosition, MakerBookState,
+    MakerOrderState, MakerQueuePosition, MarketMakerConfig, MarketMakerEngine, MarketMakerEvent,
+    SIZE_SCALAR_MAX, SIZE_SCALAR_MIN, SPREAD_MULTIPLIER_MAX, SPREAD_MULTIPLIER_MIN,
+    validate_control_value,
+};
+pub use hedger::{
+    DeltaHedger, HedgeBook, HedgeParams, HedgeTrade, MAX_HEDGE_TRADES, hedge_quantity,
 };
+pub use parity::{ParityFit, ParityQuote, fit_implied_forward};
 pub use pricer::OptionPricer;
 pub use quoter::{QuoteInput, QuoteParams, Quoter};
+pub use strategy::{
+    BookState, LadderLevel, LayeredStrategy, QuoteLadder, QuotingStrategy, SingleLevelStrategy,
+    StrategyInput, build_strategy,
+};
+pub use throttle::{QuoteLevels, RequoteDecision, RequoteParams, RequoteStats, RequoteThrottle};
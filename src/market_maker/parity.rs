@@ -0,0 +1,220 @@
+//! Put-call parity regression for implied forwards.
+//!
+//! For European options on the same expiration, put-call parity states
+//! `C - P = D · (F - K)`, where `D` is the discount factor to expiry and `F`
+//! the forward of the underlying. Regressing the observed `C - P` on the strike
+//! across a chain therefore yields a line whose slope is `-D` and whose
+//! intercept is `D · F`, so both the implied forward and the implied carry can
+//! be read directly off the order books — no assumption that the forward equals
+//! the simulator spot, which is wrong for dated futures-style underlyings.
+
+/// Largest discount factor accepted from the regression.
+///
+/// Slightly above 1.0 so mildly negative implied rates are still reported; a
+/// fitted slope beyond this bound is a degenerate fit (crossed or stale books),
+/// not a carry reading.
+const MAX_DISCOUNT_FACTOR: f64 = 1.5;
+
+/// Two-sided call and put mids observed at one strike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityQuote {
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Call mid price in cents.
+    pub call_mid: u128,
+    /// Put mid price in cents.
+    pub put_mid: u128,
+}
+
+/// Result of a put-call parity regression across one expiration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParityFit {
+    /// Implied forward price in cents (unrounded).
+    pub forward: f64,
+    /// Implied discount factor to expiry (`D` in `C - P = D · (F - K)`).
+    pub discount_factor: f64,
+    /// Per-strike residual `(C - P) - D · (F - K)` in cents, in input order.
+    pub residuals: Vec<(u64, f64)>,
+    /// Root-mean-square residual in cents.
+    pub rmse: f64,
+}
+
+impl ParityFit {
+    /// Forward price rounded to integer cents.
+    #[must_use]
+    pub fn forward_cents(&self) -> u64 {
+        self.forward.round() as u64
+    }
+
+    /// Continuously-compounded implied rate `-ln(D) / T` for `years` to expiry.
+    ///
+    /// Returns `None` when `years` is not a positive finite value.
+    #[must_use]
+    pub fn implied_rate(&self, years: f64) -> Option<f64> {
+        if !years.is_finite() || years <= 0.0 {
+            return None;
+        }
+        Some(-self.discount_factor.ln() / years)
+    }
+
+    /// Synthetic forward implied by a single strike: `K + (C - P) / D`, in cents.
+    #[must_use]
+    pub fn synthetic_forward(&self, quote: &ParityQuote) -> f64 {
+        quote.strike as f64 + (quote.call_mid as f64 - quote.put_mid as f64) / self.discount_factor
+    }
+}
+
+/// Fits the implied forward and discount factor by ordinary least squares of
+/// `C - P` on `K`.
+///
+/// Needs at least two DISTINCT strikes. Returns `None` when the inputs cannot
+/// identify a line, or when the fit is degenerate: a discount factor outside
+/// `(0, 1.5]` or a non-positive forward means the books disagree with parity
+/// too much to be read as a carry, and callers must fall back to spot rather
+/// than use it.
+#[must_use]
+pub fn fit_implied_forward(quotes: &[ParityQuote]) -> Option<ParityFit> {
+    if quotes.len() < 2 {
+        return None;
+    }
+
+    let n = quotes.len() as f64;
+    let xs: Vec<f64> = quotes.iter().map(|q| q.strike as f64).collect();
+    let ys: Vec<f64> = quotes
+        .iter()
+        .map(|q| q.call_mid as f64 - q.put_mid as f64)
+        .collect();
+
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let (mut sxx, mut sxy) = (0.0, 0.0);
+    for (x, y) in xs.iter().zip(&ys) {
+        sxx += (x - mean_x) * (x - mean_x);
+        sxy += (x - mean_x) * (y - mean_y);
+    }
+    if sxx <= 0.0 {
+        // Every quote sits on the same strike: the slope is unidentified.
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+
+    let discount_factor = -slope;
+    if !discount_factor.is_finite()
+        || discount_factor <= 0.0
+        || discount_factor > MAX_DISCOUNT_FACTOR
+    {
+        return None;
+    }
+    let forward = intercept / discount_factor;
+    if !forward.is_finite() || forward <= 0.0 {
+        return None;
+    }
+
+    let residuals: Vec<(u64, f64)> = quotes
+        .iter()
+        .zip(xs.iter().zip(&ys))
+        .map(|(q, (x, y))| (q.strike, y - (intercept + slope * x)))
+        .collect();
+    let rmse = (residuals.iter().map(|(_, r)| r * r).sum::<f64>() / n).sqrt();
+
+    Some(ParityFit {
+        forward,
+        discount_factor,
+        residuals,
+        rmse,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds quotes that satisfy parity exactly for the given forward and
+    /// discount factor, keeping both legs positive.
+    fn parity_quotes(forward: f64, discount: f64, strikes: &[u64]) -> Vec<ParityQuote> {
+        strikes
+            .iter()
+            .map(|&k| {
+                let diff = discount * (forward - k as f64);
+                let put_mid = 1_000.0 + (-diff).max(0.0);
+                let call_mid = put_mid + diff;
+                ParityQuote {
+                    strike: k,
+                    call_mid: call_mid.round() as u128,
+                    put_mid: put_mid.round() as u128,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_recovers_forward_and_discount() {
+        let quotes = parity_quotes(5_050_000.0, 0.99, &[4_800_000, 5_000_000, 5_200_000]);
+        let fit = fit_implied_forward(&quotes).expect("well-posed fit");
+
+        assert!((fit.forward - 5_050_000.0).abs() < 2.0, "{}", fit.forward);
+        assert!((fit.discount_factor - 0.99).abs() < 1e-6);
+        assert!(fit.rmse < 1.0);
+        assert_eq!(fit.forward_cents(), 5_050_000);
+    }
+
+    #[test]
+    fn test_fit_flags_single_strike_deviation_in_residuals() {
+        let mut quotes = parity_quotes(10_000.0, 1.0, &[9_000, 9_500, 10_000, 10_500, 11_000]);
+        // Inflate the 10_000 call by 200 cents: a parity violation.
+        quotes[2].call_mid += 200;
+
+        let fit = fit_implied_forward(&quotes).expect("fit still well-posed");
+        let (worst_strike, worst) = fit
+            .residuals
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .expect("residuals present");
+        assert_eq!(worst_strike, 10_000);
+        assert!(worst > 100.0, "residual {worst}");
+    }
+
+    #[test]
+    fn test_fit_requires_two_distinct_strikes() {
+        let one = parity_quotes(10_000.0, 1.0, &[10_000]);
+        assert!(fit_implied_forward(&one).is_none());
+
+        let same = vec![one[0], one[0]];
+        assert!(fit_implied_forward(&same).is_none());
+    }
+
+    #[test]
+    fn test_fit_rejects_degenerate_slope() {
+        // C - P rising with strike implies a negative discount factor.
+        let quotes = vec![
+            ParityQuote {
+                strike: 9_000,
+                call_mid: 100,
+                put_mid: 500,
+            },
+            ParityQuote {
+                strike: 11_000,
+                call_mid: 500,
+                put_mid: 100,
+            },
+        ];
+        assert!(fit_implied_forward(&quotes).is_none());
+    }
+
+    #[test]
+    fn test_implied_rate_and_synthetic_forward() {
+        let quotes = parity_quotes(10_000.0, (-0.05f64 * 0.5).exp(), &[9_000, 10_000, 11_000]);
+        let fit = fit_implied_forward(&quotes).expect("well-posed fit");
+
+        let rate = fit.implied_rate(0.5).expect("positive tenor");
+        assert!((rate - 0.05).abs() < 1e-3, "rate {rate}");
+        assert!(fit.implied_rate(0.0).is_none());
+
+        let synthetic = fit.synthetic_forward(&quotes[1]);
+        assert!((synthetic - 10_000.0).abs() < 2.0, "{synthetic}");
+    }
+}
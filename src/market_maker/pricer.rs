@@ -26,6 +26,18 @@ impl OptionPricer {
         }
     }
 
+    /// Annualized risk-free rate used for discounting.
+    #[must_use]
+    pub fn risk_free_rate(&self) -> f64 {
+        self.risk_free_rate
+    }
+
+    /// Implied volatility used when no override is supplied.
+    #[must_use]
+    pub fn default_iv(&self) -> f64 {
+        self.default_iv
+    }
+
     /// Calculates the theoretical value of an option.
     ///
     /// # Arguments
@@ -193,13 +205,43 @@ impl OptionPricer {
         theta / 365.0 // Daily theta
     }
 
+    /// Calculates rho for an option (per 1% rate change).
+    #[must_use]
+    pub fn rho(
+        &self,
+        spot: f64,
+        strike: f64,
+        expiration: &ExpirationDate,
+        style: OptionStyle,
+        iv: Option<f64>,
+    ) -> f64 {
+        let sigma = iv.unwrap_or(self.default_iv);
+        let t = self.time_to_expiry(expiration);
+
+        if t <= 0.0 {
+            return 0.0;
+        }
+
+        let d1 = ((spot / strike).ln() + (self.risk_free_rate + sigma * sigma / 2.0) * t)
+            / (sigma * t.sqrt());
+        let d2 = d1 - sigma * t.sqrt();
+
+        let discount = (-self.risk_free_rate * t).exp();
+
+        let rho = match style {
+            OptionStyle::Call => strike * t * discount * Self::norm_cdf(d2),
+            OptionStyle::Put => -strike * t * discount * Self::norm_cdf(-d2),
+        };
+
+        rho / 100.0 // Per 1% rate change
+    }
+
     /// Converts expiration to time in years.
     fn time_to_expiry(&self, expiration: &ExpirationDate) -> f64 {
         match expiration {
             ExpirationDate::Days(days) => days.to_f64() / 365.0,
             ExpirationDate::DateTime(dt) => {
-                let now = chrono::Utc::now();
-                let duration = *dt - now;
+                let duration = *dt - crate::clock::now();
                 duration.num_seconds() as f64 / (365.0 * 24.0 * 3600.0)
             }
         }
@@ -274,6 +316,20 @@ mod tests {
         assert!(put_delta > -0.6 && put_delta < -0.4); // ATM put delta ~-0.5
     }
 
+    #[test]
+    fn test_rho_sign_and_magnitude() {
+        let pricer = OptionPricer::default();
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+
+        let call_rho = pricer.rho(100.0, 100.0, &exp, OptionStyle::Call, Some(0.20));
+        let put_rho = pricer.rho(100.0, 100.0, &exp, OptionStyle::Put, Some(0.20));
+        assert!(call_rho > 0.0 && put_rho < 0.0);
+        // Call rho - put rho = K·T·e^(-rT) / 100 (parity in the rate).
+        let t = 30.0 / 365.0;
+        let expected = 100.0 * t * (-0.05f64 * t).exp() / 100.0;
+        assert!((call_rho - put_rho - expected).abs() < 1e-6);
+    }
+
     #[test]
     fn test_theoretical_value_non_finite_on_degenerate_iv() {
         // The Black-Scholes approximation can return a non-finite value for a
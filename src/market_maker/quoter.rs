@@ -1,6 +1,7 @@
 //! Quote generation for market making.
 
 use crate::market_maker::OptionPricer;
+use crate::ticks::TickTable;
 use optionstratlib::{ExpirationDate, OptionStyle};
 
 /// Basis-points denominator: 1 basis point = 1/10_000, so a bps value is applied
@@ -62,6 +63,8 @@ pub struct QuoteInput<'a> {
     pub directional_skew: f64,
     /// Optional implied volatility.
     pub iv: Option<f64>,
+    /// Tick-size table the prices are rounded onto (one cent when `None`).
+    pub ticks: Option<&'a TickTable>,
 }
 
 /// Quoter generates bid/ask quotes for options.
@@ -89,6 +92,12 @@ impl Quoter {
         }
     }
 
+    /// The pricer theoretical values are computed with.
+    #[must_use]
+    pub fn pricer(&self) -> &OptionPricer {
+        &self.pricer
+    }
+
     /// Generates a two-sided quote for an option.
     ///
     /// # Arguments
@@ -164,6 +173,19 @@ impl Quoter {
         let ask_price = (theo_cents as i64 + half_spread_cents as i64 + ask_adjustment)
             .max(bid_price as i64 + 1) as u128;
 
+        // Round onto the tick grid away from theo: the bid down (but never
+        // below one tick) and the ask up, which keeps the ask above the bid.
+        let (bid_price, ask_price) = match input.ticks {
+            Some(ticks) => {
+                let spot = Some(input.spot_cents);
+                let bid = ticks
+                    .round_down(bid_price, spot)
+                    .max(ticks.round_up(1, spot));
+                (bid, ticks.round_up(ask_price.max(bid + 1), spot))
+            }
+            None => (bid_price, ask_price),
+        };
+
         // Calculate sizes with scalar
         let base_size = (self.base_size as f64 * input.size_scalar).max(1.0) as u64;
 
@@ -240,6 +262,7 @@ mod tests {
             size_scalar: 1.0,
             directional_skew: 0.0,
             iv: Some(0.20),
+            ticks: None,
         };
 
         let quote = quoter
@@ -266,6 +289,7 @@ mod tests {
             size_scalar: 1.0,
             directional_skew: 0.0,
             iv: Some(0.20),
+            ticks: None,
         };
 
         let bullish_input = QuoteInput {
@@ -307,6 +331,7 @@ mod tests {
             size_scalar: 1.0,
             directional_skew: 0.0,
             iv: Some(0.50),
+            ticks: None,
         };
         let call_bullish = QuoteInput {
             directional_skew: 0.5,
@@ -441,6 +466,7 @@ mod tests {
                 size_scalar: 1.0,
                 directional_skew: 0.0,
                 iv: Some(bad_iv),
+                ticks: None,
             };
 
             assert!(
@@ -468,6 +494,7 @@ mod tests {
             size_scalar: 1.0,
             directional_skew: 0.0,
             iv: Some(f64::INFINITY),
+            ticks: None,
         };
 
         match quoter.generate_quote(&input) {
@@ -494,6 +521,7 @@ mod tests {
             size_scalar: 1.0,
             directional_skew: 0.0,
             iv: Some(0.20),
+            ticks: None,
         };
 
         let quote = quoter
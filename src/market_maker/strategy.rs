@@ -0,0 +1,504 @@
+//! Pluggable quoting strategies.
+//!
+//! A [`QuotingStrategy`] turns the state of one option — spot, the marking
+//! volatility of its underlying, the maker's inventory in it and its book —
+//! together with the control parameters into a [`QuoteLadder`]: the
+//! theoretical value and one or more price levels per side, best first. The
+//! engine amends its resting orders to match the ladder.
+//!
+//! Strategies are selected per underlying, so a new one can be tried on a
+//! single underlying without changing the engine. Two are built in:
+//!
+//! * [`SingleLevelStrategy`] (`single`, the default) quotes one bid and one ask
+//!   with the [`Quoter`];
+//! * [`LayeredStrategy`] (`layered`) quotes the same best level plus a ladder
+//!   of further levels away from it, shaped by a [`LadderConfig`].
+
+use crate::config::{LadderConfig, LadderSpacing, SizeProfile, StrategyKind};
+use crate::market_maker::{MarketMakerConfig, QuoteInput, QuoteLevels, QuoteParams, Quoter};
+use crate::ticks::TickTable;
+use option_chain_orderbook::orderbook::OptionOrderBook;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use std::sync::Arc;
+
+/// Basis-points denominator.
+const BPS_DENOMINATOR: f64 = 10_000.0;
+
+/// Top of an option's book when a strategy is asked for a quote. The maker's
+/// own resting orders are included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookState {
+    /// Best bid price in cents.
+    pub best_bid: Option<u128>,
+    /// Best ask price in cents.
+    pub best_ask: Option<u128>,
+    /// Total resting bid quantity.
+    pub bid_depth: u64,
+    /// Total resting ask quantity.
+    pub ask_depth: u64,
+}
+
+impl BookState {
+    /// Reads the top of `book`.
+    #[must_use]
+    pub fn of(book: &OptionOrderBook) -> Self {
+        Self {
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+            bid_depth: book.total_bid_depth(),
+            ask_depth: book.total_ask_depth(),
+        }
+    }
+}
+
+/// Everything a strategy may quote one option from.
+#[derive(Debug, Clone)]
+pub struct StrategyInput<'a> {
+    /// Underlying symbol.
+    pub symbol: &'a str,
+    /// Underlying price in cents.
+    pub spot_cents: u64,
+    /// Strike price in cents.
+    pub strike_cents: u64,
+    /// Expiration of the option.
+    pub expiration: &'a ExpirationDate,
+    /// Option style.
+    pub style: OptionStyle,
+    /// Tick-size table of the underlying; every quoted price must be on it.
+    pub ticks: &'a TickTable,
+    /// Volatility the underlying is marked at (e.g. the ATM vol of the latest
+    /// surface), once it has been marked.
+    pub surface_vol: Option<f64>,
+    /// The maker's net position in the option (positive = long).
+    pub inventory: i64,
+    /// Top of the option's book.
+    pub book: BookState,
+    /// Market maker control parameters.
+    pub config: &'a MarketMakerConfig,
+}
+
+/// One price level of a ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LadderLevel {
+    /// Price in cents.
+    pub price: u128,
+    /// Size.
+    pub size: u64,
+}
+
+/// A two-sided quote of one or more levels per side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteLadder {
+    /// Theoretical value in cents the ladder was built around.
+    pub theo_price: u64,
+    /// Bid levels, best (highest) first.
+    pub bids: Vec<LadderLevel>,
+    /// Ask levels, best (lowest) first.
+    pub asks: Vec<LadderLevel>,
+}
+
+impl From<QuoteParams> for QuoteLadder {
+    fn from(quote: QuoteParams) -> Self {
+        Self {
+            theo_price: quote.theo_price,
+            bids: vec![LadderLevel {
+                price: quote.bid_price,
+                size: quote.bid_size,
+            }],
+            asks: vec![LadderLevel {
+                price: quote.ask_price,
+                size: quote.ask_size,
+            }],
+        }
+    }
+}
+
+impl QuoteLadder {
+    /// Whether the ladder can be placed: both sides have at least one level,
+    /// every price and size is positive, bids strictly fall and asks strictly
+    /// rise away from the best level, and the best bid is below the best ask.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let side_ok = |levels: &[LadderLevel], descending: bool| {
+            !levels.is_empty()
+                && levels.iter().all(|l| l.price > 0 && l.size > 0)
+                && levels.windows(2).all(|w| {
+                    if descending {
+                        w[0].price > w[1].price
+                    } else {
+                        w[0].price < w[1].price
+                    }
+                })
+        };
+        side_ok(&self.bids, true)
+            && side_ok(&self.asks, false)
+            && self.bids[0].price < self.asks[0].price
+    }
+
+    /// The best bid and ask, as compared by the requote throttle.
+    ///
+    /// # Panics
+    /// Panics if a side is empty; only call it on a valid ladder.
+    #[must_use]
+    pub fn top(&self) -> QuoteLevels {
+        QuoteLevels {
+            bid_price: self.bids[0].price,
+            ask_price: self.asks[0].price,
+            bid_size: self.bids[0].size,
+            ask_size: self.asks[0].size,
+        }
+    }
+}
+
+/// A market-making strategy: prices one option into a [`QuoteLadder`].
+///
+/// Implementations must be cheap and non-blocking; they run for every option
+/// of an underlying on each price tick.
+pub trait QuotingStrategy: Send + Sync {
+    /// Short name reported by the controls API.
+    fn name(&self) -> &'static str;
+
+    /// Most price levels per side the strategy quotes.
+    fn levels(&self) -> usize {
+        1
+    }
+
+    /// Quotes one option, or `None` to leave it unquoted (e.g. when no finite
+    /// theoretical value exists). A ladder that is not
+    /// [valid](QuoteLadder::is_valid) is skipped by the engine.
+    fn quote(&self, input: &StrategyInput<'_>) -> Option<QuoteLadder>;
+}
+
+/// Builds the [`QuoteInput`] the built-in strategies price with.
+fn quote_input<'a>(input: &StrategyInput<'a>) -> QuoteInput<'a> {
+    QuoteInput {
+        spot_cents: input.spot_cents,
+        strike_cents: input.strike_cents,
+        expiration: input.expiration,
+        style: input.style,
+        spread_multiplier: input.config.spread_multiplier,
+        size_scalar: input.config.size_scalar,
+        directional_skew: input.config.directional_skew,
+        iv: None,
+        ticks: Some(input.ticks),
+    }
+}
+
+/// One bid and one ask from the [`Quoter`]: the engine's original behaviour.
+#[derive(Default)]
+pub struct SingleLevelStrategy {
+    quoter: Quoter,
+}
+
+impl SingleLevelStrategy {
+    /// Creates the strategy around `quoter`.
+    #[must_use]
+    pub fn new(quoter: Quoter) -> Self {
+        Self { quoter }
+    }
+}
+
+impl QuotingStrategy for SingleLevelStrategy {
+    fn name(&self) -> &'static str {
+        "single"
+    }
+
+    fn quote(&self, input: &StrategyInput<'_>) -> Option<QuoteLadder> {
+        self.quoter
+            .generate_quote(&quote_input(input))
+            .map(QuoteLadder::from)
+    }
+}
+
+/// A ladder per side: the [`Quoter`]'s quote as the best level, then
+/// `levels - 1` more, each one spacing further out with a size set by the
+/// size profile. Deeper levels are rounded away from the best level onto the
+/// tick grid; bid levels that would reach zero are dropped.
+pub struct LayeredStrategy {
+    quoter: Quoter,
+    ladder: LadderConfig,
+}
+
+impl Default for LayeredStrategy {
+    fn default() -> Self {
+        Self::from(&LadderConfig::default())
+    }
+}
+
+impl From<&LadderConfig> for LayeredStrategy {
+    fn from(ladder: &LadderConfig) -> Self {
+        Self::new(Quoter::default(), ladder.clone())
+    }
+}
+
+impl LayeredStrategy {
+    /// Creates the strategy. The level count is clamped to at least one.
+    #[must_use]
+    pub fn new(quoter: Quoter, mut ladder: LadderConfig) -> Self {
+        ladder.levels = ladder.levels.max(1);
+        Self { quoter, ladder }
+    }
+
+    /// Distance between levels in cents, a whole number of ticks.
+    fn step_cents(&self, theo_cents: u64, tick_cents: u128) -> u128 {
+        let tick = tick_cents.max(1);
+        match self.ladder.spacing {
+            LadderSpacing::Ticks(ticks) => tick.saturating_mul(u128::from(ticks.max(1))),
+            LadderSpacing::Bps(bps) => {
+                let cents = theo_cents as f64 * bps / BPS_DENOMINATOR;
+                ((cents / tick as f64).round().max(1.0) as u128).saturating_mul(tick)
+            }
+        }
+    }
+
+    /// Size of level `level` given the best level's `best` size; never zero.
+    fn level_size(&self, best: u64, level: usize) -> u64 {
+        let factor = match self.ladder.size_profile {
+            SizeProfile::Flat => 1.0,
+            SizeProfile::Linear => (self.ladder.levels - level) as f64 / self.ladder.levels as f64,
+            SizeProfile::Geometric => self.ladder.size_decay.powi(level as i32),
+        };
+        ((best as f64 * factor).round() as u64).max(1)
+    }
+}
+
+impl QuotingStrategy for LayeredStrategy {
+    fn name(&self) -> &'static str {
+        "layered"
+    }
+
+    fn levels(&self) -> usize {
+        self.ladder.levels
+    }
+
+    fn quote(&self, input: &StrategyInput<'_>) -> Option<QuoteLadder> {
+        let best = self.quoter.generate_quote(&quote_input(input))?;
+        let spot = Some(input.spot_cents);
+        let tick = input.ticks.tick_at(u128::from(best.theo_price), spot);
+        let step = self.step_cents(best.theo_price, tick);
+
+        let mut ladder = QuoteLadder::from(best);
+        let (best_bid, best_ask) = (ladder.bids[0], ladder.asks[0]);
+        for level in 1..self.ladder.levels {
+            let offset = step.saturating_mul(level as u128);
+            // A band boundary can round two levels onto one price; keep each
+            // side strictly moving away from the best level.
+            let price = input
+                .ticks
+                .round_down(best_bid.price.saturating_sub(offset), spot);
+            if price > 0 && ladder.bids.last().is_some_and(|last| price < last.price) {
+                ladder.bids.push(LadderLevel {
+                    price,
+                    size: self.level_size(best_bid.size, level),
+                });
+            }
+            let price = input
+                .ticks
+                .round_up(best_ask.price.saturating_add(offset), spot);
+            if ladder.asks.last().is_some_and(|last| price > last.price) {
+                ladder.asks.push(LadderLevel {
+                    price,
+                    size: self.level_size(best_ask.size, level),
+                });
+            }
+        }
+        Some(ladder)
+    }
+}
+
+/// Builds the built-in strategy `kind`; a layered one quotes `ladder`.
+#[must_use]
+pub fn build_strategy(kind: StrategyKind, ladder: &LadderConfig) -> Arc<dyn QuotingStrategy> {
+    match kind {
+        StrategyKind::Single => Arc::new(SingleLevelStrategy::default()),
+        StrategyKind::Layered => Arc::new(LayeredStrategy::from(ladder)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::prelude::Positive;
+
+    static ONE_CENT: std::sync::LazyLock<TickTable> = std::sync::LazyLock::new(TickTable::default);
+
+    fn input<'a>(exp: &'a ExpirationDate, config: &'a MarketMakerConfig) -> StrategyInput<'a> {
+        StrategyInput {
+            symbol: "BTC",
+            spot_cents: 10_000_000,
+            strike_cents: 10_000_000,
+            expiration: exp,
+            style: OptionStyle::Call,
+            ticks: &ONE_CENT,
+            surface_vol: None,
+            inventory: 0,
+            book: BookState::default(),
+            config,
+        }
+    }
+
+    fn ladder(levels: usize, spacing: LadderSpacing) -> LadderConfig {
+        LadderConfig {
+            levels,
+            spacing,
+            ..LadderConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_single_level_matches_quoter() {
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+        let config = MarketMakerConfig::default();
+        let input = input(&exp, &config);
+
+        let ladder = SingleLevelStrategy::default()
+            .quote(&input)
+            .expect("finite theo");
+        let quote = Quoter::default()
+            .generate_quote(&quote_input(&input))
+            .expect("finite theo");
+        assert_eq!(ladder, QuoteLadder::from(quote));
+        assert!(ladder.is_valid());
+    }
+
+    #[test]
+    fn test_layered_levels_step_out_with_decaying_size() {
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+        let config = MarketMakerConfig::default();
+        let input = input(&exp, &config);
+
+        let single = SingleLevelStrategy::default()
+            .quote(&input)
+            .expect("finite theo");
+        let ladder = LayeredStrategy::default()
+            .quote(&input)
+            .expect("finite theo");
+
+        assert!(ladder.is_valid());
+        assert_eq!(ladder.bids.len(), 3);
+        assert_eq!(ladder.asks.len(), 3);
+        assert_eq!(ladder.top(), single.top(), "best level unchanged");
+        let step = ladder.asks[1].price - ladder.asks[0].price;
+        assert_eq!(
+            step,
+            (ladder.theo_price as f64 * 0.005).round() as u128,
+            "50 bps of theo"
+        );
+        assert_eq!(ladder.bids[0].price - ladder.bids[2].price, 2 * step);
+        assert_eq!(ladder.bids[1].size, 5);
+        assert_eq!(ladder.bids[2].size, 3);
+    }
+
+    #[test]
+    fn test_layered_drops_bid_levels_at_zero() {
+        let strategy =
+            LayeredStrategy::new(Quoter::default(), ladder(4, LadderSpacing::Bps(100_000.0)));
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+        let config = MarketMakerConfig::default();
+
+        let ladder = strategy.quote(&input(&exp, &config)).expect("finite theo");
+        assert!(ladder.is_valid());
+        assert_eq!(
+            ladder.bids.len(),
+            1,
+            "deeper bids would be at or below zero"
+        );
+        assert_eq!(ladder.asks.len(), 4);
+    }
+
+    #[test]
+    fn test_invalid_ladders_are_detected() {
+        let level = |price, size| LadderLevel { price, size };
+        let ladder = |bids, asks| QuoteLadder {
+            theo_price: 100,
+            bids,
+            asks,
+        };
+        assert!(ladder(vec![level(99, 1)], vec![level(101, 1)]).is_valid());
+        assert!(!ladder(vec![], vec![level(101, 1)]).is_valid());
+        assert!(!ladder(vec![level(101, 1)], vec![level(101, 1)]).is_valid());
+        assert!(!ladder(vec![level(99, 0)], vec![level(101, 1)]).is_valid());
+        assert!(!ladder(vec![level(98, 1), level(99, 1)], vec![level(101, 1)]).is_valid());
+        assert!(!ladder(vec![level(99, 1)], vec![level(101, 1), level(101, 1)]).is_valid());
+    }
+
+    #[test]
+    fn test_tick_spacing_and_size_profiles() {
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+        let config = MarketMakerConfig::default();
+        let nickel = TickTable::Bands(vec![crate::ticks::TickBand {
+            below_cents: None,
+            tick_cents: 5,
+        }]);
+        let mut input = input(&exp, &config);
+        input.ticks = &nickel;
+
+        let mut flat = ladder(4, LadderSpacing::Ticks(2));
+        flat.size_profile = SizeProfile::Flat;
+        let quote = LayeredStrategy::new(Quoter::default(), flat)
+            .quote(&input)
+            .expect("finite theo");
+        assert!(quote.asks.windows(2).all(|w| w[1].price - w[0].price == 10));
+        assert!(quote.asks.iter().all(|l| l.size == 10));
+
+        let mut linear = ladder(4, LadderSpacing::Bps(0.01));
+        linear.size_profile = SizeProfile::Linear;
+        let quote = LayeredStrategy::new(Quoter::default(), linear)
+            .quote(&input)
+            .expect("finite theo");
+        assert_eq!(
+            quote.asks[1].price - quote.asks[0].price,
+            5,
+            "a sub-tick bps spacing rounds up to one tick"
+        );
+        let sizes: Vec<u64> = quote.asks.iter().map(|l| l.size).collect();
+        assert_eq!(sizes, vec![10, 8, 5, 3]);
+    }
+
+    #[test]
+    fn test_ladder_prices_stay_on_a_banded_grid() {
+        use crate::ticks::TickBand;
+
+        let exp = ExpirationDate::Days(Positive::THIRTY);
+        let config = MarketMakerConfig::default();
+        let theo = LayeredStrategy::default()
+            .quote(&input(&exp, &config))
+            .expect("finite theo")
+            .theo_price as u128;
+        // Nickels below theo, dimes from theo up: the ladder straddles the
+        // boundary.
+        let bands = TickTable::Bands(vec![
+            TickBand {
+                below_cents: Some(theo / 10 * 10),
+                tick_cents: 5,
+            },
+            TickBand {
+                below_cents: None,
+                tick_cents: 10,
+            },
+        ]);
+        let mut input = input(&exp, &config);
+        input.ticks = &bands;
+
+        let ladder = LayeredStrategy::new(Quoter::default(), ladder(5, LadderSpacing::Ticks(1)))
+            .quote(&input)
+            .expect("finite theo");
+        assert!(ladder.is_valid());
+        for level in ladder.bids.iter().chain(&ladder.asks) {
+            assert!(
+                bands.is_on_tick(level.price, None),
+                "{} off grid",
+                level.price
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_strategy_by_kind() {
+        let ladder = LadderConfig::default();
+        let single = build_strategy(StrategyKind::Single, &ladder);
+        assert_eq!((single.name(), single.levels()), ("single", 1));
+        let layered = build_strategy(StrategyKind::Layered, &ladder);
+        assert_eq!((layered.name(), layered.levels()), ("layered", 3));
+    }
+}
@@ -0,0 +1,323 @@
+//! Requote throttling.
+//!
+//! Every price tick recomputes the quote of every instrument in the chain, but
+//! most of those quotes are unchanged or differ by less than is worth a
+//! cancel/replace. The throttle remembers the last quote sent per instrument
+//! and only lets a new one through when a price moved by at least the
+//! configured number of ticks or a size changed materially, and when the
+//! instrument is still under its message-rate limit. Each decision is counted
+//! so skipped vs. sent requotes can be reported.
+
+use crate::config::RequoteConfig;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Length of the rate-limit window in milliseconds.
+const RATE_WINDOW_MS: u64 = 1_000;
+
+/// Runtime requote-throttling parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequoteParams {
+    /// Smallest bid or ask move, in price ticks, that replaces a quote.
+    pub min_price_change_ticks: u64,
+    /// Smallest bid or ask size change, in percent of the last sent size,
+    /// that replaces a quote.
+    pub min_size_change_pct: f64,
+    /// Maximum quote replacements per instrument per second (0 = unlimited).
+    pub max_updates_per_second: u32,
+}
+
+impl Default for RequoteParams {
+    fn default() -> Self {
+        Self::from(&RequoteConfig::default())
+    }
+}
+
+impl From<&RequoteConfig> for RequoteParams {
+    fn from(config: &RequoteConfig) -> Self {
+        Self {
+            min_price_change_ticks: config.min_price_change_ticks,
+            min_size_change_pct: config.min_size_change_pct,
+            max_updates_per_second: config.max_updates_per_second,
+        }
+    }
+}
+
+/// The prices and sizes of a two-sided quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteLevels {
+    /// Bid price in cents.
+    pub bid_price: u128,
+    /// Ask price in cents.
+    pub ask_price: u128,
+    /// Bid size.
+    pub bid_size: u64,
+    /// Ask size.
+    pub ask_size: u64,
+}
+
+/// Outcome of a throttle check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequoteDecision {
+    /// Replace the resting quote.
+    Send,
+    /// The new quote is identical to the resting one.
+    Unchanged,
+    /// The new quote differs by less than the thresholds.
+    BelowThreshold,
+    /// The instrument already used its message budget for this window.
+    RateLimited,
+}
+
+/// Counters of requote decisions since start-up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequoteStats {
+    /// Quotes replaced.
+    pub sent: u64,
+    /// Requotes skipped because the quote was unchanged.
+    pub skipped_unchanged: u64,
+    /// Requotes skipped because the change was below the thresholds.
+    pub skipped_below_threshold: u64,
+    /// Requotes skipped by the per-instrument rate limit.
+    pub skipped_rate_limited: u64,
+}
+
+impl RequoteStats {
+    /// Total requotes skipped for any reason.
+    #[must_use]
+    pub fn skipped(&self) -> u64 {
+        self.skipped_unchanged + self.skipped_below_threshold + self.skipped_rate_limited
+    }
+}
+
+/// Throttle state of one instrument.
+#[derive(Debug, Default)]
+struct InstrumentThrottle {
+    /// Last quote sent, if any.
+    last: Option<QuoteLevels>,
+    /// Send times within the current rate window, oldest first.
+    sends: VecDeque<u64>,
+}
+
+/// Whether `new` differs enough from `last` to be worth replacing.
+fn is_material(
+    last: &QuoteLevels,
+    new: &QuoteLevels,
+    tick_cents: u128,
+    params: &RequoteParams,
+) -> bool {
+    let min_move = tick_cents.saturating_mul(u128::from(params.min_price_change_ticks));
+    let price_moved = |a: u128, b: u128| a.abs_diff(b) >= min_move;
+    let size_moved = |a: u64, b: u64| {
+        a == 0 || (a.abs_diff(b) as f64 / a as f64) * 100.0 >= params.min_size_change_pct
+    };
+    price_moved(last.bid_price, new.bid_price)
+        || price_moved(last.ask_price, new.ask_price)
+        || (last.bid_size != new.bid_size && size_moved(last.bid_size, new.bid_size))
+        || (last.ask_size != new.ask_size && size_moved(last.ask_size, new.ask_size))
+}
+
+/// Requote throttle keyed by instrument.
+#[derive(Debug)]
+pub struct RequoteThrottle<K> {
+    /// Current parameters.
+    params: RequoteParams,
+    /// Per-instrument state.
+    instruments: HashMap<K, InstrumentThrottle>,
+    /// Decision counters.
+    stats: RequoteStats,
+}
+
+impl<K: Eq + Hash> Default for RequoteThrottle<K> {
+    fn default() -> Self {
+        Self::new(RequoteParams::default())
+    }
+}
+
+impl<K: Eq + Hash> RequoteThrottle<K> {
+    /// Creates a throttle with `params` and no history.
+    #[must_use]
+    pub fn new(params: RequoteParams) -> Self {
+        Self {
+            params,
+            instruments: HashMap::new(),
+            stats: RequoteStats::default(),
+        }
+    }
+
+    /// Current parameters.
+    #[must_use]
+    pub fn params(&self) -> RequoteParams {
+        self.params
+    }
+
+    /// Replaces the parameters; the history is kept.
+    pub fn set_params(&mut self, params: RequoteParams) {
+        self.params = params;
+    }
+
+    /// Decision counters since start-up.
+    #[must_use]
+    pub fn stats(&self) -> RequoteStats {
+        self.stats
+    }
+
+    /// Decides whether `quote` should replace the resting quote of `key` at
+    /// `now_ms`, counting skips. `resting` is whether both legs of the last
+    /// quote still rest; when one is gone the quote is replaced regardless of
+    /// the thresholds, but still within the rate limit. A [`RequoteDecision::Send`]
+    /// must be followed by [`Self::record_sent`] once the quote is placed.
+    pub fn check(
+        &mut self,
+        key: &K,
+        quote: &QuoteLevels,
+        resting: bool,
+        tick_cents: u128,
+        now_ms: u64,
+    ) -> RequoteDecision {
+        let params = self.params;
+        let decision = match self.instruments.get_mut(key) {
+            None => RequoteDecision::Send,
+            Some(state) => {
+                while state
+                    .sends
+                    .front()
+                    .is_some_and(|&t| now_ms.saturating_sub(t) >= RATE_WINDOW_MS)
+                {
+                    state.sends.pop_front();
+                }
+                match state.last {
+                    Some(last) if resting && last == *quote => RequoteDecision::Unchanged,
+                    Some(last) if resting && !is_material(&last, quote, tick_cents, &params) => {
+                        RequoteDecision::BelowThreshold
+                    }
+                    _ if params.max_updates_per_second > 0
+                        && state.sends.len() >= params.max_updates_per_second as usize =>
+                    {
+                        RequoteDecision::RateLimited
+                    }
+                    _ => RequoteDecision::Send,
+                }
+            }
+        };
+        match decision {
+            RequoteDecision::Send => {}
+            RequoteDecision::Unchanged => self.stats.skipped_unchanged += 1,
+            RequoteDecision::BelowThreshold => self.stats.skipped_below_threshold += 1,
+            RequoteDecision::RateLimited => self.stats.skipped_rate_limited += 1,
+        }
+        decision
+    }
+
+    /// Records that `quote` was sent for `key` at `now_ms`.
+    pub fn record_sent(&mut self, key: K, quote: QuoteLevels, now_ms: u64) {
+        let state = self.instruments.entry(key).or_default();
+        state.last = Some(quote);
+        state.sends.push_back(now_ms);
+        if self.params.max_updates_per_second > 0 {
+            while state.sends.len() > self.params.max_updates_per_second as usize {
+                state.sends.pop_front();
+            }
+        } else {
+            state.sends.clear();
+        }
+        self.stats.sent += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(bid: u128, ask: u128, size: u64) -> QuoteLevels {
+        QuoteLevels {
+            bid_price: bid,
+            ask_price: ask,
+            bid_size: size,
+            ask_size: size,
+        }
+    }
+
+    fn throttle(ticks: u64, size_pct: f64, rate: u32) -> RequoteThrottle<&'static str> {
+        RequoteThrottle::new(RequoteParams {
+            min_price_change_ticks: ticks,
+            min_size_change_pct: size_pct,
+            max_updates_per_second: rate,
+        })
+    }
+
+    #[test]
+    fn test_first_quote_is_sent_and_unchanged_is_skipped() {
+        let mut t = throttle(1, 10.0, 0);
+        let q = quote(100, 110, 10);
+        assert_eq!(t.check(&"A", &q, false, 1, 0), RequoteDecision::Send);
+        t.record_sent("A", q, 0);
+        assert_eq!(t.check(&"A", &q, true, 1, 10), RequoteDecision::Unchanged);
+        assert_eq!(
+            t.stats(),
+            RequoteStats {
+                sent: 1,
+                skipped_unchanged: 1,
+                ..RequoteStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_price_threshold_in_ticks() {
+        let mut t = throttle(3, 10.0, 0);
+        t.record_sent("A", quote(100, 110, 10), 0);
+        assert_eq!(
+            t.check(&"A", &quote(102, 112, 10), true, 1, 1),
+            RequoteDecision::BelowThreshold
+        );
+        assert_eq!(
+            t.check(&"A", &quote(100, 113, 10), true, 1, 2),
+            RequoteDecision::Send
+        );
+        // A 5-cent tick makes a 10-cent move less than 3 ticks.
+        assert_eq!(
+            t.check(&"A", &quote(110, 120, 10), true, 5, 3),
+            RequoteDecision::BelowThreshold
+        );
+    }
+
+    #[test]
+    fn test_size_threshold_in_percent() {
+        let mut t = throttle(1, 25.0, 0);
+        t.record_sent("A", quote(100, 110, 10), 0);
+        let mut smaller = quote(100, 110, 10);
+        smaller.ask_size = 8;
+        assert_eq!(
+            t.check(&"A", &smaller, true, 1, 1),
+            RequoteDecision::BelowThreshold
+        );
+        smaller.ask_size = 7;
+        assert_eq!(t.check(&"A", &smaller, true, 1, 2), RequoteDecision::Send);
+    }
+
+    #[test]
+    fn test_missing_leg_forces_a_requote() {
+        let mut t = throttle(5, 50.0, 0);
+        let q = quote(100, 110, 10);
+        t.record_sent("A", q, 0);
+        assert_eq!(t.check(&"A", &q, false, 1, 1), RequoteDecision::Send);
+    }
+
+    #[test]
+    fn test_rate_limit_per_instrument_window() {
+        let mut t = throttle(1, 10.0, 2);
+        t.record_sent("A", quote(100, 110, 10), 0);
+        t.record_sent("A", quote(101, 111, 10), 400);
+        let next = quote(105, 115, 10);
+        assert_eq!(
+            t.check(&"A", &next, true, 1, 900),
+            RequoteDecision::RateLimited
+        );
+        assert_eq!(t.check(&"B", &next, true, 1, 900), RequoteDecision::Send);
+        // The first send leaves the window after one second.
+        assert_eq!(t.check(&"A", &next, true, 1, 1_000), RequoteDecision::Send);
+        assert_eq!(t.stats().skipped_rate_limited, 1);
+        assert_eq!(t.stats().skipped(), 1);
+    }
+}
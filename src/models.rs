@@ -455,7 +455,7 @@ pub struct LastTradeResponse {
 }
 
 /// Internal storage for last trade information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LastTradeInfo {
     /// The option symbol.
     pub symbol: String,
@@ -569,10 +569,12 @@ pub struct OrderFillInfo {
 }
 
 /// Internal storage for order information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderInfo {
     /// Unique order identifier.
     pub order_id: String,
+    /// Trading account that placed the order (the caller's token subject).
+    pub account: String,
     /// Option symbol (e.g., "AAPL-20240329-150-C").
     pub symbol: String,
     /// Underlying symbol.
@@ -704,7 +706,7 @@ fn default_limit() -> usize {
 // ============================================================================
 
 /// Internal storage for position information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PositionInfo {
     /// Option symbol (e.g., "AAPL-20240329-150-C").
     pub symbol: String,
@@ -716,6 +718,8 @@ pub struct PositionInfo {
     pub average_price: u128,
     /// Realized P&L in smallest units.
     pub realized_pnl: i64,
+    /// Units of the underlying per contract; money amounts scale by it.
+    pub multiplier: u64,
     /// Creation timestamp in milliseconds.
     pub created_at_ms: u64,
     /// Last update timestamp in milliseconds.
@@ -738,11 +742,19 @@ impl PositionInfo {
             quantity,
             average_price: price,
             realized_pnl: 0,
+            multiplier: 1,
             created_at_ms: timestamp_ms,
             updated_at_ms: timestamp_ms,
         }
     }
 
+    /// Sets the contract multiplier of the position.
+    #[must_use]
+    pub fn with_multiplier(mut self, multiplier: u64) -> Self {
+        self.multiplier = multiplier.max(1);
+        self
+    }
+
     /// Updates the position with a new fill.
     ///
     /// Returns the realized P&L (cents) from this fill (zero when the fill only
@@ -801,8 +813,8 @@ impl PositionInfo {
                     .min(self.quantity.unsigned_abs()),
             );
 
-            // Realized P&L in i128: (fill_price - average_price) * close_quantity,
-            // negated for a short. The sign is captured up front so a full i128
+            // Realized P&L in i128: (fill_price - average_price) * close_quantity
+            // * multiplier, negated for a short. The sign is captured up front so a full i128
             // overflow still saturates in the correct direction.
             let diff = i128::try_from(fill_price)
                 .ok()
@@ -813,7 +825,9 @@ impl PositionInfo {
                 Some(d) => d.checked_neg(),
                 None => None,
             };
-            let realized_i128 = signed_diff.and_then(|d| d.checked_mul(close_quantity));
+            let realized_i128 = signed_diff
+                .and_then(|d| d.checked_mul(close_quantity))
+                .and_then(|v| v.checked_mul(i128::from(self.multiplier)));
 
             realized = match realized_i128.and_then(|v| i64::try_from(v).ok()) {
                 Some(v) => v,
@@ -855,8 +869,8 @@ impl PositionInfo {
 
     /// Calculates unrealized P&L (cents) given the current market price (cents).
     ///
-    /// Computes `(current_price - average_price) * quantity` in `i128` with
-    /// checked arithmetic, then narrows to `i64`.
+    /// Computes `(current_price - average_price) * quantity * multiplier` in
+    /// `i128` with checked arithmetic, then narrows to `i64`.
     ///
     /// Returns `None` on arithmetic overflow — i.e. when the difference, the
     /// product, or the final narrowing to `i64` cannot be represented. `None`
@@ -868,20 +882,24 @@ impl PositionInfo {
         let current = i128::try_from(current_price).ok()?;
         let average = i128::try_from(self.average_price).ok()?;
         let price_diff = current.checked_sub(average)?;
-        let pnl = price_diff.checked_mul(i128::from(self.quantity))?;
+        let pnl = price_diff
+            .checked_mul(i128::from(self.quantity))?
+            .checked_mul(i128::from(self.multiplier))?;
         i64::try_from(pnl).ok()
     }
 
     /// Calculates notional value (cents) given the current market price (cents).
     ///
-    /// Computes `current_price * abs(quantity)` in `u128` with checked
-    /// multiplication.
+    /// Computes `current_price * abs(quantity) * multiplier` in `u128` with
+    /// checked multiplication.
     ///
     /// Returns `None` on arithmetic overflow (the product exceeds `u128`). `None`
     /// here means OVERFLOW, NOT an unpriced position (see [`Self::unrealized_pnl`]).
     #[must_use]
     pub fn notional_value(&self, current_price: u128) -> Option<u128> {
-        current_price.checked_mul(u128::from(self.quantity.unsigned_abs()))
+        current_price
+            .checked_mul(u128::from(self.quantity.unsigned_abs()))?
+            .checked_mul(u128::from(self.multiplier))
     }
 }
 
@@ -1130,6 +1148,61 @@ pub struct OhlcResponse {
     pub bars: Vec<OhlcBar>,
 }
 
+/// Request to rebuild stored OHLC bars from persisted executions.
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct OhlcBackfillRequest {
+    /// Start timestamp in seconds, widened to the start of its day
+    /// (default: the first execution).
+    #[serde(default)]
+    pub from: Option<u64>,
+    /// End timestamp in seconds, widened to the end of its day (default:
+    /// the last execution).
+    #[serde(default)]
+    pub to: Option<u64>,
+    /// Option symbol (`UNDERLYING-EXPIRATION-STRIKE-STYLE`) to rebuild;
+    /// every symbol when omitted.
+    #[serde(default)]
+    pub symbol: Option<String>,
+}
+
+/// Response for an OHLC backfill.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OhlcBackfillResponse {
+    /// Executions read.
+    pub executions: u64,
+    /// Bars written or updated, across every interval.
+    pub bars: u64,
+    /// Start of the rebuilt range in seconds.
+    pub from: u64,
+    /// End of the rebuilt range in seconds (exclusive); absent when
+    /// open-ended.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+}
+
+/// Query parameters for the quote history endpoint.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct QuoteHistoryQuery {
+    /// Start timestamp in milliseconds (optional).
+    #[serde(default)]
+    pub from: Option<u64>,
+    /// End timestamp in milliseconds (optional).
+    #[serde(default)]
+    pub to: Option<u64>,
+    /// Maximum number of quotes to return (default 1000).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Response for the quote history endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteHistoryResponse {
+    /// Symbol identifier.
+    pub symbol: String,
+    /// Recorded top-of-book changes, oldest first.
+    pub quotes: Vec<QuoteResponse>,
+}
+
 // ============================================================================
 // Order Modification Types
 // ============================================================================
@@ -1397,12 +1470,84 @@ pub struct OptionChainResponse {
     pub expiration: String,
     /// Current spot price (if available).
     pub spot_price: Option<u128>,
-    /// At-the-money strike (closest to spot price).
+    /// Implied forward price in cents from put-call parity across the
+    /// expiration's two-sided books, when derivable.
+    pub forward_price: Option<u64>,
+    /// At-the-money strike (closest to the implied forward, falling back to
+    /// the spot price when no forward is derivable).
     pub atm_strike: Option<u64>,
     /// Chain data: list of strikes with call and put quotes.
     pub chain: Vec<ChainStrikeRow>,
 }
 
+// ============================================================================
+// Implied Forward / Put-Call Parity Types
+// ============================================================================
+
+/// Query parameters for the implied forward endpoint.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForwardQuery {
+    /// Parity deviation threshold, in basis points of the implied forward,
+    /// above which a strike is flagged as a violation. Defaults to 50 bps.
+    #[serde(default)]
+    pub threshold_bps: Option<f64>,
+}
+
+/// Put-call parity diagnostics for a single strike.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ParityStrikeDiagnostic {
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Two-sided call mid in cents, when quoted.
+    pub call_mid: Option<u128>,
+    /// Two-sided put mid in cents, when quoted.
+    pub put_mid: Option<u128>,
+    /// Synthetic forward `K + (C - P) / D` in cents, when both legs are quoted
+    /// and a fit exists.
+    pub synthetic_forward: Option<u64>,
+    /// Parity residual `(C - P) - D · (F - K)` in cents.
+    pub residual_cents: Option<i64>,
+    /// Absolute residual in basis points of the implied forward.
+    pub deviation_bps: Option<f64>,
+    /// Whether the deviation exceeds the requested threshold.
+    pub violation: bool,
+}
+
+/// Response for the implied forward endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImpliedForwardResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration date string.
+    pub expiration: String,
+    /// Current spot price in cents (if available).
+    pub spot_price: Option<u64>,
+    /// Days to expiration (minimum 1).
+    pub days_to_expiry: u64,
+    /// Implied forward price in cents, when the parity fit is well-posed.
+    pub forward_price: Option<u64>,
+    /// Implied discount factor to expiry.
+    pub discount_factor: Option<f64>,
+    /// Continuously-compounded implied rate, `-ln(D) / T`.
+    pub implied_rate: Option<f64>,
+    /// Continuously-compounded implied carry against spot, `ln(F / S) / T`.
+    pub implied_carry: Option<f64>,
+    /// Forward minus spot in cents.
+    pub basis: Option<i64>,
+    /// Root-mean-square parity residual in cents.
+    pub rmse_cents: Option<f64>,
+    /// Number of strikes with both legs two-sided (the regression inputs).
+    pub strikes_used: usize,
+    /// Deviation threshold applied, in basis points of the forward.
+    pub threshold_bps: f64,
+    /// Number of strikes flagged as parity violations.
+    pub violations: usize,
+    /// Per-strike diagnostics, ordered by strike.
+    pub strikes: Vec<ParityStrikeDiagnostic>,
+    /// Timestamp of calculation in milliseconds.
+    pub timestamp_ms: u64,
+}
+
 // ============================================================================
 // Greeks Types
 // ============================================================================
@@ -1465,6 +1610,45 @@ pub struct GreeksResponse {
     pub timestamp_ms: u64,
 }
 
+// ============================================================================
+// Instrument Reference Data Types
+// ============================================================================
+
+/// One price band of an instrument's tick-size table.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TickBandInfo {
+    /// Exclusive upper bound of the band in cents; absent for the last band.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub below: Option<u128>,
+    /// Tick of the band in cents.
+    pub tick: u128,
+}
+
+/// Reference data of one option instrument.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InstrumentSpecResponse {
+    /// Option symbol (e.g., "AAPL-20240329-150-C").
+    pub symbol: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration date (YYYYMMDD).
+    pub expiration: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Option style.
+    pub style: OptionStyle,
+    /// Units of the underlying per contract; prices are per unit.
+    pub contract_multiplier: u64,
+    /// Contracts per lot; order quantities are whole lots.
+    pub min_lot_size: u64,
+    /// Largest quantity of a single order, if limited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_order_size: Option<u64>,
+    /// Tick-size bands in ascending price order. A tick set as a fraction of
+    /// the underlying is reported as a single band at the current spot.
+    pub tick_bands: Vec<TickBandInfo>,
+}
+
 // ============================================================================
 // Implied Volatility Surface Types
 // ============================================================================
@@ -1492,7 +1676,7 @@ pub struct ATMTermStructurePoint {
 }
 
 /// Response for volatility surface endpoint.
-#[derive(Debug, Clone, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct VolatilitySurfaceResponse {
     /// Underlying symbol.
     pub underlying: String,
@@ -1504,12 +1688,75 @@ pub struct VolatilitySurfaceResponse {
     pub expirations: Vec<String>,
     /// List of strikes.
     pub strikes: Vec<u64>,
+    /// Implied forward per expiration in cents (put-call parity), for the
+    /// expirations where one is derivable. IVs for those expirations are
+    /// computed off the forward instead of spot.
+    pub forwards: std::collections::HashMap<String, u64>,
     /// IV surface: expiration -> strike -> StrikeIV.
     pub surface: std::collections::HashMap<String, std::collections::HashMap<u64, StrikeIV>>,
     /// ATM term structure.
     pub atm_term_structure: Vec<ATMTermStructurePoint>,
 }
 
+/// Skew metrics of one expiration of a volatility surface.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExpirationSkew {
+    /// Expiration date string.
+    pub expiration: String,
+    /// Days to expiration when the surface was computed.
+    pub days: u64,
+    /// ATM implied volatility.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atm_iv: Option<f64>,
+    /// 25-delta risk reversal: 25-delta call IV minus 25-delta put IV.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_reversal_25d: Option<f64>,
+    /// 25-delta butterfly: mean of the 25-delta call and put IVs minus the
+    /// ATM IV.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub butterfly_25d: Option<f64>,
+}
+
+/// Skew metrics of one stored volatility surface.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SurfaceSkewPoint {
+    /// Surface computation time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Spot price the surface was computed against (if available).
+    pub spot_price: Option<u64>,
+    /// Metrics per expiration, nearest first.
+    pub expirations: Vec<ExpirationSkew>,
+}
+
+/// Query parameters for the volatility surface history endpoint.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct VolatilitySurfaceHistoryQuery {
+    /// Start of the metrics series in milliseconds (optional).
+    #[serde(default)]
+    pub from: Option<u64>,
+    /// End of the metrics series in milliseconds (optional).
+    #[serde(default)]
+    pub to: Option<u64>,
+    /// Comma-separated times in milliseconds; the latest surface stored at or
+    /// before each is returned in full (optional).
+    #[serde(default)]
+    pub at: Option<String>,
+    /// Maximum number of series points (default 500).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Response for the volatility surface history endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VolatilitySurfaceHistoryResponse {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Stored surfaces at the requested `at` times, in request order.
+    pub surfaces: Vec<VolatilitySurfaceResponse>,
+    /// ATM IV, risk reversal and butterfly per stored surface, oldest first.
+    pub series: Vec<SurfaceSkewPoint>,
+}
+
 // ============================================================================
 // Authentication Types (JWT + x509)
 // ============================================================================
@@ -1541,6 +1788,11 @@ pub struct TokenRequest {
     /// Optional token lifetime in seconds (defaults to the server's TTL).
     #[serde(default)]
     pub ttl_secs: Option<u64>,
+    /// Optional trading account to embed as the token subject. Tokens minted
+    /// for the same account share its positions; when omitted a fresh random
+    /// account is assigned.
+    #[serde(default)]
+    pub account: Option<String>,
 }
 
 /// Response for `POST /api/v1/auth/token`.
@@ -1829,6 +2081,9 @@ pub struct CreateSnapshotResponse {
     pub orders_saved: u64,
     /// Number of orderbooks skipped because their state failed to serialize.
     pub orderbooks_failed: u64,
+    /// Whether the snapshot was written to durable storage (always `false`
+    /// when no snapshot store is configured).
+    pub persisted: bool,
     /// Timestamp of the snapshot.
     pub timestamp_ms: u64,
 }
@@ -1842,6 +2097,41 @@ pub struct SnapshotsListResponse {
     pub total: u64,
 }
 
+/// What took a snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotTrigger {
+    /// `POST /api/v1/admin/snapshot`.
+    #[default]
+    Manual,
+    /// The `[snapshots] interval_seconds` schedule.
+    Scheduled,
+    /// Graceful shutdown with `[snapshots] on_shutdown` set.
+    Shutdown,
+}
+
+impl SnapshotTrigger {
+    /// The lowercase wire name.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Scheduled => "scheduled",
+            Self::Shutdown => "shutdown",
+        }
+    }
+
+    /// Parses a wire name; unknown names read as [`Self::Manual`].
+    #[must_use]
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "scheduled" => Self::Scheduled,
+            "shutdown" => Self::Shutdown,
+            _ => Self::Manual,
+        }
+    }
+}
+
 /// Summary of a snapshot for listing.
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct SnapshotSummary {
@@ -1851,6 +2141,11 @@ pub struct SnapshotSummary {
     pub orderbook_count: u64,
     /// Total number of orders.
     pub total_orders: u64,
+    /// `false` when at least one orderbook failed to serialize and the
+    /// snapshot is partial.
+    pub success: bool,
+    /// What took the snapshot.
+    pub trigger: SnapshotTrigger,
     /// Creation timestamp in milliseconds.
     pub created_at: u64,
 }
@@ -1871,6 +2166,12 @@ pub struct RestoreSnapshotResponse {
     /// Number of orderbooks that could not be restored (unparseable data,
     /// invalid expiration/style, or an upstream restore failure).
     pub orderbooks_failed: u64,
+    /// Whether the orders, positions, executions, trades, OHLC bars and
+    /// maker order tracking were restored too (whole-state snapshots only).
+    pub state_restored: bool,
+    /// Sequence of the last journal entry the snapshot includes (0 when it
+    /// was taken without a journal); replay resumes after it.
+    pub journal_seq: u64,
     /// Timestamp of the restore operation.
     pub timestamp_ms: u64,
 }
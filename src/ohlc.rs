@@ -5,6 +5,7 @@
 
 use crate::models::{OhlcBar, OhlcInterval};
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 /// Key for storing OHLC bars: (symbol, interval).
@@ -21,14 +22,30 @@ type BarKey = (String, OhlcInterval);
 /// `get_bars` `limit` is applied on top of the retained set).
 const MAX_BARS_PER_SERIES: usize = 2_048;
 
+/// Every bar of one `(symbol, interval)` series, oldest first, as captured
+/// in a whole-state snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhlcSeries {
+    /// The symbol identifier.
+    pub symbol: String,
+    /// The bar interval.
+    pub interval: OhlcInterval,
+    /// The bars, oldest first.
+    pub bars: Vec<OhlcBar>,
+}
+
 /// OHLC aggregator that collects trades and produces candlestick bars.
 ///
 /// Bars are stored in memory, keyed by symbol and interval.
 /// Each symbol/interval combination has a sorted map of bars by timestamp.
+/// Series changed since the last [`OhlcAggregator::take_dirty`] are tracked
+/// so the history store only writes bars that moved.
 #[derive(Debug, Default)]
 pub struct OhlcAggregator {
     /// Storage for OHLC bars: (symbol, interval) -> (timestamp -> bar).
     bars: DashMap<BarKey, BTreeMap<u64, OhlcBar>>,
+    /// Oldest bar timestamp changed per series since the last drain.
+    dirty: DashMap<BarKey, u64>,
 }
 
 impl OhlcAggregator {
@@ -37,6 +54,7 @@ impl OhlcAggregator {
     pub fn new() -> Self {
         Self {
             bars: DashMap::new(),
+            dirty: DashMap::new(),
         }
     }
 
@@ -78,7 +96,7 @@ impl OhlcAggregator {
         let bar_timestamp = interval.floor_timestamp(timestamp_secs);
         let key = (symbol.to_string(), interval);
 
-        let mut series = self.bars.entry(key).or_default();
+        let mut series = self.bars.entry(key.clone()).or_default();
         series
             .entry(bar_timestamp)
             .and_modify(|bar| bar.update(price, quantity))
@@ -94,6 +112,16 @@ impl OhlcAggregator {
                 break;
             }
         }
+        drop(series);
+        self.mark_dirty(key, bar_timestamp);
+    }
+
+    /// Records that the bars of `key` from `timestamp` on have changed.
+    fn mark_dirty(&self, key: BarKey, timestamp: u64) {
+        self.dirty
+            .entry(key)
+            .and_modify(|since| *since = (*since).min(timestamp))
+            .or_insert(timestamp);
     }
 
     /// Gets OHLC bars for a symbol and interval within a time range.
@@ -189,12 +217,142 @@ impl OhlcAggregator {
         ] {
             let key = (symbol.to_string(), interval);
             self.bars.remove(&key);
+            self.dirty.remove(&key);
         }
     }
 
     /// Clears all stored bars.
     pub fn clear_all(&self) {
         self.bars.clear();
+        self.dirty.clear();
+    }
+
+    /// Returns every stored series.
+    #[must_use]
+    pub fn series(&self) -> Vec<OhlcSeries> {
+        self.bars
+            .iter()
+            .map(|entry| OhlcSeries {
+                symbol: entry.key().0.clone(),
+                interval: entry.key().1,
+                bars: entry.value().values().copied().collect(),
+            })
+            .collect()
+    }
+
+    /// Replaces every stored bar with `series`, keeping the newest
+    /// `MAX_BARS_PER_SERIES` bars of each.
+    ///
+    /// Every restored series is marked changed, so the history store writes
+    /// it on its next flush.
+    pub fn restore_series(&self, series: Vec<OhlcSeries>) {
+        self.bars.clear();
+        self.dirty.clear();
+        for OhlcSeries {
+            symbol,
+            interval,
+            bars,
+        } in series
+        {
+            let skip = bars.len().saturating_sub(MAX_BARS_PER_SERIES);
+            let bars: BTreeMap<u64, OhlcBar> = bars
+                .into_iter()
+                .skip(skip)
+                .map(|bar| (bar.timestamp, bar))
+                .collect();
+            if let Some(&oldest) = bars.keys().next() {
+                self.mark_dirty((symbol.clone(), interval), oldest);
+            }
+            self.bars.insert((symbol, interval), bars);
+        }
+    }
+
+    /// Drains the change tracking: returns, for every series changed since
+    /// the last call, its bars from the oldest changed one on.
+    ///
+    /// A trade recorded while the drain runs marks its series again, so no
+    /// change is ever lost between two drains.
+    #[must_use]
+    pub fn take_dirty(&self) -> Vec<OhlcSeries> {
+        let keys: Vec<BarKey> = self.dirty.iter().map(|entry| entry.key().clone()).collect();
+        let mut changed = Vec::with_capacity(keys.len());
+        for key in keys {
+            let Some((key, since)) = self.dirty.remove(&key) else {
+                continue;
+            };
+            let Some(series) = self.bars.get(&key) else {
+                continue;
+            };
+            let bars: Vec<OhlcBar> = series.range(since..).map(|(_, bar)| *bar).collect();
+            drop(series);
+            if !bars.is_empty() {
+                changed.push(OhlcSeries {
+                    symbol: key.0,
+                    interval: key.1,
+                    bars,
+                });
+            }
+        }
+        changed
+    }
+
+    /// Marks `series` changed again, for a drain whose bars could not be
+    /// written.
+    pub fn requeue(&self, series: &[OhlcSeries]) {
+        for entry in series {
+            if let Some(oldest) = entry.bars.first() {
+                self.mark_dirty((entry.symbol.clone(), entry.interval), oldest.timestamp);
+            }
+        }
+    }
+
+    /// Adds the bars of `series` this aggregator does not hold yet, without
+    /// marking them changed. Used to resume the bars still open when the
+    /// previous run stopped, so trades after a restart extend them instead
+    /// of starting over.
+    pub fn seed_series(&self, series: Vec<OhlcSeries>) {
+        for OhlcSeries {
+            symbol,
+            interval,
+            bars,
+        } in series
+        {
+            let mut stored = self.bars.entry((symbol, interval)).or_default();
+            for bar in bars {
+                stored.entry(bar.timestamp).or_insert(bar);
+            }
+            while stored.len() > MAX_BARS_PER_SERIES {
+                if stored.pop_first().is_none() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Merges bars read from the history store with the in-memory bars of the
+/// same series and applies the [`OhlcAggregator::get_bars`] limit semantics:
+/// the oldest `limit` bars when `anchor_at_from`, otherwise the newest.
+///
+/// Where both hold a bar for the same timestamp the in-memory one wins, as it
+/// has seen every trade since it was last written.
+#[must_use]
+pub fn merge_bars(
+    persisted: Vec<OhlcBar>,
+    live: Vec<OhlcBar>,
+    anchor_at_from: bool,
+    limit: usize,
+) -> Vec<OhlcBar> {
+    let mut merged: BTreeMap<u64, OhlcBar> = persisted
+        .into_iter()
+        .map(|bar| (bar.timestamp, bar))
+        .collect();
+    merged.extend(live.into_iter().map(|bar| (bar.timestamp, bar)));
+    if anchor_at_from {
+        merged.into_values().take(limit).collect()
+    } else {
+        let skip = merged.len().saturating_sub(limit);
+        merged.into_values().skip(skip).collect()
     }
 }
 
@@ -551,6 +709,100 @@ mod tests {
         assert_eq!(aggregator.bar_count("SYM2", OhlcInterval::OneMinute), 0);
     }
 
+    #[test]
+    fn test_series_round_trip() {
+        let aggregator = OhlcAggregator::new();
+        aggregator.record_trade("SYM1", 1704067200000, 500, 100);
+        aggregator.record_trade("SYM1", 1704067260000, 510, 50);
+
+        let restored = OhlcAggregator::new();
+        restored.record_trade("STALE", 1704067200000, 1, 1);
+        restored.restore_series(aggregator.series());
+
+        assert_eq!(restored.bar_count("STALE", OhlcInterval::OneMinute), 0);
+        assert_eq!(restored.bar_count("SYM1", OhlcInterval::OneMinute), 2);
+        let latest = restored
+            .get_latest_bar("SYM1", OhlcInterval::OneDay)
+            .expect("daily bar restored");
+        assert_eq!(latest.volume, 150);
+        assert_eq!(latest.close, 510);
+    }
+
+    #[test]
+    fn test_take_dirty_returns_changed_bars_once() {
+        let aggregator = OhlcAggregator::new();
+        aggregator.record_trade("SYM1", 1704067200000, 500, 100);
+        aggregator.record_trade("SYM1", 1704067260000, 510, 50);
+
+        let changed = aggregator.take_dirty();
+        assert_eq!(changed.len(), 6, "one series per interval");
+        let minute = changed
+            .iter()
+            .find(|s| s.interval == OhlcInterval::OneMinute)
+            .expect("1m series");
+        assert_eq!(minute.bars.len(), 2);
+        assert!(aggregator.take_dirty().is_empty());
+
+        // Only the bars from the oldest changed one on are returned.
+        aggregator.record_trade("SYM1", 1704067270000, 520, 10);
+        let changed = aggregator.take_dirty();
+        let minute = changed
+            .iter()
+            .find(|s| s.interval == OhlcInterval::OneMinute)
+            .expect("1m series");
+        assert_eq!(minute.bars.len(), 1);
+        assert_eq!(minute.bars[0].close, 520);
+
+        // A failed write is retried on the next drain.
+        aggregator.requeue(&changed);
+        assert_eq!(aggregator.take_dirty().len(), 6);
+    }
+
+    #[test]
+    fn test_seed_series_keeps_existing_bars_and_stays_clean() {
+        let aggregator = OhlcAggregator::new();
+        aggregator.record_trade("SYM1", 1704067260000, 510, 50);
+        let _ = aggregator.take_dirty();
+
+        aggregator.seed_series(vec![OhlcSeries {
+            symbol: "SYM1".to_string(),
+            interval: OhlcInterval::OneMinute,
+            bars: vec![
+                OhlcBar::new(1704067200, 400, 7),
+                OhlcBar::new(1704067260, 1, 1),
+            ],
+        }]);
+        let bars = aggregator.get_bars("SYM1", OhlcInterval::OneMinute, None, None, 10);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, 400);
+        assert_eq!(bars[1].open, 510, "held bar is not replaced");
+        assert!(aggregator.take_dirty().is_empty());
+
+        // Trades after the seed extend the seeded bar.
+        aggregator.record_trade("SYM1", 1704067210000, 420, 3);
+        let bar = aggregator.get_bars("SYM1", OhlcInterval::OneMinute, None, None, 10)[0];
+        assert_eq!((bar.open, bar.close, bar.volume), (400, 420, 10));
+    }
+
+    #[test]
+    fn test_merge_bars_prefers_live_and_applies_limit() {
+        let persisted = vec![
+            OhlcBar::new(60, 1, 1),
+            OhlcBar::new(120, 2, 1),
+            OhlcBar::new(180, 3, 1),
+        ];
+        let live = vec![OhlcBar::new(180, 30, 5), OhlcBar::new(240, 4, 1)];
+
+        let newest = merge_bars(persisted.clone(), live.clone(), false, 3);
+        let timestamps: Vec<u64> = newest.iter().map(|bar| bar.timestamp).collect();
+        assert_eq!(timestamps, vec![120, 180, 240]);
+        assert_eq!(newest[1].open, 30, "in-memory bar wins");
+
+        let oldest = merge_bars(persisted, live, true, 2);
+        let timestamps: Vec<u64> = oldest.iter().map(|bar| bar.timestamp).collect();
+        assert_eq!(timestamps, vec![60, 120]);
+    }
+
     #[test]
     fn test_interval_floor_timestamp() {
         // 1m interval
@@ -0,0 +1,591 @@
+//! Durable OHLC history.
+//!
+//! The [`OhlcAggregator`] keeps a bounded window of bars in memory and loses
+//! it on restart. With a database connected, an [`OhlcStore`] periodically
+//! writes every bar changed since its last flush to the `ohlc_bars` table,
+//! and `get_ohlc` merges the stored history with the in-memory bars. The bars
+//! still open when the server stopped are resumed on startup, and
+//! [`OhlcStore::backfill`] rebuilds bars from the `executions` table, which
+//! the [`Repository`](crate::db::Repository) fills with every execution.
+
+use crate::db::DatabasePool;
+use crate::models::{OhlcBar, OhlcInterval};
+use crate::ohlc::{OhlcAggregator, OhlcSeries};
+use sqlx::{PgConnection, SqliteConnection};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Every bar interval, shortest first.
+const INTERVALS: [OhlcInterval; 6] = [
+    OhlcInterval::OneMinute,
+    OhlcInterval::FiveMinutes,
+    OhlcInterval::FifteenMinutes,
+    OhlcInterval::OneHour,
+    OhlcInterval::FourHours,
+    OhlcInterval::OneDay,
+];
+
+/// Seconds in the longest interval; backfill ranges are widened to whole
+/// days so no rebuilt bar covers only part of its trades.
+const DAY_SECS: u64 = 86_400;
+
+// The `ohlc_bars` queries are the same on both backends.
+
+/// The oldest bars of a series within a range.
+const LOAD_OLDEST_BARS: &str = r#"
+    SELECT bar_start, open_cents, high_cents, low_cents, close_cents, volume, trade_count
+    FROM ohlc_bars
+    WHERE symbol = $1 AND bar_interval = $2 AND bar_start >= $3 AND bar_start <= $4
+    ORDER BY bar_start ASC
+    LIMIT $5
+"#;
+
+/// The newest bars of a series within a range.
+const LOAD_NEWEST_BARS: &str = r#"
+    SELECT bar_start, open_cents, high_cents, low_cents, close_cents, volume, trade_count
+    FROM ohlc_bars
+    WHERE symbol = $1 AND bar_interval = $2 AND bar_start >= $3 AND bar_start <= $4
+    ORDER BY bar_start DESC
+    LIMIT $5
+"#;
+
+/// Every bar of an interval starting at or after `$2`.
+const LOAD_OPEN_BARS: &str = r#"
+    SELECT symbol, bar_start, open_cents, high_cents, low_cents, close_cents,
+           volume, trade_count
+    FROM ohlc_bars
+    WHERE bar_interval = $1 AND bar_start >= $2
+"#;
+
+const UPSERT_BAR: &str = r#"
+    INSERT INTO ohlc_bars
+        (symbol, bar_interval, bar_start, open_cents, high_cents, low_cents,
+         close_cents, volume, trade_count)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    ON CONFLICT (symbol, bar_interval, bar_start) DO UPDATE SET
+        open_cents = EXCLUDED.open_cents,
+        high_cents = EXCLUDED.high_cents,
+        low_cents = EXCLUDED.low_cents,
+        close_cents = EXCLUDED.close_cents,
+        volume = EXCLUDED.volume,
+        trade_count = EXCLUDED.trade_count
+"#;
+
+/// The executions of `$1` (or of every instrument) from `$2` until `$3`
+/// seconds, oldest first.
+const LOAD_EXECUTIONS: &str = r#"
+    SELECT instrument, (EXTRACT(EPOCH FROM executed_at) * 1000)::BIGINT,
+           price_cents, quantity
+    FROM executions
+    WHERE ($1::TEXT IS NULL OR instrument = $1)
+      AND executed_at >= to_timestamp($2)
+      AND ($3::BIGINT IS NULL OR executed_at < to_timestamp($3))
+    ORDER BY executed_at
+"#;
+
+/// [`LOAD_EXECUTIONS`] for SQLite, which keeps times in milliseconds.
+const SQLITE_LOAD_EXECUTIONS: &str = r#"
+    SELECT instrument, executed_at_ms, price_cents, quantity
+    FROM executions
+    WHERE ($1 IS NULL OR instrument = $1)
+      AND executed_at_ms >= $2 * 1000
+      AND ($3 IS NULL OR executed_at_ms < $3 * 1000)
+    ORDER BY executed_at_ms, id
+"#;
+
+/// Errors from the OHLC store.
+#[derive(Debug, Error)]
+pub enum OhlcStoreError {
+    /// A database query failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What a flush wrote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushSummary {
+    /// Bars written or updated.
+    pub bars: u64,
+}
+
+/// What a backfill rebuilt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackfillSummary {
+    /// Executions read.
+    pub executions: u64,
+    /// Bars written or updated.
+    pub bars: u64,
+    /// Start of the rebuilt range in seconds, widened to a day boundary.
+    pub from: u64,
+    /// End of the rebuilt range in seconds (exclusive), widened to a day
+    /// boundary; `None` when open-ended.
+    pub to: Option<u64>,
+}
+
+/// Database-backed OHLC history, on PostgreSQL or SQLite.
+pub struct OhlcStore {
+    db: DatabasePool,
+}
+
+impl OhlcStore {
+    /// Creates a store writing to `db`.
+    #[must_use]
+    pub fn new(db: DatabasePool) -> Self {
+        Self { db }
+    }
+
+    /// Writes the bars of `aggregator` changed since the last flush, in one
+    /// transaction. On failure they are kept for the next flush.
+    ///
+    /// # Errors
+    /// Returns [`OhlcStoreError`] when the write fails.
+    pub async fn flush(&self, aggregator: &OhlcAggregator) -> Result<FlushSummary, OhlcStoreError> {
+        let series = aggregator.take_dirty();
+        if series.is_empty() {
+            return Ok(FlushSummary::default());
+        }
+        match self.write(&series).await {
+            Ok(bars) => Ok(FlushSummary { bars }),
+            Err(e) => {
+                aggregator.requeue(&series);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Writes every bar of `series` in one transaction.
+    async fn write(&self, series: &[OhlcSeries]) -> Result<u64, sqlx::Error> {
+        match &self.db {
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                let bars = upsert_bars(&mut tx, series).await?;
+                tx.commit().await?;
+                Ok(bars)
+            }
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                let bars = sqlite_upsert_bars(&mut tx, series).await?;
+                tx.commit().await?;
+                Ok(bars)
+            }
+        }
+    }
+
+    /// Loads the stored bars of `symbol` at `interval` within `[from, to]`,
+    /// oldest first: the oldest `limit` when `anchor_at_from`, otherwise the
+    /// newest.
+    ///
+    /// # Errors
+    /// Returns [`OhlcStoreError`] when the query fails.
+    pub async fn load_bars(
+        &self,
+        symbol: &str,
+        interval: OhlcInterval,
+        from: Option<u64>,
+        to: Option<u64>,
+        anchor_at_from: bool,
+        limit: usize,
+    ) -> Result<Vec<OhlcBar>, OhlcStoreError> {
+        let query = if anchor_at_from {
+            LOAD_OLDEST_BARS
+        } else {
+            LOAD_NEWEST_BARS
+        };
+        let from = to_i64(from.unwrap_or(0).into());
+        let to = to_i64(to.unwrap_or(u64::MAX).into());
+        let limit = to_i64(limit as u128);
+        let rows: Vec<BarRow> = match &self.db {
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as(query)
+                    .bind(symbol)
+                    .bind(interval.to_string())
+                    .bind(from)
+                    .bind(to)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as(query)
+                    .bind(symbol)
+                    .bind(interval.to_string())
+                    .bind(from)
+                    .bind(to)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
+        let mut bars: Vec<OhlcBar> = rows.into_iter().map(bar_from_row).collect();
+        if !anchor_at_from {
+            bars.reverse();
+        }
+        Ok(bars)
+    }
+
+    /// Seeds `aggregator` with the stored bars still open at `now_secs`, so
+    /// the first trades after a restart extend them instead of replacing
+    /// them. Returns the number of bars seeded.
+    ///
+    /// # Errors
+    /// Returns [`OhlcStoreError`] when the query fails.
+    pub async fn resume_open_bars(
+        &self,
+        aggregator: &OhlcAggregator,
+        now_secs: u64,
+    ) -> Result<usize, OhlcStoreError> {
+        let mut seeded = 0;
+        for interval in INTERVALS {
+            let open_since = to_i64(interval.floor_timestamp(now_secs).into());
+            let rows: Vec<SymbolBarRow> = match &self.db {
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query_as(LOAD_OPEN_BARS)
+                        .bind(interval.to_string())
+                        .bind(open_since)
+                        .fetch_all(pool)
+                        .await?
+                }
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query_as(LOAD_OPEN_BARS)
+                        .bind(interval.to_string())
+                        .bind(open_since)
+                        .fetch_all(pool)
+                        .await?
+                }
+            };
+            seeded += rows.len();
+            let series = rows
+                .into_iter()
+                .map(
+                    |(symbol, bar_start, open, high, low, close, volume, trades)| OhlcSeries {
+                        symbol,
+                        interval,
+                        bars: vec![bar_from_row((
+                            bar_start, open, high, low, close, volume, trades,
+                        ))],
+                    },
+                )
+                .collect();
+            aggregator.seed_series(series);
+        }
+        Ok(seeded)
+    }
+
+    /// Rebuilds the stored bars from the `executions` table: every bar of
+    /// `symbol` (or of every symbol) with trades between `from` and `to`,
+    /// both in seconds and widened to whole days. Rebuilt bars replace the
+    /// stored ones; bars without executions in the table are left alone.
+    ///
+    /// # Errors
+    /// Returns [`OhlcStoreError`] when a query fails.
+    pub async fn backfill(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        symbol: Option<&str>,
+    ) -> Result<BackfillSummary, OhlcStoreError> {
+        let from = OhlcInterval::OneDay.floor_timestamp(from.unwrap_or(0));
+        let to = to.map(|to| {
+            OhlcInterval::OneDay
+                .floor_timestamp(to)
+                .saturating_add(DAY_SECS)
+        });
+        let from_secs = to_i64(from.into());
+        let to_secs = to.map(|to| to_i64(to.into()));
+        let rows: Vec<(String, i64, i64, i64)> = match &self.db {
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as(LOAD_EXECUTIONS)
+                    .bind(symbol)
+                    .bind(from_secs)
+                    .bind(to_secs)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as(SQLITE_LOAD_EXECUTIONS)
+                    .bind(symbol)
+                    .bind(from_secs)
+                    .bind(to_secs)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
+
+        let trades: Vec<(String, u64, u128, u64)> = rows
+            .into_iter()
+            .map(|(symbol, executed_at_ms, price, quantity)| {
+                (
+                    symbol,
+                    executed_at_ms.max(0) as u64,
+                    price.max(0) as u128,
+                    quantity.max(0) as u64,
+                )
+            })
+            .collect();
+        let series = fold_bars(&trades);
+        let bars = self.write(&series).await?;
+        Ok(BackfillSummary {
+            executions: trades.len() as u64,
+            bars,
+            from,
+            to,
+        })
+    }
+}
+
+/// Writes every bar of `series`, replacing stored bars with the same start.
+async fn upsert_bars(conn: &mut PgConnection, series: &[OhlcSeries]) -> Result<u64, sqlx::Error> {
+    let mut written = 0;
+    for (symbol, interval, bar) in bars_of(series) {
+        sqlx::query(UPSERT_BAR)
+            .bind(symbol)
+            .bind(interval)
+            .bind(to_i64(bar.timestamp.into()))
+            .bind(to_i64(bar.open))
+            .bind(to_i64(bar.high))
+            .bind(to_i64(bar.low))
+            .bind(to_i64(bar.close))
+            .bind(to_i64(bar.volume.into()))
+            .bind(to_i64(bar.trade_count.into()))
+            .execute(&mut *conn)
+            .await?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// [`upsert_bars`] for SQLite.
+async fn sqlite_upsert_bars(
+    conn: &mut SqliteConnection,
+    series: &[OhlcSeries],
+) -> Result<u64, sqlx::Error> {
+    let mut written = 0;
+    for (symbol, interval, bar) in bars_of(series) {
+        sqlx::query(UPSERT_BAR)
+            .bind(symbol)
+            .bind(interval)
+            .bind(to_i64(bar.timestamp.into()))
+            .bind(to_i64(bar.open))
+            .bind(to_i64(bar.high))
+            .bind(to_i64(bar.low))
+            .bind(to_i64(bar.close))
+            .bind(to_i64(bar.volume.into()))
+            .bind(to_i64(bar.trade_count.into()))
+            .execute(&mut *conn)
+            .await?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Every bar of `series` with its symbol and interval.
+fn bars_of(series: &[OhlcSeries]) -> impl Iterator<Item = (&str, String, &OhlcBar)> {
+    series.iter().flat_map(|entry| {
+        entry
+            .bars
+            .iter()
+            .map(|bar| (entry.symbol.as_str(), entry.interval.to_string(), bar))
+    })
+}
+
+/// Folds `(symbol, timestamp_ms, price, quantity)` trades, in time order,
+/// into bars of every interval. Unlike the aggregator this keeps every bar.
+fn fold_bars(trades: &[(String, u64, u128, u64)]) -> Vec<OhlcSeries> {
+    let mut series: HashMap<(String, OhlcInterval), BTreeMap<u64, OhlcBar>> = HashMap::new();
+    for (symbol, timestamp_ms, price, quantity) in trades {
+        for interval in INTERVALS {
+            let start = interval.floor_timestamp(timestamp_ms / 1000);
+            series
+                .entry((symbol.clone(), interval))
+                .or_default()
+                .entry(start)
+                .and_modify(|bar| bar.update(*price, *quantity))
+                .or_insert_with(|| OhlcBar::new(start, *price, *quantity));
+        }
+    }
+    series
+        .into_iter()
+        .map(|((symbol, interval), bars)| OhlcSeries {
+            symbol,
+            interval,
+            bars: bars.into_values().collect(),
+        })
+        .collect()
+}
+
+/// One stored bar: start, open, high, low, close, volume and trade count.
+type BarRow = (i64, i64, i64, i64, i64, i64, i64);
+
+/// One stored bar preceded by its symbol.
+type SymbolBarRow = (String, i64, i64, i64, i64, i64, i64, i64);
+
+fn bar_from_row((bar_start, open, high, low, close, volume, trades): BarRow) -> OhlcBar {
+    OhlcBar {
+        timestamp: bar_start.max(0) as u64,
+        open: open.max(0) as u128,
+        high: high.max(0) as u128,
+        low: low.max(0) as u128,
+        close: close.max(0) as u128,
+        volume: volume.max(0) as u64,
+        trade_count: trades.max(0) as u64,
+    }
+}
+
+/// Saturating conversion for the `BIGINT` columns.
+fn to_i64(value: u128) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExecutionInfo, OrderSide};
+
+    const SYMBOL: &str = "BTC-20240329-50000-C";
+    /// 2024-01-01T00:00:00Z, a day boundary.
+    const MIDNIGHT_MS: u64 = 1_704_067_200_000;
+
+    async fn sqlite_db() -> DatabasePool {
+        let db = DatabasePool::new("sqlite::memory:")
+            .await
+            .expect("connected");
+        db.run_migrations().await.expect("migrated");
+        db
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_flush_load_and_resume_bars() {
+        let store = OhlcStore::new(sqlite_db().await);
+        let aggregator = OhlcAggregator::new();
+        aggregator.record_trade(SYMBOL, MIDNIGHT_MS, 500, 2);
+        aggregator.record_trade(SYMBOL, MIDNIGHT_MS + 30_000, 480, 1);
+        aggregator.record_trade(SYMBOL, MIDNIGHT_MS + 60_000, 510, 4);
+
+        let flushed = store.flush(&aggregator).await.expect("flushed");
+        assert_eq!(
+            flushed.bars, 7,
+            "two minute bars and one per longer interval"
+        );
+        assert_eq!(store.flush(&aggregator).await.expect("flushed").bars, 0);
+
+        let minute = MIDNIGHT_MS / 1000;
+        let newest = store
+            .load_bars(SYMBOL, OhlcInterval::OneMinute, None, None, false, 1)
+            .await
+            .expect("loaded");
+        assert_eq!(newest.len(), 1);
+        assert_eq!((newest[0].timestamp, newest[0].close), (minute + 60, 510));
+        let oldest = store
+            .load_bars(
+                SYMBOL,
+                OhlcInterval::OneMinute,
+                Some(minute),
+                Some(minute + 60),
+                true,
+                1,
+            )
+            .await
+            .expect("loaded");
+        assert_eq!(oldest.len(), 1);
+        assert_eq!(
+            (oldest[0].open, oldest[0].low, oldest[0].close),
+            (500, 480, 480)
+        );
+        assert_eq!((oldest[0].volume, oldest[0].trade_count), (3, 2));
+
+        // After a restart the open minute bar is extended, not replaced.
+        let restarted = OhlcAggregator::new();
+        let seeded = store
+            .resume_open_bars(&restarted, minute + 90)
+            .await
+            .expect("resumed");
+        assert_eq!(seeded, 6);
+        restarted.record_trade(SYMBOL, (minute + 90) * 1000, 520, 1);
+        let bar = restarted
+            .get_latest_bar(SYMBOL, OhlcInterval::OneMinute)
+            .expect("bar");
+        assert_eq!((bar.open, bar.close), (510, 520));
+        assert_eq!((bar.volume, bar.trade_count), (5, 2));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_backfill_rebuilds_bars_from_stored_executions() {
+        let db = sqlite_db().await;
+        let fill = |id: &str, price: u64, quantity: u64, timestamp_ms: u64| ExecutionInfo {
+            execution_id: id.to_string(),
+            order_id: format!("order-{id}"),
+            symbol: SYMBOL.to_string(),
+            side: OrderSide::Buy,
+            price,
+            quantity,
+            timestamp_ms,
+            counterparty_order_id: None,
+            is_maker: false,
+            fee: 0,
+            edge: None,
+        };
+        db.repository()
+            .save_fills(&[
+                fill("t0", 7, 1, MIDNIGHT_MS - 1_000),
+                fill("t1", 500, 2, MIDNIGHT_MS),
+                fill("t2", 480, 1, MIDNIGHT_MS + 30_000),
+            ])
+            .await
+            .expect("saved");
+        let store = OhlcStore::new(db);
+
+        let midnight = MIDNIGHT_MS / 1000;
+        let summary = store
+            .backfill(Some(midnight + 10), Some(midnight + 20), Some(SYMBOL))
+            .await
+            .expect("backfilled");
+        assert_eq!(
+            summary,
+            BackfillSummary {
+                executions: 2,
+                bars: 6,
+                from: midnight,
+                to: Some(midnight + DAY_SECS),
+            }
+        );
+        let days = store
+            .load_bars(SYMBOL, OhlcInterval::OneDay, None, None, false, 10)
+            .await
+            .expect("loaded");
+        assert_eq!(days.len(), 1);
+        assert_eq!((days[0].open, days[0].close), (500, 480));
+        assert_eq!((days[0].volume, days[0].trade_count), (3, 2));
+    }
+
+    #[test]
+    fn test_fold_bars_builds_every_interval() {
+        let minute = 1_704_067_200_000;
+        let trades = vec![
+            ("SYM1".to_string(), minute, 500, 2),
+            ("SYM1".to_string(), minute + 30_000, 480, 1),
+            ("SYM1".to_string(), minute + 60_000, 510, 4),
+            ("SYM2".to_string(), minute, 7, 1),
+        ];
+        let series = fold_bars(&trades);
+        assert_eq!(series.len(), 12, "six intervals for each of two symbols");
+
+        let find = |symbol: &str, interval: OhlcInterval| {
+            series
+                .iter()
+                .find(|s| s.symbol == symbol && s.interval == interval)
+                .expect("series")
+                .bars
+                .clone()
+        };
+        let minutes = find("SYM1", OhlcInterval::OneMinute);
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(
+            (minutes[0].open, minutes[0].low, minutes[0].close),
+            (500, 480, 480)
+        );
+        let days = find("SYM1", OhlcInterval::OneDay);
+        assert_eq!(days.len(), 1);
+        assert_eq!((days[0].volume, days[0].trade_count), (7, 3));
+        assert_eq!((days[0].high, days[0].close), (510, 510));
+    }
+}
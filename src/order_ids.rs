@@ -0,0 +1,101 @@
+//! Deterministic order IDs.
+//!
+//! Every order the server creates — client submissions, amends and the
+//! market maker's quotes — takes its ID from one [`OrderIdSource`]: a
+//! version-5 UUID of a per-run namespace and a counter. A journal replay
+//! that seeks the source to the position recorded with each entry hands out
+//! exactly the IDs the live run did.
+
+use orderbook_rs::OrderId;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where an [`OrderIdSource`] stands: the namespace and the counter value of
+/// the next ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderIdPosition {
+    /// Namespace every ID of this run is derived from.
+    pub namespace: Uuid,
+    /// Counter value of the next ID.
+    pub next: u64,
+}
+
+impl OrderIdPosition {
+    /// Returns the start of a fresh random namespace, so IDs never collide
+    /// with those of an earlier run.
+    #[must_use]
+    pub fn fresh() -> Self {
+        Self {
+            namespace: Uuid::new_v4(),
+            next: 0,
+        }
+    }
+
+    /// Returns the ID at this position.
+    #[must_use]
+    pub fn order_id(&self) -> OrderId {
+        OrderId::from_uuid(Uuid::new_v5(&self.namespace, &self.next.to_be_bytes()))
+    }
+}
+
+/// Hands out order IDs in a reproducible sequence.
+#[derive(Debug)]
+pub struct OrderIdSource {
+    position: Mutex<OrderIdPosition>,
+}
+
+impl OrderIdSource {
+    /// Creates a source at [`OrderIdPosition::fresh`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            position: Mutex::new(OrderIdPosition::fresh()),
+        }
+    }
+
+    /// Returns the next ID and advances the counter.
+    pub fn next_id(&self) -> OrderId {
+        let mut position = self.position.lock();
+        let id = position.order_id();
+        position.next += 1;
+        id
+    }
+
+    /// Returns the current position.
+    #[must_use]
+    pub fn position(&self) -> OrderIdPosition {
+        *self.position.lock()
+    }
+
+    /// Moves the source to `position`.
+    pub fn seek(&self, position: OrderIdPosition) {
+        *self.position.lock() = position;
+    }
+}
+
+impl Default for OrderIdSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_replays_the_same_ids() {
+        let source = OrderIdSource::new();
+        let start = source.position();
+        let first: Vec<OrderId> = (0..3).map(|_| source.next_id()).collect();
+        assert_eq!(source.position().next, 3);
+        assert_ne!(first[0], first[1]);
+
+        let replay = OrderIdSource::new();
+        assert_ne!(replay.next_id(), first[0]);
+        replay.seek(start);
+        let again: Vec<OrderId> = (0..3).map(|_| replay.next_id()).collect();
+        assert_eq!(first, again);
+    }
+}
@@ -0,0 +1,162 @@
+//! Orders tracked by the API, indexed by account.
+//!
+//! [`OrderStore`] keeps every tracked order by ID, like a plain map, plus the
+//! IDs of each account's orders, so per-account work such as the pre-trade
+//! margin check touches only that account's orders instead of scanning every
+//! order in the system. Reads go through the underlying [`DashMap`]; inserts
+//! and removals go through [`OrderStore`] so the index stays in step.
+
+use crate::models::OrderInfo;
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::ops::Deref;
+
+/// Tracked orders by ID, with an index of order IDs by account.
+#[derive(Debug, Default)]
+pub struct OrderStore {
+    orders: DashMap<String, OrderInfo>,
+    by_account: DashMap<String, HashSet<String>>,
+}
+
+impl OrderStore {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts or replaces the order with ID `order_id`, returning the order
+    /// it replaced.
+    pub fn insert(&self, order_id: String, order: OrderInfo) -> Option<OrderInfo> {
+        let account = order.account.clone();
+        self.by_account
+            .entry(account.clone())
+            .or_default()
+            .insert(order_id.clone());
+        let previous = self.orders.insert(order_id.clone(), order);
+        if let Some(previous) = &previous
+            && previous.account != account
+        {
+            self.unindex(&previous.account, &order_id);
+        }
+        previous
+    }
+
+    /// Removes the order with ID `order_id`, returning it with its ID.
+    pub fn remove(&self, order_id: &str) -> Option<(String, OrderInfo)> {
+        let removed = self.orders.remove(order_id)?;
+        self.unindex(&removed.1.account, order_id);
+        Some(removed)
+    }
+
+    /// Removes every order.
+    pub fn clear(&self) {
+        self.orders.clear();
+        self.by_account.clear();
+    }
+
+    /// Returns the orders of `account`.
+    #[must_use]
+    pub fn account_orders(&self, account: &str) -> Vec<OrderInfo> {
+        let Some(ids) = self.by_account.get(account).map(|ids| ids.clone()) else {
+            return Vec::new();
+        };
+        ids.iter()
+            .filter_map(|id| self.orders.get(id).map(|order| order.clone()))
+            .filter(|order| order.account == account)
+            .collect()
+    }
+
+    /// Drops `order_id` from the index of `account`.
+    fn unindex(&self, account: &str, order_id: &str) {
+        if let Some(mut ids) = self.by_account.get_mut(account) {
+            ids.remove(order_id);
+        }
+        self.by_account.remove_if(account, |_, ids| ids.is_empty());
+    }
+}
+
+impl Deref for OrderStore {
+    type Target = DashMap<String, OrderInfo>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{OrderSide, OrderStatus, OrderTimeInForce};
+
+    fn order(order_id: &str, account: &str) -> OrderInfo {
+        OrderInfo {
+            order_id: order_id.to_string(),
+            account: account.to_string(),
+            symbol: "BTC-20240329-50000-C".to_string(),
+            underlying: "BTC".to_string(),
+            expiration: "20240329".to_string(),
+            strike: 50000,
+            style: "call".to_string(),
+            side: OrderSide::Buy,
+            price: 100,
+            original_quantity: 10,
+            remaining_quantity: 10,
+            filled_quantity: 0,
+            status: OrderStatus::Active,
+            time_in_force: OrderTimeInForce::Gtc,
+            created_at_ms: 0,
+            updated_at_ms: 0,
+            fills: vec![],
+        }
+    }
+
+    fn ids(orders: Vec<OrderInfo>) -> Vec<String> {
+        let mut ids: Vec<String> = orders.into_iter().map(|o| o.order_id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_account_orders_follow_inserts_and_removes() {
+        let store = OrderStore::new();
+        store.insert("a1".to_string(), order("a1", "alice"));
+        store.insert("a2".to_string(), order("a2", "alice"));
+        store.insert("b1".to_string(), order("b1", "bob"));
+
+        assert_eq!(ids(store.account_orders("alice")), vec!["a1", "a2"]);
+        assert_eq!(ids(store.account_orders("bob")), vec!["b1"]);
+        assert!(store.account_orders("carol").is_empty());
+
+        assert!(store.remove("a1").is_some());
+        assert!(store.remove("a1").is_none());
+        assert_eq!(ids(store.account_orders("alice")), vec!["a2"]);
+        assert_eq!(store.len(), 2);
+
+        store.clear();
+        assert!(store.account_orders("alice").is_empty());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_reinsert_under_another_account_moves_the_order() {
+        let store = OrderStore::new();
+        store.insert("o1".to_string(), order("o1", "alice"));
+        let previous = store.insert("o1".to_string(), order("o1", "bob"));
+
+        assert_eq!(previous.map(|o| o.account), Some("alice".to_string()));
+        assert!(store.account_orders("alice").is_empty());
+        assert_eq!(ids(store.account_orders("bob")), vec!["o1"]);
+    }
+
+    #[test]
+    fn test_reads_go_through_the_map() {
+        let store = OrderStore::new();
+        store.insert("o1".to_string(), order("o1", "alice"));
+        if let Some(mut entry) = store.get_mut("o1") {
+            entry.remaining_quantity = 4;
+        }
+        assert_eq!(store.get("o1").map(|o| o.remaining_quantity), Some(4));
+        assert!(store.contains_key("o1"));
+    }
+}
@@ -0,0 +1,351 @@
+//! Top-of-book history.
+//!
+//! Nothing else keeps the evolution of bids and asks. With a database
+//! connected and `[quote_history] enabled`, a [`QuoteRecorder`] samples the
+//! best bid, best ask and their sizes of every option book on a fixed
+//! interval, buffers each one that changed since the previous sample and
+//! appends the buffered changes to the `quote_history` table on every flush.
+//! `get_quote_history` reads them back by instrument and time range.
+
+use chrono::Utc;
+use dashmap::DashMap;
+use option_chain_orderbook::orderbook::{OptionOrderBook, UnderlyingOrderBookManager};
+use optionstratlib::OptionStyle;
+use parking_lot::Mutex;
+use sqlx::PgPool;
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Most changes buffered between two flushes; beyond it the oldest are
+/// dropped.
+const MAX_PENDING_TICKS: usize = 262_144;
+
+/// The oldest changes of a symbol within a range.
+const LOAD_OLDEST_TICKS: &str = r#"
+    SELECT recorded_at, bid_price, bid_size, ask_price, ask_size
+    FROM quote_history
+    WHERE symbol = $1 AND recorded_at >= $2 AND recorded_at <= $3
+    ORDER BY recorded_at ASC
+    LIMIT $4
+"#;
+
+/// The newest changes of a symbol within a range.
+const LOAD_NEWEST_TICKS: &str = r#"
+    SELECT recorded_at, bid_price, bid_size, ask_price, ask_size
+    FROM quote_history
+    WHERE symbol = $1 AND recorded_at >= $2 AND recorded_at <= $3
+    ORDER BY recorded_at DESC
+    LIMIT $4
+"#;
+
+/// Errors from the quote history.
+#[derive(Debug, Error)]
+pub enum QuoteHistoryError {
+    /// A database query failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Best bid and ask of one book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopOfBook {
+    /// Best bid price in cents.
+    pub bid_price: Option<u128>,
+    /// Quantity at the best bid.
+    pub bid_size: u64,
+    /// Best ask price in cents.
+    pub ask_price: Option<u128>,
+    /// Quantity at the best ask.
+    pub ask_size: u64,
+}
+
+impl TopOfBook {
+    /// Reads the current top of `book`.
+    #[must_use]
+    pub fn of(book: &OptionOrderBook) -> Self {
+        let quote = book.best_quote();
+        Self {
+            bid_price: quote.bid_price().map(|p| p.as_u128()),
+            bid_size: quote.bid_size().as_u64(),
+            ask_price: quote.ask_price().map(|p| p.as_u128()),
+            ask_size: quote.ask_size().as_u64(),
+        }
+    }
+}
+
+/// One recorded change of a book's top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteTick {
+    /// Canonical `UNDERLYING-EXPIRATION-STRIKE-STYLE` option key.
+    pub symbol: String,
+    /// Sample time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Top of the book at that time.
+    pub top: TopOfBook,
+}
+
+/// Last top seen per book and the changes not yet written.
+#[derive(Debug, Default)]
+struct QuoteTape {
+    last: DashMap<String, TopOfBook>,
+    pending: Mutex<VecDeque<QuoteTick>>,
+}
+
+impl QuoteTape {
+    /// Buffers `top` of `symbol` when it differs from the last one seen.
+    /// Returns whether it was buffered.
+    fn observe(&self, symbol: &str, top: TopOfBook, timestamp_ms: u64) -> bool {
+        if self.last.get(symbol).is_some_and(|last| *last == top) {
+            return false;
+        }
+        self.last.insert(symbol.to_string(), top);
+        let mut pending = self.pending.lock();
+        if pending.len() >= MAX_PENDING_TICKS {
+            pending.pop_front();
+            tracing::warn!("quote history buffer full; dropping the oldest change");
+        }
+        pending.push_back(QuoteTick {
+            symbol: symbol.to_string(),
+            timestamp_ms,
+            top,
+        });
+        true
+    }
+
+    /// Takes every buffered change.
+    fn take(&self) -> Vec<QuoteTick> {
+        self.pending.lock().drain(..).collect()
+    }
+
+    /// Puts `ticks` back in front of those buffered since they were taken,
+    /// keeping the newest when the total exceeds the cap.
+    fn requeue(&self, ticks: Vec<QuoteTick>) {
+        let mut pending = self.pending.lock();
+        for tick in ticks.into_iter().rev() {
+            pending.push_front(tick);
+        }
+        while pending.len() > MAX_PENDING_TICKS {
+            pending.pop_front();
+        }
+    }
+}
+
+/// Postgres-backed top-of-book recorder.
+pub struct QuoteRecorder {
+    pool: PgPool,
+    tape: QuoteTape,
+}
+
+impl QuoteRecorder {
+    /// Creates a recorder writing to `pool`.
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            tape: QuoteTape::default(),
+        }
+    }
+
+    /// Samples the top of every option book of `manager` and buffers those
+    /// that changed since the previous sample. Returns the number buffered.
+    pub fn sample(&self, manager: &UnderlyingOrderBookManager) -> usize {
+        let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+        sample_books(&self.tape, manager, now_ms)
+    }
+
+    /// Appends the buffered changes to `quote_history`. On failure they are
+    /// kept for the next flush. Returns the number of rows written.
+    ///
+    /// # Errors
+    /// Returns [`QuoteHistoryError`] when the write fails.
+    pub async fn flush(&self) -> Result<u64, QuoteHistoryError> {
+        let ticks = self.tape.take();
+        if ticks.is_empty() {
+            return Ok(0);
+        }
+        match self.write(&ticks).await {
+            Ok(written) => Ok(written),
+            Err(e) => {
+                self.tape.requeue(ticks);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn write(&self, ticks: &[QuoteTick]) -> Result<u64, sqlx::Error> {
+        let mut symbols = Vec::with_capacity(ticks.len());
+        let mut recorded_at = Vec::with_capacity(ticks.len());
+        let mut bid_prices = Vec::with_capacity(ticks.len());
+        let mut bid_sizes = Vec::with_capacity(ticks.len());
+        let mut ask_prices = Vec::with_capacity(ticks.len());
+        let mut ask_sizes = Vec::with_capacity(ticks.len());
+        for tick in ticks {
+            symbols.push(tick.symbol.clone());
+            recorded_at.push(to_i64(tick.timestamp_ms.into()));
+            bid_prices.push(tick.top.bid_price.map(to_i64));
+            bid_sizes.push(to_i64(tick.top.bid_size.into()));
+            ask_prices.push(tick.top.ask_price.map(to_i64));
+            ask_sizes.push(to_i64(tick.top.ask_size.into()));
+        }
+        let result = sqlx::query(
+            r#"
+            INSERT INTO quote_history
+                (symbol, recorded_at, bid_price, bid_size, ask_price, ask_size)
+            SELECT * FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::BIGINT[], $4::BIGINT[],
+                                 $5::BIGINT[], $6::BIGINT[])
+            "#,
+        )
+        .bind(symbols)
+        .bind(recorded_at)
+        .bind(bid_prices)
+        .bind(bid_sizes)
+        .bind(ask_prices)
+        .bind(ask_sizes)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Loads the recorded changes of `symbol` within `[from_ms, to_ms]`,
+    /// oldest first: the oldest `limit` when `anchor_at_from`, otherwise the
+    /// newest.
+    ///
+    /// # Errors
+    /// Returns [`QuoteHistoryError`] when the query fails.
+    pub async fn load(
+        &self,
+        symbol: &str,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+        anchor_at_from: bool,
+        limit: usize,
+    ) -> Result<Vec<QuoteTick>, QuoteHistoryError> {
+        let query = if anchor_at_from {
+            LOAD_OLDEST_TICKS
+        } else {
+            LOAD_NEWEST_TICKS
+        };
+        let rows: Vec<TickRow> = sqlx::query_as(query)
+            .bind(symbol)
+            .bind(to_i64(from_ms.unwrap_or(0).into()))
+            .bind(to_i64(to_ms.unwrap_or(u64::MAX).into()))
+            .bind(to_i64(limit as u128))
+            .fetch_all(&self.pool)
+            .await?;
+        let mut ticks: Vec<QuoteTick> = rows
+            .into_iter()
+            .map(
+                |(recorded_at, bid_price, bid_size, ask_price, ask_size)| QuoteTick {
+                    symbol: symbol.to_string(),
+                    timestamp_ms: recorded_at.max(0) as u64,
+                    top: TopOfBook {
+                        bid_price: bid_price.map(|p| p.max(0) as u128),
+                        bid_size: bid_size.max(0) as u64,
+                        ask_price: ask_price.map(|p| p.max(0) as u128),
+                        ask_size: ask_size.max(0) as u64,
+                    },
+                },
+            )
+            .collect();
+        if !anchor_at_from {
+            ticks.reverse();
+        }
+        Ok(ticks)
+    }
+}
+
+/// Observes the top of every option book of `manager` on `tape`. Returns
+/// the number of changes buffered.
+fn sample_books(tape: &QuoteTape, manager: &UnderlyingOrderBookManager, now_ms: u64) -> usize {
+    let mut changed = 0;
+    for underlying_symbol in manager.underlying_symbols() {
+        let Ok(underlying) = manager.get(&underlying_symbol) else {
+            continue;
+        };
+        for (exp, _) in underlying.expirations().iter() {
+            let Ok(exp_book) = underlying.get_expiration(&exp) else {
+                continue;
+            };
+            for strike in exp_book.strike_prices() {
+                let Ok(strike_book) = exp_book.get_strike(strike) else {
+                    continue;
+                };
+                for style in [OptionStyle::Call, OptionStyle::Put] {
+                    let book = strike_book.get(style);
+                    if tape.observe(book.symbol(), TopOfBook::of(book), now_ms) {
+                        changed += 1;
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// One stored change: time, bid price, bid size, ask price and ask size.
+type TickRow = (i64, Option<i64>, i64, Option<i64>, i64);
+
+/// Saturating conversion for the `BIGINT` columns.
+fn to_i64(value: u128) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::Positive;
+    use orderbook_rs::{OrderId, Side};
+
+    fn top(bid: u128, bid_size: u64) -> TopOfBook {
+        TopOfBook {
+            bid_price: Some(bid),
+            bid_size,
+            ..TopOfBook::default()
+        }
+    }
+
+    #[test]
+    fn test_tape_buffers_only_changes() {
+        let tape = QuoteTape::default();
+        assert!(tape.observe("A", top(100, 1), 1));
+        assert!(!tape.observe("A", top(100, 1), 2));
+        assert!(tape.observe("A", top(100, 2), 3));
+        assert!(tape.observe("B", top(100, 1), 3));
+
+        let taken = tape.take();
+        assert_eq!(taken.len(), 3);
+        assert!(tape.take().is_empty());
+
+        // The last top survives a flush: an unchanged book stays quiet.
+        assert!(!tape.observe("A", top(100, 2), 4));
+        assert!(tape.observe("A", TopOfBook::default(), 5));
+        tape.requeue(taken);
+        let times: Vec<u64> = tape.take().iter().map(|t| t.timestamp_ms).collect();
+        assert_eq!(times, vec![1, 3, 3, 5]);
+    }
+
+    #[test]
+    fn test_sample_books_records_top_of_book_changes() {
+        let manager = UnderlyingOrderBookManager::new();
+        let underlying = manager.get_or_create("BTC");
+        let exp_book = underlying.get_or_create_expiration(ExpirationDate::Days(Positive::THIRTY));
+        let strike_book = exp_book.get_or_create_strike(100_000);
+        let tape = QuoteTape::default();
+
+        // An empty call and put are each recorded once.
+        assert_eq!(sample_books(&tape, &manager, 1), 2);
+        assert_eq!(sample_books(&tape, &manager, 2), 0);
+
+        let call = strike_book.get(OptionStyle::Call);
+        call.add_limit_order(OrderId::new(), Side::Buy, 500, 3)
+            .expect("order");
+        assert_eq!(sample_books(&tape, &manager, 3), 1);
+
+        let ticks = tape.take();
+        let last = ticks.last().expect("tick");
+        assert_eq!(last.symbol, call.symbol());
+        assert_eq!(last.timestamp_ms, 3);
+        assert_eq!(last.top, top(500, 3));
+    }
+}
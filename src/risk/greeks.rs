@@ -0,0 +1,348 @@
+//! Portfolio greeks aggregation.
+//!
+//! Each position is valued with the quoter's Black-Scholes pricer in DOLLAR
+//! units (cents / 100, matching [`Quoter::generate_quote`]) and scaled by its
+//! signed size `q` in units of the underlying (quantity × contract
+//! multiplier). Totals are summed per expiration, per underlying, and
+//! across the whole book:
+//!
+//! * `delta` — underlying-equivalent units (Σ q·Δ).
+//! * `gamma` — change in `delta` per $1 move in the underlying (Σ q·Γ).
+//! * `vega` — dollars per 1 vol point (Σ q·vega).
+//! * `theta` — dollars per calendar day (Σ q·θ).
+//! * `rho` — dollars per 1% change in the risk-free rate (Σ q·ρ).
+//! * `dollar_delta` — dollars per 100% move in the underlying (Σ q·Δ·S).
+//! * `dollar_gamma` — change in `dollar_delta` per 1% move (Σ q·Γ·S² / 100).
+//!
+//! [`Quoter::generate_quote`]: crate::market_maker::Quoter::generate_quote
+
+use crate::market_maker::OptionPricer;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+/// A signed option position to be risked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskPosition {
+    /// Instrument identifier, reported back for positions that cannot be priced.
+    pub instrument: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration of the option.
+    pub expiration: ExpirationDate,
+    /// Expiration bucket key (YYYYMMDD).
+    pub expiration_key: String,
+    /// Strike price in cents.
+    pub strike: u64,
+    /// Call or Put.
+    pub style: OptionStyle,
+    /// Net quantity (positive = long, negative = short).
+    pub quantity: i64,
+    /// Units of the underlying per contract.
+    pub multiplier: u64,
+}
+
+impl RiskPosition {
+    /// Signed size in units of the underlying: quantity × multiplier.
+    #[must_use]
+    pub fn units(&self) -> f64 {
+        self.quantity as f64 * self.multiplier as f64
+    }
+}
+
+/// Net greeks of a set of positions. See the module docs for units.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GreekTotals {
+    /// Net delta.
+    pub delta: f64,
+    /// Net gamma.
+    pub gamma: f64,
+    /// Net vega.
+    pub vega: f64,
+    /// Net daily theta.
+    pub theta: f64,
+    /// Net rho.
+    pub rho: f64,
+    /// Net dollar delta.
+    pub dollar_delta: f64,
+    /// Net dollar gamma.
+    pub dollar_gamma: f64,
+}
+
+impl AddAssign for GreekTotals {
+    fn add_assign(&mut self, other: Self) {
+        self.delta += other.delta;
+        self.gamma += other.gamma;
+        self.vega += other.vega;
+        self.theta += other.theta;
+        self.rho += other.rho;
+        self.dollar_delta += other.dollar_delta;
+        self.dollar_gamma += other.dollar_gamma;
+    }
+}
+
+/// Net greeks of one expiration bucket within an underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpirationGreeks {
+    /// Expiration bucket key (YYYYMMDD).
+    pub expiration: String,
+    /// Number of positions in the bucket.
+    pub positions: usize,
+    /// Net greeks of the bucket.
+    pub greeks: GreekTotals,
+}
+
+/// Net greeks of one underlying, with its expiration breakdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingGreeks {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Spot price in cents the positions were valued against.
+    pub spot: u64,
+    /// Net greeks across every expiration of the underlying.
+    pub greeks: GreekTotals,
+    /// Per-expiration breakdown, ordered by expiration key.
+    pub expirations: Vec<ExpirationGreeks>,
+}
+
+/// Aggregated greeks of a whole book.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PortfolioGreeks {
+    /// Per-underlying breakdown, ordered by symbol.
+    pub underlyings: Vec<UnderlyingGreeks>,
+    /// Net greeks across the whole book.
+    pub total: GreekTotals,
+    /// Instruments left out because their underlying has no spot price or
+    /// the pricer returned a non-finite value.
+    pub unpriced: Vec<String>,
+}
+
+/// Expiration key -> (position count, net greeks) within one underlying.
+type ExpirationBuckets<'a> = BTreeMap<&'a str, (usize, GreekTotals)>;
+
+/// Greeks of a single signed position at `spot` cents.
+///
+/// Returns `None` when the pricer yields a non-finite value (degenerate inputs
+/// such as a zero strike), so one bad instrument cannot poison a total.
+#[must_use]
+pub fn position_greeks(
+    pricer: &OptionPricer,
+    position: &RiskPosition,
+    spot: u64,
+) -> Option<GreekTotals> {
+    let s = spot as f64 / 100.0;
+    let k = position.strike as f64 / 100.0;
+    let q = position.units();
+    let exp = &position.expiration;
+    let style = position.style;
+
+    let delta = pricer.delta(s, k, exp, style, None);
+    let gamma = pricer.gamma(s, k, exp, None);
+    let greeks = GreekTotals {
+        delta: q * delta,
+        gamma: q * gamma,
+        vega: q * pricer.vega(s, k, exp, None),
+        theta: q * pricer.theta(s, k, exp, style, None),
+        rho: q * pricer.rho(s, k, exp, style, None),
+        dollar_delta: q * delta * s,
+        dollar_gamma: q * gamma * s * s / 100.0,
+    };
+
+    let finite = [
+        greeks.delta,
+        greeks.gamma,
+        greeks.vega,
+        greeks.theta,
+        greeks.rho,
+        greeks.dollar_delta,
+        greeks.dollar_gamma,
+    ]
+    .iter()
+    .all(|g| g.is_finite());
+    finite.then_some(greeks)
+}
+
+/// Aggregates `positions` into per-expiration, per-underlying, and total
+/// greeks.
+///
+/// `spot` resolves an underlying's current price in cents; positions whose
+/// underlying has no price (or that price to a non-finite value) are listed in
+/// [`PortfolioGreeks::unpriced`] instead of being valued at a guess. Flat
+/// positions are skipped.
+#[must_use]
+pub fn aggregate_greeks(
+    pricer: &OptionPricer,
+    positions: &[RiskPosition],
+    spot: impl Fn(&str) -> Option<u64>,
+) -> PortfolioGreeks {
+    // underlying -> (spot, expiration key -> (count, totals))
+    let mut buckets: BTreeMap<&str, (u64, ExpirationBuckets<'_>)> = BTreeMap::new();
+    let mut unpriced = Vec::new();
+
+    for position in positions.iter().filter(|p| p.quantity != 0) {
+        let spot_cents = match buckets.get(position.underlying.as_str()) {
+            Some((s, _)) => Some(*s),
+            None => spot(&position.underlying),
+        };
+        let Some(spot_cents) = spot_cents.filter(|s| *s > 0) else {
+            unpriced.push(position.instrument.clone());
+            continue;
+        };
+        let Some(greeks) = position_greeks(pricer, position, spot_cents) else {
+            unpriced.push(position.instrument.clone());
+            continue;
+        };
+
+        let (_, expirations) = buckets
+            .entry(position.underlying.as_str())
+            .or_insert_with(|| (spot_cents, BTreeMap::new()));
+        let (count, totals) = expirations
+            .entry(position.expiration_key.as_str())
+            .or_default();
+        *count += 1;
+        *totals += greeks;
+    }
+
+    let mut total = GreekTotals::default();
+    let underlyings = buckets
+        .into_iter()
+        .map(|(underlying, (spot, expirations))| {
+            let mut greeks = GreekTotals::default();
+            let expirations = expirations
+                .into_iter()
+                .map(|(expiration, (positions, totals))| {
+                    greeks += totals;
+                    ExpirationGreeks {
+                        expiration: expiration.to_string(),
+                        positions,
+                        greeks: totals,
+                    }
+                })
+                .collect();
+            total += greeks;
+            UnderlyingGreeks {
+                underlying: underlying.to_string(),
+                spot,
+                greeks,
+                expirations,
+            }
+        })
+        .collect();
+
+    unpriced.sort();
+    PortfolioGreeks {
+        underlyings,
+        total,
+        unpriced,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::prelude::Positive;
+
+    fn position(
+        underlying: &str,
+        days: f64,
+        strike: u64,
+        style: OptionStyle,
+        qty: i64,
+    ) -> RiskPosition {
+        RiskPosition {
+            instrument: format!("{underlying}-{days}-{strike}"),
+            underlying: underlying.to_string(),
+            expiration: ExpirationDate::Days(Positive::new(days).expect("positive days")),
+            expiration_key: format!("{days}"),
+            strike,
+            style,
+            quantity: qty,
+            multiplier: 1,
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9 * (1.0 + a.abs().max(b.abs()))
+    }
+
+    #[test]
+    fn test_position_greeks_scale_with_signed_quantity() {
+        let pricer = OptionPricer::default();
+        let long = position("BTC", 30.0, 10_000, OptionStyle::Call, 2);
+        let short = RiskPosition {
+            quantity: -2,
+            ..long.clone()
+        };
+
+        let l = position_greeks(&pricer, &long, 10_000).expect("finite");
+        let s = position_greeks(&pricer, &short, 10_000).expect("finite");
+        assert!(l.delta > 0.0 && l.gamma > 0.0 && l.vega > 0.0 && l.theta < 0.0);
+        assert!(close(l.delta, -s.delta));
+        assert!(close(l.vega, -s.vega));
+
+        let single = pricer.delta(100.0, 100.0, &long.expiration, OptionStyle::Call, None);
+        assert!(close(l.delta, 2.0 * single));
+        assert!(close(l.dollar_delta, l.delta * 100.0));
+        assert!(close(l.dollar_gamma, l.gamma * 100.0 * 100.0 / 100.0));
+
+        // Two 100-unit contracts carry a hundred times the exposure.
+        let hundreds = RiskPosition {
+            multiplier: 100,
+            ..long.clone()
+        };
+        let h = position_greeks(&pricer, &hundreds, 10_000).expect("finite");
+        assert!(close(h.delta, 100.0 * l.delta));
+        assert!(close(h.dollar_delta, 100.0 * l.dollar_delta));
+        assert!(close(h.vega, 100.0 * l.vega));
+    }
+
+    #[test]
+    fn test_aggregate_buckets_by_underlying_and_expiration() {
+        let pricer = OptionPricer::default();
+        let positions = vec![
+            position("BTC", 30.0, 10_000, OptionStyle::Call, 1),
+            position("BTC", 30.0, 11_000, OptionStyle::Put, -3),
+            position("BTC", 60.0, 10_000, OptionStyle::Call, 2),
+            position("ETH", 30.0, 5_000, OptionStyle::Call, 5),
+            position("ETH", 30.0, 5_000, OptionStyle::Put, 0),
+        ];
+        let spots = |u: &str| match u {
+            "BTC" => Some(10_000),
+            "ETH" => Some(5_000),
+            _ => None,
+        };
+
+        let book = aggregate_greeks(&pricer, &positions, spots);
+        assert!(book.unpriced.is_empty());
+        assert_eq!(book.underlyings.len(), 2);
+
+        let btc = &book.underlyings[0];
+        assert_eq!(btc.underlying, "BTC");
+        assert_eq!(btc.spot, 10_000);
+        assert_eq!(btc.expirations.len(), 2);
+        assert_eq!(btc.expirations[0].positions, 2);
+
+        // Buckets sum to the underlying, underlyings sum to the total.
+        let bucket_sum: f64 = btc.expirations.iter().map(|e| e.greeks.delta).sum();
+        assert!(close(bucket_sum, btc.greeks.delta));
+        let total: f64 = book.underlyings.iter().map(|u| u.greeks.vega).sum();
+        assert!(close(total, book.total.vega));
+
+        // The flat ETH put is skipped, not counted.
+        assert_eq!(book.underlyings[1].expirations[0].positions, 1);
+    }
+
+    #[test]
+    fn test_aggregate_reports_positions_without_spot_as_unpriced() {
+        let pricer = OptionPricer::default();
+        let positions = vec![
+            position("BTC", 30.0, 10_000, OptionStyle::Call, 1),
+            position("XYZ", 30.0, 10_000, OptionStyle::Call, 1),
+        ];
+
+        let book = aggregate_greeks(&pricer, &positions, |u| (u == "BTC").then_some(10_000));
+        assert_eq!(book.underlyings.len(), 1);
+        assert_eq!(book.unpriced, vec!["XYZ-30-10000".to_string()]);
+        assert!(close(book.total.delta, book.underlyings[0].greeks.delta));
+    }
+}
@@ -0,0 +1,331 @@
+//! Risk-array portfolio margin.
+//!
+//! Modelled on SPAN: every underlying is a scan group whose positions are
+//! fully revalued under sixteen scenarios — seven spot moves across the price
+//! scan range, each with volatility up and down, plus two extreme moves at
+//! [`EXTREME_MOVE_MULTIPLE`] times the range of which only
+//! [`EXTREME_MOVE_COVERAGE`] counts. The worst weighted loss is the group's scan
+//! risk.
+//!
+//! Because a group is scanned as a whole, offsetting positions (spreads,
+//! hedged shorts) net inside the risk array; the difference against scanning
+//! each position on its own is reported as the group's spread credit. The
+//! requirement of a group is the larger of its scan risk and a short option
+//! minimum per unit of the underlying sold, so deep out-of-the-money shorts
+//! are never free.
+
+use super::{MarketShock, RiskPosition, ScenarioBook};
+use crate::market_maker::OptionPricer;
+use std::collections::BTreeMap;
+
+/// Multiple of the price scan range used by the two extreme-move scenarios.
+pub const EXTREME_MOVE_MULTIPLE: f64 = 2.0;
+
+/// Fraction of an extreme-move loss that counts towards scan risk.
+pub const EXTREME_MOVE_COVERAGE: f64 = 0.35;
+
+/// Fractions of the price scan range covered by the regular scenarios.
+const PRICE_SCAN_FRACTIONS: [f64; 7] =
+    [0.0, 1.0 / 3.0, -1.0 / 3.0, 2.0 / 3.0, -2.0 / 3.0, 1.0, -1.0];
+
+/// Parameters of the margin model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginParams {
+    /// Price scan range, in percent of spot.
+    pub price_scan_pct: f64,
+    /// Volatility scan range, in vol points.
+    pub vol_scan_pts: f64,
+    /// Short option minimum per short contract, in percent of spot.
+    pub short_option_minimum_pct: f64,
+    /// Maintenance margin as a fraction of initial margin.
+    pub maintenance_ratio: f64,
+}
+
+/// One risk-array scenario: a shock and the fraction of its loss that counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskScenario {
+    /// Spot and volatility shock.
+    pub shock: MarketShock,
+    /// Weight applied to the scenario's loss.
+    pub weight: f64,
+}
+
+/// Margin of one underlying (scan group).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingMargin {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Worst weighted loss across the risk array, in cents (never negative).
+    pub scan_risk_cents: f64,
+    /// Index into [`risk_scenarios`] of the scenario that set the scan risk.
+    pub worst_scenario: usize,
+    /// Scan risk saved by netting positions within the group, in cents.
+    pub spread_credit_cents: f64,
+    /// Short option minimum, in cents.
+    pub short_option_minimum_cents: f64,
+    /// Requirement of the group: the larger of scan risk and the minimum.
+    pub requirement_cents: f64,
+}
+
+/// Margin requirement of a set of positions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarginRequirement {
+    /// Per-underlying breakdown, ordered by symbol.
+    pub underlyings: Vec<UnderlyingMargin>,
+    /// Initial margin in cents: the sum of the group requirements.
+    pub initial_cents: f64,
+    /// Maintenance margin in cents.
+    pub maintenance_cents: f64,
+    /// Instruments that could not be valued and are not margined.
+    pub unpriced: Vec<String>,
+}
+
+/// The sixteen risk-array scenarios for `params`.
+#[must_use]
+pub fn risk_scenarios(params: &MarginParams) -> Vec<RiskScenario> {
+    let mut scenarios = Vec::with_capacity(16);
+    for fraction in PRICE_SCAN_FRACTIONS {
+        for vol_sign in [1.0, -1.0] {
+            scenarios.push(RiskScenario {
+                shock: MarketShock {
+                    spot_pct: fraction * params.price_scan_pct,
+                    vol_pts: vol_sign * params.vol_scan_pts,
+                },
+                weight: 1.0,
+            });
+        }
+    }
+    for direction in [1.0, -1.0] {
+        scenarios.push(RiskScenario {
+            shock: MarketShock {
+                spot_pct: (direction * EXTREME_MOVE_MULTIPLE * params.price_scan_pct).max(-99.0),
+                vol_pts: 0.0,
+            },
+            weight: EXTREME_MOVE_COVERAGE,
+        });
+    }
+    scenarios
+}
+
+/// Worst weighted loss of `book` per underlying, with the scenario index.
+fn scan_risk(
+    book: &ScenarioBook,
+    pricer: &OptionPricer,
+    scenarios: &[RiskScenario],
+) -> BTreeMap<String, (f64, usize)> {
+    let mut worst: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+    for (index, scenario) in scenarios.iter().enumerate() {
+        let pnl = book.revalue(pricer, |_| scenario.shock, 0);
+        for (underlying, cents) in pnl.by_underlying {
+            let loss = -cents * scenario.weight;
+            let entry = worst.entry(underlying).or_insert((0.0, 0));
+            if loss > entry.0 {
+                *entry = (loss, index);
+            }
+        }
+    }
+    worst
+}
+
+/// Computes the margin requirement of `positions`.
+///
+/// `spot` resolves an underlying's price in cents; positions without one are
+/// listed in [`MarginRequirement::unpriced`] and carry no requirement.
+#[must_use]
+pub fn compute_margin(
+    pricer: &OptionPricer,
+    positions: Vec<RiskPosition>,
+    spot: impl Fn(&str) -> Option<u64>,
+    params: &MarginParams,
+) -> MarginRequirement {
+    let scenarios = risk_scenarios(params);
+
+    // Standalone scan risk of every position, summed per underlying, is what
+    // the group would need without netting; the difference is the credit.
+    let mut gross: BTreeMap<String, f64> = BTreeMap::new();
+    let mut short_units: BTreeMap<String, u64> = BTreeMap::new();
+    for position in &positions {
+        let single = ScenarioBook::new(pricer, vec![position.clone()], &spot);
+        for (underlying, (loss, _)) in scan_risk(&single, pricer, &scenarios) {
+            *gross.entry(underlying).or_default() += loss;
+        }
+        if position.quantity < 0 && !single.is_empty() {
+            *short_units.entry(position.underlying.clone()).or_default() += position
+                .quantity
+                .unsigned_abs()
+                .saturating_mul(position.multiplier);
+        }
+    }
+
+    let book = ScenarioBook::new(pricer, positions, &spot);
+    let netted = scan_risk(&book, pricer, &scenarios);
+
+    let mut initial = 0.0;
+    let underlyings = book
+        .underlyings()
+        .into_iter()
+        .map(|underlying| {
+            let (scan_risk_cents, worst_scenario) =
+                netted.get(&underlying).copied().unwrap_or((0.0, 0));
+            let spot_cents = spot(&underlying).unwrap_or(0) as f64;
+            let shorts = short_units.get(&underlying).copied().unwrap_or(0) as f64;
+            let short_option_minimum_cents =
+                shorts * spot_cents * params.short_option_minimum_pct / 100.0;
+            let requirement_cents = scan_risk_cents.max(short_option_minimum_cents);
+            initial += requirement_cents;
+            UnderlyingMargin {
+                spread_credit_cents: (gross.get(&underlying).copied().unwrap_or(0.0)
+                    - scan_risk_cents)
+                    .max(0.0),
+                underlying,
+                scan_risk_cents,
+                worst_scenario,
+                short_option_minimum_cents,
+                requirement_cents,
+            }
+        })
+        .collect();
+
+    MarginRequirement {
+        underlyings,
+        initial_cents: initial,
+        maintenance_cents: initial * params.maintenance_ratio,
+        unpriced: book.unpriced().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::prelude::Positive;
+    use optionstratlib::{ExpirationDate, OptionStyle};
+
+    fn params() -> MarginParams {
+        MarginParams {
+            price_scan_pct: 15.0,
+            vol_scan_pts: 10.0,
+            short_option_minimum_pct: 1.0,
+            maintenance_ratio: 0.75,
+        }
+    }
+
+    fn position(underlying: &str, strike: u64, style: OptionStyle, qty: i64) -> RiskPosition {
+        RiskPosition {
+            instrument: format!("{underlying}-30-{strike}-{style:?}"),
+            underlying: underlying.to_string(),
+            expiration: ExpirationDate::Days(Positive::new(30.0).expect("positive")),
+            expiration_key: "30".to_string(),
+            strike,
+            style,
+            quantity: qty,
+            multiplier: 1,
+        }
+    }
+
+    fn spots(underlying: &str) -> Option<u64> {
+        match underlying {
+            "BTC" => Some(10_000),
+            "ETH" => Some(5_000),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_risk_scenarios_follow_the_span_layout() {
+        let scenarios = risk_scenarios(&params());
+        assert_eq!(scenarios.len(), 16);
+        assert_eq!(scenarios[0].shock.spot_pct, 0.0);
+        assert_eq!(scenarios[1].shock.vol_pts, -10.0);
+        assert_eq!(scenarios[10].shock.spot_pct, 15.0);
+        assert_eq!(scenarios[13].shock.spot_pct, -15.0);
+        assert_eq!(scenarios[14].shock.spot_pct, 30.0);
+        assert_eq!(scenarios[15].weight, EXTREME_MOVE_COVERAGE);
+    }
+
+    #[test]
+    fn test_naked_short_call_needs_more_than_a_long_call() {
+        let pricer = OptionPricer::default();
+        let short = compute_margin(
+            &pricer,
+            vec![position("BTC", 10_000, OptionStyle::Call, -1)],
+            spots,
+            &params(),
+        );
+        let long = compute_margin(
+            &pricer,
+            vec![position("BTC", 10_000, OptionStyle::Call, 1)],
+            spots,
+            &params(),
+        );
+        assert!(short.initial_cents > long.initial_cents);
+        assert!((short.maintenance_cents - short.initial_cents * 0.75).abs() < 1e-9);
+        // A long option can lose at most its premium, and never needs more.
+        assert!(long.underlyings[0].scan_risk_cents > 0.0);
+        assert_eq!(long.underlyings[0].short_option_minimum_cents, 0.0);
+    }
+
+    #[test]
+    fn test_vertical_spread_earns_a_spread_credit() {
+        let pricer = OptionPricer::default();
+        let naked = compute_margin(
+            &pricer,
+            vec![position("BTC", 10_000, OptionStyle::Call, -1)],
+            spots,
+            &params(),
+        );
+        let spread = compute_margin(
+            &pricer,
+            vec![
+                position("BTC", 10_000, OptionStyle::Call, -1),
+                position("BTC", 11_000, OptionStyle::Call, 1),
+            ],
+            spots,
+            &params(),
+        );
+
+        let group = &spread.underlyings[0];
+        assert!(spread.initial_cents < naked.initial_cents);
+        assert!(group.spread_credit_cents > 0.0);
+        // A call spread can never lose more than its $10 strike width.
+        assert!(group.scan_risk_cents <= 1_000.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_short_option_minimum_floors_far_otm_shorts() {
+        let pricer = OptionPricer::default();
+        let margin = compute_margin(
+            &pricer,
+            vec![
+                position("BTC", 40_000, OptionStyle::Call, -10),
+                position("XYZ", 100, OptionStyle::Put, -1),
+            ],
+            spots,
+            &params(),
+        );
+
+        let group = &margin.underlyings[0];
+        // 10 shorts at 1% of a $100 spot.
+        assert!((group.short_option_minimum_cents - 1_000.0).abs() < 1e-9);
+        assert_eq!(group.requirement_cents, 1_000.0);
+        assert_eq!(margin.unpriced.len(), 1);
+        assert_eq!(margin.underlyings.len(), 1);
+    }
+
+    #[test]
+    fn test_margin_scales_with_the_contract_multiplier() {
+        let pricer = OptionPricer::default();
+        let short = position("BTC", 10_000, OptionStyle::Call, -2);
+        let single = compute_margin(&pricer, vec![short.clone()], spots, &params());
+        let hundreds = compute_margin(
+            &pricer,
+            vec![RiskPosition {
+                multiplier: 100,
+                ..short
+            }],
+            spots,
+            &params(),
+        );
+        let ratio = hundreds.initial_cents / single.initial_cents;
+        assert!((ratio - 100.0).abs() < 1e-6, "ratio {ratio}");
+    }
+}
@@ -0,0 +1,24 @@
+//! Portfolio risk analytics.
+//!
+//! Aggregates per-contract analytics from the market maker's
+//! [`OptionPricer`](crate::market_maker::OptionPricer) into portfolio-level
+//! exposure, so a book is valued exactly as the engine quotes it.
+
+mod greeks;
+mod margin;
+mod scenario;
+mod var;
+
+pub use greeks::{
+    ExpirationGreeks, GreekTotals, PortfolioGreeks, RiskPosition, UnderlyingGreeks,
+    aggregate_greeks, position_greeks,
+};
+pub use margin::{
+    EXTREME_MOVE_COVERAGE, EXTREME_MOVE_MULTIPLE, MarginParams, MarginRequirement, RiskScenario,
+    UnderlyingMargin, compute_margin, risk_scenarios,
+};
+pub use scenario::{MIN_SCENARIO_VOL, MarketShock, ScenarioBook, ScenarioPnl};
+pub use var::{
+    UnderlyingModel, VarEstimate, VarReport, historical_returns, monte_carlo_returns,
+    scenario_pnls, var_es,
+};
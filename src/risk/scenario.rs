@@ -0,0 +1,372 @@
+//! Scenario and stress revaluation.
+//!
+//! Unlike the greeks in [`super::greeks`], scenarios are FULL revaluations:
+//! every position is repriced with the quoter's Black-Scholes pricer under the
+//! shocked spot, shocked volatility, and rolled-forward expiry, so large moves
+//! capture the convexity a delta/gamma approximation misses.
+//!
+//! P&L is reported in cents against the current mark-to-model value (spot
+//! unchanged, the pricer's default volatility, today), so a pure time roll
+//! reports the book's theta decay.
+
+use super::RiskPosition;
+use crate::market_maker::OptionPricer;
+use optionstratlib::ExpirationDate;
+use optionstratlib::prelude::Positive;
+use std::collections::BTreeMap;
+
+/// Floor applied to a shocked implied volatility, so a large negative vol
+/// shock degrades to a near-zero-vol valuation instead of a `NaN`.
+pub const MIN_SCENARIO_VOL: f64 = 0.01;
+
+/// Shock applied to one underlying.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketShock {
+    /// Relative spot move in percent (e.g. `-30.0`).
+    pub spot_pct: f64,
+    /// Additive implied-volatility move in vol points (e.g. `20.0`).
+    pub vol_pts: f64,
+}
+
+/// P&L of one scenario.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScenarioPnl {
+    /// Total P&L in cents.
+    pub total_cents: f64,
+    /// P&L in cents per underlying, ordered by symbol.
+    pub by_underlying: BTreeMap<String, f64>,
+}
+
+/// A position ready for revaluation: its spot and current model value.
+#[derive(Debug, Clone)]
+struct ValuedPosition {
+    position: RiskPosition,
+    /// Spot in dollars.
+    spot: f64,
+    /// Strike in dollars.
+    strike: f64,
+    /// Current model value per contract in dollars.
+    base_value: f64,
+}
+
+/// A set of positions snapshotted against current spots for revaluation.
+#[derive(Debug, Clone)]
+pub struct ScenarioBook {
+    entries: Vec<ValuedPosition>,
+    unpriced: Vec<String>,
+}
+
+impl ScenarioBook {
+    /// Snapshots `positions` against `spot` (cents per underlying).
+    ///
+    /// Flat positions are dropped. A position whose underlying has no price, or
+    /// whose current model value is non-finite, is listed in
+    /// [`ScenarioBook::unpriced`] and excluded from every scenario.
+    #[must_use]
+    pub fn new(
+        pricer: &OptionPricer,
+        positions: Vec<RiskPosition>,
+        spot: impl Fn(&str) -> Option<u64>,
+    ) -> Self {
+        let mut entries = Vec::with_capacity(positions.len());
+        let mut unpriced = Vec::new();
+
+        for position in positions.into_iter().filter(|p| p.quantity != 0) {
+            let Some(spot_cents) = spot(&position.underlying).filter(|s| *s > 0) else {
+                unpriced.push(position.instrument);
+                continue;
+            };
+            let spot = spot_cents as f64 / 100.0;
+            let strike = position.strike as f64 / 100.0;
+            let base_value =
+                pricer.theoretical_value(spot, strike, &position.expiration, position.style, None);
+            if !base_value.is_finite() {
+                unpriced.push(position.instrument);
+                continue;
+            }
+            entries.push(ValuedPosition {
+                position,
+                spot,
+                strike,
+                base_value,
+            });
+        }
+
+        unpriced.sort();
+        Self { entries, unpriced }
+    }
+
+    /// Instruments excluded from revaluation, sorted.
+    #[must_use]
+    pub fn unpriced(&self) -> &[String] {
+        &self.unpriced
+    }
+
+    /// Number of positions that are revalued.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no position is revalued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Underlyings of the revalued positions, sorted and deduplicated.
+    #[must_use]
+    pub fn underlyings(&self) -> Vec<String> {
+        let mut underlyings: Vec<String> = self
+            .entries
+            .iter()
+            .map(|e| e.position.underlying.clone())
+            .collect();
+        underlyings.sort();
+        underlyings.dedup();
+        underlyings
+    }
+
+    /// Current signed mark-to-model value of the book in cents.
+    #[must_use]
+    pub fn base_value_cents(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|e| e.position.units() * e.base_value * 100.0)
+            .sum()
+    }
+
+    /// Fully revalues the book with `shock_for(underlying)` applied and time
+    /// rolled forward by `days_forward`, returning the P&L against the current
+    /// model value.
+    #[must_use]
+    pub fn revalue(
+        &self,
+        pricer: &OptionPricer,
+        shock_for: impl Fn(&str) -> MarketShock,
+        days_forward: u32,
+    ) -> ScenarioPnl {
+        let base_iv = pricer.default_iv();
+        let mut pnl = ScenarioPnl::default();
+
+        for entry in &self.entries {
+            let position = &entry.position;
+            let shock = shock_for(&position.underlying);
+            let spot = entry.spot * (1.0 + shock.spot_pct / 100.0);
+            let iv = (base_iv + shock.vol_pts / 100.0).max(MIN_SCENARIO_VOL);
+            let expiration = roll_forward(&position.expiration, days_forward);
+
+            let value =
+                pricer.theoretical_value(spot, entry.strike, &expiration, position.style, Some(iv));
+            // Inputs are validated (spot > 0, vol floored), so a non-finite
+            // value cannot arise; never let one poison the totals regardless.
+            if !value.is_finite() {
+                continue;
+            }
+
+            let cents = position.units() * (value - entry.base_value) * 100.0;
+            pnl.total_cents += cents;
+            *pnl.by_underlying
+                .entry(position.underlying.clone())
+                .or_default() += cents;
+        }
+
+        pnl
+    }
+
+    /// Total P&L in cents over a grid of uniform shocks, applied to every
+    /// underlying: `grid[i][j]` is the P&L for `spot_shocks_pct[i]` and
+    /// `vol_shocks_pts[j]` after rolling `days_forward` days.
+    #[must_use]
+    pub fn pnl_grid(
+        &self,
+        pricer: &OptionPricer,
+        spot_shocks_pct: &[f64],
+        vol_shocks_pts: &[f64],
+        days_forward: u32,
+    ) -> Vec<Vec<f64>> {
+        spot_shocks_pct
+            .iter()
+            .map(|&spot_pct| {
+                vol_shocks_pts
+                    .iter()
+                    .map(|&vol_pts| {
+                        self.revalue(pricer, |_| MarketShock { spot_pct, vol_pts }, days_forward)
+                            .total_cents
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Moves `expiration` `days` closer, so the pricer sees the remaining tenor as
+/// of `days` from now. A relative expiration is floored at zero days (expired,
+/// valued at intrinsic).
+fn roll_forward(expiration: &ExpirationDate, days: u32) -> ExpirationDate {
+    if days == 0 {
+        return *expiration;
+    }
+    match expiration {
+        ExpirationDate::DateTime(dt) => {
+            ExpirationDate::DateTime(*dt - chrono::Duration::days(i64::from(days)))
+        }
+        ExpirationDate::Days(remaining) => {
+            let left = (remaining.to_f64() - f64::from(days)).max(0.0);
+            ExpirationDate::Days(Positive::new(left).unwrap_or(Positive::ZERO))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+
+    fn position(underlying: &str, strike: u64, style: OptionStyle, qty: i64) -> RiskPosition {
+        RiskPosition {
+            instrument: format!("{underlying}-30-{strike}-{style:?}"),
+            underlying: underlying.to_string(),
+            expiration: ExpirationDate::Days(Positive::new(30.0).expect("positive")),
+            expiration_key: "30".to_string(),
+            strike,
+            style,
+            quantity: qty,
+            multiplier: 1,
+        }
+    }
+
+    fn spots(underlying: &str) -> Option<u64> {
+        match underlying {
+            "BTC" => Some(10_000),
+            "ETH" => Some(5_000),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_unshocked_scenario_has_zero_pnl() {
+        let pricer = OptionPricer::default();
+        let book = ScenarioBook::new(
+            &pricer,
+            vec![
+                position("BTC", 10_000, OptionStyle::Call, 3),
+                position("ETH", 5_000, OptionStyle::Put, -2),
+            ],
+            spots,
+        );
+
+        let pnl = book.revalue(&pricer, |_| MarketShock::default(), 0);
+        assert!(pnl.total_cents.abs() < 1e-6, "{}", pnl.total_cents);
+        assert!(book.base_value_cents() > 0.0 || book.base_value_cents() < 0.0);
+    }
+
+    #[test]
+    fn test_full_revaluation_captures_convexity() {
+        let pricer = OptionPricer::default();
+        let book = ScenarioBook::new(
+            &pricer,
+            vec![position("BTC", 10_000, OptionStyle::Call, 1)],
+            spots,
+        );
+
+        let up = book.revalue(
+            &pricer,
+            |_| MarketShock {
+                spot_pct: 30.0,
+                vol_pts: 0.0,
+            },
+            0,
+        );
+        let down = book.revalue(
+            &pricer,
+            |_| MarketShock {
+                spot_pct: -30.0,
+                vol_pts: 0.0,
+            },
+            0,
+        );
+        // A long call gains more on the way up than it loses on the way down,
+        // and can never lose more than its premium.
+        assert!(up.total_cents > -down.total_cents);
+        assert!(-down.total_cents <= book.base_value_cents() + 1e-6);
+    }
+
+    #[test]
+    fn test_shocks_apply_per_underlying() {
+        let pricer = OptionPricer::default();
+        let book = ScenarioBook::new(
+            &pricer,
+            vec![
+                position("BTC", 10_000, OptionStyle::Call, 1),
+                position("ETH", 5_000, OptionStyle::Call, 1),
+            ],
+            spots,
+        );
+
+        let pnl = book.revalue(
+            &pricer,
+            |u| match u {
+                "BTC" => MarketShock {
+                    spot_pct: -30.0,
+                    vol_pts: 20.0,
+                },
+                _ => MarketShock::default(),
+            },
+            0,
+        );
+        assert!(pnl.by_underlying["BTC"] != 0.0);
+        assert!(pnl.by_underlying["ETH"].abs() < 1e-6);
+        let sum: f64 = pnl.by_underlying.values().sum();
+        assert!((sum - pnl.total_cents).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_time_roll_and_vol_shock_signs() {
+        let pricer = OptionPricer::default();
+        let book = ScenarioBook::new(
+            &pricer,
+            vec![position("BTC", 10_000, OptionStyle::Put, 1)],
+            spots,
+        );
+
+        let decay = book.revalue(&pricer, |_| MarketShock::default(), 7);
+        assert!(decay.total_cents < 0.0, "long options lose value to time");
+        let crush = book.revalue(
+            &pricer,
+            |_| MarketShock {
+                spot_pct: 0.0,
+                vol_pts: -100.0,
+            },
+            0,
+        );
+        assert!(crush.total_cents < 0.0 && crush.total_cents.is_finite());
+        let expired = book.revalue(&pricer, |_| MarketShock::default(), 365);
+        // ATM put rolled past expiry is worth its (zero) intrinsic value.
+        assert!((expired.total_cents + book.base_value_cents()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pnl_grid_shape_and_unpriced() {
+        let pricer = OptionPricer::default();
+        let book = ScenarioBook::new(
+            &pricer,
+            vec![
+                position("BTC", 10_000, OptionStyle::Call, -1),
+                position("XYZ", 100, OptionStyle::Call, 1),
+                position("BTC", 10_000, OptionStyle::Put, 0),
+            ],
+            spots,
+        );
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.unpriced().len(), 1);
+        assert_eq!(book.underlyings(), vec!["BTC".to_string()]);
+
+        let grid = book.pnl_grid(&pricer, &[-10.0, 0.0, 10.0], &[-5.0, 5.0], 0);
+        assert_eq!(grid.len(), 3);
+        assert!(grid.iter().all(|row| row.len() == 2));
+        // Short call: loses when spot rallies, gains when vol falls.
+        assert!(grid[2][1] < grid[0][1]);
+        assert!(grid[1][0] > 0.0);
+    }
+}
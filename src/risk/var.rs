@@ -0,0 +1,287 @@
+//! Value-at-Risk and expected shortfall.
+//!
+//! Both methods draw joint horizon returns for the underlyings, apply each draw
+//! as a spot shock to a [`ScenarioBook`] rolled forward by the horizon, and read
+//! VaR and expected shortfall off the resulting P&L distribution:
+//!
+//! * Monte Carlo — returns come from the same walk models that drive the price
+//!   simulation ([`simulate_horizon_returns`]). Underlyings are simulated
+//!   independently.
+//! * Historical — returns are taken from stored daily closes over the dates
+//!   every underlying has a price for, so cross-asset co-movement is preserved.
+//!
+//! Volatility is held at the pricer's default in every scenario; only spot and
+//! time move. Losses are reported as positive cents.
+
+use super::{MarketShock, ScenarioBook};
+use crate::config::WalkTypeConfig;
+use crate::market_maker::OptionPricer;
+use crate::simulation::{SimulationError, simulate_horizon_returns};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// VaR and expected shortfall of one P&L distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarEstimate {
+    /// Value-at-Risk in cents: the loss not exceeded at the confidence level.
+    pub var_cents: f64,
+    /// Expected shortfall in cents: the mean loss in the tail beyond VaR.
+    pub expected_shortfall_cents: f64,
+    /// Number of scenarios the estimate is based on.
+    pub scenarios: usize,
+}
+
+/// Dynamics of one underlying for Monte Carlo simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingModel {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Annualised volatility.
+    pub volatility: f64,
+    /// Annualised drift.
+    pub drift: f64,
+}
+
+/// A VaR run for one account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarReport {
+    /// Account the positions belong to.
+    pub account: String,
+    /// Confidence level used.
+    pub confidence: f64,
+    /// Holding period in days.
+    pub horizon_days: u32,
+    /// Number of positions revalued.
+    pub positions: usize,
+    /// Current mark-to-model value of the book in cents.
+    pub base_value_cents: f64,
+    /// Monte Carlo estimate, if it could be computed.
+    pub monte_carlo: Option<VarEstimate>,
+    /// Historical-simulation estimate, if enough stored history exists.
+    pub historical: Option<VarEstimate>,
+    /// Instruments excluded from revaluation.
+    pub unpriced: Vec<String>,
+    /// Why an estimate is missing, if one is.
+    pub notes: Vec<String>,
+    /// When the report was computed, in milliseconds since the epoch.
+    pub computed_at_ms: u64,
+}
+
+/// VaR and expected shortfall of `pnls` (cents) at `confidence`.
+///
+/// The tail holds the worst `ceil(n · (1 - confidence))` scenarios (at least
+/// one); VaR is the best of them and expected shortfall their mean, both
+/// reported as losses. Returns `None` for an empty distribution.
+#[must_use]
+pub fn var_es(pnls: &[f64], confidence: f64) -> Option<VarEstimate> {
+    if pnls.is_empty() {
+        return None;
+    }
+    let mut sorted = pnls.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    // The epsilon keeps an exact tail (100 scenarios at 95%) from rounding up
+    // to one extra scenario through floating-point noise in `1 - confidence`.
+    let tail =
+        ((sorted.len() as f64 * (1.0 - confidence) - 1e-9).ceil() as usize).clamp(1, sorted.len());
+    let worst = &sorted[..tail];
+    Some(VarEstimate {
+        var_cents: -worst[tail - 1],
+        expected_shortfall_cents: -worst.iter().sum::<f64>() / tail as f64,
+        scenarios: sorted.len(),
+    })
+}
+
+/// P&L in cents of `book` under each joint return scenario.
+///
+/// `returns` maps an underlying to its simple return per scenario; the number
+/// of scenarios is the shortest series. An underlying missing from `returns`
+/// is held at its current spot.
+#[must_use]
+pub fn scenario_pnls(
+    book: &ScenarioBook,
+    pricer: &OptionPricer,
+    returns: &BTreeMap<String, Vec<f64>>,
+    horizon_days: u32,
+) -> Vec<f64> {
+    let count = returns.values().map(Vec::len).min().unwrap_or(0);
+    (0..count)
+        .map(|i| {
+            book.revalue(
+                pricer,
+                |underlying| MarketShock {
+                    spot_pct: returns.get(underlying).map_or(0.0, |r| r[i] * 100.0),
+                    vol_pts: 0.0,
+                },
+                horizon_days,
+            )
+            .total_cents
+        })
+        .collect()
+}
+
+/// Simulates `paths` horizon returns for each underlying.
+///
+/// # Errors
+/// Returns the first [`SimulationError`] raised by the walk generator.
+pub fn monte_carlo_returns(
+    models: &[UnderlyingModel],
+    walk_type: &WalkTypeConfig,
+    horizon_days: u32,
+    paths: usize,
+) -> Result<BTreeMap<String, Vec<f64>>, SimulationError> {
+    models
+        .iter()
+        .map(|model| {
+            simulate_horizon_returns(
+                model.volatility,
+                model.drift,
+                walk_type,
+                horizon_days,
+                paths,
+            )
+            .map(|returns| (model.underlying.clone(), returns))
+        })
+        .collect()
+}
+
+/// Overlapping `horizon_days`-observation returns from daily closes.
+///
+/// Only dates on which every underlying has a close are used, so scenario `i`
+/// of every series covers the same window. Returns an empty map when fewer
+/// than `horizon_days + 1` common dates exist.
+#[must_use]
+pub fn historical_returns(
+    closes: &BTreeMap<String, BTreeMap<NaiveDate, f64>>,
+    horizon_days: u32,
+) -> BTreeMap<String, Vec<f64>> {
+    let mut series = closes.values();
+    let Some(first) = series.next() else {
+        return BTreeMap::new();
+    };
+    let rest: Vec<_> = series.collect();
+    let common: Vec<NaiveDate> = first
+        .keys()
+        .filter(|date| rest.iter().all(|s| s.contains_key(*date)))
+        .copied()
+        .collect();
+
+    let horizon = horizon_days.max(1) as usize;
+    if common.len() <= horizon {
+        return BTreeMap::new();
+    }
+
+    closes
+        .iter()
+        .map(|(underlying, by_date)| {
+            let prices: Vec<f64> = common.iter().map(|d| by_date[d]).collect();
+            let returns = prices
+                .windows(horizon + 1)
+                .map(|w| w[horizon] / w[0] - 1.0)
+                .collect();
+            (underlying.clone(), returns)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::RiskPosition;
+    use optionstratlib::prelude::Positive;
+    use optionstratlib::{ExpirationDate, OptionStyle};
+
+    fn book(pricer: &OptionPricer, qty: i64) -> ScenarioBook {
+        ScenarioBook::new(
+            pricer,
+            vec![RiskPosition {
+                instrument: "BTC-30-10000-C".to_string(),
+                underlying: "BTC".to_string(),
+                expiration: ExpirationDate::Days(Positive::new(30.0).expect("positive")),
+                expiration_key: "30".to_string(),
+                strike: 10_000,
+                style: OptionStyle::Call,
+                quantity: qty,
+                multiplier: 1,
+            }],
+            |_| Some(10_000),
+        )
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).expect("valid date")
+    }
+
+    #[test]
+    fn test_var_es_reads_the_loss_tail() {
+        let pnls: Vec<f64> = (1..=100).map(|i| f64::from(i) - 51.0).collect();
+        let estimate = var_es(&pnls, 0.95).expect("non-empty");
+        // Worst five: -50..=-46.
+        assert_eq!(estimate.scenarios, 100);
+        assert_eq!(estimate.var_cents, 46.0);
+        assert_eq!(estimate.expected_shortfall_cents, 48.0);
+        assert!(estimate.expected_shortfall_cents >= estimate.var_cents);
+
+        assert!(var_es(&[], 0.99).is_none());
+        let single = var_es(&[-7.0], 0.99).expect("non-empty");
+        assert_eq!(single.var_cents, 7.0);
+    }
+
+    #[test]
+    fn test_historical_returns_align_on_common_dates() {
+        let mut closes = BTreeMap::new();
+        closes.insert(
+            "BTC".to_string(),
+            BTreeMap::from([(date(1), 100.0), (date(2), 110.0), (date(3), 99.0)]),
+        );
+        closes.insert(
+            "ETH".to_string(),
+            BTreeMap::from([(date(1), 10.0), (date(3), 12.0), (date(4), 6.0)]),
+        );
+
+        let returns = historical_returns(&closes, 1);
+        // Common dates are the 1st and 3rd only.
+        assert_eq!(returns["BTC"].len(), 1);
+        assert!((returns["BTC"][0] - (-0.01)).abs() < 1e-12);
+        assert!((returns["ETH"][0] - 0.2).abs() < 1e-12);
+
+        assert!(historical_returns(&closes, 2).is_empty());
+        assert!(historical_returns(&BTreeMap::new(), 1).is_empty());
+    }
+
+    #[test]
+    fn test_scenario_pnls_short_call_loses_on_rallies() {
+        let pricer = OptionPricer::default();
+        let short = book(&pricer, -1);
+        let returns = BTreeMap::from([("BTC".to_string(), vec![0.2, 0.0, -0.2])]);
+
+        let pnls = scenario_pnls(&short, &pricer, &returns, 1);
+        assert_eq!(pnls.len(), 3);
+        assert!(pnls[0] < 0.0);
+        assert!(pnls[2] > 0.0);
+
+        let estimate = var_es(&pnls, 0.9).expect("non-empty");
+        assert_eq!(estimate.var_cents, -pnls[0]);
+    }
+
+    #[test]
+    fn test_monte_carlo_var_is_bounded_by_premium_for_long_options() {
+        let pricer = OptionPricer::default();
+        let long = book(&pricer, 1);
+        let models = [UnderlyingModel {
+            underlying: "BTC".to_string(),
+            volatility: 0.8,
+            drift: 0.0,
+        }];
+
+        let returns = monte_carlo_returns(&models, &WalkTypeConfig::GeometricBrownian, 5, 300)
+            .expect("walks generate");
+        assert_eq!(returns["BTC"].len(), 300);
+
+        let estimate =
+            var_es(&scenario_pnls(&long, &pricer, &returns, 5), 0.99).expect("non-empty");
+        assert!(estimate.var_cents > 0.0);
+        assert!(estimate.expected_shortfall_cents >= estimate.var_cents);
+        assert!(estimate.expected_shortfall_cents <= long.base_value_cents() + 1e-6);
+    }
+}
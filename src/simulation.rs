@@ -1,6 +1,9 @@
 //! Price simulation service using OptionStratLib random walk models.
 
+use crate::clock;
 use crate::config::{AssetConfig, SimulationConfig, WalkTypeConfig};
+use crate::db::{PriceRecord, Repository};
+use crate::journal::{Journal, JournalEvent};
 use crate::market_maker::MarketMakerEngine;
 use optionstratlib::prelude::ExpirationDate;
 use optionstratlib::prelude::Positive;
@@ -14,7 +17,7 @@ use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::AddAssign;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tokio::sync::{broadcast, watch};
 use tokio::time::interval;
@@ -52,6 +55,9 @@ const JUMP_VOLATILITY: f64 = 0.05;
 /// (its oldest messages are dropped) rather than stalling the price producer.
 const PRICE_BROADCAST_CAPACITY: usize = 1024;
 
+/// Calendar days per year used to turn a daily step into an annualised `dt`.
+const DAYS_PER_YEAR: f64 = 365.0;
+
 /// Error returned when a price path cannot be generated.
 ///
 /// Each variant carries enough context for the caller to log a structured
@@ -113,6 +119,62 @@ fn generate_price_path(
     drift: f64,
     walk_type_config: &WalkTypeConfig,
     n_steps: usize,
+) -> Result<Vec<f64>, SimulationError> {
+    let dt = convert_time_frame(
+        Positive::ONE / Positive::THIRTY,
+        &TimeFrame::Minute,
+        &TimeFrame::Day,
+    );
+    generate_walk(
+        initial_price,
+        volatility,
+        drift,
+        walk_type_config,
+        n_steps,
+        dt,
+    )
+}
+
+/// Simulates `paths` independent walks of `horizon_days` daily steps and
+/// returns each path's terminal simple return (`S_T / S_0 - 1`).
+///
+/// `volatility` and `drift` are annualised; each step is one calendar day
+/// (`dt = 1/365`). Used by Monte Carlo Value-at-Risk to draw horizon returns
+/// from the same walk models that drive the live price simulation.
+///
+/// # Errors
+/// Returns a [`SimulationError`] for an invalid volatility or a walker failure.
+pub fn simulate_horizon_returns(
+    volatility: f64,
+    drift: f64,
+    walk_type_config: &WalkTypeConfig,
+    horizon_days: u32,
+    paths: usize,
+) -> Result<Vec<f64>, SimulationError> {
+    // Returns are scale-free, so every path starts from the same nominal price.
+    const INITIAL: f64 = 100.0;
+    // A compile-time ratio that is always a valid Positive.
+    let dt =
+        Positive::new(1.0 / DAYS_PER_YEAR).expect("DAYS_PER_YEAR is a valid positive constant");
+    let steps = horizon_days.max(1) as usize + 1;
+
+    (0..paths)
+        .map(|_| {
+            let path = generate_walk(INITIAL, volatility, drift, walk_type_config, steps, dt)?;
+            let terminal = path.last().copied().unwrap_or(INITIAL);
+            Ok(terminal / INITIAL - 1.0)
+        })
+        .collect()
+}
+
+/// Builds and runs one walk of `n_steps` values with time step `dt`.
+fn generate_walk(
+    initial_price: f64,
+    volatility: f64,
+    drift: f64,
+    walk_type_config: &WalkTypeConfig,
+    n_steps: usize,
+    dt: Positive,
 ) -> Result<Vec<f64>, SimulationError> {
     let initial = Positive::new(initial_price)
         .map_err(|_| SimulationError::InvalidInitialPrice(initial_price))?;
@@ -123,12 +185,12 @@ fn generate_price_path(
 
     let walk_type = match walk_type_config {
         WalkTypeConfig::GeometricBrownian => WalkType::GeometricBrownian {
-            dt: convert_time_frame(Positive::ONE / days, &TimeFrame::Minute, &TimeFrame::Day),
+            dt,
             drift: drift_dec,
             volatility: vol,
         },
         WalkTypeConfig::MeanReverting => WalkType::MeanReverting {
-            dt: convert_time_frame(Positive::ONE / days, &TimeFrame::Minute, &TimeFrame::Day),
+            dt,
             volatility: vol,
             // A compile-time literal that is always a valid Positive.
             speed: Positive::new(MEAN_REVERSION_SPEED)
@@ -136,7 +198,7 @@ fn generate_price_path(
             mean: initial,
         },
         WalkTypeConfig::JumpDiffusion => WalkType::JumpDiffusion {
-            dt: convert_time_frame(Positive::ONE / days, &TimeFrame::Minute, &TimeFrame::Day),
+            dt,
             drift: drift_dec,
             volatility: vol,
             // Compile-time literals that are always valid Positives.
@@ -240,6 +302,11 @@ pub struct PriceSimulator {
     price_tx: broadcast::Sender<PriceUpdate>,
     /// Random walk state per asset (thread-safe).
     simulations: RwLock<HashMap<String, AssetSimulation>>,
+    /// Write-ahead journal every price is appended to before the market
+    /// maker sees it, when attached.
+    journal: OnceLock<Arc<Journal>>,
+    /// Repository every applied price is recorded to, when set.
+    repository: Option<Arc<dyn Repository>>,
 }
 
 impl PriceSimulator {
@@ -325,9 +392,24 @@ impl PriceSimulator {
             config,
             price_tx,
             simulations: RwLock::new(simulations),
+            journal: OnceLock::new(),
+            repository: None,
         }
     }
 
+    /// Records every applied price into `repository`, with source
+    /// `simulator`.
+    #[must_use]
+    pub fn with_repository(mut self, repository: Arc<dyn Repository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Attaches the write-ahead journal. Subsequent calls are no-ops.
+    pub fn set_journal(&self, journal: Arc<Journal>) {
+        let _ = self.journal.set(journal);
+    }
+
     /// Returns a receiver for price updates.
     #[must_use]
     pub fn subscribe(&self) -> broadcast::Receiver<PriceUpdate> {
@@ -421,15 +503,21 @@ impl PriceSimulator {
         }
     }
 
-    /// Processes one simulation tick: advances every asset's walk and notifies
-    /// the market maker. Heavy walk regeneration is offloaded inside
-    /// [`Self::get_next_price`]; this never holds a lock across an `.await`.
+    /// Processes one simulation tick: advances every asset's walk, notifies
+    /// the market maker and records the applied prices. Heavy walk
+    /// regeneration is offloaded inside [`Self::get_next_price`]; this never
+    /// holds a lock across an `.await`.
     async fn process_tick(&self, market_maker: Option<&Arc<MarketMakerEngine>>) {
+        let mut applied = Vec::with_capacity(self.assets.len());
         for asset in &self.assets {
             let new_price = self.get_next_price(&asset.symbol, asset).await;
 
-            if let Some(mm) = market_maker {
-                mm.update_price(&asset.symbol, new_price);
+            let accepted = match market_maker {
+                Some(mm) => self.apply_price(mm, &asset.symbol, new_price).await,
+                None => true,
+            };
+            if accepted {
+                applied.push((asset.symbol.clone(), new_price));
             }
 
             debug!(
@@ -438,6 +526,61 @@ impl PriceSimulator {
                 new_price as f64 / 100.0
             );
         }
+        self.record_prices(applied).await;
+    }
+
+    /// Records the prices of one tick when a repository is set. Recording is
+    /// best-effort: a failure is logged and the prices stay applied.
+    async fn record_prices(&self, prices: Vec<(String, u64)>) {
+        let Some(repository) = &self.repository else {
+            return;
+        };
+        if prices.is_empty() {
+            return;
+        }
+        let timestamp = chrono::Utc::now();
+        let records: Vec<PriceRecord> = prices
+            .into_iter()
+            .map(|(symbol, price)| PriceRecord {
+                symbol,
+                price_cents: i64::try_from(price).unwrap_or(i64::MAX),
+                bid_cents: None,
+                ask_cents: None,
+                volume: None,
+                timestamp,
+                source: Some("simulator".to_string()),
+            })
+            .collect();
+        if let Err(e) = repository.insert_prices(&records).await {
+            warn!(error = %e, "failed to record simulated prices");
+        }
+    }
+
+    /// Hands a new price to the market maker, journaling it first when a
+    /// journal is attached. A price that cannot be journaled is dropped; the
+    /// next tick brings a fresh one. Returns whether the price was applied.
+    async fn apply_price(&self, mm: &MarketMakerEngine, symbol: &str, price_cents: u64) -> bool {
+        let Some(journal) = self.journal.get() else {
+            mm.update_price(symbol, price_cents);
+            return true;
+        };
+        let event = JournalEvent::Price {
+            symbol: symbol.to_string(),
+            price_cents,
+        };
+        let mut journal = journal.lock().await;
+        match journal.append(event, mm.order_ids().position()).await {
+            Ok(appended) => {
+                clock::at_sync(appended.timestamp_ms, || {
+                    mm.update_price(symbol, price_cents);
+                });
+                true
+            }
+            Err(e) => {
+                error!(symbol, error = %e, "failed to journal a price; dropping it");
+                false
+            }
+        }
     }
 
     /// Gets the next price from the random walk simulation.
@@ -672,6 +815,10 @@ mod tests {
             expirations: vec!["20251231".to_string()],
             num_strikes: 10,
             strike_spacing: 5.0,
+            tick_size: None,
+            contract_multiplier: 1,
+            min_lot_size: 1,
+            max_order_size: None,
         }
     }
 
@@ -707,6 +854,25 @@ mod tests {
         assert!(prices.iter().all(|&p| p > 0.0));
     }
 
+    #[test]
+    fn test_simulate_horizon_returns() {
+        let returns =
+            simulate_horizon_returns(0.5, 0.0, &WalkTypeConfig::GeometricBrownian, 10, 200)
+                .expect("walk generation succeeds");
+        assert_eq!(returns.len(), 200);
+        assert!(returns.iter().all(|r| r.is_finite() && *r > -1.0));
+        // Ten days at 50% annual vol: the dispersion is a few percent, not zero.
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let var = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        assert!(
+            var.sqrt() > 0.01 && var.sqrt() < 0.2,
+            "stdev {}",
+            var.sqrt()
+        );
+
+        assert!(simulate_horizon_returns(-0.5, 0.0, &WalkTypeConfig::JumpDiffusion, 1, 1).is_err());
+    }
+
     #[test]
     fn test_generate_price_path_invalid_initial_price() {
         // A non-positive initial price must yield an error, not a panic.
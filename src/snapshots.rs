@@ -0,0 +1,635 @@
+//! Durable orderbook snapshot storage.
+//!
+//! `POST /api/v1/admin/snapshot` keeps every snapshot in memory and, when a
+//! store is configured, also writes it to durable storage so it survives a
+//! restart: the `orderbook_snapshots` table when a database is connected,
+//! otherwise one gzip-compressed JSON file per snapshot in the `[snapshots]`
+//! directory. Listing, loading and restoring fall back to the store for
+//! snapshots no longer in memory, and the store is pruned by count and age
+//! after every write.
+//!
+//! Snapshots are versioned: version 1 records hold the order books only,
+//! version 2 records add the [`EngineState`] captured with them and version 3
+//! extends it with the cash ledger, collateral, liquidations and the market
+//! maker's inventory, hedges and P&L attribution.
+
+use crate::api::liquidation::Liquidation;
+use crate::config::SnapshotConfig;
+use crate::ledger::JournalEntry as LedgerEntry;
+use crate::market_maker::{MakerBookState, MakerOrderState};
+use crate::models::{
+    ExecutionInfo, LastTradeInfo, OrderInfo, OrderbookSnapshotInfo, PositionInfo, SnapshotSummary,
+    SnapshotTrigger,
+};
+use crate::ohlc::OhlcSeries;
+use crate::order_ids::OrderIdPosition;
+use crate::state::StoredSnapshot;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name suffix of a snapshot file.
+const FILE_SUFFIX: &str = ".json.gz";
+
+/// Snapshot format written by this build. Version 1 holds the order books
+/// only; version 2 adds the [`EngineState`] captured with them; version 3
+/// adds the ledger, collateral, liquidations and market maker book to it.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 3;
+
+/// Format version of records written before the version was recorded.
+fn legacy_format_version() -> u32 {
+    1
+}
+
+/// The application state a whole-state snapshot captures alongside the
+/// books, taken under a pause of every mutation so that orders, fills,
+/// positions and the maker's order tracking agree with the books.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineState {
+    /// Tracked orders, including terminal ones not yet cleaned up.
+    pub orders: Vec<OrderInfo>,
+    /// Operator positions.
+    pub positions: Vec<PositionInfo>,
+    /// Positions per account.
+    pub account_positions: Vec<(String, Vec<PositionInfo>)>,
+    /// Execution reports.
+    pub executions: Vec<ExecutionInfo>,
+    /// Last trade per symbol.
+    pub last_trades: Vec<LastTradeInfo>,
+    /// OHLC bar series.
+    pub ohlc: Vec<OhlcSeries>,
+    /// The market maker's resting orders and quote ladders.
+    pub market_maker: MakerOrderState,
+    /// The market maker's inventory, hedge ledger and P&L attribution.
+    #[serde(default)]
+    pub market_maker_book: MakerBookState,
+    /// Cash ledger entries, oldest first; balances are rebuilt from them.
+    #[serde(default)]
+    pub ledger: Vec<LedgerEntry>,
+    /// Pledged collateral in cents per account.
+    #[serde(default)]
+    pub collateral: Vec<(String, u64)>,
+    /// Latest liquidation per account.
+    #[serde(default)]
+    pub liquidations: Vec<Liquidation>,
+    /// Where the order ID source stood, so IDs continue from there.
+    #[serde(default)]
+    pub order_ids: Option<OrderIdPosition>,
+    /// Sequence of the last journal entry applied before the capture (0
+    /// without a journal). Replay resumes after it.
+    #[serde(default)]
+    pub journal_seq: u64,
+}
+
+/// Errors from the snapshot store.
+#[derive(Debug, Error)]
+pub enum SnapshotStoreError {
+    /// A database query failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// A snapshot file could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// A snapshot could not be encoded or decoded.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// A blocking file task panicked or was cancelled.
+    #[error("snapshot task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    /// A snapshot was written by a newer build in a format this one cannot
+    /// read.
+    #[error("unsupported snapshot format version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// Serialized form of a snapshot: its identity plus the per-orderbook entries
+/// and, from format version 2, the engine state.
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotRecord {
+    #[serde(default = "legacy_format_version")]
+    version: u32,
+    snapshot_id: String,
+    created_at_ms: u64,
+    infos: Vec<OrderbookSnapshotInfo>,
+    #[serde(default)]
+    engine: Option<EngineState>,
+    #[serde(default)]
+    orderbooks_failed: u64,
+    #[serde(default)]
+    trigger: SnapshotTrigger,
+}
+
+impl SnapshotRecord {
+    fn summary(&self) -> SnapshotSummary {
+        SnapshotSummary {
+            snapshot_id: self.snapshot_id.clone(),
+            orderbook_count: self.infos.len() as u64,
+            total_orders: self.infos.iter().map(|i| i.order_count).sum(),
+            success: self.orderbooks_failed == 0,
+            trigger: self.trigger,
+            created_at: self.created_at_ms,
+        }
+    }
+
+    fn into_snapshot(self) -> Result<StoredSnapshot, SnapshotStoreError> {
+        if self.version > SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotStoreError::UnsupportedVersion(self.version));
+        }
+        let mut snapshot = StoredSnapshot::new(self.created_at_ms, self.infos);
+        snapshot.orderbooks_failed = self.orderbooks_failed;
+        snapshot.trigger = self.trigger;
+        snapshot.engine = self.engine;
+        Ok(snapshot)
+    }
+}
+
+/// Retention policy of durable snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// Most snapshots kept.
+    pub max_count: usize,
+    /// Age in milliseconds past which snapshots are deleted; `None` keeps
+    /// them regardless of age.
+    pub max_age_ms: Option<u64>,
+}
+
+impl From<&SnapshotConfig> for Retention {
+    fn from(config: &SnapshotConfig) -> Self {
+        Self {
+            max_count: config.max_count.max(1),
+            max_age_ms: (config.max_age_seconds > 0)
+                .then(|| config.max_age_seconds.saturating_mul(1_000)),
+        }
+    }
+}
+
+impl Retention {
+    /// Ids to delete from `snapshots` (id, creation time) at `now_ms`: those
+    /// past the maximum age, then the oldest beyond the maximum count.
+    fn expired(&self, mut snapshots: Vec<(String, u64)>, now_ms: u64) -> Vec<String> {
+        snapshots.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        snapshots
+            .into_iter()
+            .enumerate()
+            .filter(|(rank, (_, created_at_ms))| {
+                *rank >= self.max_count
+                    || self
+                        .max_age_ms
+                        .is_some_and(|age| now_ms.saturating_sub(*created_at_ms) > age)
+            })
+            .map(|(_, (id, _))| id)
+            .collect()
+    }
+}
+
+/// Where durable snapshots are kept.
+#[derive(Clone)]
+pub enum SnapshotStore {
+    /// The `orderbook_snapshots` table.
+    Postgres(PgPool),
+    /// One `<created_at_ms>-<snapshot_id>.json.gz` file per snapshot.
+    Directory(PathBuf),
+}
+
+impl SnapshotStore {
+    /// Writes `snapshot` under `snapshot_id`.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotStoreError`] when the snapshot cannot be encoded or
+    /// written.
+    pub async fn save(
+        &self,
+        snapshot_id: &str,
+        snapshot: &StoredSnapshot,
+    ) -> Result<(), SnapshotStoreError> {
+        let record = SnapshotRecord {
+            version: SNAPSHOT_FORMAT_VERSION,
+            snapshot_id: snapshot_id.to_string(),
+            created_at_ms: snapshot.created_at_ms,
+            infos: snapshot.infos.clone(),
+            engine: snapshot.engine.clone(),
+            orderbooks_failed: snapshot.orderbooks_failed,
+            trigger: snapshot.trigger,
+        };
+        match self {
+            Self::Postgres(pool) => {
+                let summary = record.summary();
+                sqlx::query(
+                    r#"
+                    INSERT INTO orderbook_snapshots
+                        (snapshot_id, created_at_ms, orderbook_count, total_orders,
+                         orderbooks_failed, trigger, format_version, data, engine_state)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    ON CONFLICT (snapshot_id) DO UPDATE SET
+                        created_at_ms = EXCLUDED.created_at_ms,
+                        orderbook_count = EXCLUDED.orderbook_count,
+                        total_orders = EXCLUDED.total_orders,
+                        orderbooks_failed = EXCLUDED.orderbooks_failed,
+                        trigger = EXCLUDED.trigger,
+                        format_version = EXCLUDED.format_version,
+                        data = EXCLUDED.data,
+                        engine_state = EXCLUDED.engine_state
+                    "#,
+                )
+                .bind(&record.snapshot_id)
+                .bind(to_i64(record.created_at_ms))
+                .bind(to_i64(summary.orderbook_count))
+                .bind(to_i64(summary.total_orders))
+                .bind(to_i64(record.orderbooks_failed))
+                .bind(record.trigger.as_str())
+                .bind(record.version as i32)
+                .bind(serde_json::to_string(&record.infos)?)
+                .bind(
+                    record
+                        .engine
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                )
+                .execute(pool)
+                .await?;
+                Ok(())
+            }
+            Self::Directory(dir) => {
+                let dir = dir.clone();
+                tokio::task::spawn_blocking(move || write_file(&dir, &record)).await?
+            }
+        }
+    }
+
+    /// Summaries of every stored snapshot, newest first.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotStoreError`] when the store cannot be read.
+    pub async fn list(&self) -> Result<Vec<SnapshotSummary>, SnapshotStoreError> {
+        let mut summaries = match self {
+            Self::Postgres(pool) => {
+                let rows: Vec<SummaryRow> = sqlx::query_as(
+                    r#"
+                    SELECT snapshot_id, created_at_ms, orderbook_count, total_orders,
+                           orderbooks_failed, trigger
+                    FROM orderbook_snapshots
+                    "#,
+                )
+                .fetch_all(pool)
+                .await?;
+                rows.into_iter().map(SnapshotSummary::from).collect()
+            }
+            Self::Directory(dir) => {
+                let dir = dir.clone();
+                tokio::task::spawn_blocking(move || -> Result<_, SnapshotStoreError> {
+                    let mut summaries = Vec::new();
+                    for (_, _, path) in snapshot_files(&dir)? {
+                        match read_file(&path) {
+                            Ok(record) => summaries.push(record.summary()),
+                            Err(e) => tracing::warn!(
+                                path = %path.display(),
+                                error = %e,
+                                "skipping unreadable snapshot file"
+                            ),
+                        }
+                    }
+                    Ok(summaries)
+                })
+                .await??
+            }
+        };
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        Ok(summaries)
+    }
+
+    /// Loads the snapshot `snapshot_id`, or `None` when it is not stored.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotStoreError`] when the store cannot be read or the
+    /// snapshot does not decode.
+    pub async fn load(
+        &self,
+        snapshot_id: &str,
+    ) -> Result<Option<StoredSnapshot>, SnapshotStoreError> {
+        match self {
+            Self::Postgres(pool) => {
+                let row: Option<SnapshotRow> = sqlx::query_as(
+                    r#"
+                    SELECT created_at_ms, orderbooks_failed, trigger, format_version, data,
+                           engine_state
+                    FROM orderbook_snapshots
+                    WHERE snapshot_id = $1
+                    "#,
+                )
+                .bind(snapshot_id)
+                .fetch_optional(pool)
+                .await?;
+                row.map(|row| {
+                    SnapshotRecord {
+                        version: row.format_version.max(0) as u32,
+                        snapshot_id: snapshot_id.to_string(),
+                        created_at_ms: row.created_at_ms.max(0) as u64,
+                        infos: serde_json::from_str(&row.data)?,
+                        engine: row
+                            .engine_state
+                            .as_deref()
+                            .map(serde_json::from_str)
+                            .transpose()?,
+                        orderbooks_failed: row.orderbooks_failed.max(0) as u64,
+                        trigger: SnapshotTrigger::parse(&row.trigger),
+                    }
+                    .into_snapshot()
+                })
+                .transpose()
+            }
+            Self::Directory(dir) => {
+                let dir = dir.clone();
+                let snapshot_id = snapshot_id.to_string();
+                tokio::task::spawn_blocking(move || {
+                    let Some((_, _, path)) = snapshot_files(&dir)?
+                        .into_iter()
+                        .find(|(id, _, _)| *id == snapshot_id)
+                    else {
+                        return Ok(None);
+                    };
+                    read_file(&path)?.into_snapshot().map(Some)
+                })
+                .await?
+            }
+        }
+    }
+
+    /// The newest stored snapshot and its id, or `None` when the store is
+    /// empty.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotStoreError`] when the store cannot be read.
+    pub async fn latest(&self) -> Result<Option<(String, StoredSnapshot)>, SnapshotStoreError> {
+        let Some(newest) = self.list().await?.into_iter().next() else {
+            return Ok(None);
+        };
+        Ok(self
+            .load(&newest.snapshot_id)
+            .await?
+            .map(|snapshot| (newest.snapshot_id, snapshot)))
+    }
+
+    /// Deletes the snapshots `retention` no longer keeps at `now_ms` and
+    /// returns how many were deleted.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotStoreError`] when the store cannot be read or a
+    /// snapshot cannot be deleted.
+    pub async fn prune(
+        &self,
+        retention: Retention,
+        now_ms: u64,
+    ) -> Result<usize, SnapshotStoreError> {
+        match self {
+            Self::Postgres(pool) => {
+                let rows: Vec<(String, i64)> =
+                    sqlx::query_as("SELECT snapshot_id, created_at_ms FROM orderbook_snapshots")
+                        .fetch_all(pool)
+                        .await?;
+                let expired = retention.expired(
+                    rows.into_iter()
+                        .map(|(id, created_at)| (id, created_at.max(0) as u64))
+                        .collect(),
+                    now_ms,
+                );
+                if !expired.is_empty() {
+                    sqlx::query("DELETE FROM orderbook_snapshots WHERE snapshot_id = ANY($1)")
+                        .bind(&expired)
+                        .execute(pool)
+                        .await?;
+                }
+                Ok(expired.len())
+            }
+            Self::Directory(dir) => {
+                let dir = dir.clone();
+                tokio::task::spawn_blocking(move || {
+                    let files = snapshot_files(&dir)?;
+                    let expired = retention.expired(
+                        files
+                            .iter()
+                            .map(|(id, created_at_ms, _)| (id.clone(), *created_at_ms))
+                            .collect(),
+                        now_ms,
+                    );
+                    for (_, _, path) in files.iter().filter(|(id, _, _)| expired.contains(id)) {
+                        std::fs::remove_file(path)?;
+                    }
+                    Ok(expired.len())
+                })
+                .await?
+            }
+        }
+    }
+}
+
+/// One row of the snapshot listing query.
+#[derive(sqlx::FromRow)]
+struct SummaryRow {
+    snapshot_id: String,
+    created_at_ms: i64,
+    orderbook_count: i64,
+    total_orders: i64,
+    orderbooks_failed: i64,
+    trigger: String,
+}
+
+impl From<SummaryRow> for SnapshotSummary {
+    fn from(row: SummaryRow) -> Self {
+        Self {
+            snapshot_id: row.snapshot_id,
+            orderbook_count: row.orderbook_count.max(0) as u64,
+            total_orders: row.total_orders.max(0) as u64,
+            success: row.orderbooks_failed == 0,
+            trigger: SnapshotTrigger::parse(&row.trigger),
+            created_at: row.created_at_ms.max(0) as u64,
+        }
+    }
+}
+
+/// One row of the snapshot load query.
+#[derive(sqlx::FromRow)]
+struct SnapshotRow {
+    created_at_ms: i64,
+    orderbooks_failed: i64,
+    trigger: String,
+    format_version: i32,
+    data: String,
+    engine_state: Option<String>,
+}
+
+/// Saturating conversion for the `BIGINT` columns.
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+/// Writes `record` to `dir`, creating the directory if needed. The file is
+/// written under a temporary name and renamed so a crash never leaves a
+/// truncated snapshot behind.
+fn write_file(dir: &Path, record: &SnapshotRecord) -> Result<(), SnapshotStoreError> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!(
+        "{}-{}{FILE_SUFFIX}",
+        record.created_at_ms, record.snapshot_id
+    );
+    let tmp = dir.join(format!(".{name}.tmp"));
+    let mut encoder = GzEncoder::new(std::fs::File::create(&tmp)?, Compression::default());
+    encoder.write_all(&serde_json::to_vec(record)?)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&tmp, dir.join(name))?;
+    Ok(())
+}
+
+/// Reads and decodes one snapshot file.
+fn read_file(path: &Path) -> Result<SnapshotRecord, SnapshotStoreError> {
+    let mut json = Vec::new();
+    GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// The snapshot files in `dir` as (id, creation time, path). A missing
+/// directory holds no snapshots; files not named like a snapshot are ignored.
+fn snapshot_files(dir: &Path) -> Result<Vec<(String, u64, PathBuf)>, SnapshotStoreError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let Some(stem) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(FILE_SUFFIX))
+        else {
+            continue;
+        };
+        let Some((created_at_ms, id)) = stem
+            .split_once('-')
+            .and_then(|(ts, id)| Some((ts.parse::<u64>().ok()?, id.to_string())))
+        else {
+            continue;
+        };
+        files.push((id, created_at_ms, path));
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> (SnapshotStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", uuid::Uuid::new_v4()));
+        (SnapshotStore::Directory(dir.clone()), dir)
+    }
+
+    fn snapshot(created_at_ms: u64, order_count: u64) -> StoredSnapshot {
+        StoredSnapshot::new(
+            created_at_ms,
+            vec![OrderbookSnapshotInfo {
+                snapshot_id: String::new(),
+                underlying: "BTC".to_string(),
+                expiration: "20251231".to_string(),
+                strike: 5_000_000,
+                style: "call".to_string(),
+                order_count,
+                bid_levels: 1,
+                ask_levels: 0,
+                data: "{}".to_string(),
+                created_at: created_at_ms,
+            }],
+        )
+    }
+
+    #[test]
+    fn test_retention_by_count_and_age() {
+        let snapshots = vec![
+            ("a".to_string(), 1_000),
+            ("b".to_string(), 2_000),
+            ("c".to_string(), 3_000),
+        ];
+        let by_count = Retention {
+            max_count: 2,
+            max_age_ms: None,
+        };
+        assert_eq!(by_count.expired(snapshots.clone(), 3_000), vec!["a"]);
+
+        let by_age = Retention {
+            max_count: 10,
+            max_age_ms: Some(1_500),
+        };
+        assert_eq!(by_age.expired(snapshots, 3_000), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_directory_store_round_trip() {
+        let (store, dir) = temp_store();
+        assert!(store.list().await.expect("missing dir is empty").is_empty());
+        assert!(store.latest().await.expect("readable").is_none());
+
+        store.save("old", &snapshot(1_000, 3)).await.expect("saved");
+        store.save("new", &snapshot(2_000, 5)).await.expect("saved");
+        // Stray files are ignored.
+        std::fs::write(dir.join("notes.txt"), "x").expect("written");
+
+        let list = store.list().await.expect("listed");
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].snapshot_id, "new");
+        assert_eq!(list[0].total_orders, 5);
+
+        let loaded = store.load("old").await.expect("read").expect("stored");
+        assert_eq!(loaded.created_at_ms, 1_000);
+        assert_eq!(loaded.infos[0].order_count, 3);
+        assert!(store.load("missing").await.expect("read").is_none());
+
+        let (id, latest) = store.latest().await.expect("read").expect("non-empty");
+        assert_eq!(id, "new");
+        assert_eq!(latest.created_at_ms, 2_000);
+
+        let retention = Retention {
+            max_count: 1,
+            max_age_ms: None,
+        };
+        assert_eq!(store.prune(retention, 2_000).await.expect("pruned"), 1);
+        assert!(store.load("old").await.expect("read").is_none());
+        assert_eq!(store.list().await.expect("listed").len(), 1);
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_format_versions() {
+        let (store, dir) = temp_store();
+        let mut whole = snapshot(1_000, 2);
+        whole.engine = Some(EngineState::default());
+        store.save("whole", &whole).await.expect("saved");
+        let loaded = store.load("whole").await.expect("read").expect("stored");
+        assert!(loaded.engine.is_some(), "the engine state round-trips");
+
+        // A record from before versioning reads as a books-only snapshot.
+        let legacy = r#"{"snapshot_id":"legacy","created_at_ms":500,"infos":[]}"#;
+        let record: SnapshotRecord = serde_json::from_str(legacy).expect("decodes");
+        assert_eq!(record.version, 1);
+        assert!(record.into_snapshot().expect("readable").engine.is_none());
+
+        // A newer format is refused rather than half-read.
+        let newer = format!(
+            r#"{{"version":{},"snapshot_id":"newer","created_at_ms":500,"infos":[]}}"#,
+            SNAPSHOT_FORMAT_VERSION + 1
+        );
+        let record: SnapshotRecord = serde_json::from_str(&newer).expect("decodes");
+        assert!(matches!(
+            record.into_snapshot(),
+            Err(SnapshotStoreError::UnsupportedVersion(_))
+        ));
+
+        std::fs::remove_dir_all(dir).expect("cleaned up");
+    }
+}
@@ -1,16 +1,32 @@
 //! Application state management.
 
+use crate::api::liquidation::Liquidation;
 use crate::api::websocket::OrderbookSubscriptionManager;
 use crate::auth::JwtAuth;
+use crate::clock;
 use crate::config::{AssetConfig, Config};
-use crate::db::DatabasePool;
-use crate::market_maker::MarketMakerEngine;
-use crate::models::{ExecutionInfo, LastTradeInfo, OrderInfo, OrderbookSnapshotInfo, PositionInfo};
+use crate::contract::ContractSpec;
+use crate::db::{DatabasePool, InMemoryRepository, Repository};
+use crate::journal::{Journal, JournalError, JournalEvent};
+use crate::ledger::Ledger;
+use crate::market_maker::{HedgeParams, MarketMakerEngine, RequoteParams, build_strategy};
+use crate::models::{
+    ExecutionInfo, LastTradeInfo, OrderbookSnapshotInfo, PositionInfo, SnapshotTrigger,
+};
 use crate::ohlc::OhlcAggregator;
+use crate::ohlc_store::OhlcStore;
+use crate::order_ids::OrderIdPosition;
+use crate::order_store::OrderStore;
+use crate::quote_history::QuoteRecorder;
+use crate::risk::{MarginRequirement, VarReport};
 use crate::simulation::PriceSimulator;
+use crate::snapshots::{EngineState, Retention, SnapshotStore};
+use crate::surface_history::SurfaceStore;
+use crate::ticks::TickTable;
 use dashmap::DashMap;
 use option_chain_orderbook::orderbook::UnderlyingOrderBookManager;
 use optionstratlib::ExpirationDate;
+use std::future::Future;
 use std::sync::Arc;
 use tracing::{info, warn};
 
@@ -30,6 +46,13 @@ pub struct StoredSnapshot {
     pub created_at_ms: u64,
     /// Per-orderbook snapshot entries.
     pub infos: Vec<OrderbookSnapshotInfo>,
+    /// Orderbooks left out because their state failed to serialize.
+    pub orderbooks_failed: u64,
+    /// What took the snapshot.
+    pub trigger: SnapshotTrigger,
+    /// Orders, positions, executions, trades, OHLC bars and maker order
+    /// tracking captured with the books; `None` for a books-only snapshot.
+    pub engine: Option<EngineState>,
     /// Monotonic creation sequence, used to break `created_at_ms` ties during
     /// eviction so that within the same millisecond the earliest-created
     /// snapshot is evicted first — never the one just inserted.
@@ -44,6 +67,9 @@ impl StoredSnapshot {
         Self {
             created_at_ms,
             infos,
+            orderbooks_failed: 0,
+            trigger: SnapshotTrigger::Manual,
+            engine: None,
             seq: SNAPSHOT_SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
         }
     }
@@ -66,6 +92,9 @@ pub struct AppState {
     pub manager: Arc<UnderlyingOrderBookManager>,
     /// Optional database pool.
     pub db: Option<DatabasePool>,
+    /// Prices and trading state storage: Postgres when a database is
+    /// connected, otherwise process memory.
+    pub repository: Arc<dyn Repository>,
     /// Market maker engine.
     pub market_maker: Arc<MarketMakerEngine>,
     /// Price simulator.
@@ -75,13 +104,22 @@ pub struct AppState {
     /// Storage for last trade information per symbol.
     pub last_trades: Arc<DashMap<String, LastTradeInfo>>,
     /// Storage for order information by order ID.
-    pub orders: Arc<DashMap<String, OrderInfo>>,
+    pub orders: Arc<OrderStore>,
     /// Storage for position information by symbol.
     pub positions: Arc<DashMap<String, PositionInfo>>,
+    /// Per-account positions: account id → symbol → position. Unlike
+    /// `positions` (the operator's taker-side view), both sides of every fill
+    /// between tracked orders are booked here, each to its own account.
+    pub account_positions: Arc<DashMap<String, DashMap<String, PositionInfo>>>,
     /// Orderbook subscription manager for WebSocket real-time updates.
     pub orderbook_subscriptions: Arc<OrderbookSubscriptionManager>,
     /// OHLC candlestick data aggregator.
     pub ohlc_aggregator: Arc<OhlcAggregator>,
+    /// Durable OHLC history, present when a database is connected.
+    pub ohlc_store: Option<Arc<OhlcStore>>,
+    /// Top-of-book recorder, present when a database is connected and
+    /// `[quote_history] enabled`.
+    pub quote_recorder: Option<Arc<QuoteRecorder>>,
     /// JWT authentication core (signing/verification keys + rate limiter).
     pub auth: Arc<JwtAuth>,
     /// Operator bootstrap secret for the token-issuance endpoint. `None` disables
@@ -96,16 +134,45 @@ pub struct AppState {
     pub executions: Arc<DashMap<String, ExecutionInfo>>,
     /// Storage for orderbook snapshots by snapshot ID.
     pub snapshots: Arc<DashMap<String, StoredSnapshot>>,
+    /// Durable snapshot storage: the database when connected, else the
+    /// `[snapshots]` directory. `None` keeps snapshots in memory only.
+    pub snapshot_store: Option<SnapshotStore>,
     /// Cached volatility surfaces by underlying (issue #125): the IV sweep is
     /// a ~1000-candidate Black-Scholes grid search per leg, so concurrent and
     /// rapid repeat requests reuse the last computed surface while the spot
     /// is unchanged and the entry is fresh (see the handler's TTL).
     pub surface_cache: Arc<DashMap<String, CachedSurface>>,
+    /// Volatility surface history, present when a database is connected.
+    pub surface_store: Option<Arc<SurfaceStore>>,
+    /// Latest Value-at-Risk report per account, refreshed on the
+    /// `[risk.var]` schedule and on demand by `GET /api/v1/risk/var`.
+    pub var_reports: Arc<DashMap<String, VarReport>>,
+    /// Pledged (non-cash) collateral in cents per account, set by the admin
+    /// collateral endpoint. Accounts without an entry hold
+    /// `[risk.margin] default_collateral`; cash in the ledger counts on top.
+    pub collateral: Arc<DashMap<String, u64>>,
+    /// Latest margin requirement per account, recomputed on every fill and
+    /// price update of the account's underlyings.
+    pub margin_reports: Arc<DashMap<String, MarginRequirement>>,
+    /// Double-entry cash ledger: deposits, premium, fees and settlements.
+    pub ledger: Arc<Ledger>,
+    /// Latest liquidation per account; an account whose status is
+    /// `liquidating` may only place risk-reducing orders.
+    pub liquidations: Arc<DashMap<String, Liquidation>>,
     /// Graceful-shutdown signal (issue #118): set once by `main.rs` after the
     /// watch channel exists; live WebSocket connections subscribe so they
     /// close promptly on shutdown instead of keeping `serve()` alive until an
     /// idle client disconnects.
     shutdown_rx: std::sync::OnceLock<tokio::sync::watch::Receiver<bool>>,
+    /// Held for reading by every mutating request and background sweep;
+    /// [`Self::pause_mutations`] takes it for writing so a whole-state
+    /// snapshot or restore sees every map at rest.
+    mutation_gate: Arc<tokio::sync::RwLock<()>>,
+    /// Per-account order-entry locks, see [`Self::account_guard`]. Idle ones
+    /// are dropped by [`Self::cleanup_old_orders`].
+    account_gates: Arc<DashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Write-ahead journal, attached once by `main.rs` after startup replay.
+    journal: std::sync::OnceLock<Arc<Journal>>,
 }
 
 impl AppState {
@@ -118,21 +185,35 @@ impl AppState {
         Self {
             manager,
             db: None,
+            repository: Arc::new(InMemoryRepository::new()),
             market_maker,
             price_simulator: None,
             config: None,
             last_trades: Arc::new(DashMap::new()),
-            orders: Arc::new(DashMap::new()),
+            orders: Arc::new(OrderStore::new()),
             positions: Arc::new(DashMap::new()),
+            account_positions: Arc::new(DashMap::new()),
             orderbook_subscriptions: Arc::new(OrderbookSubscriptionManager::new()),
             ohlc_aggregator: Arc::new(OhlcAggregator::new()),
+            ohlc_store: None,
+            quote_recorder: None,
             auth: Arc::new(JwtAuth::dev()),
             bootstrap_secret: None,
             trust_proxy: false,
             executions: Arc::new(DashMap::new()),
             snapshots: Arc::new(DashMap::new()),
+            snapshot_store: None,
             surface_cache: Arc::new(DashMap::new()),
+            surface_store: None,
+            var_reports: Arc::new(DashMap::new()),
+            collateral: Arc::new(DashMap::new()),
+            margin_reports: Arc::new(DashMap::new()),
+            ledger: Arc::new(Ledger::new()),
+            liquidations: Arc::new(DashMap::new()),
             shutdown_rx: std::sync::OnceLock::new(),
+            mutation_gate: Arc::new(tokio::sync::RwLock::new(())),
+            account_gates: Arc::new(DashMap::new()),
+            journal: std::sync::OnceLock::new(),
         }
     }
 
@@ -145,30 +226,60 @@ impl AppState {
             Some(db.clone()),
         ));
 
+        let pg = db.postgres().cloned();
+        let ohlc_store = Arc::new(OhlcStore::new(db.clone()));
+
         Self {
             manager,
+            repository: db.repository(),
             db: Some(db),
             market_maker,
             price_simulator: None,
             config: None,
             last_trades: Arc::new(DashMap::new()),
-            orders: Arc::new(DashMap::new()),
+            orders: Arc::new(OrderStore::new()),
             positions: Arc::new(DashMap::new()),
+            account_positions: Arc::new(DashMap::new()),
             orderbook_subscriptions: Arc::new(OrderbookSubscriptionManager::new()),
             ohlc_aggregator: Arc::new(OhlcAggregator::new()),
+            ohlc_store: Some(ohlc_store),
+            quote_recorder: None,
             auth: Arc::new(JwtAuth::dev()),
             bootstrap_secret: None,
             trust_proxy: false,
             executions: Arc::new(DashMap::new()),
             snapshots: Arc::new(DashMap::new()),
+            snapshot_store: pg.clone().map(SnapshotStore::Postgres),
             surface_cache: Arc::new(DashMap::new()),
+            surface_store: pg.map(|pool| Arc::new(SurfaceStore::new(pool))),
+            var_reports: Arc::new(DashMap::new()),
+            collateral: Arc::new(DashMap::new()),
+            margin_reports: Arc::new(DashMap::new()),
+            ledger: Arc::new(Ledger::new()),
+            liquidations: Arc::new(DashMap::new()),
             shutdown_rx: std::sync::OnceLock::new(),
+            mutation_gate: Arc::new(tokio::sync::RwLock::new(())),
+            account_gates: Arc::new(DashMap::new()),
+            journal: std::sync::OnceLock::new(),
         }
     }
 
     /// Creates a new application state from configuration.
     #[must_use]
     pub fn from_config(config: Config, db: Option<DatabasePool>) -> Self {
+        Self::from_config_with_order_ids(config, db, OrderIdPosition::fresh())
+    }
+
+    /// Creates a new application state from configuration, handing out
+    /// order IDs from `order_ids` on. Replaying a journal from its start
+    /// passes the position the recorded run started from, so the quotes
+    /// placed at startup get the IDs they had then.
+    #[must_use]
+    pub fn from_config_with_order_ids(
+        config: Config,
+        db: Option<DatabasePool>,
+        order_ids: OrderIdPosition,
+    ) -> Self {
         let manager = Arc::new(UnderlyingOrderBookManager::new());
 
         // Initialize order books from config
@@ -177,6 +288,28 @@ impl AppState {
         }
 
         let market_maker = Arc::new(MarketMakerEngine::new(Arc::clone(&manager), db.clone()));
+        market_maker.order_ids().seek(order_ids);
+        market_maker.set_hedge_params(HedgeParams::from(&config.market_maker.hedging));
+        market_maker.set_requote_params(RequoteParams::from(&config.market_maker.requote));
+        let strategies = &config.market_maker.strategy;
+        market_maker.set_default_strategy(build_strategy(strategies.default, &strategies.layered));
+        for underlying in strategies
+            .underlyings
+            .keys()
+            .chain(strategies.ladders.keys())
+        {
+            let strategy = build_strategy(
+                strategies.kind_for(underlying),
+                strategies.ladder_for(underlying),
+            );
+            market_maker.set_strategy(underlying, Some(strategy));
+        }
+        for asset in &config.assets {
+            if let Some(tick_size) = &asset.tick_size {
+                market_maker.set_tick_table(&asset.symbol, TickTable::from(tick_size));
+            }
+            market_maker.set_contract_spec(&asset.symbol, ContractSpec::from(asset));
+        }
 
         // Set initial prices in market maker, rounding dollars→cents through the
         // single canonical helper. A non-finite or out-of-range price is logged
@@ -193,30 +326,64 @@ impl AppState {
             }
         }
 
+        let repository: Arc<dyn Repository> = match &db {
+            Some(db) => db.repository(),
+            None => Arc::new(InMemoryRepository::new()),
+        };
+        let ohlc_store = db.clone().map(|db| Arc::new(OhlcStore::new(db)));
+        // Snapshots, quote and surface history are Postgres only
+        let pg = db.as_ref().and_then(DatabasePool::postgres).cloned();
+        let surface_store = pg.clone().map(|pool| Arc::new(SurfaceStore::new(pool)));
+        let quote_recorder = pg
+            .clone()
+            .filter(|_| config.quote_history.enabled)
+            .map(|pool| Arc::new(QuoteRecorder::new(pool)));
+        let snapshot_store = match (pg, &config.snapshots.directory) {
+            (Some(pool), _) => Some(SnapshotStore::Postgres(pool)),
+            (None, Some(dir)) => Some(SnapshotStore::Directory(dir.into())),
+            (None, None) => None,
+        };
+
         // Create price simulator
-        let price_simulator = Arc::new(PriceSimulator::new(
-            config.assets.clone(),
-            config.simulation.clone(),
-        ));
+        let mut price_simulator =
+            PriceSimulator::new(config.assets.clone(), config.simulation.clone());
+        if config.simulation.record_prices && db.is_some() {
+            price_simulator = price_simulator.with_repository(Arc::clone(&repository));
+        }
+        let price_simulator = Arc::new(price_simulator);
 
         Self {
             manager,
             db,
+            repository,
             market_maker,
             price_simulator: Some(price_simulator),
             config: Some(config),
             last_trades: Arc::new(DashMap::new()),
-            orders: Arc::new(DashMap::new()),
+            orders: Arc::new(OrderStore::new()),
             positions: Arc::new(DashMap::new()),
+            account_positions: Arc::new(DashMap::new()),
             orderbook_subscriptions: Arc::new(OrderbookSubscriptionManager::new()),
             ohlc_aggregator: Arc::new(OhlcAggregator::new()),
+            ohlc_store,
+            quote_recorder,
             auth: Arc::new(JwtAuth::dev()),
             bootstrap_secret: None,
             trust_proxy: false,
             executions: Arc::new(DashMap::new()),
             snapshots: Arc::new(DashMap::new()),
+            snapshot_store,
             surface_cache: Arc::new(DashMap::new()),
+            surface_store,
+            var_reports: Arc::new(DashMap::new()),
+            collateral: Arc::new(DashMap::new()),
+            margin_reports: Arc::new(DashMap::new()),
+            ledger: Arc::new(Ledger::new()),
+            liquidations: Arc::new(DashMap::new()),
             shutdown_rx: std::sync::OnceLock::new(),
+            mutation_gate: Arc::new(tokio::sync::RwLock::new(())),
+            account_gates: Arc::new(DashMap::new()),
+            journal: std::sync::OnceLock::new(),
         }
     }
 
@@ -277,15 +444,21 @@ impl AppState {
         Some(ExpirationDate::DateTime(utc_datetime))
     }
 
-    /// Removes filled or canceled orders older than the specified age.
+    /// Removes filled or canceled orders older than the specified age, and
+    /// the account guards nobody holds or waits for.
     ///
     /// # Arguments
+    /// * `now` - Time the age is measured from.
     /// * `max_age_secs` - Maximum age in seconds for retention.
     ///
     /// # Returns
     /// Number of orders removed.
-    pub fn cleanup_old_orders(&self, max_age_secs: u64) -> usize {
-        let threshold = chrono::Utc::now() - chrono::Duration::seconds(max_age_secs as i64);
+    pub fn cleanup_old_orders(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        max_age_secs: u64,
+    ) -> usize {
+        let threshold = now - chrono::Duration::seconds(max_age_secs as i64);
 
         // Identify keys to remove first to avoid deadlock or long holding of locks
         // Dashmap creates deadlocks if you hold a read reference and try to remove.
@@ -324,6 +497,12 @@ impl AppState {
             }
         }
 
+        // A gate only the map refers to is idle, and `account_guard` clones it
+        // under the same shard lock, so dropping it cannot split an account's
+        // lock in two.
+        self.account_gates
+            .retain(|_, gate| Arc::strong_count(gate) > 1);
+
         count
     }
 
@@ -372,6 +551,127 @@ impl AppState {
         }
     }
 
+    /// Retention policy of durable snapshots, from `[snapshots]`.
+    #[must_use]
+    pub fn snapshot_retention(&self) -> Retention {
+        self.config
+            .as_ref()
+            .map(|config| Retention::from(&config.snapshots))
+            .unwrap_or_else(|| Retention::from(&crate::config::SnapshotConfig::default()))
+    }
+
+    /// Admits one mutation: waits out a snapshot or restore in progress and
+    /// holds the next one off until the guard drops.
+    pub async fn mutation_guard(&self) -> tokio::sync::RwLockReadGuard<'_, ()> {
+        self.mutation_gate.read().await
+    }
+
+    /// Serializes order entry for `account`: held from the pre-trade margin
+    /// check until the order is tracked, so two concurrent orders of one
+    /// account cannot both pass the check on the same exposure.
+    pub async fn account_guard(&self, account: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let gate = Arc::clone(
+            self.account_gates
+                .entry(account.to_string())
+                .or_default()
+                .value(),
+        );
+        gate.lock_owned().await
+    }
+
+    /// Waits for in-flight mutations to finish and holds new ones off until
+    /// the guard drops. Price-driven requotes are paused separately with
+    /// [`MarketMakerEngine::pause_quoting`].
+    pub async fn pause_mutations(&self) -> tokio::sync::RwLockWriteGuard<'_, ()> {
+        self.mutation_gate.write().await
+    }
+
+    /// Captures everything a whole-state snapshot holds besides the books.
+    /// Pause mutations first: the maps are read one after another.
+    #[must_use]
+    pub fn engine_state(&self) -> EngineState {
+        EngineState {
+            orders: self.orders.iter().map(|e| e.value().clone()).collect(),
+            positions: self.positions.iter().map(|e| e.value().clone()).collect(),
+            account_positions: self
+                .account_positions
+                .iter()
+                .map(|account| {
+                    let positions = account.value().iter().map(|e| e.value().clone()).collect();
+                    (account.key().clone(), positions)
+                })
+                .collect(),
+            executions: self.executions.iter().map(|e| e.value().clone()).collect(),
+            last_trades: self.last_trades.iter().map(|e| e.value().clone()).collect(),
+            ohlc: self.ohlc_aggregator.series(),
+            market_maker: self.market_maker.order_state(),
+            market_maker_book: self.market_maker.book_state(),
+            ledger: self.ledger.entries(),
+            collateral: self
+                .collateral
+                .iter()
+                .map(|e| (e.key().clone(), *e.value()))
+                .collect(),
+            liquidations: self
+                .liquidations
+                .iter()
+                .map(|e| e.value().clone())
+                .collect(),
+            order_ids: Some(self.market_maker.order_ids().position()),
+            journal_seq: 0,
+        }
+    }
+
+    /// Replaces the orders, positions, executions, last trades, OHLC bars,
+    /// cash ledger, collateral, liquidations and the market maker's orders and
+    /// book with `engine`, rebuilding each map from its key.
+    pub fn restore_engine_state(&self, engine: EngineState) {
+        self.orders.clear();
+        for order in engine.orders {
+            self.orders.insert(order.order_id.clone(), order);
+        }
+        self.positions.clear();
+        for position in engine.positions {
+            self.positions.insert(position.symbol.clone(), position);
+        }
+        self.account_positions.clear();
+        for (account, positions) in engine.account_positions {
+            let book: DashMap<String, PositionInfo> = positions
+                .into_iter()
+                .map(|position| (position.symbol.clone(), position))
+                .collect();
+            self.account_positions.insert(account, book);
+        }
+        self.executions.clear();
+        for execution in engine.executions {
+            self.executions
+                .insert(execution.execution_id.clone(), execution);
+        }
+        self.last_trades.clear();
+        for trade in engine.last_trades {
+            self.last_trades.insert(trade.symbol.clone(), trade);
+        }
+        self.ohlc_aggregator.restore_series(engine.ohlc);
+        if let Err(e) = self.ledger.restore(engine.ledger) {
+            warn!(error = %e, "snapshot ledger not restored");
+        }
+        self.collateral.clear();
+        for (account, cents) in engine.collateral {
+            self.collateral.insert(account, cents);
+        }
+        self.liquidations.clear();
+        for liquidation in engine.liquidations {
+            self.liquidations
+                .insert(liquidation.account.clone(), liquidation);
+        }
+        self.market_maker.restore_order_state(engine.market_maker);
+        self.market_maker
+            .restore_book_state(engine.market_maker_book);
+        if let Some(position) = engine.order_ids {
+            self.market_maker.order_ids().seek(position);
+        }
+    }
+
     /// Installs the graceful-shutdown signal (issue #118). Called once from
     /// `main.rs` after the watch channel is created; subsequent calls are
     /// no-ops (`OnceLock` semantics).
@@ -387,6 +687,61 @@ impl AppState {
     pub fn shutdown_signal(&self) -> Option<tokio::sync::watch::Receiver<bool>> {
         self.shutdown_rx.get().cloned()
     }
+
+    /// Attaches the write-ahead journal. Called once from `main.rs` after
+    /// startup replay; subsequent calls are no-ops.
+    pub fn set_journal(&self, journal: Arc<Journal>) {
+        let _ = self.journal.set(journal);
+    }
+
+    /// Returns the write-ahead journal, when one is attached.
+    #[must_use]
+    pub fn journal(&self) -> Option<&Arc<Journal>> {
+        self.journal.get()
+    }
+
+    /// Appends `event` to the journal, when one is attached, then runs
+    /// `apply` at the entry's time (see [`crate::clock`]) before releasing
+    /// the journal, so entries are applied in journal order.
+    ///
+    /// # Errors
+    /// Returns the journal error, without running `apply`, when the append
+    /// fails.
+    pub async fn apply_journaled<T>(
+        &self,
+        event: JournalEvent,
+        apply: impl FnOnce() -> T,
+    ) -> Result<T, JournalError> {
+        let Some(journal) = self.journal() else {
+            return Ok(apply());
+        };
+        let mut guard = journal.lock().await;
+        let appended = guard
+            .append(event, self.market_maker.order_ids().position())
+            .await?;
+        Ok(clock::at_sync(appended.timestamp_ms, apply))
+    }
+
+    /// As [`Self::apply_journaled`], for an input applied by a future (one
+    /// that waits for account guards, say).
+    ///
+    /// # Errors
+    /// Returns the journal error, without running `apply`, when the append
+    /// fails.
+    pub async fn apply_journaled_async<F: Future>(
+        &self,
+        event: JournalEvent,
+        apply: F,
+    ) -> Result<F::Output, JournalError> {
+        let Some(journal) = self.journal() else {
+            return Ok(apply.await);
+        };
+        let mut guard = journal.lock().await;
+        let appended = guard
+            .append(event, self.market_maker.order_ids().position())
+            .await?;
+        Ok(clock::at(appended.timestamp_ms, apply).await)
+    }
 }
 
 impl Default for AppState {
@@ -398,7 +753,7 @@ impl Default for AppState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{OrderSide, OrderStatus, OrderTimeInForce};
+    use crate::models::{OrderInfo, OrderSide, OrderStatus, OrderTimeInForce};
 
     fn stored_snapshot(id: &str, created_at: u64) -> StoredSnapshot {
         StoredSnapshot::new(
@@ -544,6 +899,22 @@ mod tests {
         assert!(AppState::parse_expiration(multibyte).is_none());
     }
 
+    #[tokio::test]
+    async fn test_cleanup_drops_idle_account_gates() {
+        let state = AppState::new();
+        let held = state.account_guard("alice").await;
+        drop(state.account_guard("bob").await);
+        assert_eq!(state.account_gates.len(), 2);
+
+        state.cleanup_old_orders(chrono::Utc::now(), 60);
+        assert!(state.account_gates.contains_key("alice"), "held gate kept");
+        assert!(!state.account_gates.contains_key("bob"));
+
+        drop(held);
+        state.cleanup_old_orders(chrono::Utc::now(), 60);
+        assert!(state.account_gates.is_empty());
+    }
+
     #[test]
     fn test_cleanup_old_orders() {
         let state = AppState::new();
@@ -553,6 +924,7 @@ mod tests {
         // 1. Active order (should not be removed)
         let active_order = OrderInfo {
             order_id: "active1".to_string(),
+            account: "acct".to_string(),
             symbol: "BTC".to_string(),
             underlying: "BTC".to_string(),
             expiration: "20251231".to_string(),
@@ -574,6 +946,7 @@ mod tests {
         // 2. Old filled order (should be removed)
         let filled_order = OrderInfo {
             order_id: "filled1".to_string(),
+            account: "acct".to_string(),
             symbol: "BTC".to_string(),
             underlying: "BTC".to_string(),
             expiration: "20251231".to_string(),
@@ -595,6 +968,7 @@ mod tests {
         // 3. Recent filled order (should not be removed yet)
         let recent_filled = OrderInfo {
             order_id: "filled_recent".to_string(),
+            account: "acct".to_string(),
             symbol: "BTC".to_string(),
             underlying: "BTC".to_string(),
             expiration: "20251231".to_string(),
@@ -616,7 +990,7 @@ mod tests {
             .insert("filled_recent".to_string(), recent_filled);
 
         // Run cleanup with 500s retention
-        let removed = state.cleanup_old_orders(500);
+        let removed = state.cleanup_old_orders(now, 500);
 
         assert_eq!(removed, 1);
         assert!(state.orders.contains_key("active1")); // Active kept
@@ -639,6 +1013,10 @@ mod tests {
                 expirations: vec!["20251231".to_string()],
                 num_strikes: 2,
                 strike_spacing: 10.0,
+                tick_size: None,
+                contract_multiplier: 1,
+                min_lot_size: 1,
+                max_order_size: None,
             }],
             ..Config::default()
         };
@@ -0,0 +1,360 @@
+//! Volatility surface history.
+//!
+//! `AppState::surface_cache` keeps only the latest surface per underlying.
+//! With a database connected, a [`SurfaceStore`] keeps every surface
+//! captured on the `[surface_history]` schedule in the `volatility_surfaces`
+//! table, together with its [`skew_metrics`]: the ATM IV, 25-delta risk
+//! reversal and 25-delta butterfly of each expiration. The history endpoint
+//! returns stored surfaces at requested times and the metrics as a series.
+
+use crate::market_maker::OptionPricer;
+use crate::models::{ExpirationSkew, StrikeIV, SurfaceSkewPoint, VolatilitySurfaceResponse};
+use chrono::{DateTime, NaiveDate, Utc};
+use optionstratlib::prelude::Positive;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Call delta of the wing strikes; put wings sit at its negative.
+const WING_DELTA: f64 = 0.25;
+
+/// Widest gap between a strike's delta and [`WING_DELTA`] for the strike to
+/// stand for the wing; an expiration without one reports no risk reversal
+/// or butterfly.
+const WING_DELTA_TOLERANCE: f64 = 0.10;
+
+/// The oldest stored metrics of an underlying within a range.
+const LOAD_OLDEST_METRICS: &str = r#"
+    SELECT captured_at, spot_price, metrics
+    FROM volatility_surfaces
+    WHERE underlying = $1 AND captured_at >= $2 AND captured_at <= $3
+    ORDER BY captured_at ASC
+    LIMIT $4
+"#;
+
+/// The newest stored metrics of an underlying within a range.
+const LOAD_NEWEST_METRICS: &str = r#"
+    SELECT captured_at, spot_price, metrics
+    FROM volatility_surfaces
+    WHERE underlying = $1 AND captured_at >= $2 AND captured_at <= $3
+    ORDER BY captured_at DESC
+    LIMIT $4
+"#;
+
+/// Errors from the surface history.
+#[derive(Debug, Error)]
+pub enum SurfaceHistoryError {
+    /// A database query failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// A surface could not be encoded.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Postgres-backed volatility surface history.
+pub struct SurfaceStore {
+    pool: PgPool,
+}
+
+impl SurfaceStore {
+    /// Creates a store writing to `pool`.
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores `surface` with its skew metrics. Returns `false` when a
+    /// surface of the same underlying and time is already stored.
+    ///
+    /// # Errors
+    /// Returns [`SurfaceHistoryError`] when the write fails.
+    pub async fn record(
+        &self,
+        surface: &VolatilitySurfaceResponse,
+    ) -> Result<bool, SurfaceHistoryError> {
+        let metrics = serde_json::to_string(&skew_metrics(surface))?;
+        let data = serde_json::to_string(surface)?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO volatility_surfaces
+                (underlying, captured_at, spot_price, surface, metrics)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (underlying, captured_at) DO NOTHING
+            "#,
+        )
+        .bind(&surface.underlying)
+        .bind(to_i64(surface.timestamp_ms))
+        .bind(surface.spot_price.map(to_i64))
+        .bind(data)
+        .bind(metrics)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the skew metrics of the surfaces of `underlying` stored within
+    /// `[from_ms, to_ms]`, oldest first: the oldest `limit` when
+    /// `anchor_at_from`, otherwise the newest. Rows that no longer parse are
+    /// skipped.
+    ///
+    /// # Errors
+    /// Returns [`SurfaceHistoryError`] when the query fails.
+    pub async fn load_series(
+        &self,
+        underlying: &str,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+        anchor_at_from: bool,
+        limit: usize,
+    ) -> Result<Vec<SurfaceSkewPoint>, SurfaceHistoryError> {
+        let query = if anchor_at_from {
+            LOAD_OLDEST_METRICS
+        } else {
+            LOAD_NEWEST_METRICS
+        };
+        let rows: Vec<(i64, Option<i64>, String)> = sqlx::query_as(query)
+            .bind(underlying)
+            .bind(to_i64(from_ms.unwrap_or(0)))
+            .bind(to_i64(to_ms.unwrap_or(u64::MAX)))
+            .bind(to_i64(limit as u64))
+            .fetch_all(&self.pool)
+            .await?;
+        let mut series: Vec<SurfaceSkewPoint> = rows
+            .into_iter()
+            .filter_map(|(captured_at, spot_price, metrics)| {
+                match serde_json::from_str(&metrics) {
+                    Ok(expirations) => Some(SurfaceSkewPoint {
+                        timestamp_ms: captured_at.max(0) as u64,
+                        spot_price: spot_price.map(|p| p.max(0) as u64),
+                        expirations,
+                    }),
+                    Err(e) => {
+                        tracing::warn!(underlying, captured_at, error = %e, "stored surface metrics skipped");
+                        None
+                    }
+                }
+            })
+            .collect();
+        if !anchor_at_from {
+            series.reverse();
+        }
+        Ok(series)
+    }
+
+    /// Loads the latest surface of `underlying` stored at or before `at_ms`.
+    ///
+    /// # Errors
+    /// Returns [`SurfaceHistoryError`] when the query fails or the stored
+    /// surface does not parse.
+    pub async fn load_at(
+        &self,
+        underlying: &str,
+        at_ms: u64,
+    ) -> Result<Option<VolatilitySurfaceResponse>, SurfaceHistoryError> {
+        let row: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT surface
+            FROM volatility_surfaces
+            WHERE underlying = $1 AND captured_at <= $2
+            ORDER BY captured_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(underlying)
+        .bind(to_i64(at_ms))
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(|(surface,)| serde_json::from_str(&surface))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+/// Derives the ATM IV, 25-delta risk reversal and 25-delta butterfly of
+/// every expiration of `surface`, nearest first.
+///
+/// Wing deltas are Black-Scholes deltas at each strike's own IV, off the
+/// implied forward of the expiration (spot when there is none) with a zero
+/// rate, as the IVs were solved. Each wing is the strike whose delta is
+/// closest to ±0.25, within [`WING_DELTA_TOLERANCE`]; no interpolation
+/// happens between strikes.
+#[must_use]
+pub fn skew_metrics(surface: &VolatilitySurfaceResponse) -> Vec<ExpirationSkew> {
+    let captured_at = i64::try_from(surface.timestamp_ms)
+        .ok()
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .unwrap_or_default();
+    let pricer = OptionPricer::new(0.0, 0.0);
+
+    surface
+        .expirations
+        .iter()
+        .filter_map(|expiration| {
+            let strikes = surface.surface.get(expiration)?;
+            let atm = surface
+                .atm_term_structure
+                .iter()
+                .find(|point| point.expiration == *expiration);
+            let days = atm.map_or_else(
+                || expiration_days(expiration, captured_at),
+                |point| point.days,
+            );
+            let atm_iv = atm.map(|point| point.iv);
+            let reference = surface
+                .forwards
+                .get(expiration)
+                .copied()
+                .or(surface.spot_price);
+            let (call, put) = reference.map_or((None, None), |reference| {
+                wing_ivs(&pricer, strikes, reference, days)
+            });
+            let risk_reversal_25d = call.zip(put).map(|(call, put)| call - put);
+            let butterfly_25d = call
+                .zip(put)
+                .zip(atm_iv)
+                .map(|((call, put), atm)| (call + put) / 2.0 - atm);
+            Some(ExpirationSkew {
+                expiration: expiration.clone(),
+                days,
+                atm_iv,
+                risk_reversal_25d,
+                butterfly_25d,
+            })
+        })
+        .collect()
+}
+
+/// Returns the IVs of the 25-delta call and put of one expiration.
+fn wing_ivs(
+    pricer: &OptionPricer,
+    strikes: &HashMap<u64, StrikeIV>,
+    reference: u64,
+    days: u64,
+) -> (Option<f64>, Option<f64>) {
+    let Ok(days) = Positive::new(days as f64) else {
+        return (None, None);
+    };
+    let expiration = ExpirationDate::Days(days);
+    let wing = |style: OptionStyle, target: f64| {
+        strikes
+            .iter()
+            .filter_map(|(&strike, iv)| {
+                let iv = match style {
+                    OptionStyle::Call => iv.call_iv,
+                    OptionStyle::Put => iv.put_iv,
+                }?;
+                let delta = pricer.delta(
+                    reference as f64,
+                    strike as f64,
+                    &expiration,
+                    style,
+                    Some(iv),
+                );
+                let gap = (delta - target).abs();
+                (gap <= WING_DELTA_TOLERANCE).then_some((gap, strike, iv))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, _, iv)| iv)
+    };
+    (
+        wing(OptionStyle::Call, WING_DELTA),
+        wing(OptionStyle::Put, -WING_DELTA),
+    )
+}
+
+/// Days from `captured_at` to a `YYYYMMDD` expiration, floored at 1 like
+/// the surface's own tenors.
+fn expiration_days(expiration: &str, captured_at: DateTime<Utc>) -> u64 {
+    NaiveDate::parse_from_str(expiration, "%Y%m%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map_or(1, |date| {
+            (date.and_utc() - captured_at).num_days().max(1) as u64
+        })
+}
+
+/// Saturating conversion for the `BIGINT` columns.
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ATMTermStructurePoint;
+
+    /// A 30-day surface with a forward of 100 and a put skew: IV falls by
+    /// one point per strike step from 30% at the 80 strike.
+    fn skewed_surface() -> VolatilitySurfaceResponse {
+        let expiration = "20300131".to_string();
+        let strikes: HashMap<u64, StrikeIV> = (0..9)
+            .map(|step| {
+                let iv = 0.30 - 0.01 * step as f64;
+                (
+                    8_000 + 500 * step,
+                    StrikeIV {
+                        call_iv: Some(iv),
+                        put_iv: Some(iv),
+                    },
+                )
+            })
+            .collect();
+        VolatilitySurfaceResponse {
+            underlying: "BTC".to_string(),
+            spot_price: Some(9_900),
+            timestamp_ms: 1_700_000_000_000,
+            expirations: vec![expiration.clone()],
+            strikes: strikes.keys().copied().collect(),
+            forwards: HashMap::from([(expiration.clone(), 10_000)]),
+            surface: HashMap::from([(expiration.clone(), strikes)]),
+            atm_term_structure: vec![ATMTermStructurePoint {
+                expiration,
+                days: 30,
+                iv: 0.26,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_skew_metrics_reads_wings_off_the_forward() {
+        let metrics = skew_metrics(&skewed_surface());
+        assert_eq!(metrics.len(), 1);
+        let skew = &metrics[0];
+        assert_eq!(skew.days, 30);
+        assert_eq!(skew.atm_iv, Some(0.26));
+
+        // The 25-delta call is the 105 strike (25%) and the 25-delta put the
+        // 95 strike (27%): a put skew around a 26% ATM with no smile.
+        let rr = skew.risk_reversal_25d.expect("risk reversal");
+        assert!((rr + 0.02).abs() < 1e-9, "risk reversal {rr}");
+        let bf = skew.butterfly_25d.expect("butterfly");
+        assert!(bf.abs() < 1e-9, "butterfly {bf}");
+    }
+
+    #[test]
+    fn test_skew_metrics_omits_wings_without_strikes_near_25_delta() {
+        let mut surface = skewed_surface();
+        let expiration = surface.expirations[0].clone();
+        // Only the ATM strike is left: its delta is ~0.5, far from the wings.
+        surface
+            .surface
+            .get_mut(&expiration)
+            .expect("expiration")
+            .retain(|&strike, _| strike == 10_000);
+
+        let skew = &skew_metrics(&surface)[0];
+        assert_eq!(skew.atm_iv, Some(0.26));
+        assert!(skew.risk_reversal_25d.is_none());
+        assert!(skew.butterfly_25d.is_none());
+    }
+
+    #[test]
+    fn test_expiration_days_floors_at_one() {
+        let captured_at = DateTime::from_timestamp(1_700_000_000, 0).expect("time");
+        assert_eq!(expiration_days("20231125", captured_at), 10);
+        assert_eq!(expiration_days("20200101", captured_at), 1);
+        assert_eq!(expiration_days("garbage", captured_at), 1);
+    }
+}
@@ -0,0 +1,204 @@
+//! Tick-size tables: the minimum price increment of an asset's options.
+//!
+//! A table is either a list of price bands, each with its own tick (listed
+//! equity options quote in 0.05 below $3 and 0.10 above), or a fraction of the
+//! underlying price (crypto venues quote in a fixed fraction of spot). Prices
+//! are in cents. A price is on the grid when it is a multiple of the tick of
+//! the band it falls in; the order entry paths reject prices off the grid and
+//! the market maker rounds its quotes onto it.
+
+use crate::config::{TickSizeConfig, dollars_to_cents};
+
+/// One band of a [`TickTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickBand {
+    /// Exclusive upper bound of the band in cents; `None` for the last band.
+    pub below_cents: Option<u128>,
+    /// Tick of the band in cents.
+    pub tick_cents: u128,
+}
+
+/// Runtime tick-size rule of one underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TickTable {
+    /// Price bands in ascending order; the last band is unbounded.
+    Bands(Vec<TickBand>),
+    /// A tick of this fraction of the underlying price, rounded to whole
+    /// cents (at least one).
+    UnderlyingFraction(f64),
+}
+
+impl Default for TickTable {
+    /// A one-cent tick at every price.
+    fn default() -> Self {
+        Self::Bands(vec![TickBand {
+            below_cents: None,
+            tick_cents: 1,
+        }])
+    }
+}
+
+impl From<&TickSizeConfig> for TickTable {
+    fn from(config: &TickSizeConfig) -> Self {
+        match config {
+            TickSizeConfig::Bands(bands) => Self::Bands(
+                bands
+                    .iter()
+                    .map(|band| TickBand {
+                        below_cents: band.below.and_then(dollars_to_cents).map(u128::from),
+                        tick_cents: dollars_to_cents(band.tick).map_or(1, u128::from).max(1),
+                    })
+                    .collect(),
+            ),
+            TickSizeConfig::UnderlyingFraction(fraction) => Self::UnderlyingFraction(*fraction),
+        }
+    }
+}
+
+impl TickTable {
+    /// Tick in cents at `price`. `spot_cents` is the underlying price, used by
+    /// the fraction rule; without it that rule falls back to one cent.
+    #[must_use]
+    pub fn tick_at(&self, price: u128, spot_cents: Option<u64>) -> u128 {
+        match self {
+            Self::Bands(bands) => bands
+                .iter()
+                .find(|band| band.below_cents.is_none_or(|below| price < below))
+                .or(bands.last())
+                .map_or(1, |band| band.tick_cents.max(1)),
+            Self::UnderlyingFraction(fraction) => spot_cents
+                .map(|spot| (spot as f64 * fraction).round() as u128)
+                .unwrap_or(1)
+                .max(1),
+        }
+    }
+
+    /// Whether `price` is a positive multiple of its tick.
+    #[must_use]
+    pub fn is_on_tick(&self, price: u128, spot_cents: Option<u64>) -> bool {
+        price > 0 && price.is_multiple_of(self.tick_at(price, spot_cents))
+    }
+
+    /// Largest price on the grid at or below `price`; zero when there is none.
+    #[must_use]
+    pub fn round_down(&self, price: u128, spot_cents: Option<u64>) -> u128 {
+        let mut rounded = price;
+        // Rounding down can land in a lower band with a tick that does not
+        // divide the result; repeat until the price is on its own band's grid.
+        while rounded > 0 && !self.is_on_tick(rounded, spot_cents) {
+            rounded -= rounded % self.tick_at(rounded, spot_cents);
+        }
+        rounded
+    }
+
+    /// Smallest price on the grid at or above `price`, at least one tick.
+    #[must_use]
+    pub fn round_up(&self, price: u128, spot_cents: Option<u64>) -> u128 {
+        let mut rounded = price.max(1);
+        while !self.is_on_tick(rounded, spot_cents) {
+            let tick = self.tick_at(rounded, spot_cents);
+            rounded = rounded.div_ceil(tick).saturating_mul(tick);
+        }
+        rounded
+    }
+
+    /// The table as price bands at `spot_cents`: a fraction rule resolves to
+    /// a single unbounded band.
+    #[must_use]
+    pub fn bands_at(&self, spot_cents: Option<u64>) -> Vec<TickBand> {
+        match self {
+            Self::Bands(bands) => bands.clone(),
+            Self::UnderlyingFraction(_) => vec![TickBand {
+                below_cents: None,
+                tick_cents: self.tick_at(0, spot_cents),
+            }],
+        }
+    }
+
+    /// Checks that `price` is on the grid.
+    ///
+    /// # Errors
+    /// Returns a message naming the price and the tick when it is not.
+    pub fn check(&self, price: u128, spot_cents: Option<u64>) -> Result<(), String> {
+        if self.is_on_tick(price, spot_cents) {
+            return Ok(());
+        }
+        Err(format!(
+            "price {price} is not a multiple of the {}-cent tick",
+            self.tick_at(price, spot_cents)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TickBandConfig;
+
+    fn equity_bands() -> TickTable {
+        TickTable::from(&TickSizeConfig::Bands(vec![
+            TickBandConfig {
+                below: Some(3.0),
+                tick: 0.05,
+            },
+            TickBandConfig {
+                below: None,
+                tick: 0.10,
+            },
+        ]))
+    }
+
+    #[test]
+    fn test_default_is_one_cent() {
+        let table = TickTable::default();
+        assert_eq!(table.tick_at(12_345, None), 1);
+        assert!(table.is_on_tick(1, None));
+        assert!(!table.is_on_tick(0, None));
+    }
+
+    #[test]
+    fn test_bands_pick_the_tick_by_price() {
+        let table = equity_bands();
+        assert_eq!(table.tick_at(295, None), 5);
+        assert_eq!(table.tick_at(300, None), 10);
+        assert!(table.is_on_tick(295, None));
+        assert!(!table.is_on_tick(302, None));
+        assert!(
+            !table.is_on_tick(305, None),
+            "above $3 the tick is 10 cents"
+        );
+        assert_eq!(
+            table.check(305, None),
+            Err("price 305 is not a multiple of the 10-cent tick".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rounding_across_band_boundaries() {
+        let table = equity_bands();
+        assert_eq!(table.round_down(298, None), 295);
+        assert_eq!(table.round_up(298, None), 300);
+        assert_eq!(table.round_down(307, None), 300);
+        assert_eq!(table.round_up(301, None), 310);
+        assert_eq!(table.round_down(3, None), 0);
+        assert_eq!(table.round_up(0, None), 5);
+    }
+
+    #[test]
+    fn test_underlying_fraction() {
+        let table = TickTable::UnderlyingFraction(0.0005);
+        // $90,000 spot: a $45 tick.
+        assert_eq!(table.tick_at(100_000, Some(9_000_000)), 4_500);
+        assert!(table.is_on_tick(13_500, Some(9_000_000)));
+        assert_eq!(table.round_up(13_501, Some(9_000_000)), 18_000);
+        assert_eq!(table.tick_at(100, None), 1, "no spot, no constraint");
+        assert_eq!(table.tick_at(100, Some(100)), 1, "at least one cent");
+        assert_eq!(
+            table.bands_at(Some(9_000_000)),
+            vec![TickBand {
+                below_cents: None,
+                tick_cents: 4_500
+            }]
+        );
+    }
+}